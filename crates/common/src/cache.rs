@@ -0,0 +1,176 @@
+//! Memory accounting for in-memory caches.
+//!
+//! Caches register themselves with the [global registry](CacheRegistry::global), which reports
+//! their memory usage as metrics and shrinks them proportionally once their combined usage
+//! exceeds the configured budget.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// A cache whose memory usage is accounted for by the [CacheRegistry].
+pub trait MemoryAccounted: Send + Sync {
+    /// The approximate memory used by the cached entries, in bytes.
+    fn memory_usage(&self) -> usize;
+
+    /// Evicts the least recently used entries until at most `bytes` are used.
+    fn shrink_to(&self, bytes: usize);
+}
+
+#[derive(Default)]
+pub struct CacheRegistry {
+    caches: Mutex<Vec<(&'static str, Weak<dyn MemoryAccounted>)>>,
+    /// Zero means unlimited.
+    max_memory: AtomicUsize,
+}
+
+impl CacheRegistry {
+    /// The registry used by all of pathfinder's caches.
+    pub fn global() -> &'static CacheRegistry {
+        static REGISTRY: OnceLock<CacheRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(Default::default)
+    }
+
+    /// Registers the cache under `name`. Caches are unregistered once they are dropped.
+    ///
+    /// Multiple caches may share a name, their usage is then reported as a sum.
+    pub fn register(&self, name: &'static str, cache: &Arc<dyn MemoryAccounted>) {
+        let mut caches = self.caches.lock().unwrap();
+        caches.retain(|(_, cache)| cache.strong_count() > 0);
+        caches.push((name, Arc::downgrade(cache)));
+    }
+
+    /// Sets the budget for the combined memory usage of all caches, or removes it if [None].
+    pub fn set_max_memory(&self, bytes: Option<usize>) {
+        self.max_memory
+            .store(bytes.unwrap_or_default(), Ordering::Relaxed);
+    }
+
+    pub fn max_memory(&self) -> Option<usize> {
+        match self.max_memory.load(Ordering::Relaxed) {
+            0 => None,
+            bytes => Some(bytes),
+        }
+    }
+
+    /// The memory usage in bytes per cache name.
+    pub fn usage(&self) -> Vec<(&'static str, usize)> {
+        let mut usage: Vec<(&'static str, usize)> = Vec::new();
+        for (name, cache) in self.live_caches() {
+            let bytes = cache.memory_usage();
+            match usage.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, total)) => *total += bytes,
+                None => usage.push((name, bytes)),
+            }
+        }
+        usage
+    }
+
+    /// Shrinks all caches by the same fraction if their combined usage exceeds the budget.
+    pub fn enforce_max_memory(&self) {
+        let Some(max_memory) = self.max_memory() else {
+            return;
+        };
+
+        let caches = self
+            .live_caches()
+            .into_iter()
+            .map(|(_, cache)| {
+                let bytes = cache.memory_usage();
+                (cache, bytes)
+            })
+            .collect::<Vec<_>>();
+
+        let total: usize = caches.iter().map(|(_, bytes)| bytes).sum();
+        if total <= max_memory {
+            return;
+        }
+
+        for (cache, bytes) in caches {
+            let target = (bytes as u128 * max_memory as u128 / total as u128) as usize;
+            cache.shrink_to(target);
+        }
+    }
+
+    /// Records the memory usage metrics and enforces the budget.
+    pub fn update(&self) {
+        for (name, bytes) in self.usage() {
+            metrics::gauge!("cache_memory_bytes", bytes as f64, "cache" => name);
+        }
+        if let Some(max_memory) = self.max_memory() {
+            metrics::gauge!("cache_memory_limit_bytes", max_memory as f64);
+        }
+
+        self.enforce_max_memory();
+    }
+
+    fn live_caches(&self) -> Vec<(&'static str, Arc<dyn MemoryAccounted>)> {
+        let mut caches = self.caches.lock().unwrap();
+        caches.retain(|(_, cache)| cache.strong_count() > 0);
+        caches
+            .iter()
+            .filter_map(|(name, cache)| Some((*name, cache.upgrade()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entries of one byte each, evicted in insertion order.
+    struct Bytes(Mutex<usize>);
+
+    impl MemoryAccounted for Bytes {
+        fn memory_usage(&self) -> usize {
+            *self.0.lock().unwrap()
+        }
+
+        fn shrink_to(&self, bytes: usize) {
+            let mut usage = self.0.lock().unwrap();
+            *usage = (*usage).min(bytes);
+        }
+    }
+
+    fn cache(bytes: usize) -> Arc<dyn MemoryAccounted> {
+        Arc::new(Bytes(Mutex::new(bytes)))
+    }
+
+    #[test]
+    fn usage_is_summed_per_name() {
+        let registry = CacheRegistry::default();
+        let a = cache(10);
+        let b = cache(20);
+        let c = cache(5);
+        registry.register("a", &a);
+        registry.register("b", &b);
+        registry.register("a", &c);
+
+        assert_eq!(registry.usage(), vec![("a", 15), ("b", 20)]);
+    }
+
+    #[test]
+    fn dropped_caches_are_unregistered() {
+        let registry = CacheRegistry::default();
+        let a = cache(10);
+        registry.register("a", &a);
+        drop(a);
+
+        assert_eq!(registry.usage(), vec![]);
+    }
+
+    #[test]
+    fn caches_are_shrunk_proportionally() {
+        let registry = CacheRegistry::default();
+        let a = cache(100);
+        let b = cache(300);
+        registry.register("a", &a);
+        registry.register("b", &b);
+
+        registry.enforce_max_memory();
+        assert_eq!(registry.usage(), vec![("a", 100), ("b", 300)]);
+
+        registry.set_max_memory(Some(200));
+        registry.enforce_max_memory();
+        assert_eq!(registry.usage(), vec![("a", 50), ("b", 150)]);
+    }
+}
@@ -8,6 +8,7 @@ use pathfinder_crypto::Felt;
 use primitive_types::{H160, H256};
 use serde::{Deserialize, Serialize};
 
+pub mod cache;
 pub mod consts;
 pub mod event;
 pub mod hash;
@@ -157,6 +158,18 @@ macros::i64_backed_u64::serdes!(TransactionIndex);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Default, Dummy)]
 pub struct GasPrice(pub u128);
 
+/// The tip paid to the sequencer by a v3 transaction, on top of its resource fees.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Default, Dummy)]
+pub struct Tip(pub u64);
+
+/// The maximum amount of a resource a v3 transaction may consume.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Default, Dummy)]
+pub struct ResourceAmount(pub u64);
+
+/// The maximum price per unit of a resource a v3 transaction is willing to pay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Default, Dummy)]
+pub struct ResourcePricePerUnit(pub u128);
+
 /// Starknet transaction version.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub struct TransactionVersion(pub H256);
@@ -195,9 +208,14 @@ impl TransactionVersion {
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         0, 2,
     ]));
+    pub const THREE: Self = Self(H256([
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 3,
+    ]));
     pub const ZERO_WITH_QUERY_VERSION: Self = Self::ZERO.with_query_version();
     pub const ONE_WITH_QUERY_VERSION: Self = Self::ONE.with_query_version();
     pub const TWO_WITH_QUERY_VERSION: Self = Self::TWO.with_query_version();
+    pub const THREE_WITH_QUERY_VERSION: Self = Self::THREE.with_query_version();
 }
 
 /// A way of identifying a specific block.
@@ -425,6 +443,7 @@ impl From<String> for StarknetVersion {
 
 macros::felt_newtypes!(
     [
+        AccountDeploymentDataElem,
         ByteCodeOffset,
         BlockCommitmentSignatureElem,
         CallParam,
@@ -445,6 +464,8 @@ macros::felt_newtypes!(
         L1ToL2MessageNonce,
         L1ToL2MessagePayloadElem,
         L2ToL1MessagePayloadElem,
+        PaymasterDataElem,
+        PublicKey,
         SequencerAddress,
         BlockHash,
         TransactionHash,
@@ -467,6 +488,22 @@ macros::felt_newtypes!(
 macros::fmt::thin_display!(BlockNumber);
 macros::fmt::thin_display!(BlockTimestamp);
 
+/// The pattern of felts in the starknet-specs.
+const FELT_PATTERN: &str = "^0x(0|[a-fA-F1-9]{1}[a-fA-F0-9]{0,62})$";
+
+/// The [JsonSchema](schemars::JsonSchema) of the felt newtypes, a hex string.
+fn felt_schema() -> schemars::schema::Schema {
+    schemars::schema::SchemaObject {
+        instance_type: Some(schemars::schema::InstanceType::String.into()),
+        string: Some(Box::new(schemars::schema::StringValidation {
+            pattern: Some(FELT_PATTERN.to_owned()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum AllowedOrigins {
     Any,
@@ -88,7 +88,8 @@ pub(super) mod i64_backed_u64 {
     pub(crate) use {new_get_partialeq, serdes};
 }
 
-/// Generates felt newtype-wrappers and the `macro_prelude` module.
+/// Generates felt newtype-wrappers, the `felt_newtype_schema` lookup and the `macro_prelude`
+/// module.
 ///
 /// Note that this is a single-use macro as it generates a module.
 ///
@@ -100,6 +101,17 @@ macro_rules! felt_newtypes {
         crate::macros::felt_newtypes!(@define_felt $($felt),*);
         crate::macros::felt_newtypes!(@define_felt251 $($felt251),*);
 
+        /// The [JsonSchema](schemars::JsonSchema) of the felt newtype with the given name, which
+        /// is the name it is deserialized as a newtype struct with.
+        pub fn felt_newtype_schema(name: &str) -> Option<schemars::schema::Schema> {
+            let mut generator = schemars::gen::SchemaGenerator::default();
+            match name {
+                $(stringify!($felt) => Some(<$felt as schemars::JsonSchema>::json_schema(&mut generator)),)*
+                $(stringify!($felt251) => Some(<$felt251 as schemars::JsonSchema>::json_schema(&mut generator)),)*
+                _ => None,
+            }
+        }
+
         pub mod macro_prelude {
             pub use super::felt;
             pub use super::felt_bytes;
@@ -131,6 +143,16 @@ macro_rules! felt_newtypes {
                 }
             }
 
+            impl schemars::JsonSchema for $target {
+                fn schema_name() -> String {
+                    stringify!($target).to_owned()
+                }
+
+                fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                    crate::felt_schema()
+                }
+            }
+
             $crate::macros::fmt::thin_debug!($target);
             $crate::macros::fmt::thin_display!($target);
         }
@@ -185,8 +207,37 @@ macro_rules! felt_newtypes {
                 where
                     D: serde::Deserializer<'de>,
                 {
-                    let felt = Felt::deserialize(de)?;
-                    $target::new(felt).context("Felt251 overflow").map_err(serde::de::Error::custom)
+                    struct Felt251Visitor;
+
+                    impl<'de> serde::de::Visitor<'de> for Felt251Visitor {
+                        type Value = $target;
+
+                        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                            f.write_str(concat!("a ", stringify!($target)))
+                        }
+
+                        fn visit_newtype_struct<D>(self, de: D) -> Result<Self::Value, D::Error>
+                        where
+                            D: serde::Deserializer<'de>,
+                        {
+                            let felt = Felt::deserialize(de)?;
+                            $target::new(felt).context("Felt251 overflow").map_err(serde::de::Error::custom)
+                        }
+                    }
+
+                    // Like the derived implementation of the other felt newtypes, so that the
+                    // type's name is known to the deserializer.
+                    de.deserialize_newtype_struct(stringify!($target), Felt251Visitor)
+                }
+            }
+
+            impl schemars::JsonSchema for $target {
+                fn schema_name() -> String {
+                    stringify!($target).to_owned()
+                }
+
+                fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                    crate::felt_schema()
                 }
             }
         }
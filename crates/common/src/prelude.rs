@@ -1,3 +1,4 @@
+pub use crate::AccountDeploymentDataElem;
 pub use crate::BlockHash;
 pub use crate::BlockHeader;
 pub use crate::BlockNumber;
@@ -28,6 +29,9 @@ pub use crate::GasPrice;
 pub use crate::L1ToL2MessageNonce;
 pub use crate::L1ToL2MessagePayloadElem;
 pub use crate::L2ToL1MessagePayloadElem;
+pub use crate::PaymasterDataElem;
+pub use crate::ResourceAmount;
+pub use crate::ResourcePricePerUnit;
 pub use crate::SequencerAddress;
 pub use crate::SierraHash;
 pub use crate::StarknetVersion;
@@ -36,6 +40,7 @@ pub use crate::StateUpdate;
 pub use crate::StorageAddress;
 pub use crate::StorageCommitment;
 pub use crate::StorageValue;
+pub use crate::Tip;
 pub use crate::TransactionCommitment;
 pub use crate::TransactionHash;
 pub use crate::TransactionIndex;
@@ -1,6 +1,6 @@
 use crate::BlockCommitmentSignatureElem;
 
-#[derive(Default, Debug, Clone, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct BlockCommitmentSignature {
     pub r: BlockCommitmentSignatureElem,
     pub s: BlockCommitmentSignatureElem,
@@ -213,6 +213,112 @@ impl StateUpdate {
         })
     }
 
+    /// The changes in this state update which are not already part of `previous`, e.g. the
+    /// growth of a pending state update between two polls.
+    ///
+    /// [Applying](Self::apply) the changes to `previous` results in this state update, provided
+    /// this state update contains everything `previous` does. The block hash and state
+    /// commitments are taken from this state update.
+    pub fn changes_since(&self, previous: &StateUpdate) -> StateUpdate {
+        let contract_updates = self
+            .contract_updates
+            .iter()
+            .filter_map(|(address, update)| {
+                let previous = previous.contract_updates.get(address);
+                let storage = update
+                    .storage
+                    .iter()
+                    .filter(|(key, value)| previous.and_then(|p| p.storage.get(key)) != Some(value))
+                    .map(|(key, value)| (*key, *value))
+                    .collect::<HashMap<_, _>>();
+                let class = update
+                    .class
+                    .clone()
+                    .filter(|class| previous.and_then(|p| p.class.as_ref()) != Some(class));
+                let nonce = update
+                    .nonce
+                    .filter(|nonce| previous.and_then(|p| p.nonce.as_ref()) != Some(nonce));
+
+                let changed = !storage.is_empty() || class.is_some() || nonce.is_some();
+                changed.then_some((
+                    *address,
+                    ContractUpdate {
+                        storage,
+                        class,
+                        nonce,
+                    },
+                ))
+            })
+            .collect();
+
+        let system_contract_updates = self
+            .system_contract_updates
+            .iter()
+            .filter_map(|(address, update)| {
+                let previous = previous.system_contract_updates.get(address);
+                let storage = update
+                    .storage
+                    .iter()
+                    .filter(|(key, value)| previous.and_then(|p| p.storage.get(key)) != Some(value))
+                    .map(|(key, value)| (*key, *value))
+                    .collect::<HashMap<_, _>>();
+
+                (!storage.is_empty()).then_some((*address, SystemContractUpdate { storage }))
+            })
+            .collect();
+
+        StateUpdate {
+            block_hash: self.block_hash,
+            parent_state_commitment: self.parent_state_commitment,
+            state_commitment: self.state_commitment,
+            contract_updates,
+            system_contract_updates,
+            declared_cairo_classes: self
+                .declared_cairo_classes
+                .difference(&previous.declared_cairo_classes)
+                .copied()
+                .collect(),
+            declared_sierra_classes: self
+                .declared_sierra_classes
+                .iter()
+                .filter(|(sierra, casm)| previous.declared_sierra_classes.get(sierra) != Some(casm))
+                .map(|(sierra, casm)| (*sierra, *casm))
+                .collect(),
+        }
+    }
+
+    /// Applies the changes of a later state update, as returned by
+    /// [changes_since](Self::changes_since).
+    pub fn apply(&mut self, changes: StateUpdate) {
+        self.block_hash = changes.block_hash;
+        self.parent_state_commitment = changes.parent_state_commitment;
+        self.state_commitment = changes.state_commitment;
+
+        for (address, update) in changes.contract_updates {
+            let current = self.contract_updates.entry(address).or_default();
+            current.storage.extend(update.storage);
+            if update.class.is_some() {
+                current.class = update.class;
+            }
+            if update.nonce.is_some() {
+                current.nonce = update.nonce;
+            }
+        }
+
+        for (address, update) in changes.system_contract_updates {
+            self.system_contract_updates
+                .entry(address)
+                .or_default()
+                .storage
+                .extend(update.storage);
+        }
+
+        self.declared_cairo_classes
+            .extend(changes.declared_cairo_classes);
+        self.declared_sierra_classes
+            .extend(changes.declared_sierra_classes);
+    }
+
     /// Compute the state diff commitment used in block commitment signatures.
     ///
     /// How to compute the value is documented in [this Starknet Community article](https://community.starknet.io/t/introducing-p2p-authentication-and-mismatch-resolution-in-v0-12-2/97993).
@@ -458,6 +564,64 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn changes_since() {
+        let previous = StateUpdate::default()
+            .with_contract_nonce(contract_address!("0x1"), contract_nonce!("0x1"))
+            .with_storage_update(
+                contract_address!("0x1"),
+                storage_address!("0x10"),
+                storage_value!("0x1"),
+            )
+            .with_storage_update(
+                contract_address!("0x1"),
+                storage_address!("0x11"),
+                storage_value!("0x1"),
+            )
+            .with_declared_cairo_class(class_hash!("0x3"));
+
+        let current = previous
+            .clone()
+            .with_contract_nonce(contract_address!("0x1"), contract_nonce!("0x2"))
+            .with_storage_update(
+                contract_address!("0x1"),
+                storage_address!("0x11"),
+                storage_value!("0x2"),
+            )
+            .with_deployed_contract(contract_address!("0x2"), class_hash!("0x4"))
+            .with_system_storage_update(
+                ContractAddress::ONE,
+                storage_address!("0x10"),
+                storage_value!("0x99"),
+            )
+            .with_declared_cairo_class(class_hash!("0x5"))
+            .with_declared_sierra_class(sierra_hash!("0x6"), casm_hash!("0x7"));
+
+        let changes = current.changes_since(&previous);
+        let expected = StateUpdate::default()
+            .with_contract_nonce(contract_address!("0x1"), contract_nonce!("0x2"))
+            .with_storage_update(
+                contract_address!("0x1"),
+                storage_address!("0x11"),
+                storage_value!("0x2"),
+            )
+            .with_deployed_contract(contract_address!("0x2"), class_hash!("0x4"))
+            .with_system_storage_update(
+                ContractAddress::ONE,
+                storage_address!("0x10"),
+                storage_value!("0x99"),
+            )
+            .with_declared_cairo_class(class_hash!("0x5"))
+            .with_declared_sierra_class(sierra_hash!("0x6"), casm_hash!("0x7"));
+        assert_eq!(changes, expected);
+
+        let mut applied = previous;
+        applied.apply(changes);
+        assert_eq!(applied, current);
+
+        assert_eq!(current.changes_since(&current).change_count(), 0);
+    }
+
     mod storage_value {
         use super::*;
 
@@ -11,11 +11,14 @@ pub enum TransactionVariant {
     DeclareV0(DeclareTransactionV0V1),
     DeclareV1(DeclareTransactionV0V1),
     DeclareV2(DeclareTransactionV2),
+    DeclareV3(DeclareTransactionV3),
     // Regenesis: deploy is a legacy variant and can be removed after regenesis.
     Deploy(DeployTransaction),
     DeployAccount(DeployAccountTransaction),
+    DeployAccountV3(DeployAccountTransactionV3),
     InvokeV0(InvokeTransactionV0),
     InvokeV1(InvokeTransactionV1),
+    InvokeV3(InvokeTransactionV3),
     L1Handler(L1HandlerTransaction),
 }
 
@@ -24,6 +27,11 @@ impl From<DeclareTransactionV2> for TransactionVariant {
         Self::DeclareV2(value)
     }
 }
+impl From<DeclareTransactionV3> for TransactionVariant {
+    fn from(value: DeclareTransactionV3) -> Self {
+        Self::DeclareV3(value)
+    }
+}
 impl From<DeployTransaction> for TransactionVariant {
     fn from(value: DeployTransaction) -> Self {
         Self::Deploy(value)
@@ -34,6 +42,11 @@ impl From<DeployAccountTransaction> for TransactionVariant {
         Self::DeployAccount(value)
     }
 }
+impl From<DeployAccountTransactionV3> for TransactionVariant {
+    fn from(value: DeployAccountTransactionV3) -> Self {
+        Self::DeployAccountV3(value)
+    }
+}
 impl From<InvokeTransactionV0> for TransactionVariant {
     fn from(value: InvokeTransactionV0) -> Self {
         Self::InvokeV0(value)
@@ -44,6 +57,11 @@ impl From<InvokeTransactionV1> for TransactionVariant {
         Self::InvokeV1(value)
     }
 }
+impl From<InvokeTransactionV3> for TransactionVariant {
+    fn from(value: InvokeTransactionV3) -> Self {
+        Self::InvokeV3(value)
+    }
+}
 impl From<L1HandlerTransaction> for TransactionVariant {
     fn from(value: L1HandlerTransaction) -> Self {
         Self::L1Handler(value)
@@ -69,6 +87,21 @@ pub struct DeclareTransactionV2 {
     pub compiled_class_hash: CasmHash,
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct DeclareTransactionV3 {
+    pub class_hash: ClassHash,
+    pub nonce: TransactionNonce,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    pub resource_bounds: ResourceBounds,
+    pub tip: Tip,
+    pub paymaster_data: Vec<PaymasterDataElem>,
+    pub signature: Vec<TransactionSignatureElem>,
+    pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+    pub sender_address: ContractAddress,
+    pub compiled_class_hash: CasmHash,
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct DeployTransaction {
     pub contract_address: ContractAddress,
@@ -90,6 +123,21 @@ pub struct DeployAccountTransaction {
     pub class_hash: ClassHash,
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct DeployAccountTransactionV3 {
+    pub contract_address: ContractAddress,
+    pub signature: Vec<TransactionSignatureElem>,
+    pub nonce: TransactionNonce,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    pub resource_bounds: ResourceBounds,
+    pub tip: Tip,
+    pub paymaster_data: Vec<PaymasterDataElem>,
+    pub contract_address_salt: ContractAddressSalt,
+    pub constructor_calldata: Vec<CallParam>,
+    pub class_hash: ClassHash,
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct InvokeTransactionV0 {
     pub calldata: Vec<CallParam>,
@@ -109,6 +157,20 @@ pub struct InvokeTransactionV1 {
     pub nonce: TransactionNonce,
 }
 
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct InvokeTransactionV3 {
+    pub signature: Vec<TransactionSignatureElem>,
+    pub nonce: TransactionNonce,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    pub resource_bounds: ResourceBounds,
+    pub tip: Tip,
+    pub paymaster_data: Vec<PaymasterDataElem>,
+    pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+    pub calldata: Vec<CallParam>,
+    pub sender_address: ContractAddress,
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct L1HandlerTransaction {
     pub contract_address: ContractAddress,
@@ -123,3 +185,25 @@ pub enum EntryPointType {
     External,
     L1Handler,
 }
+
+/// The maximum amounts of each resource a v3 transaction may consume, and the maximum prices per
+/// unit it is willing to pay for them.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ResourceBounds {
+    pub l1_gas: ResourceBound,
+    pub l2_gas: ResourceBound,
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ResourceBound {
+    pub max_amount: ResourceAmount,
+    pub max_price_per_unit: ResourcePricePerUnit,
+}
+
+/// Where the state updates of a v3 transaction's nonce and fee payment are published.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum DataAvailabilityMode {
+    #[default]
+    L1,
+    L2,
+}
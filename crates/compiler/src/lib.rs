@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use anyhow::Context;
-use pathfinder_common::StarknetVersion;
+use pathfinder_common::{CasmHash, StarknetVersion};
 
 pub const COMPILER_VERSION: &str = env!("SIERRA_CASM_COMPILER_VERSION");
 
@@ -43,6 +43,17 @@ pub fn compile_to_casm_with_latest_compiler(sierra_definition: &[u8]) -> anyhow:
     v2::compile(definition)
 }
 
+/// Like [compile_to_casm_with_latest_compiler], but also returns the hash of the CASM class,
+/// i.e. the compiled class hash of a declare transaction.
+pub fn compile_and_hash_with_latest_compiler(
+    sierra_definition: &[u8],
+) -> anyhow::Result<(Vec<u8>, CasmHash)> {
+    let definition = serde_json::from_slice::<FeederGatewayContractClass<'_>>(sierra_definition)
+        .context("Parsing Sierra class")?;
+
+    v2::compile_and_hash(definition)
+}
+
 mod v1_0_0_alpha6 {
     use anyhow::Context;
     use casm_compiler_v1_0_0_alpha6::allowed_libfuncs::{
@@ -187,6 +198,8 @@ mod v2 {
     use casm_compiler_v2::allowed_libfuncs::{validate_compatible_sierra_version, ListSelector};
     use casm_compiler_v2::casm_contract_class::CasmContractClass;
     use casm_compiler_v2::contract_class::ContractClass;
+    use pathfinder_common::CasmHash;
+    use pathfinder_crypto::Felt;
 
     use super::FeederGatewayContractClass;
 
@@ -205,6 +218,26 @@ mod v2 {
     }
 
     pub(super) fn compile(definition: FeederGatewayContractClass<'_>) -> anyhow::Result<Vec<u8>> {
+        let casm_class = compile_class(definition)?;
+        let casm_definition = serde_json::to_vec(&casm_class)?;
+
+        Ok(casm_definition)
+    }
+
+    pub(super) fn compile_and_hash(
+        definition: FeederGatewayContractClass<'_>,
+    ) -> anyhow::Result<(Vec<u8>, CasmHash)> {
+        let casm_class = compile_class(definition)?;
+        let casm_hash = Felt::from_be_bytes(casm_class.compiled_class_hash().to_be_bytes())
+            .context("Converting compiled class hash")?;
+        let casm_definition = serde_json::to_vec(&casm_class)?;
+
+        Ok((casm_definition, CasmHash(casm_hash)))
+    }
+
+    fn compile_class(
+        definition: FeederGatewayContractClass<'_>,
+    ) -> anyhow::Result<CasmContractClass> {
         let sierra_class: ContractClass = definition
             .try_into()
             .context("Converting to Sierra class")?;
@@ -217,11 +250,7 @@ mod v2 {
         )
         .context("Validating Sierra class")?;
 
-        let casm_class = CasmContractClass::from_contract_class(sierra_class, true)
-            .context("Compiling to CASM")?;
-        let casm_definition = serde_json::to_vec(&casm_class)?;
-
-        Ok(casm_definition)
+        CasmContractClass::from_contract_class(sierra_class, true).context("Compiling to CASM")
     }
 }
 
@@ -0,0 +1,429 @@
+//! Reads Starknet state diffs which were posted to Ethereum as [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844)
+//! blobs.
+//!
+//! Blobs are not part of the execution layer, so they are read from a beacon node's
+//! [blob sidecars](https://ethereum.github.io/beacon-APIs/#/Beacon/getBlobSidecars). Each blob
+//! holds 4096 field elements of the BLS12-381 scalar field. Starknet posts its data as the
+//! coefficients of a polynomial, and the blob holds the polynomial's evaluations at the roots of
+//! unity in bit-reversed order. The data is therefore recovered with an inverse FFT.
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use num_bigint::BigUint;
+use pathfinder_common::{
+    CasmHash, ClassHash, ContractAddress, ContractNonce, StorageAddress, StorageValue,
+};
+use pathfinder_crypto::Felt;
+use primitive_types::H256;
+use sha2::{Digest, Sha256};
+
+use crate::{EthereumClient, L1Transaction};
+
+/// The number of field elements in a blob.
+const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * 32;
+const SECONDS_PER_SLOT: u64 = 12;
+/// The version byte of a blob's versioned hash.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// A Starknet state diff as posted to Ethereum.
+///
+/// Values are final, i.e. the diff of a state update covering multiple blocks holds the values
+/// at its last block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobStateDiff {
+    pub contracts: Vec<ContractDiff>,
+    /// Declared Sierra classes and their compiled class hashes.
+    pub declared_classes: Vec<(ClassHash, CasmHash)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractDiff {
+    pub address: ContractAddress,
+    pub nonce: ContractNonce,
+    /// Set if the contract was deployed or its class was replaced.
+    pub class_hash: Option<ClassHash>,
+    pub storage: Vec<(StorageAddress, StorageValue)>,
+}
+
+/// Reads the state diffs of Starknet state updates which were posted as blobs.
+#[derive(Clone, Debug)]
+pub struct BlobReader {
+    ethereum: EthereumClient,
+    http: reqwest::Client,
+    beacon_url: reqwest::Url,
+}
+
+impl BlobReader {
+    pub fn new(ethereum: EthereumClient, beacon_url: reqwest::Url) -> anyhow::Result<Self> {
+        Ok(Self {
+            ethereum,
+            http: reqwest::ClientBuilder::new().build()?,
+            beacon_url,
+        })
+    }
+
+    /// The state diff posted by the L1 transaction, or [None] if it posted no blobs, i.e. the
+    /// state diff was posted as calldata.
+    pub async fn state_diff(
+        &self,
+        transaction: &L1Transaction,
+    ) -> anyhow::Result<Option<BlobStateDiff>> {
+        let versioned_hashes = self
+            .ethereum
+            .get_blob_versioned_hashes(transaction.hash)
+            .await
+            .context("Fetching blob versioned hashes")?;
+        if versioned_hashes.is_empty() {
+            return Ok(None);
+        }
+
+        let genesis_time = self.genesis_time().await.context("Fetching genesis time")?;
+        let slot = transaction
+            .timestamp
+            .checked_sub(genesis_time)
+            .context("L1 transaction predates the beacon chain")?
+            / SECONDS_PER_SLOT;
+        let sidecars = self
+            .blob_sidecars(slot)
+            .await
+            .context("Fetching blob sidecars")?;
+
+        // The data spans the transaction's blobs in order.
+        let mut data = Vec::new();
+        for versioned_hash in versioned_hashes {
+            let (_, blob) = sidecars
+                .iter()
+                .find(|(commitment, _)| kzg_to_versioned_hash(commitment) == versioned_hash)
+                .with_context(|| format!("Blob {versioned_hash:?} is missing from slot {slot}"))?;
+            data.extend(decode_blob(blob)?);
+        }
+
+        parse_state_diff(&data).map(Some)
+    }
+
+    async fn genesis_time(&self) -> anyhow::Result<u64> {
+        let genesis = self.get_beacon("eth/v1/beacon/genesis").await?;
+        genesis["data"]["genesis_time"]
+            .as_str()
+            .and_then(|time| time.parse().ok())
+            .context("Parsing genesis time")
+    }
+
+    /// The KZG commitments and blobs of the slot's blob sidecars.
+    async fn blob_sidecars(&self, slot: u64) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let sidecars = self
+            .get_beacon(&format!("eth/v1/beacon/blob_sidecars/{slot}"))
+            .await?;
+
+        sidecars["data"]
+            .as_array()
+            .context("Blob sidecars are not an array")?
+            .iter()
+            .map(|sidecar| {
+                let commitment =
+                    decode_hex(&sidecar["kzg_commitment"]).context("Decoding KZG commitment")?;
+                let blob = decode_hex(&sidecar["blob"]).context("Decoding blob")?;
+                Ok((commitment, blob))
+            })
+            .collect()
+    }
+
+    async fn get_beacon(&self, path: &str) -> anyhow::Result<serde_json::Value> {
+        let url = self.beacon_url.join(path)?;
+        let response = self.http.get(url).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            tracing::error!(code=%status.as_u16(), %path, "Beacon node request failed");
+            anyhow::bail!(status.as_u16());
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+fn decode_hex(value: &serde_json::Value) -> anyhow::Result<Vec<u8>> {
+    let value = value.as_str().context("Value is not a string")?;
+    Ok(hex::decode(value.strip_prefix("0x").unwrap_or(value))?)
+}
+
+/// The versioned hash by which a transaction references the blob of a KZG commitment.
+fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(hash)
+}
+
+/// The order of the BLS12-381 scalar field.
+fn bls_modulus() -> &'static BigUint {
+    static MODULUS: OnceLock<BigUint> = OnceLock::new();
+    MODULUS.get_or_init(|| {
+        BigUint::parse_bytes(
+            b"52435875175126190479447740508185965837690552500527637822603658699938581184513",
+            10,
+        )
+        .expect("Valid BLS modulus")
+    })
+}
+
+/// The primitive 4096th root of unity of the BLS12-381 scalar field, as used by EIP-4844.
+fn root_of_unity() -> &'static BigUint {
+    static ROOT: OnceLock<BigUint> = OnceLock::new();
+    ROOT.get_or_init(|| {
+        BigUint::parse_bytes(
+            b"39033254847818212395286706435128746857159659164139250548781411570340225835782",
+            10,
+        )
+        .expect("Valid root of unity")
+    })
+}
+
+/// Recovers the data of a blob, i.e. the coefficients of the polynomial it evaluates.
+pub fn decode_blob(blob: &[u8]) -> anyhow::Result<Vec<Felt>> {
+    anyhow::ensure!(
+        blob.len() == BYTES_PER_BLOB,
+        "Blob has {} bytes instead of {BYTES_PER_BLOB}",
+        blob.len()
+    );
+
+    let modulus = bls_modulus();
+    let evaluations = blob
+        .chunks_exact(32)
+        .map(BigUint::from_bytes_be)
+        .collect::<Vec<_>>();
+    anyhow::ensure!(
+        evaluations.iter().all(|x| x < modulus),
+        "Blob contains a non-canonical field element"
+    );
+
+    // Undo the bit-reversed order of the evaluations.
+    let bits = FIELD_ELEMENTS_PER_BLOB.trailing_zeros();
+    let evaluations = (0..FIELD_ELEMENTS_PER_BLOB)
+        .map(|i| evaluations[i.reverse_bits() >> (usize::BITS - bits)].clone())
+        .collect();
+
+    // The inverse FFT is the FFT with the inverse root, scaled by the inverse of its size.
+    let exponent = modulus - 2u32;
+    let inverse_root = root_of_unity().modpow(&exponent, modulus);
+    let inverse_size = BigUint::from(FIELD_ELEMENTS_PER_BLOB).modpow(&exponent, modulus);
+
+    fft(evaluations, &inverse_root, modulus)
+        .into_iter()
+        .map(|coefficient| {
+            let coefficient = coefficient * &inverse_size % modulus;
+            Felt::from_be_slice(&coefficient.to_bytes_be())
+                .context("Blob data is not a Starknet field element")
+        })
+        .collect()
+}
+
+/// Evaluates the polynomial with the given coefficients at the powers of `root`, which must be
+/// a primitive root of unity of the same order as the number of coefficients.
+fn fft(coefficients: Vec<BigUint>, root: &BigUint, modulus: &BigUint) -> Vec<BigUint> {
+    let size = coefficients.len();
+    if size == 1 {
+        return coefficients;
+    }
+
+    let (even, odd): (Vec<_>, Vec<_>) = coefficients
+        .into_iter()
+        .enumerate()
+        .partition(|(i, _)| i % 2 == 0);
+    let root_squared = root * root % modulus;
+    let even = fft(
+        even.into_iter().map(|(_, x)| x).collect(),
+        &root_squared,
+        modulus,
+    );
+    let odd = fft(
+        odd.into_iter().map(|(_, x)| x).collect(),
+        &root_squared,
+        modulus,
+    );
+
+    let half = size / 2;
+    let mut evaluations = vec![BigUint::default(); size];
+    let mut power = BigUint::from(1u32);
+    for i in 0..half {
+        let term = &power * &odd[i] % modulus;
+        evaluations[i] = (&even[i] + &term) % modulus;
+        evaluations[i + half] = (&even[i] + modulus - &term) % modulus;
+        power = power * root % modulus;
+    }
+
+    evaluations
+}
+
+/// Parses the state diff encoding used since Starknet v0.13.1.
+///
+/// The diff consists of the number of updated contracts, followed by each contract's address,
+/// its packed class flag, nonce and number of storage updates, its class hash if the flag is
+/// set, and its storage updates as key and value pairs. These are followed by the number of
+/// declared classes, and their class hash and compiled class hash pairs. Any remaining data is
+/// padding.
+pub fn parse_state_diff(data: &[Felt]) -> anyhow::Result<BlobStateDiff> {
+    let mut data = data.iter().copied();
+    let mut next = || data.next().context("Unexpected end of state diff");
+
+    let contract_count = felt_to_u64(next()?).context("Parsing contract count")?;
+    let mut contracts = Vec::new();
+    for _ in 0..contract_count {
+        let address = ContractAddress(next()?);
+
+        // [.. | class flag (1 bit) | nonce (64 bits) | number of storage updates (64 bits)]
+        let info = next()?.to_be_bytes();
+        anyhow::ensure!(
+            info[..15].iter().all(|byte| *byte == 0) && info[15] <= 1,
+            "Invalid contract info of {address}"
+        );
+        let has_class = info[15] == 1;
+        let nonce = u64::from_be_bytes(info[16..24].try_into().expect("8 bytes"));
+        let storage_count = u64::from_be_bytes(info[24..32].try_into().expect("8 bytes"));
+
+        let class_hash = if has_class {
+            Some(ClassHash(next()?))
+        } else {
+            None
+        };
+
+        let storage = (0..storage_count)
+            .map(|_| Ok((StorageAddress(next()?), StorageValue(next()?))))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        contracts.push(ContractDiff {
+            address,
+            nonce: ContractNonce(Felt::from_u64(nonce)),
+            class_hash,
+            storage,
+        });
+    }
+
+    let class_count = felt_to_u64(next()?).context("Parsing declared class count")?;
+    let declared_classes = (0..class_count)
+        .map(|_| Ok((ClassHash(next()?), CasmHash(next()?))))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(BlobStateDiff {
+        contracts,
+        declared_classes,
+    })
+}
+
+fn felt_to_u64(felt: Felt) -> anyhow::Result<u64> {
+    let bytes = felt.to_be_bytes();
+    anyhow::ensure!(
+        bytes[..24].iter().all(|byte| *byte == 0),
+        "{felt} does not fit into a u64"
+    );
+    Ok(u64::from_be_bytes(bytes[24..].try_into().expect("8 bytes")))
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    /// Encodes data as a blob, the inverse of [decode_blob].
+    fn encode_blob(data: &[Felt]) -> Vec<u8> {
+        let modulus = bls_modulus();
+        let mut coefficients = data
+            .iter()
+            .map(|felt| BigUint::from_bytes_be(felt.as_be_bytes()))
+            .collect::<Vec<_>>();
+        coefficients.resize(FIELD_ELEMENTS_PER_BLOB, BigUint::default());
+
+        let evaluations = fft(coefficients, root_of_unity(), modulus);
+
+        let bits = FIELD_ELEMENTS_PER_BLOB.trailing_zeros();
+        (0..FIELD_ELEMENTS_PER_BLOB)
+            .flat_map(|i| {
+                let evaluation = &evaluations[i.reverse_bits() >> (usize::BITS - bits)];
+                let bytes = evaluation.to_bytes_be();
+                let mut padded = vec![0u8; 32 - bytes.len()];
+                padded.extend(bytes);
+                padded
+            })
+            .collect()
+    }
+
+    fn state_diff_data() -> Vec<Felt> {
+        let mut info = [0u8; 32];
+        info[15] = 1;
+        info[16..24].copy_from_slice(&5u64.to_be_bytes());
+        info[24..32].copy_from_slice(&2u64.to_be_bytes());
+        let info_with_class = Felt::from_be_bytes(info).unwrap();
+
+        vec![
+            // Contracts
+            Felt::from_u64(2),
+            felt!("0x123"),
+            info_with_class,
+            felt!("0xc1a55"),
+            felt!("0x1"),
+            felt!("0x10"),
+            felt!("0x2"),
+            felt!("0x20"),
+            felt!("0x456"),
+            Felt::from_u64(0),
+            // Declared classes
+            Felt::from_u64(1),
+            felt!("0xde"),
+            felt!("0xca5e"),
+        ]
+    }
+
+    #[test]
+    fn parse() {
+        let expected = BlobStateDiff {
+            contracts: vec![
+                ContractDiff {
+                    address: contract_address!("0x123"),
+                    nonce: contract_nonce!("0x5"),
+                    class_hash: Some(class_hash!("0xc1a55")),
+                    storage: vec![
+                        (storage_address!("0x1"), storage_value!("0x10")),
+                        (storage_address!("0x2"), storage_value!("0x20")),
+                    ],
+                },
+                ContractDiff {
+                    address: contract_address!("0x456"),
+                    nonce: ContractNonce::ZERO,
+                    class_hash: None,
+                    storage: vec![],
+                },
+            ],
+            declared_classes: vec![(class_hash!("0xde"), casm_hash!("0xca5e"))],
+        };
+
+        let mut data = state_diff_data();
+        // Padding is ignored.
+        data.extend([Felt::ZERO; 3]);
+
+        assert_eq!(parse_state_diff(&data).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let data = state_diff_data();
+        parse_state_diff(&data[..data.len() - 1]).unwrap_err();
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        let data = state_diff_data();
+
+        let blob = encode_blob(&data);
+        let decoded = decode_blob(&blob).unwrap();
+
+        assert_eq!(&decoded[..data.len()], data.as_slice());
+        assert!(decoded[data.len()..].iter().all(|x| *x == Felt::ZERO));
+    }
+
+    #[test]
+    fn versioned_hash() {
+        let hash = kzg_to_versioned_hash(&[0u8; 48]);
+        assert_eq!(hash.as_bytes()[0], VERSIONED_HASH_VERSION_KZG);
+    }
+}
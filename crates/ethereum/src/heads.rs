@@ -0,0 +1,140 @@
+//! Watches new Ethereum blocks using an `eth_subscribe` WebSocket subscription.
+use std::time::Duration;
+
+use anyhow::Context;
+use futures::{SinkExt, StreamExt};
+use primitive_types::U256;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::Message;
+
+/// The delay before the first resubscription attempt, doubled on each further failure.
+const MIN_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(60);
+/// Subscriptions which don't deliver a head for this long are considered stale and renewed.
+/// Ethereum produces a block every 12 seconds.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Subscribes to the new blocks of the Ethereum node at the WebSocket `url`.
+///
+/// The receiver holds the number of the latest block, or [None] while there is no working
+/// subscription, in which case the caller should fall back to polling. Failed subscriptions are
+/// renewed automatically. The subscription ends once the receiver is dropped.
+pub fn watch_new_heads(url: reqwest::Url) -> watch::Receiver<Option<u64>> {
+    let (tx, rx) = watch::channel(None);
+
+    tokio::spawn(async move {
+        let mut delay = MIN_RESUBSCRIBE_DELAY;
+        loop {
+            let result = tokio::select! {
+                result = subscribe(&url, &tx) => result,
+                _ = tx.closed() => return,
+            };
+            tx.send_replace(None);
+
+            match result {
+                // A subscription which delivered heads was working, so it is renewed right away.
+                Ok(true) => delay = MIN_RESUBSCRIBE_DELAY,
+                Ok(false) => {}
+                Err(error) => {
+                    tracing::debug!(%error, "Ethereum head subscription failed");
+                }
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RESUBSCRIBE_DELAY);
+        }
+    });
+
+    rx
+}
+
+/// Forwards new heads until the subscription fails. Returns whether any head was received.
+async fn subscribe(url: &reqwest::Url, tx: &watch::Sender<Option<u64>>) -> anyhow::Result<bool> {
+    let (mut ws, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .context("Connecting")?;
+
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "eth_subscribe",
+        "params": ["newHeads"],
+        "id": 0
+    });
+    ws.send(Message::Text(request.to_string()))
+        .await
+        .context("Sending subscription request")?;
+
+    let mut received = false;
+    loop {
+        let message = match tokio::time::timeout(HEAD_TIMEOUT, ws.next()).await {
+            Ok(Some(message)) => message.context("Receiving message")?,
+            Ok(None) => return Ok(received),
+            Err(_) => anyhow::bail!("No new head within {HEAD_TIMEOUT:?}"),
+        };
+
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => return Ok(received),
+            // Pings are answered by the WebSocket implementation.
+            _ => continue,
+        };
+
+        let message: serde_json::Value = serde_json::from_str(&text).context("Parsing message")?;
+        if let Some(error) = message.get("error") {
+            anyhow::bail!("Subscribing failed: {error}");
+        }
+        // The subscription's ID is not needed, as this is the only subscription.
+        if message["method"] != "eth_subscription" {
+            continue;
+        }
+
+        let number = message["params"]["result"]["number"]
+            .as_str()
+            .and_then(|number| U256::from_str_radix(number.trim_start_matches("0x"), 16).ok())
+            .context("Parsing head number")?;
+
+        received = true;
+        tx.send_replace(Some(number.as_u64()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn heads_are_watched() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["method"], "eth_subscribe");
+
+            let subscribed = serde_json::json!({"jsonrpc": "2.0", "id": 0, "result": "0x1"});
+            ws.send(Message::Text(subscribed.to_string()))
+                .await
+                .unwrap();
+            let head = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_subscription",
+                "params": {"subscription": "0x1", "result": {"number": "0x10"}}
+            });
+            ws.send(Message::Text(head.to_string())).await.unwrap();
+
+            // The connection is closed once the test drops it.
+            ws
+        });
+
+        let mut heads = watch_new_heads(url.parse().unwrap());
+        heads.wait_for(|head| *head == Some(16)).await.unwrap();
+
+        // Closing the connection falls back to polling.
+        drop(server.await.unwrap());
+        heads.wait_for(|head| head.is_none()).await.unwrap();
+    }
+}
@@ -1,7 +1,13 @@
+use anyhow::Context;
 use pathfinder_common::{BlockHash, BlockNumber, EthereumChain, StateCommitment};
 use pathfinder_crypto::Felt;
 use primitive_types::{H160, H256, U256};
 
+pub mod blob;
+mod heads;
+
+pub use heads::watch_new_heads;
+
 pub mod core_addr {
     use const_decoder::Decoder;
 
@@ -17,11 +23,36 @@ pub struct EthereumStateUpdate {
     pub state_root: StateCommitment,
     pub block_number: BlockNumber,
     pub block_hash: BlockHash,
+    /// [None] if the transaction could not be found, e.g. because it is too old.
+    pub l1_transaction: Option<L1Transaction>,
+    /// The block of the preceding update on L1, i.e. this update accepted the blocks after it.
+    /// [None] if it is unknown.
+    pub previous_block_number: Option<BlockNumber>,
+    /// The L1 block at which the update was read. [None] for updates stored before this was
+    /// tracked.
+    pub l1_block: Option<L1Block>,
+}
+
+/// An L1 block, identified by both its number and hash so that reorgs can be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1Block {
+    pub number: u64,
+    pub hash: H256,
+}
+
+/// The L1 transaction which submitted an [EthereumStateUpdate].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1Transaction {
+    pub hash: H256,
+    /// The timestamp of the L1 block containing the transaction.
+    pub timestamp: u64,
 }
 
 #[async_trait::async_trait]
 pub trait EthereumApi {
     async fn get_starknet_state(&self, address: &H160) -> anyhow::Result<EthereumStateUpdate>;
+    /// The hash of the canonical L1 block with the given number, if it exists.
+    async fn get_block_hash(&self, number: u64) -> anyhow::Result<Option<H256>>;
     async fn get_chain(&self) -> anyhow::Result<EthereumChain>;
 }
 
@@ -29,10 +60,20 @@ pub trait EthereumApi {
 pub struct EthereumClient {
     http: reqwest::Client,
     url: reqwest::Url,
+    /// The state update read from a core contract at the last finalized block, which is reused
+    /// until the next block is finalized.
+    finalized_state: std::sync::Arc<std::sync::Mutex<Option<(H256, H160, EthereumStateUpdate)>>>,
+    /// The depth below the latest block at which the state is read. The finalized block is used
+    /// if [None].
+    confirmations: Option<u64>,
 }
 
 const HTTP_OK: u16 = 200;
 
+/// The number of L1 blocks, before the finalized block, searched for the transaction of a
+/// state update. This covers a few hours of L1 blocks.
+const STATE_UPDATE_LOG_RANGE: u64 = 1000;
+
 impl EthereumClient {
     pub fn with_password(mut url: reqwest::Url, password: &str) -> anyhow::Result<Self> {
         url.set_password(Some(password))
@@ -44,21 +85,153 @@ impl EthereumClient {
         Ok(Self {
             http: reqwest::ClientBuilder::new().build()?,
             url,
+            finalized_state: Default::default(),
+            confirmations: None,
         })
     }
 
-    async fn get_finalized_block_hash(&self) -> anyhow::Result<H256> {
+    /// Reads the Starknet state at the block `confirmations` blocks below the latest block,
+    /// instead of at the latest finalized block.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = Some(confirmations);
+        self
+    }
+
+    /// The hash and number of the latest L1 block considered final, i.e. the latest finalized
+    /// block or the block [confirmations](Self::with_confirmations) deep.
+    async fn get_finalized_block(&self) -> anyhow::Result<(H256, u64)> {
+        let block = match self.confirmations {
+            None => serde_json::json!("finalized"),
+            Some(confirmations) => {
+                let latest = self
+                    .call_ethereum(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "method": "eth_blockNumber",
+                        "params": [],
+                        "id": 0
+                    }))
+                    .await
+                    .and_then(|value| get_u256(&value))?
+                    .as_u64();
+                serde_json::json!(format!("0x{:x}", latest.saturating_sub(confirmations)))
+            }
+        };
+        let block = self
+            .call_ethereum(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBlockByNumber",
+                "params": [
+                    block,
+                    false
+                ],
+                "id": 0
+            }))
+            .await?;
+
+        let hash = get_h256(&block["hash"])?;
+        let number = get_u256(&block["number"])?.as_u64();
+        Ok((hash, number))
+    }
+
+    /// Searches the L1 blocks up to `to_block` for the transaction which submitted the state
+    /// update of the given Starknet block, along with the block of the preceding update if it is
+    /// also within these L1 blocks.
+    async fn get_state_update_transaction(
+        &self,
+        address: &str,
+        to_block: u64,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<(L1Transaction, Option<BlockNumber>)>> {
+        let from_block = to_block.saturating_sub(STATE_UPDATE_LOG_RANGE);
+        let logs = self
+            .call_ethereum(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getLogs",
+                "params": [
+                    {
+                        "address": address,
+                        "topics": [state_update_topic()],
+                        "fromBlock": format!("0x{from_block:x}"),
+                        "toBlock": format!("0x{to_block:x}")
+                    }
+                ],
+                "id": 0
+            }))
+            .await?;
+        let logs = logs.as_array().context("Logs are not an array")?;
+
+        // LogStateUpdate(uint256 globalRoot, int256 blockNumber, uint256 blockHash)
+        let log_block_number = |log: &serde_json::Value| {
+            log["data"]
+                .as_str()
+                .and_then(|data| data.strip_prefix("0x"))
+                .and_then(|data| data.get(64..128))
+                .and_then(|number| U256::from_str_radix(number, 16).ok())
+        };
+        let Some(position) = logs
+            .iter()
+            .rposition(|log| log_block_number(log) == Some(U256::from(block_number.get())))
+        else {
+            return Ok(None);
+        };
+        let log = &logs[position];
+        // The logs are ordered, so the preceding log is the previous update.
+        let previous_block_number = logs[..position]
+            .last()
+            .and_then(log_block_number)
+            .and_then(|number| get_number(number).ok());
+
+        let hash = get_h256(&log["transactionHash"])?;
+        let block = self
+            .call_ethereum(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBlockByHash",
+                "params": [
+                    log["blockHash"].clone(),
+                    false
+                ],
+                "id": 0
+            }))
+            .await?;
+        let timestamp = get_u256(&block["timestamp"])?.as_u64();
+
+        Ok(Some((
+            L1Transaction { hash, timestamp },
+            previous_block_number,
+        )))
+    }
+
+    /// The versioned hashes of the blobs posted by an L1 transaction. Empty if the transaction
+    /// posted no blobs.
+    pub async fn get_blob_versioned_hashes(&self, transaction: H256) -> anyhow::Result<Vec<H256>> {
+        let transaction = self
+            .call_ethereum(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getTransactionByHash",
+                "params": [
+                    format!("0x{}", hex::encode(transaction.as_bytes()))
+                ],
+                "id": 0
+            }))
+            .await?;
+        anyhow::ensure!(!transaction.is_null(), "L1 transaction not found");
+
+        match transaction["blobVersionedHashes"].as_array() {
+            Some(hashes) => hashes.iter().map(get_h256).collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The current gas price of L1, in wei.
+    pub async fn gas_price(&self) -> anyhow::Result<U256> {
         self.call_ethereum(serde_json::json!({
             "jsonrpc": "2.0",
-            "method": "eth_getBlockByNumber",
-            "params": [
-                "finalized",
-                false
-            ],
+            "method": "eth_gasPrice",
+            "params": [],
             "id": 0
         }))
         .await
-        .and_then(|value| get_h256(&value["hash"]))
+        .and_then(|value| get_u256(&value))
     }
 
     async fn call_starknet_contract(
@@ -102,10 +275,16 @@ impl EthereumClient {
 #[async_trait::async_trait]
 impl EthereumApi for EthereumClient {
     async fn get_starknet_state(&self, address: &H160) -> anyhow::Result<EthereumStateUpdate> {
-        let hash = self.get_finalized_block_hash().await?;
-        let hash = format!("0x{}", hex::encode(hash.as_bytes()));
+        let (finalized_hash, finalized_number) = self.get_finalized_block().await?;
+        if let Some((hash, core_address, update)) = &*self.finalized_state.lock().unwrap() {
+            if *hash == finalized_hash && core_address == address {
+                return Ok(update.clone());
+            }
+        }
+
+        let hash = format!("0x{}", hex::encode(finalized_hash.as_bytes()));
         let addr = format!("0x{}", hex::encode(address.as_bytes()));
-        Ok(EthereumStateUpdate {
+        let mut update = EthereumStateUpdate {
             state_root: self
                 .call_starknet_contract(&hash, &addr, "stateRoot()")
                 .await
@@ -123,7 +302,53 @@ impl EthereumApi for EthereumClient {
                 .await
                 .and_then(|value| get_u256(&value))
                 .and_then(get_number)?,
-        })
+            l1_transaction: None,
+            previous_block_number: None,
+            l1_block: Some(L1Block {
+                number: finalized_number,
+                hash: finalized_hash,
+            }),
+        };
+
+        // The update itself is authoritative, its transaction is only informational.
+        match self
+            .get_state_update_transaction(&addr, finalized_number, update.block_number)
+            .await
+        {
+            Ok(found) => {
+                if let Some((l1_transaction, previous_block_number)) = found {
+                    update.l1_transaction = Some(l1_transaction);
+                    update.previous_block_number = previous_block_number;
+                }
+                // Only complete updates are reused, so that the transaction is searched again.
+                *self.finalized_state.lock().unwrap() =
+                    Some((finalized_hash, *address, update.clone()));
+            }
+            Err(error) => {
+                tracing::debug!(%error, "Fetching L1 state update transaction failed");
+            }
+        }
+
+        Ok(update)
+    }
+
+    async fn get_block_hash(&self, number: u64) -> anyhow::Result<Option<H256>> {
+        let block = self
+            .call_ethereum(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_getBlockByNumber",
+                "params": [
+                    format!("0x{number:x}"),
+                    false
+                ],
+                "id": 0
+            }))
+            .await?;
+
+        if block.is_null() {
+            return Ok(None);
+        }
+        get_h256(&block["hash"]).map(Some)
     }
 
     async fn get_chain(&self) -> anyhow::Result<EthereumChain> {
@@ -144,6 +369,13 @@ impl EthereumApi for EthereumClient {
     }
 }
 
+/// The topic of the Starknet core contract's `LogStateUpdate` event.
+fn state_update_topic() -> String {
+    let mut output: [u8; 32] = Default::default();
+    keccak_hash::keccak_256(b"LogStateUpdate(uint256,int256,uint256)", &mut output[..]);
+    format!("0x{}", hex::encode(output))
+}
+
 fn encode_ethereum_call_data(signature: &[u8]) -> String {
     let mut output: [u8; 32] = Default::default();
     keccak_hash::keccak_256(signature, &mut output[..]);
@@ -237,6 +469,84 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_block_hash() -> anyhow::Result<()> {
+        let server = MockServer::start_async().await;
+
+        let mock = server.mock(|when, then| {
+            when.path("/")
+                .method(POST)
+                .header("Content-type", "application/json")
+                .body(r#"{"id":0,"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["0x10",false]}"#);
+            then.status(200)
+                .header("Content-type", "application/json")
+                .body(r#"{"jsonrpc":"2.0","id":0,"result":{"number":"0x10","hash":"0x9921984fd976f261e0d70618b51e3db3724b9f4d28d0534c3483dd2162f13fff"}}"#);
+        });
+        let mock_missing = server.mock(|when, then| {
+            when.path("/")
+                .method(POST)
+                .header("Content-type", "application/json")
+                .body(r#"{"id":0,"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["0x11",false]}"#);
+            then.status(200)
+                .header("Content-type", "application/json")
+                .body(r#"{"jsonrpc":"2.0","id":0,"result":null}"#);
+        });
+
+        let url = Url::parse(&server.url("/"))?;
+        let eth = EthereumClient::new(url)?;
+
+        let hash = eth.get_block_hash(0x10).await?;
+        mock.assert();
+        assert_eq!(
+            hash,
+            Some(H256::from_str(
+                "0x9921984fd976f261e0d70618b51e3db3724b9f4d28d0534c3483dd2162f13fff"
+            )?)
+        );
+
+        let hash = eth.get_block_hash(0x11).await?;
+        mock_missing.assert();
+        assert_eq!(hash, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_block() -> anyhow::Result<()> {
+        let server = MockServer::start_async().await;
+
+        let mock_latest = server.mock(|when, then| {
+            when.path("/")
+                .method(POST)
+                .header("Content-type", "application/json")
+                .body(r#"{"id":0,"jsonrpc":"2.0","method":"eth_blockNumber","params":[]}"#);
+            then.status(200)
+                .header("Content-type", "application/json")
+                .body(r#"{"jsonrpc":"2.0","id":0,"result":"0x100"}"#);
+        });
+        let mock_block = server.mock(|when, then| {
+            when.path("/")
+                .method(POST)
+                .header("Content-type", "application/json")
+                .body(r#"{"id":0,"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["0xf6",false]}"#);
+            then.status(200)
+                .header("Content-type", "application/json")
+                .body(r#"{"jsonrpc":"2.0","id":0,"result":{"number":"0xf6","hash":"0x9921984fd976f261e0d70618b51e3db3724b9f4d28d0534c3483dd2162f13fff"}}"#);
+        });
+
+        let url = Url::parse(&server.url("/"))?;
+        let eth = EthereumClient::new(url)?.with_confirmations(10);
+        let (hash, number) = eth.get_finalized_block().await?;
+
+        mock_latest.assert();
+        mock_block.assert();
+        assert_eq!(number, 0xf6);
+        assert_eq!(
+            hash,
+            H256::from_str("0x9921984fd976f261e0d70618b51e3db3724b9f4d28d0534c3483dd2162f13fff")?
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_starknet_state() -> anyhow::Result<()> {
         let server = MockServer::start_async().await;
@@ -281,6 +591,27 @@ mod tests {
                 .body(r#"{"jsonrpc":"2.0","id":0,"result":"0x02a4651c1ba5151c48ebeb4477216b04d7a65058a5b99e5fbc602507ae933d2f"}"#);
         });
 
+        let mock_logs = server.mock(|when, then| {
+            when.path("/")
+                .method(POST)
+                .header("Content-type", "application/json")
+                .body_contains(r#""method":"eth_getLogs""#)
+                .body_contains(r#""fromBlock":"0x1048a26","toBlock":"0x1048e0e""#);
+            then.status(200)
+                .header("Content-type", "application/json")
+                .body(r#"{"jsonrpc":"2.0","id":0,"result":[{"blockHash":"0xabce000000000000000000000000000000000000000000000000000000000000","data":"0x02a4651c1ba5151c48ebeb4477216b04d7a65058a5b99e5fbc602507ae933d2f0000000000000000000000000000000000000000000000000000000000007ee002a4651c1ba5151c48ebeb4477216b04d7a65058a5b99e5fbc602507ae933d2f","transactionHash":"0x1233000000000000000000000000000000000000000000000000000000000000"},{"blockHash":"0xabcd000000000000000000000000000000000000000000000000000000000000","data":"0x02a4651c1ba5151c48ebeb4477216b04d7a65058a5b99e5fbc602507ae933d2f0000000000000000000000000000000000000000000000000000000000007eeb02a4651c1ba5151c48ebeb4477216b04d7a65058a5b99e5fbc602507ae933d2f","transactionHash":"0x1234000000000000000000000000000000000000000000000000000000000000"}]}"#);
+        });
+
+        let mock_log_block = server.mock(|when, then| {
+            when.path("/")
+                .method(POST)
+                .header("Content-type", "application/json")
+                .body(r#"{"id":0,"jsonrpc":"2.0","method":"eth_getBlockByHash","params":["0xabcd000000000000000000000000000000000000000000000000000000000000",false]}"#);
+            then.status(200)
+                .header("Content-type", "application/json")
+                .body(r#"{"jsonrpc":"2.0","id":0,"result":{"number":"0x1048d00","timestamp":"0x65000000"}}"#);
+        });
+
         let url = Url::parse(&server.url("/"))?;
         let eth = EthereumClient::new(url)?;
 
@@ -293,6 +624,19 @@ mod tests {
             state_root: StateCommitment(get_felt(global_root)?),
             block_number: get_number(block_number)?,
             block_hash: BlockHash(get_felt(block_hash)?),
+            l1_transaction: Some(L1Transaction {
+                hash: H256::from_str(
+                    "0x1234000000000000000000000000000000000000000000000000000000000000",
+                )?,
+                timestamp: 0x65000000,
+            }),
+            previous_block_number: Some(BlockNumber::new_or_panic(0x7ee0)),
+            l1_block: Some(L1Block {
+                number: 0x1048e0e,
+                hash: H256::from_str(
+                    "0x9921984fd976f261e0d70618b51e3db3724b9f4d28d0534c3483dd2162f13fff",
+                )?,
+            }),
         };
 
         let addr = H160::from_slice(&core_addr::MAINNET);
@@ -302,6 +646,15 @@ mod tests {
         mock_block_number.assert();
         mock_block_hash.assert();
         mock_state_root.assert();
+        mock_logs.assert();
+        mock_log_block.assert();
+        assert_eq!(state, expected);
+
+        // The state is only read again once the next block is finalized.
+        let state = eth.get_starknet_state(&addr).await?;
+        mock_ethereum_block.assert_hits(2);
+        mock_block_number.assert();
+        mock_logs.assert();
         assert_eq!(state, expected);
         Ok(())
     }
@@ -12,6 +12,9 @@ use super::felt::IntoStarkFelt;
 pub const FEE_TOKEN_ADDRESS: ContractAddress =
     contract_address!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
 
+pub(crate) const INVOKE_TX_MAX_N_STEPS: u32 = 3_000_000;
+const VALIDATE_MAX_N_STEPS: u32 = 1_000_000;
+
 pub(super) fn construct_block_context(
     execution_state: &ExecutionState<'_>,
 ) -> anyhow::Result<BlockContext> {
@@ -42,8 +45,18 @@ pub(super) fn construct_block_context(
         fee_token_address,
         vm_resource_fee_cost: Arc::new(default_resource_fee_costs()),
         gas_price: execution_state.header.gas_price.0,
-        invoke_tx_max_n_steps: 3_000_000,
-        validate_max_n_steps: 1_000_000,
+        invoke_tx_max_n_steps: execution_state
+            .limits
+            .max_steps
+            .map_or(INVOKE_TX_MAX_N_STEPS, |limit| {
+                limit.min(INVOKE_TX_MAX_N_STEPS)
+            }),
+        validate_max_n_steps: execution_state
+            .limits
+            .max_steps
+            .map_or(VALIDATE_MAX_N_STEPS, |limit| {
+                limit.min(VALIDATE_MAX_N_STEPS)
+            }),
         max_recursion_depth: 50,
     })
 }
@@ -19,6 +19,7 @@ pub fn call(
     entry_point_selector: EntryPoint,
     calldata: Vec<CallParam>,
 ) -> Result<Vec<CallResultValue>, CallError> {
+    let limits = execution_state.limits;
     let (mut state, block_context) = execution_state.starknet_state()?;
 
     let contract_address = starknet_api::core::ContractAddress(PatriciaKey::try_from(
@@ -47,7 +48,10 @@ pub fn call(
         &AccountTransactionContext::default(),
     );
 
-    let call_info = call_entry_point.execute(&mut state, &mut resources, &mut context)?;
+    let call_info = call_entry_point
+        .execute(&mut state, &mut resources, &mut context)
+        .map_err(|error| limits.check_steps(error.into()))?;
+    limits.check_builtins(resources.vm_resources.builtin_instance_counter.values())?;
 
     let result = call_info
         .execution
@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use blockifier::state::errors::StateError;
+
+use crate::CallError;
+
+/// Stops an execution whose result is no longer needed, see
+/// [ExecutionState::with_cancellation](crate::ExecutionState::with_cancellation).
+///
+/// Blockifier runs the Cairo VM to completion without a way to interrupt it, so cancellation is
+/// checked before each transaction and on each read of state the execution has not read before.
+/// Executions run via [ExecutionState::run_in_chunks](crate::ExecutionState::run_in_chunks) also
+/// check it each time they exhaust a chunk of steps.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns a guard which cancels the token once it is dropped, e.g. along with the future of
+    /// a request whose client disconnected.
+    pub fn drop_guard(&self) -> DropGuard {
+        DropGuard(self.clone())
+    }
+
+    pub(crate) fn check(&self) -> Result<(), CallError> {
+        match self.is_cancelled() {
+            true => Err(CallError::Internal(anyhow::anyhow!("Execution cancelled"))),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_state_read(&self) -> Result<(), StateError> {
+        match self.is_cancelled() {
+            true => Err(StateError::StateReadError("Execution cancelled".to_owned())),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Cancels its [CancellationToken] when dropped.
+#[derive(Debug)]
+pub struct DropGuard(CancellationToken);
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
@@ -9,6 +9,11 @@ pub enum CallError {
     ContractNotFound,
     InvalidMessageSelector,
     Reverted(String),
+    /// The execution exceeded one of its [ExecutionLimits](crate::ExecutionLimits).
+    ResourcesExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
     Internal(anyhow::Error),
     Custom(anyhow::Error),
 }
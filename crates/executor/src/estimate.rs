@@ -13,10 +13,14 @@ pub fn estimate(
     let gas_price: U256 = execution_state.header.gas_price.0.into();
     let block_number = execution_state.header.number;
 
+    let cancellation = execution_state.cancellation.clone();
+    let limits = execution_state.limits;
     let (mut state, block_context) = execution_state.starknet_state()?;
 
     let mut fees = Vec::with_capacity(transactions.len());
     for (transaction_idx, transaction) in transactions.into_iter().enumerate() {
+        cancellation.check()?;
+
         let _span = tracing::debug_span!("estimate", transaction_hash=%super::transaction::transaction_hash(&transaction), %block_number, %transaction_idx).entered();
 
         let tx_info = transaction
@@ -37,8 +41,9 @@ pub fn estimate(
             Ok(tx_info) => {
                 if let Some(revert_error) = tx_info.revert_error {
                     tracing::debug!(%revert_error, "Transaction reverted");
-                    return Err(CallError::Reverted(revert_error));
+                    return Err(limits.check_steps(CallError::Reverted(revert_error)));
                 }
+                limits.check_transaction_builtins(&tx_info.actual_resources)?;
 
                 tracing::trace!(actual_fee=%tx_info.actual_fee.0, actual_resources=?tx_info.actual_resources, "Transaction estimation finished");
 
@@ -50,7 +55,7 @@ pub fn estimate(
             }
             Err(error) => {
                 tracing::debug!(%error, %transaction_idx, "Transaction estimation failed");
-                return Err(error.into());
+                return Err(limits.check_steps(error.into()));
             }
         }
     }
@@ -6,13 +6,27 @@ use blockifier::{
     state::{cached_state::CachedState, state_api::State},
 };
 use pathfinder_common::{BlockHeader, ChainId, StateUpdate};
+use std::sync::Arc;
 
+use crate::{
+    block_context::INVOKE_TX_MAX_N_STEPS, CallError, CancellationToken, ExecutionLimits,
+    RemoteState,
+};
+
+/// The steps of the first attempt of [ExecutionState::run_in_chunks].
+const STEP_CHUNK: u32 = 100_000;
+
+#[derive(Clone)]
 pub struct ExecutionState<'tx> {
     transaction: &'tx pathfinder_storage::Transaction<'tx>,
     pub chain_id: ChainId,
     pub header: BlockHeader,
     execute_on_parent_state: bool,
     pending_state: Option<StateUpdate>,
+    remote_state: Option<Arc<dyn RemoteState>>,
+    pub(super) cancellation: CancellationToken,
+    pub(super) limits: ExecutionLimits,
+    step_chunk: u32,
 }
 
 impl<'tx> ExecutionState<'tx> {
@@ -34,6 +48,8 @@ impl<'tx> ExecutionState<'tx> {
             self.transaction,
             block_number,
             self.pending_state.is_some(),
+            self.remote_state.clone(),
+            self.cancellation.clone(),
         );
         let mut cached_state = LruCachedReader::new_cached_state(raw_reader)?;
 
@@ -42,10 +58,19 @@ impl<'tx> ExecutionState<'tx> {
         if self.execute_on_parent_state && self.header.number.get() >= 10 {
             let block_number_whose_hash_becomes_available =
                 pathfinder_common::BlockNumber::new_or_panic(self.header.number.get() - 10);
-            let (_, block_hash) = self
+            let block_hash = match self
                 .transaction
                 .block_id(block_number_whose_hash_becomes_available.into())?
-                .context("Getting historical block hash")?;
+            {
+                Some((_, block_hash)) => Some(block_hash),
+                None => match &self.remote_state {
+                    Some(remote_state) => {
+                        remote_state.block_hash(block_number_whose_hash_becomes_available)?
+                    }
+                    None => None,
+                },
+            }
+            .context("Getting historical block hash")?;
 
             tracing::trace!(%block_number_whose_hash_becomes_available, %block_hash, "Setting historical block hash");
 
@@ -81,6 +106,10 @@ impl<'tx> ExecutionState<'tx> {
             header,
             pending_state,
             execute_on_parent_state: true,
+            remote_state: None,
+            cancellation: Default::default(),
+            limits: Default::default(),
+            step_chunk: STEP_CHUNK,
         }
     }
 
@@ -96,6 +125,76 @@ impl<'tx> ExecutionState<'tx> {
             header,
             pending_state,
             execute_on_parent_state: false,
+            remote_state: None,
+            cancellation: Default::default(),
+            limits: Default::default(),
+            step_chunk: STEP_CHUNK,
         }
     }
+
+    /// Reads state which is missing from the database from `remote_state` instead.
+    pub fn with_remote_state(mut self, remote_state: Option<Arc<dyn RemoteState>>) -> Self {
+        self.remote_state = remote_state;
+        self
+    }
+
+    /// Stops the execution with an error once `cancellation` is cancelled.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Fails transactions and calls which exceed `limits` with
+    /// [CallError::ResourcesExceeded](crate::CallError::ResourcesExceeded).
+    pub fn with_limits(mut self, limits: ExecutionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the steps of the first attempt of [run_in_chunks](Self::run_in_chunks).
+    pub fn with_step_chunk(mut self, steps: u32) -> Self {
+        self.step_chunk = steps;
+        self
+    }
+
+    /// Runs `execute` with a growing step limit, so that [cancellation](Self::with_cancellation)
+    /// is noticed while a long running execution is in progress.
+    ///
+    /// Blockifier can't interrupt the VM, so the execution is first limited to a chunk of steps.
+    /// Once it runs out of them, the cancellation is checked and it is restarted with twice the
+    /// steps, until it runs with its full [limits](Self::with_limits). The steps of all attempts
+    /// before the last add up to fewer than those of the last one.
+    ///
+    /// `execute` must fail with [CallError::ResourcesExceeded] once it runs out of steps, which
+    /// rules out traces: these report transactions which ran out of steps as reverted.
+    pub fn run_in_chunks<T>(
+        self,
+        mut execute: impl FnMut(ExecutionState<'tx>) -> Result<T, CallError>,
+    ) -> Result<T, CallError> {
+        let max_steps = self
+            .limits
+            .max_steps
+            .map_or(INVOKE_TX_MAX_N_STEPS, |limit| {
+                limit.min(INVOKE_TX_MAX_N_STEPS)
+            });
+
+        let mut steps = self.step_chunk.max(1);
+        while steps < max_steps {
+            self.cancellation.check()?;
+
+            let limits = ExecutionLimits {
+                max_steps: Some(steps),
+                ..self.limits
+            };
+            match execute(self.clone().with_limits(limits)) {
+                Err(CallError::ResourcesExceeded {
+                    resource: "steps", ..
+                }) => steps = steps.saturating_mul(2),
+                result => return result,
+            }
+        }
+
+        self.cancellation.check()?;
+        execute(self)
+    }
 }
@@ -1,11 +1,14 @@
 pub(crate) mod block_context;
 pub(crate) mod call;
+pub(crate) mod cancellation;
 pub(crate) mod class;
 pub(crate) mod error;
 pub(crate) mod estimate;
 pub(crate) mod execution_state;
 pub(crate) mod felt;
+pub(crate) mod limits;
 pub(crate) mod pending;
+pub(crate) mod remote_state;
 pub(crate) mod simulate;
 pub(crate) mod state_reader;
 pub(crate) mod transaction;
@@ -13,12 +16,15 @@ pub mod types;
 
 pub use block_context::FEE_TOKEN_ADDRESS;
 pub use call::call;
+pub use cancellation::{CancellationToken, DropGuard};
 pub use class::{parse_casm_definition, parse_deprecated_class_definition};
 pub use error::CallError;
 pub use estimate::estimate;
 pub use execution_state::ExecutionState;
 pub use felt::{IntoFelt, IntoStarkFelt};
-pub use simulate::{simulate, trace_all, trace_one};
+pub use limits::ExecutionLimits;
+pub use remote_state::RemoteState;
+pub use simulate::{execute_block, simulate, trace_all, trace_one};
 
 // re-export blockifier transaction type since it's exposed on our API
 pub use blockifier::transaction::account_transaction::AccountTransaction;
@@ -0,0 +1,69 @@
+use blockifier::transaction::objects::ResourcesMapping;
+
+use crate::CallError;
+
+/// Limits on the resources each transaction or call of an execution may use, see
+/// [ExecutionState::with_limits](crate::ExecutionState::with_limits).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Cairo steps, enforced by the VM while executing. Limits above the network's own step
+    /// limits have no effect.
+    pub max_steps: Option<u32>,
+    /// Builtin instances, e.g. range checks and hashes, checked once a transaction or call
+    /// finished.
+    pub max_builtins: Option<usize>,
+}
+
+/// The message of the VM once it ran out of steps.
+const STEPS_EXHAUSTED: &str = "RunResources has no remaining steps";
+
+impl ExecutionLimits {
+    /// Replaces the error or revert reason of an execution which ran out of steps with
+    /// [CallError::ResourcesExceeded].
+    pub(crate) fn check_steps(&self, error: CallError) -> CallError {
+        let message = match &error {
+            CallError::Reverted(message) => message.clone(),
+            CallError::Custom(e) | CallError::Internal(e) => format!("{e:#}"),
+            _ => return error,
+        };
+
+        match self.max_steps {
+            Some(limit) if message.contains(STEPS_EXHAUSTED) => CallError::ResourcesExceeded {
+                resource: "steps",
+                limit: limit as usize,
+            },
+            _ => error,
+        }
+    }
+
+    pub(crate) fn check_builtins<'a>(
+        &self,
+        builtins: impl IntoIterator<Item = &'a usize>,
+    ) -> Result<(), CallError> {
+        let Some(limit) = self.max_builtins else {
+            return Ok(());
+        };
+
+        match builtins.into_iter().sum::<usize>() > limit {
+            true => Err(CallError::ResourcesExceeded {
+                resource: "builtins",
+                limit,
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Checks the builtins of a transaction's resources, which also contain its steps and gas.
+    pub(crate) fn check_transaction_builtins(
+        &self,
+        resources: &ResourcesMapping,
+    ) -> Result<(), CallError> {
+        self.check_builtins(
+            resources
+                .0
+                .iter()
+                .filter(|(name, _)| name.ends_with("_builtin"))
+                .map(|(_, count)| count),
+        )
+    }
+}
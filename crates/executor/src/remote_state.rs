@@ -0,0 +1,37 @@
+use pathfinder_common::{
+    BlockHash, BlockNumber, ClassHash, ContractAddress, ContractNonce, StorageAddress, StorageValue,
+};
+
+/// The state of a remote network at a fixed block, which is read wherever the local database has
+/// no value, see [ExecutionState::with_remote_state](crate::ExecutionState::with_remote_state).
+///
+/// This lets local blocks be executed on top of a network's state without syncing it first.
+/// The reads are blocking, as is the rest of the executor.
+pub trait RemoteState: Send + Sync {
+    fn storage_value(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageAddress,
+    ) -> anyhow::Result<StorageValue>;
+
+    fn contract_nonce(&self, contract_address: ContractAddress) -> anyhow::Result<ContractNonce>;
+
+    /// [None] if no contract is deployed at the address.
+    fn contract_class_hash(
+        &self,
+        contract_address: ContractAddress,
+    ) -> anyhow::Result<Option<ClassHash>>;
+
+    /// The definition of a class as served by the gateway, or [None] if the class is not declared.
+    ///
+    /// The executor only reads the definitions of Cairo classes, and the CASM definitions of
+    /// Sierra classes, but `starknet_getClass` serves both.
+    fn class_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// The CASM definition of a Sierra class, or [None] if the class is not a declared Sierra
+    /// class.
+    fn casm_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// The hash of a block at or below the remote state's block.
+    fn block_hash(&self, block: BlockNumber) -> anyhow::Result<Option<BlockHash>>;
+}
@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
 use blockifier::{
+    block_context::BlockContext,
     state::{cached_state::CachedState, errors::StateError, state_api::State},
     transaction::transaction_execution::Transaction,
     transaction::{errors::TransactionExecutionError, transactions::ExecutableTransaction},
@@ -18,13 +19,13 @@ use crate::{
         DeployedContract, ExecuteInvocation, InvokeTransactionTrace, L1HandlerTransactionTrace,
         ReplacedClass, StateDiff, StorageDiff,
     },
-    IntoFelt,
+    IntoFelt, IntoStarkFelt,
 };
 
 use super::{
     error::CallError,
     execution_state::ExecutionState,
-    types::{FeeEstimate, TransactionSimulation, TransactionTrace},
+    types::{FeeEstimate, TransactionExecution, TransactionSimulation, TransactionTrace},
 };
 
 pub fn simulate(
@@ -32,20 +33,29 @@ pub fn simulate(
     transactions: Vec<Transaction>,
     skip_validate: bool,
     skip_fee_charge: bool,
+    skip_balance_check: bool,
 ) -> Result<Vec<TransactionSimulation>, CallError> {
     let gas_price: U256 = execution_state.header.gas_price.0.into();
     let block_number = execution_state.header.number;
 
+    let cancellation = execution_state.cancellation.clone();
+    let limits = execution_state.limits;
     let (mut state, block_context) = execution_state.starknet_state()?;
 
     let mut simulations = Vec::with_capacity(transactions.len());
     for (transaction_idx, transaction) in transactions.into_iter().enumerate() {
+        cancellation.check()?;
+
         let _span = tracing::debug_span!("simulate", transaction_hash=%super::transaction::transaction_hash(&transaction), %block_number, %transaction_idx).entered();
 
         let transaction_type = transaction_type(&transaction);
         let transaction_declared_deprecated_class_hash =
             transaction_declared_deprecated_class(&transaction);
 
+        if skip_balance_check {
+            fund_deployed_account(&mut state, &block_context, &transaction)?;
+        }
+
         let mut tx_state = CachedState::<_>::create_transactional(&mut state);
         let tx_info = transaction
             .execute(
@@ -72,8 +82,9 @@ pub fn simulate(
             Ok(tx_info) => {
                 if let Some(revert_error) = tx_info.revert_error {
                     tracing::trace!(%revert_error, "Transaction reverted");
-                    return Err(CallError::Reverted(revert_error));
+                    return Err(limits.check_steps(CallError::Reverted(revert_error)));
                 }
+                limits.check_transaction_builtins(&tx_info.actual_resources)?;
 
                 tracing::trace!(actual_fee=%tx_info.actual_fee.0, actual_resources=?tx_info.actual_resources, "Transaction simulation finished");
 
@@ -88,7 +99,7 @@ pub fn simulate(
             }
             Err(error) => {
                 tracing::debug!(%error, %transaction_idx, "Transaction simulation failed");
-                return Err(error.into());
+                return Err(limits.check_steps(error.into()));
             }
         }
     }
@@ -102,9 +113,12 @@ pub fn trace_one(
     charge_fee: bool,
     validate: bool,
 ) -> Result<TransactionTrace, CallError> {
+    let cancellation = execution_state.cancellation.clone();
     let (mut state, block_context) = execution_state.starknet_state()?;
 
     for (transaction_idx, tx) in transactions.into_iter().enumerate() {
+        cancellation.check()?;
+
         let _span = tracing::debug_span!("simulate", transaction_hash=%super::transaction::transaction_hash(&tx), %transaction_idx).entered();
 
         let hash = transaction_hash(&tx);
@@ -134,10 +148,13 @@ pub fn trace_all(
     charge_fee: bool,
     validate: bool,
 ) -> Result<Vec<(TransactionHash, TransactionTrace)>, CallError> {
+    let cancellation = execution_state.cancellation.clone();
     let (mut state, block_context) = execution_state.starknet_state()?;
 
     let mut ret = Vec::with_capacity(transactions.len());
     for (transaction_idx, tx) in transactions.into_iter().enumerate() {
+        cancellation.check()?;
+
         let _span = tracing::debug_span!("simulate", transaction_hash=%super::transaction::transaction_hash(&tx), %transaction_idx).entered();
 
         let hash = transaction_hash(&tx);
@@ -156,6 +173,66 @@ pub fn trace_all(
     Ok(ret)
 }
 
+/// Executes the transactions of a block on the state of its parent, charging fees and validating
+/// them as the sequencer did.
+///
+/// Unlike [simulate], reverted transactions are executed as part of the block, with the revert
+/// reason in their trace.
+pub fn execute_block(
+    mut execution_state: ExecutionState<'_>,
+    transactions: Vec<Transaction>,
+) -> Result<Vec<TransactionExecution>, CallError> {
+    let cancellation = execution_state.cancellation.clone();
+    let (mut state, block_context) = execution_state.starknet_state()?;
+
+    let mut executions = Vec::with_capacity(transactions.len());
+    for (transaction_idx, tx) in transactions.into_iter().enumerate() {
+        cancellation.check()?;
+
+        let hash = transaction_hash(&tx);
+        let _span =
+            tracing::debug_span!("execute", transaction_hash=%hash, %transaction_idx).entered();
+
+        let tx_type = transaction_type(&tx);
+        let tx_declared_deprecated_class_hash = transaction_declared_deprecated_class(&tx);
+
+        let mut tx_state = CachedState::<_>::create_transactional(&mut state);
+        let tx_info = tx.execute(&mut tx_state, &block_context, true, true)?;
+        let state_diff = to_state_diff(&mut tx_state, tx_declared_deprecated_class_hash)?;
+        tx_state.commit();
+
+        let memory_holes = [
+            &tx_info.validate_call_info,
+            &tx_info.execute_call_info,
+            &tx_info.fee_transfer_call_info,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|call_info| call_info.vm_resources.n_memory_holes)
+        .sum();
+
+        // The resources also contain the L1 gas usage, which is not an execution resource.
+        let resources = &tx_info.actual_resources.0;
+        let steps = resources.get("n_steps").copied().unwrap_or_default();
+        let builtins = resources
+            .iter()
+            .filter(|(name, _)| name.ends_with("_builtin"))
+            .map(|(name, count)| (name.clone(), *count))
+            .collect();
+
+        executions.push(TransactionExecution {
+            transaction_hash: hash,
+            actual_fee: tx_info.actual_fee.0,
+            steps,
+            memory_holes,
+            builtins,
+            trace: to_trace(tx_type, tx_info, state_diff)?,
+        });
+    }
+
+    Ok(executions)
+}
+
 enum TransactionType {
     Declare,
     DeployAccount,
@@ -180,6 +257,35 @@ fn transaction_type(transaction: &Transaction) -> TransactionType {
     }
 }
 
+/// Credits the account deployed by a DEPLOY_ACCOUNT transaction with enough of the fee token to
+/// cover its max fee, so that the transaction passes the balance check even if the address has
+/// not been funded yet. The fee is still charged, so the fee transfer is part of the simulation.
+fn fund_deployed_account<S: State>(
+    state: &mut S,
+    block_context: &BlockContext,
+    transaction: &Transaction,
+) -> Result<(), StateError> {
+    let Transaction::AccountTransaction(
+        blockifier::transaction::account_transaction::AccountTransaction::DeployAccount(tx),
+    ) = transaction
+    else {
+        return Ok(());
+    };
+
+    let balance_key = blockifier::abi::abi_utils::get_fee_token_var_address(&tx.contract_address);
+    let balance = state.get_storage_at(block_context.fee_token_address, balance_key)?;
+    let max_fee = pathfinder_crypto::Felt::from(tx.max_fee.0);
+    if balance.into_felt() < max_fee {
+        state.set_storage_at(
+            block_context.fee_token_address,
+            balance_key,
+            max_fee.into_starkfelt(),
+        );
+    }
+
+    Ok(())
+}
+
 fn transaction_declared_deprecated_class(transaction: &Transaction) -> Option<ClassHash> {
     match transaction {
         Transaction::AccountTransaction(
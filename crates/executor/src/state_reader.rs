@@ -5,7 +5,7 @@ use blockifier::{
         state_api::StateResult,
     },
 };
-use cached::{Cached, SizedCache};
+use pathfinder_common::cache::{CacheRegistry, MemoryAccounted};
 use pathfinder_common::{BlockNumber, ClassHash, StorageAddress, StorageValue};
 use pathfinder_crypto::Felt;
 use starknet_api::{
@@ -14,15 +14,34 @@ use starknet_api::{
     state::StorageKey,
     StarknetApiError,
 };
+use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex, MutexGuard};
 use tracing::warn;
 
 use super::felt::{IntoFelt, IntoStarkFelt};
+use crate::{CancellationToken, RemoteState};
 
-type ContractClassLRUCache = SizedCache<starknet_api::core::ClassHash, ContractClass>;
+type ContractClassLRUCache = lru::LruCache<starknet_api::core::ClassHash, ContractClass>;
+
+/// Estimated memory used by a compiled class, as their actual size is not exposed.
+const ESTIMATED_CLASS_SIZE: usize = 1024 * 1024;
 
 #[derive(Clone)]
-struct GlobalContractCache(pub Arc<Mutex<ContractClassLRUCache>>);
+struct GlobalContractCache(pub Arc<ContractCacheInner>);
+
+struct ContractCacheInner(Mutex<ContractClassLRUCache>);
+
+impl MemoryAccounted for ContractCacheInner {
+    fn memory_usage(&self) -> usize {
+        self.0.lock().map(|cache| cache.len()).unwrap_or_default() * ESTIMATED_CLASS_SIZE
+    }
+
+    fn shrink_to(&self, bytes: usize) {
+        if let Ok(mut cache) = self.0.lock() {
+            while cache.len() * ESTIMATED_CLASS_SIZE > bytes && cache.pop_lru().is_some() {}
+        }
+    }
+}
 
 /// A `StateReader` wrapper designed to cache the compiled contract classes.
 /// Contract classes are immutable once deployed so caching should not cause any side effect.
@@ -53,8 +72,13 @@ where
     fn new(inner_reader: R) -> anyhow::Result<Self> {
         lazy_static::lazy_static!(
             static ref CONTRACT_CACHE: GlobalContractCache = {
-                let inner = SizedCache::with_size(128);
-                GlobalContractCache(Arc::new(Mutex::new(inner)))
+                let inner = lru::LruCache::new(NonZeroUsize::new(128).unwrap());
+                let inner = Arc::new(ContractCacheInner(Mutex::new(inner)));
+
+                let accounted: Arc<dyn MemoryAccounted> = inner.clone();
+                CacheRegistry::global().register("executor_classes", &accounted);
+
+                GlobalContractCache(inner)
             };
         );
 
@@ -64,10 +88,8 @@ where
         })
     }
 
-    fn locked_cache(
-        &mut self,
-    ) -> StateResult<MutexGuard<'_, SizedCache<StarknetClassHash, ContractClass>>> {
-        self.compiled_class_cache.0.lock().map_err(|err| {
+    fn locked_cache(&mut self) -> StateResult<MutexGuard<'_, ContractClassLRUCache>> {
+        self.compiled_class_cache.0 .0.lock().map_err(|err| {
             warn!("Contract class cache lock is poisoned. Cause: {}.", err);
             StateError::StateReadError("Poisoned lock".to_string())
         })
@@ -104,14 +126,14 @@ where
         // Check the cache, if not found then lookup & insert.
         // Because the lookup can take quite a lot of time and classes are insert-only, it's better
         // to separate the get & set operations and release the lock in the meantime.
-        if let Some(contract_class) = self.locked_cache()?.cache_get(class_hash) {
+        if let Some(contract_class) = self.locked_cache()?.get(class_hash) {
             return Ok(contract_class.clone());
         }
 
         let contract_class = self.inner_reader.get_compiled_contract_class(class_hash)?;
 
         self.locked_cache()?
-            .cache_set(*class_hash, contract_class.clone());
+            .put(*class_hash, contract_class.clone());
 
         Ok(contract_class)
     }
@@ -131,6 +153,9 @@ pub(super) struct PathfinderStateReader<'tx> {
     // This flag makes it possible to find these classes -- essentially makes the state
     // reader look up classes which are not declared at a canonical block yet.
     ignore_block_number_for_classes: bool,
+    /// Read wherever the database has no value.
+    remote_state: Option<Arc<dyn RemoteState>>,
+    cancellation: CancellationToken,
 }
 
 impl<'tx> PathfinderStateReader<'tx> {
@@ -138,11 +163,15 @@ impl<'tx> PathfinderStateReader<'tx> {
         transaction: &'tx pathfinder_storage::Transaction<'tx>,
         block_number: Option<BlockNumber>,
         ignore_block_number_for_classes: bool,
+        remote_state: Option<Arc<dyn RemoteState>>,
+        cancellation: CancellationToken,
     ) -> Self {
         Self {
             transaction,
             block_number,
             ignore_block_number_for_classes,
+            remote_state,
+            cancellation,
         }
     }
     fn state_block_id(&self) -> Option<pathfinder_storage::BlockId> {
@@ -156,6 +185,8 @@ impl StateReader for PathfinderStateReader<'_> {
         contract_address: starknet_api::core::ContractAddress,
         storage_key: starknet_api::state::StorageKey,
     ) -> blockifier::state::state_api::StateResult<StarkFelt> {
+        self.cancellation.check_state_read()?;
+
         let storage_key =
             StorageAddress::new(storage_key.0.key().into_felt()).ok_or_else(|| {
                 StateError::StarknetApiError(StarknetApiError::OutOfRange {
@@ -179,8 +210,14 @@ impl StateReader for PathfinderStateReader<'_> {
         let storage_val = self
             .transaction
             .storage_value(block_id, pathfinder_contract_address, storage_key)
-            .map_err(map_anyhow_to_state_err)?
-            .unwrap_or(StorageValue(Felt::ZERO));
+            .map_err(map_anyhow_to_state_err)?;
+        let storage_val = match (storage_val, &self.remote_state) {
+            (Some(storage_val), _) => storage_val,
+            (None, Some(remote_state)) => remote_state
+                .storage_value(pathfinder_contract_address, storage_key)
+                .map_err(map_anyhow_to_state_err)?,
+            (None, None) => StorageValue(Felt::ZERO),
+        };
 
         tracing::trace!(storage_value=%storage_val, "Got storage value");
 
@@ -191,6 +228,8 @@ impl StateReader for PathfinderStateReader<'_> {
         &mut self,
         contract_address: starknet_api::core::ContractAddress,
     ) -> blockifier::state::state_api::StateResult<starknet_api::core::Nonce> {
+        self.cancellation.check_state_read()?;
+
         let pathfinder_contract_address =
             pathfinder_common::ContractAddress::new_or_panic(contract_address.0.key().into_felt());
 
@@ -209,8 +248,14 @@ impl StateReader for PathfinderStateReader<'_> {
         let nonce = self
             .transaction
             .contract_nonce(pathfinder_contract_address, block_id)
-            .map_err(map_anyhow_to_state_err)?
-            .unwrap_or(pathfinder_common::ContractNonce::ZERO);
+            .map_err(map_anyhow_to_state_err)?;
+        let nonce = match (nonce, &self.remote_state) {
+            (Some(nonce), _) => nonce,
+            (None, Some(remote_state)) => remote_state
+                .contract_nonce(pathfinder_contract_address)
+                .map_err(map_anyhow_to_state_err)?,
+            (None, None) => pathfinder_common::ContractNonce::ZERO,
+        };
 
         Ok(starknet_api::core::Nonce(nonce.0.into_starkfelt()))
     }
@@ -219,6 +264,8 @@ impl StateReader for PathfinderStateReader<'_> {
         &mut self,
         contract_address: starknet_api::core::ContractAddress,
     ) -> blockifier::state::state_api::StateResult<starknet_api::core::ClassHash> {
+        self.cancellation.check_state_read()?;
+
         let pathfinder_contract_address =
             pathfinder_common::ContractAddress::new_or_panic(contract_address.0.key().into_felt());
 
@@ -236,6 +283,12 @@ impl StateReader for PathfinderStateReader<'_> {
             .transaction
             .contract_class_hash(block_id, pathfinder_contract_address)
             .map_err(map_anyhow_to_state_err)?;
+        let class_hash = match (class_hash, &self.remote_state) {
+            (None, Some(remote_state)) => remote_state
+                .contract_class_hash(pathfinder_contract_address)
+                .map_err(map_anyhow_to_state_err)?,
+            (class_hash, _) => class_hash,
+        };
 
         let Some(class_hash) = class_hash else {
             return Ok(starknet_api::core::ClassHash(
@@ -252,6 +305,8 @@ impl StateReader for PathfinderStateReader<'_> {
     ) -> blockifier::state::state_api::StateResult<
         blockifier::execution::contract_class::ContractClass,
     > {
+        self.cancellation.check_state_read()?;
+
         let pathfinder_class_hash = ClassHash(class_hash.0.into_felt());
 
         let _span =
@@ -272,6 +327,10 @@ impl StateReader for PathfinderStateReader<'_> {
             self.transaction
                 .casm_definition_at(block_id, pathfinder_class_hash)
         };
+        let casm_definition = match (casm_definition, &self.remote_state) {
+            (Ok(None), Some(remote_state)) => remote_state.casm_definition(pathfinder_class_hash),
+            (casm_definition, _) => casm_definition,
+        };
 
         if let Some(casm_definition) = casm_definition.map_err(map_anyhow_to_state_err)? {
             let casm_definition = String::from_utf8(casm_definition).map_err(|error| {
@@ -298,6 +357,10 @@ impl StateReader for PathfinderStateReader<'_> {
             self.transaction
                 .class_definition_at(block_id, pathfinder_class_hash)
         };
+        let definition = match (definition, &self.remote_state) {
+            (Ok(None), Some(remote_state)) => remote_state.class_definition(pathfinder_class_hash),
+            (definition, _) => definition,
+        };
 
         if let Some(definition) = definition.map_err(map_anyhow_to_state_err)? {
             let definition = String::from_utf8(definition).map_err(|error| {
@@ -327,6 +390,8 @@ impl StateReader for PathfinderStateReader<'_> {
         &mut self,
         class_hash: starknet_api::core::ClassHash,
     ) -> blockifier::state::state_api::StateResult<starknet_api::core::CompiledClassHash> {
+        self.cancellation.check_state_read()?;
+
         let class_hash = ClassHash(class_hash.0.into_felt());
 
         tracing::trace!(%class_hash, "Getting compiled class hash");
@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, HashSet};
 use blockifier::execution::entry_point::OrderedL2ToL1Message;
 use pathfinder_common::{
     CasmHash, ClassHash, ContractAddress, ContractNonce, SierraHash, StorageAddress, StorageValue,
+    TransactionHash,
 };
 use pathfinder_crypto::Felt;
 
@@ -15,7 +16,7 @@ pub struct FeeEstimate {
     pub overall_fee: primitive_types::U256,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EntryPointType {
     Constructor,
     External,
@@ -28,6 +29,20 @@ pub struct TransactionSimulation {
     pub fee_estimation: FeeEstimate,
 }
 
+/// A transaction executed as part of its block, see [execute_block](crate::execute_block).
+#[derive(Debug)]
+pub struct TransactionExecution {
+    pub transaction_hash: TransactionHash,
+    pub actual_fee: u128,
+    /// The Cairo steps, including memory holes.
+    pub steps: usize,
+    /// The memory holes of the transaction's calls.
+    pub memory_holes: usize,
+    /// The builtin instances by builtin name, e.g. `range_check_builtin`.
+    pub builtins: BTreeMap<String, usize>,
+    pub trace: TransactionTrace,
+}
+
 #[derive(Debug)]
 pub enum TransactionTrace {
     Declare(DeclareTransactionTrace),
@@ -36,6 +51,171 @@ pub enum TransactionTrace {
     L1Handler(L1HandlerTransactionTrace),
 }
 
+impl TransactionTrace {
+    /// The top level invocations of the transaction in execution order, along with the phase of
+    /// the transaction they belong to.
+    pub fn invocations(&self) -> Vec<(&'static str, &FunctionInvocation)> {
+        let invocations = match self {
+            TransactionTrace::Declare(trace) => vec![
+                ("validate", trace.validate_invocation.as_ref()),
+                ("fee_transfer", trace.fee_transfer_invocation.as_ref()),
+            ],
+            TransactionTrace::DeployAccount(trace) => vec![
+                ("constructor", trace.constructor_invocation.as_ref()),
+                ("validate", trace.validate_invocation.as_ref()),
+                ("fee_transfer", trace.fee_transfer_invocation.as_ref()),
+            ],
+            TransactionTrace::Invoke(trace) => vec![
+                ("validate", trace.validate_invocation.as_ref()),
+                (
+                    "execute",
+                    match &trace.execute_invocation {
+                        ExecuteInvocation::FunctionInvocation(invocation) => invocation.as_ref(),
+                        ExecuteInvocation::RevertedReason(_) => None,
+                    },
+                ),
+                ("fee_transfer", trace.fee_transfer_invocation.as_ref()),
+            ],
+            TransactionTrace::L1Handler(trace) => {
+                vec![("l1_handler", trace.function_invocation.as_ref())]
+            }
+        };
+
+        invocations
+            .into_iter()
+            .filter_map(|(phase, invocation)| invocation.map(|invocation| (phase, invocation)))
+            .collect()
+    }
+
+    /// All calls of the transaction, depth-first in execution order.
+    pub fn flattened_calls(&self) -> Vec<FlattenedCall> {
+        fn flatten(
+            phase: &'static str,
+            depth: usize,
+            invocation: &FunctionInvocation,
+            calls: &mut Vec<FlattenedCall>,
+        ) {
+            calls.push(FlattenedCall {
+                phase,
+                depth,
+                contract_address: invocation.contract_address,
+                selector: invocation.selector,
+                class_hash: invocation.class_hash,
+                caller_address: invocation.caller_address,
+                call_type: invocation.call_type,
+                entry_point_type: invocation.entry_point_type,
+                steps: invocation.steps,
+            });
+            for call in &invocation.internal_calls {
+                flatten(phase, depth + 1, call, calls);
+            }
+        }
+
+        let mut calls = Vec::new();
+        for (phase, invocation) in self.invocations() {
+            flatten(phase, 0, invocation, &mut calls);
+        }
+        calls
+    }
+
+    /// The calls of the transaction in the folded stack format of flamegraph tooling, e.g.
+    /// `execute;0x1:0x2;0x3:0x4 120`.
+    ///
+    /// Each line is the stack of a call, starting with the phase of the transaction and followed
+    /// by `contract_address:selector` frames, weighted by the Cairo steps the call executed
+    /// itself, i.e. excluding its internal calls. Calls which executed no steps themselves are
+    /// omitted.
+    pub fn folded_stacks(&self) -> String {
+        fn fold(stack: &mut Vec<String>, invocation: &FunctionInvocation, lines: &mut String) {
+            use std::fmt::Write;
+
+            stack.push(format!(
+                "{}:{}",
+                invocation.contract_address.0.to_hex_str(),
+                invocation.selector.to_hex_str()
+            ));
+
+            let internal_steps: usize = invocation.internal_calls.iter().map(|c| c.steps).sum();
+            let own_steps = invocation.steps.saturating_sub(internal_steps);
+            if own_steps > 0 {
+                let _ = writeln!(lines, "{} {own_steps}", stack.join(";"));
+            }
+
+            for call in &invocation.internal_calls {
+                fold(stack, call, lines);
+            }
+            stack.pop();
+        }
+
+        let mut lines = String::new();
+        for (phase, invocation) in self.invocations() {
+            fold(&mut vec![phase.to_owned()], invocation, &mut lines);
+        }
+        lines
+    }
+
+    /// Restricts the trace to the calls touching `address`, see
+    /// [FunctionInvocation::retain_calls_touching]. Top level invocations which don't touch
+    /// `address` are removed, the state diff is kept as-is.
+    ///
+    /// Returns `false` if no call of the transaction touches `address`.
+    pub fn retain_calls_touching(&mut self, address: ContractAddress) -> bool {
+        let invocations = match self {
+            TransactionTrace::Declare(trace) => vec![
+                &mut trace.validate_invocation,
+                &mut trace.fee_transfer_invocation,
+            ],
+            TransactionTrace::DeployAccount(trace) => vec![
+                &mut trace.constructor_invocation,
+                &mut trace.validate_invocation,
+                &mut trace.fee_transfer_invocation,
+            ],
+            TransactionTrace::Invoke(trace) => {
+                let mut invocations = vec![
+                    &mut trace.validate_invocation,
+                    &mut trace.fee_transfer_invocation,
+                ];
+                if let ExecuteInvocation::FunctionInvocation(invocation) =
+                    &mut trace.execute_invocation
+                {
+                    invocations.push(invocation);
+                }
+                invocations
+            }
+            TransactionTrace::L1Handler(trace) => vec![&mut trace.function_invocation],
+        };
+
+        let mut touched = false;
+        for invocation in invocations {
+            if invocation
+                .as_mut()
+                .is_some_and(|invocation| invocation.retain_calls_touching(address))
+            {
+                touched = true;
+            } else {
+                *invocation = None;
+            }
+        }
+        touched
+    }
+}
+
+/// A call of a [TransactionTrace], without its nested calls.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FlattenedCall {
+    /// The phase of the transaction the call belongs to, e.g. `validate`.
+    pub phase: &'static str,
+    /// The depth of the call, starting at zero for the top level invocation of its phase.
+    pub depth: usize,
+    pub contract_address: ContractAddress,
+    pub selector: Felt,
+    pub class_hash: Option<Felt>,
+    pub caller_address: Felt,
+    pub call_type: CallType,
+    pub entry_point_type: EntryPointType,
+    pub steps: usize,
+}
+
 #[derive(Debug)]
 pub struct DeclareTransactionTrace {
     pub validate_invocation: Option<FunctionInvocation>,
@@ -71,7 +251,7 @@ pub struct L1HandlerTransactionTrace {
     pub state_diff: StateDiff,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CallType {
     Call,
     Delegate,
@@ -97,6 +277,24 @@ pub struct FunctionInvocation {
     pub events: Vec<Event>,
     pub messages: Vec<MsgToL1>,
     pub result: Vec<Felt>,
+    /// The number of Cairo steps executed by the call, including its internal calls.
+    pub steps: usize,
+}
+
+impl FunctionInvocation {
+    /// Removes the internal calls which neither are to `address` nor lead to a call to it. Calls
+    /// to `address` are kept along with all of their internal calls.
+    ///
+    /// Returns `false` if neither this call nor any of its internal calls is to `address`.
+    pub fn retain_calls_touching(&mut self, address: ContractAddress) -> bool {
+        if self.contract_address == address {
+            return true;
+        }
+
+        self.internal_calls
+            .retain_mut(|call| call.retain_calls_touching(address));
+        !self.internal_calls.is_empty()
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -163,6 +361,8 @@ impl TryFrom<blockifier::execution::entry_point::CallInfo> for FunctionInvocatio
             .map(Into::into)
             .collect();
 
+        let steps = call_info.vm_resources.n_steps;
+
         let result = call_info
             .execution
             .retdata
@@ -194,6 +394,7 @@ impl TryFrom<blockifier::execution::entry_point::CallInfo> for FunctionInvocatio
             events,
             messages,
             result,
+            steps,
         })
     }
 }
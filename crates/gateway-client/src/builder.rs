@@ -9,8 +9,11 @@
 //!   2. [Method](stage::Method) where you select the REST API method.
 //!   3. [Params](stage::Params) where you select the retry behavior.
 //!   4. [Final](stage::Final) where you select the REST operation type, which is then executed.
-use crate::metrics::{with_metrics, BlockTag, RequestMetadata};
-use pathfinder_common::{BlockId, ClassHash, TransactionHash};
+use crate::metrics::{
+    with_metrics, BlockTag, RequestMetadata, METRIC_DOWNLOADED_BYTES, METRIC_RESUMED_DOWNLOADS,
+};
+use crate::recording::Recorder;
+use pathfinder_common::{BlockId, ClassHash, ContractAddress, TransactionHash};
 use starknet_gateway_types::error::SequencerError;
 
 /// A Sequencer Request builder.
@@ -18,6 +21,8 @@ pub struct Request<'a, S: RequestState> {
     state: S,
     url: reqwest::Url,
     client: &'a reqwest::Client,
+    /// Records or replays the responses of `GET` requests.
+    recording: Option<&'a Recorder>,
 }
 
 pub mod stage {
@@ -49,7 +54,7 @@ pub mod stage {
 
     /// Specify the REST operation send the request:
     /// - [get](super::Request::get)
-    /// - [get_as_bytes](super::Request::get_as_bytes)
+    /// - [get_as_bytes_with_limit](super::Request::get_as_bytes_with_limit)
     /// - [post_with_json](super::Request::post_with_json)
     pub struct Final {
         pub meta: RequestMetadata,
@@ -64,10 +69,15 @@ pub mod stage {
 
 impl<'a> Request<'a, stage::Init> {
     /// Initialize a [Request] builder.
-    pub fn builder(client: &'a reqwest::Client, url: reqwest::Url) -> Request<'a, stage::Method> {
+    pub fn builder(
+        client: &'a reqwest::Client,
+        recording: Option<&'a Recorder>,
+        url: reqwest::Url,
+    ) -> Request<'a, stage::Method> {
         Request {
             url,
             client,
+            recording,
             state: stage::Method,
         }
     }
@@ -132,6 +142,10 @@ impl<'a> Request<'a, stage::Method> {
         get_block_traces,
         get_transaction_trace,
         get_signature,
+        get_public_key,
+        get_storage_at,
+        get_nonce,
+        get_class_hash_at,
     );
 
     /// Appends the given method to the request url.
@@ -144,6 +158,7 @@ impl<'a> Request<'a, stage::Method> {
         Request {
             url: self.url,
             client: self.client,
+            recording: self.recording,
             state: stage::Params {
                 meta: RequestMetadata::new(method),
             },
@@ -175,6 +190,10 @@ impl<'a> Request<'a, stage::Params> {
         self.add_param("classHash", &class_hash.0.to_hex_str())
     }
 
+    pub fn with_contract_address(self, address: ContractAddress) -> Self {
+        self.add_param("contractAddress", &address.0.to_hex_str())
+    }
+
     pub fn with_optional_token(self, token: Option<&str>) -> Self {
         match token {
             Some(token) => self.add_param("token", token),
@@ -201,6 +220,7 @@ impl<'a> Request<'a, stage::Params> {
         Request {
             url: self.url,
             client: self.client,
+            recording: self.recording,
             state: stage::Final {
                 meta: self.state.meta,
                 retry,
@@ -218,23 +238,24 @@ impl<'a> Request<'a, stage::Final> {
         async fn send_request<T: serde::de::DeserializeOwned>(
             url: reqwest::Url,
             client: &reqwest::Client,
+            recording: Option<&Recorder>,
             meta: RequestMetadata,
         ) -> Result<T, SequencerError> {
             with_metrics(meta, async move {
                 tracing::trace!(%url, "Fetching data from feeder gateway");
-                let response = client.get(url).send().await?;
+                let response = send_get(client, recording, url).await?;
                 parse::<T>(response).await
             })
             .await
         }
 
         match self.state.retry {
-            false => send_request(self.url, self.client, self.state.meta).await,
+            false => send_request(self.url, self.client, self.recording, self.state.meta).await,
             true => {
                 retry0(
                     || async {
                         let clone_url = self.url.clone();
-                        send_request(clone_url, self.client, self.state.meta).await
+                        send_request(clone_url, self.client, self.recording, self.state.meta).await
                     },
                     retry_condition,
                 )
@@ -243,29 +264,43 @@ impl<'a> Request<'a, stage::Final> {
         }
     }
 
-    /// Sends the Sequencer request as a REST `GET` operation and returns the response's bytes.
-    pub async fn get_as_bytes(self) -> Result<bytes::Bytes, SequencerError> {
-        async fn get_as_bytes_inner(
-            url: reqwest::Url,
-            client: &reqwest::Client,
-            meta: RequestMetadata,
-        ) -> Result<bytes::Bytes, SequencerError> {
-            with_metrics(meta, async {
-                let response = client.get(url).send().await?;
-                let response = parse_raw(response).await?;
-                let bytes = response.bytes().await?;
-                Ok(bytes)
-            })
-            .await
-        }
-
+    /// Sends the Sequencer request as a REST `GET` operation and streams the response's bytes,
+    /// failing with [SequencerError::ResponseTooLarge] once they exceed `limit`.
+    ///
+    /// A download which is interrupted is retried from where it stopped using a range request.
+    /// If the gateway does not support range requests, or replies with a range which doesn't
+    /// continue the download, it is restarted instead.
+    pub async fn get_as_bytes_with_limit(
+        self,
+        limit: usize,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        // The bytes downloaded so far, kept across retries.
+        let downloaded = std::sync::Mutex::new(Vec::new());
         match self.state.retry {
-            false => get_as_bytes_inner(self.url, self.client, self.state.meta).await,
+            false => {
+                download_with_limit(
+                    self.url,
+                    self.client,
+                    self.recording,
+                    self.state.meta,
+                    limit,
+                    &downloaded,
+                )
+                .await
+            }
             true => {
                 retry0(
                     || async {
                         let clone_url = self.url.clone();
-                        get_as_bytes_inner(clone_url, self.client, self.state.meta).await
+                        download_with_limit(
+                            clone_url,
+                            self.client,
+                            self.recording,
+                            self.state.meta,
+                            limit,
+                            &downloaded,
+                        )
+                        .await
                     },
                     retry_condition,
                 )
@@ -314,6 +349,92 @@ impl<'a> Request<'a, stage::Final> {
     }
 }
 
+/// Downloads the response of a `GET` request into `downloaded`, resuming after the bytes it
+/// already contains. See [Request::get_as_bytes_with_limit].
+async fn download_with_limit(
+    url: reqwest::Url,
+    client: &reqwest::Client,
+    recording: Option<&Recorder>,
+    meta: RequestMetadata,
+    limit: usize,
+    downloaded: &std::sync::Mutex<Vec<u8>>,
+) -> Result<bytes::Bytes, SequencerError> {
+    with_metrics(meta, async {
+        let resume_from = downloaded.lock().unwrap().len();
+        // Recorded responses are always served in full.
+        let response = if resume_from == 0 || recording.is_some() {
+            send_get(client, recording, url.clone()).await?
+        } else {
+            tracing::debug!(%url, bytes=%resume_from, "Resuming download");
+            metrics::increment_counter!(METRIC_RESUMED_DOWNLOADS, "method" => meta.method);
+            client
+                .get(url.clone())
+                .header(reqwest::header::RANGE, format!("bytes={resume_from}-"))
+                .send()
+                .await?
+        };
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // Start over, so that the retry doesn't request the same range again.
+            downloaded.lock().unwrap().clear();
+        }
+        let mut response = parse_raw(response).await?;
+
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            downloaded.lock().unwrap().clear();
+        } else if content_range_start(&response) != Some(resume_from) {
+            // The bytes don't continue the download, so start over without a range.
+            let range = response.headers().get(reqwest::header::CONTENT_RANGE);
+            tracing::debug!(%url, bytes=%resume_from, ?range, "Download resumed at a different offset, restarting");
+            downloaded.lock().unwrap().clear();
+            response = parse_raw(send_get(client, recording, url).await?).await?;
+        }
+        let resume_from = downloaded.lock().unwrap().len();
+
+        // Fail early if the gateway announces the size.
+        if let Some(length) = response.content_length() {
+            if resume_from.saturating_add(length as usize) > limit {
+                return Err(SequencerError::ResponseTooLarge { limit });
+            }
+        }
+
+        while let Some(chunk) = response.chunk().await? {
+            let mut buffer = downloaded.lock().unwrap();
+            if buffer.len() + chunk.len() > limit {
+                return Err(SequencerError::ResponseTooLarge { limit });
+            }
+            buffer.extend_from_slice(&chunk);
+            metrics::counter!(METRIC_DOWNLOADED_BYTES, chunk.len() as u64, "method" => meta.method);
+        }
+
+        Ok(std::mem::take(&mut *downloaded.lock().unwrap()).into())
+    })
+    .await
+}
+
+/// The offset of the first byte of a partial response, from its `Content-Range` header such as
+/// `bytes 100-199/1000`.
+fn content_range_start(response: &reqwest::Response) -> Option<usize> {
+    let range = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?;
+    let (start, _) = range.strip_prefix("bytes ")?.split_once('-')?;
+    start.parse().ok()
+}
+
+/// Sends a `GET` request, via the [Recorder] if there is one.
+async fn send_get(
+    client: &reqwest::Client,
+    recording: Option<&Recorder>,
+    url: reqwest::Url,
+) -> Result<reqwest::Response, reqwest::Error> {
+    match recording {
+        Some(recording) => recording.get(client, url).await,
+        None => client.get(url).send().await,
+    }
+}
+
 async fn parse<T>(response: reqwest::Response) -> Result<T, SequencerError>
 where
     T: ::serde::de::DeserializeOwned,
@@ -401,7 +522,7 @@ fn retry_condition(e: &SequencerError) -> bool {
 
             true
         }
-        SequencerError::StarknetError(_) => false,
+        SequencerError::StarknetError(_) | SequencerError::ResponseTooLarge { .. } => false,
         SequencerError::InvalidStarknetErrorVariant => {
             error!(reason=%e, "Request failed, retrying");
             true
@@ -567,6 +688,114 @@ mod tests {
         }
     }
 
+    mod resume {
+        use crate::builder::download_with_limit;
+        use crate::metrics::RequestMetadata;
+        use http::{response::Builder, StatusCode};
+        use pretty_assertions::assert_eq;
+        use starknet_gateway_types::error::SequencerError;
+        use std::sync::Mutex;
+        use warp::Filter;
+
+        const BODY: &str = "0123456789";
+
+        /// Replies to range requests with `range_reply` of the requested offset, and to other
+        /// requests with the whole [BODY].
+        fn server(
+            range_reply: fn(usize) -> (StatusCode, Option<String>, &'static str),
+        ) -> (tokio::task::JoinHandle<()>, std::net::SocketAddr) {
+            let any =
+                warp::header::optional::<String>("range").map(move |range: Option<String>| {
+                    let (status, content_range, body) = match range {
+                        Some(range) => {
+                            let start = range
+                                .strip_prefix("bytes=")
+                                .and_then(|range| range.strip_suffix('-'))
+                                .unwrap()
+                                .parse()
+                                .unwrap();
+                            range_reply(start)
+                        }
+                        None => (StatusCode::OK, None, BODY),
+                    };
+
+                    let mut response = Builder::new().status(status);
+                    if let Some(content_range) = content_range {
+                        response = response.header("content-range", content_range);
+                    }
+                    response.body(body)
+                });
+            let (addr, run_srv) = warp::serve(any).bind_ephemeral(([127, 0, 0, 1], 0));
+            let server_handle = tokio::spawn(run_srv);
+            (server_handle, addr)
+        }
+
+        async fn download(
+            addr: std::net::SocketAddr,
+            downloaded: &Mutex<Vec<u8>>,
+        ) -> Result<bytes::Bytes, SequencerError> {
+            let mut url = reqwest::Url::parse("http://localhost/").unwrap();
+            url.set_port(Some(addr.port())).unwrap();
+            download_with_limit(
+                url,
+                &reqwest::Client::new(),
+                None,
+                RequestMetadata::new("test"),
+                BODY.len(),
+                downloaded,
+            )
+            .await
+        }
+
+        #[tokio::test]
+        async fn partial_content() {
+            let (_jh, addr) = server(|start| {
+                let range = format!("bytes {start}-9/10");
+                (StatusCode::PARTIAL_CONTENT, Some(range), &BODY[start..])
+            });
+            let downloaded = Mutex::new(b"01234".to_vec());
+
+            let result = download(addr, &downloaded).await.unwrap();
+            assert_eq!(result, BODY.as_bytes());
+        }
+
+        #[tokio::test]
+        async fn partial_content_at_other_offset_restarts() {
+            let (_jh, addr) = server(|_| {
+                (
+                    StatusCode::PARTIAL_CONTENT,
+                    Some("bytes 2-9/10".to_owned()),
+                    &BODY[2..],
+                )
+            });
+            let downloaded = Mutex::new(b"01234".to_vec());
+
+            let result = download(addr, &downloaded).await.unwrap();
+            assert_eq!(result, BODY.as_bytes());
+        }
+
+        #[tokio::test]
+        async fn full_content_restarts() {
+            let (_jh, addr) = server(|_| (StatusCode::OK, None, BODY));
+            let downloaded = Mutex::new(b"01234".to_vec());
+
+            let result = download(addr, &downloaded).await.unwrap();
+            assert_eq!(result, BODY.as_bytes());
+        }
+
+        #[tokio::test]
+        async fn range_not_satisfiable_restarts_on_retry() {
+            let (_jh, addr) = server(|_| (StatusCode::RANGE_NOT_SATISFIABLE, None, ""));
+            let downloaded = Mutex::new(b"01234".to_vec());
+
+            download(addr, &downloaded).await.unwrap_err();
+            assert!(downloaded.lock().unwrap().is_empty());
+
+            let result = download(addr, &downloaded).await.unwrap();
+            assert_eq!(result, BODY.as_bytes());
+        }
+    }
+
     mod invalid_starknet_error_variant {
         use crate::Client;
         use http::response::Builder;
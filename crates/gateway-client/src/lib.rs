@@ -1,8 +1,8 @@
 //! Starknet L2 sequencer client.
 use pathfinder_common::{
     BlockHash, BlockId, BlockNumber, CallParam, CasmHash, Chain, ClassHash, ContractAddress,
-    ContractAddressSalt, EntryPoint, Fee, StateUpdate, TransactionHash, TransactionNonce,
-    TransactionSignatureElem, TransactionVersion,
+    ContractAddressSalt, ContractNonce, EntryPoint, Fee, PublicKey, StateUpdate, StorageAddress,
+    StorageValue, TransactionHash, TransactionNonce, TransactionSignatureElem, TransactionVersion,
 };
 use reqwest::Url;
 use starknet_gateway_types::trace::{BlockTrace, TransactionTrace};
@@ -10,13 +10,20 @@ use starknet_gateway_types::{
     error::SequencerError,
     reply,
     request::add_transaction::{
-        AddTransaction, ContractDefinition, Declare, DeployAccount, InvokeFunction,
+        AddTransaction, ContractDefinition, Declare, DeployAccount, InvokeFunction, V3Properties,
     },
 };
-use std::{fmt::Debug, result::Result, time::Duration};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::{fmt::Debug, result::Result, sync::Arc, time::Duration};
 
 mod builder;
 mod metrics;
+mod recording;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+use recording::Recorder;
+pub use recording::Recording;
 
 #[allow(unused_variables)]
 #[mockall::automock]
@@ -54,6 +61,47 @@ pub trait GatewayApi: Sync {
         unimplemented!();
     }
 
+    async fn class_by_hash(
+        &self,
+        class_hash: ClassHash,
+        block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        unimplemented!();
+    }
+
+    async fn casm_by_hash(
+        &self,
+        class_hash: ClassHash,
+        block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        unimplemented!();
+    }
+
+    async fn storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageAddress,
+        block: BlockId,
+    ) -> Result<StorageValue, SequencerError> {
+        unimplemented!();
+    }
+
+    async fn nonce(
+        &self,
+        contract_address: ContractAddress,
+        block: BlockId,
+    ) -> Result<ContractNonce, SequencerError> {
+        unimplemented!();
+    }
+
+    async fn class_hash_at(
+        &self,
+        contract_address: ContractAddress,
+        block: BlockId,
+    ) -> Result<ClassHash, SequencerError> {
+        unimplemented!();
+    }
+
     async fn transaction(
         &self,
         transaction_hash: TransactionHash,
@@ -86,6 +134,7 @@ pub trait GatewayApi: Sync {
         contract_address: ContractAddress,
         entry_point_selector: Option<EntryPoint>,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<reply::add_transaction::InvokeResponse, SequencerError> {
         unimplemented!();
     }
@@ -100,6 +149,7 @@ pub trait GatewayApi: Sync {
         contract_definition: ContractDefinition,
         sender_address: ContractAddress,
         compiled_class_hash: Option<CasmHash>,
+        v3_properties: Option<V3Properties>,
         token: Option<String>,
     ) -> Result<reply::add_transaction::DeclareResponse, SequencerError> {
         unimplemented!();
@@ -115,6 +165,7 @@ pub trait GatewayApi: Sync {
         contract_address_salt: ContractAddressSalt,
         class_hash: ClassHash,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<reply::add_transaction::DeployAccountResponse, SequencerError> {
         unimplemented!();
     }
@@ -140,6 +191,10 @@ pub trait GatewayApi: Sync {
     async fn signature(&self, block: BlockId) -> Result<reply::BlockSignature, SequencerError> {
         unimplemented!();
     }
+
+    async fn public_key(&self) -> Result<PublicKey, SequencerError> {
+        unimplemented!();
+    }
 }
 
 /// This is a **temporary** measure to keep the sync logic unchanged
@@ -188,6 +243,47 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
         self.as_ref().pending_casm_by_hash(class_hash).await
     }
 
+    async fn class_by_hash(
+        &self,
+        class_hash: ClassHash,
+        block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        self.as_ref().class_by_hash(class_hash, block).await
+    }
+
+    async fn casm_by_hash(
+        &self,
+        class_hash: ClassHash,
+        block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        self.as_ref().casm_by_hash(class_hash, block).await
+    }
+
+    async fn storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageAddress,
+        block: BlockId,
+    ) -> Result<StorageValue, SequencerError> {
+        self.as_ref().storage_at(contract_address, key, block).await
+    }
+
+    async fn nonce(
+        &self,
+        contract_address: ContractAddress,
+        block: BlockId,
+    ) -> Result<ContractNonce, SequencerError> {
+        self.as_ref().nonce(contract_address, block).await
+    }
+
+    async fn class_hash_at(
+        &self,
+        contract_address: ContractAddress,
+        block: BlockId,
+    ) -> Result<ClassHash, SequencerError> {
+        self.as_ref().class_hash_at(contract_address, block).await
+    }
+
     async fn transaction(
         &self,
         transaction_hash: TransactionHash,
@@ -220,6 +316,7 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
         contract_address: ContractAddress,
         entry_point_selector: Option<EntryPoint>,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<reply::add_transaction::InvokeResponse, SequencerError> {
         self.as_ref()
             .add_invoke_transaction(
@@ -230,6 +327,7 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
                 contract_address,
                 entry_point_selector,
                 calldata,
+                v3_properties,
             )
             .await
     }
@@ -244,6 +342,7 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
         contract_definition: ContractDefinition,
         sender_address: ContractAddress,
         compiled_class_hash: Option<CasmHash>,
+        v3_properties: Option<V3Properties>,
         token: Option<String>,
     ) -> Result<reply::add_transaction::DeclareResponse, SequencerError> {
         self.as_ref()
@@ -255,6 +354,7 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
                 contract_definition,
                 sender_address,
                 compiled_class_hash,
+                v3_properties,
                 token,
             )
             .await
@@ -270,6 +370,7 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
         contract_address_salt: ContractAddressSalt,
         class_hash: ClassHash,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<reply::add_transaction::DeployAccountResponse, SequencerError> {
         self.as_ref()
             .add_deploy_account(
@@ -280,6 +381,7 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
                 contract_address_salt,
                 class_hash,
                 calldata,
+                v3_properties,
             )
             .await
     }
@@ -298,8 +400,21 @@ impl<T: GatewayApi + Sync + Send> GatewayApi for std::sync::Arc<T> {
     async fn signature(&self, block: BlockId) -> Result<reply::BlockSignature, SequencerError> {
         self.as_ref().signature(block).await
     }
+
+    async fn public_key(&self) -> Result<PublicKey, SequencerError> {
+        self.as_ref().public_key().await
+    }
 }
 
+/// The default maximum size of a class definition in bytes, well above the largest classes
+/// declared so far.
+pub const DEFAULT_MAX_CLASS_SIZE: usize = 64 * 1024 * 1024;
+
+/// The number of [state_update_with_block](GatewayApi::state_update_with_block) calls which fetch
+/// the block and state update separately after the gateway replied without the block, before the
+/// combined request is tried again.
+const SEPARATE_STATE_UPDATE_CALLS: u32 = 100;
+
 /// Starknet sequencer client using REST API.
 ///
 /// Retry is performed on __all__ types of errors __except for__
@@ -321,6 +436,13 @@ pub struct Client {
     /// Whether __read only__ requests should be retried, defaults to __true__ for production.
     /// Use [disable_retry_for_tests](Client::disable_retry_for_tests) to disable retry logic for all __read only__ requests when testing.
     retry: bool,
+    /// Records or replays the responses of __read only__ requests, see [with_recording](Client::with_recording).
+    recording: Option<Arc<Recorder>>,
+    /// The maximum size of a class definition, see [with_max_class_size](Client::with_max_class_size).
+    max_class_size: usize,
+    /// The number of [state_update_with_block](GatewayApi::state_update_with_block) calls which
+    /// still fetch the block and state update separately, see [SEPARATE_STATE_UPDATE_CALLS].
+    separate_state_update: Arc<AtomicU32>,
 }
 
 impl Client {
@@ -364,6 +486,9 @@ impl Client {
             gateway,
             feeder_gateway,
             retry: true,
+            recording: None,
+            max_class_size: DEFAULT_MAX_CLASS_SIZE,
+            separate_state_update: Default::default(),
         })
     }
 
@@ -375,12 +500,39 @@ impl Client {
         }
     }
 
+    /// Records the responses of all __read only__ requests to disk, or serves them from a
+    /// previous recording instead of the gateway.
+    pub fn with_recording(self, recording: Recording) -> Self {
+        Self {
+            recording: Some(Arc::new(Recorder::new(recording))),
+            ..self
+        }
+    }
+
+    /// Limits the size of downloaded class definitions, so that a pathological response can't
+    /// exhaust memory. Defaults to [DEFAULT_MAX_CLASS_SIZE].
+    pub fn with_max_class_size(self, max_class_size: usize) -> Self {
+        Self {
+            max_class_size,
+            ..self
+        }
+    }
+
+    /// The feeder gateway [Url] this client is using.
+    pub fn feeder_gateway_url(&self) -> &Url {
+        &self.feeder_gateway
+    }
+
     fn gateway_request(&self) -> builder::Request<'_, builder::stage::Method> {
-        builder::Request::builder(&self.inner, self.gateway.clone())
+        builder::Request::builder(&self.inner, self.recording.as_deref(), self.gateway.clone())
     }
 
     fn feeder_gateway_request(&self) -> builder::Request<'_, builder::stage::Method> {
-        builder::Request::builder(&self.inner, self.feeder_gateway.clone())
+        builder::Request::builder(
+            &self.inner,
+            self.recording.as_deref(),
+            self.feeder_gateway.clone(),
+        )
     }
 
     async fn block_with_retry_behaviour(
@@ -469,7 +621,7 @@ impl GatewayApi for Client {
             .with_class_hash(class_hash)
             .with_block(BlockId::Pending)
             .with_retry(self.retry)
-            .get_as_bytes()
+            .get_as_bytes_with_limit(self.max_class_size)
             .await
     }
 
@@ -484,7 +636,89 @@ impl GatewayApi for Client {
             .with_class_hash(class_hash)
             .with_block(BlockId::Pending)
             .with_retry(self.retry)
-            .get_as_bytes()
+            .get_as_bytes_with_limit(self.max_class_size)
+            .await
+    }
+
+    /// Gets class for a particular class hash at the given block.
+    #[tracing::instrument(skip(self))]
+    async fn class_by_hash(
+        &self,
+        class_hash: ClassHash,
+        block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        self.feeder_gateway_request()
+            .get_class_by_hash()
+            .with_class_hash(class_hash)
+            .with_block(block)
+            .with_retry(self.retry)
+            .get_as_bytes_with_limit(self.max_class_size)
+            .await
+    }
+
+    /// Gets CASM for a particular class hash at the given block.
+    #[tracing::instrument(skip(self))]
+    async fn casm_by_hash(
+        &self,
+        class_hash: ClassHash,
+        block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        self.feeder_gateway_request()
+            .get_compiled_class_by_class_hash()
+            .with_class_hash(class_hash)
+            .with_block(block)
+            .with_retry(self.retry)
+            .get_as_bytes_with_limit(self.max_class_size)
+            .await
+    }
+
+    /// Gets the value of a contract's storage slot at the given block.
+    #[tracing::instrument(skip(self))]
+    async fn storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageAddress,
+        block: BlockId,
+    ) -> Result<StorageValue, SequencerError> {
+        self.feeder_gateway_request()
+            .get_storage_at()
+            .with_contract_address(contract_address)
+            .add_param("key", &key.0.to_hex_str())
+            .with_block(block)
+            .with_retry(self.retry)
+            .get()
+            .await
+    }
+
+    /// Gets a contract's nonce at the given block.
+    #[tracing::instrument(skip(self))]
+    async fn nonce(
+        &self,
+        contract_address: ContractAddress,
+        block: BlockId,
+    ) -> Result<ContractNonce, SequencerError> {
+        self.feeder_gateway_request()
+            .get_nonce()
+            .with_contract_address(contract_address)
+            .with_block(block)
+            .with_retry(self.retry)
+            .get()
+            .await
+    }
+
+    /// Gets the class hash of the contract deployed at the address at the given block.
+    #[tracing::instrument(skip(self))]
+    async fn class_hash_at(
+        &self,
+        contract_address: ContractAddress,
+        block: BlockId,
+    ) -> Result<ClassHash, SequencerError> {
+        self.feeder_gateway_request()
+            .get_class_hash_at()
+            .with_contract_address(contract_address)
+            .with_block(block)
+            .with_retry(self.retry)
+            .get()
             .await
     }
 
@@ -521,12 +755,29 @@ impl GatewayApi for Client {
     ///
     /// This is useful because using fetching both in a single request guarantees the consistency
     /// of the block and state update information for the pending block.
+    ///
+    /// Older gateways reply with the state update only, in which case the block is fetched in a
+    /// separate request, as are both for the next [SEPARATE_STATE_UPDATE_CALLS] calls. The two are
+    /// then not guaranteed to be consistent. The combined request is tried again afterwards, in
+    /// case the gateway was upgraded or the reply came from an outdated instance.
     #[tracing::instrument(skip(self))]
     async fn state_update_with_block(
         &self,
         block: BlockId,
     ) -> Result<(reply::MaybePendingBlock, StateUpdate), SequencerError> {
-        let result: reply::StateUpdateWithBlock = self
+        let separate = self
+            .separate_state_update
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |calls| {
+                calls.checked_sub(1)
+            })
+            .is_ok();
+        if separate {
+            let block_reply = self.block(block).await?;
+            let state_update = self.state_update(block).await?;
+            return Ok((block_reply, state_update));
+        }
+
+        let result: reply::MaybeStateUpdateWithBlock = self
             .feeder_gateway_request()
             .get_state_update()
             .with_block(block)
@@ -534,7 +785,21 @@ impl GatewayApi for Client {
             .with_retry(self.retry)
             .get()
             .await?;
-        Ok((result.block, result.state_update.into()))
+
+        match result {
+            reply::MaybeStateUpdateWithBlock::WithBlock(result) => {
+                Ok((result.block, result.state_update.into()))
+            }
+            reply::MaybeStateUpdateWithBlock::StateUpdate(state_update) => {
+                tracing::info!(
+                    "Gateway does not support fetching a block with its state update, falling back to separate requests"
+                );
+                self.separate_state_update
+                    .store(SEPARATE_STATE_UPDATE_CALLS, Ordering::Relaxed);
+                let block_reply = self.block(block).await?;
+                Ok((block_reply, state_update.into()))
+            }
+        }
     }
 
     /// Gets addresses of the Ethereum contracts crucial to Starknet operation.
@@ -558,6 +823,7 @@ impl GatewayApi for Client {
         sender_address: ContractAddress,
         entry_point_selector: Option<EntryPoint>,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<reply::add_transaction::InvokeResponse, SequencerError> {
         let req = AddTransaction::Invoke(InvokeFunction {
             sender_address,
@@ -567,6 +833,7 @@ impl GatewayApi for Client {
             signature,
             nonce,
             entry_point_selector,
+            v3_properties,
         });
 
         // Note that we don't do retries here.
@@ -591,6 +858,7 @@ impl GatewayApi for Client {
         contract_definition: ContractDefinition,
         sender_address: ContractAddress,
         compiled_class_hash: Option<CasmHash>,
+        v3_properties: Option<V3Properties>,
         token: Option<String>,
     ) -> Result<reply::add_transaction::DeclareResponse, SequencerError> {
         let req = AddTransaction::Declare(Declare {
@@ -601,6 +869,7 @@ impl GatewayApi for Client {
             nonce,
             version,
             compiled_class_hash,
+            v3_properties,
         });
 
         // Note that we don't do retries here.
@@ -626,6 +895,7 @@ impl GatewayApi for Client {
         contract_address_salt: ContractAddressSalt,
         class_hash: ClassHash,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<reply::add_transaction::DeployAccountResponse, SequencerError> {
         let req = AddTransaction::DeployAccount(DeployAccount {
             version,
@@ -635,6 +905,7 @@ impl GatewayApi for Client {
             class_hash,
             contract_address_salt,
             constructor_calldata: calldata,
+            v3_properties,
         });
 
         // Note that we don't do retries here.
@@ -681,6 +952,16 @@ impl GatewayApi for Client {
             .get()
             .await
     }
+
+    /// The public key which the sequencer signs blocks with.
+    #[tracing::instrument(skip(self))]
+    async fn public_key(&self) -> Result<PublicKey, SequencerError> {
+        self.feeder_gateway_request()
+            .get_public_key()
+            .with_retry(self.retry)
+            .get()
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -1264,6 +1545,116 @@ mod tests {
                 .await
                 .unwrap();
         }
+
+        #[tokio::test]
+        async fn pending_with_block_from_older_gateway() {
+            let (_jh, client) = setup([
+                (
+                    "/feeder_gateway/get_state_update?blockNumber=pending&includeBlock=true",
+                    (v0_11_0::state_update::PENDING, 200),
+                ),
+                (
+                    "/feeder_gateway/get_block?blockNumber=pending",
+                    (v0_9_0::block::PENDING, 200),
+                ),
+                (
+                    "/feeder_gateway/get_state_update?blockNumber=pending",
+                    (v0_11_0::state_update::PENDING, 200),
+                ),
+            ]);
+
+            let first = client
+                .state_update_with_block(BlockId::Pending)
+                .await
+                .unwrap();
+            // Subsequent requests fetch the block and state update separately.
+            let second = client
+                .state_update_with_block(BlockId::Pending)
+                .await
+                .unwrap();
+
+            assert_eq!(first, second);
+            assert_matches!(first.0, reply::MaybePendingBlock::Pending(_));
+        }
+
+        #[tokio::test]
+        async fn pending_with_block_probed_again() {
+            let (_jh, client) = setup_with_varied_responses([
+                (
+                    "/feeder_gateway/get_state_update?blockNumber=pending&includeBlock=true"
+                        .to_owned(),
+                    [
+                        (v0_11_0::state_update::PENDING.to_owned(), 200),
+                        (v0_12_2::state_update::PENDING_WITH_BLOCK.to_owned(), 200),
+                    ],
+                ),
+                (
+                    "/feeder_gateway/get_block?blockNumber=pending".to_owned(),
+                    [
+                        (v0_9_0::block::PENDING.to_owned(), 200),
+                        (v0_9_0::block::PENDING.to_owned(), 200),
+                    ],
+                ),
+                (
+                    "/feeder_gateway/get_state_update?blockNumber=pending".to_owned(),
+                    [
+                        (v0_11_0::state_update::PENDING.to_owned(), 200),
+                        (v0_11_0::state_update::PENDING.to_owned(), 200),
+                    ],
+                ),
+            ]);
+
+            client
+                .state_update_with_block(BlockId::Pending)
+                .await
+                .unwrap();
+            assert_eq!(
+                client.separate_state_update.load(Ordering::Relaxed),
+                SEPARATE_STATE_UPDATE_CALLS
+            );
+
+            // Skip to the last call with separate requests.
+            client.separate_state_update.store(1, Ordering::Relaxed);
+            client
+                .state_update_with_block(BlockId::Pending)
+                .await
+                .unwrap();
+
+            // The combined request is tried again, and used from then on.
+            client
+                .state_update_with_block(BlockId::Pending)
+                .await
+                .unwrap();
+            assert_eq!(client.separate_state_update.load(Ordering::Relaxed), 0);
+        }
+    }
+
+    mod class_by_hash {
+        use super::*;
+
+        const URL: &str = "/feeder_gateway/get_class_by_hash?classHash=0x1&blockNumber=pending";
+
+        #[tokio::test]
+        async fn within_size_limit() {
+            let (_jh, client) = setup([(URL, (r#"{"class":"definition"}"#, 200))]);
+            let definition = client
+                .with_max_class_size(22)
+                .pending_class_by_hash(class_hash!("0x1"))
+                .await
+                .unwrap();
+            assert_eq!(definition.as_ref(), br#"{"class":"definition"}"#);
+        }
+
+        #[tokio::test]
+        async fn exceeds_size_limit() {
+            let (_jh, client) = setup([(URL, (r#"{"class":"definition"}"#, 200))]);
+            let error = client
+                .with_max_class_size(21)
+                .pending_class_by_hash(class_hash!("0x1"))
+                .await
+                .unwrap_err();
+            assert_matches!(error, SequencerError::ResponseTooLarge { limit: 21 });
+        }
     }
 
     #[tokio::test]
@@ -1346,6 +1737,7 @@ mod tests {
                         addr,
                         None,
                         call,
+                        None,
                     )
                     .await
                     .unwrap_err();
@@ -1367,7 +1759,7 @@ mod tests {
                 // test with values dumped from `starknet invoke` for a test contract
                 let (ver, fee, sig, nonce, addr, call) = inputs();
                 client
-                    .add_invoke_transaction(ver, fee, sig, Some(nonce), addr, None, call)
+                    .add_invoke_transaction(ver, fee, sig, Some(nonce), addr, None, call, None)
                     .await
                     .unwrap();
             }
@@ -1397,6 +1789,7 @@ mod tests {
                         contract_address!("0x1"),
                         None,
                         None,
+                        None,
                     )
                     .await
                     .unwrap_err();
@@ -1428,6 +1821,7 @@ mod tests {
                         contract_address!("0x1"),
                         None,
                         None,
+                        None,
                     )
                     .await
                     .unwrap();
@@ -1506,6 +1900,7 @@ mod tests {
                             "0x5bcd45099caf3dca6c0c0f6697698c90eebf02851acbbaf911186b173472fcc"
                         )),
                         None,
+                        None,
                     )
                     .await
                     .unwrap();
@@ -1538,6 +1933,7 @@ mod tests {
                     req.contract_address_salt,
                     req.class_hash,
                     req.constructor_calldata,
+                    req.v3_properties,
                 )
                 .await
                 .expect("DEPLOY_ACCOUNT response");
@@ -1645,6 +2041,7 @@ mod tests {
                         }),
                         ContractAddress::new_or_panic(Felt::ZERO),
                         None,
+                        None,
                         Some(EXPECTED_TOKEN.to_owned()),
                     )
                     .await
@@ -1672,6 +2069,7 @@ mod tests {
                         ContractAddress::new_or_panic(Felt::ZERO),
                         None,
                         None,
+                        None,
                     )
                     .await
                     .unwrap_err();
@@ -1786,6 +2184,65 @@ mod tests {
         }
     }
 
+    mod contract_state {
+        use super::*;
+
+        #[tokio::test]
+        async fn storage_at() {
+            let (_jh, client) = setup([(
+                "/feeder_gateway/get_storage_at?contractAddress=0x1&key=0x2&blockNumber=5",
+                (r#""0x3""#, 200),
+            )]);
+
+            let value = client
+                .storage_at(
+                    contract_address!("0x1"),
+                    storage_address!("0x2"),
+                    BlockNumber::new_or_panic(5).into(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(value, storage_value!("0x3"));
+        }
+
+        #[tokio::test]
+        async fn nonce() {
+            let (_jh, client) = setup([(
+                "/feeder_gateway/get_nonce?contractAddress=0x1&blockNumber=5",
+                (r#""0x4""#, 200),
+            )]);
+
+            let nonce = client
+                .nonce(
+                    contract_address!("0x1"),
+                    BlockNumber::new_or_panic(5).into(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(nonce, contract_nonce!("0x4"));
+        }
+
+        #[tokio::test]
+        async fn class_hash_at_uninitialized_contract() {
+            let (_jh, client) = setup([(
+                "/feeder_gateway/get_class_hash_at?contractAddress=0x1&blockNumber=5",
+                response_from(KnownStarknetErrorCode::UninitializedContract),
+            )]);
+
+            let error = client
+                .class_hash_at(
+                    contract_address!("0x1"),
+                    BlockNumber::new_or_panic(5).into(),
+                )
+                .await
+                .unwrap_err();
+            assert_matches!(
+                error,
+                SequencerError::StarknetError(e) => assert_eq!(e.code, KnownStarknetErrorCode::UninitializedContract.into())
+            );
+        }
+    }
+
     mod signature {
         use super::*;
 
@@ -1805,4 +2262,27 @@ mod tests {
                 .unwrap();
         }
     }
+
+    mod public_key {
+        use super::*;
+
+        #[tokio::test]
+        async fn success() {
+            let (_jh, client) = setup([(
+                "/feeder_gateway/get_public_key",
+                (
+                    r#""0x48253ff2c3bed7af18bde0b611b083b39445959102d4947c51c4db6aa4f4e58""#,
+                    200,
+                ),
+            )]);
+
+            let public_key = client.public_key().await.unwrap();
+            assert_eq!(
+                public_key,
+                PublicKey(felt!(
+                    "0x48253ff2c3bed7af18bde0b611b083b39445959102d4947c51c4db6aa4f4e58"
+                ))
+            );
+        }
+    }
 }
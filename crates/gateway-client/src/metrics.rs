@@ -9,6 +9,10 @@ use pathfinder_common::BlockId;
 const METRIC_REQUESTS: &str = "gateway_requests_total";
 const METRIC_FAILED_REQUESTS: &str = "gateway_requests_failed_total";
 const METRICS: [&str; 2] = [METRIC_REQUESTS, METRIC_FAILED_REQUESTS];
+/// The bytes received by streamed downloads, see [get_as_bytes_with_limit](Request::get_as_bytes_with_limit).
+pub const METRIC_DOWNLOADED_BYTES: &str = "gateway_downloaded_bytes_total";
+/// The streamed downloads which were resumed after being interrupted.
+pub const METRIC_RESUMED_DOWNLOADS: &str = "gateway_resumed_downloads_total";
 const TAG_LATEST: &str = "latest";
 const TAG_PENDING: &str = "pending";
 const TAGS: &[&str] = &[TAG_LATEST, TAG_PENDING];
@@ -163,7 +167,7 @@ pub async fn with_metrics<T>(
             {
                 increment_failed(meta, REASON_RATE_LIMITING);
             }
-            SequencerError::ReqwestError(_) => {}
+            SequencerError::ReqwestError(_) | SequencerError::ResponseTooLarge { .. } => {}
         }
 
         e
@@ -0,0 +1,341 @@
+//! Recording of gateway responses to disk, and serving them back without network access.
+//!
+//! Each response is stored in its own file, named after the request's method and parameters, so
+//! that a recorded sync can be replayed deterministically, e.g. for offline integration tests of
+//! the sync pipeline. Only `GET` requests are recorded, transactions are always submitted to the
+//! gateway.
+//!
+//! Repeated requests, e.g. for the pending block, are recorded in sequence with the file name
+//! suffixed by their index, e.g. `get_block_blockNumber=pending.1`, and replayed in the same
+//! order. Once the sequence is exhausted, its last response is replayed. Existing files are never
+//! overwritten, a later recording into the same directory continues their sequence instead.
+//!
+//! A file holds the response's status code on the first line, followed by its body.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::{StatusCode, Url};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recording {
+    /// Responses received from the gateway are written to the directory.
+    Record(PathBuf),
+    /// Responses are served from the directory instead of the gateway.
+    ///
+    /// Requests without a recorded response fail with `404 Not Found`.
+    Replay(PathBuf),
+}
+
+/// A [Recording] along with the position in the sequence of responses of each request.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    recording: Recording,
+    /// The index of the next response of each request, by file name.
+    next: Mutex<HashMap<String, usize>>,
+}
+
+impl Recorder {
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            next: Default::default(),
+        }
+    }
+
+    /// Sends a `GET` request to `url`, recording or replaying the response.
+    pub(crate) async fn get(
+        &self,
+        client: &reqwest::Client,
+        url: Url,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let name = file_name(&url);
+
+        match &self.recording {
+            Recording::Record(directory) => {
+                let response = client.get(url).send().await?;
+                let status = response.status();
+                let body = response.bytes().await?;
+
+                // Files of an earlier recording are skipped, so that they are never overwritten.
+                loop {
+                    let path = directory.join(self.next_file_name(&name));
+                    match write_new(&path, status, &body).await {
+                        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+                        Err(error) => {
+                            tracing::warn!(path=%path.display(), %error, "Failed to record gateway response");
+                            break;
+                        }
+                        Ok(()) => break,
+                    }
+                }
+
+                Ok(to_response(status, body))
+            }
+            Recording::Replay(directory) => {
+                let path = directory.join(self.replayed_file_name(directory, &name));
+                match read(&path).await {
+                    Ok((status, body)) => Ok(to_response(status, body)),
+                    Err(error) => {
+                        tracing::warn!(%url, path=%path.display(), %error, "No recorded gateway response");
+                        Ok(to_response(StatusCode::NOT_FOUND, Default::default()))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Recorder {
+    /// The file name of the next response to `name`.
+    fn next_file_name(&self, name: &str) -> String {
+        let mut next = self.next.lock().unwrap();
+        let index = next.entry(name.to_owned()).or_default();
+        let file_name = indexed_file_name(name, *index);
+        *index += 1;
+        file_name
+    }
+
+    /// The file name of the next recorded response to `name`, or of its last one once all have
+    /// been replayed.
+    fn replayed_file_name(&self, directory: &Path, name: &str) -> String {
+        let mut next = self.next.lock().unwrap();
+        let index = next.entry(name.to_owned()).or_default();
+        let file_name = indexed_file_name(name, *index);
+        if directory.join(&file_name).exists() {
+            *index += 1;
+            file_name
+        } else {
+            indexed_file_name(name, index.saturating_sub(1))
+        }
+    }
+}
+
+/// The file name of the response with the given index in the sequence of responses to `name`.
+fn indexed_file_name(name: &str, index: usize) -> String {
+    match index {
+        0 => name.to_owned(),
+        index => format!("{name}.{index}"),
+    }
+}
+
+/// The file name of the request's response, e.g. `get_block_blockNumber=5`.
+///
+/// Parameters are sorted so that their order does not matter, and the optional API token is
+/// omitted so that it isn't persisted.
+fn file_name(url: &Url) -> String {
+    let method = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default()
+        .to_owned();
+
+    let mut params = url
+        .query_pairs()
+        .filter(|(name, _)| name != "token")
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>();
+    params.sort();
+
+    std::iter::once(method)
+        .chain(params)
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '=' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Writes the response to a new file at `path`, failing if the file already exists.
+async fn write_new(path: &Path, status: StatusCode, body: &[u8]) -> std::io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut contents = format!("{}\n", status.as_u16()).into_bytes();
+    contents.extend_from_slice(body);
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await?;
+    file.write_all(&contents).await?;
+    file.flush().await
+}
+
+async fn read(path: &Path) -> std::io::Result<(StatusCode, bytes::Bytes)> {
+    use std::io::{Error, ErrorKind};
+
+    let mut contents = bytes::Bytes::from(tokio::fs::read(path).await?);
+
+    let newline = contents
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing status code"))?;
+    let body = contents.split_off(newline + 1);
+    let status = std::str::from_utf8(&contents[..newline])
+        .ok()
+        .and_then(|status| status.parse::<u16>().ok())
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Invalid status code"))?;
+
+    Ok((status, body))
+}
+
+fn to_response(status: StatusCode, body: bytes::Bytes) -> reqwest::Response {
+    let mut response = http::Response::new(body);
+    *response.status_mut() = status;
+    response.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Client, GatewayApi};
+    use http::response::Builder;
+    use pathfinder_common::{BlockId, BlockNumber};
+    use starknet_gateway_test_fixtures::v0_9_0;
+    use starknet_gateway_types::error::{KnownStarknetErrorCode, SequencerError};
+    use warp::Filter;
+
+    #[test]
+    fn file_names() {
+        let url = Url::parse(
+            "https://example.com/feeder_gateway/get_block?token=secret&blockNumber=latest&headerOnly=true",
+        )
+        .unwrap();
+        assert_eq!(
+            file_name(&url),
+            "get_block_blockNumber=latest_headerOnly=true"
+        );
+
+        let url = Url::parse("https://example.com/feeder_gateway/get_class_by_hash?classHash=0x1a")
+            .unwrap();
+        assert_eq!(file_name(&url), "get_class_by_hash_classHash=0x1a");
+    }
+
+    fn server() -> (tokio::task::JoinHandle<()>, std::net::SocketAddr) {
+        let block = warp::path!("feeder_gateway" / "get_block")
+            .and(warp::query::<std::collections::HashMap<String, String>>())
+            .map(|params: std::collections::HashMap<String, String>| {
+                match params.get("blockNumber").map(String::as_str) {
+                    Some("0") => Builder::new()
+                        .status(200)
+                        .body(v0_9_0::block::GENESIS.to_owned()),
+                    _ => Builder::new().status(400).body(
+                        r#"{"code":"StarknetErrorCode.BLOCK_NOT_FOUND","message":""}"#.to_owned(),
+                    ),
+                }
+            });
+        let (addr, run_srv) = warp::serve(block).bind_ephemeral(([127, 0, 0, 1], 0));
+        (tokio::spawn(run_srv), addr)
+    }
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let (server, addr) = server();
+        let mut url = Url::parse("http://localhost/").unwrap();
+        url.set_port(Some(addr.port())).unwrap();
+
+        let recorder = Client::with_base_url(url.clone())
+            .unwrap()
+            .disable_retry_for_tests()
+            .with_recording(Recording::Record(directory.path().to_owned()));
+        let genesis = recorder.block(BlockNumber::GENESIS.into()).await.unwrap();
+        let error = recorder
+            .block(BlockNumber::new_or_panic(1).into())
+            .await
+            .unwrap_err();
+
+        // Replaying must not depend on the gateway.
+        server.abort();
+        let _ = server.await;
+
+        let replayer = Client::with_base_url(url)
+            .unwrap()
+            .disable_retry_for_tests()
+            .with_recording(Recording::Replay(directory.path().to_owned()));
+        assert_eq!(
+            replayer.block(BlockNumber::GENESIS.into()).await.unwrap(),
+            genesis
+        );
+
+        let replayed = replayer
+            .block(BlockNumber::new_or_panic(1).into())
+            .await
+            .unwrap_err();
+        assert_eq!(replayed.to_string(), error.to_string());
+        assert_matches::assert_matches!(
+            replayed,
+            SequencerError::StarknetError(e) => assert_eq!(e.code, KnownStarknetErrorCode::BlockNotFound.into())
+        );
+
+        let error = replayer.block(BlockId::Latest).await.unwrap_err();
+        assert_matches::assert_matches!(
+            error,
+            SequencerError::ReqwestError(e) => assert_eq!(e.status(), Some(StatusCode::NOT_FOUND))
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_requests_are_recorded_in_sequence() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let (_server, addr) = server();
+        let mut url = Url::parse("http://localhost/").unwrap();
+        url.set_port(Some(addr.port())).unwrap();
+
+        let recorder = Client::with_base_url(url.clone())
+            .unwrap()
+            .disable_retry_for_tests()
+            .with_recording(Recording::Record(directory.path().to_owned()));
+        recorder.block(BlockNumber::GENESIS.into()).await.unwrap();
+        recorder.block(BlockNumber::GENESIS.into()).await.unwrap();
+
+        // A later recording into the same directory continues the sequence.
+        let recorder = Client::with_base_url(url)
+            .unwrap()
+            .disable_retry_for_tests()
+            .with_recording(Recording::Record(directory.path().to_owned()));
+        recorder.block(BlockNumber::GENESIS.into()).await.unwrap();
+
+        let mut files = std::fs::read_dir(directory.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                "get_block_blockNumber=0",
+                "get_block_blockNumber=0.1",
+                "get_block_blockNumber=0.2",
+            ]
+        );
+    }
+
+    #[test]
+    fn replayed_in_sequence() {
+        let directory = tempfile::tempdir().unwrap();
+        for name in ["pending", "pending.1"] {
+            std::fs::write(directory.path().join(name), "200\n").unwrap();
+        }
+
+        let replayer = Recorder::new(Recording::Replay(directory.path().to_owned()));
+        let replayed = || replayer.replayed_file_name(directory.path(), "pending");
+        assert_eq!(replayed(), "pending");
+        assert_eq!(replayed(), "pending.1");
+        // The last response is repeated once the sequence is exhausted.
+        assert_eq!(replayed(), "pending.1");
+        assert_eq!(replayed(), "pending.1");
+
+        assert_eq!(
+            replayer.replayed_file_name(directory.path(), "missing"),
+            "missing"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! A scriptable in-memory sequencer for deterministic tests of the sync process.
+//!
+//! [MockSequencer] serves a chain which tests extend, fork and reorg at will, along with an
+//! optional pending block whose publication can be delayed. Unlike [MockGatewayApi](crate::MockGatewayApi)
+//! no expectations need to be set up, sync simply sees whatever the chain looks like when it asks.
+//!
+//! Generated blocks have fake hashes, so sync must be run with block hash validation disabled.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use pathfinder_common::{
+    BlockCommitmentSignatureElem, BlockHash, BlockId, BlockNumber, BlockTimestamp, ClassHash,
+    GasPrice, SequencerAddress, StarknetVersion, StateCommitment, StateDiffCommitment, StateUpdate,
+};
+use pathfinder_crypto::Felt;
+use starknet_gateway_types::error::{KnownStarknetErrorCode, SequencerError, StarknetError};
+use starknet_gateway_types::reply;
+
+use crate::GatewayApi;
+
+/// A sequencer serving a scripted chain.
+///
+/// Clones share the same chain, so a test can keep a handle to script the chain while sync
+/// queries another.
+#[derive(Clone, Default)]
+pub struct MockSequencer(Arc<Mutex<Inner>>);
+
+#[derive(Default)]
+struct Inner {
+    chain: Vec<MockBlock>,
+    /// Incremented on each reorg so that replacement blocks get new hashes.
+    fork: u64,
+    pending: Option<Pending>,
+    classes: HashMap<ClassHash, bytes::Bytes>,
+}
+
+struct Pending {
+    block: reply::PendingBlock,
+    state_update: StateUpdate,
+    /// The number of pending queries which are still answered with the latest block instead.
+    delay: usize,
+}
+
+/// A block of the [MockSequencer]'s chain.
+#[derive(Clone, Debug)]
+pub struct MockBlock {
+    pub block: reply::Block,
+    pub state_update: StateUpdate,
+    pub signature: reply::BlockSignature,
+}
+
+impl MockSequencer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number and hash of the latest block, if any.
+    pub fn latest(&self) -> Option<(BlockNumber, BlockHash)> {
+        let inner = self.0.lock().unwrap();
+        inner
+            .chain
+            .last()
+            .map(|b| (b.block.block_number, b.block.block_hash))
+    }
+
+    /// The block at `number` of the current chain.
+    pub fn block_at(&self, number: BlockNumber) -> Option<MockBlock> {
+        let inner = self.0.lock().unwrap();
+        inner.chain.get(number.get() as usize).cloned()
+    }
+
+    /// Appends an empty block to the chain and returns its hash.
+    pub fn produce_block(&self) -> BlockHash {
+        self.produce_block_with(StateUpdate::default())
+    }
+
+    /// Appends a block with the given state diff to the chain and returns its hash.
+    ///
+    /// The state update's block hash and state commitments are overwritten to match the chain.
+    pub fn produce_block_with(&self, state_update: StateUpdate) -> BlockHash {
+        let mut inner = self.0.lock().unwrap();
+
+        let number = BlockNumber::new_or_panic(inner.chain.len() as u64);
+        let (parent_hash, parent_state_commitment) = inner
+            .chain
+            .last()
+            .map(|b| (b.block.block_hash, b.block.state_commitment))
+            .unwrap_or_default();
+
+        let hash = BlockHash(fake_felt("block", number, inner.fork));
+        let state_commitment = StateCommitment(fake_felt("state", number, inner.fork));
+
+        let block = reply::Block {
+            block_hash: hash,
+            block_number: number,
+            gas_price: Some(GasPrice::ZERO),
+            parent_block_hash: parent_hash,
+            sequencer_address: Some(SequencerAddress::ZERO),
+            state_commitment,
+            status: reply::Status::AcceptedOnL2,
+            timestamp: BlockTimestamp::new_or_panic(number.get()),
+            transaction_receipts: vec![],
+            transactions: vec![],
+            starknet_version: StarknetVersion::default(),
+        };
+        let state_update = state_update
+            .with_block_hash(hash)
+            .with_state_commitment(state_commitment)
+            .with_parent_state_commitment(parent_state_commitment);
+        let signature = reply::BlockSignature {
+            block_number: number,
+            signature: [BlockCommitmentSignatureElem::ZERO; 2],
+            signature_input: reply::BlockSignatureInput {
+                block_hash: hash,
+                state_diff_commitment: StateDiffCommitment::ZERO,
+            },
+        };
+
+        inner.chain.push(MockBlock {
+            block,
+            state_update,
+            signature,
+        });
+
+        hash
+    }
+
+    /// Removes all blocks from `number` onwards, so that `number` becomes the next block to be
+    /// produced. Blocks produced afterwards have different hashes than the removed ones.
+    ///
+    /// The pending block is removed as well.
+    pub fn reorg(&self, number: BlockNumber) {
+        let mut inner = self.0.lock().unwrap();
+        inner.chain.truncate(number.get() as usize);
+        inner.fork += 1;
+        inner.pending = None;
+    }
+
+    /// Reorgs the chain to `number` and produces `count` new blocks from there on, i.e. replaces
+    /// the chain from `number` with a fork.
+    pub fn fork(&self, number: BlockNumber, count: usize) {
+        self.reorg(number);
+        for _ in 0..count {
+            self.produce_block();
+        }
+    }
+
+    /// Serves a pending block on top of the current latest block.
+    ///
+    /// Its parent hash and state commitment are overwritten to match the latest block. The
+    /// pending block is kept when new blocks are produced, which lets tests serve outdated
+    /// pending data, until it is replaced or [cleared](Self::clear_pending).
+    ///
+    /// Note that sync only emits pending blocks with transactions.
+    pub fn set_pending(&self, mut block: reply::PendingBlock, state_update: StateUpdate) {
+        let mut inner = self.0.lock().unwrap();
+        let (parent_hash, parent_state_commitment) = inner
+            .chain
+            .last()
+            .map(|b| (b.block.block_hash, b.block.state_commitment))
+            .unwrap_or_default();
+
+        block.parent_hash = parent_hash;
+        block.status = reply::Status::Pending;
+        let state_update = state_update
+            .with_block_hash(BlockHash::ZERO)
+            .with_state_commitment(StateCommitment::ZERO)
+            .with_parent_state_commitment(parent_state_commitment);
+
+        inner.pending = Some(Pending {
+            block,
+            state_update,
+            delay: 0,
+        });
+    }
+
+    /// Answers the next `queries` pending queries with the latest block instead of the pending
+    /// block, as the gateway does until it has published a new pending block.
+    pub fn delay_pending(&self, queries: usize) {
+        let mut inner = self.0.lock().unwrap();
+        if let Some(pending) = inner.pending.as_mut() {
+            pending.delay = queries;
+        }
+    }
+
+    pub fn clear_pending(&self) {
+        self.0.lock().unwrap().pending = None;
+    }
+
+    /// Serves the class definition from the class endpoints.
+    pub fn add_class(&self, hash: ClassHash, definition: bytes::Bytes) {
+        self.0.lock().unwrap().classes.insert(hash, definition);
+    }
+
+    fn resolve(&self, block: BlockId) -> Result<MockBlock, SequencerError> {
+        let inner = self.0.lock().unwrap();
+        let block = match block {
+            BlockId::Number(number) => inner.chain.get(number.get() as usize),
+            BlockId::Hash(hash) => inner.chain.iter().find(|b| b.block.block_hash == hash),
+            BlockId::Latest | BlockId::Pending => inner.chain.last(),
+        };
+
+        block.cloned().ok_or_else(block_not_found)
+    }
+
+    /// The pending block and state update, or the latest block if there is no pending block
+    /// yet.
+    fn pending(&self) -> Result<(reply::MaybePendingBlock, StateUpdate), SequencerError> {
+        {
+            let mut inner = self.0.lock().unwrap();
+            if let Some(pending) = inner.pending.as_mut() {
+                if pending.delay == 0 {
+                    return Ok((
+                        reply::MaybePendingBlock::Pending(pending.block.clone()),
+                        pending.state_update.clone(),
+                    ));
+                }
+                pending.delay -= 1;
+            }
+        }
+
+        let latest = self.resolve(BlockId::Latest)?;
+        Ok((latest.block.into(), latest.state_update))
+    }
+}
+
+fn fake_felt(kind: &str, number: BlockNumber, fork: u64) -> Felt {
+    Felt::from_be_slice(format!("{kind} {number} fork {fork}").as_bytes())
+        .expect("Fits into a felt")
+}
+
+fn block_not_found() -> SequencerError {
+    SequencerError::StarknetError(StarknetError {
+        code: KnownStarknetErrorCode::BlockNotFound.into(),
+        message: "Block not found".to_owned(),
+    })
+}
+
+#[async_trait::async_trait]
+impl GatewayApi for MockSequencer {
+    async fn block(&self, block: BlockId) -> Result<reply::MaybePendingBlock, SequencerError> {
+        self.block_without_retry(block).await
+    }
+
+    async fn block_without_retry(
+        &self,
+        block: BlockId,
+    ) -> Result<reply::MaybePendingBlock, SequencerError> {
+        match block {
+            BlockId::Pending => self.pending().map(|(block, _)| block),
+            other => self.resolve(other).map(|b| b.block.into()),
+        }
+    }
+
+    async fn block_header(
+        &self,
+        block: BlockId,
+    ) -> Result<(BlockNumber, BlockHash), SequencerError> {
+        self.resolve(block)
+            .map(|b| (b.block.block_number, b.block.block_hash))
+    }
+
+    async fn pending_class_by_hash(
+        &self,
+        class_hash: ClassHash,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        self.class_by_hash(class_hash, BlockId::Pending).await
+    }
+
+    async fn class_by_hash(
+        &self,
+        class_hash: ClassHash,
+        _block: BlockId,
+    ) -> Result<bytes::Bytes, SequencerError> {
+        let inner = self.0.lock().unwrap();
+        inner.classes.get(&class_hash).cloned().ok_or_else(|| {
+            SequencerError::StarknetError(StarknetError {
+                code: KnownStarknetErrorCode::UndeclaredClass.into(),
+                message: format!("Class {class_hash} is not declared"),
+            })
+        })
+    }
+
+    async fn state_update(&self, block: BlockId) -> Result<StateUpdate, SequencerError> {
+        match block {
+            BlockId::Pending => self.pending().map(|(_, state_update)| state_update),
+            other => self.resolve(other).map(|b| b.state_update),
+        }
+    }
+
+    async fn state_update_with_block(
+        &self,
+        block: BlockId,
+    ) -> Result<(reply::MaybePendingBlock, StateUpdate), SequencerError> {
+        match block {
+            BlockId::Pending => self.pending(),
+            other => self
+                .resolve(other)
+                .map(|b| (b.block.into(), b.state_update)),
+        }
+    }
+
+    async fn signature(&self, block: BlockId) -> Result<reply::BlockSignature, SequencerError> {
+        self.resolve(block).map(|b| b.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reorg_replaces_blocks() {
+        let sequencer = MockSequencer::new();
+        sequencer.produce_block();
+        let hash1 = sequencer.produce_block();
+        sequencer.produce_block();
+
+        sequencer.fork(BlockNumber::new_or_panic(1), 1);
+
+        let (number, hash) = sequencer.head().await.unwrap();
+        assert_eq!(number, BlockNumber::new_or_panic(1));
+        assert_ne!(hash, hash1);
+
+        let block = sequencer.block_at(number).unwrap().block;
+        assert_eq!(
+            block.parent_block_hash,
+            sequencer
+                .block_at(BlockNumber::GENESIS)
+                .unwrap()
+                .block
+                .block_hash
+        );
+
+        let error = sequencer
+            .block(BlockNumber::new_or_panic(2).into())
+            .await
+            .unwrap_err();
+        assert_matches::assert_matches!(
+            error,
+            SequencerError::StarknetError(e) if e.code == KnownStarknetErrorCode::BlockNotFound.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn delayed_pending() {
+        let sequencer = MockSequencer::new();
+        let head = sequencer.produce_block();
+        sequencer.set_pending(Default::default(), Default::default());
+        sequencer.delay_pending(1);
+
+        let (block, _) = sequencer
+            .state_update_with_block(BlockId::Pending)
+            .await
+            .unwrap();
+        assert_matches::assert_matches!(block, reply::MaybePendingBlock::Block(b) if b.block_hash == head);
+
+        let (block, _) = sequencer
+            .state_update_with_block(BlockId::Pending)
+            .await
+            .unwrap();
+        assert_matches::assert_matches!(block, reply::MaybePendingBlock::Pending(b) if b.parent_hash == head);
+    }
+}
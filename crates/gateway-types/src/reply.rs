@@ -1,4 +1,5 @@
 //! Structures used for deserializing replies from Starkware's sequencer REST API.
+use anyhow::Context;
 use pathfinder_common::{
     BlockCommitmentSignatureElem, BlockHash, BlockNumber, BlockTimestamp, ContractAddress,
     EthereumAddress, GasPrice, SequencerAddress, StarknetVersion, StateCommitment,
@@ -11,7 +12,6 @@ use serde_with::serde_as;
 /// Used to deserialize replies to Starknet block requests.
 #[serde_as]
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, serde::Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct Block {
     pub block_hash: BlockHash,
     pub block_number: BlockNumber,
@@ -35,6 +35,62 @@ pub struct Block {
     pub starknet_version: StarknetVersion,
 }
 
+impl Block {
+    /// The block's gas price, or zero for blocks prior to Starknet 0.9.
+    ///
+    /// Fails if the gas price is missing although the block's version includes it.
+    pub fn gas_price(&self) -> anyhow::Result<GasPrice> {
+        let gas_price = included_since(
+            &self.starknet_version,
+            (0, 9, 0),
+            "gas_price",
+            self.gas_price,
+        )?;
+        Ok(gas_price.unwrap_or(GasPrice::ZERO))
+    }
+
+    /// The block's sequencer address, or zero for blocks prior to Starknet 0.8.
+    ///
+    /// Fails if the sequencer address is missing although the block's version includes it.
+    pub fn sequencer_address(&self) -> anyhow::Result<SequencerAddress> {
+        let sequencer_address = included_since(
+            &self.starknet_version,
+            (0, 8, 0),
+            "sequencer_address",
+            self.sequencer_address,
+        )?;
+        Ok(sequencer_address.unwrap_or(SequencerAddress::ZERO))
+    }
+}
+
+/// Checks an optional `field` of a reply, which the gateway includes in the replies of Starknet
+/// versions `since` and later.
+///
+/// Replies without a Starknet version predate all such fields, as the version was only added in
+/// Starknet 0.9.1, and may therefore omit them.
+fn included_since<T>(
+    starknet_version: &StarknetVersion,
+    (major, minor, patch): (u64, u64, u64),
+    field: &str,
+    value: Option<T>,
+) -> anyhow::Result<Option<T>> {
+    let version = starknet_version
+        .parse_as_semver()
+        .context("Parsing Starknet version")?;
+
+    match version {
+        Some(version)
+            if value.is_none() && version >= semver::Version::new(major, minor, patch) =>
+        {
+            anyhow::bail!(
+                "{field} is missing from a Starknet {} reply",
+                starknet_version.as_str()
+            )
+        }
+        _ => Ok(value),
+    }
+}
+
 #[serde_as]
 #[derive(Clone, Default, Debug, Deserialize, PartialEq, Eq)]
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -48,6 +104,10 @@ pub struct PendingBlock {
     pub timestamp: BlockTimestamp,
     pub transaction_receipts: Vec<transaction::Receipt>,
     pub transactions: Vec<transaction::Transaction>,
+    /// Transactions which the sequencer intends to include next, but which are not executed yet
+    /// and may still be dropped. Only exposed by some sequencers.
+    #[serde(default)]
+    pub candidate_transactions: Vec<transaction::Transaction>,
     /// Version metadata introduced in 0.9.1, older blocks will not have it.
     #[serde(default)]
     pub starknet_version: StarknetVersion,
@@ -104,7 +164,6 @@ impl MaybePendingBlock {
 
 /// Block and transaction status values.
 #[derive(Copy, Clone, Default, Debug, Deserialize, PartialEq, Eq, serde::Serialize)]
-#[serde(deny_unknown_fields)]
 pub enum Status {
     #[serde(rename = "NOT_RECEIVED")]
     NotReceived,
@@ -149,7 +208,6 @@ pub mod call {
     /// Describes problems encountered during some of call failures .
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct Problems {
         #[serde_as(as = "HashMap<_, _>")]
         pub calldata: HashMap<u64, Vec<String>>,
@@ -201,14 +259,16 @@ pub mod transaction_status {
 pub mod transaction {
     use fake::{Dummy, Fake, Faker};
     use pathfinder_common::{
-        CallParam, CasmHash, ClassHash, ConstructorParam, ContractAddress, ContractAddressSalt,
-        EntryPoint, EthereumAddress, Fee, L1ToL2MessageNonce, L1ToL2MessagePayloadElem,
-        L2ToL1MessagePayloadElem, TransactionHash, TransactionIndex, TransactionNonce,
+        AccountDeploymentDataElem, CallParam, CasmHash, ClassHash, ConstructorParam,
+        ContractAddress, ContractAddressSalt, EntryPoint, EthereumAddress, Fee, L1ToL2MessageNonce,
+        L1ToL2MessagePayloadElem, L2ToL1MessagePayloadElem, PaymasterDataElem, ResourceAmount,
+        ResourcePricePerUnit, Tip, TransactionHash, TransactionIndex, TransactionNonce,
         TransactionSignatureElem, TransactionVersion,
     };
     use pathfinder_serde::{
         CallParamAsDecimalStr, ConstructorParamAsDecimalStr, EthereumAddressAsHexStr,
         L1ToL2MessagePayloadElemAsDecimalStr, L2ToL1MessagePayloadElemAsDecimalStr,
+        ResourceAmountAsHexStr, ResourcePricePerUnitAsHexStr, TipAsHexStr,
         TransactionSignatureElemAsDecimalStr, TransactionVersionAsHexStr,
     };
     use primitive_types::H256;
@@ -217,7 +277,6 @@ pub mod transaction {
 
     /// Represents deserialized L2 transaction entry point values.
     #[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
-    #[serde(deny_unknown_fields)]
     pub enum EntryPointType {
         #[serde(rename = "EXTERNAL")]
         External,
@@ -246,7 +305,6 @@ pub mod transaction {
 
     /// Represents execution resources for L2 transaction.
     #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct ExecutionResources {
         pub builtin_instance_counter: BuiltinCounters,
         pub n_steps: u64,
@@ -299,7 +357,6 @@ pub mod transaction {
     /// Represents deserialized L1 to L2 message.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct L1ToL2Message {
         #[serde_as(as = "EthereumAddressAsHexStr")]
         pub from_address: EthereumAddress,
@@ -327,7 +384,6 @@ pub mod transaction {
     /// Represents deserialized L2 to L1 message.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
-    #[serde(deny_unknown_fields)]
     pub struct L2ToL1Message {
         pub from_address: ContractAddress,
         #[serde_as(as = "Vec<L2ToL1MessagePayloadElemAsDecimalStr>")]
@@ -336,6 +392,33 @@ pub mod transaction {
         pub to_address: EthereumAddress,
     }
 
+    impl L2ToL1Message {
+        /// The hash with which the Starknet core contract records the message on L1, once the
+        /// block containing it is accepted on L1.
+        pub fn calculate_message_hash(&self) -> H256 {
+            use sha3::{Digest, Keccak256};
+
+            let mut hash = Keccak256::new();
+
+            hash.update(self.from_address.0.as_be_bytes());
+            // Pad the ethereum address to 32 bytes to match a felt.
+            hash.update([0u8; 12]);
+            hash.update(self.to_address.0.as_bytes());
+
+            // Pad the u64 to 32 bytes to match a felt.
+            hash.update([0u8; 24]);
+            hash.update((self.payload.len() as u64).to_be_bytes());
+
+            for elem in &self.payload {
+                hash.update(elem.0.as_be_bytes());
+            }
+
+            let hash = <[u8; 32]>::from(hash.finalize());
+
+            hash.into()
+        }
+    }
+
     #[derive(Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
     #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
     pub enum ExecutionStatus {
@@ -348,7 +431,6 @@ pub mod transaction {
 
     /// Represents deserialized L2 transaction receipt data.
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct Receipt {
         #[serde(default)]
         pub actual_fee: Option<Fee>,
@@ -392,7 +474,6 @@ pub mod transaction {
     /// Represents deserialized L2 transaction data.
     #[derive(Clone, Debug, Serialize, PartialEq, Eq, Dummy)]
     #[serde(tag = "type")]
-    #[serde(deny_unknown_fields)]
     pub enum Transaction {
         #[serde(rename = "DECLARE")]
         Declare(DeclareTransaction),
@@ -427,7 +508,7 @@ pub mod transaction {
             /// Copy of [Transaction] to deserialize into, before converting to [Transaction]
             /// with the potential Invoke V0 -> L1 handler cast.
             #[derive(Deserialize)]
-            #[serde(tag = "type", deny_unknown_fields)]
+            #[serde(tag = "type")]
             pub enum InnerTransaction {
                 #[serde(rename = "DECLARE")]
                 Declare(DeclareTransaction),
@@ -471,12 +552,14 @@ pub mod transaction {
     impl From<pathfinder_common::transaction::Transaction> for Transaction {
         fn from(value: pathfinder_common::transaction::Transaction) -> Self {
             use pathfinder_common::transaction::TransactionVariant::{
-                DeclareV0, DeclareV1, DeclareV2, Deploy, DeployAccount, InvokeV0, InvokeV1,
-                L1Handler,
+                DeclareV0, DeclareV1, DeclareV2, DeclareV3, Deploy, DeployAccount, DeployAccountV3,
+                InvokeV0, InvokeV1, InvokeV3, L1Handler,
             };
             use pathfinder_common::transaction::{
-                DeclareTransactionV0V1, DeclareTransactionV2, DeployAccountTransaction,
-                DeployTransaction, InvokeTransactionV0, InvokeTransactionV1, L1HandlerTransaction,
+                DeclareTransactionV0V1, DeclareTransactionV2, DeclareTransactionV3,
+                DeployAccountTransaction, DeployAccountTransactionV3, DeployTransaction,
+                InvokeTransactionV0, InvokeTransactionV1, InvokeTransactionV3,
+                L1HandlerTransaction,
             };
 
             let transaction_hash = value.hash;
@@ -525,6 +608,32 @@ pub mod transaction {
                     transaction_hash,
                     compiled_class_hash,
                 })),
+                DeclareV3(DeclareTransactionV3 {
+                    class_hash,
+                    nonce,
+                    nonce_data_availability_mode,
+                    fee_data_availability_mode,
+                    resource_bounds,
+                    tip,
+                    paymaster_data,
+                    signature,
+                    account_deployment_data,
+                    sender_address,
+                    compiled_class_hash,
+                }) => Self::Declare(DeclareTransaction::V3(self::DeclareTransactionV3 {
+                    class_hash,
+                    nonce,
+                    nonce_data_availability_mode: nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: fee_data_availability_mode.into(),
+                    resource_bounds: resource_bounds.into(),
+                    tip,
+                    paymaster_data,
+                    sender_address,
+                    signature,
+                    transaction_hash,
+                    compiled_class_hash,
+                    account_deployment_data,
+                })),
                 Deploy(DeployTransaction {
                     contract_address,
                     contract_address_salt,
@@ -548,17 +657,48 @@ pub mod transaction {
                     contract_address_salt,
                     constructor_calldata,
                     class_hash,
-                }) => Self::DeployAccount(self::DeployAccountTransaction {
+                }) => Self::DeployAccount(self::DeployAccountTransaction::V0V1(
+                    self::DeployAccountTransactionV0V1 {
+                        contract_address,
+                        transaction_hash,
+                        max_fee,
+                        version,
+                        signature,
+                        nonce,
+                        contract_address_salt,
+                        constructor_calldata,
+                        class_hash,
+                    },
+                )),
+                DeployAccountV3(DeployAccountTransactionV3 {
                     contract_address,
-                    transaction_hash,
-                    max_fee,
-                    version,
                     signature,
                     nonce,
+                    nonce_data_availability_mode,
+                    fee_data_availability_mode,
+                    resource_bounds,
+                    tip,
+                    paymaster_data,
                     contract_address_salt,
                     constructor_calldata,
                     class_hash,
-                }),
+                }) => Self::DeployAccount(self::DeployAccountTransaction::V3(
+                    self::DeployAccountTransactionV3 {
+                        nonce,
+                        nonce_data_availability_mode: nonce_data_availability_mode.into(),
+                        fee_data_availability_mode: fee_data_availability_mode.into(),
+                        resource_bounds: resource_bounds.into(),
+                        tip,
+                        paymaster_data,
+                        sender_address: contract_address,
+                        signature,
+                        transaction_hash,
+                        version: TransactionVersion::THREE,
+                        contract_address_salt,
+                        constructor_calldata,
+                        class_hash,
+                    },
+                )),
                 InvokeV0(InvokeTransactionV0 {
                     calldata,
                     sender_address,
@@ -589,6 +729,30 @@ pub mod transaction {
                     nonce,
                     transaction_hash,
                 })),
+                InvokeV3(InvokeTransactionV3 {
+                    signature,
+                    nonce,
+                    nonce_data_availability_mode,
+                    fee_data_availability_mode,
+                    resource_bounds,
+                    tip,
+                    paymaster_data,
+                    account_deployment_data,
+                    calldata,
+                    sender_address,
+                }) => Self::Invoke(InvokeTransaction::V3(self::InvokeTransactionV3 {
+                    nonce,
+                    nonce_data_availability_mode: nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: fee_data_availability_mode.into(),
+                    resource_bounds: resource_bounds.into(),
+                    tip,
+                    paymaster_data,
+                    sender_address,
+                    signature,
+                    transaction_hash,
+                    calldata,
+                    account_deployment_data,
+                })),
                 L1Handler(L1HandlerTransaction {
                     contract_address,
                     entry_point_selector,
@@ -663,6 +827,34 @@ pub mod transaction {
                         compiled_class_hash,
                     },
                 ),
+                Transaction::Declare(DeclareTransaction::V3(DeclareTransactionV3 {
+                    class_hash,
+                    nonce,
+                    nonce_data_availability_mode,
+                    fee_data_availability_mode,
+                    resource_bounds,
+                    tip,
+                    paymaster_data,
+                    sender_address,
+                    signature,
+                    transaction_hash: _,
+                    compiled_class_hash,
+                    account_deployment_data,
+                })) => TransactionVariant::DeclareV3(
+                    pathfinder_common::transaction::DeclareTransactionV3 {
+                        class_hash,
+                        nonce,
+                        nonce_data_availability_mode: nonce_data_availability_mode.into(),
+                        fee_data_availability_mode: fee_data_availability_mode.into(),
+                        resource_bounds: resource_bounds.into(),
+                        tip,
+                        paymaster_data,
+                        signature,
+                        account_deployment_data,
+                        sender_address,
+                        compiled_class_hash,
+                    },
+                ),
                 Transaction::Deploy(DeployTransaction {
                     contract_address,
                     contract_address_salt,
@@ -679,17 +871,19 @@ pub mod transaction {
                         version,
                     })
                 }
-                Transaction::DeployAccount(DeployAccountTransaction {
-                    contract_address,
-                    transaction_hash: _,
-                    max_fee,
-                    version,
-                    signature,
-                    nonce,
-                    contract_address_salt,
-                    constructor_calldata,
-                    class_hash,
-                }) => TransactionVariant::DeployAccount(
+                Transaction::DeployAccount(DeployAccountTransaction::V0V1(
+                    DeployAccountTransactionV0V1 {
+                        contract_address,
+                        transaction_hash: _,
+                        max_fee,
+                        version,
+                        signature,
+                        nonce,
+                        contract_address_salt,
+                        constructor_calldata,
+                        class_hash,
+                    },
+                )) => TransactionVariant::DeployAccount(
                     pathfinder_common::transaction::DeployAccountTransaction {
                         contract_address,
                         max_fee,
@@ -701,6 +895,37 @@ pub mod transaction {
                         class_hash,
                     },
                 ),
+                Transaction::DeployAccount(DeployAccountTransaction::V3(
+                    DeployAccountTransactionV3 {
+                        nonce,
+                        nonce_data_availability_mode,
+                        fee_data_availability_mode,
+                        resource_bounds,
+                        tip,
+                        paymaster_data,
+                        sender_address,
+                        signature,
+                        transaction_hash: _,
+                        version: _,
+                        contract_address_salt,
+                        constructor_calldata,
+                        class_hash,
+                    },
+                )) => TransactionVariant::DeployAccountV3(
+                    pathfinder_common::transaction::DeployAccountTransactionV3 {
+                        contract_address: sender_address,
+                        signature,
+                        nonce,
+                        nonce_data_availability_mode: nonce_data_availability_mode.into(),
+                        fee_data_availability_mode: fee_data_availability_mode.into(),
+                        resource_bounds: resource_bounds.into(),
+                        tip,
+                        paymaster_data,
+                        contract_address_salt,
+                        constructor_calldata,
+                        class_hash,
+                    },
+                ),
                 Transaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0 {
                     calldata,
                     sender_address,
@@ -735,6 +960,32 @@ pub mod transaction {
                         nonce,
                     },
                 ),
+                Transaction::Invoke(InvokeTransaction::V3(InvokeTransactionV3 {
+                    nonce,
+                    nonce_data_availability_mode,
+                    fee_data_availability_mode,
+                    resource_bounds,
+                    tip,
+                    paymaster_data,
+                    sender_address,
+                    signature,
+                    transaction_hash: _,
+                    calldata,
+                    account_deployment_data,
+                })) => TransactionVariant::InvokeV3(
+                    pathfinder_common::transaction::InvokeTransactionV3 {
+                        signature,
+                        nonce,
+                        nonce_data_availability_mode: nonce_data_availability_mode.into(),
+                        fee_data_availability_mode: fee_data_availability_mode.into(),
+                        resource_bounds: resource_bounds.into(),
+                        tip,
+                        paymaster_data,
+                        account_deployment_data,
+                        calldata,
+                        sender_address,
+                    },
+                ),
                 Transaction::L1Handler(L1HandlerTransaction {
                     contract_address,
                     entry_point_selector,
@@ -765,12 +1016,14 @@ pub mod transaction {
                     DeclareTransaction::V0(t) => t.transaction_hash,
                     DeclareTransaction::V1(t) => t.transaction_hash,
                     DeclareTransaction::V2(t) => t.transaction_hash,
+                    DeclareTransaction::V3(t) => t.transaction_hash,
                 },
                 Transaction::Deploy(t) => t.transaction_hash,
-                Transaction::DeployAccount(t) => t.transaction_hash,
+                Transaction::DeployAccount(t) => t.transaction_hash(),
                 Transaction::Invoke(t) => match t {
                     InvokeTransaction::V0(t) => t.transaction_hash,
                     InvokeTransaction::V1(t) => t.transaction_hash,
+                    InvokeTransaction::V3(t) => t.transaction_hash,
                 },
                 Transaction::L1Handler(t) => t.transaction_hash,
             }
@@ -781,25 +1034,42 @@ pub mod transaction {
                 Transaction::Declare(DeclareTransaction::V0(t)) => t.sender_address,
                 Transaction::Declare(DeclareTransaction::V1(t)) => t.sender_address,
                 Transaction::Declare(DeclareTransaction::V2(t)) => t.sender_address,
+                Transaction::Declare(DeclareTransaction::V3(t)) => t.sender_address,
                 Transaction::Deploy(t) => t.contract_address,
-                Transaction::DeployAccount(t) => t.contract_address,
+                Transaction::DeployAccount(t) => t.contract_address(),
                 Transaction::Invoke(t) => match t {
                     InvokeTransaction::V0(t) => t.sender_address,
                     InvokeTransaction::V1(t) => t.sender_address,
+                    InvokeTransaction::V3(t) => t.sender_address,
                 },
                 Transaction::L1Handler(t) => t.contract_address,
             }
         }
 
+        /// The account which sent the transaction, or [None] for `DEPLOY` and `L1_HANDLER`
+        /// transactions which are not sent by an account.
+        ///
+        /// `DEPLOY_ACCOUNT` transactions are sent by the account they deploy.
+        pub fn sender_address(&self) -> Option<ContractAddress> {
+            match self {
+                Transaction::Declare(_)
+                | Transaction::Invoke(_)
+                | Transaction::DeployAccount(_) => Some(self.contract_address()),
+                Transaction::Deploy(_) | Transaction::L1Handler(_) => None,
+            }
+        }
+
         pub fn version(&self) -> TransactionVersion {
             match self {
                 Transaction::Declare(DeclareTransaction::V0(_)) => TransactionVersion::ZERO,
                 Transaction::Declare(DeclareTransaction::V1(_)) => TransactionVersion::ONE,
                 Transaction::Declare(DeclareTransaction::V2(_)) => TransactionVersion::TWO,
+                Transaction::Declare(DeclareTransaction::V3(_)) => TransactionVersion::THREE,
                 Transaction::Deploy(t) => t.version,
-                Transaction::DeployAccount(t) => t.version,
+                Transaction::DeployAccount(t) => t.version(),
                 Transaction::Invoke(InvokeTransaction::V0(_)) => TransactionVersion::ZERO,
                 Transaction::Invoke(InvokeTransaction::V1(_)) => TransactionVersion::ONE,
+                Transaction::Invoke(InvokeTransaction::V3(_)) => TransactionVersion::THREE,
                 Transaction::L1Handler(t) => t.version,
             }
         }
@@ -814,6 +1084,8 @@ pub mod transaction {
         V1(DeclareTransactionV0V1),
         #[serde(rename = "0x2")]
         V2(DeclareTransactionV2),
+        #[serde(rename = "0x3")]
+        V3(DeclareTransactionV3),
     }
 
     impl<'de> Deserialize<'de> for DeclareTransaction {
@@ -833,7 +1105,7 @@ pub mod transaction {
 
             let mut v = serde_json::Value::deserialize(deserializer)?;
             let version = Version::deserialize(&v).map_err(de::Error::custom)?;
-            // remove "version", since v0 and v1 transactions use deny_unknown_fields
+            // remove "version", since it only selects the variant and is not part of v0 and v1 transactions
             v.as_object_mut()
                 .expect("must be an object because deserializing version succeeded")
                 .remove("version");
@@ -847,7 +1119,10 @@ pub mod transaction {
                 TransactionVersion(x) if x == H256::from_low_u64_be(2) => Ok(Self::V2(
                     DeclareTransactionV2::deserialize(&v).map_err(de::Error::custom)?,
                 )),
-                _v => Err(de::Error::custom("version must be 0, 1 or 2")),
+                TransactionVersion(x) if x == H256::from_low_u64_be(3) => Ok(Self::V3(
+                    DeclareTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _v => Err(de::Error::custom("version must be 0, 1, 2 or 3")),
             }
         }
     }
@@ -858,6 +1133,17 @@ pub mod transaction {
                 DeclareTransaction::V0(tx) => tx.signature.as_ref(),
                 DeclareTransaction::V1(tx) => tx.signature.as_ref(),
                 DeclareTransaction::V2(tx) => tx.signature.as_ref(),
+                DeclareTransaction::V3(tx) => tx.signature.as_ref(),
+            }
+        }
+
+        /// The hash of the declared class, which is the Sierra hash of V2 and V3 declares.
+        pub fn class_hash(&self) -> ClassHash {
+            match self {
+                DeclareTransaction::V0(tx) => tx.class_hash,
+                DeclareTransaction::V1(tx) => tx.class_hash,
+                DeclareTransaction::V2(tx) => tx.class_hash,
+                DeclareTransaction::V3(tx) => tx.class_hash,
             }
         }
     }
@@ -865,7 +1151,6 @@ pub mod transaction {
     /// A version 0 or 1 declare transaction.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
-    #[serde(deny_unknown_fields)]
     pub struct DeclareTransactionV0V1 {
         pub class_hash: ClassHash,
         pub max_fee: Fee,
@@ -880,7 +1165,6 @@ pub mod transaction {
     /// A version 2 declare transaction.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
-    #[serde(deny_unknown_fields)]
     pub struct DeclareTransactionV2 {
         pub class_hash: ClassHash,
         pub max_fee: Fee,
@@ -893,6 +1177,142 @@ pub mod transaction {
         pub compiled_class_hash: CasmHash,
     }
 
+    /// A version 3 declare transaction.
+    #[serde_as]
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
+    pub struct DeclareTransactionV3 {
+        pub class_hash: ClassHash,
+        pub nonce: TransactionNonce,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub sender_address: ContractAddress,
+        #[serde_as(as = "Vec<TransactionSignatureElemAsDecimalStr>")]
+        #[serde(default)]
+        pub signature: Vec<TransactionSignatureElem>,
+        pub transaction_hash: TransactionHash,
+        pub compiled_class_hash: CasmHash,
+        pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+    }
+
+    /// The resource bounds of a v3 transaction, keyed by resource.
+    #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, Dummy)]
+    pub struct ResourceBounds {
+        #[serde(rename = "L1_GAS")]
+        pub l1_gas: ResourceBound,
+        #[serde(rename = "L2_GAS")]
+        pub l2_gas: ResourceBound,
+    }
+
+    #[serde_as]
+    #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, Dummy)]
+    pub struct ResourceBound {
+        #[serde_as(as = "ResourceAmountAsHexStr")]
+        pub max_amount: ResourceAmount,
+        #[serde_as(as = "ResourcePricePerUnitAsHexStr")]
+        pub max_price_per_unit: ResourcePricePerUnit,
+    }
+
+    impl From<ResourceBounds> for pathfinder_common::transaction::ResourceBounds {
+        fn from(value: ResourceBounds) -> Self {
+            Self {
+                l1_gas: value.l1_gas.into(),
+                l2_gas: value.l2_gas.into(),
+            }
+        }
+    }
+
+    impl From<pathfinder_common::transaction::ResourceBounds> for ResourceBounds {
+        fn from(value: pathfinder_common::transaction::ResourceBounds) -> Self {
+            Self {
+                l1_gas: value.l1_gas.into(),
+                l2_gas: value.l2_gas.into(),
+            }
+        }
+    }
+
+    impl From<ResourceBound> for pathfinder_common::transaction::ResourceBound {
+        fn from(value: ResourceBound) -> Self {
+            Self {
+                max_amount: value.max_amount,
+                max_price_per_unit: value.max_price_per_unit,
+            }
+        }
+    }
+
+    impl From<pathfinder_common::transaction::ResourceBound> for ResourceBound {
+        fn from(value: pathfinder_common::transaction::ResourceBound) -> Self {
+            Self {
+                max_amount: value.max_amount,
+                max_price_per_unit: value.max_price_per_unit,
+            }
+        }
+    }
+
+    /// Where the state updates of a v3 transaction's nonce and fee payment are published.
+    ///
+    /// The gateway serves these as integers, i.e. `0` for L1 and `1` for L2.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Dummy)]
+    pub enum DataAvailabilityMode {
+        #[default]
+        L1,
+        L2,
+    }
+
+    impl Serialize for DataAvailabilityMode {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            match self {
+                DataAvailabilityMode::L1 => serializer.serialize_u8(0),
+                DataAvailabilityMode::L2 => serializer.serialize_u8(1),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DataAvailabilityMode {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            use serde::de;
+
+            // Deserialized via a json value as `serde_json`'s `arbitrary_precision` feature makes
+            // numbers opaque to other visitors. The names used by the RPC API are accepted too.
+            match serde_json::Value::deserialize(deserializer)? {
+                serde_json::Value::Number(n) if n.as_u64() == Some(0) => Ok(Self::L1),
+                serde_json::Value::Number(n) if n.as_u64() == Some(1) => Ok(Self::L2),
+                serde_json::Value::String(s) if s == "L1" => Ok(Self::L1),
+                serde_json::Value::String(s) if s == "L2" => Ok(Self::L2),
+                other => Err(de::Error::custom(format!(
+                    "data availability mode must be 0 (L1) or 1 (L2), got {other}"
+                ))),
+            }
+        }
+    }
+
+    impl From<DataAvailabilityMode> for pathfinder_common::transaction::DataAvailabilityMode {
+        fn from(value: DataAvailabilityMode) -> Self {
+            match value {
+                DataAvailabilityMode::L1 => Self::L1,
+                DataAvailabilityMode::L2 => Self::L2,
+            }
+        }
+    }
+
+    impl From<pathfinder_common::transaction::DataAvailabilityMode> for DataAvailabilityMode {
+        fn from(value: pathfinder_common::transaction::DataAvailabilityMode) -> Self {
+            match value {
+                pathfinder_common::transaction::DataAvailabilityMode::L1 => Self::L1,
+                pathfinder_common::transaction::DataAvailabilityMode::L2 => Self::L2,
+            }
+        }
+    }
+
     fn transaction_version_zero() -> TransactionVersion {
         TransactionVersion(primitive_types::H256::zero())
     }
@@ -900,7 +1320,6 @@ pub mod transaction {
     /// Represents deserialized L2 deploy transaction data.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct DeployTransaction {
         pub contract_address: ContractAddress,
         pub contract_address_salt: ContractAddressSalt,
@@ -927,10 +1346,113 @@ pub mod transaction {
     }
 
     /// Represents deserialized L2 deploy account transaction data.
+    #[derive(Clone, Debug, Serialize, PartialEq, Eq, Dummy)]
+    #[serde(untagged)]
+    pub enum DeployAccountTransaction {
+        V0V1(DeployAccountTransactionV0V1),
+        V3(DeployAccountTransactionV3),
+    }
+
+    impl<'de> Deserialize<'de> for DeployAccountTransaction {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            use serde::de;
+
+            #[serde_as]
+            #[derive(Deserialize)]
+            struct Version {
+                #[serde_as(as = "TransactionVersionAsHexStr")]
+                #[serde(default = "transaction_version_zero")]
+                pub version: TransactionVersion,
+            }
+
+            // Unlike for the other transaction types, the version is part of both variants as
+            // query versions have to be preserved.
+            let v = serde_json::Value::deserialize(deserializer)?;
+            let version = Version::deserialize(&v).map_err(de::Error::custom)?;
+            match version.version.without_query_version() {
+                0 | 1 => Ok(Self::V0V1(
+                    DeployAccountTransactionV0V1::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                3 => Ok(Self::V3(
+                    DeployAccountTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _v => Err(de::Error::custom("version must be 0, 1 or 3")),
+            }
+        }
+    }
+
+    impl DeployAccountTransaction {
+        pub fn contract_address(&self) -> ContractAddress {
+            match self {
+                Self::V0V1(tx) => tx.contract_address,
+                Self::V3(tx) => tx.sender_address,
+            }
+        }
+
+        pub fn transaction_hash(&self) -> TransactionHash {
+            match self {
+                Self::V0V1(tx) => tx.transaction_hash,
+                Self::V3(tx) => tx.transaction_hash,
+            }
+        }
+
+        pub fn transaction_hash_mut(&mut self) -> &mut TransactionHash {
+            match self {
+                Self::V0V1(tx) => &mut tx.transaction_hash,
+                Self::V3(tx) => &mut tx.transaction_hash,
+            }
+        }
+
+        pub fn version(&self) -> TransactionVersion {
+            match self {
+                Self::V0V1(tx) => tx.version,
+                Self::V3(tx) => tx.version,
+            }
+        }
+
+        pub fn signature(&self) -> &[TransactionSignatureElem] {
+            match self {
+                Self::V0V1(tx) => tx.signature.as_ref(),
+                Self::V3(tx) => tx.signature.as_ref(),
+            }
+        }
+
+        pub fn nonce(&self) -> TransactionNonce {
+            match self {
+                Self::V0V1(tx) => tx.nonce,
+                Self::V3(tx) => tx.nonce,
+            }
+        }
+
+        pub fn class_hash(&self) -> ClassHash {
+            match self {
+                Self::V0V1(tx) => tx.class_hash,
+                Self::V3(tx) => tx.class_hash,
+            }
+        }
+
+        pub fn contract_address_salt(&self) -> ContractAddressSalt {
+            match self {
+                Self::V0V1(tx) => tx.contract_address_salt,
+                Self::V3(tx) => tx.contract_address_salt,
+            }
+        }
+
+        pub fn constructor_calldata(&self) -> &[CallParam] {
+            match self {
+                Self::V0V1(tx) => tx.constructor_calldata.as_ref(),
+                Self::V3(tx) => tx.constructor_calldata.as_ref(),
+            }
+        }
+    }
+
+    /// A version 0 or 1 deploy account transaction.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
-    pub struct DeployAccountTransaction {
+    pub struct DeployAccountTransactionV0V1 {
         pub contract_address: ContractAddress,
         pub transaction_hash: TransactionHash,
         pub max_fee: Fee,
@@ -945,7 +1467,7 @@ pub mod transaction {
         pub class_hash: ClassHash,
     }
 
-    impl<T> Dummy<T> for DeployAccountTransaction {
+    impl<T> Dummy<T> for DeployAccountTransactionV0V1 {
         fn dummy_with_rng<R: rand::Rng + ?Sized>(_: &T, rng: &mut R) -> Self {
             Self {
                 // TODO verify this is the only realistic value
@@ -963,6 +1485,51 @@ pub mod transaction {
         }
     }
 
+    /// A version 3 deploy account transaction.
+    #[serde_as]
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+    pub struct DeployAccountTransactionV3 {
+        pub nonce: TransactionNonce,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        /// The address of the deployed account.
+        pub sender_address: ContractAddress,
+        #[serde_as(as = "Vec<TransactionSignatureElemAsDecimalStr>")]
+        pub signature: Vec<TransactionSignatureElem>,
+        pub transaction_hash: TransactionHash,
+        #[serde_as(as = "TransactionVersionAsHexStr")]
+        pub version: TransactionVersion,
+        pub contract_address_salt: ContractAddressSalt,
+        #[serde_as(as = "Vec<CallParamAsDecimalStr>")]
+        pub constructor_calldata: Vec<CallParam>,
+        pub class_hash: ClassHash,
+    }
+
+    impl<T> Dummy<T> for DeployAccountTransactionV3 {
+        fn dummy_with_rng<R: rand::Rng + ?Sized>(_: &T, rng: &mut R) -> Self {
+            Self {
+                version: TransactionVersion::THREE,
+
+                nonce: Faker.fake_with_rng(rng),
+                nonce_data_availability_mode: Faker.fake_with_rng(rng),
+                fee_data_availability_mode: Faker.fake_with_rng(rng),
+                resource_bounds: Faker.fake_with_rng(rng),
+                tip: Faker.fake_with_rng(rng),
+                paymaster_data: Faker.fake_with_rng(rng),
+                sender_address: Faker.fake_with_rng(rng),
+                signature: Faker.fake_with_rng(rng),
+                transaction_hash: Faker.fake_with_rng(rng),
+                contract_address_salt: Faker.fake_with_rng(rng),
+                constructor_calldata: Faker.fake_with_rng(rng),
+                class_hash: Faker.fake_with_rng(rng),
+            }
+        }
+    }
+
     #[derive(Clone, Debug, Serialize, PartialEq, Eq, Dummy)]
     #[serde(tag = "version")]
     pub enum InvokeTransaction {
@@ -970,6 +1537,8 @@ pub mod transaction {
         V0(InvokeTransactionV0),
         #[serde(rename = "0x1")]
         V1(InvokeTransactionV1),
+        #[serde(rename = "0x3")]
+        V3(InvokeTransactionV3),
     }
 
     impl<'de> Deserialize<'de> for InvokeTransaction {
@@ -989,7 +1558,7 @@ pub mod transaction {
 
             let mut v = serde_json::Value::deserialize(deserializer)?;
             let version = Version::deserialize(&v).map_err(de::Error::custom)?;
-            // remove "version", since v0 and v1 transactions use deny_unknown_fields
+            // remove "version", since it only selects the variant and is not part of v0 and v1 transactions
             v.as_object_mut()
                 .expect("must be an object because deserializing version succeeded")
                 .remove("version");
@@ -1000,7 +1569,10 @@ pub mod transaction {
                 TransactionVersion(x) if x == H256::from_low_u64_be(1) => Ok(Self::V1(
                     InvokeTransactionV1::deserialize(&v).map_err(de::Error::custom)?,
                 )),
-                _v => Err(de::Error::custom("version must be 0 or 1")),
+                TransactionVersion(x) if x == H256::from_low_u64_be(3) => Ok(Self::V3(
+                    InvokeTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _v => Err(de::Error::custom("version must be 0, 1 or 3")),
             }
         }
     }
@@ -1010,6 +1582,7 @@ pub mod transaction {
             match self {
                 Self::V0(tx) => tx.signature.as_ref(),
                 Self::V1(tx) => tx.signature.as_ref(),
+                Self::V3(tx) => tx.signature.as_ref(),
             }
         }
     }
@@ -1017,7 +1590,6 @@ pub mod transaction {
     /// Represents deserialized L2 invoke transaction v0 data.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
-    #[serde(deny_unknown_fields)]
     pub struct InvokeTransactionV0 {
         #[serde_as(as = "Vec<CallParamAsDecimalStr>")]
         pub calldata: Vec<CallParam>,
@@ -1039,7 +1611,6 @@ pub mod transaction {
     /// Represents deserialized L2 invoke transaction v1 data.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
-    #[serde(deny_unknown_fields)]
     pub struct InvokeTransactionV1 {
         #[serde_as(as = "Vec<CallParamAsDecimalStr>")]
         pub calldata: Vec<CallParam>,
@@ -1056,10 +1627,29 @@ pub mod transaction {
         pub transaction_hash: TransactionHash,
     }
 
+    /// Represents deserialized L2 invoke transaction v3 data.
+    #[serde_as]
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Dummy)]
+    pub struct InvokeTransactionV3 {
+        pub nonce: TransactionNonce,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub sender_address: ContractAddress,
+        #[serde_as(as = "Vec<TransactionSignatureElemAsDecimalStr>")]
+        pub signature: Vec<TransactionSignatureElem>,
+        pub transaction_hash: TransactionHash,
+        #[serde_as(as = "Vec<CallParamAsDecimalStr>")]
+        pub calldata: Vec<CallParam>,
+        pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+    }
+
     /// Represents deserialized L2 "L1 handler" transaction data.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct L1HandlerTransaction {
         pub contract_address: ContractAddress,
         pub entry_point_selector: EntryPoint,
@@ -1159,9 +1749,14 @@ pub mod transaction {
         }
     }
 
+    impl From<InvokeTransactionV3> for InvokeTransaction {
+        fn from(tx: InvokeTransactionV3) -> Self {
+            Self::V3(tx)
+        }
+    }
+
     /// Describes L2 transaction failure details.
     #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct Failure {
         pub code: String,
         pub error_message: String,
@@ -1170,7 +1765,6 @@ pub mod transaction {
 
 /// Used to deserialize replies to StarkNet state update requests.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct StateUpdate {
     /// Gets default value for pending state updates.
     #[serde(default)]
@@ -1261,7 +1855,6 @@ pub mod state_update {
     /// L2 state diff.
     #[serde_as]
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
-    #[serde(deny_unknown_fields)]
     pub struct StateDiff {
         #[serde_as(as = "HashMap<_, Vec<_>>")]
         pub storage_diffs: HashMap<ContractAddress, Vec<StorageDiff>>,
@@ -1274,7 +1867,6 @@ pub mod state_update {
 
     /// L2 storage diff.
     #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
-    #[serde(deny_unknown_fields)]
     pub struct StorageDiff {
         pub key: StorageAddress,
         pub value: StorageValue,
@@ -1282,7 +1874,6 @@ pub mod state_update {
 
     /// L2 contract data within state diff.
     #[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
-    #[serde(deny_unknown_fields)]
     pub struct DeployedContract {
         pub address: ContractAddress,
         /// `class_hash` is the field name from cairo 0.9.0 onwards
@@ -1293,7 +1884,6 @@ pub mod state_update {
 
     /// Describes a newly declared class. Maps Sierra class hash to a Casm hash.
     #[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
-    #[serde(deny_unknown_fields)]
     pub struct DeclaredSierraClass {
         pub class_hash: SierraHash,
         pub compiled_class_hash: CasmHash,
@@ -1301,7 +1891,6 @@ pub mod state_update {
 
     /// Describes a newly replaced class. Maps contract address to a new class.
     #[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
-    #[serde(deny_unknown_fields)]
     pub struct ReplacedClass {
         pub address: ContractAddress,
         pub class_hash: ClassHash,
@@ -1347,6 +1936,36 @@ pub struct StateUpdateWithBlock {
     pub state_update: StateUpdate,
 }
 
+/// Used to deserialize replies to `get_state_update&includeBlock=true` from gateways which may
+/// predate Starknet 0.12.2. These ignore `includeBlock` and reply with the state update only.
+#[derive(Clone, Debug)]
+pub enum MaybeStateUpdateWithBlock {
+    WithBlock(Box<StateUpdateWithBlock>),
+    StateUpdate(StateUpdate),
+}
+
+impl<'de> Deserialize<'de> for MaybeStateUpdateWithBlock {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de;
+
+        // Selects the variant by the `block` field, rather than trying both, so that the error of
+        // the reply's actual variant is reported.
+        let v = serde_json::Value::deserialize(deserializer)?;
+        if v.get("block").is_some() {
+            Ok(Self::WithBlock(Box::new(
+                StateUpdateWithBlock::deserialize(&v).map_err(de::Error::custom)?,
+            )))
+        } else {
+            Ok(Self::StateUpdate(
+                StateUpdate::deserialize(&v).map_err(de::Error::custom)?,
+            ))
+        }
+    }
+}
+
 /// Used to deserialize replies to Starknet Ethereum contract requests.
 #[serde_as]
 #[derive(Clone, Debug, Deserialize)]
@@ -1361,7 +1980,6 @@ pub mod add_transaction {
 
     /// API response for an INVOKE_FUNCTION transaction
     #[derive(Clone, Debug, serde::Deserialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct InvokeResponse {
         pub code: String, // TRANSACTION_RECEIVED
         pub transaction_hash: TransactionHash,
@@ -1369,7 +1987,6 @@ pub mod add_transaction {
 
     /// API response for a DECLARE transaction
     #[derive(Clone, Debug, serde::Deserialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct DeclareResponse {
         pub code: String, // TRANSACTION_RECEIVED
         pub transaction_hash: TransactionHash,
@@ -1378,7 +1995,6 @@ pub mod add_transaction {
 
     /// API response for a DEPLOY transaction
     #[derive(Clone, Debug, serde::Deserialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct DeployResponse {
         pub code: String, // TRANSACTION_RECEIVED
         pub transaction_hash: TransactionHash,
@@ -1387,7 +2003,6 @@ pub mod add_transaction {
 
     /// API response for a DEPLOY ACCOUNT transaction
     #[derive(Clone, Debug, serde::Deserialize, PartialEq, Eq)]
-    #[serde(deny_unknown_fields)]
     pub struct DeployAccountResponse {
         pub code: String, // TRANSACTION_RECEIVED
         pub transaction_hash: TransactionHash,
@@ -1461,7 +2076,7 @@ mod tests {
     use crate::reply::state_update::{
         DeclaredSierraClass, DeployedContract, ReplacedClass, StorageDiff,
     };
-    use crate::reply::transaction::L1HandlerTransaction;
+    use crate::reply::transaction::{L1HandlerTransaction, L2ToL1Message};
 
     /// The aim of these tests is to make sure pathfinder is still able to correctly
     /// deserialize replies from the mainnet sequencer when it still is using some
@@ -1526,6 +2141,162 @@ mod tests {
         }
     }
 
+    /// Replays the recorded gateway fixtures of all Starknet versions. Each fixture is also
+    /// replayed with an unknown field added to the reply and to its transactions and receipts,
+    /// as a new gateway version might do.
+    mod compatibility {
+        use serde::de::DeserializeOwned;
+        use starknet_gateway_test_fixtures::*;
+
+        use super::super::transaction::Transaction;
+        use super::super::{
+            Block, BlockSignature, MaybePendingBlock, StateUpdate, StateUpdateWithBlock,
+        };
+
+        fn add_unknown_fields(json: &mut serde_json::Value) {
+            let object = json.as_object_mut().expect("Fixture must be an object");
+            object.insert("field_from_the_future".to_owned(), "value".into());
+
+            for key in ["transaction", "block", "state_update"] {
+                if let Some(nested) = object.get_mut(key) {
+                    add_unknown_fields(nested);
+                }
+            }
+            for key in ["transactions", "transaction_receipts"] {
+                if let Some(items) = object.get_mut(key).and_then(|x| x.as_array_mut()) {
+                    items.iter_mut().for_each(add_unknown_fields);
+                }
+            }
+        }
+
+        fn replay<T: DeserializeOwned>(fixtures: &[&str]) {
+            for (i, fixture) in fixtures.iter().enumerate() {
+                serde_json::from_str::<T>(fixture)
+                    .unwrap_or_else(|e| panic!("Parsing fixture {i}: {e}"));
+
+                let mut json = serde_json::from_str::<serde_json::Value>(fixture).unwrap();
+                add_unknown_fields(&mut json);
+                serde_json::from_value::<T>(json)
+                    .unwrap_or_else(|e| panic!("Parsing fixture {i} with unknown fields: {e}"));
+            }
+        }
+
+        #[test]
+        fn blocks() {
+            replay::<MaybePendingBlock>(&[
+                old::block::NUMBER_192,
+                v0_8_2::block::GENESIS,
+                v0_8_2::block::NUMBER_1716,
+                v0_8_2::block::PENDING,
+                v0_9_0::block::GENESIS,
+                v0_9_0::block::NUMBER_1716,
+                v0_9_0::block::NUMBER_90000,
+                v0_9_0::block::NUMBER_156000,
+                v0_9_0::block::NUMBER_231579,
+                v0_9_0::block::PENDING,
+                integration::block::NUMBER_1,
+                integration::block::NUMBER_192844,
+                integration::block::NUMBER_216171,
+                integration::block::NUMBER_216591,
+                integration::block::NUMBER_228457,
+                integration::block::NUMBER_285915,
+                integration::block::PENDING,
+            ]);
+        }
+
+        #[test]
+        fn state_updates() {
+            replay::<StateUpdate>(&[
+                v0_11_0::state_update::GENESIS,
+                v0_11_0::state_update::NUMBER_315700,
+                v0_11_0::state_update::PENDING,
+                integration::state_update::NUMBER_283364,
+                integration::state_update::NUMBER_283428,
+            ]);
+            replay::<StateUpdateWithBlock>(&[v0_12_2::state_update::PENDING_WITH_BLOCK]);
+        }
+
+        #[test]
+        fn maybe_state_update_with_block() {
+            use super::super::MaybeStateUpdateWithBlock;
+
+            replay::<MaybeStateUpdateWithBlock>(&[
+                v0_11_0::state_update::PENDING,
+                v0_12_2::state_update::PENDING_WITH_BLOCK,
+            ]);
+
+            let reply = serde_json::from_str(v0_11_0::state_update::PENDING).unwrap();
+            assert!(matches!(reply, MaybeStateUpdateWithBlock::StateUpdate(_)));
+            let reply = serde_json::from_str(v0_12_2::state_update::PENDING_WITH_BLOCK).unwrap();
+            assert!(matches!(reply, MaybeStateUpdateWithBlock::WithBlock(_)));
+
+            // The error of the block is reported, instead of no variant matching.
+            let mut json: serde_json::Value =
+                serde_json::from_str(v0_12_2::state_update::PENDING_WITH_BLOCK).unwrap();
+            json["block"]["transactions"] = "invalid".into();
+            let error = serde_json::from_value::<MaybeStateUpdateWithBlock>(json).unwrap_err();
+            assert!(error.to_string().contains("expected a sequence"), "{error}");
+        }
+
+        #[test]
+        fn transactions() {
+            #[derive(serde::Deserialize)]
+            struct Wrapper {
+                #[allow(dead_code)]
+                transaction: Transaction,
+            }
+
+            replay::<Wrapper>(&[
+                v0_8_2::transaction::INVOKE,
+                v0_9_0::transaction::DECLARE,
+                v0_9_0::transaction::DEPLOY,
+                v0_9_0::transaction::INVOKE,
+                v0_11_0::transaction::declare::v1::BLOCK_463319,
+                v0_11_0::transaction::declare::v1::BLOCK_797215,
+                v0_11_0::transaction::declare::v2::BLOCK_797220,
+                v0_11_0::transaction::deploy::v0::GENESIS,
+                v0_11_0::transaction::deploy::v1::GENESIS_TESTNET2,
+                v0_11_0::transaction::deploy::v1::BLOCK_485004,
+                v0_11_0::transaction::deploy_account::v1::BLOCK_375919,
+                v0_11_0::transaction::deploy_account::v1::BLOCK_797K,
+                v0_11_0::transaction::invoke::v0::GENESIS,
+                v0_11_0::transaction::invoke::v0::BLOCK_854_IDX_96,
+                v0_11_0::transaction::invoke::v1::BLOCK_420K,
+                v0_11_0::transaction::invoke::v1::BLOCK_790K,
+                v0_11_0::transaction::l1_handler::v0::BLOCK_854_IDX_96,
+                v0_11_0::transaction::l1_handler::v0::BLOCK_1564,
+                v0_11_0::transaction::l1_handler::v0::BLOCK_272866,
+                v0_11_0::transaction::l1_handler::v0::BLOCK_790K,
+            ]);
+        }
+
+        #[test]
+        fn signatures() {
+            replay::<BlockSignature>(&[v0_12_2::signature::BLOCK_350000]);
+        }
+
+        #[test]
+        fn version_gated_fields() {
+            use pathfinder_common::{GasPrice, SequencerAddress, StarknetVersion};
+
+            let mut block = serde_json::from_str::<Block>(v0_9_0::block::NUMBER_1716).unwrap();
+            block.gas_price = None;
+            block.sequencer_address = None;
+
+            // Blocks without a version predate these fields.
+            block.starknet_version = StarknetVersion::default();
+            assert_eq!(block.gas_price().unwrap(), GasPrice::ZERO);
+            assert_eq!(block.sequencer_address().unwrap(), SequencerAddress::ZERO);
+
+            block.starknet_version = StarknetVersion::new(0, 12, 0);
+            block.gas_price().unwrap_err();
+            block.sequencer_address().unwrap_err();
+
+            block.gas_price = Some(GasPrice(1));
+            assert_eq!(block.gas_price().unwrap(), GasPrice(1));
+        }
+    }
+
     #[test]
     fn from_state_update() {
         use pathfinder_common::macro_prelude::*;
@@ -1723,6 +2494,34 @@ mod tests {
         assert_eq!(message_hash, expected);
     }
 
+    #[test]
+    fn l2_to_l1_message_hash() {
+        use pathfinder_common::macro_prelude::*;
+        use pathfinder_common::EthereumAddress;
+        use primitive_types::H160;
+
+        let message = L2ToL1Message {
+            from_address: contract_address!(
+                "0x73314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82"
+            ),
+            payload: vec![
+                l2_to_l1_message_payload_elem!("0x0"),
+                l2_to_l1_message_payload_elem!("0xdeadbeef"),
+                l2_to_l1_message_payload_elem!("0x11e14e1039c000"),
+                l2_to_l1_message_payload_elem!("0x0"),
+            ],
+            to_address: EthereumAddress(
+                H160::from_str("ae0ee0a63a2ce6baeeffe56e7714fb4efe48d419").unwrap(),
+            ),
+        };
+
+        let expected =
+            H256::from_str("54642b4f39c7eeb178b2bcb70288cc066613aba66cade3c2b01cb284bc14a201")
+                .unwrap();
+
+        assert_eq!(message.calculate_message_hash(), expected);
+    }
+
     mod block_signature {
         use pathfinder_common::{
             block_commitment_signature_elem, block_hash, state_diff_commitment, BlockNumber,
@@ -192,11 +192,14 @@ pub mod contract {
 pub mod add_transaction {
     use super::contract::{EntryPointType, SelectorAndFunctionIndex, SelectorAndOffset};
     use super::{CallParam, ContractAddress, Fee, TransactionSignatureElem};
+    use crate::reply::transaction::{DataAvailabilityMode, ResourceBounds};
     use pathfinder_common::{
-        CasmHash, ClassHash, ContractAddressSalt, EntryPoint, TransactionNonce, TransactionVersion,
+        AccountDeploymentDataElem, CasmHash, ClassHash, ContractAddressSalt, EntryPoint,
+        PaymasterDataElem, Tip, TransactionNonce, TransactionVersion,
     };
     use pathfinder_serde::{
-        CallParamAsDecimalStr, TransactionSignatureElemAsDecimalStr, TransactionVersionAsHexStr,
+        CallParamAsDecimalStr, TipAsHexStr, TransactionSignatureElemAsDecimalStr,
+        TransactionVersionAsHexStr,
     };
     use serde_with::serde_as;
     use std::collections::HashMap;
@@ -230,6 +233,21 @@ pub mod add_transaction {
         pub abi: String,
     }
 
+    /// The properties only v3 transactions have.
+    #[serde_as]
+    #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+    pub struct V3Properties {
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+        // NOTE: this is optional because deploy account transactions do not have it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub account_deployment_data: Option<Vec<AccountDeploymentDataElem>>,
+    }
+
     /// Account deployment transaction details.
     #[serde_as]
     #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -247,6 +265,10 @@ pub mod add_transaction {
         pub contract_address_salt: ContractAddressSalt,
         #[serde_as(as = "Vec<CallParamAsDecimalStr>")]
         pub constructor_calldata: Vec<CallParam>,
+
+        // Required for v3 transactions
+        #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+        pub v3_properties: Option<V3Properties>,
     }
 
     /// Invoke contract transaction details.
@@ -271,6 +293,10 @@ pub mod add_transaction {
         pub entry_point_selector: Option<EntryPoint>,
         #[serde_as(as = "Vec<CallParamAsDecimalStr>")]
         pub calldata: Vec<CallParam>,
+
+        // Required for v3 transactions
+        #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+        pub v3_properties: Option<V3Properties>,
     }
 
     /// Declare transaction details.
@@ -293,6 +319,10 @@ pub mod add_transaction {
         // Required for declare v2 transactions
         #[serde(default, skip_serializing_if = "Option::is_none")]
         pub compiled_class_hash: Option<CasmHash>,
+
+        // Required for v3 transactions
+        #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+        pub v3_properties: Option<V3Properties>,
     }
 
     /// Add transaction API operation.
@@ -1,18 +1,21 @@
 //! Calculate transaction hashes.
 
 use crate::reply::transaction::{
-    DeclareTransaction, DeclareTransactionV0V1, DeclareTransactionV2, DeployAccountTransaction,
-    DeployTransaction, InvokeTransaction, InvokeTransactionV0, InvokeTransactionV1,
-    L1HandlerTransaction, Transaction,
+    DataAvailabilityMode, DeclareTransaction, DeclareTransactionV0V1, DeclareTransactionV2,
+    DeclareTransactionV3, DeployAccountTransaction, DeployAccountTransactionV0V1,
+    DeployAccountTransactionV3, DeployTransaction, InvokeTransaction, InvokeTransactionV0,
+    InvokeTransactionV1, InvokeTransactionV3, L1HandlerTransaction, ResourceBound, ResourceBounds,
+    Transaction,
 };
 use pathfinder_common::{
-    BlockNumber, CasmHash, ClassHash, ContractAddress, EntryPoint, Fee, TransactionHash,
-    TransactionNonce, TransactionVersion,
+    BlockNumber, CasmHash, ClassHash, ContractAddress, EntryPoint, Fee, PaymasterDataElem, Tip,
+    TransactionHash, TransactionNonce, TransactionVersion,
 };
 
 use crate::class_hash::truncated_keccak;
 use pathfinder_common::ChainId;
-use pathfinder_crypto::{hash::HashChain, Felt};
+use pathfinder_crypto::hash::{HashChain, PoseidonHasher};
+use pathfinder_crypto::Felt;
 use sha3::{Digest, Keccak256};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -75,10 +78,17 @@ pub fn compute_transaction_hash(txn: &Transaction, chain_id: ChainId) -> Transac
         Transaction::Declare(DeclareTransaction::V0(txn)) => compute_declare_v0_hash(txn, chain_id),
         Transaction::Declare(DeclareTransaction::V1(txn)) => compute_declare_v1_hash(txn, chain_id),
         Transaction::Declare(DeclareTransaction::V2(txn)) => compute_declare_v2_hash(txn, chain_id),
+        Transaction::Declare(DeclareTransaction::V3(txn)) => compute_declare_v3_hash(txn, chain_id),
         Transaction::Deploy(txn) => compute_deploy_hash(txn, chain_id),
-        Transaction::DeployAccount(txn) => compute_deploy_account_hash(txn, chain_id),
+        Transaction::DeployAccount(DeployAccountTransaction::V0V1(txn)) => {
+            compute_deploy_account_hash(txn, chain_id)
+        }
+        Transaction::DeployAccount(DeployAccountTransaction::V3(txn)) => {
+            compute_deploy_account_v3_hash(txn, chain_id)
+        }
         Transaction::Invoke(InvokeTransaction::V0(txn)) => compute_invoke_v0_hash(txn, chain_id),
         Transaction::Invoke(InvokeTransaction::V1(txn)) => compute_invoke_v1_hash(txn, chain_id),
+        Transaction::Invoke(InvokeTransaction::V3(txn)) => compute_invoke_v3_hash(txn, chain_id),
         Transaction::L1Handler(txn) => compute_l1_handler_hash(txn, chain_id),
     }
 }
@@ -224,7 +234,7 @@ fn compute_deploy_hash(txn: &DeployTransaction, chain_id: ChainId) -> Transactio
 ///
 /// Where `h` is [Pedersen hash](https://docs.starknet.io/documentation/architecture_and_concepts/Hashing/hash-functions/#pedersen_hash)
 fn compute_deploy_account_hash(
-    txn: &DeployAccountTransaction,
+    txn: &DeployAccountTransactionV0V1,
     chain_id: ChainId,
 ) -> TransactionHash {
     compute_txn_hash(
@@ -375,6 +385,170 @@ fn compute_l1_handler_hash(txn: &L1HandlerTransaction, chain_id: ChainId) -> Tra
     }
 }
 
+/// The fields which all v3 transactions commit to, replacing the `max_fee` of earlier versions
+/// with resource bounds.
+struct V3Fields<'a> {
+    /// The contract address of deploy account transactions.
+    sender_address: ContractAddress,
+    nonce: TransactionNonce,
+    tip: Tip,
+    resource_bounds: ResourceBounds,
+    paymaster_data: &'a [PaymasterDataElem],
+    nonce_data_availability_mode: DataAvailabilityMode,
+    fee_data_availability_mode: DataAvailabilityMode,
+}
+
+/// Computes invoke v3 transaction hash based on [SNIP-8](https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md):
+/// ```text=
+/// invoke_v3_tx_hash = h("invoke", version, sender_address,
+///     h(tip, l1_gas_bounds, l2_gas_bounds), h(paymaster_data), chain_id, nonce,
+///     data_availability_modes, h(account_deployment_data), h(calldata))
+/// ```
+///
+/// Where `h` is [Poseidon hash](https://docs.starknet.io/documentation/architecture_and_concepts/Cryptography/hash-functions/#poseidon_hash)
+fn compute_invoke_v3_hash(txn: &InvokeTransactionV3, chain_id: ChainId) -> TransactionHash {
+    compute_v3_txn_hash(
+        b"invoke",
+        &V3Fields {
+            sender_address: txn.sender_address,
+            nonce: txn.nonce,
+            tip: txn.tip,
+            resource_bounds: txn.resource_bounds,
+            paymaster_data: &txn.paymaster_data,
+            nonce_data_availability_mode: txn.nonce_data_availability_mode,
+            fee_data_availability_mode: txn.fee_data_availability_mode,
+        },
+        chain_id,
+        &[
+            poseidon_hash_felts(txn.account_deployment_data.iter().map(|x| x.0)),
+            poseidon_hash_felts(txn.calldata.iter().map(|x| x.0)),
+        ],
+    )
+}
+
+/// Computes declare v3 transaction hash based on [SNIP-8](https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md):
+/// ```text=
+/// declare_v3_tx_hash = h("declare", version, sender_address,
+///     h(tip, l1_gas_bounds, l2_gas_bounds), h(paymaster_data), chain_id, nonce,
+///     data_availability_modes, h(account_deployment_data), class_hash, compiled_class_hash)
+/// ```
+///
+/// Where `h` is [Poseidon hash](https://docs.starknet.io/documentation/architecture_and_concepts/Cryptography/hash-functions/#poseidon_hash)
+fn compute_declare_v3_hash(txn: &DeclareTransactionV3, chain_id: ChainId) -> TransactionHash {
+    compute_v3_txn_hash(
+        b"declare",
+        &V3Fields {
+            sender_address: txn.sender_address,
+            nonce: txn.nonce,
+            tip: txn.tip,
+            resource_bounds: txn.resource_bounds,
+            paymaster_data: &txn.paymaster_data,
+            nonce_data_availability_mode: txn.nonce_data_availability_mode,
+            fee_data_availability_mode: txn.fee_data_availability_mode,
+        },
+        chain_id,
+        &[
+            poseidon_hash_felts(txn.account_deployment_data.iter().map(|x| x.0)),
+            txn.class_hash.0,
+            txn.compiled_class_hash.0,
+        ],
+    )
+}
+
+/// Computes deploy account v3 transaction hash based on [SNIP-8](https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md):
+/// ```text=
+/// deploy_account_v3_tx_hash = h("deploy_account", version, contract_address,
+///     h(tip, l1_gas_bounds, l2_gas_bounds), h(paymaster_data), chain_id, nonce,
+///     data_availability_modes, h(constructor_calldata), class_hash, contract_address_salt)
+/// ```
+///
+/// Where `h` is [Poseidon hash](https://docs.starknet.io/documentation/architecture_and_concepts/Cryptography/hash-functions/#poseidon_hash)
+fn compute_deploy_account_v3_hash(
+    txn: &DeployAccountTransactionV3,
+    chain_id: ChainId,
+) -> TransactionHash {
+    compute_v3_txn_hash(
+        b"deploy_account",
+        &V3Fields {
+            sender_address: txn.sender_address,
+            nonce: txn.nonce,
+            tip: txn.tip,
+            resource_bounds: txn.resource_bounds,
+            paymaster_data: &txn.paymaster_data,
+            nonce_data_availability_mode: txn.nonce_data_availability_mode,
+            fee_data_availability_mode: txn.fee_data_availability_mode,
+        },
+        chain_id,
+        &[
+            poseidon_hash_felts(txn.constructor_calldata.iter().map(|x| x.0)),
+            txn.class_hash.0,
+            txn.contract_address_salt.0,
+        ],
+    )
+}
+
+/// _Generic_ compute transaction hash for v3 transactions, which are followed by the
+/// type specific `tail`.
+fn compute_v3_txn_hash(
+    prefix: &[u8],
+    fields: &V3Fields<'_>,
+    chain_id: ChainId,
+    tail: &[Felt],
+) -> TransactionHash {
+    let fee_fields_hash = poseidon_hash_felts([
+        Felt::from(fields.tip.0),
+        encode_resource_bound(b"L1_GAS", fields.resource_bounds.l1_gas),
+        encode_resource_bound(b"L2_GAS", fields.resource_bounds.l2_gas),
+    ]);
+    let data_availability_modes = (data_availability_mode(fields.nonce_data_availability_mode)
+        << 32)
+        + data_availability_mode(fields.fee_data_availability_mode);
+
+    let mut h = PoseidonHasher::new();
+    h.write(
+        Felt::from_be_slice(prefix)
+            .expect("prefix is convertible")
+            .into(),
+    );
+    h.write(Felt::from_u64(3).into());
+    h.write((*fields.sender_address.get()).into());
+    h.write(fee_fields_hash.into());
+    h.write(poseidon_hash_felts(fields.paymaster_data.iter().map(|x| x.0)).into());
+    h.write(chain_id.0.into());
+    h.write(fields.nonce.0.into());
+    h.write(data_availability_modes.into());
+    for felt in tail {
+        h.write((*felt).into());
+    }
+
+    TransactionHash(h.finish().into())
+}
+
+/// Encodes the bound as `0 | resource name (7 bytes) | max amount (8 bytes) | max price per unit
+/// (16 bytes)`.
+fn encode_resource_bound(name: &[u8; 6], bound: ResourceBound) -> Felt {
+    let mut bytes = [0u8; 32];
+    bytes[2..8].copy_from_slice(name);
+    bytes[8..16].copy_from_slice(&bound.max_amount.0.to_be_bytes());
+    bytes[16..].copy_from_slice(&bound.max_price_per_unit.0.to_be_bytes());
+    Felt::from_be_slice(&bytes).expect("leading zero byte keeps the bound in range")
+}
+
+fn data_availability_mode(mode: DataAvailabilityMode) -> u64 {
+    match mode {
+        DataAvailabilityMode::L1 => 0,
+        DataAvailabilityMode::L2 => 1,
+    }
+}
+
+fn poseidon_hash_felts(felts: impl IntoIterator<Item = Felt>) -> Felt {
+    let mut h = PoseidonHasher::new();
+    for felt in felts {
+        h.write(felt.into());
+    }
+    h.finish().into()
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum NonceOrClassHash {
     Nonce(TransactionNonce),
@@ -580,4 +754,242 @@ mod tests {
             ))
         }
     }
+
+    mod v3 {
+        use super::super::*;
+        use pathfinder_common::macro_prelude::*;
+        use pathfinder_common::{ChainId, ResourceAmount, ResourcePricePerUnit};
+
+        fn invoke() -> InvokeTransactionV3 {
+            InvokeTransactionV3 {
+                nonce: transaction_nonce!("0x5"),
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L1,
+                resource_bounds: ResourceBounds {
+                    l1_gas: ResourceBound {
+                        max_amount: ResourceAmount(0x186a0),
+                        max_price_per_unit: ResourcePricePerUnit(0x5af3107a4000),
+                    },
+                    l2_gas: ResourceBound::default(),
+                },
+                tip: Tip(0),
+                paymaster_data: vec![],
+                sender_address: contract_address!("0x1234"),
+                signature: vec![],
+                transaction_hash: transaction_hash!("0x0"),
+                calldata: vec![call_param!("0x1"), call_param!("0x2")],
+                account_deployment_data: vec![],
+            }
+        }
+
+        #[test]
+        fn resource_bound_encoding() {
+            let bound = ResourceBound {
+                max_amount: ResourceAmount(1),
+                max_price_per_unit: ResourcePricePerUnit(2),
+            };
+
+            assert_eq!(
+                encode_resource_bound(b"L1_GAS", bound),
+                felt!("0x4c315f474153000000000000000100000000000000000000000000000002")
+            );
+        }
+
+        #[test]
+        fn every_field_is_committed_to() {
+            let hash = compute_invoke_v3_hash(&invoke(), ChainId::TESTNET);
+
+            let changed = [
+                InvokeTransactionV3 {
+                    tip: Tip(1),
+                    ..invoke()
+                },
+                InvokeTransactionV3 {
+                    fee_data_availability_mode: DataAvailabilityMode::L2,
+                    ..invoke()
+                },
+                InvokeTransactionV3 {
+                    nonce_data_availability_mode: DataAvailabilityMode::L2,
+                    ..invoke()
+                },
+                InvokeTransactionV3 {
+                    resource_bounds: ResourceBounds {
+                        l2_gas: ResourceBound {
+                            max_amount: ResourceAmount(1),
+                            max_price_per_unit: ResourcePricePerUnit(0),
+                        },
+                        ..invoke().resource_bounds
+                    },
+                    ..invoke()
+                },
+                InvokeTransactionV3 {
+                    paymaster_data: vec![paymaster_data_elem!("0x1")],
+                    ..invoke()
+                },
+                InvokeTransactionV3 {
+                    account_deployment_data: vec![account_deployment_data_elem!("0x1")],
+                    ..invoke()
+                },
+            ];
+            for changed in changed {
+                assert_ne!(compute_invoke_v3_hash(&changed, ChainId::TESTNET), hash);
+            }
+
+            assert_ne!(compute_invoke_v3_hash(&invoke(), ChainId::MAINNET), hash);
+        }
+
+        /// The fields shared by all v3 transactions of [invoke], as encoded by SNIP-8.
+        fn common_fields() -> [Felt; 6] {
+            let l1_gas = felt!("0x4c315f47415300000000000186a0000000000000000000005af3107a4000");
+            let l2_gas = felt!("0x4c325f474153000000000000000000000000000000000000000000000000");
+
+            [
+                // version
+                felt!("0x3"),
+                // sender address
+                felt!("0x1234"),
+                h(&[felt!("0x0"), l1_gas, l2_gas]),
+                // paymaster data
+                h(&[]),
+                ChainId::TESTNET.0,
+                // nonce
+                felt!("0x5"),
+            ]
+        }
+
+        fn h(felts: &[Felt]) -> Felt {
+            let felts = felts.iter().copied().map(Into::into).collect::<Vec<_>>();
+            pathfinder_crypto::hash::poseidon_hash_many(&felts).into()
+        }
+
+        /// The hashes are derived from the field list of SNIP-8 instead of the shared
+        /// [compute_v3_txn_hash].
+        mod snip8 {
+            use super::*;
+
+            #[test]
+            fn invoke_v3() {
+                let expected = h(&[
+                    &[felt!("0x696e766f6b65")][..],
+                    &common_fields(),
+                    &[
+                        // nonce data availability mode is L2
+                        felt!("0x100000000"),
+                        // account deployment data
+                        h(&[]),
+                        // calldata
+                        h(&[felt!("0x1"), felt!("0x2")]),
+                    ],
+                ]
+                .concat());
+
+                let txn = InvokeTransactionV3 {
+                    nonce_data_availability_mode: DataAvailabilityMode::L2,
+                    ..invoke()
+                };
+
+                assert_eq!(
+                    compute_invoke_v3_hash(&txn, ChainId::TESTNET),
+                    TransactionHash(expected)
+                );
+            }
+
+            #[test]
+            fn declare_v3() {
+                let expected = h(&[
+                    &[felt!("0x6465636c617265")][..],
+                    &common_fields(),
+                    &[
+                        // fee data availability mode is L2
+                        felt!("0x1"),
+                        // account deployment data
+                        h(&[felt!("0x7")]),
+                        // class hash
+                        felt!("0xabc"),
+                        // compiled class hash
+                        felt!("0xdef"),
+                    ],
+                ]
+                .concat());
+
+                let invoke = invoke();
+                let txn = DeclareTransactionV3 {
+                    class_hash: class_hash!("0xabc"),
+                    nonce: invoke.nonce,
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L2,
+                    resource_bounds: invoke.resource_bounds,
+                    tip: invoke.tip,
+                    paymaster_data: vec![],
+                    sender_address: invoke.sender_address,
+                    signature: vec![],
+                    transaction_hash: transaction_hash!("0x0"),
+                    compiled_class_hash: casm_hash!("0xdef"),
+                    account_deployment_data: vec![account_deployment_data_elem!("0x7")],
+                };
+
+                assert_eq!(
+                    compute_declare_v3_hash(&txn, ChainId::TESTNET),
+                    TransactionHash(expected)
+                );
+            }
+
+            #[test]
+            fn deploy_account_v3() {
+                let expected = h(&[
+                    &[felt!("0x6465706c6f795f6163636f756e74")][..],
+                    &common_fields(),
+                    &[
+                        // data availability modes are L1
+                        felt!("0x0"),
+                        // constructor calldata
+                        h(&[felt!("0x9")]),
+                        // class hash
+                        felt!("0xabc"),
+                        // contract address salt
+                        felt!("0x55"),
+                    ],
+                ]
+                .concat());
+
+                let invoke = invoke();
+                let txn = DeployAccountTransactionV3 {
+                    nonce: invoke.nonce,
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    resource_bounds: invoke.resource_bounds,
+                    tip: invoke.tip,
+                    paymaster_data: vec![],
+                    sender_address: invoke.sender_address,
+                    signature: vec![],
+                    transaction_hash: transaction_hash!("0x0"),
+                    version: TransactionVersion::THREE,
+                    contract_address_salt: contract_address_salt!("0x55"),
+                    constructor_calldata: vec![call_param!("0x9")],
+                    class_hash: class_hash!("0xabc"),
+                };
+
+                assert_eq!(
+                    compute_deploy_account_v3_hash(&txn, ChainId::TESTNET),
+                    TransactionHash(expected)
+                );
+            }
+        }
+
+        #[test]
+        fn verification() {
+            let mut txn = invoke();
+            txn.transaction_hash = compute_invoke_v3_hash(&txn, ChainId::TESTNET);
+            let txn = Transaction::Invoke(InvokeTransaction::V3(txn));
+
+            assert_eq!(
+                verify(&txn, ChainId::TESTNET, BlockNumber::new_or_panic(1)),
+                VerifyResult::Match
+            );
+            assert_matches::assert_matches!(
+                verify(&txn, ChainId::MAINNET, BlockNumber::new_or_panic(1)),
+                VerifyResult::Mismatch(_)
+            );
+        }
+    }
 }
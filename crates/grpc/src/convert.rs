@@ -0,0 +1,113 @@
+//! Conversions from pathfinder's types into their [protobuf](crate::proto) counterparts.
+use pathfinder_common::state_update::ContractClassUpdate;
+use pathfinder_common::{BlockHeader, BlockNumber, StateUpdate};
+use pathfinder_crypto::Felt;
+use starknet_gateway_types::reply::transaction as gateway;
+
+use crate::proto;
+
+pub(crate) fn felt(felt: &Felt) -> Vec<u8> {
+    felt.as_be_bytes().to_vec()
+}
+
+pub(crate) fn header(header: BlockHeader) -> proto::BlockHeader {
+    proto::BlockHeader {
+        number: header.number.get(),
+        hash: felt(&header.hash.0),
+        parent_hash: felt(&header.parent_hash.0),
+        timestamp: header.timestamp.get(),
+        gas_price: header.gas_price.0.to_be_bytes().to_vec(),
+        sequencer_address: felt(&header.sequencer_address.0),
+        starknet_version: header.starknet_version.take_inner(),
+        state_commitment: felt(&header.state_commitment.0),
+        transaction_count: header.transaction_count as u64,
+        event_count: header.event_count as u64,
+    }
+}
+
+pub(crate) fn transaction(
+    transaction: &gateway::Transaction,
+    receipt: &gateway::Receipt,
+) -> proto::Transaction {
+    let kind = match transaction {
+        gateway::Transaction::Declare(_) => proto::TransactionType::Declare,
+        gateway::Transaction::Deploy(_) => proto::TransactionType::Deploy,
+        gateway::Transaction::DeployAccount(_) => proto::TransactionType::DeployAccount,
+        gateway::Transaction::Invoke(_) => proto::TransactionType::Invoke,
+        gateway::Transaction::L1Handler(_) => proto::TransactionType::L1Handler,
+    };
+
+    proto::Transaction {
+        hash: felt(&transaction.hash().0),
+        r#type: kind.into(),
+        contract_address: felt(&transaction.contract_address().0),
+        actual_fee: receipt.actual_fee.map(|fee| felt(&fee.0)),
+        reverted: receipt.execution_status == gateway::ExecutionStatus::Reverted,
+        event_count: receipt.events.len() as u32,
+    }
+}
+
+pub(crate) fn state_update(number: BlockNumber, state_update: StateUpdate) -> proto::StateUpdate {
+    let mut contracts = state_update
+        .contract_updates
+        .into_iter()
+        .map(|(address, update)| proto::ContractDiff {
+            address: felt(&address.0),
+            storage: storage(update.storage),
+            nonce: update.nonce.map(|nonce| felt(&nonce.0)),
+            class_hash: update
+                .class
+                .as_ref()
+                .map(|class| felt(&class.class_hash().0)),
+            class_replaced: matches!(update.class, Some(ContractClassUpdate::Replace(_))),
+        })
+        .collect::<Vec<_>>();
+    contracts.extend(
+        state_update
+            .system_contract_updates
+            .into_iter()
+            .map(|(address, update)| proto::ContractDiff {
+                address: felt(&address.0),
+                storage: storage(update.storage),
+                ..Default::default()
+            }),
+    );
+
+    proto::StateUpdate {
+        block_number: number.get(),
+        block_hash: felt(&state_update.block_hash.0),
+        state_commitment: felt(&state_update.state_commitment.0),
+        parent_state_commitment: felt(&state_update.parent_state_commitment.0),
+        contracts,
+        declared_cairo_classes: state_update
+            .declared_cairo_classes
+            .iter()
+            .map(|class| felt(&class.0))
+            .collect(),
+        declared_sierra_classes: state_update
+            .declared_sierra_classes
+            .iter()
+            .map(|(sierra, casm)| proto::DeclaredSierraClass {
+                class_hash: felt(&sierra.0),
+                compiled_class_hash: felt(&casm.0),
+            })
+            .collect(),
+    }
+}
+
+fn storage(
+    storage: impl IntoIterator<
+        Item = (
+            pathfinder_common::StorageAddress,
+            pathfinder_common::StorageValue,
+        ),
+    >,
+) -> Vec<proto::StorageEntry> {
+    storage
+        .into_iter()
+        .map(|(key, value)| proto::StorageEntry {
+            key: felt(&key.0),
+            value: felt(&value.0),
+        })
+        .collect()
+}
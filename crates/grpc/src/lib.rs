@@ -0,0 +1,405 @@
+#![deny(rust_2018_idioms)]
+
+//! A gRPC server streaming historical block data, for indexers which need higher throughput than
+//! the JSON-RPC API provides.
+//!
+//! The API is defined in `proto/read.proto`. Streams are read from storage on a blocking task
+//! and sent through a bounded channel, so slow clients apply backpressure instead of buffering
+//! data in memory.
+use std::net::SocketAddr;
+
+use anyhow::Context;
+use pathfinder_common::BlockNumber;
+use pathfinder_storage::{Storage, Transaction};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+mod convert;
+
+pub mod proto {
+    tonic::include_proto!("pathfinder.v1");
+}
+
+use proto::read_api_server::{ReadApi, ReadApiServer};
+
+/// The number of messages buffered per stream.
+const STREAM_BUFFER: usize = 64;
+/// The number of blocks read per database transaction, which limits how long a stream keeps
+/// the database snapshot of a transaction alive.
+const BLOCKS_PER_TRANSACTION: u64 = 1000;
+
+/// Starts the gRPC server on `address` and returns its handle and the address it listens on.
+pub async fn serve(
+    storage: Storage,
+    address: SocketAddr,
+) -> anyhow::Result<(JoinHandle<()>, SocketAddr)> {
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .context("Binding gRPC server address")?;
+    let local_addr = listener
+        .local_addr()
+        .context("Getting gRPC server address")?;
+    let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+
+    let handle = tokio::spawn(async move {
+        let result = tonic::transport::Server::builder()
+            .add_service(ReadApiServer::new(ReadService { storage }))
+            .serve_with_incoming(incoming)
+            .await;
+        if let Err(error) = result {
+            tracing::error!(%error, "gRPC server stopped");
+        }
+    });
+
+    Ok((handle, local_addr))
+}
+
+struct ReadService {
+    storage: Storage,
+}
+
+type Stream<T> = ReceiverStream<Result<T, Status>>;
+
+impl ReadService {
+    /// Streams the items `read` returns for each block of the range, in order.
+    ///
+    /// `read` returns [None] if the block is not available, which ends the stream with
+    /// [NOT_FOUND](tonic::Code::NotFound).
+    fn stream<T, F>(&self, range: proto::BlockRange, read: F) -> Result<Response<Stream<T>>, Status>
+    where
+        T: Send + 'static,
+        F: Fn(&Transaction<'_>, BlockNumber) -> anyhow::Result<Option<Vec<T>>> + Send + 'static,
+    {
+        let from = BlockNumber::new(range.from)
+            .ok_or_else(|| Status::invalid_argument("Invalid from block"))?;
+        let to = BlockNumber::new(range.to)
+            .ok_or_else(|| Status::invalid_argument("Invalid to block"))?;
+        if from > to {
+            return Err(Status::invalid_argument("The range must not be empty"));
+        }
+
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let storage = self.storage.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let result = (|| -> Result<(), Status> {
+                let mut db = storage.connection().map_err(internal)?;
+                let mut number = from;
+
+                loop {
+                    let tx = db.transaction().map_err(internal)?;
+                    let chunk_end = std::cmp::min(to, number + (BLOCKS_PER_TRANSACTION - 1));
+
+                    loop {
+                        let items = read(&tx, number).map_err(internal)?.ok_or_else(|| {
+                            Status::not_found(format!("Block {number} not found"))
+                        })?;
+                        for item in items {
+                            if sender.blocking_send(Ok(item)).is_err() {
+                                // The client is gone.
+                                return Ok(());
+                            }
+                        }
+
+                        if number == chunk_end {
+                            break;
+                        }
+                        number += 1;
+                    }
+
+                    if number == to {
+                        return Ok(());
+                    }
+                    number += 1;
+                }
+            })();
+
+            if let Err(status) = result {
+                let _ = sender.blocking_send(Err(status));
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+fn internal(error: anyhow::Error) -> Status {
+    tracing::warn!(reason=?error, "gRPC request failed");
+    Status::internal("Internal error")
+}
+
+fn read_block(tx: &Transaction<'_>, number: BlockNumber) -> anyhow::Result<Option<proto::Block>> {
+    let Some(header) = tx
+        .block_header(number.into())
+        .context("Querying block header")?
+    else {
+        return Ok(None);
+    };
+    let transactions = tx
+        .transaction_data_for_block(number.into())
+        .context("Querying transactions")?
+        .unwrap_or_default();
+
+    Ok(Some(proto::Block {
+        header: Some(convert::header(header)),
+        transactions: transactions
+            .iter()
+            .map(|(transaction, receipt)| convert::transaction(transaction, receipt))
+            .collect(),
+    }))
+}
+
+fn read_events(
+    tx: &Transaction<'_>,
+    number: BlockNumber,
+) -> anyhow::Result<Option<Vec<proto::Event>>> {
+    let Some(block_hash) = tx
+        .block_id(number.into())
+        .context("Querying block hash")?
+        .map(|(_, hash)| hash)
+    else {
+        return Ok(None);
+    };
+    let transactions = tx
+        .transaction_data_for_block(number.into())
+        .context("Querying transactions")?
+        .unwrap_or_default();
+
+    let events = transactions
+        .into_iter()
+        .flat_map(|(_, receipt)| {
+            let transaction_hash = convert::felt(&receipt.transaction_hash.0);
+            let transaction_index = receipt.transaction_index.get();
+            receipt
+                .events
+                .into_iter()
+                .enumerate()
+                .map(move |(index, event)| proto::Event {
+                    block_number: number.get(),
+                    block_hash: convert::felt(&block_hash.0),
+                    transaction_hash: transaction_hash.clone(),
+                    transaction_index,
+                    event_index: index as u64,
+                    from_address: convert::felt(&event.from_address.0),
+                    keys: event.keys.iter().map(|key| convert::felt(&key.0)).collect(),
+                    data: event
+                        .data
+                        .iter()
+                        .map(|data| convert::felt(&data.0))
+                        .collect(),
+                })
+        })
+        .collect();
+
+    Ok(Some(events))
+}
+
+/// Reads the complete [record](proto::BlockRecord) of a block, or [None] if the block is not
+/// available.
+pub fn read_block_record(
+    tx: &Transaction<'_>,
+    number: BlockNumber,
+) -> anyhow::Result<Option<proto::BlockRecord>> {
+    let Some(block) = read_block(tx, number)? else {
+        return Ok(None);
+    };
+    let events = read_events(tx, number)?.unwrap_or_default();
+    let state_update = tx
+        .state_update(number.into())
+        .context("Querying state update")?
+        .context("State update is missing")?;
+
+    Ok(Some(proto::BlockRecord {
+        block: Some(block),
+        events,
+        state_update: Some(convert::state_update(number, state_update)),
+    }))
+}
+
+#[tonic::async_trait]
+impl ReadApi for ReadService {
+    async fn get_block(
+        &self,
+        request: Request<proto::GetBlockRequest>,
+    ) -> Result<Response<proto::Block>, Status> {
+        let number = BlockNumber::new(request.into_inner().number)
+            .ok_or_else(|| Status::invalid_argument("Invalid block number"))?;
+        let storage = self.storage.clone();
+
+        let block = tokio::task::spawn_blocking(move || {
+            let mut db = storage.connection()?;
+            let tx = db.transaction()?;
+            read_block(&tx, number)
+        })
+        .await
+        .map_err(|e| internal(e.into()))?
+        .map_err(internal)?
+        .ok_or_else(|| Status::not_found(format!("Block {number} not found")))?;
+
+        Ok(Response::new(block))
+    }
+
+    type StreamBlocksStream = Stream<proto::Block>;
+
+    async fn stream_blocks(
+        &self,
+        request: Request<proto::BlockRange>,
+    ) -> Result<Response<Self::StreamBlocksStream>, Status> {
+        self.stream(request.into_inner(), |tx, number| {
+            Ok(read_block(tx, number)?.map(|block| vec![block]))
+        })
+    }
+
+    type StreamStateUpdatesStream = Stream<proto::StateUpdate>;
+
+    async fn stream_state_updates(
+        &self,
+        request: Request<proto::BlockRange>,
+    ) -> Result<Response<Self::StreamStateUpdatesStream>, Status> {
+        self.stream(request.into_inner(), |tx, number| {
+            let state_update = tx
+                .state_update(number.into())
+                .context("Querying state update")?;
+            Ok(state_update.map(|update| vec![convert::state_update(number, update)]))
+        })
+    }
+
+    type StreamEventsStream = Stream<proto::Event>;
+
+    async fn stream_events(
+        &self,
+        request: Request<proto::EventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let request = request.into_inner();
+        let range = request
+            .range
+            .ok_or_else(|| Status::invalid_argument("Missing range"))?;
+        let from_address = request.from_address;
+
+        self.stream(range, move |tx, number| {
+            let events = read_events(tx, number)?.map(|events| {
+                events
+                    .into_iter()
+                    .filter(|event| {
+                        from_address
+                            .as_ref()
+                            .map_or(true, |address| &event.from_address == address)
+                    })
+                    .collect()
+            });
+            Ok(events)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_storage::test_utils;
+    use tokio_stream::StreamExt;
+
+    use super::proto::read_api_client::ReadApiClient;
+    use super::*;
+
+    async fn setup() -> (
+        ReadApiClient<tonic::transport::Channel>,
+        test_utils::TestData,
+    ) {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let (_, address) = serve(storage, ([127, 0, 0, 1], 0).into()).await.unwrap();
+        let client = ReadApiClient::connect(format!("http://{address}"))
+            .await
+            .unwrap();
+
+        (client, test_data)
+    }
+
+    fn full_range(test_data: &test_utils::TestData) -> proto::BlockRange {
+        proto::BlockRange {
+            from: 0,
+            to: test_data.headers.last().unwrap().number.get(),
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_blocks() {
+        let (mut client, test_data) = setup().await;
+
+        let blocks = client
+            .stream_blocks(full_range(&test_data))
+            .await
+            .unwrap()
+            .into_inner()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .unwrap();
+
+        let hashes = blocks
+            .into_iter()
+            .map(|block| block.header.unwrap().hash)
+            .collect::<Vec<_>>();
+        let expected = test_data
+            .headers
+            .iter()
+            .map(|header| convert::felt(&header.hash.0))
+            .collect::<Vec<_>>();
+        assert_eq!(hashes, expected);
+    }
+
+    #[tokio::test]
+    async fn stream_events() {
+        let (mut client, test_data) = setup().await;
+
+        let events = client
+            .stream_events(proto::EventsRequest {
+                range: Some(full_range(&test_data)),
+                from_address: None,
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .unwrap();
+        assert_eq!(events.len(), test_data.events.len());
+
+        let address = test_data.events[0].from_address;
+        let filtered = client
+            .stream_events(proto::EventsRequest {
+                range: Some(full_range(&test_data)),
+                from_address: Some(convert::felt(&address.0)),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .unwrap();
+        let expected = test_data
+            .events
+            .iter()
+            .filter(|event| event.from_address == address)
+            .count();
+        assert_eq!(filtered.len(), expected);
+    }
+
+    #[tokio::test]
+    async fn missing_block_ends_stream_with_not_found() {
+        let (mut client, test_data) = setup().await;
+        let mut range = full_range(&test_data);
+        range.to += 1;
+
+        let mut stream = client.stream_blocks(range).await.unwrap().into_inner();
+        let mut received = 0;
+        let status = loop {
+            match stream.next().await.unwrap() {
+                Ok(_) => received += 1,
+                Err(status) => break status,
+            }
+        };
+
+        assert_eq!(received, test_data.headers.len());
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+}
@@ -60,6 +60,17 @@ impl<'tx> ClassCommitmentTree<'tx> {
         self.tree.set(&self.storage, key, value.0)
     }
 
+    /// Adds many leaf nodes at once, see [`MerkleTree::set_many`].
+    pub fn set_many(
+        &mut self,
+        leaves: impl IntoIterator<Item = (SierraHash, ClassCommitmentLeafHash)>,
+    ) -> anyhow::Result<()> {
+        let leaves = leaves
+            .into_iter()
+            .map(|(class, value)| (class.view_bits().to_owned(), value.0));
+        self.tree.set_many(&self.storage, leaves)
+    }
+
     /// Commits the changes and calculates the new node hashes. Returns the new commitment and
     /// any potentially newly created nodes.
     pub fn commit(self) -> anyhow::Result<(ClassCommitment, HashMap<Felt, Node>)> {
@@ -98,6 +98,17 @@ impl<'tx> ContractsStorageTree<'tx> {
         self.tree.set(&self.storage, key, value.0)
     }
 
+    /// Sets many storage values at once, see [`MerkleTree::set_many`].
+    pub fn set_many(
+        &mut self,
+        updates: impl IntoIterator<Item = (StorageAddress, StorageValue)>,
+    ) -> anyhow::Result<()> {
+        let leaves = updates
+            .into_iter()
+            .map(|(address, value)| (address.view_bits().to_owned(), value.0));
+        self.tree.set_many(&self.storage, leaves)
+    }
+
     /// Commits the changes and calculates the new node hashes. Returns the new commitment and
     /// any potentially newly created nodes.
     pub fn commit(self) -> anyhow::Result<(ContractRoot, HashMap<Felt, Node>)> {
@@ -165,6 +176,17 @@ impl<'tx> StorageCommitmentTree<'tx> {
         self.tree.set(&self.storage, key, value.0)
     }
 
+    /// Sets many contract state hashes at once, see [`MerkleTree::set_many`].
+    pub fn set_many(
+        &mut self,
+        updates: impl IntoIterator<Item = (ContractAddress, ContractStateHash)>,
+    ) -> anyhow::Result<()> {
+        let leaves = updates
+            .into_iter()
+            .map(|(address, value)| (address.view_bits().to_owned(), value.0));
+        self.tree.set_many(&self.storage, leaves)
+    }
+
     /// Commits the changes and calculates the new node hashes. Returns the new commitment and
     /// any potentially newly created nodes.
     pub fn commit(self) -> anyhow::Result<(StorageCommitment, HashMap<Felt, Node>)> {
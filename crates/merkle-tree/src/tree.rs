@@ -52,7 +52,7 @@ use pathfinder_common::hash::FeltHash;
 use pathfinder_common::trie::TrieNode;
 use pathfinder_crypto::Felt;
 use pathfinder_storage::{Node, StoredNode};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::ControlFlow;
 use std::{cell::RefCell, rc::Rc};
 
@@ -354,6 +354,230 @@ impl<H: FeltHash, const HEIGHT: usize> MerkleTree<H, HEIGHT> {
         Ok(())
     }
 
+    /// Sets the values of many keys at once. A value of [Felt::ZERO] deletes the key.
+    ///
+    /// This is equivalent to calling [MerkleTree::set] for each key, except that instead of
+    /// traversing the tree from the root for each key, the new leaves are first built into an
+    /// in-memory subtree from the bottom up. This subtree is then merged into the tree in a single
+    /// pass, resolving each affected node only once.
+    pub fn set_many(
+        &mut self,
+        storage: &impl Storage,
+        leaves: impl IntoIterator<Item = (BitVec<u8, Msb0>, Felt)>,
+    ) -> anyhow::Result<()> {
+        // Sorted, and later values take precedence as with consecutive calls to `set`.
+        let leaves = leaves.into_iter().collect::<BTreeMap<_, _>>();
+
+        let mut keys = Vec::with_capacity(leaves.len());
+        for (key, value) in leaves {
+            anyhow::ensure!(
+                key.len() == HEIGHT,
+                "Key length {} does not match the tree height {HEIGHT}",
+                key.len()
+            );
+
+            if value == Felt::ZERO {
+                // Deletions are rare enough to not warrant a batched implementation.
+                self.delete_leaf(storage, &key)?;
+            } else {
+                keys.push(key.clone());
+                self.leaves.insert(key, value);
+            }
+        }
+
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let subtree = Self::build_subtree(&keys, 0);
+        let root = match self.root.clone() {
+            Some(root) => self.merge(storage, root, subtree, 0)?,
+            None => subtree,
+        };
+        self.root = Some(root);
+
+        Ok(())
+    }
+
+    /// Builds the subtree at `height` containing the given leaves.
+    ///
+    /// `keys` must be sorted, unique and non-empty.
+    fn build_subtree(keys: &[BitVec<u8, Msb0>], height: usize) -> Rc<RefCell<InternalNode>> {
+        if height == HEIGHT {
+            return Rc::new(RefCell::new(InternalNode::Leaf));
+        }
+
+        // Since the keys are sorted, the path shared by the first and last keys is shared by all.
+        let first = &keys[0][height..];
+        let last = &keys[keys.len() - 1][height..];
+        let common = (0..first.len())
+            .take_while(|&i| first[i] == last[i])
+            .count();
+
+        let node = if common > 0 {
+            InternalNode::Edge(EdgeNode {
+                height,
+                path: first[..common].to_bitvec(),
+                child: Self::build_subtree(keys, height + common),
+            })
+        } else {
+            let split = keys.partition_point(|key| !key[height]);
+            InternalNode::Binary(BinaryNode {
+                height,
+                left: Self::build_subtree(&keys[..split], height + 1),
+                right: Self::build_subtree(&keys[split..], height + 1),
+            })
+        };
+
+        Rc::new(RefCell::new(node))
+    }
+
+    /// Merges the `new` subtree, as created by [MerkleTree::build_subtree], into the `existing`
+    /// subtree at the same height.
+    ///
+    /// Leaves present in both subtrees take their value from [MerkleTree::leaves], and existing
+    /// subtrees which are not affected by the merge are left unresolved.
+    fn merge(
+        &self,
+        storage: &impl Storage,
+        existing: Rc<RefCell<InternalNode>>,
+        new: Rc<RefCell<InternalNode>>,
+        height: usize,
+    ) -> anyhow::Result<Rc<RefCell<InternalNode>>> {
+        use InternalNode::*;
+
+        let unresolved = match &*existing.borrow() {
+            Unresolved(idx) => Some(*idx),
+            _ => None,
+        };
+        if let Some(idx) = unresolved {
+            let node = self.resolve(storage, idx, height)?;
+            existing.swap(&RefCell::new(node));
+        }
+
+        let existing = existing.borrow().clone();
+        let new = new.borrow().clone();
+        let merged = match (existing, new) {
+            (Leaf, Leaf) => Leaf,
+            (Binary(existing), Binary(new)) => Binary(BinaryNode {
+                height,
+                left: self.merge(storage, existing.left, new.left, height + 1)?,
+                right: self.merge(storage, existing.right, new.right, height + 1)?,
+            }),
+            (Binary(existing), Edge(new)) => {
+                let direction = Direction::from(new.path[0]);
+                let child = self.merge(
+                    storage,
+                    existing.get_child(direction),
+                    Self::edge_suffix(&new, 1),
+                    height + 1,
+                )?;
+                let (left, right) = match direction {
+                    Direction::Left => (child, existing.right),
+                    Direction::Right => (existing.left, child),
+                };
+                Binary(BinaryNode {
+                    height,
+                    left,
+                    right,
+                })
+            }
+            (Edge(existing), Binary(new)) => {
+                let direction = Direction::from(existing.path[0]);
+                let child = self.merge(
+                    storage,
+                    Self::edge_suffix(&existing, 1),
+                    new.get_child(direction),
+                    height + 1,
+                )?;
+                let (left, right) = match direction {
+                    Direction::Left => (child, new.right),
+                    Direction::Right => (new.left, child),
+                };
+                Binary(BinaryNode {
+                    height,
+                    left,
+                    right,
+                })
+            }
+            (Edge(existing), Edge(new)) => {
+                let common = (0..existing.path.len().min(new.path.len()))
+                    .take_while(|&i| existing.path[i] == new.path[i])
+                    .count();
+
+                if common > 0 {
+                    let child = self.merge(
+                        storage,
+                        Self::edge_suffix(&existing, common),
+                        Self::edge_suffix(&new, common),
+                        height + common,
+                    )?;
+                    return Ok(Self::prepend_edge(height, &existing.path[..common], child));
+                }
+
+                // The paths diverge immediately, so the edges become the children of a new
+                // binary node.
+                let existing_child = Self::edge_suffix(&existing, 1);
+                let new_child = Self::edge_suffix(&new, 1);
+                let (left, right) = match Direction::from(existing.path[0]) {
+                    Direction::Left => (existing_child, new_child),
+                    Direction::Right => (new_child, existing_child),
+                };
+                Binary(BinaryNode {
+                    height,
+                    left,
+                    right,
+                })
+            }
+            (existing, new) => {
+                anyhow::bail!("Cannot merge {existing:?} with {new:?} at height {height}")
+            }
+        };
+
+        Ok(Rc::new(RefCell::new(merged)))
+    }
+
+    /// Returns the part of the edge after its first `n` bits, which is the edge's child if `n`
+    /// is the full path length.
+    fn edge_suffix(edge: &EdgeNode, n: usize) -> Rc<RefCell<InternalNode>> {
+        if n == edge.path.len() {
+            return edge.child.clone();
+        }
+
+        Rc::new(RefCell::new(InternalNode::Edge(EdgeNode {
+            height: edge.height + n,
+            path: edge.path[n..].to_bitvec(),
+            child: edge.child.clone(),
+        })))
+    }
+
+    /// Creates an edge with the given path leading to `child`, merging the two if `child` is
+    /// also an edge.
+    fn prepend_edge(
+        height: usize,
+        path: &BitSlice<u8, Msb0>,
+        child: Rc<RefCell<InternalNode>>,
+    ) -> Rc<RefCell<InternalNode>> {
+        let edge = match &*child.borrow() {
+            InternalNode::Edge(child) => {
+                let mut path = path.to_bitvec();
+                path.extend_from_bitslice(&child.path);
+                EdgeNode {
+                    height,
+                    path,
+                    child: child.child.clone(),
+                }
+            }
+            _ => EdgeNode {
+                height,
+                path: path.to_bitvec(),
+                child: child.clone(),
+            },
+        };
+
+        Rc::new(RefCell::new(InternalNode::Edge(edge)))
+    }
+
     /// Deletes a leaf node from the tree.
     ///
     /// This is not an external facing API; the functionality is instead accessed by calling
@@ -944,6 +1168,105 @@ mod tests {
         }
     }
 
+    mod set_many {
+        use super::*;
+
+        fn random_leaves(n: usize) -> Vec<(BitVec<u8, Msb0>, Felt)> {
+            let mut rng = rand::rngs::ThreadRng::default();
+            std::iter::repeat_with(|| (Felt::random(&mut rng), Felt::random(&mut rng)))
+                .filter(|(key, _)| !key.has_more_than_251_bits())
+                .map(|(key, value)| (key.view_bits().to_bitvec(), value))
+                .take(n)
+                .collect()
+        }
+
+        fn root_with_set(
+            mut tree: TestTree,
+            storage: &TestStorage,
+            leaves: &[(BitVec<u8, Msb0>, Felt)],
+        ) -> Felt {
+            for (key, value) in leaves {
+                tree.set(storage, key.clone(), *value).unwrap();
+            }
+            tree.commit(storage).unwrap().root
+        }
+
+        fn root_with_set_many(
+            mut tree: TestTree,
+            storage: &TestStorage,
+            leaves: &[(BitVec<u8, Msb0>, Felt)],
+        ) -> Felt {
+            tree.set_many(storage, leaves.iter().cloned()).unwrap();
+            tree.commit(storage).unwrap().root
+        }
+
+        #[test]
+        fn empty_tree() {
+            let storage = TestStorage::default();
+            let leaves = random_leaves(200);
+
+            let expected = root_with_set(TestTree::empty(), &storage, &leaves);
+            let root = root_with_set_many(TestTree::empty(), &storage, &leaves);
+            assert_eq!(root, expected);
+        }
+
+        #[test]
+        fn adjacent_keys() {
+            let storage = TestStorage::default();
+            let leaves = ["0x0", "0x1", "0x2", "0x3", "0x7", "0x10"]
+                .into_iter()
+                .map(|key| {
+                    let key = Felt::from_hex_str(key).unwrap();
+                    (key.view_bits().to_bitvec(), felt!("0xabc"))
+                })
+                .collect::<Vec<_>>();
+
+            let expected = root_with_set(TestTree::empty(), &storage, &leaves);
+            let root = root_with_set_many(TestTree::empty(), &storage, &leaves);
+            assert_eq!(root, expected);
+        }
+
+        #[test]
+        fn existing_tree() {
+            let mut storage = TestStorage::default();
+            let existing = random_leaves(200);
+
+            let mut tree = TestTree::empty();
+            for (key, value) in &existing {
+                tree.set(&storage, key.clone(), *value).unwrap();
+            }
+            let (_, root_idx) = commit_and_persist(tree, &mut storage);
+
+            // New keys, overwritten keys and deleted keys.
+            let mut leaves = random_leaves(100);
+            leaves.extend(
+                existing[..50]
+                    .iter()
+                    .map(|(key, _)| (key.clone(), felt!("0x1"))),
+            );
+            leaves.extend(
+                existing[50..70]
+                    .iter()
+                    .map(|(key, _)| (key.clone(), Felt::ZERO)),
+            );
+
+            let expected = root_with_set(TestTree::new(root_idx), &storage, &leaves);
+            let root = root_with_set_many(TestTree::new(root_idx), &storage, &leaves);
+            assert_eq!(root, expected);
+        }
+
+        #[test]
+        fn later_values_take_precedence() {
+            let storage = TestStorage::default();
+            let key = felt!("0x123").view_bits().to_bitvec();
+            let leaves = vec![(key.clone(), felt!("0x1")), (key.clone(), felt!("0x2"))];
+
+            let mut uut = TestTree::empty();
+            uut.set_many(&storage, leaves).unwrap();
+            assert_eq!(uut.get(&storage, key).unwrap(), Some(felt!("0x2")));
+        }
+    }
+
     mod tree_state {
         use super::*;
 
@@ -2,7 +2,7 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::time::Duration;
 
-use crate::sync::codec;
+use crate::sync::{codec, protocol};
 use libp2p::autonat;
 use libp2p::dcutr;
 use libp2p::gossipsub::{self, IdentTopic, MessageAuthenticity, MessageId};
@@ -20,6 +20,7 @@ use p2p_proto::block::{
 use p2p_proto::event::{EventsRequest, EventsResponseList};
 use p2p_proto::receipt::{ReceiptsRequest, ReceiptsResponseList};
 use p2p_proto::transaction::{TransactionsRequest, TransactionsResponseList};
+use pathfinder_common::ChainId;
 
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "Event", event_process = false)]
@@ -38,13 +39,23 @@ pub struct Behaviour {
     pub events_sync: request_response::Behaviour<codec::Events>,
 }
 
-pub const KADEMLIA_PROTOCOL_NAME: &str = "/pathfinder/kad/1.0.0";
+/// The name of the DHT protocol of the network with the given chain ID.
+pub fn kademlia_protocol_name(chain_id: ChainId) -> String {
+    format!("{}/kad/1.0.0", protocol::prefix(chain_id))
+}
+
+/// The protocol version peers of the network with the given chain ID announce via identify.
+/// Peers announcing a different version belong to another network.
 // FIXME: clarify what version number should be
-// FIXME: we're also missing the starting '/'
-const PROTOCOL_VERSION: &str = "starknet/0.9.1";
+pub fn protocol_version(chain_id: ChainId) -> String {
+    format!("{}/0.9.1", protocol::prefix(chain_id))
+}
 
 impl Behaviour {
-    pub fn new(identity: &identity::Keypair) -> (Self, relay::client::Transport) {
+    pub fn new(
+        identity: &identity::Keypair,
+        chain_id: ChainId,
+    ) -> (Self, relay::client::Transport) {
         const PROVIDER_PUBLICATION_INTERVAL: Duration = Duration::from_secs(600);
 
         let mut kademlia_config = kad::Config::default();
@@ -53,7 +64,10 @@ impl Behaviour {
         kademlia_config.set_provider_publication_interval(Some(PROVIDER_PUBLICATION_INTERVAL));
         // This makes sure that the DHT we're implementing is incompatible with the "default" IPFS
         // DHT from libp2p.
-        kademlia_config.set_protocol_names(vec![StreamProtocol::new(KADEMLIA_PROTOCOL_NAME)]);
+        kademlia_config.set_protocol_names(vec![StreamProtocol::try_from_owned(
+            kademlia_protocol_name(chain_id),
+        )
+        .expect("valid protocol name")]);
 
         let peer_id = identity.public().to_peer_id();
 
@@ -77,11 +91,16 @@ impl Behaviour {
         )
         .expect("valid gossipsub params");
 
-        let headers_sync = request_response_behavior::<codec::Headers>();
-        let bodies_sync = request_response_behavior::<codec::Bodies>();
-        let transactions_sync = request_response_behavior::<codec::Transactions>();
-        let receipts_sync = request_response_behavior::<codec::Receipts>();
-        let events_sync = request_response_behavior::<codec::Events>();
+        let headers_sync =
+            request_response_behavior::<codec::Headers>(protocol::Headers::new(chain_id));
+        let bodies_sync =
+            request_response_behavior::<codec::Bodies>(protocol::Bodies::new(chain_id));
+        let transactions_sync =
+            request_response_behavior::<codec::Transactions>(protocol::Transactions::new(chain_id));
+        let receipts_sync =
+            request_response_behavior::<codec::Receipts>(protocol::Receipts::new(chain_id));
+        let events_sync =
+            request_response_behavior::<codec::Events>(protocol::Events::new(chain_id));
 
         let (relay_transport, relay) = relay::client::new(peer_id);
 
@@ -92,7 +111,7 @@ impl Behaviour {
                 dcutr: dcutr::Behaviour::new(peer_id),
                 ping: ping::Behaviour::new(ping::Config::new()),
                 identify: identify::Behaviour::new(
-                    identify::Config::new(PROTOCOL_VERSION.to_string(), identity.public())
+                    identify::Config::new(protocol_version(chain_id), identity.public())
                         .with_agent_version(format!("pathfinder/{}", env!("CARGO_PKG_VERSION"))),
                 ),
                 kademlia,
@@ -124,13 +143,12 @@ impl Behaviour {
     }
 }
 
-fn request_response_behavior<C>() -> request_response::Behaviour<C>
+fn request_response_behavior<C>(protocol: C::Protocol) -> request_response::Behaviour<C>
 where
     C: Default + request_response::Codec + Clone + Send,
-    C::Protocol: Default,
 {
     request_response::Behaviour::new(
-        std::iter::once((C::Protocol::default(), ProtocolSupport::Full)),
+        std::iter::once((protocol, ProtocolSupport::Full)),
         Default::default(),
     )
 }
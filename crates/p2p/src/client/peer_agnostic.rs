@@ -15,7 +15,7 @@ use p2p_proto::event::{EventsRequest, EventsResponseList};
 use p2p_proto::receipt::{Receipt, ReceiptsRequest, ReceiptsResponseList};
 use p2p_proto::transaction::{TransactionsRequest, TransactionsResponseList};
 use pathfinder_common::{
-    event::Event, transaction::TransactionVariant, BlockHash, BlockNumber, TransactionHash,
+    event::Event, transaction::TransactionVariant, BlockHash, BlockNumber, ChainId, TransactionHash,
 };
 use tokio::sync::RwLock;
 
@@ -36,6 +36,7 @@ use parse::ParserState;
 pub struct Client {
     inner: peer_aware::Client,
     block_propagation_topic: String,
+    chain_id: ChainId,
     peers_with_capability: Arc<RwLock<PeersWithCapability>>,
     // FIXME
     _peers: Arc<RwLock<peers::Peers>>,
@@ -48,16 +49,22 @@ impl Client {
     pub fn new(
         inner: peer_aware::Client,
         block_propagation_topic: String,
+        chain_id: ChainId,
         peers: Arc<RwLock<peers::Peers>>,
     ) -> Self {
         Self {
             inner,
             block_propagation_topic,
+            chain_id,
             peers_with_capability: Default::default(),
             _peers: peers,
         }
     }
 
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
     // Propagate new L2 head head
     pub async fn propagate_new_head(
         &self,
@@ -114,7 +121,7 @@ impl Client {
         let limit: u64 = num_blocks.try_into()?;
 
         for peer in self
-            .get_update_peers_with_sync_capability(protocol::Headers::NAME)
+            .get_update_peers_with_sync_capability(protocol::Headers::new(self.chain_id).as_ref())
             .await
         {
             let request = BlockHeadersRequest {
@@ -171,7 +178,7 @@ impl Client {
         // dump everything from this peer and try with the next peer.
         // We're not permissive when it comes to following the spec.
         let peers = self
-            .get_update_peers_with_sync_capability(protocol::Bodies::NAME)
+            .get_update_peers_with_sync_capability(protocol::Bodies::new(self.chain_id).as_ref())
             .await;
         for peer in peers {
             let request = BlockBodiesRequest {
@@ -228,7 +235,9 @@ impl Client {
         // dump everything from this peer and try with the next peer.
         // We're not permissive when it comes to following the spec.
         let peers = self
-            .get_update_peers_with_sync_capability(protocol::Transactions::NAME)
+            .get_update_peers_with_sync_capability(
+                protocol::Transactions::new(self.chain_id).as_ref(),
+            )
             .await;
         for peer in peers {
             let request = TransactionsRequest {
@@ -288,7 +297,7 @@ impl Client {
         // dump everything from this peer and try with the next peer.
         // We're not permissive when it comes to following the spec.
         let peers = self
-            .get_update_peers_with_sync_capability(protocol::Receipts::NAME)
+            .get_update_peers_with_sync_capability(protocol::Receipts::new(self.chain_id).as_ref())
             .await;
         for peer in peers {
             let request = ReceiptsRequest {
@@ -345,7 +354,7 @@ impl Client {
         // dump everything from this peer and try with the next peer.
         // We're not permissive when it comes to following the spec.
         let peers = self
-            .get_update_peers_with_sync_capability(protocol::Events::NAME)
+            .get_update_peers_with_sync_capability(protocol::Events::new(self.chain_id).as_ref())
             .await;
         for peer in peers {
             let request = EventsRequest {
@@ -57,8 +57,8 @@ pub(crate) mod block_header {
     use crate::client::types::BlockHeader;
     use anyhow::Context;
     use p2p_proto::block::BlockHeadersResponsePart;
-    use p2p_proto::common::{Error, Fin};
-    use pathfinder_common::BlockHash;
+    use p2p_proto::common::{ConsensusSignature, Error, Fin};
+    use pathfinder_common::{BlockCommitmentSignature, BlockCommitmentSignatureElem, BlockHash};
     use std::collections::HashMap;
 
     #[derive(Debug, Default)]
@@ -68,7 +68,9 @@ pub(crate) mod block_header {
         Header {
             headers: HashMap<BlockHash, BlockHeader>,
         },
-        _Signatures, // TODO add signature support
+        Signatures {
+            headers: HashMap<BlockHash, BlockHeader>,
+        },
         Delimited {
             headers: HashMap<BlockHash, BlockHeader>,
         },
@@ -97,12 +99,32 @@ pub(crate) mod block_header {
                 (State::Uninitialized, BlockHeadersResponsePart::Fin(Fin { error })) => {
                     Self::Empty { error }
                 }
-                (State::Header { headers }, BlockHeadersResponsePart::Fin(Fin { error })) => {
-                    match error {
-                        Some(error) => State::DelimitedWithError { error, headers },
-                        None => State::Delimited { headers },
-                    }
+                (
+                    State::Header { mut headers },
+                    BlockHeadersResponsePart::Signatures(signatures),
+                ) => {
+                    let header = headers
+                        .get_mut(&BlockHash(signatures.block.hash.0))
+                        .filter(|header| header.number.get() == signatures.block.number)
+                        .context("signatures for another block")?;
+                    // Only the sequencer signs blocks for now.
+                    let [ConsensusSignature { r, s }]: [ConsensusSignature; 1] = signatures
+                        .signatures
+                        .try_into()
+                        .map_err(|_| anyhow::anyhow!("expected a single signature"))?;
+                    header.signature = Some(BlockCommitmentSignature {
+                        r: BlockCommitmentSignatureElem(r),
+                        s: BlockCommitmentSignatureElem(s),
+                    });
+                    Self::Signatures { headers }
                 }
+                (
+                    State::Header { headers } | State::Signatures { headers },
+                    BlockHeadersResponsePart::Fin(Fin { error }),
+                ) => match error {
+                    Some(error) => State::DelimitedWithError { error, headers },
+                    None => State::Delimited { headers },
+                },
                 (State::Delimited { mut headers }, BlockHeadersResponsePart::Header(header)) => {
                     if headers.contains_key(&BlockHash(header.hash.0)) {
                         anyhow::bail!("unexpected response");
@@ -16,6 +16,7 @@ use p2p_proto::event::EventsResponseList;
 use p2p_proto::receipt::ReceiptsResponseList;
 use p2p_proto::transaction::TransactionsResponseList;
 use p2p_proto::{ToProtobuf, TryFromProtobuf};
+use pathfinder_common::ChainId;
 use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::time::Duration;
 
@@ -33,6 +34,9 @@ pub struct MainLoop {
     command_receiver: mpsc::Receiver<Command>,
     event_sender: mpsc::Sender<Event>,
     peers: Arc<RwLock<peers::Peers>>,
+    /// The identify protocol version of peers of our network.
+    protocol_version: String,
+    kademlia_protocol_name: String,
     pending_dials: HashMap<PeerId, EmptyResultSender>,
     pending_sync_requests: PendingRequests,
     // TODO there's no sync status message anymore so we have to:
@@ -66,6 +70,7 @@ impl MainLoop {
         event_sender: mpsc::Sender<Event>,
         peers: Arc<RwLock<peers::Peers>>,
         periodic_cfg: PeriodicTaskConfig,
+        chain_id: ChainId,
     ) -> Self {
         Self {
             bootstrap_cfg: periodic_cfg.bootstrap,
@@ -73,6 +78,8 @@ impl MainLoop {
             command_receiver,
             event_sender,
             peers,
+            protocol_version: behaviour::protocol_version(chain_id),
+            kademlia_protocol_name: behaviour::kademlia_protocol_name(chain_id),
             pending_dials: Default::default(),
             pending_sync_requests: Default::default(),
             pending_queries: Default::default(),
@@ -216,6 +223,7 @@ impl MainLoop {
                     peer_id,
                     info:
                         identify::Info {
+                            protocol_version,
                             listen_addrs,
                             protocols,
                             observed_addr,
@@ -223,6 +231,13 @@ impl MainLoop {
                         },
                 } = *e
                 {
+                    if protocol_version != self.protocol_version {
+                        tracing::debug!(%peer_id, %protocol_version, "Disconnecting peer of another network");
+                        self.peers.write().await.peer_disconnecting(&peer_id);
+                        let _ = self.swarm.disconnect_peer_id(peer_id);
+                        return;
+                    }
+
                     // Important change in libp2p-v0.52 compared to v0.51:
                     //
                     // https://github.com/libp2p/rust-libp2p/releases/tag/libp2p-v0.52.0
@@ -242,7 +257,7 @@ impl MainLoop {
 
                     if protocols
                         .iter()
-                        .any(|p| p.as_ref() == behaviour::KADEMLIA_PROTOCOL_NAME)
+                        .any(|p| p.as_ref() == self.kademlia_protocol_name)
                     {
                         for addr in &listen_addrs {
                             self.swarm
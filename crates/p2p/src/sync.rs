@@ -4,36 +4,94 @@
 //! streaming response protocol is implemented
 
 pub mod protocol {
+    use pathfinder_common::ChainId;
+
+    /// The prefix of all protocol names of the network with the given chain ID, e.g.
+    /// `/starknet/SN_MAIN`.
+    ///
+    /// Chain IDs which are not printable ASCII, as it can be the case for custom networks, are
+    /// used in their hex form. Nodes of different networks never share a protocol, so they cannot
+    /// talk to each other.
+    pub fn prefix(chain_id: ChainId) -> String {
+        let bytes = chain_id.0.as_be_bytes();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        let name = &bytes[start..];
+
+        if !name.is_empty() && name.iter().all(u8::is_ascii_graphic) && !name.contains(&b'/') {
+            format!("/starknet/{}", String::from_utf8_lossy(name))
+        } else {
+            format!("/starknet/{}", chain_id.to_hex_str())
+        }
+    }
+
     macro_rules! define_protocol {
         ($type_name:ident, $name:literal) => {
-            #[derive(Debug, Clone, Default)]
-            pub struct $type_name;
+            #[derive(Debug, Clone)]
+            pub struct $type_name(String);
 
             impl $type_name {
+                /// The name of the protocol without the network [prefix].
                 pub const NAME: &'static str = $name;
+
+                pub fn new(chain_id: ChainId) -> Self {
+                    Self(format!("{}{}", prefix(chain_id), Self::NAME))
+                }
             }
 
             impl AsRef<str> for $type_name {
                 fn as_ref(&self) -> &str {
-                    Self::NAME
+                    &self.0
                 }
             }
         };
     }
 
-    define_protocol!(Headers, "/core/headers-sync/1");
-    define_protocol!(Bodies, "/core/bodies-sync/1");
-    define_protocol!(Transactions, "/core/transactions-sync/1");
-    define_protocol!(Receipts, "/core/receipts-sync/1");
-    define_protocol!(Events, "/core/events-sync/1");
-
-    pub const PROTOCOLS: &[&str] = &[
-        Headers::NAME,
-        Bodies::NAME,
-        Transactions::NAME,
-        Receipts::NAME,
-        Events::NAME,
-    ];
+    define_protocol!(Headers, "/headers-sync/1");
+    define_protocol!(Bodies, "/bodies-sync/1");
+    define_protocol!(Transactions, "/transactions-sync/1");
+    define_protocol!(Receipts, "/receipts-sync/1");
+    define_protocol!(Events, "/events-sync/1");
+
+    /// The names of the sync protocols of the network, which are also the capabilities nodes
+    /// provide in the DHT.
+    pub fn protocols(chain_id: ChainId) -> Vec<String> {
+        vec![
+            Headers::new(chain_id).0,
+            Bodies::new(chain_id).0,
+            Transactions::new(chain_id).0,
+            Receipts::new(chain_id).0,
+            Events::new(chain_id).0,
+        ]
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pathfinder_crypto::Felt;
+
+        #[test]
+        fn prefix_uses_the_chain_id_name() {
+            assert_eq!(prefix(ChainId::MAINNET), "/starknet/SN_MAIN");
+            assert_eq!(
+                Headers::new(ChainId::TESTNET).as_ref(),
+                "/starknet/SN_GOERLI/headers-sync/1"
+            );
+        }
+
+        #[test]
+        fn prefix_of_non_printable_chain_id_is_hex() {
+            let chain_id = ChainId(Felt::from_be_slice(&[0x01, 0xff]).unwrap());
+            assert_eq!(prefix(chain_id), "/starknet/0x1ff");
+
+            let chain_id = ChainId(Felt::from_be_slice(b"MY/CHAIN").unwrap());
+            assert_eq!(
+                prefix(chain_id),
+                format!("/starknet/{}", chain_id.to_hex_str())
+            );
+
+            assert_eq!(prefix(ChainId(Felt::ZERO)), "/starknet/0x0");
+        }
+    }
 }
 
 pub(crate) mod codec {
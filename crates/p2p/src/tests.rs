@@ -6,6 +6,7 @@ use core::panic;
 use libp2p::identity::Keypair;
 use libp2p::multiaddr::Protocol;
 use libp2p::{Multiaddr, PeerId};
+use pathfinder_common::ChainId;
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::str::FromStr;
@@ -28,11 +29,16 @@ struct TestPeer {
 impl TestPeer {
     #[must_use]
     pub fn new(periodic_cfg: PeriodicTaskConfig) -> Self {
+        Self::with_chain_id(periodic_cfg, ChainId::TESTNET)
+    }
+
+    #[must_use]
+    pub fn with_chain_id(periodic_cfg: PeriodicTaskConfig, chain_id: ChainId) -> Self {
         let keypair = Keypair::generate_ed25519();
         let peer_id = keypair.public().to_peer_id();
         let peers: Arc<RwLock<Peers>> = Default::default();
         let (client, event_receiver, main_loop) =
-            p2p::new(keypair.clone(), peers.clone(), periodic_cfg);
+            p2p::new(keypair.clone(), peers.clone(), periodic_cfg, chain_id);
         let main_loop_jh = tokio::spawn(main_loop.run());
         Self {
             keypair,
@@ -146,6 +152,31 @@ async fn dial() {
     assert_eq!(peers_of2, [peer1.peer_id].into());
 }
 
+#[test_log::test(tokio::test)]
+async fn peers_of_other_networks_are_disconnected() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let mut peer1 = TestPeer::with_chain_id(Default::default(), ChainId::MAINNET);
+    let mut peer2 = TestPeer::with_chain_id(Default::default(), ChainId::TESTNET);
+
+    let _ = peer1.start_listening().await.unwrap();
+    let addr2 = peer2.start_listening().await.unwrap();
+
+    consume_events(peer1.event_receiver);
+    consume_events(peer2.event_receiver);
+
+    peer1.client.dial(peer2.peer_id, addr2).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while peer1.peers.read().await.connected().next().is_some()
+            || peer2.peers.read().await.connected().next().is_some()
+        {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("peers to disconnect");
+}
+
 #[test_log::test(tokio::test)]
 async fn periodic_bootstrap() {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -21,14 +21,24 @@ pub struct BootstrapBehaviour {
     pub kademlia: kad::Behaviour<MemoryStore>,
 }
 
-pub const KADEMLIA_PROTOCOL_NAME: &str = "/pathfinder/kad/1.0.0";
+/// The prefix of the protocol names of `network`, which has to match the one the `p2p` crate
+/// derives from the chain ID.
+fn prefix(network: &str) -> String {
+    format!("/starknet/{network}")
+}
+
+pub fn kademlia_protocol_name(network: &str) -> String {
+    format!("{}/kad/1.0.0", prefix(network))
+}
+
+// FIXME: clarify what version number should be
+pub fn protocol_version(network: &str) -> String {
+    format!("{}/0.9.1", prefix(network))
+}
 
 impl BootstrapBehaviour {
-    pub fn new(pub_key: identity::PublicKey) -> Self {
+    pub fn new(pub_key: identity::PublicKey, network: &str) -> anyhow::Result<Self> {
         const PROVIDER_PUBLICATION_INTERVAL: Duration = Duration::from_secs(600);
-        // FIXME: clarify what version number should be
-        // FIXME: we're also missing the starting '/'
-        const PROTOCOL_VERSION: &str = "starknet/0.9.1";
 
         let mut kademlia_config = kad::Config::default();
         kademlia_config.set_record_ttl(Some(Duration::from_secs(0)));
@@ -36,7 +46,9 @@ impl BootstrapBehaviour {
         kademlia_config.set_provider_publication_interval(Some(PROVIDER_PUBLICATION_INTERVAL));
         // FIXME: this make sure that the DHT we're implementing is incompatible with the "default" IPFS
         // DHT from libp2p.
-        kademlia_config.set_protocol_names(vec![StreamProtocol::new(KADEMLIA_PROTOCOL_NAME)]);
+        kademlia_config.set_protocol_names(vec![StreamProtocol::try_from_owned(
+            kademlia_protocol_name(network),
+        )?]);
 
         let kademlia = kad::Behaviour::with_config(
             pub_key.to_peer_id(),
@@ -46,17 +58,17 @@ impl BootstrapBehaviour {
 
         let peer_id = pub_key.to_peer_id();
 
-        Self {
+        Ok(Self {
             relay: relay::Behaviour::new(peer_id, Default::default()),
             autonat: autonat::Behaviour::new(peer_id, Default::default()),
             dcutr: dcutr::Behaviour::new(peer_id),
             ping: ping::Behaviour::new(ping::Config::new()),
             identify: identify::Behaviour::new(
-                identify::Config::new(PROTOCOL_VERSION.to_string(), pub_key)
+                identify::Config::new(protocol_version(network), pub_key)
                     .with_agent_version(format!("pathfinder/{}", env!("CARGO_PKG_VERSION"))),
             ),
             kademlia,
-        }
+        })
     }
 }
 
@@ -26,6 +26,10 @@ struct Args {
     bootstrap_interval_seconds: u64,
     #[clap(long, short, value_parser, env = "PRETTY_LOG", default_value = "false")]
     pretty_log: bool,
+    /// The network name used in protocol names: the chain ID as text, e.g. `SN_GOERLI`, or in
+    /// hex if it is not printable.
+    #[clap(long, short, value_parser, env = "NETWORK")]
+    network: String,
 }
 
 #[derive(Clone, Deserialize)]
@@ -82,13 +86,16 @@ async fn main() -> anyhow::Result<()> {
 
     let mut swarm = Swarm::new(
         transport,
-        behaviour::BootstrapBehaviour::new(keypair.public()),
+        behaviour::BootstrapBehaviour::new(keypair.public(), &args.network)?,
         keypair.public().to_peer_id(),
         Config::with_tokio_executor(),
     );
 
     swarm.listen_on(args.listen_on)?;
 
+    let protocol_version = behaviour::protocol_version(&args.network);
+    let kademlia_protocol_name = behaviour::kademlia_protocol_name(&args.network);
+
     let mut bootstrap_interval =
         tokio::time::interval(Duration::from_secs(args.bootstrap_interval_seconds));
 
@@ -121,6 +128,7 @@ async fn main() -> anyhow::Result<()> {
                             peer_id,
                             info:
                                 identify::Info {
+                                    protocol_version: peer_protocol_version,
                                     listen_addrs,
                                     protocols,
                                     observed_addr,
@@ -128,6 +136,12 @@ async fn main() -> anyhow::Result<()> {
                                 },
                         } = *e
                         {
+                            if peer_protocol_version != protocol_version {
+                                tracing::debug!(%peer_id, protocol_version=%peer_protocol_version, "Disconnecting peer of another network");
+                                let _ = swarm.disconnect_peer_id(peer_id);
+                                continue;
+                            }
+
                             // Important change in libp2p-v0.52 compared to v0.51:
                             //
                             // https://github.com/libp2p/rust-libp2p/releases/tag/libp2p-v0.52.0
@@ -147,7 +161,7 @@ async fn main() -> anyhow::Result<()> {
 
                             if protocols
                                 .iter()
-                                .any(|p| p.as_ref() == behaviour::KADEMLIA_PROTOCOL_NAME)
+                                .any(|p| p.as_ref() == kademlia_protocol_name)
                             {
                                 for addr in listen_addrs {
                                     swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
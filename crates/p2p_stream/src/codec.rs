@@ -26,6 +26,10 @@ use async_trait::async_trait;
 use futures::prelude::*;
 use std::io;
 
+mod prost;
+
+pub use self::prost::{ProstCodec, DEFAULT_MAX_MESSAGE_SIZE};
+
 /// A `Codec` defines the request and response types
 /// for a request/streaming-response [`Behaviour`](crate::Behaviour) protocol or
 /// protocol family and how they are encoded / decoded on an I/O stream.
@@ -0,0 +1,186 @@
+use super::Codec;
+use async_trait::async_trait;
+use futures::prelude::*;
+use libp2p::swarm::StreamProtocol;
+use std::io;
+use std::marker::PhantomData;
+
+/// The default upper bound for the encoded size of a single message.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// The maximum length of a varint encoding a `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+/// A [`Codec`] for protocols which exchange [`prost`] messages, each prefixed with its varint
+/// encoded length.
+///
+/// Messages larger than the maximum message size are rejected on both read and write.
+pub struct ProstCodec<Req, Resp> {
+    max_message_size: usize,
+    _messages: PhantomData<fn() -> (Req, Resp)>,
+}
+
+impl<Req, Resp> ProstCodec<Req, Resp> {
+    /// Creates a codec which accepts messages of at most `max_message_size` bytes.
+    pub fn new(max_message_size: usize) -> Self {
+        Self {
+            max_message_size,
+            _messages: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp> Default for ProstCodec<Req, Resp> {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_MESSAGE_SIZE)
+    }
+}
+
+impl<Req, Resp> Clone for ProstCodec<Req, Resp> {
+    fn clone(&self) -> Self {
+        Self::new(self.max_message_size)
+    }
+}
+
+impl<Req, Resp> std::fmt::Debug for ProstCodec<Req, Resp> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProstCodec")
+            .field("max_message_size", &self.max_message_size)
+            .finish()
+    }
+}
+
+impl<Req, Resp> ProstCodec<Req, Resp> {
+    /// Reads a length-delimited message, or returns [`None`] if the stream ends before the length
+    /// prefix.
+    async fn read<T, M>(&self, io: &mut T) -> io::Result<Option<M>>
+    where
+        T: AsyncRead + Unpin + Send,
+        M: ::prost::Message + Default,
+    {
+        let Some(length) = read_length(io).await? else {
+            return Ok(None);
+        };
+
+        if length > self.max_message_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Message length {length} exceeds the maximum message size {}",
+                    self.max_message_size
+                ),
+            ));
+        }
+
+        let mut buffer = vec![0u8; length];
+        io.read_exact(&mut buffer).await?;
+
+        let message = M::decode(buffer.as_slice())?;
+        Ok(Some(message))
+    }
+
+    async fn write<T, M>(&self, io: &mut T, message: M) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+        M: ::prost::Message,
+    {
+        let length = message.encoded_len();
+        if length > self.max_message_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Message length {length} exceeds the maximum message size {}",
+                    self.max_message_size
+                ),
+            ));
+        }
+
+        io.write_all(&message.encode_length_delimited_to_vec())
+            .await
+    }
+}
+
+/// Reads a varint length prefix, or returns [`None`] if the stream ends before its first byte.
+async fn read_length<T>(io: &mut T) -> io::Result<Option<usize>>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    let mut prefix = [0u8; MAX_VARINT_LEN];
+
+    for i in 0..MAX_VARINT_LEN {
+        let n = io.read(&mut prefix[i..=i]).await?;
+        if n == 0 {
+            if i == 0 {
+                return Ok(None);
+            }
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        if prefix[i] & 0x80 == 0 {
+            let length = ::prost::encoding::decode_varint(&mut &prefix[..=i])?;
+            let length = usize::try_from(length)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            return Ok(Some(length));
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Message length prefix is too long",
+    ))
+}
+
+#[async_trait]
+impl<Req, Resp> Codec for ProstCodec<Req, Resp>
+where
+    Req: ::prost::Message + Default,
+    Resp: ::prost::Message + Default,
+{
+    type Protocol = StreamProtocol;
+    type Request = Req;
+    type Response = Resp;
+
+    async fn read_request<T>(&mut self, _: &Self::Protocol, io: &mut T) -> io::Result<Self::Request>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        self.read(io)
+            .await?
+            .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+    }
+
+    async fn read_response<T>(
+        &mut self,
+        _: &Self::Protocol,
+        io: &mut T,
+    ) -> io::Result<Option<Self::Response>>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        self.read(io).await
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        _: &Self::Protocol,
+        io: &mut T,
+        request: Self::Request,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        self.write(io, request).await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        _: &Self::Protocol,
+        io: &mut T,
+        response: Self::Response,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        self.write(io, response).await
+    }
+}
@@ -26,7 +26,9 @@ pub(crate) mod protocol;
 
 use crate::codec::Codec;
 use crate::handler::protocol::Protocol;
-use crate::{InboundRequestId, OutboundRequestId, EMPTY_QUEUE_SHRINK_THRESHOLD};
+use crate::{
+    InboundRequestId, OutboundRequestId, ResponseStreamStats, EMPTY_QUEUE_SHRINK_THRESHOLD,
+};
 
 use futures::{channel::mpsc, prelude::*};
 use libp2p::swarm::handler::{
@@ -41,12 +43,13 @@ use smallvec::SmallVec;
 use std::{
     collections::VecDeque,
     fmt, io,
+    pin::Pin,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 /// A connection handler for a request/streaming-response [`Behaviour`](super::Behaviour) protocol.
@@ -84,6 +87,15 @@ where
     inbound_request_id: Arc<AtomicU64>,
 
     worker_streams: futures_bounded::FuturesMap<RequestId, Result<Event<TCodec>, io::Error>>,
+
+    /// The number of active inbound streams of the peer, shared by the handlers of all of its
+    /// connections.
+    peer_inbound_streams: Arc<AtomicUsize>,
+    /// The limit for [`Self::peer_inbound_streams`].
+    max_peer_inbound_streams: usize,
+    /// The number of active inbound streams on this connection, which are released from
+    /// [`Self::peer_inbound_streams`] when the handler is dropped.
+    inbound_streams: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -102,6 +114,8 @@ where
         substream_timeout: Duration,
         inbound_request_id: Arc<AtomicU64>,
         max_concurrent_streams: usize,
+        peer_inbound_streams: Arc<AtomicUsize>,
+        max_peer_inbound_streams: usize,
     ) -> Self {
         let (inbound_sender, inbound_receiver) = mpsc::channel(0);
         let (outbound_sender, outbound_receiver) = mpsc::channel(0);
@@ -120,9 +134,30 @@ where
                 substream_timeout,
                 max_concurrent_streams,
             ),
+            peer_inbound_streams,
+            max_peer_inbound_streams,
+            inbound_streams: 0,
         }
     }
 
+    /// Counts a new inbound stream against the peer's limit. Returns `false` if the peer is
+    /// already at its limit.
+    fn acquire_inbound_stream(&mut self) -> bool {
+        let previous = self.peer_inbound_streams.fetch_add(1, Ordering::Relaxed);
+        if previous >= self.max_peer_inbound_streams {
+            self.peer_inbound_streams.fetch_sub(1, Ordering::Relaxed);
+            return false;
+        }
+
+        self.inbound_streams += 1;
+        true
+    }
+
+    fn release_inbound_stream(&mut self) {
+        self.inbound_streams -= 1;
+        self.peer_inbound_streams.fetch_sub(1, Ordering::Relaxed);
+    }
+
     /// Returns the next inbound request ID.
     fn next_inbound_request_id(&mut self) -> InboundRequestId {
         InboundRequestId(self.inbound_request_id.fetch_add(1, Ordering::Relaxed))
@@ -138,6 +173,11 @@ where
             <Self as ConnectionHandler>::InboundOpenInfo,
         >,
     ) {
+        if !self.acquire_inbound_stream() {
+            tracing::debug!("Dropping inbound stream because the peer is at its stream limit");
+            return;
+        }
+
         let mut codec = self.codec.clone();
         let request_id = self.next_inbound_request_id();
         let mut sender = self.inbound_sender.clone();
@@ -176,6 +216,7 @@ where
             )
             .is_err()
         {
+            self.release_inbound_stream();
             tracing::warn!("Dropping inbound stream because we are at capacity")
         }
     }
@@ -203,6 +244,7 @@ where
         let mut sender = self.outbound_sender.clone();
 
         let send_req_then_fwd_incoming_responses = async move {
+            let started = Instant::now();
             let write = codec.write_request(&protocol, &mut stream, message.request);
             write.await?;
 
@@ -214,10 +256,14 @@ where
                 .expect("`ConnectionHandler` owns both ends of the channel");
             drop(sender);
 
+            let mut stream = CountingReader::new(stream);
+            let mut responses = 0;
+
             // Keep on forwarding until the channel is closed or error occurs
             loop {
                 match codec.read_response(&protocol, &mut stream).await {
                     Ok(Some(response)) => {
+                        responses += 1;
                         rs_send
                             .send(response)
                             .await
@@ -230,7 +276,14 @@ where
                 }
             }
 
-            Ok(Event::InboundResponseStreamClosed(request_id))
+            Ok(Event::InboundResponseStreamClosed {
+                request_id,
+                stats: ResponseStreamStats {
+                    responses,
+                    bytes: stream.bytes,
+                    duration: started.elapsed(),
+                },
+            })
         };
 
         if self
@@ -316,7 +369,10 @@ where
     /// An outbound response stream to an inbound request was closed.
     OutboundResponseStreamClosed(InboundRequestId),
     /// An inbound response stream to an outbound request was closed.
-    InboundResponseStreamClosed(OutboundRequestId),
+    InboundResponseStreamClosed {
+        request_id: OutboundRequestId,
+        stats: ResponseStreamStats,
+    },
     /// An outbound request timed out while sending the request
     /// or waiting for the response.
     OutboundTimeout(OutboundRequestId),
@@ -353,9 +409,10 @@ impl<TCodec: Codec> fmt::Debug for Event<TCodec> {
                 .debug_struct("Event::OutboundRequestSentAwaitingResponses")
                 .field("request_id", request_id)
                 .finish(),
-            Event::InboundResponseStreamClosed(request_id) => f
+            Event::InboundResponseStreamClosed { request_id, stats } => f
                 .debug_struct("Event::InboundResponseStreamClosed")
                 .field("request_id", request_id)
+                .field("stats", stats)
                 .finish(),
             Event::OutboundResponseStreamClosed(request_id) => f
                 .debug_struct("Event::OutboundResponseStreamClosed")
@@ -387,6 +444,43 @@ impl<TCodec: Codec> fmt::Debug for Event<TCodec> {
     }
 }
 
+impl<TCodec> Drop for Handler<TCodec>
+where
+    TCodec: Codec,
+{
+    fn drop(&mut self) {
+        // Streams of a closed connection no longer count against the peer's limit.
+        self.peer_inbound_streams
+            .fetch_sub(self.inbound_streams, Ordering::Relaxed);
+    }
+}
+
+/// An [`AsyncRead`] which counts the bytes read from the inner stream.
+struct CountingReader<T> {
+    inner: T,
+    bytes: u64,
+}
+
+impl<T> CountingReader<T> {
+    fn new(inner: T) -> Self {
+        Self { inner, bytes: 0 }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for CountingReader<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let read = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = &read {
+            self.bytes += *n as u64;
+        }
+        read
+    }
+}
+
 pub struct OutboundMessage<TCodec: Codec> {
     pub(crate) request_id: OutboundRequestId,
     pub(crate) request: TCodec::Request,
@@ -431,7 +525,12 @@ where
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<ConnectionHandlerEvent<Protocol<TCodec::Protocol>, (), Self::ToBehaviour>> {
-        match self.worker_streams.poll_unpin(cx) {
+        let worker_stream = self.worker_streams.poll_unpin(cx);
+        if let Poll::Ready((RequestId::Inbound(_), _)) = &worker_stream {
+            self.release_inbound_stream();
+        }
+
+        match worker_stream {
             Poll::Ready((_, Ok(Ok(event)))) => {
                 return Poll::Ready(ConnectionHandlerEvent::NotifyBehaviour(event));
             }
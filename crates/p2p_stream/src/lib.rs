@@ -52,7 +52,7 @@
 mod codec;
 mod handler;
 
-pub use codec::Codec;
+pub use codec::{Codec, ProstCodec, DEFAULT_MAX_MESSAGE_SIZE};
 
 use crate::handler::OutboundMessage;
 use futures::channel::mpsc;
@@ -69,7 +69,10 @@ use smallvec::SmallVec;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     fmt, io,
-    sync::{atomic::AtomicU64, Arc},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize},
+        Arc,
+    },
     task::{Context, Poll},
     time::Duration,
 };
@@ -126,9 +129,23 @@ pub enum Event<TRequest, TResponse, TChannelResponse = TResponse> {
         peer: PeerId,
         /// The ID of the outbound request to which responses were received.
         request_id: OutboundRequestId,
+        /// Statistics of the responses received.
+        stats: ResponseStreamStats,
     },
 }
 
+/// Statistics of an inbound response stream to an outbound request, which
+/// allow callers to compare the throughput of peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseStreamStats {
+    /// The number of responses received.
+    pub responses: usize,
+    /// The number of bytes of the responses received, as read from the stream.
+    pub bytes: u64,
+    /// The time from opening the stream until the last response was received.
+    pub duration: Duration,
+}
+
 /// Possible failures occurring in the context of sending
 /// an outbound request and receiving the response.
 #[derive(Debug)]
@@ -231,6 +248,8 @@ impl fmt::Display for OutboundRequestId {
 pub struct Config {
     request_timeout: Duration,
     max_concurrent_streams: usize,
+    max_concurrent_inbound_streams_per_peer: usize,
+    max_concurrent_outbound_streams_per_peer: usize,
 }
 
 impl Default for Config {
@@ -238,6 +257,8 @@ impl Default for Config {
         Self {
             request_timeout: Duration::from_secs(10),
             max_concurrent_streams: 100,
+            max_concurrent_inbound_streams_per_peer: usize::MAX,
+            max_concurrent_outbound_streams_per_peer: usize::MAX,
         }
     }
 }
@@ -254,6 +275,25 @@ impl Config {
         self.max_concurrent_streams = num_streams;
         self
     }
+
+    /// Sets the upper bound for the number of concurrent inbound streams of a single peer,
+    /// across all of its connections. Inbound streams above the limit are dropped.
+    ///
+    /// Unlimited by default.
+    pub fn with_max_concurrent_inbound_streams_per_peer(mut self, num_streams: usize) -> Self {
+        self.max_concurrent_inbound_streams_per_peer = num_streams;
+        self
+    }
+
+    /// Sets the upper bound for the number of concurrent outbound streams to a single peer,
+    /// across all of its connections. Requests above the limit are queued until one of the
+    /// peer's response streams is closed or fails.
+    ///
+    /// Unlimited by default.
+    pub fn with_max_concurrent_outbound_streams_per_peer(mut self, num_streams: usize) -> Self {
+        self.max_concurrent_outbound_streams_per_peer = num_streams;
+        self
+    }
 }
 
 /// A request/streaming-response protocol for some message codec.
@@ -281,6 +321,12 @@ where
     /// Requests that have not yet been sent and are waiting for a connection
     /// to be established.
     pending_outbound_requests: HashMap<PeerId, SmallVec<[OutboundMessage<TCodec>; 10]>>,
+    /// Requests that have not yet been sent because the peer is at its limit of concurrent
+    /// outbound streams.
+    queued_outbound_requests: HashMap<PeerId, VecDeque<OutboundMessage<TCodec>>>,
+    /// The number of active inbound streams of each connected peer, shared by the handlers of
+    /// its connections.
+    inbound_streams: HashMap<PeerId, Arc<AtomicUsize>>,
 }
 
 impl<TCodec> Behaviour<TCodec>
@@ -315,6 +361,8 @@ where
             pending_events: VecDeque::new(),
             connected: HashMap::new(),
             pending_outbound_requests: HashMap::new(),
+            queued_outbound_requests: HashMap::new(),
+            inbound_streams: HashMap::new(),
         }
     }
 
@@ -322,7 +370,8 @@ where
     ///
     /// If the targeted peer is currently not connected, a dialing
     /// attempt is initiated and the request is sent as soon as a
-    /// connection is established.
+    /// connection is established. If the peer is at its limit of
+    /// concurrent outbound streams, the request is queued instead.
     ///
     /// > **Note**: In order for such a dialing attempt to succeed,
     /// > the `RequestResponse` protocol must be embedded
@@ -337,15 +386,13 @@ where
             protocols: self.protocols.clone(),
         };
 
-        if let Some(request) = self.try_send_request(peer, request) {
-            self.pending_events.push_back(ToSwarm::Dial {
-                opts: DialOpts::peer_id(*peer).build(),
-            });
-
-            self.pending_outbound_requests
+        if self.outbound_streams(peer) >= self.config.max_concurrent_outbound_streams_per_peer {
+            self.queued_outbound_requests
                 .entry(*peer)
                 .or_default()
-                .push(request);
+                .push_back(request);
+        } else {
+            self.send_or_dial(peer, request);
         }
 
         request_id
@@ -360,6 +407,79 @@ where
         }
     }
 
+    /// Sends a request to a connected peer, or dials the peer and sends the request once the
+    /// connection is established.
+    fn send_or_dial(&mut self, peer: &PeerId, request: OutboundMessage<TCodec>) {
+        if let Some(request) = self.try_send_request(peer, request) {
+            self.pending_events.push_back(ToSwarm::Dial {
+                opts: DialOpts::peer_id(*peer).build(),
+            });
+
+            self.pending_outbound_requests
+                .entry(*peer)
+                .or_default()
+                .push(request);
+        }
+    }
+
+    /// Returns the number of outbound streams to the peer which count against its limit, i.e.
+    /// requests waiting for a connection and streams which have not finished yet.
+    fn outbound_streams(&self, peer: &PeerId) -> usize {
+        let pending = self
+            .pending_outbound_requests
+            .get(peer)
+            .map_or(0, |requests| requests.len());
+        let active = self.connected.get(peer).map_or(0, |connections| {
+            connections.iter().map(|c| c.outbound_streams.len()).sum()
+        });
+
+        pending + active
+    }
+
+    /// Marks the outbound stream of the request as finished and sends queued requests to the
+    /// peer, if any.
+    fn on_outbound_stream_finished(
+        &mut self,
+        peer: &PeerId,
+        connection: ConnectionId,
+        request: OutboundRequestId,
+    ) {
+        if let Some(connection) = self.get_connection_mut(peer, connection) {
+            connection.outbound_streams.remove(&request);
+        }
+
+        self.send_queued_requests(peer);
+    }
+
+    /// Sends queued requests to the peer while it is below its limit of concurrent outbound
+    /// streams.
+    fn send_queued_requests(&mut self, peer: &PeerId) {
+        while self.outbound_streams(peer) < self.config.max_concurrent_outbound_streams_per_peer {
+            let Some(queue) = self.queued_outbound_requests.get_mut(peer) else {
+                return;
+            };
+            let Some(request) = queue.pop_front() else {
+                self.queued_outbound_requests.remove(peer);
+                return;
+            };
+
+            self.send_or_dial(peer, request);
+        }
+    }
+
+    /// Creates the [`Handler`] of a new connection to the peer.
+    fn new_handler(&mut self, peer: PeerId) -> Handler<TCodec> {
+        Handler::new(
+            self.protocols.clone(),
+            self.codec.clone(),
+            self.config.request_timeout,
+            self.next_inbound_request_id.clone(),
+            self.config.max_concurrent_streams,
+            self.inbound_streams.entry(peer).or_default().clone(),
+            self.config.max_concurrent_inbound_streams_per_peer,
+        )
+    }
+
     /// Returns the next outbound request ID.
     fn next_outbound_request_id(&mut self) -> OutboundRequestId {
         let request_id = self.next_outbound_request_id;
@@ -383,6 +503,7 @@ where
             let conn = &mut connections[ix];
             conn.pending_outbound_response_streams
                 .insert(request.request_id);
+            conn.outbound_streams.insert(request.request_id);
             self.pending_events.push_back(ToSwarm::NotifyHandler {
                 peer_id: *peer,
                 handler: NotifyHandler::One(conn.id),
@@ -486,6 +607,7 @@ where
         debug_assert_eq!(connections.is_empty(), remaining_established == 0);
         if connections.is_empty() {
             self.connected.remove(&peer_id);
+            self.inbound_streams.remove(&peer_id);
         }
 
         for request_id in connection.pending_inbound_response_streams {
@@ -505,6 +627,9 @@ where
                     error: OutboundFailure::ConnectionClosed,
                 }));
         }
+
+        // The streams of the closed connection no longer count against the peer's limit.
+        self.send_queued_requests(&peer_id);
     }
 
     fn on_dial_failure(&mut self, DialFailure { peer_id, .. }: DialFailure<'_>) {
@@ -515,15 +640,24 @@ where
             // only created when a peer is not connected when a request is made.
             // Thus these requests must be considered failed, even if there is
             // another, concurrent dialing attempt ongoing.
-            if let Some(pending) = self.pending_outbound_requests.remove(&peer) {
-                for request in pending {
-                    self.pending_events
-                        .push_back(ToSwarm::GenerateEvent(Event::OutboundFailure {
-                            peer,
-                            request_id: request.request_id,
-                            error: OutboundFailure::DialFailure,
-                        }));
-                }
+            // Queued requests would have to dial the peer as well, so they fail too.
+            let pending = self
+                .pending_outbound_requests
+                .remove(&peer)
+                .into_iter()
+                .flatten();
+            let queued = self
+                .queued_outbound_requests
+                .remove(&peer)
+                .into_iter()
+                .flatten();
+            for request in pending.chain(queued) {
+                self.pending_events
+                    .push_back(ToSwarm::GenerateEvent(Event::OutboundFailure {
+                        peer,
+                        request_id: request.request_id,
+                        error: OutboundFailure::DialFailure,
+                    }));
             }
         }
     }
@@ -543,6 +677,7 @@ where
                 connection
                     .pending_outbound_response_streams
                     .insert(request.request_id);
+                connection.outbound_streams.insert(request.request_id);
                 handler.on_behaviour_event(request);
             }
         }
@@ -565,13 +700,7 @@ where
         _: &Multiaddr,
         _: &Multiaddr,
     ) -> Result<THandler<Self>, ConnectionDenied> {
-        let mut handler = Handler::new(
-            self.protocols.clone(),
-            self.codec.clone(),
-            self.config.request_timeout,
-            self.next_inbound_request_id.clone(),
-            self.config.max_concurrent_streams,
-        );
+        let mut handler = self.new_handler(peer);
 
         self.preload_new_handler(&mut handler, peer, connection_id, None);
 
@@ -605,13 +734,7 @@ where
         remote_address: &Multiaddr,
         _: Endpoint,
     ) -> Result<THandler<Self>, ConnectionDenied> {
-        let mut handler = Handler::new(
-            self.protocols.clone(),
-            self.codec.clone(),
-            self.config.request_timeout,
-            self.next_inbound_request_id.clone(),
-            self.config.max_concurrent_streams,
-        );
+        let mut handler = self.new_handler(peer);
 
         self.preload_new_handler(
             &mut handler,
@@ -697,7 +820,7 @@ where
                     Event::OutboundResponseStreamClosed { peer, request_id },
                 ));
             }
-            handler::Event::InboundResponseStreamClosed(request_id) => {
+            handler::Event::InboundResponseStreamClosed { request_id, stats } => {
                 let removed =
                     self.remove_pending_outbound_response_stream(&peer, connection, request_id);
 
@@ -705,13 +828,19 @@ where
                     !removed,
                     "Expect request_id to have been removed from pending because the response channel has already been available."
                 );
+                self.on_outbound_stream_finished(&peer, connection, request_id);
 
                 self.pending_events.push_back(ToSwarm::GenerateEvent(
-                    Event::InboundResponseStreamClosed { peer, request_id },
+                    Event::InboundResponseStreamClosed {
+                        peer,
+                        request_id,
+                        stats,
+                    },
                 ));
             }
             handler::Event::OutboundTimeout(request_id) => {
                 self.remove_pending_outbound_response_stream(&peer, connection, request_id);
+                self.on_outbound_stream_finished(&peer, connection, request_id);
 
                 self.pending_events
                     .push_back(ToSwarm::GenerateEvent(Event::OutboundFailure {
@@ -727,6 +856,7 @@ where
                     removed,
                     "Expect request_id to be pending before failing to connect.",
                 );
+                self.on_outbound_stream_finished(&peer, connection, request_id);
 
                 self.pending_events
                     .push_back(ToSwarm::GenerateEvent(Event::OutboundFailure {
@@ -737,6 +867,7 @@ where
             }
             handler::Event::OutboundStreamFailed { request_id, error } => {
                 self.remove_pending_outbound_response_stream(&peer, connection, request_id);
+                self.on_outbound_stream_finished(&peer, connection, request_id);
 
                 self.pending_events
                     .push_back(ToSwarm::GenerateEvent(Event::OutboundFailure {
@@ -810,6 +941,10 @@ struct Connection {
     /// Pending inbound responses for previously sent requests on this
     /// connection.
     pending_inbound_response_streams: HashSet<InboundRequestId>,
+    /// Requests sent on this connection whose response streams have not
+    /// finished yet. These count against the peer's limit of concurrent
+    /// outbound streams.
+    outbound_streams: HashSet<OutboundRequestId>,
 }
 
 impl Connection {
@@ -819,6 +954,7 @@ impl Connection {
             remote_address,
             pending_outbound_response_streams: Default::default(),
             pending_inbound_response_streams: Default::default(),
+            outbound_streams: Default::default(),
         }
     }
 }
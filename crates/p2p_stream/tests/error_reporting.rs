@@ -202,7 +202,7 @@ async fn report_inbound_closure_on_read_request_failure() {
 
         assert!(resp_rx.next().await.is_none());
 
-        let (peer, req_id_done) = wait_inbound_response_stream_closed(&mut swarm2)
+        let (peer, req_id_done, _) = wait_inbound_response_stream_closed(&mut swarm2)
             .await
             .unwrap();
         assert_eq!(peer, peer1_id);
@@ -319,7 +319,7 @@ async fn report_inbound_timeout_on_write_response_timeout() {
 
         assert!(resp_channel.next().await.is_none());
 
-        let (peer, req_id_done) = wait_inbound_response_stream_closed(&mut swarm2)
+        let (peer, req_id_done, _) = wait_inbound_response_stream_closed(&mut swarm2)
             .await
             .unwrap();
 
@@ -0,0 +1,118 @@
+use futures::io::Cursor;
+use libp2p::swarm::StreamProtocol;
+use p2p_stream::{Codec, ProstCodec};
+use std::io;
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Message {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    payload: Vec<u8>,
+}
+
+type TestCodec = ProstCodec<Message, Message>;
+
+const PROTOCOL: StreamProtocol = StreamProtocol::new("/test/1");
+
+fn message(payload_len: usize) -> Message {
+    Message {
+        id: 42,
+        payload: vec![0xAB; payload_len],
+    }
+}
+
+#[tokio::test]
+async fn round_trip() {
+    let mut codec = TestCodec::default();
+    let mut io = Cursor::new(Vec::new());
+
+    codec
+        .write_request(&PROTOCOL, &mut io, message(10))
+        .await
+        .unwrap();
+    codec
+        .write_response(&PROTOCOL, &mut io, message(1000))
+        .await
+        .unwrap();
+    codec
+        .write_response(&PROTOCOL, &mut io, Message::default())
+        .await
+        .unwrap();
+
+    io.set_position(0);
+    assert_eq!(
+        codec.read_request(&PROTOCOL, &mut io).await.unwrap(),
+        message(10)
+    );
+    assert_eq!(
+        codec.read_response(&PROTOCOL, &mut io).await.unwrap(),
+        Some(message(1000))
+    );
+    assert_eq!(
+        codec.read_response(&PROTOCOL, &mut io).await.unwrap(),
+        Some(Message::default())
+    );
+    // The stream is closed gracefully.
+    assert_eq!(codec.read_response(&PROTOCOL, &mut io).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn missing_request_is_an_error() {
+    let mut codec = TestCodec::default();
+    let mut io = Cursor::new(Vec::new());
+
+    let error = codec.read_request(&PROTOCOL, &mut io).await.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn truncated_message_is_an_error() {
+    let mut codec = TestCodec::default();
+    let mut io = Cursor::new(Vec::new());
+    codec
+        .write_response(&PROTOCOL, &mut io, message(100))
+        .await
+        .unwrap();
+
+    let mut bytes = io.into_inner();
+    bytes.pop();
+
+    let error = codec
+        .read_response(&PROTOCOL, &mut Cursor::new(bytes))
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn oversized_messages_are_rejected() {
+    let mut large = TestCodec::default();
+    let mut small = TestCodec::new(100);
+    let mut io = Cursor::new(Vec::new());
+
+    let error = small
+        .write_request(&PROTOCOL, &mut io, message(100))
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(io.get_ref().is_empty());
+
+    large
+        .write_response(&PROTOCOL, &mut io, message(100))
+        .await
+        .unwrap();
+    io.set_position(0);
+
+    let error = small.read_response(&PROTOCOL, &mut io).await.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn overlong_length_prefix_is_rejected() {
+    let mut codec = TestCodec::default();
+    let mut io = Cursor::new(vec![0xFF; 11]);
+
+    let error = codec.read_response(&PROTOCOL, &mut io).await.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
@@ -139,12 +139,15 @@ async fn sanity(
             );
         }
 
-        let (peer, req_id_done) = wait_inbound_response_stream_closed(&mut requester.swarm)
+        let (peer, req_id_done, stats) = wait_inbound_response_stream_closed(&mut requester.swarm)
             .await
             .unwrap();
 
         assert_eq!(peer, responder.peer_id);
         assert_eq!(req_id_done, req_id);
+        assert_eq!(stats.responses, num_responses);
+        // Each response is encoded as a `u32`.
+        assert_eq!(stats.bytes, 4 * num_responses as u64);
     };
 
     tokio::join!(responder_task, requester_task);
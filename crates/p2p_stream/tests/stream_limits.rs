@@ -0,0 +1,136 @@
+use futures::prelude::*;
+use libp2p_swarm_test::SwarmExt;
+use std::time::Duration;
+
+pub mod utils;
+
+use utils::{
+    new_swarm_with_config, wait_inbound_request, wait_inbound_response_stream_closed,
+    wait_outbound_request_sent_awaiting_responses, wait_outbound_response_stream_closed, Action,
+    TestSwarm,
+};
+
+async fn setup(srv_cfg: p2p_stream::Config, cli_cfg: p2p_stream::Config) -> (TestSwarm, TestSwarm) {
+    let (_, mut srv_swarm) = new_swarm_with_config(srv_cfg);
+    let (_, mut cli_swarm) = new_swarm_with_config(cli_cfg);
+
+    srv_swarm.listen().with_memory_addr_external().await;
+    cli_swarm.connect(&mut srv_swarm).await;
+
+    (srv_swarm, cli_swarm)
+}
+
+fn config() -> p2p_stream::Config {
+    p2p_stream::Config::default().with_request_timeout(Duration::from_secs(10))
+}
+
+#[tokio::test]
+async fn outbound_requests_above_limit_are_queued() {
+    let (mut srv_swarm, mut cli_swarm) = setup(
+        config(),
+        config().with_max_concurrent_outbound_streams_per_peer(1),
+    )
+    .await;
+    let srv_peer_id = *srv_swarm.local_peer_id();
+
+    let server_task = async move {
+        let (_, _, _, resp_tx) = wait_inbound_request(&mut srv_swarm).await.unwrap();
+
+        // The second request is held back by the client while the first stream is open.
+        let second = tokio::time::timeout(
+            Duration::from_millis(500),
+            wait_inbound_request(&mut srv_swarm),
+        )
+        .await;
+        assert!(second.is_err(), "Unexpected second request");
+
+        drop(resp_tx);
+        wait_outbound_response_stream_closed(&mut srv_swarm)
+            .await
+            .unwrap();
+
+        let (_, _, _, resp_tx) = wait_inbound_request(&mut srv_swarm).await.unwrap();
+        drop(resp_tx);
+        wait_outbound_response_stream_closed(&mut srv_swarm)
+            .await
+            .unwrap();
+    };
+
+    let client_task = async move {
+        let first = cli_swarm
+            .behaviour_mut()
+            .send_request(&srv_peer_id, Action::SanityRequest);
+        let second = cli_swarm
+            .behaviour_mut()
+            .send_request(&srv_peer_id, Action::SanityRequest);
+
+        for request_id in [first, second] {
+            let (_, id, mut resp_rx) =
+                wait_outbound_request_sent_awaiting_responses(&mut cli_swarm)
+                    .await
+                    .unwrap();
+            assert_eq!(id, request_id);
+
+            let (_, id, _) = wait_inbound_response_stream_closed(&mut cli_swarm)
+                .await
+                .unwrap();
+            assert_eq!(id, request_id);
+            assert!(resp_rx.next().await.is_none());
+        }
+    };
+
+    tokio::join!(server_task, client_task);
+}
+
+#[tokio::test]
+async fn inbound_streams_above_limit_are_dropped() {
+    let (mut srv_swarm, mut cli_swarm) = setup(
+        config().with_max_concurrent_inbound_streams_per_peer(1),
+        config(),
+    )
+    .await;
+    let srv_peer_id = *srv_swarm.local_peer_id();
+
+    let server_task = async move {
+        let (_, _, _, resp_tx) = wait_inbound_request(&mut srv_swarm).await.unwrap();
+
+        let second = tokio::time::timeout(
+            Duration::from_millis(500),
+            wait_inbound_request(&mut srv_swarm),
+        )
+        .await;
+        assert!(second.is_err(), "Unexpected second request");
+
+        drop(resp_tx);
+    };
+
+    let client_task = async move {
+        let requests = [
+            cli_swarm
+                .behaviour_mut()
+                .send_request(&srv_peer_id, Action::SanityRequest),
+            cli_swarm
+                .behaviour_mut()
+                .send_request(&srv_peer_id, Action::SanityRequest),
+        ];
+
+        // The dropped stream ends without any responses, while the other one stays open.
+        loop {
+            match cli_swarm
+                .select_next_some()
+                .await
+                .try_into_behaviour_event()
+            {
+                Ok(p2p_stream::Event::InboundResponseStreamClosed { request_id, .. })
+                | Ok(p2p_stream::Event::OutboundFailure { request_id, .. }) => {
+                    assert!(requests.contains(&request_id));
+                    break;
+                }
+                Ok(p2p_stream::Event::OutboundRequestSentAwaitingResponses { .. }) | Err(_) => {}
+                Ok(event) => panic!("Unexpected event: {event:?}"),
+            }
+        }
+    };
+
+    tokio::join!(server_task, client_task);
+}
@@ -8,7 +8,10 @@ use libp2p::core::upgrade::Version;
 use libp2p::identity::{Keypair, PeerId};
 use libp2p::swarm::{self, NetworkBehaviour, StreamProtocol, Swarm};
 use libp2p::{yamux, Transport};
-use p2p_stream::{Codec, InboundFailure, InboundRequestId, OutboundFailure, OutboundRequestId};
+use p2p_stream::{
+    Codec, InboundFailure, InboundRequestId, OutboundFailure, OutboundRequestId,
+    ResponseStreamStats,
+};
 use std::fmt::Debug;
 use std::time::Duration;
 use std::{io, iter};
@@ -216,9 +219,14 @@ where
 
 pub fn new_swarm_with_timeout(
     timeout: Duration,
+) -> (PeerId, Swarm<p2p_stream::Behaviour<TestCodec>>) {
+    new_swarm_with_config(p2p_stream::Config::default().with_request_timeout(timeout))
+}
+
+pub fn new_swarm_with_config(
+    cfg: p2p_stream::Config,
 ) -> (PeerId, Swarm<p2p_stream::Behaviour<TestCodec>>) {
     let protocols = iter::once(StreamProtocol::new("/test/1"));
-    let cfg = p2p_stream::Config::default().with_request_timeout(timeout);
 
     // SwarmExt::new_ephemeral uses async::std
     let swarm = new_ephemeral_with_tokio_executor(|_| {
@@ -297,13 +305,15 @@ pub async fn wait_outbound_response_stream_closed(
 
 pub async fn wait_inbound_response_stream_closed(
     swarm: &mut Swarm<p2p_stream::Behaviour<TestCodec>>,
-) -> Result<(PeerId, OutboundRequestId)> {
+) -> Result<(PeerId, OutboundRequestId, ResponseStreamStats)> {
     loop {
         match swarm.select_next_some().await.try_into_behaviour_event() {
             Ok(p2p_stream::Event::InboundResponseStreamClosed {
-                peer, request_id, ..
+                peer,
+                request_id,
+                stats,
             }) => {
-                return Ok((peer, request_id));
+                return Ok((peer, request_id, stats));
             }
             Ok(ev) => bail!("Unexpected event: {ev:?}"),
             Err(..) => {}
@@ -0,0 +1,70 @@
+//! Reports the alerts of the monitors, e.g. [stall_alert](crate::stall_alert) and
+//! [head_divergence](crate::head_divergence).
+//!
+//! Each alert sets a gauge labelled with what it is about to 1 while it is raised and to 0 once it
+//! is cleared, and is posted as JSON to an optional webhook.
+use std::time::Duration;
+
+use anyhow::Context;
+
+/// The timeout of webhook requests, so that an unresponsive webhook doesn't delay later alerts.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) struct Alerts {
+    /// The name of the gauge.
+    gauge: &'static str,
+    /// The name of the gauge's label.
+    label: &'static str,
+    webhook: Option<(reqwest::Client, reqwest::Url)>,
+}
+
+impl Alerts {
+    /// Clears the gauges of all `values` of the label.
+    pub(crate) fn new(
+        gauge: &'static str,
+        label: &'static str,
+        values: &[&'static str],
+        webhook: Option<reqwest::Url>,
+    ) -> anyhow::Result<Self> {
+        let webhook = match webhook {
+            Some(url) => {
+                let client = reqwest::Client::builder()
+                    .user_agent(pathfinder_common::consts::USER_AGENT)
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()
+                    .context("Creating webhook client")?;
+                Some((client, url))
+            }
+            None => None,
+        };
+
+        for &value in values {
+            metrics::gauge!(gauge, 0.0, label => value);
+        }
+
+        Ok(Self {
+            gauge,
+            label,
+            webhook,
+        })
+    }
+
+    /// Raises or clears the alert about `value`, and posts its `body` to the webhook.
+    pub(crate) async fn report<T: serde::Serialize>(
+        &self,
+        value: &'static str,
+        raised: bool,
+        body: &T,
+    ) {
+        let gauge = if raised { 1.0 } else { 0.0 };
+        metrics::gauge!(self.gauge, gauge, self.label => value);
+
+        if let Some((client, url)) = &self.webhook {
+            // Failing to page must not stop the monitoring.
+            let response = client.post(url.clone()).json(body).send().await;
+            if let Err(error) = response.and_then(|r| r.error_for_status()) {
+                tracing::warn!(alert=%self.gauge, %value, %error, "Calling alert webhook failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! The `database analyze` command which reports on the database's storage and access patterns.
+
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+use anyhow::Context;
+use pathfinder_storage::{DatabaseAnalysis, SlowQueryStats, StorageManager};
+
+use crate::config::Config;
+
+const MIB: u64 = 1024 * 1024;
+/// The share of free pages above which vacuuming is suggested.
+const FREE_PAGES_PERCENT: u64 = 10;
+/// The number of slow queries which are reported.
+const REPORTED_SLOW_QUERIES: usize = 10;
+/// The trie tables, in the main database unless split into a separate one.
+const TRIE_TABLES: [&str; 3] = ["trie_class", "trie_contracts", "trie_storage"];
+
+/// Runs `ANALYZE` on the database and prints a report with suggestions to stdout.
+pub async fn run(storage_manager: StorageManager, config: &Config) -> anyhow::Result<()> {
+    let storage = storage_manager
+        .create_pool(NonZeroU32::new(1).unwrap())
+        .context("Creating database connection pool for analysis")?;
+
+    tracing::info!("Analyzing database, this can take a while");
+    let analysis = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+        let mut db = storage.connection()?;
+        let tx = db.transaction()?;
+        let analysis = tx.analyze()?;
+        // Keep the statistics of the query planner.
+        tx.commit()?;
+        Ok(analysis)
+    })
+    .await
+    .context("Joining analyze task")??;
+
+    print_report(&analysis);
+
+    let suggestions = suggestions(&analysis, config);
+    if !suggestions.is_empty() {
+        println!("\nSuggestions:");
+        for suggestion in suggestions {
+            println!("- {suggestion}");
+        }
+    }
+
+    Ok(())
+}
+
+fn print_report(analysis: &DatabaseAnalysis) {
+    let size = analysis.page_count * analysis.page_size;
+    let free = analysis.free_pages * analysis.page_size;
+    println!(
+        "Database: {} MiB, of which {} MiB are unused",
+        size / MIB,
+        free / MIB
+    );
+
+    let bloated = analysis
+        .indexes
+        .iter()
+        .filter(|index| index.is_bloated())
+        .collect::<Vec<_>>();
+    if !bloated.is_empty() {
+        println!("\nBloated indexes, with more than half of their space unused:");
+        for index in bloated {
+            println!(
+                "  {} on {}: {} MiB, {} MiB unused",
+                index.name,
+                index.table,
+                index.bytes / MIB,
+                index.unused_bytes / MIB
+            );
+        }
+    }
+
+    let unselective = analysis
+        .indexes
+        .iter()
+        .filter(|index| index.is_unselective())
+        .collect::<Vec<_>>();
+    if !unselective.is_empty() {
+        println!("\nIndexes which the query planner is unlikely to use, as they match more than half their table's rows:");
+        for index in unselective {
+            println!(
+                "  {} on {}: {} MiB",
+                index.name,
+                index.table,
+                index.bytes / MIB
+            );
+        }
+    }
+
+    if !analysis.slow_queries.is_empty() {
+        println!("\nSlowest queries, by total time:");
+        for (name, stats) in slowest_queries(&analysis.slow_queries)
+            .iter()
+            .take(REPORTED_SLOW_QUERIES)
+        {
+            println!(
+                "  {name}: {} executions, {:?} total, {:?} average, {:?} max",
+                stats.count,
+                stats.total,
+                stats.average(),
+                stats.max
+            );
+        }
+    }
+}
+
+fn slowest_queries(queries: &[(String, SlowQueryStats)]) -> Vec<(String, SlowQueryStats)> {
+    let mut queries = queries.to_vec();
+    queries.sort_by(|a, b| b.1.total.cmp(&a.1.total));
+    queries
+}
+
+fn suggestions(analysis: &DatabaseAnalysis, config: &Config) -> Vec<String> {
+    let mut suggestions = Vec::new();
+
+    let bloated = analysis.indexes.iter().any(|index| index.is_bloated());
+    if analysis.free_pages * 100 > analysis.page_count * FREE_PAGES_PERCENT || bloated {
+        let mut suggestion = "Run 'pathfinder database vacuum' to return unused space to the file system and rebuild bloated indexes.".to_owned();
+        if config.auto_vacuum_window.is_none() {
+            suggestion.push_str(
+                " Afterwards, set '--storage.auto-vacuum-window' to keep returning freed space.",
+            );
+        }
+        suggestions.push(suggestion);
+    }
+
+    let database_bytes = analysis.page_count * analysis.page_size;
+    let trie_bytes: u64 = analysis
+        .tables
+        .iter()
+        .filter(|table| TRIE_TABLES.contains(&table.name.as_str()))
+        .map(|table| table.bytes)
+        .sum();
+    if config.trie_database.is_none() && trie_bytes * 2 > database_bytes {
+        suggestions.push(format!(
+            "The merkle tries take up {}% of the database. Consider moving them into a separate file on a faster disk using 'pathfinder database split-tries' and '--storage.trie-database'.",
+            trie_bytes * 100 / database_bytes
+        ));
+    }
+
+    let slow_time = |trie: bool| {
+        analysis
+            .slow_queries
+            .iter()
+            .filter(|(name, _)| !trie || name.contains("trie") || name.contains("root"))
+            .map(|(_, stats)| stats.total)
+            .sum::<Duration>()
+    };
+    if !slow_time(false).is_zero() && slow_time(true) * 2 > slow_time(false) {
+        suggestions.push("Most of the time of slow queries is spent reading the merkle tries. Consider a larger '--storage.cache-size' or '--storage.profile performance' to keep more of them in memory.".to_owned());
+    }
+
+    if analysis.slow_queries.is_empty() {
+        suggestions.push("No slow queries were recorded. Run the node with '--storage.slow-query-threshold' to record the queries which are slow under your workload, and analyze again.".to_owned());
+    }
+
+    suggestions
+}
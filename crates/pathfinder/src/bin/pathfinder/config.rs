@@ -1,8 +1,12 @@
 use clap::{CommandFactory, Parser};
 #[cfg(feature = "p2p")]
 use p2p::libp2p::Multiaddr;
-use pathfinder_common::AllowedOrigins;
-use pathfinder_storage::JournalMode;
+use pathfinder_common::{AllowedOrigins, BlockHash, GasPrice};
+use pathfinder_lib::block_stream::Output as BlockStreamOutput;
+use pathfinder_lib::head_divergence::DivergenceConfig;
+use pathfinder_lib::stall_alert::StallThresholds;
+use pathfinder_lib::vacuum::VacuumWindow;
+use pathfinder_storage::{JournalMode, SqliteTuning};
 use reqwest::Url;
 use std::collections::HashSet;
 use std::net::SocketAddr;
@@ -19,7 +23,13 @@ use pathfinder_common::consts::VERGEN_GIT_DESCRIBE;
     about = "A Starknet node implemented by Equilibrium Labs. Submit bug reports and issues at https://github.com/eqlabs/pathfinder."
 )]
 struct Cli {
+    /// The node is run if no command is given. The options are shared by all commands, and can be
+    /// given before or after the command.
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(
+        global = true,
         long,
         value_name = "DIR", 
         value_hint = clap::ValueHint::DirPath,
@@ -30,6 +40,7 @@ struct Cli {
     data_directory: PathBuf,
 
     #[arg(
+        global = true,
         long = "ethereum.password",
         long_help = "The optional password to use for the Ethereum API",
         value_name = None,
@@ -38,9 +49,12 @@ struct Cli {
     ethereum_password: Option<String>,
 
     #[arg(
+        global = true,
         long = "ethereum.url",
         long_help = r"This should point to the HTTP RPC endpoint of your Ethereum entry-point, typically a local Ethereum client or a hosted gateway service such as Infura or Cloudflare.
 
+Required unless running with '--network devnet' or '--fork.block'.
+
 Examples:
     infura: https://goerli.infura.io/v3/<PROJECT_ID>
     geth:   https://localhost:8545",
@@ -48,9 +62,45 @@ Examples:
         value_hint = clap::ValueHint::Url,
         env = "PATHFINDER_ETHEREUM_API_URL", 
     )]
-    ethereum_url: Url,
+    ethereum_url: Option<Url>,
+
+    #[arg(
+        global = true,
+        long = "ethereum.beacon-url",
+        long_help = r"The HTTP API endpoint of an Ethereum beacon node, used to read the state diffs which Starknet posts to Ethereum as blobs.
+
+If set, the state diff of each state update accepted on Ethereum is checked against the local state, and mismatches are logged.",
+        value_name = "HTTP(s) URL",
+        value_hint = clap::ValueHint::Url,
+        env = "PATHFINDER_ETHEREUM_BEACON_URL",
+    )]
+    ethereum_beacon_url: Option<Url>,
+
+    #[arg(
+        global = true,
+        long = "ethereum.ws-url",
+        long_help = r"The WebSocket RPC endpoint of the Ethereum entry-point given by '--ethereum.url'.
+
+If set, new Ethereum blocks are subscribed to using 'eth_subscribe', and the Starknet state on Ethereum is read on each new block instead of being polled. This lowers the latency of L1 updates and the number of requests to the Ethereum entry-point. Polling is resumed while the subscription fails.",
+        value_name = "WS(s) URL",
+        value_hint = clap::ValueHint::Url,
+        env = "PATHFINDER_ETHEREUM_WS_URL",
+    )]
+    ethereum_ws_url: Option<Url>,
+
+    #[arg(
+        global = true,
+        long = "ethereum.confirmations",
+        long_help = r"The number of Ethereum blocks a Starknet state update must be below the latest Ethereum block before its blocks are marked as accepted on L1.
+
+By default, state updates are accepted once their Ethereum block is finalized by the beacon chain.",
+        value_name = "BLOCKS",
+        env = "PATHFINDER_ETHEREUM_CONFIRMATIONS"
+    )]
+    ethereum_confirmations: Option<u64>,
 
     #[arg(
+        global = true,
         long = "http-rpc",
         long_help = "HTTP-RPC listening address",
         value_name = "IP:PORT",
@@ -60,6 +110,7 @@ Examples:
     rpc_address: SocketAddr,
 
     #[arg(
+        global = true,
         long = "rpc.cors-domains",
         long_help = r"Comma separated list of domains from which Cross-Origin requests will be accepted by the RPC server.
 
@@ -76,6 +127,85 @@ Examples:
     rpc_cors_domains: Vec<String>,
 
     #[arg(
+        global = true,
+        long = "rpc.signing-key-file",
+        long_help = r"Path to the ed25519 key file with which RPC responses are signed, as created by 'pathfinder signing-key generate'.
+
+If set, each JSON response carries the hex encoded signature of the SHA-256 hash of the request body followed by the SHA-256 hash of the response body in the 'pathfinder-signature' header, and the hex encoded public key in the 'pathfinder-public-key' header. Clients which trust this node's public key can use these to verify that responses came from this node.",
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+        env = "PATHFINDER_RPC_SIGNING_KEY_FILE"
+    )]
+    rpc_signing_key_file: Option<PathBuf>,
+
+    #[arg(
+        global = true,
+        long = "rpc.auth-jwt-secret",
+        long_help = r"Requires RPC requests to be authenticated with a bearer token, which may be a JWT signed with this secret using HS256.
+
+The JWT must have an 'exp' claim, and may have a 'methods' claim listing the methods it may call. Method names ending in '*' match all methods with that prefix, e.g. 'starknet_*'. All methods may be called if the claim is missing.
+
+Prefer passing the secret using the environment variable.",
+        value_name = "SECRET",
+        env = "PATHFINDER_RPC_AUTH_JWT_SECRET"
+    )]
+    rpc_auth_jwt_secret: Option<String>,
+
+    #[arg(
+        global = true,
+        long = "rpc.auth-tokens-file",
+        long_help = r#"Requires RPC requests to be authenticated with a bearer token, which may be one of the static tokens in this file.
+
+The file maps each token to the list of methods it may call, with the same patterns as for '--rpc.auth-jwt-secret'.
+
+Example:
+    {
+        "read-only-token": ["starknet_get*", "starknet_call"],
+        "trace-token": ["starknet_*"]
+    }"#,
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+        env = "PATHFINDER_RPC_AUTH_TOKENS_FILE"
+    )]
+    rpc_auth_tokens_file: Option<PathBuf>,
+
+    #[arg(
+        global = true,
+        long = "rpc.allow-methods",
+        long_help = r"Comma separated list of the methods served at the '--http-rpc' address. All methods are served if empty.
+
+Method names ending in '*' match all methods with that prefix, e.g. 'starknet_get*'. The method groups 'write' (methods which submit transactions), 'trace' (methods which trace or simulate transactions), 'admin' (methods which expose the node's diagnostics, e.g. 'pathfinder_getBlockSyncTimings') and 'read' (all other methods) are also supported.
+
+Examples:
+    reads only:    read
+    with tracing:  read,trace",
+        value_name = "METHOD LIST",
+        value_delimiter = ',',
+        env = "PATHFINDER_RPC_ALLOW_METHODS"
+    )]
+    rpc_allow_methods: Vec<String>,
+
+    #[arg(
+        global = true,
+        long = "rpc.deny-methods",
+        long_help = "Comma separated list of the methods not served at the '--http-rpc' address, even if allowed by '--rpc.allow-methods'. Supports the same patterns.",
+        value_name = "METHOD LIST",
+        value_delimiter = ',',
+        env = "PATHFINDER_RPC_DENY_METHODS"
+    )]
+    rpc_deny_methods: Vec<String>,
+
+    #[arg(
+        global = true,
+        long = "rpc.internal-address",
+        long_help = "Additional HTTP-RPC listening address which serves all methods, regardless of '--rpc.allow-methods' and '--rpc.deny-methods'. Intended for internal networks, while the '--http-rpc' address is public.",
+        value_name = "IP:PORT",
+        env = "PATHFINDER_RPC_INTERNAL_ADDRESS"
+    )]
+    rpc_internal_address: Option<SocketAddr>,
+
+    #[arg(
+        global = true,
         long = "rpc.root-version",
         long_help = "Version of the JSON-RPC API to serve on the / (root) path",
         default_value = "v04",
@@ -84,6 +214,7 @@ Examples:
     rpc_root_version: RpcVersion,
 
     #[arg(
+        global = true,
         long = "rpc.execution-concurrency",
         long_help = "The number of Cairo VM executors that can work concurrently. Defaults to the number of CPU cores available.",
         env = "PATHFINDER_RPC_EXECUTION_CONCURRENCY"
@@ -91,6 +222,7 @@ Examples:
     execution_concurrency: Option<std::num::NonZeroU32>,
 
     #[arg(
+        global = true,
         long = "monitor-address",
         long_help = "The address at which pathfinder will serve monitoring related information",
         value_name = "IP:PORT",
@@ -98,12 +230,42 @@ Examples:
     )]
     monitor_address: Option<SocketAddr>,
 
+    #[arg(
+        global = true,
+        long = "monitor.heap-profiling",
+        long_help = "Samples heap allocations from the start, so that heap profiles can be downloaded from '/debug/heap_profile' at the '--monitor-address'. The profiles can be analyzed using jemalloc's 'jeprof'. Requires a build with the 'jemalloc' feature, and slows down allocations.",
+        default_value = "false",
+        env = "PATHFINDER_MONITOR_HEAP_PROFILING"
+    )]
+    heap_profiling: bool,
+
+    #[arg(
+        global = true,
+        long = "grpc.address",
+        long_help = "Serve the gRPC read API, which streams historical blocks, state updates and events for indexers, at this address. Disabled by default.",
+        value_name = "IP:PORT",
+        env = "PATHFINDER_GRPC_ADDRESS"
+    )]
+    grpc_address: Option<SocketAddr>,
+
+    #[arg(
+        global = true,
+        long = "sync.block-stream",
+        long_help = r"Write each committed block (header, transactions, receipt events and state diff) as a length-delimited protobuf record to this append-only file, or to stdout if '-'. Reorgs are written as a record naming the first removed block.
+
+A file is resumed after its last complete record on restart. When streaming to stdout, console logs are written to stderr instead. The record format is 'StreamRecord' in 'crates/grpc/proto/read.proto'.",
+        value_name = "PATH",
+        env = "PATHFINDER_SYNC_BLOCK_STREAM"
+    )]
+    block_stream: Option<BlockStreamOutput>,
+
     #[clap(flatten)]
     network: NetworkCli,
 
     /// poll_pending and p2p are mutually exclusive
     #[cfg(not(feature = "p2p"))]
     #[arg(
+        global = true,
         long = "poll-pending",
         long_help = "Enable polling pending block",
         action = clap::ArgAction::Set,
@@ -113,6 +275,7 @@ Examples:
     poll_pending: bool,
 
     #[arg(
+        global = true,
         long = "python-subprocesses",
         long_help = "This value is now unused and the argument was kept for compatibility reasons",
         default_value = "2",
@@ -121,6 +284,7 @@ Examples:
     python_subprocesses: std::num::NonZeroUsize,
 
     #[arg(
+        global = true,
         long = "sqlite-wal",
         long_help = "Enable SQLite write-ahead logging",
         action = clap::ArgAction::Set,
@@ -130,6 +294,57 @@ Examples:
     sqlite_wal: bool,
 
     #[arg(
+        global = true,
+        long = "storage.profile",
+        long_help = r"The SQLite memory and IO settings to use, which can be adjusted individually using '--storage.cache-size', '--storage.mmap-size' and '--storage.temp-store'.
+
+'low-memory' uses a 512 KiB page cache per connection and keeps temporary data on disk. 'default' uses SQLite's defaults. 'performance' uses a 64 MiB page cache per connection, memory-maps up to 1 GiB of the database and keeps temporary data in memory.",
+        value_enum,
+        default_value = "default",
+        env = "PATHFINDER_STORAGE_PROFILE"
+    )]
+    storage_profile: StorageProfile,
+
+    #[arg(
+        global = true,
+        long = "storage.cache-size",
+        long_help = "The SQLite page cache size of each database connection, in KiB. Overrides the value of '--storage.profile'.",
+        value_name = "KiB",
+        env = "PATHFINDER_STORAGE_CACHE_SIZE"
+    )]
+    storage_cache_size: Option<u64>,
+
+    #[arg(
+        global = true,
+        long = "storage.mmap-size",
+        long_help = "The maximum number of MiB of the database file SQLite memory-maps, or 0 to disable memory-mapping. Overrides the value of '--storage.profile'.",
+        value_name = "MiB",
+        env = "PATHFINDER_STORAGE_MMAP_SIZE"
+    )]
+    storage_mmap_size: Option<u64>,
+
+    #[arg(
+        global = true,
+        long = "storage.temp-store",
+        long_help = "Where SQLite keeps temporary tables and indices. Overrides the value of '--storage.profile'.",
+        value_enum,
+        env = "PATHFINDER_STORAGE_TEMP_STORE"
+    )]
+    storage_temp_store: Option<TempStore>,
+
+    #[arg(
+        global = true,
+        long = "storage.slow-query-threshold",
+        long_help = r"Database queries taking longer than this many milliseconds are logged with a summary of their parameters, and counted per query in the 'storage_slow_queries_total' metric.
+
+This helps identifying missing indexes under a specific workload. By default queries are not timed.",
+        value_name = "MILLISECONDS",
+        env = "PATHFINDER_STORAGE_SLOW_QUERY_THRESHOLD_MS"
+    )]
+    storage_slow_query_threshold: Option<u64>,
+
+    #[arg(
+        global = true,
         long = "max-rpc-connections",
         long_help = "Set the maximum number of connections allowed",
         env = "PATHFINDER_MAX_RPC_CONNECTIONS",
@@ -138,14 +353,103 @@ Examples:
     max_rpc_connections: std::num::NonZeroUsize,
 
     #[arg(
+        global = true,
         long = "sync.poll-interval",
-        long_help = "New block poll interval in seconds",
-        default_value = "5",
+        long_help = "New block poll interval in seconds. Defaults to 5 seconds on mainnet and custom networks, and 10 seconds on the test networks.",
+        value_name = "SECONDS",
         env = "PATHFINDER_HEAD_POLL_INTERVAL_SECONDS"
     )]
-    poll_interval: std::num::NonZeroU64,
+    poll_interval: Option<std::num::NonZeroU64>,
+
+    #[arg(
+        global = true,
+        long = "sync.pending-poll-interval",
+        long_help = "Pending block poll interval in seconds, if polling pending is enabled. Defaults to 2 seconds on mainnet and custom networks, and 5 seconds on the test networks.",
+        value_name = "SECONDS",
+        env = "PATHFINDER_PENDING_POLL_INTERVAL_SECONDS"
+    )]
+    pending_poll_interval: Option<std::num::NonZeroU64>,
+
+    #[arg(
+        global = true,
+        long = "sync.disable-pending",
+        long_help = "Never poll the pending block, even if '--poll-pending' is enabled. This saves gateway requests on nodes which don't serve pending data.",
+        action = clap::ArgAction::Set,
+        default_value = "false",
+        env = "PATHFINDER_SYNC_DISABLE_PENDING"
+    )]
+    disable_pending: bool,
 
     #[arg(
+        global = true,
+        long = "sync.event-capacity",
+        long_help = "The number of sync events, such as downloaded blocks and pending updates, which can be queued up for processing. Downloads pause while the queue is full. The queue depth is exported as the `sync_event_queue_depth` metric.",
+        value_name = "CAPACITY",
+        default_value = "2",
+        env = "PATHFINDER_SYNC_EVENT_CAPACITY"
+    )]
+    sync_event_capacity: NonZeroUsize,
+
+    #[arg(
+        global = true,
+        long = "sync.event-overflow",
+        long_help = r"How queued sync events are processed if processing falls behind.
+
+'backpressure' processes every event, pausing downloads while the queue is full. 'coalesce-pending' additionally skips queued pending updates which are superseded by a directly following pending update.",
+        value_name = "STRATEGY",
+        default_value = "backpressure",
+        env = "PATHFINDER_SYNC_EVENT_OVERFLOW"
+    )]
+    sync_event_overflow: SyncEventOverflow,
+
+    #[arg(
+        global = true,
+        long = "sync.max-restarts",
+        long_help = "The number of consecutive failures after which L2 sync is no longer restarted, and the node is reported as unhealthy at the monitoring '/health' endpoint. Restarts back off exponentially from the restart delay, and failures are no longer consecutive once a block was synced.",
+        value_name = "COUNT",
+        default_value = "10",
+        env = "PATHFINDER_SYNC_MAX_RESTARTS"
+    )]
+    sync_max_restarts: NonZeroUsize,
+
+    #[arg(
+        global = true,
+        long = "alert.pending-stall-minutes",
+        long_help = "Alert if no new pending block was polled for this many minutes, e.g. because the sequencer stalled. Requires polling the pending block. See '--alert.webhook-url'.",
+        value_name = "MINUTES",
+        env = "PATHFINDER_ALERT_PENDING_STALL_MINUTES"
+    )]
+    alert_pending_stall_minutes: Option<std::num::NonZeroU64>,
+
+    #[arg(
+        global = true,
+        long = "alert.l2-stall-minutes",
+        long_help = "Alert if no new L2 block was synced for this many minutes. See '--alert.webhook-url'.",
+        value_name = "MINUTES",
+        env = "PATHFINDER_ALERT_L2_STALL_MINUTES"
+    )]
+    alert_l2_stall_minutes: Option<std::num::NonZeroU64>,
+
+    #[arg(
+        global = true,
+        long = "alert.l1-stall-minutes",
+        long_help = "Alert if the L1 head was not updated for this many minutes. See '--alert.webhook-url'.",
+        value_name = "MINUTES",
+        env = "PATHFINDER_ALERT_L1_STALL_MINUTES"
+    )]
+    alert_l1_stall_minutes: Option<std::num::NonZeroU64>,
+
+    #[arg(
+        global = true,
+        long = "alert.webhook-url",
+        long_help = r#"Alerts set the `sync_stalled{head="pending|l2|l1"}` metric to 1, and back to 0 once the head changes again. If this URL is set, each such change is also POSTed to it as JSON, e.g. '{"head":"l2","stalled":true,"unchanged_for":600}'."#,
+        value_name = "URL",
+        env = "PATHFINDER_ALERT_WEBHOOK_URL"
+    )]
+    alert_webhook_url: Option<Url>,
+
+    #[arg(
+        global = true,
         long = "color",
         long_help = "This flag controls when to use colors in the output logs.",
         default_value = "auto",
@@ -169,11 +473,15 @@ Examples:
     #[clap(flatten)]
     websocket: WebsocketConfig,
 
+    #[clap(flatten)]
+    log_file: LogFileConfig,
+
     #[cfg(not(feature = "p2p"))]
     #[clap(skip)]
     debug: (),
 
     #[arg(
+        global = true,
         long = "sync.verify_tree_node_data",
         long_help = r"When enabled, state tree node hashes are verified when loaded from disk.
 
@@ -188,6 +496,18 @@ This should only be enabled for debugging purposes as it adds substantial proces
     verify_tree_node_data: bool,
 
     #[arg(
+        global = true,
+        long = "sync.strict-transaction-hashes",
+        long_help = "When enabled, blocks are rejected if the hash of one of their transactions doesn't match the hash recomputed from its contents, which guards against the gateway serving altered transactions. When disabled, mismatches are only logged.",
+        action = clap::ArgAction::Set,
+        default_value = "true",
+        env = "PATHFINDER_SYNC_STRICT_TRANSACTION_HASHES",
+        value_name = "BOOL"
+    )]
+    strict_transaction_hashes: bool,
+
+    #[arg(
+        global = true,
         long = "rpc.batch-concurrency-limit",
         long_help = "Sets the concurrency limit for request batch processing. \
             May lower the latency for large batches. \
@@ -198,6 +518,353 @@ This should only be enabled for debugging purposes as it adds substantial proces
         default_value = "1"
     )]
     rpc_batch_concurrency_limit: NonZeroUsize,
+
+    #[arg(
+        global = true,
+        long = "rpc.params-strictness",
+        long_help = r"Controls how fields in RPC request params which are unknown to the method are handled.
+
+'strict' rejects such requests with an error naming the unknown field, while 'lenient' ignores these fields.",
+        default_value = "strict",
+        env = "PATHFINDER_RPC_PARAMS_STRICTNESS"
+    )]
+    rpc_params_strictness: ParamsStrictness,
+
+    #[arg(
+        global = true,
+        long = "rpc.max-response-bytes",
+        long_help = r"The maximum size of an RPC method's serialized result.
+
+Larger results are replaced by a 'response too large' error, upon which clients should reduce the page size of their request. By default the size is not limited.",
+        env = "PATHFINDER_RPC_MAX_RESPONSE_BYTES",
+        value_name = "BYTES"
+    )]
+    rpc_max_response_bytes: Option<NonZeroUsize>,
+
+    #[arg(
+        global = true,
+        long = "rpc.call.max-steps",
+        long_help = r"The maximum number of Cairo steps a single starknet_call request may execute.
+
+Requests exceeding it fail with an 'execution resources exceeded' error. By default only the network's own limits apply.",
+        env = "PATHFINDER_RPC_CALL_MAX_STEPS",
+        value_name = "STEPS"
+    )]
+    rpc_call_max_steps: Option<u32>,
+
+    #[arg(
+        global = true,
+        long = "rpc.call.max-builtins",
+        long_help = r"The maximum number of builtin instances a single starknet_call request may use.
+
+Requests exceeding it fail with an 'execution resources exceeded' error. By default the number is not limited.",
+        env = "PATHFINDER_RPC_CALL_MAX_BUILTINS",
+        value_name = "BUILTINS"
+    )]
+    rpc_call_max_builtins: Option<usize>,
+
+    #[arg(
+        global = true,
+        long = "rpc.estimate-fee.max-steps",
+        long_help = r"The maximum number of Cairo steps a single fee estimation request may execute.
+
+Requests exceeding it fail with an 'execution resources exceeded' error. By default only the network's own limits apply.",
+        env = "PATHFINDER_RPC_ESTIMATE_FEE_MAX_STEPS",
+        value_name = "STEPS"
+    )]
+    rpc_estimate_fee_max_steps: Option<u32>,
+
+    #[arg(
+        global = true,
+        long = "rpc.estimate-fee.max-builtins",
+        long_help = r"The maximum number of builtin instances a single fee estimation request may use.
+
+Requests exceeding it fail with an 'execution resources exceeded' error. By default the number is not limited.",
+        env = "PATHFINDER_RPC_ESTIMATE_FEE_MAX_BUILTINS",
+        value_name = "BUILTINS"
+    )]
+    rpc_estimate_fee_max_builtins: Option<usize>,
+
+    #[arg(
+        global = true,
+        long = "rpc.simulate.max-steps",
+        long_help = r"The maximum number of Cairo steps a single starknet_simulateTransactions request may execute.
+
+Requests exceeding it fail with an 'execution resources exceeded' error. By default only the network's own limits apply.",
+        env = "PATHFINDER_RPC_SIMULATE_MAX_STEPS",
+        value_name = "STEPS"
+    )]
+    rpc_simulate_max_steps: Option<u32>,
+
+    #[arg(
+        global = true,
+        long = "rpc.simulate.max-builtins",
+        long_help = r"The maximum number of builtin instances a single starknet_simulateTransactions request may use.
+
+Requests exceeding it fail with an 'execution resources exceeded' error. By default the number is not limited.",
+        env = "PATHFINDER_RPC_SIMULATE_MAX_BUILTINS",
+        value_name = "BUILTINS"
+    )]
+    rpc_simulate_max_builtins: Option<usize>,
+
+    #[arg(
+        global = true,
+        long = "rpc.load-shedding.max-in-flight",
+        long_help = r"The number of RPC requests in flight above which traces and simulations are rejected.
+
+Rejected requests fail with a retryable 'node is busy' error, keeping head queries and writes responsive under load spikes. By default requests are not rejected.",
+        env = "PATHFINDER_RPC_LOAD_SHEDDING_MAX_IN_FLIGHT",
+        value_name = "REQUESTS"
+    )]
+    rpc_load_shedding_max_in_flight: Option<NonZeroUsize>,
+
+    #[arg(
+        global = true,
+        long = "rpc.load-shedding.max-executions",
+        long_help = r"The number of RPC requests executing transactions, i.e. calls, fee estimates, simulations and traces, above which traces and simulations are rejected.
+
+Rejected requests fail with a retryable 'node is busy' error. By default requests are not rejected.",
+        env = "PATHFINDER_RPC_LOAD_SHEDDING_MAX_EXECUTIONS",
+        value_name = "REQUESTS"
+    )]
+    rpc_load_shedding_max_executions: Option<NonZeroUsize>,
+
+    #[arg(
+        global = true,
+        long = "fee.gas-price-source",
+        long_help = r"Where the gas price of fee estimates comes from.
+
+'gateway' uses the gas price of the block the estimate is executed on, i.e. the pending block's at 'pending'. 'l1' uses the current gas price of Ethereum, and requires '--ethereum.url'. 'fixed:<wei>' uses a fixed gas price, e.g. for devnets whose gateway reports a zero gas price.",
+        value_name = "gateway|l1|fixed:<wei>",
+        value_parser = parse_gas_price_source,
+        default_value = "gateway",
+        env = "PATHFINDER_FEE_GAS_PRICE_SOURCE"
+    )]
+    gas_price_source: GasPriceSource,
+
+    #[arg(
+        global = true,
+        long = "rpc.response-cache-size",
+        long_help = "The number of RPC responses to queries which address a block by its hash, i.e. for blocks, state updates and block traces, which are cached in memory. Such responses don't change unless the block is removed by a reorg, in which case they are evicted.",
+        default_value = "1024",
+        env = "PATHFINDER_RPC_RESPONSE_CACHE_SIZE"
+    )]
+    rpc_response_cache_size: std::num::NonZeroUsize,
+
+    #[arg(
+        global = true,
+        long = "cache.max-memory",
+        long_help = r"The maximum memory in MiB used by in-memory caches combined.
+
+Once exceeded, all caches are shrunk by the same fraction. By default the caches are only limited by their number of entries.",
+        env = "PATHFINDER_CACHE_MAX_MEMORY",
+        value_name = "MiB"
+    )]
+    cache_max_memory: Option<std::num::NonZeroU64>,
+
+    #[arg(
+        global = true,
+        long = "storage.trie-database",
+        long_help = r"Store the merkle trie nodes in this separate SQLite database file instead of the main database, e.g. to place them on a different disk.
+
+This requires '--sqlite-wal=false', as commits spanning both databases are not atomic in WAL mode. An existing database must first be split using 'pathfinder database split-tries', after which it can only be opened along with its trie database.",
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+        env = "PATHFINDER_STORAGE_TRIE_DATABASE"
+    )]
+    trie_database: Option<PathBuf>,
+
+    #[arg(
+        global = true,
+        long = "storage.auto-vacuum-window",
+        long_help = r"A daily time window in UTC, e.g. '02:00-05:00', during which the database is incrementally vacuumed, returning space freed by deleted data to the file system.
+
+This should cover a period of low RPC traffic. Requires running 'pathfinder database vacuum' once beforehand.",
+        value_name = "HH:MM-HH:MM",
+        value_parser = parse_vacuum_window,
+        env = "PATHFINDER_STORAGE_AUTO_VACUUM_WINDOW"
+    )]
+    auto_vacuum_window: Option<VacuumWindow>,
+
+    #[arg(
+        global = true,
+        long = "gateway.record",
+        long_help = r"Record the responses of all gateway queries to files in this directory.
+
+Repeated queries are recorded in sequence, and existing files are never overwritten. The recording can later be served back using '--gateway.replay'.",
+        value_name = "DIR",
+        value_hint = clap::ValueHint::DirPath,
+        env = "PATHFINDER_GATEWAY_RECORD",
+        conflicts_with = "gateway_replay"
+    )]
+    gateway_record: Option<PathBuf>,
+
+    #[arg(
+        global = true,
+        long = "gateway.replay",
+        long_help = r"Serve gateway queries from the responses recorded in this directory using '--gateway.record', instead of querying the gateway.
+
+Queries without a recorded response fail as not found. Transactions are still submitted to the gateway.",
+        value_name = "DIR",
+        value_hint = clap::ValueHint::DirPath,
+        env = "PATHFINDER_GATEWAY_REPLAY"
+    )]
+    gateway_replay: Option<PathBuf>,
+
+    #[arg(
+        global = true,
+        long = "gateway.max-class-size",
+        long_help = "The maximum size in MiB of a class definition downloaded from the gateway. Larger classes fail to download instead of exhausting memory.",
+        value_name = "MiB",
+        default_value = "64",
+        env = "PATHFINDER_GATEWAY_MAX_CLASS_SIZE"
+    )]
+    gateway_max_class_size: NonZeroUsize,
+
+    #[arg(
+        global = true,
+        long = "fork.block",
+        long_help = r"Fork the network at the block with this hash instead of syncing it, similar to anvil's fork mode.
+
+Each transaction submitted via the RPC write API is executed and produces a new local block on top of the fork block. State which was not changed locally is read from the network's feeder gateway. Requires '--network', and does not use Ethereum.",
+        value_name = "BLOCK HASH",
+        value_parser = parse_block_hash,
+        env = "PATHFINDER_FORK_BLOCK",
+        requires = "network"
+    )]
+    fork_block: Option<BlockHash>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Runs the node. This is the default if no command is given.
+    Run,
+    /// Imports a database snapshot, as the network's database in the data directory.
+    ///
+    /// The snapshot may be zstd compressed. The node must not be running. Pending database
+    /// migrations are applied when the node is started next.
+    ImportSnapshot {
+        /// The snapshot file.
+        #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        file: PathBuf,
+        /// Replace an existing database.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Checks the gateway, Ethereum endpoint, database, disk space and system clock and prints a
+    /// report of any problems found, without starting the node.
+    Doctor,
+    /// Rebuilds the state tries of a block range from the stored state updates, verifying the
+    /// resulting state commitments against the stored block headers.
+    ///
+    /// The tries of the block preceding `--from` must be intact. This can be used to repair
+    /// corrupted trie data.
+    Replay {
+        /// The first block to replay.
+        #[arg(long, value_name = "BLOCK")]
+        from: u64,
+        /// The last block to replay.
+        #[arg(long, value_name = "BLOCK")]
+        to: u64,
+    },
+    /// Database maintenance.
+    #[command(subcommand)]
+    Database(DatabaseCommand),
+    /// Manages the key which RPC responses are signed with, see '--rpc.signing-key-file'.
+    #[command(subcommand)]
+    SigningKey(SigningKeyCommand),
+    /// Exports historical block data into one file per table, e.g. for analytics.
+    ///
+    /// Hashes, addresses and other field elements are written as hex strings. Event keys and data
+    /// are written as space separated lists.
+    Export {
+        #[arg(long, value_enum, default_value = "parquet")]
+        format: ExportFormat,
+        /// Comma separated list of the tables to export.
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "blocks,transactions,events"
+        )]
+        tables: Vec<ExportTable>,
+        /// The first block to export.
+        #[arg(long, value_name = "BLOCK")]
+        from: u64,
+        /// The last block to export.
+        #[arg(long, value_name = "BLOCK")]
+        to: u64,
+        /// The directory to write the files to.
+        #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        output: PathBuf,
+    },
+    /// Writes the JSON which the RPC API serves for a block, i.e. the block, its receipts, state
+    /// update and traces, as test vectors for validating the deserializers of SDKs.
+    ///
+    /// The responses are generated by the same storage and executor as when serving requests.
+    GenerateTestVectors {
+        /// The block to generate the test vectors of.
+        #[arg(long, value_name = "BLOCK")]
+        block: u64,
+        /// The directory to write the files to.
+        #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        output: PathBuf,
+    },
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ExportTable {
+    Blocks,
+    Transactions,
+    Events,
+}
+
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq)]
+pub enum DatabaseCommand {
+    /// Rebuilds the database file, returning all space freed by deleted data to the file system,
+    /// and enables incremental vacuuming for '--storage.auto-vacuum-window'.
+    ///
+    /// The node must not be running, and up to twice the size of the database is required in
+    /// free disk space.
+    Vacuum,
+    /// Moves the merkle trie nodes of an existing database into the separate database file given
+    /// by '--storage.trie-database'.
+    ///
+    /// The node must not be running. Run 'pathfinder database vacuum' afterwards to return the
+    /// space used by the tries in the main database to the file system.
+    SplitTries,
+    /// Updates the statistics of the query planner, and reports unused space, bloated or
+    /// unselective indexes and the slowest queries recorded with '--storage.slow-query-threshold',
+    /// along with suggested storage settings.
+    ///
+    /// This scans the whole database and can take a while. The merkle tries are only included if
+    /// they are kept in the main database.
+    Analyze,
+}
+
+#[derive(clap::Subcommand, Debug, Clone, PartialEq)]
+pub enum SigningKeyCommand {
+    /// Creates a new key file and prints its public key.
+    Generate {
+        /// The key file to create.
+        #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        file: PathBuf,
+    },
+    /// Replaces the key in the key file with a new key, and prints the old and new public keys.
+    ///
+    /// The old key is kept in '<file>.previous'. The node signs with the new key once
+    /// restarted.
+    Rotate {
+        /// The key file to rotate.
+        #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        file: PathBuf,
+    },
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
@@ -220,6 +887,20 @@ impl Color {
     }
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum StorageProfile {
+    LowMemory,
+    Default,
+    Performance,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum TempStore {
+    Default,
+    File,
+    Memory,
+}
+
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
 pub enum RpcVersion {
     V03,
@@ -227,27 +908,38 @@ pub enum RpcVersion {
     V05,
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ParamsStrictness {
+    Strict,
+    Lenient,
+}
+
 #[derive(clap::Args)]
 struct NetworkCli {
     #[arg(
+        global = true,
         long = "network",
         long_help = r"Specify the Starknet network for pathfinder to operate on.
 
-Note that 'custom' requires also setting the --gateway-url and --feeder-gateway-url options.",
+Note that 'custom' requires also setting the --gateway-url and --feeder-gateway-url options.
+
+'devnet' runs a local network without a sequencer or Ethereum, which produces a block for each transaction submitted via the RPC write API. Its chain ID can be set using --chain-id and defaults to SN_DEVNET.",
         value_enum,
         env = "PATHFINDER_NETWORK"
     )]
     network: Option<Network>,
 
     #[arg(
+        global = true,
         long,
-        long_help = "Set a custom Starknet chain ID (e.g. SN_GOERLI)",
+        long_help = "Set a custom Starknet chain ID (e.g. SN_GOERLI). Requires '--network custom' or '--network devnet'.",
         value_name = "CHAIN ID",
         env = "PATHFINDER_CHAIN_ID",
         required_if_eq("network", Network::Custom)
     )]
     chain_id: Option<String>,
     #[arg(
+        global = true,
         long = "feeder-gateway-url",
         value_name = "URL",
         value_hint = clap::ValueHint::Url,
@@ -258,6 +950,7 @@ Note that 'custom' requires also setting the --gateway-url and --feeder-gateway-
     feeder_gateway: Option<Url>,
 
     #[arg(
+        global = true,
         long = "gateway-url",
         value_name = "URL",
         value_hint = clap::ValueHint::Url,
@@ -272,6 +965,7 @@ Note that 'custom' requires also setting the --gateway-url and --feeder-gateway-
 #[derive(clap::Args)]
 struct P2PCli {
     #[arg(
+        global = true,
         long = "p2p.proxy",
         long_help = "Enable syncing from feeder gateway and proxy to p2p network. Otherwise sync from p2p network, which is the default.",
         default_value = "false",
@@ -280,6 +974,7 @@ struct P2PCli {
     )]
     proxy: bool,
     #[arg(
+        global = true,
         long = "p2p.identity-config-file",
         long_help = "Path to file containing the private key of the node. If not provided, a new random key will be generated.",
         value_name = "PATH",
@@ -287,6 +982,7 @@ struct P2PCli {
     )]
     identity_config_file: Option<std::path::PathBuf>,
     #[arg(
+        global = true,
         long = "p2p.listen-on",
         long_help = "The multiaddress on which to listen for incoming p2p connections. If not provided, default route on randomly assigned port will be used.",
         value_name = "MULTIADDRESS",
@@ -295,18 +991,37 @@ struct P2PCli {
     )]
     listen_on: Multiaddr,
     #[arg(
+        global = true,
         long = "p2p.bootstrap-addresses",
         long_help = "Comma separated list of multiaddresses to use as bootstrap nodes. The list cannot be empty.",
         value_name = "MULTIADDRESS_LIST",
         env = "PATHFINDER_P2P_BOOTSTRAP_ADDRESSES"
     )]
     bootstrap_addresses: Vec<String>,
+    #[arg(
+        global = true,
+        long = "p2p.head-divergence-threshold",
+        long_help = r#"Compare the gateway's head with the head propagated by peers and the latest block accepted on L1, and alert if they are further apart than this many blocks or on different blocks at the same height. Alerts set the `sync_head_diverged{source="peers|l1"}` metric to 1, and back to 0 once the heads agree again. See '--alert.webhook-url'."#,
+        value_name = "BLOCKS",
+        env = "PATHFINDER_P2P_HEAD_DIVERGENCE_THRESHOLD"
+    )]
+    head_divergence_threshold: Option<u64>,
+    #[arg(
+        global = true,
+        long = "p2p.halt-on-head-divergence",
+        long_help = "Stop syncing from the gateway while its head diverges, see '--p2p.head-divergence-threshold'. Only applies to proxy nodes.",
+        default_value = "false",
+        action = clap::ArgAction::Set,
+        env = "PATHFINDER_P2P_HALT_ON_HEAD_DIVERGENCE"
+    )]
+    halt_on_head_divergence: bool,
 }
 
 #[cfg(feature = "p2p")]
 #[derive(clap::Args)]
 struct DebugCli {
     #[arg(
+        global = true,
         long = "debug.pretty-log",
         long_help = "Enable pretty logging, which is especially helpful when debugging p2p behavior",
         action = clap::ArgAction::Set,
@@ -316,6 +1031,7 @@ struct DebugCli {
     pretty_log: bool,
 
     #[arg(
+        global = true,
         long = "debug.restart-delay",
         long_help = "L2 restart delay after failure, in seconds",
         action = clap::ArgAction::Set,
@@ -325,13 +1041,14 @@ struct DebugCli {
     restart_delay: u64,
 }
 
-#[derive(clap::ValueEnum, Clone)]
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
 enum Network {
     Mainnet,
     Testnet,
     Testnet2,
     Integration,
     Custom,
+    Devnet,
 }
 
 impl From<Network> for clap::builder::OsStr {
@@ -342,11 +1059,71 @@ impl From<Network> for clap::builder::OsStr {
             Network::Testnet2 => "testnet2",
             Network::Integration => "integration",
             Network::Custom => "custom",
+            Network::Devnet => "devnet",
         }
         .into()
     }
 }
 
+fn parse_block_hash(input: &str) -> Result<BlockHash, String> {
+    pathfinder_crypto::Felt::from_hex_str(input)
+        .map(BlockHash)
+        .map_err(|e| e.to_string())
+}
+
+fn sqlite_tuning(
+    profile: StorageProfile,
+    cache_size_kib: Option<u64>,
+    mmap_size_mib: Option<u64>,
+    temp_store: Option<TempStore>,
+) -> SqliteTuning {
+    let mut tuning = SqliteTuning::for_profile(match profile {
+        StorageProfile::LowMemory => pathfinder_storage::StorageProfile::LowMemory,
+        StorageProfile::Default => pathfinder_storage::StorageProfile::Default,
+        StorageProfile::Performance => pathfinder_storage::StorageProfile::Performance,
+    });
+
+    if let Some(kib) = cache_size_kib {
+        tuning.cache_size_kib = kib;
+    }
+    if let Some(mib) = mmap_size_mib {
+        tuning.mmap_size = mib.saturating_mul(1024 * 1024);
+    }
+    if let Some(temp_store) = temp_store {
+        tuning.temp_store = match temp_store {
+            TempStore::Default => pathfinder_storage::TempStore::Default,
+            TempStore::File => pathfinder_storage::TempStore::File,
+            TempStore::Memory => pathfinder_storage::TempStore::Memory,
+        };
+    }
+
+    tuning
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GasPriceSource {
+    Gateway,
+    L1,
+    Fixed(GasPrice),
+}
+
+fn parse_gas_price_source(input: &str) -> Result<GasPriceSource, String> {
+    match input {
+        "gateway" => Ok(GasPriceSource::Gateway),
+        "l1" => Ok(GasPriceSource::L1),
+        other => other
+            .strip_prefix("fixed:")
+            .ok_or_else(|| format!("Expected gateway, l1 or fixed:<wei>, got '{other}'"))?
+            .parse()
+            .map(|wei| GasPriceSource::Fixed(GasPrice(wei)))
+            .map_err(|e| format!("Invalid fixed gas price: {e}")),
+    }
+}
+
+fn parse_vacuum_window(input: &str) -> Result<VacuumWindow, String> {
+    input.parse().map_err(|e: anyhow::Error| format!("{e:#}"))
+}
+
 fn parse_cors(inputs: Vec<String>) -> Result<Option<AllowedOrigins>, RpcCorsDomainsParseError> {
     if inputs.is_empty() {
         return Ok(None);
@@ -417,29 +1194,73 @@ enum RpcCorsDomainsParseError {
 }
 
 pub struct Config {
+    pub command: Option<Command>,
     pub data_directory: PathBuf,
-    pub ethereum: Ethereum,
+    /// [None] only in devnet and fork mode, which do not use Ethereum.
+    pub ethereum: Option<Ethereum>,
     pub rpc_address: SocketAddr,
     pub rpc_cors_domains: Option<AllowedOrigins>,
+    pub rpc_signing_key_file: Option<PathBuf>,
+    pub rpc_auth_jwt_secret: Option<String>,
+    pub rpc_auth_tokens_file: Option<PathBuf>,
+    pub rpc_allow_methods: Vec<String>,
+    pub rpc_deny_methods: Vec<String>,
+    pub rpc_internal_address: Option<SocketAddr>,
     pub rpc_root_version: RpcVersion,
     pub websocket: WebsocketConfig,
     pub monitor_address: Option<SocketAddr>,
+    pub heap_profiling: bool,
+    pub grpc_address: Option<SocketAddr>,
+    pub block_stream: Option<BlockStreamOutput>,
     pub network: Option<NetworkConfig>,
+    /// False if '--sync.disable-pending' is set.
     pub poll_pending: bool,
     pub execution_concurrency: Option<std::num::NonZeroU32>,
     pub sqlite_wal: JournalMode,
+    pub sqlite_tuning: SqliteTuning,
+    pub slow_query_threshold: Option<std::time::Duration>,
+    /// Separate database file for the trie nodes.
+    pub trie_database: Option<PathBuf>,
     pub max_rpc_connections: std::num::NonZeroUsize,
-    pub poll_interval: std::time::Duration,
+    /// [None] to use the network's default.
+    pub poll_interval: Option<std::time::Duration>,
+    /// [None] to use the network's default.
+    pub pending_poll_interval: Option<std::time::Duration>,
+    pub sync_event_capacity: NonZeroUsize,
+    pub sync_event_overflow: SyncEventOverflow,
+    pub sync_max_restarts: NonZeroUsize,
+    pub stall_thresholds: StallThresholds,
+    pub alert_webhook_url: Option<Url>,
     pub color: Color,
     pub p2p: P2PConfig,
     pub debug: DebugConfig,
     pub verify_tree_hashes: bool,
+    pub strict_transaction_hashes: bool,
     pub rpc_batch_concurrency_limit: NonZeroUsize,
+    pub rpc_params_strictness: ParamsStrictness,
+    pub rpc_max_response_bytes: Option<NonZeroUsize>,
+    pub rpc_execution_limits: pathfinder_rpc::context::RequestExecutionLimits,
+    pub rpc_load_shedding: pathfinder_rpc::middleware::load_shedding::LoadSheddingConfig,
+    pub gas_price_source: GasPriceSource,
+    pub log_file: LogFileConfig,
+    /// In bytes.
+    pub cache_max_memory: Option<u64>,
+    pub rpc_response_cache_size: std::num::NonZeroUsize,
+    pub auto_vacuum_window: Option<VacuumWindow>,
+    pub gateway_recording: Option<starknet_gateway_client::Recording>,
+    /// In bytes.
+    pub gateway_max_class_size: usize,
+    /// Run a fork of the network at this block instead of syncing it.
+    pub fork_block: Option<BlockHash>,
 }
 
+#[derive(Clone)]
 pub struct Ethereum {
     pub url: Url,
     pub password: Option<String>,
+    pub beacon_url: Option<Url>,
+    pub ws_url: Option<Url>,
+    pub confirmations: Option<u64>,
 }
 
 pub enum NetworkConfig {
@@ -452,6 +1273,9 @@ pub enum NetworkConfig {
         feeder_gateway: Url,
         chain_id: String,
     },
+    Devnet {
+        chain_id: String,
+    },
 }
 
 #[cfg(feature = "p2p")]
@@ -460,6 +1284,7 @@ pub struct P2PConfig {
     pub identity_config_file: Option<std::path::PathBuf>,
     pub listen_on: Multiaddr,
     pub bootstrap_addresses: Vec<Multiaddr>,
+    pub divergence: Option<DivergenceConfig>,
 }
 
 #[cfg(not(feature = "p2p"))]
@@ -490,6 +1315,9 @@ impl NetworkConfig {
             (Some(Custom), _, _, _) => {
                 unreachable!("`--network custom` requirements are handled by clap derive")
             }
+            (Some(Devnet), None, None, chain_id) => NetworkConfig::Devnet {
+                chain_id: chain_id.unwrap_or_else(|| "SN_DEVNET".to_owned()),
+            },
             // Handle non-custom variants in an inner match so that the compiler will force
             // us to handle a new network variants explicitly. Otherwise we end up with a
             // catch-all arm that would swallow new variants silently.
@@ -499,13 +1327,14 @@ impl NetworkConfig {
                 Testnet2 => NetworkConfig::Testnet2,
                 Integration => NetworkConfig::Integration,
                 Custom => unreachable!("Network::Custom handled in outer arm already"),
+                Devnet => unreachable!("Network::Devnet handled in outer arm already"),
             },
             // clap does not support disallowing args based on an enum value, so we have check for
             // `--network non-custom` + custom required args manually.
             _ => {
                 use clap::error::ErrorKind;
 
-                Cli::command().error(ErrorKind::ArgumentConflict, "--gateway-url, --feeder-gateway-url and --chain-id may only be used with --network custom").exit()
+                Cli::command().error(ErrorKind::ArgumentConflict, "--gateway-url and --feeder-gateway-url may only be used with --network custom, and --chain-id with --network custom or devnet").exit()
             }
         };
 
@@ -518,6 +1347,14 @@ impl P2PConfig {
     fn parse_or_exit(_: ()) -> Self {
         Self
     }
+
+    pub fn head_divergence(&self) -> Option<DivergenceConfig> {
+        None
+    }
+
+    pub fn syncs_from_peers(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(feature = "p2p")]
@@ -552,8 +1389,27 @@ impl P2PConfig {
                 });
                 x
             },
+            divergence: match (args.head_divergence_threshold, args.halt_on_head_divergence) {
+                (Some(threshold), halt) => Some(DivergenceConfig { threshold, halt }),
+                (None, false) => None,
+                (None, true) => Cli::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--p2p.halt-on-head-divergence requires --p2p.head-divergence-threshold",
+                    )
+                    .exit(),
+            },
         }
     }
+
+    pub fn head_divergence(&self) -> Option<DivergenceConfig> {
+        self.divergence
+    }
+
+    /// Whether blocks are synced from peers instead of the gateway.
+    pub fn syncs_from_peers(&self) -> bool {
+        !self.proxy
+    }
 }
 
 #[cfg(not(feature = "p2p"))]
@@ -582,34 +1438,154 @@ impl Config {
 
         let network = NetworkConfig::from_components(cli.network);
 
+        if let (Some(NetworkConfig::Devnet { .. }), Some(_)) = (&network, cli.fork_block) {
+            use clap::error::ErrorKind;
+
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--fork.block cannot be used with --network devnet",
+                )
+                .exit()
+        }
+
+        let ethereum = match (cli.ethereum_url, &network) {
+            (Some(url), _) => Some(Ethereum {
+                password: cli.ethereum_password,
+                beacon_url: cli.ethereum_beacon_url,
+                ws_url: cli.ethereum_ws_url,
+                confirmations: cli.ethereum_confirmations,
+                url,
+            }),
+            (None, Some(NetworkConfig::Devnet { .. })) => None,
+            (None, _) if cli.fork_block.is_some() => None,
+            // Managing keys doesn't involve the network.
+            (None, _) if matches!(cli.command, Some(Command::SigningKey(_))) => None,
+            (None, _) => {
+                use clap::error::ErrorKind;
+
+                Cli::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "--ethereum.url is required unless running with --network devnet or --fork.block",
+                    )
+                    .exit()
+            }
+        };
+
+        if cli.gas_price_source == GasPriceSource::L1 && ethereum.is_none() {
+            use clap::error::ErrorKind;
+
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "--fee.gas-price-source=l1 requires --ethereum.url",
+                )
+                .exit()
+        }
+
         Config {
+            command: cli.command,
             data_directory: cli.data_directory,
-            ethereum: Ethereum {
-                password: cli.ethereum_password,
-                url: cli.ethereum_url,
-            },
+            ethereum,
             rpc_address: cli.rpc_address,
             rpc_cors_domains: parse_cors_or_exit(cli.rpc_cors_domains),
+            rpc_signing_key_file: cli.rpc_signing_key_file,
+            rpc_auth_jwt_secret: cli.rpc_auth_jwt_secret,
+            rpc_auth_tokens_file: cli.rpc_auth_tokens_file,
+            rpc_allow_methods: cli.rpc_allow_methods,
+            rpc_deny_methods: cli.rpc_deny_methods,
+            rpc_internal_address: cli.rpc_internal_address,
             rpc_root_version: cli.rpc_root_version,
             websocket: cli.websocket,
             monitor_address: cli.monitor_address,
+            heap_profiling: cli.heap_profiling,
+            grpc_address: cli.grpc_address,
+            block_stream: cli.block_stream,
             network,
             #[cfg(feature = "p2p")]
             poll_pending: false,
             #[cfg(not(feature = "p2p"))]
-            poll_pending: cli.poll_pending,
+            poll_pending: cli.poll_pending && !cli.disable_pending,
             execution_concurrency: cli.execution_concurrency,
             sqlite_wal: match cli.sqlite_wal {
                 true => JournalMode::WAL,
                 false => JournalMode::Rollback,
             },
+            trie_database: cli.trie_database,
+            sqlite_tuning: sqlite_tuning(
+                cli.storage_profile,
+                cli.storage_cache_size,
+                cli.storage_mmap_size,
+                cli.storage_temp_store,
+            ),
+            slow_query_threshold: cli
+                .storage_slow_query_threshold
+                .map(std::time::Duration::from_millis),
             max_rpc_connections: cli.max_rpc_connections,
-            poll_interval: std::time::Duration::from_secs(cli.poll_interval.get()),
+            poll_interval: cli
+                .poll_interval
+                .map(|secs| std::time::Duration::from_secs(secs.get())),
+            pending_poll_interval: cli
+                .pending_poll_interval
+                .map(|secs| std::time::Duration::from_secs(secs.get())),
+            sync_event_capacity: cli.sync_event_capacity,
+            sync_event_overflow: cli.sync_event_overflow,
+            sync_max_restarts: cli.sync_max_restarts,
+            stall_thresholds: StallThresholds {
+                pending: cli
+                    .alert_pending_stall_minutes
+                    .map(|minutes| std::time::Duration::from_secs(minutes.get() * 60)),
+                l2_head: cli
+                    .alert_l2_stall_minutes
+                    .map(|minutes| std::time::Duration::from_secs(minutes.get() * 60)),
+                l1_head: cli
+                    .alert_l1_stall_minutes
+                    .map(|minutes| std::time::Duration::from_secs(minutes.get() * 60)),
+            },
+            alert_webhook_url: cli.alert_webhook_url,
             color: cli.color,
             p2p: P2PConfig::parse_or_exit(cli.p2p),
             debug: DebugConfig::parse(cli.debug),
             verify_tree_hashes: cli.verify_tree_node_data,
+            strict_transaction_hashes: cli.strict_transaction_hashes,
             rpc_batch_concurrency_limit: cli.rpc_batch_concurrency_limit,
+            rpc_params_strictness: cli.rpc_params_strictness,
+            rpc_max_response_bytes: cli.rpc_max_response_bytes,
+            rpc_execution_limits: pathfinder_rpc::context::RequestExecutionLimits {
+                call: pathfinder_executor::ExecutionLimits {
+                    max_steps: cli.rpc_call_max_steps,
+                    max_builtins: cli.rpc_call_max_builtins,
+                },
+                estimate_fee: pathfinder_executor::ExecutionLimits {
+                    max_steps: cli.rpc_estimate_fee_max_steps,
+                    max_builtins: cli.rpc_estimate_fee_max_builtins,
+                },
+                simulate: pathfinder_executor::ExecutionLimits {
+                    max_steps: cli.rpc_simulate_max_steps,
+                    max_builtins: cli.rpc_simulate_max_builtins,
+                },
+            },
+            rpc_load_shedding: pathfinder_rpc::middleware::load_shedding::LoadSheddingConfig {
+                max_in_flight: cli.rpc_load_shedding_max_in_flight,
+                max_executions: cli.rpc_load_shedding_max_executions,
+            },
+            gas_price_source: cli.gas_price_source,
+            log_file: cli.log_file,
+            cache_max_memory: cli
+                .cache_max_memory
+                .map(|mib| mib.get().saturating_mul(1024 * 1024)),
+            rpc_response_cache_size: cli.rpc_response_cache_size,
+            auto_vacuum_window: cli.auto_vacuum_window,
+            gateway_recording: match (cli.gateway_record, cli.gateway_replay) {
+                (Some(directory), _) => Some(starknet_gateway_client::Recording::Record(directory)),
+                (None, Some(directory)) => {
+                    Some(starknet_gateway_client::Recording::Replay(directory))
+                }
+                (None, None) => None,
+            },
+            gateway_max_class_size: cli.gateway_max_class_size.get().saturating_mul(1024 * 1024),
+            fork_block: cli.fork_block,
         }
     }
 }
@@ -617,13 +1593,15 @@ impl Config {
 #[derive(clap::Args, Clone)]
 pub struct WebsocketConfig {
     #[arg(
+        global = true,
         long = "rpc.websocket.enabled",
-        long_help = "Enable RPC WebSocket transport at the \"/ws\" path",
+        long_help = "Enable RPC WebSocket transport at the \"/ws\" path, and the server-sent events stream of new heads at \"/sse/newHeads\"",
         default_value = "false",
         env = "PATHFINDER_WEBSOCKET_ENABLED"
     )]
     pub enabled: bool,
     #[arg(
+        global = true,
         long = "rpc.websocket.buffer-capacity",
         long_help = "The socket buffer for outbound messages. If specific clients have their \
             subscription sporadically closed due to lagging streams, consider increasing this \
@@ -634,6 +1612,7 @@ pub struct WebsocketConfig {
     )]
     pub socket_buffer_capacity: NonZeroUsize,
     #[arg(
+        global = true,
         long = "rpc.websocket.topic-capacity",
         long_help = "The topic sender capacity. The topic senders are upstream of socket buffers \
             and common to all clients and subscriptions. If a variety of clients regularly have their \
@@ -644,12 +1623,152 @@ pub struct WebsocketConfig {
         env = "PATHFINDER_WEBSOCKET_TOPIC_CAPACITY"
     )]
     pub topic_sender_capacity: NonZeroUsize,
+    #[arg(
+        global = true,
+        long = "rpc.websocket.max-subscriptions",
+        long_help = "The maximum number of active subscriptions per connection. Further \
+            subscriptions are rejected until one is unsubscribed.",
+        value_name = "COUNT",
+        default_value = "100",
+        env = "PATHFINDER_WEBSOCKET_MAX_SUBSCRIPTIONS"
+    )]
+    pub max_subscriptions: NonZeroUsize,
+    #[arg(
+        global = true,
+        long = "rpc.websocket.lag-policy",
+        long_help = r"How a subscription which can't keep up with its topic is handled.
+
+'disconnect' closes the subscription with an error notification, while 'drop-oldest' skips the oldest items and notifies the subscriber of the number of items it missed.",
+        value_name = "POLICY",
+        default_value = "disconnect",
+        env = "PATHFINDER_WEBSOCKET_LAG_POLICY"
+    )]
+    pub lag_policy: WebsocketLagPolicy,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum SyncEventOverflow {
+    Backpressure,
+    CoalescePending,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum WebsocketLagPolicy {
+    Disconnect,
+    DropOldest,
+}
+
+#[derive(clap::Args, Clone)]
+pub struct LogFileConfig {
+    #[arg(
+        global = true,
+        long = "log.file",
+        long_help = "Additionally write logs to this file. Console output is unaffected.",
+        value_name = "PATH",
+        env = "PATHFINDER_LOG_FILE"
+    )]
+    pub path: Option<PathBuf>,
+    #[arg(
+        global = true,
+        long = "log.file-filter",
+        long_help = "The log filter applied to the log file, using the same syntax as `RUST_LOG`. \
+            This is independent of the console's filter which is set via `RUST_LOG`.",
+        value_name = "FILTER",
+        default_value = "pathfinder=debug",
+        env = "PATHFINDER_LOG_FILE_FILTER"
+    )]
+    pub filter: String,
+    #[arg(
+        global = true,
+        long = "log.rotation",
+        long_help = "When to rotate the log file. Time based rotation appends the date and hour to \
+            the file name, while size based rotation appends an index once `log.max-file-size` is exceeded.",
+        value_name = "WHEN",
+        default_value = "daily",
+        env = "PATHFINDER_LOG_ROTATION"
+    )]
+    pub rotation: LogRotation,
+    #[arg(
+        global = true,
+        long = "log.max-file-size",
+        long_help = "The maximum log file size in MiB before it is rotated. Only used with `--log.rotation=size`.",
+        value_name = "MiB",
+        default_value = "100",
+        env = "PATHFINDER_LOG_MAX_FILE_SIZE"
+    )]
+    pub max_file_size: std::num::NonZeroU64,
+    #[arg(
+        global = true,
+        long = "log.max-files",
+        long_help = "The number of rotated log files to keep, the oldest files are removed first. \
+            Zero keeps all files.",
+        value_name = "COUNT",
+        default_value = "7",
+        env = "PATHFINDER_LOG_MAX_FILES"
+    )]
+    pub max_files: usize,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum LogRotation {
+    Never,
+    Hourly,
+    Daily,
+    Size,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AllowedOrigins, RpcCorsDomainsParseError};
-    use crate::config::parse_cors;
+    use super::{AllowedOrigins, Cli, Command, GasPriceSource, Network, RpcCorsDomainsParseError};
+    use crate::config::{parse_cors, parse_gas_price_source};
+    use clap::Parser;
+    use pathfinder_common::GasPrice;
+
+    #[test]
+    fn options_are_shared_by_commands() {
+        let flat = Cli::try_parse_from(["pathfinder", "--network", "testnet"]).unwrap();
+        assert_eq!(flat.command, None);
+        assert_eq!(flat.network.network, Some(Network::Testnet));
+
+        let run = Cli::try_parse_from(["pathfinder", "run", "--network", "testnet"]).unwrap();
+        assert_eq!(run.command, Some(Command::Run));
+        assert_eq!(run.network.network, Some(Network::Testnet));
+
+        let import = Cli::try_parse_from([
+            "pathfinder",
+            "--network",
+            "testnet",
+            "import-snapshot",
+            "--file",
+            "testnet.sqlite.zst",
+        ])
+        .unwrap();
+        assert_eq!(
+            import.command,
+            Some(Command::ImportSnapshot {
+                file: "testnet.sqlite.zst".into(),
+                force: false,
+            })
+        );
+        assert_eq!(import.network.network, Some(Network::Testnet));
+    }
+
+    #[test]
+    fn parse_gas_price_sources() {
+        assert_eq!(
+            parse_gas_price_source("gateway").unwrap(),
+            GasPriceSource::Gateway
+        );
+        assert_eq!(parse_gas_price_source("l1").unwrap(), GasPriceSource::L1);
+        assert_eq!(
+            parse_gas_price_source("fixed:1000000000").unwrap(),
+            GasPriceSource::Fixed(GasPrice(1_000_000_000))
+        );
+
+        parse_gas_price_source("fixed:").unwrap_err();
+        parse_gas_price_source("fixed:-1").unwrap_err();
+        parse_gas_price_source("oracle").unwrap_err();
+    }
 
     #[test]
     fn parse_cors_domains() {
@@ -0,0 +1,192 @@
+//! Runs a local devnet, see [pathfinder_lib::state::devnet].
+
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use anyhow::Context;
+use pathfinder_common::{BlockHash, ChainId};
+use pathfinder_crypto::Felt;
+use pathfinder_executor::RemoteState;
+use pathfinder_lib::state::devnet::{spawn_gateway, Devnet, Fork};
+use pathfinder_rpc::SyncState;
+use pathfinder_storage::{Storage, StorageManager};
+use tracing::info;
+
+use crate::config::Config;
+use crate::PathfinderContext;
+
+/// Serves the RPC API on top of a devnet, which produces a block for each transaction submitted
+/// via the RPC write API.
+///
+/// Transactions reach the devnet through its local gateway, so that the RPC write API is
+/// unchanged.
+pub async fn run(
+    config: Config,
+    chain_id: String,
+    available_parallelism: NonZeroUsize,
+    readiness: Arc<AtomicBool>,
+) -> anyhow::Result<()> {
+    let chain_id = ChainId(Felt::from_be_slice(chain_id.as_bytes()).context("Parsing chain ID")?);
+
+    if let Some(address) = config.monitor_address {
+        // There is no sync which could fail and mark the node unhealthy.
+        let healthy = Arc::new(AtomicBool::new(true));
+        crate::spawn_monitoring("devnet", address, readiness.clone(), healthy)
+            .await
+            .context("Starting monitoring task")?;
+    }
+
+    let database = config.data_directory.join("devnet.sqlite");
+    let (storage_manager, devnet_storage) = migrate(database, &config, available_parallelism)?;
+    let devnet = Devnet::new(devnet_storage, chain_id).context("Creating devnet")?;
+
+    serve(
+        config,
+        storage_manager,
+        devnet,
+        chain_id,
+        None,
+        available_parallelism,
+        readiness,
+    )
+    .await
+}
+
+/// Like [run], but building on top of the network's block instead of an empty state.
+///
+/// Local blocks are kept in a database of their own, which can only be reused to continue the
+/// same fork.
+pub async fn fork(
+    config: Config,
+    context: PathfinderContext,
+    block: BlockHash,
+    available_parallelism: NonZeroUsize,
+    readiness: Arc<AtomicBool>,
+) -> anyhow::Result<()> {
+    if let Some(address) = config.monitor_address {
+        // There is no sync which could fail and mark the node unhealthy.
+        let healthy = Arc::new(AtomicBool::new(true));
+        crate::spawn_monitoring("fork", address, readiness.clone(), healthy)
+            .await
+            .context("Starting monitoring task")?;
+    }
+
+    let fork = Fork::new(context.gateway, block)
+        .await
+        .context("Fetching fork block")?;
+    let remote_state = fork.state();
+    info!(network=%context.network, %block, "Forking network");
+
+    let database = context.database.with_file_name(format!(
+        "{}-fork.sqlite",
+        context
+            .database
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+    ));
+    let (storage_manager, devnet_storage) = migrate(database, &config, available_parallelism)?;
+    let devnet = Devnet::fork(devnet_storage, context.network_id, fork).context("Creating fork")?;
+
+    serve(
+        config,
+        storage_manager,
+        devnet,
+        context.network_id,
+        Some(remote_state),
+        available_parallelism,
+        readiness,
+    )
+    .await
+}
+
+fn migrate(
+    database: PathBuf,
+    config: &Config,
+    available_parallelism: NonZeroUsize,
+) -> anyhow::Result<(StorageManager, Storage)> {
+    let storage_manager = Storage::migrate_with_trie_database(
+        database.clone(),
+        config.trie_database.clone(),
+        config.sqlite_wal,
+    )
+    .context("Migrating database")?
+    .with_tuning(config.sqlite_tuning);
+    let devnet_storage = storage_manager
+        // Block production is sequential, the rest are used by the rayon thread pool workers.
+        .create_pool(NonZeroU32::new(1 + available_parallelism.get() as u32).unwrap())
+        .context("Creating database connection pool for devnet")?;
+    info!(location=?database, "Database migrated.");
+
+    Ok((storage_manager, devnet_storage))
+}
+
+async fn serve(
+    config: Config,
+    storage_manager: StorageManager,
+    devnet: Devnet,
+    chain_id: ChainId,
+    remote_state: Option<Arc<dyn RemoteState>>,
+    available_parallelism: NonZeroUsize,
+    readiness: Arc<AtomicBool>,
+) -> anyhow::Result<()> {
+    let (rpc_storage, execution_storage) =
+        crate::create_rpc_pools(&storage_manager, &config, available_parallelism)?;
+
+    let (gateway_handle, gateway_address) =
+        spawn_gateway(devnet, ([127, 0, 0, 1], 0).into()).context("Starting devnet gateway")?;
+    let gateway_url = reqwest::Url::parse(&format!("http://{gateway_address}/"))
+        .context("Parsing devnet gateway URL")?;
+    let gateway = starknet_gateway_client::Client::with_base_url(gateway_url)
+        .context("Creating gateway client")?;
+
+    // There is no pending block, but the sender must outlive the RPC server.
+    let (_tx_pending, rx_pending) = tokio::sync::watch::channel(Default::default());
+
+    let mut context = pathfinder_rpc::context::RpcContext::new(
+        rpc_storage,
+        execution_storage,
+        Arc::new(SyncState::default()),
+        chain_id,
+        gateway,
+        rx_pending,
+        config.rpc_batch_concurrency_limit,
+    );
+    if let Some(remote_state) = remote_state {
+        context = context.with_remote_state(remote_state);
+    }
+
+    let (rpc_handle, local_addr) = crate::configure_rpc_server(&config, context)?
+        .with_max_connections(config.max_rpc_connections.get())
+        .spawn()
+        .context("Starting the RPC server")?;
+
+    info!(%chain_id, "📡 Devnet HTTP-RPC server started on: {}", local_addr);
+
+    readiness.store(true, std::sync::atomic::Ordering::Relaxed);
+    crate::service::notify_ready();
+
+    tokio::select! {
+        _ = crate::service::shutdown_requested() => {
+            info!("Shutting down");
+            crate::service::notify_stopping();
+            return Ok(());
+        }
+        result = rpc_handle => {
+            match result {
+                Ok(_) => tracing::error!("RPC server process ended unexpectedly"),
+                Err(err) => tracing::error!(error=%err, "RPC server process ended unexpectedly"),
+            }
+        }
+        result = gateway_handle => {
+            match result {
+                Ok(_) => tracing::error!("Devnet gateway process ended unexpectedly"),
+                Err(err) => tracing::error!(error=%err, "Devnet gateway process ended unexpectedly"),
+            }
+        }
+    }
+
+    anyhow::bail!("Unexpected shutdown");
+}
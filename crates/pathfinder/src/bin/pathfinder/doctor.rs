@@ -0,0 +1,349 @@
+//! The `doctor` command which checks the node's environment without starting it.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use pathfinder_common::Chain;
+use pathfinder_ethereum::EthereumApi;
+use pathfinder_storage::Storage;
+use starknet_gateway_client::GatewayApi;
+
+use crate::config::{Config, NetworkConfig};
+use crate::{EthereumContext, PathfinderContext};
+
+/// Available disk space below which the disk space check fails.
+const MIN_DISK_SPACE: u64 = 1024 * 1024 * 1024;
+/// Available disk space below which the disk space check warns.
+const LOW_DISK_SPACE: u64 = 50 * 1024 * 1024 * 1024;
+/// Clock difference to the gateway above which the clock check warns.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(10);
+
+enum Outcome {
+    Ok(String),
+    Warning(String),
+    Failed(String),
+    Skipped(&'static str),
+}
+
+struct Check {
+    name: &'static str,
+    outcome: Outcome,
+    hint: Option<&'static str>,
+}
+
+impl Check {
+    fn new(name: &'static str, outcome: Outcome) -> Self {
+        Self {
+            name,
+            outcome,
+            hint: None,
+        }
+    }
+
+    fn from_result(name: &'static str, result: anyhow::Result<String>) -> Self {
+        match result {
+            Ok(details) => Self::new(name, Outcome::Ok(details)),
+            Err(e) => Self::new(name, Outcome::Failed(format!("{e:#}"))),
+        }
+    }
+
+    /// Sets the hint which is printed if the check did not pass.
+    fn with_hint(self, hint: &'static str) -> Self {
+        Self {
+            hint: Some(hint),
+            ..self
+        }
+    }
+
+    fn print(&self) {
+        let (status, details) = match &self.outcome {
+            Outcome::Ok(details) => (" OK ", details.as_str()),
+            Outcome::Warning(details) => ("WARN", details.as_str()),
+            Outcome::Failed(details) => ("FAIL", details.as_str()),
+            Outcome::Skipped(reason) => ("SKIP", *reason),
+        };
+        println!("[{status}] {}: {details}", self.name);
+
+        if let (Some(hint), Outcome::Warning(_) | Outcome::Failed(_)) = (self.hint, &self.outcome) {
+            println!("       Hint: {hint}");
+        }
+    }
+}
+
+/// Runs all checks and prints a report to stdout.
+///
+/// Errors if any of the checks failed, warnings are only reported.
+pub async fn run(config: Config) -> anyhow::Result<()> {
+    if let Some(NetworkConfig::Devnet { .. }) = config.network {
+        anyhow::bail!("The doctor checks are not available for a devnet");
+    }
+
+    let mut checks = Vec::new();
+
+    checks.push(check_disk_space(&config.data_directory).with_hint(
+        "Free up disk space or use --data-directory to select a different disk. \
+        A synced mainnet database requires several hundred GiB.",
+    ));
+
+    let ethereum = config
+        .ethereum
+        .context("Ethereum is required unless running a devnet or a fork")?;
+    let ethereum = EthereumContext::setup(ethereum.url, ethereum.password).await;
+    let ethereum = match ethereum {
+        Ok(ethereum) => {
+            checks.push(Check::new(
+                "Ethereum chain",
+                Outcome::Ok(format!("{:?}", ethereum.chain)),
+            ));
+            Some(ethereum)
+        }
+        Err(e) => {
+            checks.push(
+                Check::new("Ethereum chain", Outcome::Failed(format!("{e:#}")))
+                    .with_hint("Make sure --ethereum.url and --ethereum.password are correct."),
+            );
+            None
+        }
+    };
+
+    let network = match (config.network, &ethereum) {
+        (Some(network), _) => Ok(network),
+        (None, Some(ethereum)) => ethereum.default_network(),
+        (None, None) => Err(anyhow::anyhow!(
+            "Network cannot be determined without Ethereum, use --network to set it explicitly"
+        )),
+    };
+    let context = match network {
+        Ok(network) => {
+            // The checks are always made against the real gateway, ignoring any recording.
+            PathfinderContext::configure_and_proxy_check(
+                network,
+                config.data_directory,
+                None,
+                config.gateway_max_class_size,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    let context = match context {
+        Ok(context) => {
+            checks.push(Check::new(
+                "Starknet network",
+                Outcome::Ok(context.network.to_string()),
+            ));
+            Some(context)
+        }
+        Err(e) => {
+            checks.push(
+                Check::new("Starknet network", Outcome::Failed(format!("{e:#}")))
+                    .with_hint("Check the --network and custom gateway options."),
+            );
+            None
+        }
+    };
+
+    match (&context, &ethereum) {
+        (Some(context), Some(ethereum)) => {
+            let result = crate::verify_networks(context.network, ethereum.chain)
+                .map(|_| "Starknet and Ethereum networks match".to_owned());
+            checks.push(Check::from_result("Network compatibility", result));
+
+            let result = ethereum
+                .client
+                .get_starknet_state(&context.l1_core_address)
+                .await
+                .map(|state| format!("Starknet state at L1 is block {}", state.block_number));
+            checks.push(Check::from_result("Ethereum methods", result).with_hint(
+                "The Ethereum endpoint must support eth_chainId, eth_call and \
+                    eth_getBlockByNumber with the \"finalized\" tag.",
+            ));
+        }
+        _ => {
+            let reason = "requires the Ethereum chain and Starknet network";
+            checks.push(Check::new(
+                "Network compatibility",
+                Outcome::Skipped(reason),
+            ));
+            checks.push(Check::new("Ethereum methods", Outcome::Skipped(reason)));
+        }
+    }
+
+    match &context {
+        Some(context) => {
+            checks.push(
+                check_gateway(context)
+                    .await
+                    .with_hint("Check your internet connection and proxy settings."),
+            );
+            checks.push(
+                check_clock(context)
+                    .await
+                    .with_hint("Synchronize the system clock, e.g. by enabling NTP."),
+            );
+            checks.push(check_database(&context.database).with_hint(
+                "Restore the database from a backup or snapshot, or remove it to sync from scratch.",
+            ));
+        }
+        None => {
+            let reason = "requires the Starknet network";
+            checks.push(Check::new("Gateway", Outcome::Skipped(reason)));
+            checks.push(Check::new("System clock", Outcome::Skipped(reason)));
+            checks.push(Check::new("Database", Outcome::Skipped(reason)));
+        }
+    }
+
+    for check in &checks {
+        check.print();
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| matches!(c.outcome, Outcome::Failed(_)))
+        .count();
+    anyhow::ensure!(failed == 0, "{failed} check(s) failed");
+
+    Ok(())
+}
+
+fn check_disk_space(data_directory: &Path) -> Check {
+    const NAME: &str = "Disk space";
+
+    let available = match fs2::available_space(data_directory) {
+        Ok(available) => available,
+        Err(e) => {
+            return Check::new(
+                NAME,
+                Outcome::Failed(format!(
+                    "Reading available space of {}: {e}",
+                    data_directory.display()
+                )),
+            )
+        }
+    };
+
+    let details = format!("{} GiB available", available / (1024 * 1024 * 1024));
+    let outcome = match available {
+        x if x < MIN_DISK_SPACE => Outcome::Failed(details),
+        x if x < LOW_DISK_SPACE => Outcome::Warning(details),
+        _ => Outcome::Ok(details),
+    };
+
+    Check::new(NAME, outcome)
+}
+
+async fn check_gateway(context: &PathfinderContext) -> Check {
+    const NAME: &str = "Gateway";
+
+    let (head, _) = match context.gateway.head().await {
+        Ok(head) => head,
+        Err(e) => return Check::new(NAME, Outcome::Failed(format!("Fetching head: {e}"))),
+    };
+
+    // Proxies and custom networks cannot be identified by their genesis block.
+    if context.network != Chain::Custom {
+        match context.gateway.chain().await {
+            Ok(chain) if chain == context.network => {}
+            Ok(chain) => {
+                return Check::new(
+                    NAME,
+                    Outcome::Failed(format!(
+                        "Gateway serves {chain} but {} was expected",
+                        context.network
+                    )),
+                )
+            }
+            Err(e) => {
+                return Check::new(NAME, Outcome::Failed(format!("Identifying chain: {e:#}")))
+            }
+        }
+    }
+
+    Check::new(
+        NAME,
+        Outcome::Ok(format!("Reachable, head at block {head}")),
+    )
+}
+
+/// Compares the system clock against the `Date` header of a gateway response.
+async fn check_clock(context: &PathfinderContext) -> Check {
+    const NAME: &str = "System clock";
+
+    let result = async {
+        let response = reqwest::Client::new()
+            .head(context.gateway.feeder_gateway_url().clone())
+            .send()
+            .await
+            .context("Requesting gateway time")?;
+        let date = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .context("Gateway response has no Date header")?
+            .to_str()
+            .context("Parsing Date header")?;
+        httpdate::parse_http_date(date).context("Parsing Date header")
+    }
+    .await;
+
+    let remote = match result {
+        Ok(remote) => remote,
+        Err(e) => return Check::new(NAME, Outcome::Failed(format!("{e:#}"))),
+    };
+    let local = SystemTime::now();
+
+    let (skew, direction) = match local.duration_since(remote) {
+        Ok(ahead) => (ahead, "ahead of"),
+        Err(behind) => (behind.duration(), "behind"),
+    };
+
+    let details = format!("{}s {direction} the gateway", skew.as_secs());
+    if skew > MAX_CLOCK_SKEW {
+        Check::new(NAME, Outcome::Warning(details))
+    } else {
+        Check::new(NAME, Outcome::Ok(details))
+    }
+}
+
+fn check_database(database: &Path) -> Check {
+    const NAME: &str = "Database";
+
+    if !database.exists() {
+        return Check::new(
+            NAME,
+            Outcome::Ok(format!(
+                "{} does not exist yet and will be created",
+                database.display()
+            )),
+        );
+    }
+
+    let health = match Storage::inspect(database) {
+        Ok(health) => health,
+        Err(e) => return Check::new(NAME, Outcome::Failed(format!("{e:#}"))),
+    };
+
+    let outcome = if !health.problems.is_empty() {
+        Outcome::Failed(format!(
+            "Integrity check found problems: {}",
+            health.problems.join("; ")
+        ))
+    } else if health.schema_version > health.latest_schema_version {
+        Outcome::Failed(format!(
+            "Schema version {} is newer than this version of pathfinder supports ({})",
+            health.schema_version, health.latest_schema_version
+        ))
+    } else if health.schema_version < health.latest_schema_version {
+        Outcome::Ok(format!(
+            "Schema version {} will be migrated to {}",
+            health.schema_version, health.latest_schema_version
+        ))
+    } else {
+        Outcome::Ok(format!(
+            "Schema version {} is up-to-date",
+            health.schema_version
+        ))
+    };
+
+    Check::new(NAME, outcome)
+}
@@ -0,0 +1,257 @@
+//! Logging to a file, in addition to the console output.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::{LogFileConfig, LogRotation};
+
+/// Creates the file logging layer described by `config`.
+///
+/// The returned [WorkerGuard] must be kept alive for as long as logs should be written, as
+/// dropping it flushes and stops the background writer.
+pub fn file_layer<S>(
+    config: &LogFileConfig,
+) -> anyhow::Result<Option<(Box<dyn Layer<S> + Send + Sync>, WorkerGuard)>>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    let Some(path) = &config.path else {
+        return Ok(None);
+    };
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(directory)
+        .with_context(|| format!("Creating log directory {}", directory.display()))?;
+
+    let rotation = match config.rotation {
+        LogRotation::Never => Some(Rotation::NEVER),
+        LogRotation::Hourly => Some(Rotation::HOURLY),
+        LogRotation::Daily => Some(Rotation::DAILY),
+        LogRotation::Size => None,
+    };
+
+    let (writer, guard) = match rotation {
+        None => {
+            let writer = SizeRotatingFile::new(
+                path.clone(),
+                config.max_file_size.get() * 1024 * 1024,
+                config.max_files,
+            )
+            .with_context(|| format!("Opening log file {}", path.display()))?;
+            tracing_appender::non_blocking(writer)
+        }
+        Some(rotation) => {
+            let file_name = path
+                .file_name()
+                .context("Log file path has no file name")?
+                .to_string_lossy()
+                .into_owned();
+
+            let mut builder = RollingFileAppender::builder()
+                .rotation(rotation)
+                .filename_prefix(file_name);
+            if config.max_files > 0 {
+                builder = builder.max_log_files(config.max_files);
+            }
+            let writer = builder
+                .build(directory)
+                .with_context(|| format!("Opening log file {}", path.display()))?;
+            tracing_appender::non_blocking(writer)
+        }
+    };
+
+    // EnvFilter isn't really a Filter, so this we need this ugly workaround for filtering with it.
+    // See https://github.com/tokio-rs/tracing/issues/1868 for more details.
+    let env_filter = Arc::new(
+        tracing_subscriber::EnvFilter::try_new(&config.filter)
+            .with_context(|| format!("Parsing log file filter {}", config.filter))?,
+    );
+    let filter =
+        tracing_subscriber::filter::dynamic_filter_fn(move |m, c| env_filter.enabled(m, c.clone()));
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_filter(filter)
+        .boxed();
+
+    Ok(Some((layer, guard)))
+}
+
+/// A log file which is rotated once it exceeds a maximum size.
+///
+/// Rotated files are suffixed with an increasing index, i.e. `<path>.1` is the most recently
+/// rotated file. At most `max_files` rotated files are kept, or all of them if it is zero.
+struct SizeRotatingFile {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl SizeRotatingFile {
+    fn new(path: PathBuf, max_size: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            max_size,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        // Zero means all rotated files are kept, so make room for exactly one more.
+        let keep = match self.max_files {
+            0 => (1..).find(|i| !self.rotated_path(*i).exists()).unwrap_or(1),
+            max => max,
+        };
+
+        // Shift all rotated files by one, dropping the oldest.
+        match std::fs::remove_file(self.rotated_path(keep)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        for index in (1..keep).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(from, self.rotated_path(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+impl Write for SizeRotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(path: impl AsRef<Path>) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn rotates_at_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pathfinder.log");
+        let mut file = SizeRotatingFile::new(path.clone(), 10, 3).unwrap();
+
+        file.write_all(b"first\n").unwrap();
+        file.write_all(b"abc\n").unwrap();
+        assert!(!file.rotated_path(1).exists());
+
+        // Exceeds the limit, so the current file is rotated and the new one holds the line.
+        file.write_all(b"second\n").unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(read(file.rotated_path(1)), "first\nabc\n");
+        assert_eq!(read(&path), "second\n");
+    }
+
+    #[test]
+    fn keeps_only_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pathfinder.log");
+        let mut file = SizeRotatingFile::new(path.clone(), 4, 2).unwrap();
+
+        for line in ["one\n", "two\n", "three\n", "four\n", "five\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        assert_eq!(read(&path), "five\n");
+        assert_eq!(read(file.rotated_path(1)), "four\n");
+        assert_eq!(read(file.rotated_path(2)), "three\n");
+        assert!(!file.rotated_path(3).exists());
+    }
+
+    #[test]
+    fn zero_max_files_keeps_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pathfinder.log");
+        let mut file = SizeRotatingFile::new(path.clone(), 4, 0).unwrap();
+
+        for line in ["one\n", "two\n", "three\n", "four\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        assert_eq!(read(&path), "four\n");
+        assert_eq!(read(file.rotated_path(1)), "three\n");
+        assert_eq!(read(file.rotated_path(2)), "two\n");
+        assert_eq!(read(file.rotated_path(3)), "one\n");
+    }
+
+    #[test]
+    fn reopening_continues_after_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pathfinder.log");
+
+        let mut file = SizeRotatingFile::new(path.clone(), 10, 2).unwrap();
+        file.write_all(b"first\n").unwrap();
+        file.write_all(b"second\n").unwrap();
+        drop(file);
+
+        // The reopened file appends to the current one and counts its existing size.
+        let mut file = SizeRotatingFile::new(path.clone(), 10, 2).unwrap();
+        file.write_all(b"ab\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(read(&path), "second\nab\n");
+        assert_eq!(read(file.rotated_path(1)), "first\n");
+
+        file.write_all(b"third\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(read(&path), "third\n");
+        assert_eq!(read(file.rotated_path(1)), "second\nab\n");
+        assert_eq!(read(file.rotated_path(2)), "first\n");
+    }
+}
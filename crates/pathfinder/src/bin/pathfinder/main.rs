@@ -3,15 +3,17 @@
 use anyhow::Context;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use pathfinder_common::{consts::VERGEN_GIT_DESCRIBE, BlockNumber, Chain, ChainId, EthereumChain};
+use pathfinder_ethereum::blob::BlobReader;
 use pathfinder_ethereum::{EthereumApi, EthereumClient};
 use pathfinder_lib::state::SyncContext;
 use pathfinder_lib::{
     monitoring::{self},
     state,
 };
-use pathfinder_rpc::context::WebsocketContext;
+use pathfinder_rpc::context::{LagPolicy, WebsocketContext};
+use pathfinder_rpc::gas_price::{self, GasPriceSource};
 use pathfinder_rpc::SyncState;
-use pathfinder_storage::Storage;
+use pathfinder_storage::{JournalMode, Storage};
 use primitive_types::H160;
 use starknet_gateway_client::GatewayApi;
 use std::net::SocketAddr;
@@ -19,13 +21,41 @@ use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::sync::{atomic::AtomicBool, Arc};
 use tracing::info;
+use tracing_appender::non_blocking::WorkerGuard;
 
 use crate::config::NetworkConfig;
 
+mod analyze;
 mod config;
+mod devnet;
+mod doctor;
+mod logging;
+mod service;
+mod signing_key;
+mod snapshot;
 mod update;
 
+#[cfg(all(feature = "jemalloc", feature = "mimalloc"))]
+compile_error!("The `jemalloc` and `mimalloc` features are mutually exclusive");
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static ALLOCATOR: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
 fn main() -> anyhow::Result<()> {
+    #[cfg(windows)]
+    if let Some(result) = service::windows::run_as_service(run) {
+        return result;
+    }
+
+    run()
+}
+
+fn run() -> anyhow::Result<()> {
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .thread_stack_size(8 * 1024 * 1024)
@@ -40,9 +70,32 @@ async fn async_main() -> anyhow::Result<()> {
         std::env::set_var("RUST_LOG", "pathfinder=info");
     }
 
-    let config = config::Config::parse();
+    let mut config = config::Config::parse();
 
-    setup_tracing(config.color, config.debug.pretty_log);
+    // Dropping the guard stops file logging, so keep it alive until shutdown.
+    // Console logs must not be mixed into a block stream written to stdout.
+    let console_to_stderr = matches!(
+        config.block_stream,
+        Some(pathfinder_lib::block_stream::Output::Stdout)
+    );
+    let _log_guard = setup_tracing(
+        config.color,
+        config.debug.pretty_log,
+        console_to_stderr,
+        &config.log_file,
+    )
+    .context("Setting up logging")?;
+
+    if let Some(config::Command::Doctor) = config.command {
+        return doctor::run(config).await;
+    }
+
+    if let Some(config::Command::SigningKey(command)) = &config.command {
+        return match command {
+            config::SigningKeyCommand::Generate { file } => signing_key::generate(file),
+            config::SigningKeyCommand::Rotate { file } => signing_key::rotate(file),
+        };
+    }
 
     info!(
         // this is expected to be $(last_git_tag)-$(commits_since)-$(commit_hash)
@@ -52,6 +105,11 @@ async fn async_main() -> anyhow::Result<()> {
 
     permission_check(&config.data_directory)?;
 
+    if config.heap_profiling {
+        pathfinder_lib::heap_profile::activate().context("Activating heap profiling")?;
+        info!("Heap profiling activated");
+    }
+
     let available_parallelism = std::thread::available_parallelism()?;
 
     rayon::ThreadPoolBuilder::new()
@@ -61,13 +119,55 @@ async fn async_main() -> anyhow::Result<()> {
 
     // A readiness flag which is used to indicate that pathfinder is ready via monitoring.
     let readiness = Arc::new(AtomicBool::new(false));
+    // A health flag which is cleared via monitoring once sync gives up.
+    let healthy = Arc::new(AtomicBool::new(true));
+
+    if let Some(NetworkConfig::Devnet { chain_id }) = &config.network {
+        let chain_id = chain_id.clone();
+        return devnet::run(config, chain_id, available_parallelism, readiness).await;
+    }
+
+    if let Some(block) = config.fork_block {
+        let network = config
+            .network
+            .take()
+            .context("A network is required to fork")?;
+        let pathfinder_context = PathfinderContext::configure_and_proxy_check(
+            network,
+            config.data_directory.clone(),
+            config.gateway_recording.clone(),
+            config.gateway_max_class_size,
+        )
+        .await
+        .context("Configuring pathfinder")?;
+
+        return devnet::fork(
+            config,
+            pathfinder_context,
+            block,
+            available_parallelism,
+            readiness,
+        )
+        .await;
+    }
 
-    let ethereum = EthereumContext::setup(config.ethereum.url, config.ethereum.password)
+    let ethereum = config
+        .ethereum
+        .clone()
+        .context("Ethereum is required unless running a devnet or a fork")?;
+    let beacon_url = ethereum.beacon_url;
+    let ws_url = ethereum.ws_url;
+    let confirmations = ethereum.confirmations;
+    let ethereum = EthereumContext::setup(ethereum.url, ethereum.password)
         .await
         .context("Creating Ethereum context")?;
+    let blob_reader = beacon_url
+        .map(|url| BlobReader::new(ethereum.client.clone(), url))
+        .transpose()
+        .context("Creating blob reader")?;
 
     // Use the default starknet network if none was configured.
-    let network = match config.network {
+    let network = match config.network.take() {
         Some(network) => network,
         None => ethereum
             .default_network()
@@ -75,29 +175,121 @@ async fn async_main() -> anyhow::Result<()> {
     };
 
     // Spawn monitoring if configured.
-    if let Some(address) = config.monitor_address {
-        let network_label = match &network {
-            NetworkConfig::Mainnet => "mainnet",
-            NetworkConfig::Testnet => "testnet",
-            NetworkConfig::Testnet2 => "testnet2",
-            NetworkConfig::Integration => "integration",
-            NetworkConfig::Custom { .. } => "custom",
-        };
-        spawn_monitoring(network_label, address, readiness.clone())
-            .await
-            .context("Starting monitoring task")?;
+    let persisted_counters = match config.monitor_address {
+        Some(address) => {
+            let network_label = match &network {
+                NetworkConfig::Mainnet => "mainnet",
+                NetworkConfig::Testnet => "testnet",
+                NetworkConfig::Testnet2 => "testnet2",
+                NetworkConfig::Integration => "integration",
+                NetworkConfig::Custom { .. } => "custom",
+                NetworkConfig::Devnet { .. } => unreachable!("Devnet is handled separately"),
+            };
+            let (_, counters) =
+                spawn_monitoring(network_label, address, readiness.clone(), healthy.clone())
+                    .await
+                    .context("Starting monitoring task")?;
+            Some(counters)
+        }
+        None => None,
+    };
+
+    if let Some(starknet_gateway_client::Recording::Record(directory)) = &config.gateway_recording {
+        std::fs::create_dir_all(directory).context("Creating gateway recording directory")?;
     }
 
-    let pathfinder_context =
-        PathfinderContext::configure_and_proxy_check(network, config.data_directory)
-            .await
-            .context("Configuring pathfinder")?;
+    let pathfinder_context = PathfinderContext::configure_and_proxy_check(
+        network,
+        config.data_directory.clone(),
+        config.gateway_recording.clone(),
+        config.gateway_max_class_size,
+    )
+    .await
+    .context("Configuring pathfinder")?;
 
     verify_networks(pathfinder_context.network, ethereum.chain)?;
 
+    if let Some(config::Command::ImportSnapshot { file, force }) = &config.command {
+        anyhow::ensure!(
+            config.trie_database.is_none(),
+            "Snapshots include the tries, import without --storage.trie-database and split the database afterwards"
+        );
+        let file = file.clone();
+        let force = *force;
+        let database = pathfinder_context.database.clone();
+
+        info!(
+            ?file,
+            ?database,
+            "Importing snapshot, this can take a while"
+        );
+        tokio::task::spawn_blocking(move || snapshot::import(&file, &database, force))
+            .await
+            .context("Joining import task")??;
+        info!("Import complete. Run `pathfinder run` to start the node.");
+
+        return Ok(());
+    }
+
+    if let Some(config::Command::Database(config::DatabaseCommand::SplitTries)) = config.command {
+        let trie_database = config
+            .trie_database
+            .clone()
+            .context("Splitting the database requires --storage.trie-database")?;
+        anyhow::ensure!(
+            matches!(config.sqlite_wal, JournalMode::Rollback),
+            "A separate trie database requires --sqlite-wal=false"
+        );
+        let database = pathfinder_context.database.clone();
+        if Storage::is_trie_database_split(&database)? {
+            info!("The database is already split.");
+            return Ok(());
+        }
+
+        info!(
+            ?trie_database,
+            "Moving trie data into a separate database, this can take a while"
+        );
+        Storage::migrate(database.clone(), config.sqlite_wal).context("Migrating database")?;
+        tokio::task::spawn_blocking(move || {
+            Storage::split_trie_database(&database, &trie_database)
+        })
+        .await
+        .context("Joining split task")??;
+        info!("Split complete. Run `pathfinder database vacuum` to reclaim the space of the main database.");
+
+        return Ok(());
+    }
+
     // Setup and verify database
-    let storage_manager =
-        Storage::migrate(pathfinder_context.database.clone(), config.sqlite_wal).unwrap();
+    let storage_manager = Storage::migrate_with_trie_database(
+        pathfinder_context.database.clone(),
+        config.trie_database.clone(),
+        config.sqlite_wal,
+    )
+    .unwrap()
+    .with_tuning(config.sqlite_tuning);
+    let storage_manager = match config.slow_query_threshold {
+        Some(threshold) => storage_manager.with_slow_query_threshold(threshold),
+        None => storage_manager,
+    };
+
+    if let Some(config::Command::Database(config::DatabaseCommand::Analyze)) = config.command {
+        return analyze::run(storage_manager, &config).await;
+    }
+
+    if let Some(config::Command::Database(config::DatabaseCommand::Vacuum)) = config.command {
+        info!("Vacuuming database, this can take a while");
+        let reclaimed = tokio::task::spawn_blocking(move || storage_manager.vacuum())
+            .await
+            .context("Joining vacuum task")??;
+        info!(
+            "Vacuum complete, reclaimed {} MiB.",
+            reclaimed / (1024 * 1024)
+        );
+
+        return Ok(());
+    }
     let sync_storage = storage_manager
         // 5 is enough for normal sync operations, and then `available_parallelism` for
         // the rayon thread pool workers to use.
@@ -109,30 +301,86 @@ Hint: This is usually caused by exceeding the file descriptor limit of your syst
       Try increasing the file limit to using `ulimit` or similar tooling.",
         )?;
 
-    // Set the rpc file connection limit to a fraction of the RPC connections.
-    // Having this be too large is counter productive as disk IO will then slow down
-    // all queries.
-    let max_rpc_connections: u32 = config
-        .max_rpc_connections
-        .get()
-        .try_into()
-        .expect("usize should cast to u32");
-    let rpc_storage = std::cmp::max(10, max_rpc_connections / 8);
-    let rpc_storage = NonZeroU32::new(rpc_storage).expect("A non-zero minimum is set");
-    let rpc_storage = storage_manager.create_pool(rpc_storage).context(
-        r"Creating database connection pool for RPC
+    if let Some(config::Command::Replay { from, to }) = config.command {
+        let from = BlockNumber::new(from).context("Invalid --from block number")?;
+        let to = BlockNumber::new(to).context("Invalid --to block number")?;
+        let verify_hashes = config.verify_tree_hashes;
 
-Hint: This is usually caused by exceeding the file descriptor limit of your system.
-      Try increasing the file limit to using `ulimit` or similar tooling.",
-    )?;
+        info!(%from, %to, "Replaying state updates");
+        tokio::task::spawn_blocking(move || {
+            pathfinder_lib::state::replay::replay(sync_storage, from, to, verify_hashes)
+        })
+        .await
+        .context("Joining replay task")??;
+        info!("Replay complete, all state commitments match.");
 
-    let execution_storage_pool_size = config.execution_concurrency.unwrap_or_else(|| {
-        std::num::NonZeroU32::new(available_parallelism.get() as u32)
-            .expect("The number of CPU cores should be non-zero")
-    });
-    let execution_storage = storage_manager
-        .create_pool(execution_storage_pool_size)
-        .context(r"")?;
+        return Ok(());
+    }
+
+    if let Some(config::Command::Export {
+        format,
+        tables,
+        from,
+        to,
+        output,
+    }) = &config.command
+    {
+        let from = BlockNumber::new(*from).context("Invalid --from block number")?;
+        let to = BlockNumber::new(*to).context("Invalid --to block number")?;
+        let format = match format {
+            config::ExportFormat::Csv => pathfinder_lib::export::Format::Csv,
+            config::ExportFormat::Parquet => pathfinder_lib::export::Format::Parquet,
+        };
+        let tables = tables
+            .iter()
+            .map(|table| match table {
+                config::ExportTable::Blocks => pathfinder_lib::export::Table::Blocks,
+                config::ExportTable::Transactions => pathfinder_lib::export::Table::Transactions,
+                config::ExportTable::Events => pathfinder_lib::export::Table::Events,
+            })
+            .collect::<Vec<_>>();
+
+        let output = output.clone();
+
+        info!(%from, %to, ?output, "Exporting blocks");
+        let files = tokio::task::spawn_blocking(move || {
+            pathfinder_lib::export::export(sync_storage, &tables, format, from, to, &output)
+        })
+        .await
+        .context("Joining export task")??;
+        for (path, rows) in files {
+            info!(?path, %rows, "Exported");
+        }
+
+        return Ok(());
+    }
+
+    let (rpc_storage, execution_storage) =
+        create_rpc_pools(&storage_manager, &config, available_parallelism)?;
+
+    if let Some(config::Command::GenerateTestVectors { block, output }) = &config.command {
+        let block = BlockNumber::new(*block).context("Invalid --block number")?;
+        let (_, rx_pending) = tokio::sync::watch::channel(Default::default());
+        let context = pathfinder_rpc::context::RpcContext::new(
+            rpc_storage,
+            execution_storage,
+            Arc::new(SyncState::default()),
+            pathfinder_context.network_id,
+            pathfinder_context.gateway.clone(),
+            rx_pending,
+            config.rpc_batch_concurrency_limit,
+        );
+
+        info!(%block, ?output, "Generating test vectors");
+        let files = pathfinder_lib::test_vectors::generate(context, block, output).await?;
+        info!(
+            files = files.len(),
+            version = pathfinder_lib::test_vectors::RPC_VERSION,
+            "Generated test vectors"
+        );
+
+        return Ok(());
+    }
 
     let p2p_storage = storage_manager
         .create_pool(NonZeroU32::new(1).unwrap())
@@ -165,55 +413,66 @@ Hint: This is usually caused by exceeding the file descriptor limit of your syst
         rx_pending,
         config.rpc_batch_concurrency_limit,
     );
+    let rpc_server = configure_rpc_server(&config, context)?;
 
-    let context = if config.websocket.enabled {
-        context.with_websockets(WebsocketContext::new(
-            config.websocket.socket_buffer_capacity,
-            config.websocket.topic_sender_capacity,
-        ))
-    } else {
-        context
-    };
+    let head_divergence = config.p2p.head_divergence();
+    let syncs_from_peers = config.p2p.syncs_from_peers();
+    let (gateway_halted_tx, gateway_halted) = tokio::sync::watch::channel(false);
+    let divergence_gateway = pathfinder_context.gateway.clone();
 
-    let default_version = match config.rpc_root_version {
-        config::RpcVersion::V03 => pathfinder_rpc::DefaultVersion::V03,
-        config::RpcVersion::V04 => pathfinder_rpc::DefaultVersion::V04,
-        config::RpcVersion::V05 => pathfinder_rpc::DefaultVersion::V05,
-    };
-
-    let rpc_server = pathfinder_rpc::RpcServer::new(config.rpc_address, context, default_version);
-    let rpc_server = match config.rpc_cors_domains {
-        Some(allowed_origins) => rpc_server.with_cors(allowed_origins),
-        None => rpc_server,
-    };
-
-    let (p2p_handle, sequencer) = start_p2p(
+    let (p2p_handle, sequencer, peer_heads) = start_p2p(
         pathfinder_context.network_id,
         p2p_storage,
         pathfinder_context.gateway,
         config.p2p,
+        gateway_halted,
     )
     .await?;
 
+    // Subscribed before the sender is moved into the sync context.
+    let stall_pending = tx_pending.subscribe();
+
+    let poll_intervals = state::PollIntervals::default_for(pathfinder_context.network);
     let sync_context = SyncContext {
         storage: sync_storage,
-        ethereum: ethereum.client,
+        ethereum: match confirmations {
+            Some(confirmations) => ethereum.client.with_confirmations(confirmations),
+            None => ethereum.client,
+        },
         chain: pathfinder_context.network,
         chain_id: pathfinder_context.network_id,
         core_address: pathfinder_context.l1_core_address,
         sequencer,
         state: sync_state.clone(),
-        head_poll_interval: config.poll_interval,
+        head_poll_interval: config.poll_interval.unwrap_or(poll_intervals.head),
         pending_data: tx_pending,
-        pending_poll_interval: config
-            .poll_pending
-            .then_some(std::time::Duration::from_secs(2)),
+        pending_poll_interval: config.poll_pending.then(|| {
+            config
+                .pending_poll_interval
+                .unwrap_or(poll_intervals.pending)
+        }),
         // Currently p2p does not perform block hash and state commitment verification if p2p header lacks state commitment
         block_validation_mode: state::l2::BlockValidationMode::Strict,
+        transaction_validation_mode: if config.strict_transaction_hashes {
+            state::l2::TransactionValidationMode::Strict
+        } else {
+            state::l2::TransactionValidationMode::AllowMismatch
+        },
         websocket_txs: rpc_server.get_topic_broadcasters().cloned(),
+        response_cache: rpc_server.get_response_cache().clone(),
         block_cache_size: 1_000,
         restart_delay: config.debug.restart_delay,
+        max_restarts: config.sync_max_restarts,
+        healthy,
         verify_tree_hashes: config.verify_tree_hashes,
+        blob_reader,
+        l1_new_heads: ws_url.map(pathfinder_ethereum::watch_new_heads),
+        event_capacity: config.sync_event_capacity.get(),
+        event_overflow: match config.sync_event_overflow {
+            config::SyncEventOverflow::Backpressure => state::OverflowStrategy::Backpressure,
+            config::SyncEventOverflow::CoalescePending => state::OverflowStrategy::CoalescePending,
+        },
+        receipts_from_execution: syncs_from_peers,
     };
 
     let sync_handle = tokio::spawn(state::sync(sync_context, state::l1::sync, state::l2::sync));
@@ -225,13 +484,151 @@ Hint: This is usually caused by exceeding the file descriptor limit of your syst
 
     info!("📡 HTTP-RPC server started on: {}", local_addr);
 
+    if let Some(address) = config.grpc_address {
+        let grpc_storage = storage_manager
+            .create_pool(NonZeroU32::new(available_parallelism.get() as u32).unwrap())
+            .context("Creating database connection pool for gRPC")?;
+        let (_, local_addr) = pathfinder_grpc::serve(grpc_storage, address)
+            .await
+            .context("Starting the gRPC server")?;
+        info!("📡 gRPC server started on: {}", local_addr);
+    }
+
+    if let Some(output) = config.block_stream.clone() {
+        let storage = storage_manager
+            .create_pool(NonZeroU32::new(1).unwrap())
+            .context("Creating database connection pool for block stream")?;
+        tokio::spawn(async move {
+            if let Err(error) = pathfinder_lib::block_stream::stream_blocks(storage, output).await {
+                tracing::error!(reason=?error, "Block stream stopped");
+            }
+        });
+    }
+
     let update_handle = tokio::spawn(update::poll_github_for_releases());
 
+    pathfinder_common::cache::CacheRegistry::global().set_max_memory(
+        config
+            .cache_max_memory
+            .map(|bytes| usize::try_from(bytes).unwrap_or(usize::MAX)),
+    );
+    tokio::spawn(update_cache_memory());
+
+    if let Some(window) = config.auto_vacuum_window {
+        let storage = storage_manager
+            .create_pool(NonZeroU32::new(1).unwrap())
+            .context("Creating database connection pool for vacuuming")?;
+        tokio::spawn(async move {
+            if let Err(error) = pathfinder_lib::vacuum::auto_vacuum(storage, window).await {
+                tracing::error!(reason=?error, "Scheduled vacuuming stopped");
+            }
+        });
+    }
+
+    let mut stall_thresholds = config.stall_thresholds;
+    if stall_thresholds.pending.is_some() && !config.poll_pending {
+        tracing::warn!("Ignoring --alert.pending-stall-minutes as the pending block is not polled");
+        stall_thresholds.pending = None;
+    }
+    if stall_thresholds != Default::default() {
+        let storage = storage_manager
+            .create_pool(NonZeroU32::new(1).unwrap())
+            .context("Creating database connection pool for stall alerts")?;
+        let webhook = config.alert_webhook_url.clone();
+        tokio::spawn(async move {
+            if let Err(error) = pathfinder_lib::stall_alert::monitor(
+                storage,
+                stall_pending,
+                stall_thresholds,
+                webhook,
+            )
+            .await
+            {
+                tracing::error!(reason=?error, "Stall alerts stopped");
+            }
+        });
+    }
+
+    if let Some(divergence) = head_divergence {
+        let storage = storage_manager
+            .create_pool(NonZeroU32::new(1).unwrap())
+            .context("Creating database connection pool for head divergence alerts")?;
+        let webhook = config.alert_webhook_url.clone();
+        tokio::spawn(async move {
+            if let Err(error) = pathfinder_lib::head_divergence::monitor(
+                storage,
+                divergence_gateway,
+                peer_heads,
+                divergence,
+                webhook,
+                gateway_halted_tx,
+            )
+            .await
+            {
+                tracing::error!(reason=?error, "Head divergence alerts stopped");
+            }
+        });
+    }
+
+    let persisted_counters = match persisted_counters {
+        Some(counters) => {
+            let storage = storage_manager
+                .create_pool(NonZeroU32::new(1).unwrap())
+                .context("Creating database connection pool for metrics")?;
+            let persist_storage = storage.clone();
+            let persist_counters = counters.clone();
+            tokio::spawn(async move {
+                if let Err(error) =
+                    pathfinder_lib::persisted_metrics::persist(persist_storage, persist_counters)
+                        .await
+                {
+                    tracing::error!(reason=?error, "Persisting metrics stopped");
+                }
+            });
+            Some((storage, counters))
+        }
+        None => None,
+    };
+
+    let slow_query_storage = match config.slow_query_threshold {
+        Some(_) => {
+            let storage = storage_manager
+                .create_pool(NonZeroU32::new(1).unwrap())
+                .context("Creating database connection pool for slow queries")?;
+            let persist_storage = storage.clone();
+            tokio::spawn(async move {
+                if let Err(error) =
+                    pathfinder_lib::persisted_metrics::persist_slow_queries(persist_storage).await
+                {
+                    tracing::error!(reason=?error, "Persisting slow queries stopped");
+                }
+            });
+            Some(storage)
+        }
+        None => None,
+    };
+
     // We are now ready.
     readiness.store(true, std::sync::atomic::Ordering::Relaxed);
+    service::notify_ready();
 
     // Monitor our spawned process tasks.
     tokio::select! {
+        _ = service::shutdown_requested() => {
+            info!("Shutting down");
+            service::notify_stopping();
+            if let Some((storage, counters)) = persisted_counters {
+                if let Err(error) = pathfinder_lib::persisted_metrics::snapshot(&storage, &counters) {
+                    tracing::warn!(%error, "Persisting metrics failed");
+                }
+            }
+            if let Some(storage) = slow_query_storage {
+                if let Err(error) = pathfinder_lib::persisted_metrics::record_slow_queries(&storage) {
+                    tracing::warn!(%error, "Persisting slow queries failed");
+                }
+            }
+            return Ok(());
+        }
         result = sync_handle => {
             match result {
                 Ok(task_result) => tracing::error!("Sync process ended unexpected with: {:?}", task_result),
@@ -261,49 +658,230 @@ Hint: This is usually caused by exceeding the file descriptor limit of your syst
     anyhow::bail!("Unexpected shutdown");
 }
 
+/// Creates the database connection pools used by the RPC server, for queries and execution
+/// respectively.
+fn create_rpc_pools(
+    storage_manager: &pathfinder_storage::StorageManager,
+    config: &config::Config,
+    available_parallelism: std::num::NonZeroUsize,
+) -> anyhow::Result<(Storage, Storage)> {
+    // Set the rpc file connection limit to a fraction of the RPC connections.
+    // Having this be too large is counter productive as disk IO will then slow down
+    // all queries.
+    let max_rpc_connections: u32 = config
+        .max_rpc_connections
+        .get()
+        .try_into()
+        .expect("usize should cast to u32");
+    let rpc_storage = std::cmp::max(10, max_rpc_connections / 8);
+    let rpc_storage = NonZeroU32::new(rpc_storage).expect("A non-zero minimum is set");
+    let rpc_storage = storage_manager.create_pool(rpc_storage).context(
+        r"Creating database connection pool for RPC
+
+Hint: This is usually caused by exceeding the file descriptor limit of your system.
+      Try increasing the file limit to using `ulimit` or similar tooling.",
+    )?;
+
+    let execution_storage_pool_size = config.execution_concurrency.unwrap_or_else(|| {
+        std::num::NonZeroU32::new(available_parallelism.get() as u32)
+            .expect("The number of CPU cores should be non-zero")
+    });
+    let execution_storage = storage_manager
+        .create_pool(execution_storage_pool_size)
+        .context(r"")?;
+
+    Ok((rpc_storage, execution_storage))
+}
+
+/// Applies the RPC options of the configuration.
+fn configure_rpc_server(
+    config: &config::Config,
+    context: pathfinder_rpc::context::RpcContext,
+) -> anyhow::Result<pathfinder_rpc::RpcServer> {
+    let context = context.with_params_strictness(match config.rpc_params_strictness {
+        config::ParamsStrictness::Strict => pathfinder_rpc::ParamsStrictness::Strict,
+        config::ParamsStrictness::Lenient => pathfinder_rpc::ParamsStrictness::Lenient,
+    });
+
+    let context = match config.rpc_max_response_bytes {
+        Some(limit) => context.with_max_response_bytes(limit),
+        None => context,
+    };
+
+    let context = context
+        .with_execution_limits(config.rpc_execution_limits)
+        .with_load_shedding(config.rpc_load_shedding)
+        .with_response_cache_capacity(config.rpc_response_cache_size);
+
+    let context = context.with_gas_price_source(match &config.gas_price_source {
+        config::GasPriceSource::Gateway => GasPriceSource::Gateway,
+        config::GasPriceSource::L1 => {
+            let ethereum = config
+                .ethereum
+                .as_ref()
+                .context("The L1 gas price source requires Ethereum")?;
+            let client = ethereum_client(ethereum.url.clone(), ethereum.password.as_deref())?;
+            GasPriceSource::L1(gas_price::Cached::ethereum(client))
+        }
+        config::GasPriceSource::Fixed(gas_price) => GasPriceSource::Fixed(*gas_price),
+    });
+
+    let context = if config.websocket.enabled {
+        context.with_websockets(
+            WebsocketContext::new(
+                config.websocket.socket_buffer_capacity,
+                config.websocket.topic_sender_capacity,
+            )
+            .with_max_subscriptions(config.websocket.max_subscriptions)
+            .with_lag_policy(match config.websocket.lag_policy {
+                config::WebsocketLagPolicy::Disconnect => LagPolicy::Disconnect,
+                config::WebsocketLagPolicy::DropOldest => LagPolicy::DropOldest,
+            }),
+        )
+    } else {
+        context
+    };
+
+    let default_version = match config.rpc_root_version {
+        config::RpcVersion::V03 => pathfinder_rpc::DefaultVersion::V03,
+        config::RpcVersion::V04 => pathfinder_rpc::DefaultVersion::V04,
+        config::RpcVersion::V05 => pathfinder_rpc::DefaultVersion::V05,
+    };
+
+    let rpc_server = pathfinder_rpc::RpcServer::new(config.rpc_address, context, default_version);
+    let rpc_server = match config.rpc_cors_domains.clone() {
+        Some(allowed_origins) => rpc_server.with_cors(allowed_origins),
+        None => rpc_server,
+    };
+    let rpc_server = rpc_server.with_method_filter(
+        pathfinder_rpc::MethodFilter::default()
+            .allow(config.rpc_allow_methods.clone())
+            .deny(config.rpc_deny_methods.clone()),
+    );
+    let rpc_server = match config.rpc_internal_address {
+        Some(address) => {
+            info!(%address, "Serving all RPC methods at the internal address");
+            rpc_server.with_internal_address(address)
+        }
+        None => rpc_server,
+    };
+
+    let rpc_server =
+        if config.rpc_auth_jwt_secret.is_some() || config.rpc_auth_tokens_file.is_some() {
+            use pathfinder_rpc::middleware::auth;
+
+            let authenticator = auth::Authenticator::default();
+            let authenticator = match &config.rpc_auth_jwt_secret {
+                Some(secret) => authenticator.with_jwt_secret(secret.as_bytes()),
+                None => authenticator,
+            };
+            let authenticator = match &config.rpc_auth_tokens_file {
+                Some(file) => authenticator.with_tokens(
+                    auth::load_tokens(file)
+                        .with_context(|| format!("Loading RPC tokens from {}", file.display()))?,
+                ),
+                None => authenticator,
+            };
+            rpc_server.with_authentication(authenticator)
+        } else {
+            rpc_server
+        };
+
+    Ok(match &config.rpc_signing_key_file {
+        Some(file) => {
+            use pathfinder_rpc::middleware::signature;
+
+            let key = signature::read_key(file)
+                .with_context(|| format!("Reading RPC signing key from {}", file.display()))?;
+            info!(public_key=%signature::public_key_hex(&key), "Signing RPC responses");
+            rpc_server.with_response_signing(key)
+        }
+        None => rpc_server,
+    })
+}
+
 #[cfg(feature = "tokio-console")]
-fn setup_tracing(color: config::Color, pretty_log: bool) {
+fn setup_tracing(
+    color: config::Color,
+    pretty_log: bool,
+    console_to_stderr: bool,
+    log_file: &config::LogFileConfig,
+) -> anyhow::Result<Option<WorkerGuard>> {
     use tracing_subscriber::prelude::*;
 
     // EnvFilter isn't really a Filter, so this we need this ugly workaround for filtering with it.
     // See https://github.com/tokio-rs/tracing/issues/1868 for more details.
     let env_filter = Arc::new(tracing_subscriber::EnvFilter::from_default_env());
     let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(console_writer(console_to_stderr))
         .with_ansi(color.is_color_enabled())
         .with_target(pretty_log);
+    let fmt_layer = if pretty_log {
+        fmt_layer.pretty().boxed()
+    } else {
+        fmt_layer.compact().boxed()
+    };
     let filter =
         tracing_subscriber::filter::dynamic_filter_fn(move |m, c| env_filter.enabled(m, c.clone()));
 
-    if pretty_log {
-        tracing_subscriber::registry()
-            .with(fmt_layer.pretty().with_filter(filter))
-            .with(console_subscriber::spawn())
-            .init();
-    } else {
-        tracing_subscriber::registry()
-            .with(fmt_layer.compact().with_filter(filter))
-            .with(console_subscriber::spawn())
-            .init();
-    }
+    let (file_layer, guard) = logging::file_layer(log_file)?.unzip();
+
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(filter))
+        .with(file_layer)
+        .with(console_subscriber::spawn())
+        .init();
+
+    Ok(guard)
 }
 
 #[cfg(not(feature = "tokio-console"))]
-fn setup_tracing(color: config::Color, pretty_log: bool) {
+fn setup_tracing(
+    color: config::Color,
+    pretty_log: bool,
+    console_to_stderr: bool,
+    log_file: &config::LogFileConfig,
+) -> anyhow::Result<Option<WorkerGuard>> {
     use time::macros::format_description;
+    use tracing_subscriber::prelude::*;
 
     let time_fmt = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
     let time_fmt = tracing_subscriber::fmt::time::UtcTime::new(time_fmt);
 
-    let subscriber = tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+    // EnvFilter isn't really a Filter, so this we need this ugly workaround for filtering with it.
+    // See https://github.com/tokio-rs/tracing/issues/1868 for more details.
+    let env_filter = Arc::new(tracing_subscriber::EnvFilter::from_default_env());
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(console_writer(console_to_stderr))
         .with_target(pretty_log)
         .with_timer(time_fmt)
         .with_ansi(color.is_color_enabled());
+    let fmt_layer = if pretty_log {
+        fmt_layer.pretty().boxed()
+    } else {
+        fmt_layer.compact().boxed()
+    };
+    let filter =
+        tracing_subscriber::filter::dynamic_filter_fn(move |m, c| env_filter.enabled(m, c.clone()));
 
-    if pretty_log {
-        subscriber.pretty().init();
+    // The console and the file have independent filters.
+    let (file_layer, guard) = logging::file_layer(log_file)?.unzip();
+
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(filter))
+        .with(file_layer)
+        .init();
+
+    Ok(guard)
+}
+
+fn console_writer(stderr: bool) -> tracing_subscriber::fmt::writer::BoxMakeWriter {
+    use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+    if stderr {
+        BoxMakeWriter::new(std::io::stderr)
     } else {
-        subscriber.compact().init();
+        BoxMakeWriter::new(std::io::stdout)
     }
 }
 
@@ -322,9 +900,11 @@ async fn start_p2p(
     storage: Storage,
     sequencer: starknet_gateway_client::Client,
     config: config::P2PConfig,
+    gateway_halted: tokio::sync::watch::Receiver<bool>,
 ) -> anyhow::Result<(
     tokio::task::JoinHandle<()>,
     pathfinder_lib::p2p_network::client::HybridClient,
+    Option<p2p::HeadRx>,
 )> {
     use p2p::libp2p::identity::Keypair;
     use pathfinder_lib::p2p_network::{client::HybridClient, P2PContext};
@@ -368,14 +948,29 @@ async fn start_p2p(
         keypair,
         listen_on: config.listen_on,
         bootstrap_addresses: config.bootstrap_addresses,
+        receive_heads: config.divergence.is_some(),
     };
 
+    // Peers' state diff commitments are only trusted if the sequencer signed them.
+    let sequencer_public_key = sequencer
+        .public_key()
+        .await
+        .context("Fetching the sequencer's public key")?;
+
     let (_p2p_peers, p2p_client, head_receiver, p2p_handle) =
         pathfinder_lib::p2p_network::start(context).await?;
 
     Ok((
         p2p_handle,
-        HybridClient::new(config.proxy, p2p_client, sequencer, head_receiver),
+        HybridClient::new(
+            config.proxy,
+            p2p_client,
+            sequencer,
+            head_receiver.clone(),
+            sequencer_public_key,
+            gateway_halted,
+        ),
+        Some(head_receiver),
     ))
 }
 
@@ -385,10 +980,24 @@ async fn start_p2p(
     _: Storage,
     sequencer: starknet_gateway_client::Client,
     _: config::P2PConfig,
-) -> anyhow::Result<(tokio::task::JoinHandle<()>, starknet_gateway_client::Client)> {
+    _: tokio::sync::watch::Receiver<bool>,
+) -> anyhow::Result<(
+    tokio::task::JoinHandle<()>,
+    starknet_gateway_client::Client,
+    Option<tokio::sync::watch::Receiver<Option<pathfinder_lib::head_divergence::Head>>>,
+)> {
     let join_handle = tokio::task::spawn(async move { futures::future::pending().await });
 
-    Ok((join_handle, sequencer))
+    Ok((join_handle, sequencer, None))
+}
+
+/// Periodically records the memory used by caches, and shrinks them if they exceed the budget.
+async fn update_cache_memory() {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
+    loop {
+        interval.tick().await;
+        pathfinder_common::cache::CacheRegistry::global().update();
+    }
 }
 
 /// Spawns the monitoring task at the given address.
@@ -396,16 +1005,31 @@ async fn spawn_monitoring(
     network: &str,
     address: SocketAddr,
     readiness: Arc<AtomicBool>,
-) -> anyhow::Result<tokio::task::JoinHandle<()>> {
-    let prometheus_handle = PrometheusBuilder::new()
+    healthy: Arc<AtomicBool>,
+) -> anyhow::Result<(
+    tokio::task::JoinHandle<()>,
+    pathfinder_lib::persisted_metrics::PersistedCounters,
+)> {
+    let recorder = PrometheusBuilder::new()
         .add_global_label("network", network)
-        .install_recorder()
-        .context("Creating Prometheus recorder")?;
+        .build_recorder();
+    let prometheus_handle = recorder.handle();
+    let recorder = pathfinder_lib::persisted_metrics::PersistingRecorder::new(recorder);
+    let counters = recorder.counters();
+    metrics::set_boxed_recorder(Box::new(recorder)).context("Installing metrics recorder")?;
 
     metrics::gauge!("pathfinder_build_info", 1.0, "version" => VERGEN_GIT_DESCRIBE);
 
-    let handle = monitoring::spawn_server(address, readiness, prometheus_handle).await;
-    Ok(handle)
+    let handle = monitoring::spawn_server(address, readiness, healthy, prometheus_handle).await;
+    Ok((handle, counters))
+}
+
+fn ethereum_client(url: reqwest::Url, password: Option<&str>) -> anyhow::Result<EthereumClient> {
+    match password {
+        Some(password) => EthereumClient::with_password(url, password),
+        None => EthereumClient::new(url),
+    }
+    .context("Creating Ethereum client")
 }
 
 /// Convenience bundle for an Ethereum transport and chain.
@@ -417,11 +1041,7 @@ struct EthereumContext {
 impl EthereumContext {
     /// Configure an [EthereumContext]'s transport and read the chain ID using it.
     async fn setup(url: reqwest::Url, password: Option<String>) -> anyhow::Result<Self> {
-        let client = if let Some(password) = password.as_ref() {
-            EthereumClient::with_password(url, password).context("Creating Ethereum client")?
-        } else {
-            EthereumClient::new(url).context("Creating Ethereum client")?
-        };
+        let client = ethereum_client(url, password.as_deref())?;
 
         let chain = client.get_chain().await.context(
             r"Determining Ethereum chain.
@@ -470,39 +1090,49 @@ mod pathfinder_context {
     use pathfinder_ethereum::core_addr;
     use primitive_types::H160;
     use reqwest::Url;
-    use starknet_gateway_client::Client as GatewayClient;
+    use starknet_gateway_client::{Client as GatewayClient, Recording};
 
     impl PathfinderContext {
         pub async fn configure_and_proxy_check(
             cfg: NetworkConfig,
             data_directory: PathBuf,
+            recording: Option<Recording>,
+            max_class_size: usize,
         ) -> anyhow::Result<Self> {
+            let configure_gateway = |gateway: GatewayClient| {
+                let gateway = gateway.with_max_class_size(max_class_size);
+                match recording.clone() {
+                    Some(recording) => gateway.with_recording(recording),
+                    None => gateway,
+                }
+            };
+
             let context = match cfg {
                 NetworkConfig::Mainnet => Self {
                     network: Chain::Mainnet,
                     network_id: ChainId::MAINNET,
-                    gateway: GatewayClient::mainnet(),
+                    gateway: configure_gateway(GatewayClient::mainnet()),
                     database: data_directory.join("mainnet.sqlite"),
                     l1_core_address: H160::from(core_addr::MAINNET),
                 },
                 NetworkConfig::Testnet => Self {
                     network: Chain::Testnet,
                     network_id: ChainId::TESTNET,
-                    gateway: GatewayClient::testnet(),
+                    gateway: configure_gateway(GatewayClient::testnet()),
                     database: data_directory.join("goerli.sqlite"),
                     l1_core_address: H160::from(core_addr::TESTNET),
                 },
                 NetworkConfig::Testnet2 => Self {
                     network: Chain::Testnet2,
                     network_id: ChainId::TESTNET2,
-                    gateway: GatewayClient::testnet2(),
+                    gateway: configure_gateway(GatewayClient::testnet2()),
                     database: data_directory.join("testnet2.sqlite"),
                     l1_core_address: H160::from(core_addr::TESTNET2),
                 },
                 NetworkConfig::Integration => Self {
                     network: Chain::Integration,
                     network_id: ChainId::INTEGRATION,
-                    gateway: GatewayClient::integration(),
+                    gateway: configure_gateway(GatewayClient::integration()),
                     database: data_directory.join("integration.sqlite"),
                     l1_core_address: H160::from(core_addr::INTEGRATION),
                 },
@@ -510,9 +1140,18 @@ mod pathfinder_context {
                     gateway,
                     feeder_gateway,
                     chain_id,
-                } => Self::configure_custom(gateway, feeder_gateway, chain_id, data_directory)
-                    .await
-                    .context("Configuring custom network")?,
+                } => Self::configure_custom(
+                    configure_gateway,
+                    gateway,
+                    feeder_gateway,
+                    chain_id,
+                    data_directory,
+                )
+                .await
+                .context("Configuring custom network")?,
+                NetworkConfig::Devnet { .. } => {
+                    anyhow::bail!("A devnet has no gateway to configure")
+                }
             };
 
             Ok(context)
@@ -522,6 +1161,7 @@ mod pathfinder_context {
         /// by checking for a proxy gateway by comparing against L1 starknet address against of
         /// the known networks.
         async fn configure_custom(
+            configure_gateway: impl Fn(GatewayClient) -> GatewayClient,
             gateway: Url,
             feeder: Url,
             chain_id: String,
@@ -530,8 +1170,9 @@ mod pathfinder_context {
             use pathfinder_crypto::Felt;
             use starknet_gateway_client::GatewayApi;
 
-            let gateway =
-                GatewayClient::with_urls(gateway, feeder).context("Creating gateway client")?;
+            let gateway = GatewayClient::with_urls(gateway, feeder)
+                .map(configure_gateway)
+                .context("Creating gateway client")?;
 
             let network_id =
                 ChainId(Felt::from_be_slice(chain_id.as_bytes()).context("Parsing chain ID")?);
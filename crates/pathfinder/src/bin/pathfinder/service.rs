@@ -0,0 +1,255 @@
+//! Integration with service managers, so that they can track the node's actual readiness rather
+//! than the start of the process.
+//!
+//! On Linux, systemd is notified using the
+//! [sd_notify](https://www.freedesktop.org/software/systemd/man/sd_notify.html) protocol if
+//! `NOTIFY_SOCKET` is set, e.g. by a `Type=notify` unit. `READY=1` is sent once the node is
+//! ready, `WATCHDOG=1` keep-alives are sent if the unit sets `WatchdogSec`, and `STOPPING=1` is
+//! sent on shutdown.
+//!
+//! On Windows, the node runs as a service if started by the service control manager. The service
+//! is reported as running once the node is ready, and stopping it shuts down the node.
+use std::time::Duration;
+
+/// Notifies the service manager that the node is ready, and starts the watchdog keep-alives.
+pub fn notify_ready() {
+    #[cfg(unix)]
+    {
+        sd_notify::notify("READY=1");
+        if let Some(interval) = sd_notify::watchdog_interval() {
+            tokio::spawn(async move {
+                loop {
+                    sd_notify::notify("WATCHDOG=1");
+                    tokio::time::sleep(interval).await;
+                }
+            });
+        }
+    }
+
+    #[cfg(windows)]
+    windows::set_state(windows_service::service::ServiceState::Running);
+}
+
+/// Notifies the service manager that the node is shutting down.
+pub fn notify_stopping() {
+    #[cfg(unix)]
+    sd_notify::notify("STOPPING=1");
+
+    #[cfg(windows)]
+    windows::set_state(windows_service::service::ServiceState::StopPending);
+}
+
+/// Resolves once the node is asked to shut down, by Ctrl-C, `SIGTERM` or the Windows service
+/// manager.
+pub async fn shutdown_requested() {
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                tracing::warn!(%error, "Listening for SIGTERM failed");
+                std::future::pending::<()>().await
+            }
+        }
+    };
+    #[cfg(windows)]
+    let terminate = windows::stop_requested();
+    #[cfg(not(any(unix, windows)))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            if let Err(error) = result {
+                tracing::warn!(%error, "Listening for Ctrl-C failed");
+                std::future::pending::<()>().await
+            }
+        }
+        _ = terminate => {}
+    }
+}
+
+#[cfg(unix)]
+mod sd_notify {
+    use super::*;
+
+    /// Sends the state to the socket given by `NOTIFY_SOCKET`, if set.
+    ///
+    /// Failures are only logged, as the node works the same without a service manager.
+    pub fn notify(state: &str) {
+        let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+            return;
+        };
+
+        if let Err(error) = send(&path, state) {
+            tracing::warn!(%error, %state, "Notifying service manager failed");
+        }
+    }
+
+    fn send(path: &std::ffi::OsStr, state: &str) -> std::io::Result<()> {
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::net::UnixDatagram;
+
+        let socket = UnixDatagram::unbound()?;
+        match path.as_bytes() {
+            // Sockets in the abstract namespace are given with a leading '@'.
+            #[cfg(target_os = "linux")]
+            [b'@', name @ ..] => {
+                use std::os::linux::net::SocketAddrExt;
+                let address = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &address)?;
+            }
+            _ => {
+                socket.send_to(state.as_bytes(), path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Half of the watchdog timeout requested by the service manager, if it is meant for this
+    /// process.
+    pub fn watchdog_interval() -> Option<Duration> {
+        let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+        if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+            if pid.parse::<u32>().ok()? != std::process::id() {
+                return None;
+            }
+        }
+
+        Some(Duration::from_micros(usec) / 2)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn sends_state_to_socket() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("notify.sock");
+            let receiver = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+            send(path.as_os_str(), "READY=1").unwrap();
+
+            let mut buf = [0u8; 16];
+            let len = receiver.recv(&mut buf).unwrap();
+            assert_eq!(&buf[..len], b"READY=1");
+        }
+    }
+}
+
+#[cfg(windows)]
+pub mod windows {
+    use std::ffi::OsString;
+    use std::sync::OnceLock;
+
+    use windows_service::service::{
+        ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus,
+        ServiceType,
+    };
+    use windows_service::service_control_handler::{
+        self, ServiceControlHandlerResult, ServiceStatusHandle,
+    };
+
+    use super::*;
+
+    const SERVICE_NAME: &str = "pathfinder";
+    /// Returned by the dispatcher if the process was not started by the service control manager.
+    const ERROR_FAILED_SERVICE_CONTROLLER_CONNECT: i32 = 1063;
+
+    /// Runs the node, set before dispatching to the service control manager.
+    static RUN: OnceLock<fn() -> anyhow::Result<()>> = OnceLock::new();
+    /// Set while running as a service.
+    static STATUS_HANDLE: OnceLock<ServiceStatusHandle> = OnceLock::new();
+
+    windows_service::define_windows_service!(ffi_service_main, service_main);
+
+    /// Runs `run` as a service if the process was started by the service control manager.
+    ///
+    /// Returns [None] if it wasn't, in which case the node should be run directly.
+    pub fn run_as_service(run: fn() -> anyhow::Result<()>) -> Option<anyhow::Result<()>> {
+        RUN.set(run).ok()?;
+
+        match windows_service::service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+            Ok(()) => Some(Ok(())),
+            Err(windows_service::Error::Winapi(error))
+                if error.raw_os_error() == Some(ERROR_FAILED_SERVICE_CONTROLLER_CONNECT) =>
+            {
+                None
+            }
+            Err(error) => Some(Err(error.into())),
+        }
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        let handler = |control| match control {
+            ServiceControl::Stop => {
+                stop().notify_one();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        };
+        let handle = match service_control_handler::register(SERVICE_NAME, handler) {
+            Ok(handle) => handle,
+            Err(error) => {
+                eprintln!("Registering service control handler failed: {error}");
+                return;
+            }
+        };
+        let _ = STATUS_HANDLE.set(handle);
+        set_state(ServiceState::StartPending);
+
+        let result = RUN.get().expect("Set before dispatching")();
+        let exit_code = match &result {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("{error:?}");
+                1
+            }
+        };
+
+        if let Some(handle) = STATUS_HANDLE.get() {
+            let _ = handle.set_service_status(status(
+                ServiceState::Stopped,
+                ServiceExitCode::ServiceSpecific(exit_code),
+            ));
+        }
+    }
+
+    fn status(state: ServiceState, exit_code: ServiceExitCode) -> ServiceStatus {
+        ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: match state {
+                ServiceState::Running => ServiceControlAccept::STOP,
+                _ => ServiceControlAccept::empty(),
+            },
+            exit_code,
+            checkpoint: 0,
+            wait_hint: Duration::from_secs(60),
+            process_id: None,
+        }
+    }
+
+    pub(super) fn set_state(state: ServiceState) {
+        let Some(handle) = STATUS_HANDLE.get() else {
+            return;
+        };
+
+        if let Err(error) = handle.set_service_status(status(state, ServiceExitCode::Win32(0))) {
+            tracing::warn!(%error, "Updating service status failed");
+        }
+    }
+
+    pub(super) async fn stop_requested() {
+        stop().notified().await
+    }
+
+    fn stop() -> &'static tokio::sync::Notify {
+        static STOP: OnceLock<tokio::sync::Notify> = OnceLock::new();
+        STOP.get_or_init(tokio::sync::Notify::new)
+    }
+}
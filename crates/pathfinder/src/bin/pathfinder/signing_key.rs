@@ -0,0 +1,72 @@
+//! Managing the key RPC responses are signed with, see `pathfinder signing-key`.
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use pathfinder_rpc::middleware::signature;
+
+/// Creates the key file with a new key, and prints its public key.
+pub fn generate(file: &Path) -> anyhow::Result<()> {
+    let key = signature::generate_key();
+    signature::write_key(file, &key)?;
+
+    println!("Public key: {}", signature::public_key_hex(&key));
+    Ok(())
+}
+
+/// Replaces the key in the key file with a new key, keeping the old key in `<file>.previous`,
+/// and prints both public keys.
+pub fn rotate(file: &Path) -> anyhow::Result<()> {
+    let old = signature::read_key(file).context("Reading current key")?;
+
+    let new_file = with_suffix(file, ".new");
+    let previous_file = with_suffix(file, ".previous");
+
+    // The new key is written next to the current one first, so that a failed rotation leaves the
+    // current key in place.
+    let new = signature::generate_key();
+    signature::write_key(&new_file, &new).context("Writing new key")?;
+
+    match std::fs::remove_file(&previous_file) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(e).context("Removing previous key")
+        }
+        _ => {}
+    }
+    std::fs::rename(file, &previous_file).context("Moving current key")?;
+    std::fs::rename(&new_file, file).context("Moving new key into place")?;
+
+    println!("Previous public key: {}", signature::public_key_hex(&old));
+    println!("New public key:      {}", signature::public_key_hex(&new));
+    Ok(())
+}
+
+fn with_suffix(file: &Path, suffix: &str) -> PathBuf {
+    let mut path = file.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_keeps_previous_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("signing.key");
+
+        generate(&file).unwrap();
+        let first = signature::read_key(&file).unwrap();
+        // Existing keys are not replaced.
+        generate(&file).unwrap_err();
+
+        rotate(&file).unwrap();
+        rotate(&file).unwrap();
+        let previous = signature::read_key(&with_suffix(&file, ".previous")).unwrap();
+        let current = signature::read_key(&file).unwrap();
+
+        assert_ne!(previous.to_bytes(), first.to_bytes());
+        assert_ne!(current.to_bytes(), previous.to_bytes());
+        assert!(!with_suffix(&file, ".new").exists());
+    }
+}
@@ -0,0 +1,130 @@
+//! Importing database snapshots, see `pathfinder import-snapshot`.
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use anyhow::Context;
+
+/// The magic number at the start of zstd frames.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// The header at the start of Sqlite database files.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Imports the snapshot `file` as the `database`, decompressing it if it is zstd compressed.
+///
+/// The snapshot is written next to the database first, so that a failed import leaves an
+/// existing database untouched.
+pub fn import(file: &Path, database: &Path, force: bool) -> anyhow::Result<()> {
+    if database.exists() && !force {
+        anyhow::bail!(
+            "Database {} already exists, use --force to replace it",
+            database.display()
+        );
+    }
+
+    let mut snapshot = std::fs::File::open(file).context("Opening snapshot")?;
+    let mut magic = [0u8; 4];
+    let compressed = match snapshot.read_exact(&mut magic) {
+        Ok(()) => magic == ZSTD_MAGIC,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => false,
+        Err(e) => return Err(e).context("Reading snapshot"),
+    };
+    snapshot.rewind().context("Rewinding snapshot")?;
+
+    let mut import_path = database.as_os_str().to_owned();
+    import_path.push(".import");
+    let import_path = std::path::PathBuf::from(import_path);
+
+    let result = (|| {
+        let mut output = std::io::BufWriter::new(
+            std::fs::File::create(&import_path).context("Creating database file")?,
+        );
+        if compressed {
+            zstd::stream::copy_decode(BufReader::new(snapshot), &mut output)
+                .context("Decompressing snapshot")?;
+        } else {
+            std::io::copy(&mut BufReader::new(snapshot), &mut output)
+                .context("Copying snapshot")?;
+        }
+        let output = output.into_inner().context("Writing database file")?;
+        output.sync_all().context("Syncing database file")?;
+
+        let mut header = [0u8; SQLITE_HEADER.len()];
+        std::fs::File::open(&import_path)
+            .and_then(|mut f| f.read_exact(&mut header))
+            .context("Reading database header")?;
+        anyhow::ensure!(&header == SQLITE_HEADER, "The snapshot is not a database");
+
+        // The journal files of a replaced database would corrupt the snapshot.
+        for suffix in ["-wal", "-shm", "-journal"] {
+            let mut journal = database.as_os_str().to_owned();
+            journal.push(suffix);
+            match std::fs::remove_file(&journal) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e).context("Removing database journal")
+                }
+                _ => {}
+            }
+        }
+
+        std::fs::rename(&import_path, database).context("Moving database into place")
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&import_path);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn database_bytes() -> Vec<u8> {
+        let mut bytes = SQLITE_HEADER.to_vec();
+        bytes.extend_from_slice(&[1u8; 100]);
+        bytes
+    }
+
+    #[test]
+    fn imports_compressed_and_plain_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("mainnet.sqlite");
+
+        let plain = dir.path().join("plain.sqlite");
+        std::fs::write(&plain, database_bytes()).unwrap();
+        import(&plain, &database, false).unwrap();
+        assert_eq!(std::fs::read(&database).unwrap(), database_bytes());
+
+        // Existing databases are only replaced if forced.
+        let compressed = dir.path().join("compressed.sqlite.zst");
+        let mut encoder =
+            zstd::Encoder::new(std::fs::File::create(&compressed).unwrap(), 0).unwrap();
+        encoder.write_all(&database_bytes()).unwrap();
+        encoder.finish().unwrap();
+        import(&compressed, &database, false).unwrap_err();
+
+        let wal = dir.path().join("mainnet.sqlite-wal");
+        std::fs::write(&wal, b"wal").unwrap();
+        import(&compressed, &database, true).unwrap();
+        assert_eq!(std::fs::read(&database).unwrap(), database_bytes());
+        assert!(!wal.exists());
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("mainnet.sqlite");
+        std::fs::write(&database, database_bytes()).unwrap();
+
+        let other = dir.path().join("other.txt");
+        std::fs::write(&other, b"not a database").unwrap();
+        import(&other, &database, true).unwrap_err();
+
+        // The existing database is left untouched.
+        assert_eq!(std::fs::read(&database).unwrap(), database_bytes());
+        assert!(!dir.path().join("mainnet.sqlite.import").exists());
+    }
+}
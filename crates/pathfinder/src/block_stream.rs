@@ -0,0 +1,347 @@
+//! A flat-file stream of committed blocks, for downstream systems which want to consume the chain
+//! without polling the RPC API.
+//!
+//! Each committed block is written as a length-delimited protobuf
+//! [StreamRecord](pathfinder_grpc::proto::StreamRecord) holding its header, transactions,
+//! receipt events and state diff. Reorgs are written as a record naming the first removed block,
+//! followed by the blocks of the new chain.
+//!
+//! A stream to a file is append-only and resumes after its last record on restart. A trailing
+//! record cut short by a crash is truncated first. A stream to stdout starts at genesis.
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber};
+use pathfinder_crypto::Felt;
+use pathfinder_grpc::proto::{stream_record, StreamRecord};
+use pathfinder_storage::{BlockId, Storage, Transaction};
+use prost::Message;
+
+/// How often the database is checked for new blocks.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// The number of most recently written blocks kept to detect reorgs. Reorgs deeper than this
+/// restart the stream at the oldest kept block.
+const RECENT_BLOCKS: usize = 1024;
+/// The maximum number of blocks written per poll, which limits how long a database transaction
+/// is kept open while catching up.
+const BLOCKS_PER_POLL: usize = 1000;
+
+/// Where the block stream is written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    Stdout,
+    File(PathBuf),
+}
+
+impl std::str::FromStr for Output {
+    type Err = std::convert::Infallible;
+
+    /// `-` is stdout, anything else a file path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(Self::Stdout),
+            path => Ok(Self::File(path.into())),
+        }
+    }
+}
+
+/// Writes committed blocks to `output` as they appear in the database, forever.
+pub async fn stream_blocks(storage: Storage, output: Output) -> anyhow::Result<()> {
+    let mut stream = tokio::task::spawn_blocking(move || BlockStream::open(&output))
+        .await
+        .context("Joining block stream setup")??;
+    tracing::info!(next_block=%stream.next, "Streaming blocks");
+
+    loop {
+        let storage = storage.clone();
+        let written;
+        (stream, written) = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            let mut db = storage.connection()?;
+            let tx = db.transaction()?;
+            let written = stream.poll(&tx)?;
+            Ok((stream, written))
+        })
+        .await
+        .context("Joining block stream")??;
+
+        if written < BLOCKS_PER_POLL {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+struct BlockStream<W> {
+    writer: W,
+    /// The next block to write.
+    next: BlockNumber,
+    /// The most recently written blocks, oldest first.
+    recent: VecDeque<(BlockNumber, BlockHash)>,
+}
+
+impl BlockStream<Box<dyn Write + Send>> {
+    fn open(output: &Output) -> anyhow::Result<Self> {
+        match output {
+            Output::Stdout => Ok(Self::new(Box::new(io::stdout()))),
+            Output::File(path) => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .read(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Opening block stream {}", path.display()))?;
+                let mut stream = Self::new(Box::new(BufWriter::new(file.try_clone()?)));
+                let length = stream
+                    .resume(&mut file)
+                    .with_context(|| format!("Reading block stream {}", path.display()))?;
+                file.set_len(length)
+                    .context("Truncating incomplete block stream record")?;
+                Ok(stream)
+            }
+        }
+    }
+}
+
+impl<W: Write> BlockStream<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            next: BlockNumber::GENESIS,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Replays the records of an existing stream, and returns the length of its complete
+    /// records.
+    fn resume(&mut self, file: &mut File) -> anyhow::Result<u64> {
+        let mut reader = BufReader::new(file);
+        let mut length = 0;
+
+        while let Some((record, size)) = read_record(&mut reader)? {
+            match record.record {
+                Some(stream_record::Record::Block(block)) => {
+                    let header = block
+                        .block
+                        .and_then(|block| block.header)
+                        .context("Block record without header")?;
+                    let number = BlockNumber::new(header.number).context("Invalid block number")?;
+                    let hash = Felt::from_be_slice(&header.hash).context("Invalid block hash")?;
+                    self.push(number, BlockHash(hash));
+                }
+                Some(stream_record::Record::Reorg(number)) => {
+                    let number = BlockNumber::new(number).context("Invalid reorg block number")?;
+                    self.remove_from(number);
+                }
+                None => anyhow::bail!("Empty record"),
+            }
+            length += size;
+        }
+
+        Ok(length)
+    }
+
+    /// Writes a reorg record if written blocks were replaced, followed by up to
+    /// [BLOCKS_PER_POLL] new blocks. Returns the number of blocks written.
+    fn poll(&mut self, tx: &Transaction<'_>) -> anyhow::Result<usize> {
+        let mut first_removed = None;
+        while let Some(&(number, hash)) = self.recent.back() {
+            if tx.block_id(number.into())? == Some((number, hash)) {
+                break;
+            }
+            first_removed = Some(number);
+            self.recent.pop_back();
+        }
+        if let Some(number) = first_removed {
+            tracing::debug!(%number, "Streaming reorg");
+            self.write(stream_record::Record::Reorg(number.get()))?;
+            self.next = number;
+        }
+
+        let mut written = 0;
+        while written < BLOCKS_PER_POLL {
+            let Some(record) = pathfinder_grpc::read_block_record(tx, self.next)
+                .with_context(|| format!("Reading block {}", self.next))?
+            else {
+                break;
+            };
+            let hash = tx
+                .block_id(BlockId::Number(self.next))?
+                .map(|(_, hash)| hash)
+                .context("Block hash is missing")?;
+
+            self.write(stream_record::Record::Block(record))?;
+            self.push(self.next, hash);
+            written += 1;
+        }
+        self.writer.flush().context("Flushing block stream")?;
+
+        if written > 0 {
+            metrics::counter!("block_stream_blocks_total", written as u64);
+        }
+        Ok(written)
+    }
+
+    fn write(&mut self, record: stream_record::Record) -> anyhow::Result<()> {
+        let record = StreamRecord {
+            record: Some(record),
+        };
+        self.writer
+            .write_all(&record.encode_length_delimited_to_vec())
+            .context("Writing block stream record")
+    }
+
+    fn push(&mut self, number: BlockNumber, hash: BlockHash) {
+        if self.recent.len() == RECENT_BLOCKS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((number, hash));
+        self.next = number + 1;
+    }
+
+    fn remove_from(&mut self, number: BlockNumber) {
+        while self.recent.back().is_some_and(|(n, _)| *n >= number) {
+            self.recent.pop_back();
+        }
+        self.next = number;
+    }
+}
+
+/// Reads the next record and its size in bytes, or [None] at the end of the stream or of its
+/// complete records.
+fn read_record(reader: &mut impl Read) -> anyhow::Result<Option<(StreamRecord, u64)>> {
+    // The length prefix is a varint of at most 10 bytes.
+    let mut prefix = Vec::with_capacity(10);
+    let length = loop {
+        let mut byte = [0u8];
+        if reader.read(&mut byte)? == 0 {
+            return Ok(None);
+        }
+        prefix.push(byte[0]);
+        if byte[0] & 0x80 == 0 {
+            break prost::encoding::decode_varint(&mut prefix.as_slice())
+                .context("Decoding record length")?;
+        }
+        anyhow::ensure!(prefix.len() < 10, "Invalid record length");
+    };
+
+    let mut buffer = vec![0u8; length as usize];
+    match reader.read_exact(&mut buffer) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let record = StreamRecord::decode(buffer.as_slice()).context("Decoding record")?;
+
+    Ok(Some((record, prefix.len() as u64 + length)))
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_storage::test_utils;
+
+    use super::*;
+
+    fn records(mut bytes: &[u8]) -> Vec<stream_record::Record> {
+        std::iter::from_fn(|| read_record(&mut bytes).unwrap())
+            .map(|(record, _)| record.record.unwrap())
+            .collect()
+    }
+
+    fn block_numbers(records: &[stream_record::Record]) -> Vec<u64> {
+        records
+            .iter()
+            .filter_map(|record| match record {
+                stream_record::Record::Block(block) => Some(
+                    block
+                        .block
+                        .as_ref()
+                        .unwrap()
+                        .header
+                        .as_ref()
+                        .unwrap()
+                        .number,
+                ),
+                stream_record::Record::Reorg(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn streams_all_blocks_with_state_updates() {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let mut db = storage.connection().unwrap();
+        let tx = db.transaction().unwrap();
+
+        let mut stream = BlockStream::new(Vec::new());
+        assert_eq!(stream.poll(&tx).unwrap(), test_data.headers.len());
+        assert_eq!(stream.poll(&tx).unwrap(), 0);
+
+        let records = records(&stream.writer);
+        let expected = test_data
+            .headers
+            .iter()
+            .map(|header| header.number.get())
+            .collect::<Vec<_>>();
+        assert_eq!(block_numbers(&records), expected);
+
+        let events = records
+            .iter()
+            .map(|record| match record {
+                stream_record::Record::Block(block) => {
+                    assert!(block.state_update.is_some());
+                    block.events.len()
+                }
+                stream_record::Record::Reorg(_) => unreachable!(),
+            })
+            .sum::<usize>();
+        assert_eq!(events, test_data.events.len());
+    }
+
+    #[test]
+    fn reorg_is_streamed() {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let mut db = storage.connection().unwrap();
+        let latest = test_data.headers.last().unwrap().number;
+
+        let mut stream = BlockStream::new(Vec::new());
+        stream.poll(&db.transaction().unwrap()).unwrap();
+        stream.writer.clear();
+
+        let tx = db.transaction().unwrap();
+        tx.purge_block(latest).unwrap();
+        tx.commit().unwrap();
+        stream.poll(&db.transaction().unwrap()).unwrap();
+
+        assert_eq!(
+            records(&stream.writer),
+            vec![stream_record::Record::Reorg(latest.get())]
+        );
+        assert_eq!(stream.next, latest);
+    }
+
+    #[test]
+    fn resumes_after_last_complete_record() {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let mut db = storage.connection().unwrap();
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let output = Output::File(path.to_path_buf());
+
+        let mut stream = BlockStream::open(&output).unwrap();
+        stream.poll(&db.transaction().unwrap()).unwrap();
+        drop(stream);
+
+        // Simulate a crash while writing a record.
+        let complete = std::fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[100, 1, 2, 3]).unwrap();
+        drop(file);
+
+        let mut stream = BlockStream::open(&output).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), complete);
+        assert_eq!(stream.next, test_data.headers.last().unwrap().number + 1);
+        assert_eq!(stream.poll(&db.transaction().unwrap()).unwrap(), 0);
+    }
+}
@@ -0,0 +1,368 @@
+//! Export of historical block data into files for analytics.
+//!
+//! Each [Table] is written to its own file in the output directory, e.g. `events.parquet`.
+//! Blocks are read and written one at a time, so memory use does not depend on the size of the
+//! exported range. Hashes, addresses and other field elements are written as `0x` prefixed hex
+//! strings.
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use pathfinder_common::BlockNumber;
+use pathfinder_storage::{Storage, Transaction};
+use starknet_gateway_types::reply::transaction as gateway;
+
+mod writer;
+
+use writer::{CsvWriter, ParquetWriter, TableWriter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Table {
+    Blocks,
+    Transactions,
+    Events,
+}
+
+impl Table {
+    fn name(&self) -> &'static str {
+        match self {
+            Table::Blocks => "blocks",
+            Table::Transactions => "transactions",
+            Table::Events => "events",
+        }
+    }
+
+    fn columns(&self) -> &'static [Column] {
+        use ColumnType::*;
+
+        match self {
+            Table::Blocks => &[
+                Column("number", Int),
+                Column("hash", Text),
+                Column("parent_hash", Text),
+                Column("timestamp", Int),
+                Column("gas_price", Text),
+                Column("sequencer_address", Text),
+                Column("starknet_version", Text),
+                Column("state_commitment", Text),
+                Column("transaction_count", Int),
+                Column("event_count", Int),
+            ],
+            Table::Transactions => &[
+                Column("block_number", Int),
+                Column("transaction_index", Int),
+                Column("hash", Text),
+                Column("type", Text),
+                Column("contract_address", Text),
+                Column("actual_fee", Text),
+                Column("execution_status", Text),
+                Column("event_count", Int),
+            ],
+            Table::Events => &[
+                Column("block_number", Int),
+                Column("transaction_index", Int),
+                Column("transaction_hash", Text),
+                Column("event_index", Int),
+                Column("from_address", Text),
+                Column("keys", Text),
+                Column("data", Text),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Int,
+    Text,
+}
+
+/// A column's name and type.
+#[derive(Debug, Clone, Copy)]
+struct Column(&'static str, ColumnType);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Text(String),
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        // Block numbers, indices and timestamps all fit into an i64.
+        Self::Int(value as i64)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+/// Exports the `tables` for blocks `from..=to` into `directory`, and returns the written files
+/// along with their number of rows.
+pub fn export(
+    storage: Storage,
+    tables: &[Table],
+    format: Format,
+    from: BlockNumber,
+    to: BlockNumber,
+    directory: &Path,
+) -> anyhow::Result<Vec<(PathBuf, usize)>> {
+    anyhow::ensure!(from <= to, "The range must not be empty");
+    std::fs::create_dir_all(directory).context("Creating output directory")?;
+
+    let mut writers = tables
+        .iter()
+        .map(|table| {
+            let extension = match format {
+                Format::Csv => "csv",
+                Format::Parquet => "parquet",
+            };
+            let path = directory.join(format!("{}.{extension}", table.name()));
+            let file = std::fs::File::create(&path)
+                .with_context(|| format!("Creating {}", path.display()))?;
+
+            let writer: Box<dyn TableWriter> = match format {
+                Format::Csv => Box::new(CsvWriter::new(file, table.columns())?),
+                Format::Parquet => {
+                    Box::new(ParquetWriter::new(file, table.name(), table.columns())?)
+                }
+            };
+            Ok((*table, path, writer))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut db = storage
+        .connection()
+        .context("Creating database connection")?;
+    // A single transaction provides a consistent snapshot of the range.
+    let tx = db.transaction().context("Creating database transaction")?;
+    anyhow::ensure!(
+        tx.block_exists(to.into())?,
+        "Block {to} is not available in the database"
+    );
+
+    let mut number = from;
+    loop {
+        for (table, _, writer) in writers.iter_mut() {
+            write_block(&tx, number, *table, writer.as_mut())
+                .with_context(|| format!("Exporting {} of block {number}", table.name()))?;
+        }
+
+        if number == to {
+            break;
+        }
+        number += 1;
+    }
+
+    writers
+        .into_iter()
+        .map(|(_, path, writer)| {
+            let rows = writer
+                .finish()
+                .with_context(|| format!("Finishing {}", path.display()))?;
+            Ok((path, rows))
+        })
+        .collect()
+}
+
+fn write_block(
+    tx: &Transaction<'_>,
+    number: BlockNumber,
+    table: Table,
+    writer: &mut dyn TableWriter,
+) -> anyhow::Result<()> {
+    match table {
+        Table::Blocks => {
+            let header = tx
+                .block_header(number.into())
+                .context("Querying block header")?
+                .context("Block header is missing")?;
+
+            writer.write_row(vec![
+                header.number.get().into(),
+                header.hash.to_string().into(),
+                header.parent_hash.to_string().into(),
+                header.timestamp.get().into(),
+                header.gas_price.0.to_string().into(),
+                header.sequencer_address.to_string().into(),
+                header.starknet_version.as_str().to_owned().into(),
+                header.state_commitment.to_string().into(),
+                header.transaction_count.into(),
+                header.event_count.into(),
+            ])
+        }
+        Table::Transactions => {
+            for (transaction, receipt) in transaction_data(tx, number)? {
+                let kind = match transaction {
+                    gateway::Transaction::Declare(_) => "DECLARE",
+                    gateway::Transaction::Deploy(_) => "DEPLOY",
+                    gateway::Transaction::DeployAccount(_) => "DEPLOY_ACCOUNT",
+                    gateway::Transaction::Invoke(_) => "INVOKE",
+                    gateway::Transaction::L1Handler(_) => "L1_HANDLER",
+                };
+                let status = match receipt.execution_status {
+                    gateway::ExecutionStatus::Succeeded => "SUCCEEDED",
+                    gateway::ExecutionStatus::Reverted => "REVERTED",
+                };
+
+                writer.write_row(vec![
+                    number.get().into(),
+                    receipt.transaction_index.get().into(),
+                    transaction.hash().to_string().into(),
+                    kind.to_owned().into(),
+                    transaction.contract_address().to_string().into(),
+                    receipt
+                        .actual_fee
+                        .map(|fee| fee.to_string())
+                        .unwrap_or_default()
+                        .into(),
+                    status.to_owned().into(),
+                    receipt.events.len().into(),
+                ])?;
+            }
+            Ok(())
+        }
+        Table::Events => {
+            for (_, receipt) in transaction_data(tx, number)? {
+                for (index, event) in receipt.events.into_iter().enumerate() {
+                    writer.write_row(vec![
+                        number.get().into(),
+                        receipt.transaction_index.get().into(),
+                        receipt.transaction_hash.to_string().into(),
+                        index.into(),
+                        event.from_address.to_string().into(),
+                        join(event.keys).into(),
+                        join(event.data).into(),
+                    ])?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn transaction_data(
+    tx: &Transaction<'_>,
+    number: BlockNumber,
+) -> anyhow::Result<Vec<(gateway::Transaction, gateway::Receipt)>> {
+    tx.transaction_data_for_block(number.into())
+        .context("Querying transactions")?
+        .context("Block is missing")
+}
+
+/// Joins the values into a single space separated string.
+fn join<T: std::fmt::Display>(values: Vec<T>) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_storage::test_utils;
+
+    use super::*;
+
+    #[test]
+    fn csv() {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let directory = tempfile::tempdir().unwrap();
+        let to = test_data.headers.last().unwrap().number;
+
+        let files = export(
+            storage,
+            &[Table::Blocks, Table::Transactions, Table::Events],
+            Format::Csv,
+            BlockNumber::GENESIS,
+            to,
+            directory.path(),
+        )
+        .unwrap();
+
+        let rows = files.iter().map(|(_, rows)| *rows).collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                test_data.headers.len(),
+                test_data.transactions.len(),
+                test_data.events.len()
+            ]
+        );
+
+        let blocks = std::fs::read_to_string(directory.path().join("blocks.csv")).unwrap();
+        let mut lines = blocks.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("number,hash,parent_hash,"));
+        let first = lines.next().unwrap();
+        assert!(first.starts_with(&format!("0,{},", test_data.headers[0].hash)));
+    }
+
+    #[test]
+    fn parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let directory = tempfile::tempdir().unwrap();
+        let to = test_data.headers.last().unwrap().number;
+
+        export(
+            storage,
+            &[Table::Events],
+            Format::Parquet,
+            BlockNumber::GENESIS,
+            to,
+            directory.path(),
+        )
+        .unwrap();
+
+        let file = std::fs::File::open(directory.path().join("events.parquet")).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(
+            reader.metadata().file_metadata().num_rows() as usize,
+            test_data.events.len()
+        );
+        assert_eq!(
+            reader
+                .metadata()
+                .file_metadata()
+                .schema_descr()
+                .num_columns(),
+            Table::Events.columns().len()
+        );
+    }
+
+    #[test]
+    fn missing_blocks_are_an_error() {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let directory = tempfile::tempdir().unwrap();
+        let beyond = test_data.headers.last().unwrap().number + 1;
+
+        export(
+            storage,
+            &[Table::Blocks],
+            Format::Csv,
+            BlockNumber::GENESIS,
+            beyond,
+            directory.path(),
+        )
+        .unwrap_err();
+    }
+}
@@ -0,0 +1,172 @@
+//! Writers of the supported export [formats](super::Format).
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::Arc;
+
+use anyhow::Context;
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+
+use super::{Column, ColumnType, Value};
+
+/// The number of rows buffered per Parquet row group.
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+pub(super) trait TableWriter {
+    /// Writes a row, which must match the table's columns.
+    fn write_row(&mut self, row: Vec<Value>) -> anyhow::Result<()>;
+
+    /// Completes the file and returns the number of rows written.
+    fn finish(self: Box<Self>) -> anyhow::Result<usize>;
+}
+
+pub(super) struct CsvWriter {
+    writer: csv::Writer<BufWriter<File>>,
+    rows: usize,
+}
+
+impl CsvWriter {
+    pub fn new(file: File, columns: &[Column]) -> anyhow::Result<Self> {
+        let mut writer = csv::Writer::from_writer(BufWriter::new(file));
+        writer
+            .write_record(columns.iter().map(|column| column.0))
+            .context("Writing header")?;
+
+        Ok(Self { writer, rows: 0 })
+    }
+}
+
+impl TableWriter for CsvWriter {
+    fn write_row(&mut self, row: Vec<Value>) -> anyhow::Result<()> {
+        let record = row.into_iter().map(|value| match value {
+            Value::Int(value) => value.to_string(),
+            Value::Text(value) => value,
+        });
+        self.writer.write_record(record).context("Writing row")?;
+        self.rows += 1;
+
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> anyhow::Result<usize> {
+        self.writer.flush().context("Flushing file")?;
+        Ok(self.rows)
+    }
+}
+
+pub(super) struct ParquetWriter {
+    writer: SerializedFileWriter<File>,
+    /// The values of the current row group, per column.
+    buffers: Vec<Buffer>,
+    buffered: usize,
+    rows: usize,
+}
+
+enum Buffer {
+    Int(Vec<i64>),
+    Text(Vec<ByteArray>),
+}
+
+impl ParquetWriter {
+    pub fn new(file: File, name: &str, columns: &[Column]) -> anyhow::Result<Self> {
+        let fields = columns
+            .iter()
+            .map(|Column(name, kind)| match kind {
+                ColumnType::Int => format!("REQUIRED INT64 {name};"),
+                ColumnType::Text => format!("REQUIRED BYTE_ARRAY {name} (UTF8);"),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let schema =
+            parquet::schema::parser::parse_message_type(&format!("message {name} {{ {fields} }}"))
+                .context("Parsing schema")?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))
+            .context("Creating Parquet writer")?;
+        let buffers = columns
+            .iter()
+            .map(|Column(_, kind)| match kind {
+                ColumnType::Int => Buffer::Int(Vec::new()),
+                ColumnType::Text => Buffer::Text(Vec::new()),
+            })
+            .collect();
+
+        Ok(Self {
+            writer,
+            buffers,
+            buffered: 0,
+            rows: 0,
+        })
+    }
+
+    /// Writes the buffered rows as a row group.
+    fn flush(&mut self) -> anyhow::Result<()> {
+        let mut row_group = self.writer.next_row_group().context("Creating row group")?;
+
+        for buffer in &mut self.buffers {
+            let mut column = row_group
+                .next_column()
+                .context("Creating column writer")?
+                .context("Column writer is missing")?;
+            match buffer {
+                Buffer::Int(values) => {
+                    column
+                        .typed::<Int64Type>()
+                        .write_batch(values, None, None)?;
+                    values.clear();
+                }
+                Buffer::Text(values) => {
+                    column
+                        .typed::<ByteArrayType>()
+                        .write_batch(values, None, None)?;
+                    values.clear();
+                }
+            }
+            column.close().context("Closing column writer")?;
+        }
+
+        row_group.close().context("Closing row group")?;
+        self.buffered = 0;
+
+        Ok(())
+    }
+}
+
+impl TableWriter for ParquetWriter {
+    fn write_row(&mut self, row: Vec<Value>) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            row.len() == self.buffers.len(),
+            "Row has the wrong number of values"
+        );
+
+        for (buffer, value) in self.buffers.iter_mut().zip(row) {
+            match (buffer, value) {
+                (Buffer::Int(values), Value::Int(value)) => values.push(value),
+                (Buffer::Text(values), Value::Text(value)) => values.push(value.into()),
+                _ => anyhow::bail!("Value does not match the column type"),
+            }
+        }
+        self.buffered += 1;
+        self.rows += 1;
+
+        if self.buffered == ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> anyhow::Result<usize> {
+        if self.buffered > 0 {
+            self.flush()?;
+        }
+        self.writer.close().context("Closing Parquet writer")?;
+
+        Ok(self.rows)
+    }
+}
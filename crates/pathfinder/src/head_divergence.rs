@@ -0,0 +1,244 @@
+//! Detects the gateway diverging from the chain of the peers or of L1.
+//!
+//! In hybrid sync, a node following the gateway would otherwise serve a fork which only the
+//! gateway knows of. [monitor] periodically compares the gateway's head with the head gossiped by
+//! peers and with the latest block accepted on L1. If they diverge, i.e. they are further apart
+//! than the [threshold](DivergenceConfig::threshold) or are on different blocks at the same
+//! height, the `sync_head_diverged{source="..."}` gauge is set to 1 and the optional webhook is
+//! called. Both are reset once the heads agree again.
+//!
+//! If [halting](DivergenceConfig::halt) is enabled, following the gateway is halted while it
+//! diverges from any of the sources.
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockId, BlockNumber};
+use pathfinder_storage::Storage;
+use starknet_gateway_client::GatewayApi;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::alert::Alerts;
+
+/// How often the heads are compared.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The number and hash of a head.
+pub type Head = (BlockNumber, BlockHash);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivergenceConfig {
+    /// The number of blocks by which the heads may differ, as propagation and L1 updates lag.
+    pub threshold: u64,
+    /// Halt following the gateway while it diverges.
+    pub halt: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Source {
+    Peers,
+    L1,
+}
+
+impl Source {
+    fn label(&self) -> &'static str {
+        match self {
+            Source::Peers => "peers",
+            Source::L1 => "l1",
+        }
+    }
+}
+
+/// The body of webhook requests.
+#[derive(Debug, serde::Serialize)]
+struct Alert {
+    source: Source,
+    /// False once the heads agree again.
+    diverged: bool,
+    gateway_head: BlockNumber,
+    source_head: BlockNumber,
+}
+
+/// Whether the gateway's head diverges from the one gossiped by peers.
+///
+/// Either of them may be ahead by up to `threshold` blocks, but not be on a different block at
+/// the same height.
+fn peers_diverged(gateway: Head, peers: Head, threshold: u64) -> bool {
+    let distance = gateway.0.get().abs_diff(peers.0.get());
+    distance > threshold || (distance == 0 && gateway.1 != peers.1)
+}
+
+/// Whether the gateway's head diverges from the latest block accepted on L1.
+///
+/// `gateway_hash` is the gateway's hash of the block at the L1 head's height, if it has one. L1
+/// lags behind the gateway, so the gateway may only be behind L1 by up to `threshold` blocks.
+fn l1_diverged(gateway: Head, l1: Head, gateway_hash: Option<BlockHash>, threshold: u64) -> bool {
+    match gateway_hash {
+        Some(hash) => hash != l1.1,
+        None => l1.0.get().saturating_sub(gateway.0.get()) > threshold,
+    }
+}
+
+/// Compares the gateway's head with the peers' and L1's, forever.
+///
+/// `halt` is set while following the gateway should be halted.
+pub async fn monitor<G: GatewayApi>(
+    storage: Storage,
+    gateway: G,
+    peer_heads: Option<watch::Receiver<Option<Head>>>,
+    config: DivergenceConfig,
+    webhook: Option<reqwest::Url>,
+    halt: watch::Sender<bool>,
+) -> anyhow::Result<()> {
+    let sources = [Source::Peers, Source::L1].map(|source| source.label());
+    let alerts = Alerts::new("sync_head_diverged", "source", &sources, webhook)?;
+
+    let mut peers_state = false;
+    let mut l1_state = false;
+    // The last L1 head the gateway agreed with, as the L1 head rarely changes.
+    let mut agreed_l1_head: Option<Head> = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let gateway_head = match gateway.head().await {
+            Ok(head) => head,
+            Err(error) => {
+                tracing::debug!(%error, "Fetching gateway head for divergence check failed");
+                continue;
+            }
+        };
+
+        let peers_head = peer_heads.as_ref().and_then(|heads| *heads.borrow());
+
+        let l1_head = {
+            let storage = storage.clone();
+            tokio::task::spawn_blocking(move || l1_head(storage))
+                .await
+                .context("Joining L1 head query")
+                .and_then(|l1_head| l1_head.context("Querying L1 head"))
+        };
+        // A failing query, e.g. while the database is busy, must not stop the monitoring.
+        let l1_head = match l1_head {
+            Ok(l1_head) => l1_head,
+            Err(error) => {
+                tracing::warn!(reason=?error, "Querying L1 head for divergence check failed");
+                continue;
+            }
+        };
+        let l1 = match l1_head {
+            Some(l1_head) => {
+                let gateway_hash = match agreed_l1_head {
+                    Some(head) if head == l1_head => Some(head.1),
+                    _ if l1_head.0 > gateway_head.0 => None,
+                    _ => match gateway.block_header(BlockId::Number(l1_head.0)).await {
+                        Ok((_, hash)) => Some(hash),
+                        Err(error) => {
+                            tracing::debug!(%error, "Fetching gateway block for divergence check failed");
+                            continue;
+                        }
+                    },
+                };
+                if gateway_hash == Some(l1_head.1) {
+                    agreed_l1_head = Some(l1_head);
+                }
+                Some((l1_head, gateway_hash))
+            }
+            None => None,
+        };
+
+        let changes = [
+            peers_head.and_then(|peers_head| {
+                let diverged = peers_diverged(gateway_head, peers_head, config.threshold);
+                (diverged != peers_state).then(|| {
+                    peers_state = diverged;
+                    Alert {
+                        source: Source::Peers,
+                        diverged,
+                        gateway_head: gateway_head.0,
+                        source_head: peers_head.0,
+                    }
+                })
+            }),
+            l1.and_then(|(l1_head, gateway_hash)| {
+                let diverged = l1_diverged(gateway_head, l1_head, gateway_hash, config.threshold);
+                (diverged != l1_state).then(|| {
+                    l1_state = diverged;
+                    Alert {
+                        source: Source::L1,
+                        diverged,
+                        gateway_head: gateway_head.0,
+                        source_head: l1_head.0,
+                    }
+                })
+            }),
+        ];
+
+        for alert in changes.into_iter().flatten() {
+            let source = alert.source.label();
+            if alert.diverged {
+                tracing::warn!(%source, gateway_head=%alert.gateway_head, source_head=%alert.source_head, "Gateway head diverged");
+            } else {
+                tracing::info!(%source, "Gateway head no longer diverged");
+            }
+            alerts.report(source, alert.diverged, &alert).await;
+        }
+
+        let halted = config.halt && (peers_state || l1_state);
+        if halt.send_replace(halted) != halted {
+            if halted {
+                tracing::warn!("Halted following the gateway");
+            } else {
+                tracing::info!("Resumed following the gateway");
+            }
+        }
+    }
+}
+
+/// The latest block accepted on L1.
+fn l1_head(storage: Storage) -> anyhow::Result<Option<Head>> {
+    let mut db = storage.connection()?;
+    let tx = db.transaction()?;
+
+    let l1_head = tx
+        .latest_l1_state()?
+        .map(|state| (state.block_number, state.block_hash));
+
+    Ok(l1_head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pathfinder_common::macro_prelude::*;
+
+    #[test]
+    fn peers() {
+        let head = |number| (BlockNumber::new_or_panic(number), block_hash!("0x1"));
+
+        assert!(!peers_diverged(head(10), head(10), 0));
+        assert!(!peers_diverged(head(10), head(12), 2));
+        assert!(!peers_diverged(head(12), head(10), 2));
+        assert!(peers_diverged(head(10), head(13), 2));
+        assert!(peers_diverged(head(13), head(10), 2));
+
+        // A fork at the same height.
+        let fork = (BlockNumber::new_or_panic(10), block_hash!("0x2"));
+        assert!(peers_diverged(head(10), fork, 2));
+    }
+
+    #[test]
+    fn l1() {
+        let gateway = (BlockNumber::new_or_panic(100), block_hash!("0x100"));
+        let l1 = (BlockNumber::new_or_panic(90), block_hash!("0x90"));
+
+        assert!(!l1_diverged(gateway, l1, Some(block_hash!("0x90")), 2));
+        // The gateway has a different block than the one accepted on L1.
+        assert!(l1_diverged(gateway, l1, Some(block_hash!("0x91")), 2));
+
+        // The gateway is behind L1.
+        let l1 = (BlockNumber::new_or_panic(102), block_hash!("0x102"));
+        assert!(!l1_diverged(gateway, l1, None, 2));
+        let l1 = (BlockNumber::new_or_panic(103), block_hash!("0x103"));
+        assert!(l1_diverged(gateway, l1, None, 2));
+    }
+}
@@ -0,0 +1,67 @@
+//! Heap profiling using jemalloc, which is only supported by builds with the `jemalloc` feature.
+//!
+//! Such builds sample allocations once profiling is [activated](activate), and [dump] writes a
+//! profile of the sampled allocations which are still live, in the format read by `jeprof`.
+//! Sampling is inactive by default, as it slows down allocations.
+
+/// Configures jemalloc to support profiling, while leaving it inactive. Allocations are sampled
+/// every 512 KiB on average once active.
+///
+/// Can be overridden using the `_RJEM_MALLOC_CONF` environment variable.
+#[cfg(feature = "jemalloc")]
+#[allow(non_upper_case_globals)]
+#[export_name = "_rjem_malloc_conf"]
+pub static malloc_conf: &[u8] = b"prof:true,prof_active:false,lg_prof_sample:19\0";
+
+/// Whether this build supports heap profiling.
+pub const fn is_supported() -> bool {
+    cfg!(feature = "jemalloc")
+}
+
+pub use imp::{activate, dump};
+
+#[cfg(feature = "jemalloc")]
+mod imp {
+    use std::os::unix::ffi::OsStrExt;
+
+    use anyhow::Context;
+    use tikv_jemalloc_ctl::raw;
+
+    /// Starts sampling allocations.
+    pub fn activate() -> anyhow::Result<()> {
+        // SAFETY: `prof.active` is a boolean.
+        unsafe { raw::write(b"prof.active\0", true) }
+            .map_err(|error| anyhow::anyhow!("Activating jemalloc profiling: {error}"))
+    }
+
+    /// Returns a heap profile of the sampled allocations which are still live.
+    pub fn dump() -> anyhow::Result<Vec<u8>> {
+        // SAFETY: `prof.active` is a boolean.
+        let active = unsafe { raw::read::<bool>(b"prof.active\0") }
+            .map_err(|error| anyhow::anyhow!("Reading jemalloc profiling state: {error}"))?;
+        anyhow::ensure!(active, "Heap profiling is not active");
+
+        // jemalloc can only dump profiles to files.
+        let directory = tempfile::tempdir().context("Creating temporary directory")?;
+        let path = directory.path().join("heap.prof");
+        let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+            .context("Converting profile path")?;
+
+        // SAFETY: `prof.dump` takes a nul-terminated path, which outlives the call.
+        unsafe { raw::write(b"prof.dump\0", c_path.as_ptr()) }
+            .map_err(|error| anyhow::anyhow!("Dumping jemalloc profile: {error}"))?;
+
+        std::fs::read(&path).context("Reading heap profile")
+    }
+}
+
+#[cfg(not(feature = "jemalloc"))]
+mod imp {
+    pub fn activate() -> anyhow::Result<()> {
+        anyhow::bail!("Heap profiling requires a build with the `jemalloc` feature")
+    }
+
+    pub fn dump() -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("Heap profiling requires a build with the `jemalloc` feature")
+    }
+}
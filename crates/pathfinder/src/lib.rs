@@ -1,7 +1,16 @@
 #![deny(rust_2018_idioms)]
 
+mod alert;
+pub mod block_stream;
+pub mod export;
+pub mod head_divergence;
+pub mod heap_profile;
 pub mod monitoring;
+pub mod persisted_metrics;
+pub mod stall_alert;
 pub mod state;
+pub mod test_vectors;
+pub mod vacuum;
 
 #[cfg(feature = "p2p")]
 pub mod p2p_network;
@@ -7,9 +7,10 @@ use warp::Filter;
 pub async fn spawn_server(
     addr: impl Into<std::net::SocketAddr> + 'static,
     readiness: std::sync::Arc<AtomicBool>,
+    healthy: std::sync::Arc<AtomicBool>,
     prometheus_handle: PrometheusHandle,
 ) -> tokio::task::JoinHandle<()> {
-    let server = warp::serve(routes(readiness, prometheus_handle));
+    let server = warp::serve(routes(readiness, healthy, prometheus_handle));
     let server = server.bind(addr);
 
     tokio::spawn(server)
@@ -17,16 +18,29 @@ pub async fn spawn_server(
 
 fn routes(
     readiness: std::sync::Arc<AtomicBool>,
+    healthy: std::sync::Arc<AtomicBool>,
     prometheus_handle: PrometheusHandle,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    health_route()
+    health_route(healthy)
         .or(ready_route(readiness))
         .or(metrics_route(prometheus_handle))
+        .or(heap_profile_route())
 }
 
-/// Always returns `Ok(200)` at `/health`.
-fn health_route() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::get().and(warp::path!("health")).map(warp::reply)
+/// Returns `Ok` at `/health` if `healthy == true`, or `SERVICE_UNAVAILABLE` otherwise, e.g. once
+/// sync gave up restarting.
+fn health_route(
+    healthy: std::sync::Arc<AtomicBool>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::get()
+        .and(warp::path!("health"))
+        .map(move || -> std::sync::Arc<AtomicBool> { healthy.clone() })
+        .and_then(|healthy: std::sync::Arc<AtomicBool>| async move {
+            match healthy.load(std::sync::atomic::Ordering::Relaxed) {
+                true => Ok::<_, std::convert::Infallible>(warp::http::StatusCode::OK),
+                false => Ok(warp::http::StatusCode::SERVICE_UNAVAILABLE),
+            }
+        })
 }
 
 /// Returns `Ok` if `readiness == true`, or `SERVICE_UNAVAILABLE` otherwise.
@@ -56,6 +70,40 @@ fn metrics_route(
         })
 }
 
+/// Returns a heap profile at `/debug/heap_profile`, see [crate::heap_profile].
+///
+/// Returns `NOT_IMPLEMENTED` if the build doesn't support heap profiling.
+fn heap_profile_route(
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    use warp::http::{Response, StatusCode};
+
+    warp::get()
+        .and(warp::path!("debug" / "heap_profile"))
+        .and_then(|| async move {
+            if !crate::heap_profile::is_supported() {
+                return Ok::<_, std::convert::Infallible>(
+                    Response::builder()
+                        .status(StatusCode::NOT_IMPLEMENTED)
+                        .body(
+                            b"Heap profiling requires a build with the `jemalloc` feature".to_vec(),
+                        ),
+                );
+            }
+
+            let profile = tokio::task::spawn_blocking(crate::heap_profile::dump)
+                .await
+                .unwrap_or_else(|error| Err(error.into()));
+            Ok(match profile {
+                Ok(profile) => Response::builder()
+                    .header("content-type", "application/octet-stream")
+                    .body(profile),
+                Err(error) => Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(format!("{error:#}").into_bytes()),
+            })
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use metrics_exporter_prometheus::PrometheusBuilder;
@@ -67,10 +115,15 @@ mod tests {
         let recorder = PrometheusBuilder::new().build_recorder();
         let handle = recorder.handle();
         let readiness = Arc::new(AtomicBool::new(false));
-        let filter = super::routes(readiness, handle);
+        let healthy = Arc::new(AtomicBool::new(true));
+        let filter = super::routes(readiness, healthy.clone(), handle);
         let response = warp::test::request().path("/health").reply(&filter).await;
 
         assert_eq!(response.status(), http::StatusCode::OK);
+
+        healthy.store(false, std::sync::atomic::Ordering::Relaxed);
+        let response = warp::test::request().path("/health").reply(&filter).await;
+        assert_eq!(response.status(), http::StatusCode::SERVICE_UNAVAILABLE);
     }
 
     #[tokio::test]
@@ -78,7 +131,7 @@ mod tests {
         let recorder = PrometheusBuilder::new().build_recorder();
         let handle = recorder.handle();
         let readiness = Arc::new(AtomicBool::new(false));
-        let filter = super::routes(readiness.clone(), handle);
+        let filter = super::routes(readiness.clone(), Arc::new(AtomicBool::new(true)), handle);
         let response = warp::test::request().path("/ready").reply(&filter).await;
         assert_eq!(response.status(), http::StatusCode::SERVICE_UNAVAILABLE);
 
@@ -102,10 +155,25 @@ mod tests {
         counter.increment(123);
 
         let readiness = Arc::new(AtomicBool::new(false));
-        let filter = super::routes(readiness.clone(), handle);
+        let filter = super::routes(readiness.clone(), Arc::new(AtomicBool::new(true)), handle);
         let response = warp::test::request().path("/metrics").reply(&filter).await;
 
         assert_eq!(response.status(), http::StatusCode::OK);
         assert_eq!(response.body(), "# TYPE x counter\nx 123\n\n");
     }
+
+    #[cfg(not(feature = "jemalloc"))]
+    #[tokio::test]
+    async fn heap_profile_is_not_supported() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let readiness = Arc::new(AtomicBool::new(false));
+        let filter = super::routes(readiness, Arc::new(AtomicBool::new(true)), handle);
+        let response = warp::test::request()
+            .path("/debug/heap_profile")
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::NOT_IMPLEMENTED);
+    }
 }
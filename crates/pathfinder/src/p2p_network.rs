@@ -31,6 +31,9 @@ pub struct P2PContext {
     pub keypair: Keypair,
     pub listen_on: Multiaddr,
     pub bootstrap_addresses: Vec<Multiaddr>,
+    /// Receive the heads propagated by peers even as a proxy, so that they can be compared with
+    /// the gateway's.
+    pub receive_heads: bool,
 }
 
 #[tracing::instrument(name = "p2p", skip_all)]
@@ -42,6 +45,7 @@ pub async fn start(context: P2PContext) -> anyhow::Result<P2PNetworkHandle> {
         keypair,
         listen_on,
         bootstrap_addresses,
+        receive_heads,
     } = context;
 
     let peer_id = keypair.public().to_peer_id();
@@ -49,7 +53,7 @@ pub async fn start(context: P2PContext) -> anyhow::Result<P2PNetworkHandle> {
 
     let peers: Arc<RwLock<Peers>> = Arc::new(RwLock::new(Default::default()));
     let (p2p_client, mut p2p_events, p2p_main_loop) =
-        p2p::new(keypair, peers.clone(), Default::default());
+        p2p::new(keypair, peers.clone(), Default::default(), chain_id);
 
     let mut main_loop_handle = {
         let span = tracing::info_span!("behaviour");
@@ -82,13 +86,13 @@ pub async fn start(context: P2PContext) -> anyhow::Result<P2PNetworkHandle> {
 
     let block_propagation_topic = format!("blocks/{}", chain_id.to_hex_str());
 
-    if !proxy {
+    if !proxy || receive_heads {
         p2p_client.subscribe_topic(&block_propagation_topic).await?;
         tracing::info!(topic=%block_propagation_topic, "Subscribed to");
     }
 
-    for capability in p2p::PROTOCOLS {
-        p2p_client.provide_capability(capability).await?
+    for capability in p2p::protocols(chain_id) {
+        p2p_client.provide_capability(&capability).await?
     }
 
     let (mut tx, rx) = tokio::sync::watch::channel(None);
@@ -118,7 +122,7 @@ pub async fn start(context: P2PContext) -> anyhow::Result<P2PNetworkHandle> {
 
     Ok((
         peers.clone(),
-        peer_agnostic::Client::new(p2p_client, block_propagation_topic, peers),
+        peer_agnostic::Client::new(p2p_client, block_propagation_topic, chain_id, peers),
         rx,
         join_handle,
     ))
@@ -14,17 +14,22 @@ use pathfinder_common::{
     TransactionIndex,
 };
 use pathfinder_common::{
-    transaction::Transaction, BlockHash, BlockId, BlockNumber, CallParam, CasmHash, ClassHash,
-    ContractAddress, ContractAddressSalt, EntryPoint, Fee, StateCommitment, StateUpdate,
-    TransactionHash, TransactionNonce, TransactionSignatureElem, TransactionVersion,
+    transaction::Transaction, BlockCommitmentSignature, BlockHash, BlockId, BlockNumber, CallParam,
+    CasmHash, ClassHash, ContractAddress, ContractAddressSalt, EntryPoint, Fee, PublicKey,
+    StateCommitment, StateDiffCommitment, StateUpdate, TransactionHash, TransactionNonce,
+    TransactionSignatureElem, TransactionVersion,
 };
 use starknet_gateway_client::{GatewayApi, GossipApi};
 use starknet_gateway_types::reply as gw;
-use starknet_gateway_types::request::add_transaction::ContractDefinition;
+use starknet_gateway_types::request::add_transaction::{ContractDefinition, V3Properties};
+use starknet_gateway_types::transaction_hash::compute_transaction_hash;
 use starknet_gateway_types::{error::SequencerError, reply::Block};
 use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+use crate::state::receipt::is_executable;
 
 pub mod types;
 
@@ -36,12 +41,16 @@ pub enum HybridClient {
     GatewayProxy {
         p2p_client: peer_agnostic::Client,
         sequencer: starknet_gateway_client::Client,
+        /// Set while following the gateway is halted, see [crate::head_divergence].
+        halted: watch::Receiver<bool>,
     },
     /// Syncs from p2p network, does not propagate
     NonPropagatingP2P {
         p2p_client: peer_agnostic::Client,
         sequencer: starknet_gateway_client::Client,
         head_rx: HeadRx,
+        /// The key which the sequencer signs the state diff commitments of blocks with.
+        sequencer_public_key: PublicKey,
         /// We need to cache the last two fetched blocks via p2p otherwise sync logic will
         /// produce a false reorg from genesis when we loose connection to other p2p nodes.
         /// This was we can stay at the same height while we are disconnected.
@@ -57,6 +66,8 @@ pub struct Cache {
 #[derive(Clone, Debug)]
 pub struct CacheEntry {
     pub block: Block,
+    pub state_diff_commitment: StateDiffCommitment,
+    pub signature: BlockCommitmentSignature,
     pub definitions: HashMap<ClassHash, Vec<u8>>,
 }
 
@@ -84,6 +95,25 @@ impl Cache {
             .map(|entry| entry.block.state_commitment)
     }
 
+    fn get_state_diff_commitment(&self, block_hash: BlockHash) -> Option<StateDiffCommitment> {
+        let locked = self.inner.lock().unwrap();
+        locked
+            .peek(&block_hash)
+            .map(|entry| entry.state_diff_commitment)
+    }
+
+    fn get_signature(&self, block_hash: BlockHash) -> Option<gw::BlockSignature> {
+        let locked = self.inner.lock().unwrap();
+        locked.peek(&block_hash).map(|entry| gw::BlockSignature {
+            block_number: entry.block.block_number,
+            signature: [entry.signature.r, entry.signature.s],
+            signature_input: gw::BlockSignatureInput {
+                block_hash,
+                state_diff_commitment: entry.state_diff_commitment,
+            },
+        })
+    }
+
     fn get_definition(&self, class_hash: ClassHash) -> Option<Vec<u8>> {
         let locked = self.inner.lock().unwrap();
         locked
@@ -110,12 +140,19 @@ impl Cache {
         }
     }
 
-    fn insert_block(&self, block: Block) {
+    fn insert_block(
+        &self,
+        block: Block,
+        state_diff_commitment: StateDiffCommitment,
+        signature: BlockCommitmentSignature,
+    ) {
         let mut locked_inner = self.inner.lock().unwrap();
         locked_inner.put(
             block.block_hash,
             CacheEntry {
                 block,
+                state_diff_commitment,
+                signature,
                 definitions: Default::default(),
             },
         );
@@ -136,17 +173,21 @@ impl HybridClient {
         p2p_client: peer_agnostic::Client,
         sequencer: starknet_gateway_client::Client,
         head_rx: HeadRx,
+        sequencer_public_key: PublicKey,
+        halted: watch::Receiver<bool>,
     ) -> Self {
         if i_am_proxy {
             Self::GatewayProxy {
                 p2p_client,
                 sequencer,
+                halted,
             }
         } else {
             Self::NonPropagatingP2P {
                 p2p_client,
                 sequencer,
                 head_rx,
+                sequencer_public_key,
                 cache: Default::default(),
             }
         }
@@ -173,6 +214,10 @@ mod error {
         })
     }
 
+    pub fn halted() -> SequencerError {
+        block_not_found("following the gateway is halted as its head diverged")
+    }
+
     pub fn class_not_found(message: impl ToString) -> SequencerError {
         SequencerError::StarknetError(StarknetError {
             code: StarknetErrorCode::Known(KnownStarknetErrorCode::UndeclaredClass),
@@ -181,15 +226,117 @@ mod error {
     }
 }
 
+/// Verifies the transactions of a block fetched from peers against the commitment in its header.
+///
+/// A zero commitment is only valid for a block without transactions, as the commitment of any
+/// other block is the root of a non-empty tree.
+fn verify_transaction_commitment(
+    header: &BlockHeader,
+    transactions: &[gw::transaction::Transaction],
+) -> anyhow::Result<()> {
+    use crate::state::block_hash::{
+        calculate_transaction_commitment, TransactionCommitmentFinalHashType,
+    };
+
+    let final_hash_type =
+        TransactionCommitmentFinalHashType::for_version(&header.starknet_version)?;
+    let commitment = calculate_transaction_commitment(transactions, final_hash_type)?;
+    anyhow::ensure!(
+        commitment == header.transaction_commitment,
+        "transaction commitment mismatch: computed {}, header {}",
+        commitment,
+        header.transaction_commitment
+    );
+
+    Ok(())
+}
+
+/// Verifies the events of a block fetched from peers against the commitment in its header.
+///
+/// As with transactions, a zero commitment is only valid for a block without events.
+fn verify_event_commitment(
+    header: &BlockHeader,
+    receipts: &[gw::transaction::Receipt],
+) -> anyhow::Result<()> {
+    use crate::state::block_hash::calculate_event_commitment;
+
+    let event_count = receipts.iter().map(|r| r.events.len()).sum::<usize>();
+    anyhow::ensure!(
+        event_count == header.event_count,
+        "block has {} events, expected {}",
+        event_count,
+        header.event_count
+    );
+
+    let commitment = calculate_event_commitment(receipts)?;
+    anyhow::ensure!(
+        commitment == header.event_commitment,
+        "event commitment mismatch: computed {}, header {}",
+        commitment,
+        header.event_commitment
+    );
+
+    Ok(())
+}
+
+/// Verifies that the sequencer signed the hash and the state diff commitment of a block header
+/// fetched from peers, which makes the commitment as trustworthy as the block hash.
+fn verify_signature(header: &BlockHeader, public_key: PublicKey) -> anyhow::Result<()> {
+    use pathfinder_crypto::hash::poseidon_hash_many;
+    use pathfinder_crypto::signature::ecdsa_verify_partial;
+
+    anyhow::ensure!(
+        header.state_diff_commitment != StateDiffCommitment::ZERO,
+        "header has no state diff commitment"
+    );
+    let signature = header
+        .signature
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("header is not signed"))?;
+
+    let message =
+        poseidon_hash_many(&[header.hash.0.into(), header.state_diff_commitment.0.into()]);
+    ecdsa_verify_partial(public_key.0, message.into(), signature.r.0, signature.s.0)
+        .map_err(|error| anyhow::anyhow!("{error}"))
+}
+
+/// Verifies a state update fetched from peers against the state diff commitment in the header of
+/// its block, before it is applied to the tries. The commitment was [signed](verify_signature) by
+/// the sequencer.
+fn verify_state_diff_commitment(
+    state_update: &StateUpdate,
+    expected: StateDiffCommitment,
+) -> anyhow::Result<()> {
+    let commitment = state_update.compute_state_diff_commitment();
+    anyhow::ensure!(
+        commitment == expected,
+        "state diff commitment mismatch: computed {}, header {}",
+        commitment,
+        expected
+    );
+
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl GatewayApi for HybridClient {
     async fn block(&self, block: BlockId) -> Result<gw::MaybePendingBlock, SequencerError> {
         use error::block_not_found;
 
         match self {
-            HybridClient::GatewayProxy { sequencer, .. } => sequencer.block(block).await,
+            HybridClient::GatewayProxy {
+                sequencer, halted, ..
+            } => {
+                if *halted.borrow() {
+                    return Err(error::halted());
+                }
+                sequencer.block(block).await
+            }
             HybridClient::NonPropagatingP2P {
-                p2p_client, cache, ..
+                p2p_client,
+                cache,
+                sequencer_public_key,
+                ..
             } => {
                 match block {
                     BlockId::Number(n) => {
@@ -218,6 +365,11 @@ impl GatewayApi for HybridClient {
                             return Err(block_not_found("block number mismatch"));
                         }
 
+                        verify_signature(&header, *sequencer_public_key).map_err(|error| {
+                            block_not_found(format!("invalid signature for block {n}: {error}"))
+                        })?;
+                        let signature = header.signature.clone().unwrap_or_default();
+
                         cache.clear_if_reorg(&header);
 
                         let block_hash = header.hash;
@@ -235,35 +387,13 @@ impl GatewayApi for HybridClient {
                             block_not_found(format!("no peers with transactions for block {n}",))
                         })?;
 
-                        let receipts =
-                            p2p_client.receipts(header.hash, 1).await.map_err(|error| {
-                                block_not_found(format!(
-                                    "getting receipts failed: block {n}: {error}",
-                                ))
-                            })?;
-
-                        use crate::p2p_network::client::types::Receipt;
-
-                        let mut receipts = receipts
-                            .into_iter()
-                            .map(|(k, v)| {
-                                v.into_iter()
-                                    .map(Receipt::try_from)
-                                    .collect::<Result<Vec<_>, _>>()
-                                    .map(|r| (k, r))
-                            })
-                            .collect::<Result<HashMap<_, _>, _>>()
-                            .map_err(|error| {
-                                block_not_found(format!(
-                                    "failed to parse receipts for block {n}: {error}",
-                                ))
-                            })?;
-
-                        let receipts = receipts.remove(&block_hash).ok_or_else(|| {
-                            block_not_found(format!("no peers with receipts for block {n}",))
-                        })?;
-
-                        debug_assert_eq!(transactions.len(), receipts.len());
+                        if transactions.len() != header.transaction_count {
+                            return Err(block_not_found(format!(
+                                "block {n} has {} transactions, expected {}",
+                                transactions.len(),
+                                header.transaction_count
+                            )));
+                        }
 
                         let mut events =
                             p2p_client.event(header.hash, 1).await.map_err(|error| {
@@ -276,43 +406,136 @@ impl GatewayApi for HybridClient {
                             block_not_found(format!("no peers with events for block {n}",))
                         })?;
 
-                        // TODO: assume order is the same because proto::transaction does not carry transaction hash
-                        let (transactions, receipts): (Vec<_>, Vec<_>) = transactions
+                        let transactions = transactions
                             .into_iter()
-                            .zip(receipts)
-                            .enumerate()
-                            .map(|(i, (t, r))| {
-                                let (execution_status, revert_error) = if r.revert_error.is_empty()
-                                {
-                                    (gw::transaction::ExecutionStatus::Succeeded, None)
-                                } else {
-                                    (
-                                        gw::transaction::ExecutionStatus::Reverted,
-                                        Some(r.revert_error),
-                                    )
-                                };
-
-                                (
-                                    gw::transaction::Transaction::from(Transaction {
-                                        hash: r.transaction_hash,
-                                        variant: t,
-                                    }),
-                                    gw::transaction::Receipt {
-                                        actual_fee: Some(r.actual_fee),
-                                        events: events
-                                            .remove(&r.transaction_hash)
-                                            .unwrap_or_default(),
-                                        execution_resources: Some(r.execution_resources),
-                                        l1_to_l2_consumed_message: r.l1_to_l2_consumed_message,
-                                        l2_to_l1_messages: r.l2_to_l1_messages,
-                                        transaction_hash: r.transaction_hash,
-                                        transaction_index: TransactionIndex::new_or_panic(i as u64),
-                                        execution_status,
-                                        revert_error,
-                                    },
-                                )
+                            .map(|variant| {
+                                gw::transaction::Transaction::from(Transaction {
+                                    hash: TransactionHash::ZERO,
+                                    variant,
+                                })
                             })
-                            .unzip();
+                            .collect::<Vec<_>>();
+
+                        // The receipts of executable blocks are computed by executing them, and
+                        // only their events, which the block hash commits to, are taken from
+                        // peers. Peers' receipts are only fetched for the other blocks.
+                        let (transactions, receipts): (Vec<_>, Vec<_>) =
+                            if is_executable(&header.starknet_version, &transactions) {
+                                let chain_id = p2p_client.chain_id();
+                                transactions
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(i, transaction)| {
+                                        let hash = compute_transaction_hash(&transaction, chain_id);
+                                        let mut transaction = Transaction::from(transaction);
+                                        transaction.hash = hash;
+
+                                        (
+                                            gw::transaction::Transaction::from(transaction),
+                                            gw::transaction::Receipt {
+                                                actual_fee: None,
+                                                events: events.remove(&hash).unwrap_or_default(),
+                                                execution_resources: None,
+                                                l1_to_l2_consumed_message: None,
+                                                l2_to_l1_messages: Vec::new(),
+                                                transaction_hash: hash,
+                                                transaction_index: TransactionIndex::new_or_panic(
+                                                    i as u64,
+                                                ),
+                                                execution_status:
+                                                    gw::transaction::ExecutionStatus::Succeeded,
+                                                revert_error: None,
+                                            },
+                                        )
+                                    })
+                                    .unzip()
+                            } else {
+                                let receipts =
+                                    p2p_client.receipts(header.hash, 1).await.map_err(|error| {
+                                        block_not_found(format!(
+                                            "getting receipts failed: block {n}: {error}",
+                                        ))
+                                    })?;
+
+                                use crate::p2p_network::client::types::Receipt;
+
+                                let mut receipts = receipts
+                                    .into_iter()
+                                    .map(|(k, v)| {
+                                        v.into_iter()
+                                            .map(Receipt::try_from)
+                                            .collect::<Result<Vec<_>, _>>()
+                                            .map(|r| (k, r))
+                                    })
+                                    .collect::<Result<HashMap<_, _>, _>>()
+                                    .map_err(|error| {
+                                        block_not_found(format!(
+                                            "failed to parse receipts for block {n}: {error}",
+                                        ))
+                                    })?;
+
+                                let receipts = receipts.remove(&block_hash).ok_or_else(|| {
+                                    block_not_found(
+                                        format!("no peers with receipts for block {n}",),
+                                    )
+                                })?;
+
+                                if receipts.len() != header.transaction_count {
+                                    return Err(block_not_found(format!(
+                                        "block {n} has {} receipts, expected {}",
+                                        receipts.len(),
+                                        header.transaction_count
+                                    )));
+                                }
+
+                                // TODO: assume order is the same because proto::transaction does not carry transaction hash
+                                transactions
+                                    .into_iter()
+                                    .zip(receipts)
+                                    .enumerate()
+                                    .map(|(i, (t, r))| {
+                                        let (execution_status, revert_error) =
+                                            if r.revert_error.is_empty() {
+                                                (gw::transaction::ExecutionStatus::Succeeded, None)
+                                            } else {
+                                                (
+                                                    gw::transaction::ExecutionStatus::Reverted,
+                                                    Some(r.revert_error),
+                                                )
+                                            };
+
+                                        let mut t = Transaction::from(t);
+                                        t.hash = r.transaction_hash;
+
+                                        (
+                                            gw::transaction::Transaction::from(t),
+                                            gw::transaction::Receipt {
+                                                actual_fee: Some(r.actual_fee),
+                                                events: events
+                                                    .remove(&r.transaction_hash)
+                                                    .unwrap_or_default(),
+                                                execution_resources: Some(r.execution_resources),
+                                                l1_to_l2_consumed_message: r
+                                                    .l1_to_l2_consumed_message,
+                                                l2_to_l1_messages: r.l2_to_l1_messages,
+                                                transaction_hash: r.transaction_hash,
+                                                transaction_index: TransactionIndex::new_or_panic(
+                                                    i as u64,
+                                                ),
+                                                execution_status,
+                                                revert_error,
+                                            },
+                                        )
+                                    })
+                                    .unzip()
+                            };
+
+                        verify_transaction_commitment(&header, &transactions).map_err(|error| {
+                            block_not_found(format!("invalid transactions for block {n}: {error}"))
+                        })?;
+                        verify_event_commitment(&header, &receipts).map_err(|error| {
+                            block_not_found(format!("invalid events for block {n}: {error}"))
+                        })?;
 
                         let block = gw::Block {
                             block_hash: header.hash,
@@ -329,7 +552,7 @@ impl GatewayApi for HybridClient {
                             starknet_version: header.starknet_version,
                         };
 
-                        cache.insert_block(block.clone());
+                        cache.insert_block(block.clone(), header.state_diff_commitment, signature);
 
                         Ok(block.into())
                     }
@@ -400,7 +623,14 @@ impl GatewayApi for HybridClient {
         use error::block_not_found;
 
         match self {
-            HybridClient::GatewayProxy { sequencer, .. } => sequencer.state_update(block).await,
+            HybridClient::GatewayProxy {
+                sequencer, halted, ..
+            } => {
+                if *halted.borrow() {
+                    return Err(error::halted());
+                }
+                sequencer.state_update(block).await
+            }
             HybridClient::NonPropagatingP2P {
                 p2p_client, cache, ..
             } => match block {
@@ -450,12 +680,10 @@ impl GatewayApi for HybridClient {
                         }
                     }
 
-                    cache.insert_definitions(hash, definitions);
-
                     let state_commitment =
                         cache.get_state_commitment(block_hash).unwrap_or_default();
 
-                    Ok(StateUpdate {
+                    let state_update = StateUpdate {
                         block_hash,
                         // Luckily this field is only used when polling pending which is disabled with p2p
                         parent_state_commitment: StateCommitment::default(),
@@ -479,7 +707,18 @@ impl GatewayApi for HybridClient {
                         system_contract_updates: state_update.system_contract_updates,
                         declared_cairo_classes,
                         declared_sierra_classes,
-                    })
+                    };
+
+                    let expected = cache
+                        .get_state_diff_commitment(block_hash)
+                        .ok_or_else(|| block_not_found(format!("block {hash} is not cached")))?;
+                    verify_state_diff_commitment(&state_update, expected).map_err(|error| {
+                        block_not_found(format!("invalid state update for block {hash}: {error}"))
+                    })?;
+
+                    cache.insert_definitions(hash, definitions);
+
+                    Ok(state_update)
                 }
                 _ => unreachable!("not used in sync"),
             },
@@ -500,6 +739,7 @@ impl GatewayApi for HybridClient {
         contract_address: ContractAddress,
         entry_point_selector: Option<EntryPoint>,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<gw::add_transaction::InvokeResponse, SequencerError> {
         self.as_sequencer()
             .add_invoke_transaction(
@@ -510,6 +750,7 @@ impl GatewayApi for HybridClient {
                 contract_address,
                 entry_point_selector,
                 calldata,
+                v3_properties,
             )
             .await
     }
@@ -524,6 +765,7 @@ impl GatewayApi for HybridClient {
         contract_definition: ContractDefinition,
         sender_address: ContractAddress,
         compiled_class_hash: Option<CasmHash>,
+        v3_properties: Option<V3Properties>,
         token: Option<String>,
     ) -> Result<gw::add_transaction::DeclareResponse, SequencerError> {
         self.as_sequencer()
@@ -535,6 +777,7 @@ impl GatewayApi for HybridClient {
                 contract_definition,
                 sender_address,
                 compiled_class_hash,
+                v3_properties,
                 token,
             )
             .await
@@ -550,6 +793,7 @@ impl GatewayApi for HybridClient {
         contract_address_salt: ContractAddressSalt,
         class_hash: ClassHash,
         calldata: Vec<CallParam>,
+        v3_properties: Option<V3Properties>,
     ) -> Result<gw::add_transaction::DeployAccountResponse, SequencerError> {
         self.as_sequencer()
             .add_deploy_account(
@@ -560,16 +804,37 @@ impl GatewayApi for HybridClient {
                 contract_address_salt,
                 class_hash,
                 calldata,
+                v3_properties,
             )
             .await
     }
 
+    async fn signature(&self, block: BlockId) -> Result<gw::BlockSignature, SequencerError> {
+        match self {
+            HybridClient::GatewayProxy { sequencer, .. } => sequencer.signature(block).await,
+            HybridClient::NonPropagatingP2P { cache, .. } => match block {
+                // The signature was verified along with the header of the block.
+                BlockId::Hash(hash) => cache
+                    .get_signature(hash)
+                    .ok_or_else(|| error::block_not_found(format!("block {hash} is not cached"))),
+                _ => unreachable!("not used in sync"),
+            },
+        }
+    }
+
     /// This is a **temporary** measure to keep the sync logic unchanged
     ///
     /// TODO remove me when sync is changed to use the high level (ie. peer unaware) p2p API
     async fn head(&self) -> Result<(BlockNumber, BlockHash), SequencerError> {
         match self {
-            HybridClient::GatewayProxy { sequencer, .. } => sequencer.head().await,
+            HybridClient::GatewayProxy {
+                sequencer, halted, ..
+            } => {
+                if *halted.borrow() {
+                    return Err(error::halted());
+                }
+                sequencer.head().await
+            }
             HybridClient::NonPropagatingP2P { head_rx, .. } => {
                 let head = *head_rx.borrow();
                 tracing::trace!(?head, "HybridClient::head");
@@ -604,3 +869,151 @@ impl GossipApi for HybridClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::block_hash::{
+        calculate_event_commitment, calculate_transaction_commitment,
+        TransactionCommitmentFinalHashType,
+    };
+    use pathfinder_common::TransactionCommitment;
+
+    fn block_and_header() -> (gw::Block, BlockHeader) {
+        let json = starknet_gateway_test_fixtures::integration::block::NUMBER_285915;
+        let block: gw::Block = serde_json::from_str(json).unwrap();
+        let transaction_commitment = calculate_transaction_commitment(
+            &block.transactions,
+            TransactionCommitmentFinalHashType::Normal,
+        )
+        .unwrap();
+        let header = BlockHeader {
+            hash: block.block_hash,
+            number: block.block_number,
+            starknet_version: block.starknet_version.clone(),
+            transaction_commitment,
+            transaction_count: block.transactions.len(),
+            ..Default::default()
+        };
+
+        (block, header)
+    }
+
+    #[test]
+    fn transaction_commitment_is_verified() {
+        let (block, header) = block_and_header();
+
+        verify_transaction_commitment(&header, &block.transactions).unwrap();
+
+        let mut reordered = block.transactions;
+        reordered.swap(0, 1);
+        verify_transaction_commitment(&header, &reordered).unwrap_err();
+    }
+
+    #[test]
+    fn zero_transaction_commitment_is_rejected() {
+        let (block, mut header) = block_and_header();
+        header.transaction_commitment = TransactionCommitment::ZERO;
+
+        verify_transaction_commitment(&header, &block.transactions).unwrap_err();
+        verify_transaction_commitment(&header, &[]).unwrap();
+    }
+
+    #[test]
+    fn event_commitment_is_verified() {
+        let json = starknet_gateway_test_fixtures::v0_9_0::block::NUMBER_156000;
+        let block: gw::Block = serde_json::from_str(json).unwrap();
+        let header = BlockHeader {
+            event_commitment: calculate_event_commitment(&block.transaction_receipts).unwrap(),
+            event_count: block
+                .transaction_receipts
+                .iter()
+                .map(|r| r.events.len())
+                .sum(),
+            ..Default::default()
+        };
+
+        verify_event_commitment(&header, &block.transaction_receipts).unwrap();
+
+        let mut receipts = block.transaction_receipts;
+        let event = receipts
+            .iter_mut()
+            .find_map(|r| r.events.first_mut())
+            .unwrap();
+        event.data.push(pathfinder_common::EventData::ZERO);
+        verify_event_commitment(&header, &receipts).unwrap_err();
+
+        receipts.iter_mut().for_each(|r| r.events.clear());
+        verify_event_commitment(&header, &receipts).unwrap_err();
+    }
+
+    #[test]
+    fn zero_event_commitment_is_rejected() {
+        let json = starknet_gateway_test_fixtures::v0_9_0::block::NUMBER_156000;
+        let block: gw::Block = serde_json::from_str(json).unwrap();
+        let header = BlockHeader {
+            event_count: block
+                .transaction_receipts
+                .iter()
+                .map(|r| r.events.len())
+                .sum(),
+            ..Default::default()
+        };
+
+        verify_event_commitment(&header, &block.transaction_receipts).unwrap_err();
+
+        let mut receipts = block.transaction_receipts;
+        receipts.iter_mut().for_each(|r| r.events.clear());
+        let header = BlockHeader::default();
+        verify_event_commitment(&header, &receipts).unwrap();
+    }
+
+    #[test]
+    fn signature_is_verified() {
+        use pathfinder_common::macro_prelude::*;
+
+        // Mainnet block 350000, signed with the mainnet sequencer's key.
+        let json = starknet_gateway_test_fixtures::v0_12_2::signature::BLOCK_350000;
+        let signature: gw::BlockSignature = serde_json::from_str(json).unwrap();
+        let public_key =
+            public_key!("0x48253ff2c3bed7af18bde0b611b083b39445959102d4947c51c4db6aa4f4e58");
+        let header = BlockHeader {
+            hash: signature.signature_input.block_hash,
+            state_diff_commitment: signature.signature_input.state_diff_commitment,
+            signature: Some(signature.into()),
+            ..Default::default()
+        };
+
+        verify_signature(&header, public_key).unwrap();
+
+        let json = starknet_gateway_test_fixtures::v0_12_2::state_update::BLOCK_350000;
+        let state_update: gw::StateUpdate = serde_json::from_str(json).unwrap();
+        verify_state_diff_commitment(&state_update.into(), header.state_diff_commitment).unwrap();
+
+        let mut unsigned = header.clone();
+        unsigned.signature = None;
+        verify_signature(&unsigned, public_key).unwrap_err();
+
+        let mut forged = header.clone();
+        forged.state_diff_commitment = state_diff_commitment!("0x1");
+        verify_signature(&forged, public_key).unwrap_err();
+
+        let mut zeroed = header;
+        zeroed.state_diff_commitment = StateDiffCommitment::ZERO;
+        verify_signature(&zeroed, public_key).unwrap_err();
+    }
+
+    #[test]
+    fn state_diff_commitment_is_verified() {
+        let json = starknet_gateway_test_fixtures::v0_12_2::state_update::BLOCK_350000;
+        let state_update: gw::StateUpdate = serde_json::from_str(json).unwrap();
+        let mut state_update = StateUpdate::from(state_update);
+        let commitment = state_update.compute_state_diff_commitment();
+
+        verify_state_diff_commitment(&state_update, commitment).unwrap();
+        verify_state_diff_commitment(&state_update, StateDiffCommitment::ZERO).unwrap_err();
+
+        state_update.declared_cairo_classes.insert(ClassHash::ZERO);
+        verify_state_diff_commitment(&state_update, commitment).unwrap_err();
+    }
+}
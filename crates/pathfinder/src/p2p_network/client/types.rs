@@ -5,15 +5,18 @@ use p2p_proto::receipt::{
 use pathfinder_common::{
     event::Event,
     transaction::{
-        DeclareTransactionV0V1, DeclareTransactionV2, DeployAccountTransaction, DeployTransaction,
-        EntryPointType, InvokeTransactionV0, InvokeTransactionV1, L1HandlerTransaction,
-        TransactionVariant,
+        DataAvailabilityMode, DeclareTransactionV0V1, DeclareTransactionV2, DeclareTransactionV3,
+        DeployAccountTransaction, DeployAccountTransactionV3, DeployTransaction, EntryPointType,
+        InvokeTransactionV0, InvokeTransactionV1, InvokeTransactionV3, L1HandlerTransaction,
+        ResourceBound, ResourceBounds, TransactionVariant,
     },
-    CallParam, CasmHash, ClassHash, ConstructorParam, ContractAddress, ContractAddressSalt,
-    EntryPoint, EthereumAddress, EventData, EventKey, Fee, L1ToL2MessageNonce,
-    L1ToL2MessagePayloadElem, L2ToL1MessagePayloadElem, TransactionHash, TransactionNonce,
-    TransactionSignatureElem, TransactionVersion,
+    AccountDeploymentDataElem, CallParam, CasmHash, ClassHash, ConstructorParam, ContractAddress,
+    ContractAddressSalt, EntryPoint, EthereumAddress, EventData, EventKey, Fee, L1ToL2MessageNonce,
+    L1ToL2MessagePayloadElem, L2ToL1MessagePayloadElem, PaymasterDataElem, ResourceAmount,
+    ResourcePricePerUnit, Tip, TransactionHash, TransactionNonce, TransactionSignatureElem,
+    TransactionVersion,
 };
+use pathfinder_crypto::Felt;
 use starknet_gateway_types::reply::transaction as gw;
 
 /// We don't want to introduce circular dependencies between crates
@@ -97,7 +100,35 @@ impl TryFromDto<p2p_proto::transaction::Transaction> for TransactionVariant {
                     .collect(),
                 compiled_class_hash: CasmHash(x.compiled_class_hash),
             }),
-            DeclareV3(_) => unimplemented!(),
+            DeclareV3(x) => TransactionVariant::DeclareV3(DeclareTransactionV3 {
+                class_hash: ClassHash(x.class_hash.0),
+                nonce: TransactionNonce(x.nonce),
+                nonce_data_availability_mode: data_availability_mode(&x.nonce_domain)?,
+                fee_data_availability_mode: data_availability_mode(&x.fee_domain)?,
+                resource_bounds: ResourceBounds {
+                    l1_gas: ResourceBound::try_from_dto(x.l1_gas)?,
+                    l2_gas: ResourceBound::try_from_dto(x.l2_gas)?,
+                },
+                tip: Tip(felt_to_u128(x.tip)?.try_into()?),
+                paymaster_data: x
+                    .paymaster_data
+                    .into_iter()
+                    .map(PaymasterDataElem)
+                    .collect(),
+                signature: x
+                    .signature
+                    .parts
+                    .into_iter()
+                    .map(TransactionSignatureElem)
+                    .collect(),
+                account_deployment_data: x
+                    .account_deployment_data
+                    .into_iter()
+                    .map(AccountDeploymentDataElem)
+                    .collect(),
+                sender_address: ContractAddress(x.sender.0),
+                compiled_class_hash: CasmHash(x.compiled_class_hash),
+            }),
             Deploy(x) => TransactionVariant::Deploy(DeployTransaction {
                 contract_address: ContractAddress(x.address.0),
                 contract_address_salt: ContractAddressSalt(x.address_salt),
@@ -124,7 +155,31 @@ impl TryFromDto<p2p_proto::transaction::Transaction> for TransactionVariant {
                 constructor_calldata: x.calldata.into_iter().map(CallParam).collect(),
                 class_hash: ClassHash(x.class_hash.0),
             }),
-            DeployAccountV3(_) => unimplemented!(),
+            DeployAccountV3(x) => TransactionVariant::DeployAccountV3(DeployAccountTransactionV3 {
+                contract_address: ContractAddress(x.address.0),
+                signature: x
+                    .signature
+                    .parts
+                    .into_iter()
+                    .map(TransactionSignatureElem)
+                    .collect(),
+                nonce: TransactionNonce(x.nonce),
+                nonce_data_availability_mode: data_availability_mode(&x.nonce_domain)?,
+                fee_data_availability_mode: data_availability_mode(&x.fee_domain)?,
+                resource_bounds: ResourceBounds {
+                    l1_gas: ResourceBound::try_from_dto(x.l1_gas)?,
+                    l2_gas: ResourceBound::try_from_dto(x.l2_gas)?,
+                },
+                tip: Tip(felt_to_u128(x.tip)?.try_into()?),
+                paymaster_data: x
+                    .paymaster_data
+                    .into_iter()
+                    .map(PaymasterDataElem)
+                    .collect(),
+                contract_address_salt: ContractAddressSalt(x.address_salt),
+                constructor_calldata: x.calldata.into_iter().map(CallParam).collect(),
+                class_hash: ClassHash(x.class_hash.0),
+            }),
             InvokeV0(x) => TransactionVariant::InvokeV0(InvokeTransactionV0 {
                 calldata: x.calldata.into_iter().map(CallParam).collect(),
                 sender_address: ContractAddress(x.address.0),
@@ -156,7 +211,34 @@ impl TryFromDto<p2p_proto::transaction::Transaction> for TransactionVariant {
                     .collect(),
                 nonce: TransactionNonce(x.nonce),
             }),
-            InvokeV3(_) => unimplemented!(),
+            InvokeV3(x) => TransactionVariant::InvokeV3(InvokeTransactionV3 {
+                signature: x
+                    .signature
+                    .parts
+                    .into_iter()
+                    .map(TransactionSignatureElem)
+                    .collect(),
+                nonce: TransactionNonce(x.nonce),
+                nonce_data_availability_mode: data_availability_mode(&x.nonce_domain)?,
+                fee_data_availability_mode: data_availability_mode(&x.fee_domain)?,
+                resource_bounds: ResourceBounds {
+                    l1_gas: ResourceBound::try_from_dto(x.l1_gas)?,
+                    l2_gas: ResourceBound::try_from_dto(x.l2_gas)?,
+                },
+                tip: Tip(felt_to_u128(x.tip)?.try_into()?),
+                paymaster_data: x
+                    .paymaster_data
+                    .into_iter()
+                    .map(PaymasterDataElem)
+                    .collect(),
+                account_deployment_data: x
+                    .account_deployment_data
+                    .into_iter()
+                    .map(AccountDeploymentDataElem)
+                    .collect(),
+                calldata: x.calldata.into_iter().map(CallParam).collect(),
+                sender_address: ContractAddress(x.sender.0),
+            }),
             L1HandlerV1(x) => TransactionVariant::L1Handler(L1HandlerTransaction {
                 contract_address: ContractAddress(x.address.0),
                 entry_point_selector: EntryPoint(x.entry_point_selector),
@@ -175,6 +257,34 @@ impl TryFromDto<p2p_proto::transaction::Transaction> for TransactionVariant {
     }
 }
 
+impl TryFromDto<p2p_proto::transaction::ResourceLimits> for ResourceBound {
+    fn try_from_dto(dto: p2p_proto::transaction::ResourceLimits) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            max_amount: ResourceAmount(felt_to_u128(dto.max_amount)?.try_into()?),
+            max_price_per_unit: ResourcePricePerUnit(felt_to_u128(dto.max_price_per_unit)?),
+        })
+    }
+}
+
+fn felt_to_u128(felt: Felt) -> anyhow::Result<u128> {
+    let bytes = felt.to_be_bytes();
+    anyhow::ensure!(bytes[..16].iter().all(|b| *b == 0), "Value exceeds u128");
+    Ok(u128::from_be_bytes(
+        bytes[16..].try_into().expect("16 bytes"),
+    ))
+}
+
+fn data_availability_mode(domain: &str) -> anyhow::Result<DataAvailabilityMode> {
+    match domain {
+        "L1" => Ok(DataAvailabilityMode::L1),
+        "L2" => Ok(DataAvailabilityMode::L2),
+        _ => anyhow::bail!("Invalid data availability mode {domain}"),
+    }
+}
+
 impl TryFrom<p2p_proto::receipt::Receipt> for Receipt {
     type Error = anyhow::Error;
 
@@ -1,9 +1,11 @@
 use anyhow::Context;
 use p2p_proto::block::{
     BlockBodiesRequest, BlockBodiesResponse, BlockBodyMessage, BlockHeadersRequest,
-    BlockHeadersResponse, BlockHeadersResponsePart,
+    BlockHeadersResponse, BlockHeadersResponsePart, Signatures,
+};
+use p2p_proto::common::{
+    BlockId, BlockNumberOrHash, ConsensusSignature, Direction, Fin, Hash, Iteration, Merkle, Step,
 };
-use p2p_proto::common::{BlockId, BlockNumberOrHash, Direction, Fin, Hash, Iteration, Step};
 use p2p_proto::consts::{
     CLASSES_MESSAGE_OVERHEAD, MAX_HEADERS_PER_MESSAGE, MAX_PARTS_PER_CLASS, MESSAGE_SIZE_LIMIT,
     PER_CLASS_OVERHEAD,
@@ -13,11 +15,18 @@ use p2p_proto::receipt::{Receipts, ReceiptsRequest, ReceiptsResponse, ReceiptsRe
 use p2p_proto::transaction::{
     Transactions, TransactionsRequest, TransactionsResponse, TransactionsResponseKind,
 };
-use pathfinder_common::{BlockHash, BlockNumber, CasmHash, ClassHash, SierraHash};
+use pathfinder_common::{
+    BlockHash, BlockNumber, CasmHash, ClassHash, EventCommitment, SierraHash, TransactionCommitment,
+};
 use pathfinder_storage::Storage;
 use pathfinder_storage::Transaction;
 use tokio::sync::mpsc;
 
+use crate::state::block_hash::{
+    calculate_event_commitment, calculate_transaction_commitment,
+    TransactionCommitmentFinalHashType,
+};
+
 pub mod conv;
 #[cfg(test)]
 mod tests;
@@ -134,9 +143,57 @@ fn get_header(
     parts: &mut Vec<BlockHeadersResponsePart>,
 ) -> anyhow::Result<bool> {
     if let Some(header) = tx.block_header(block_number.into())? {
-        parts.push(BlockHeadersResponsePart::Header(Box::new(
-            header.to_proto(),
-        )));
+        let transaction_commitment_missing =
+            header.transaction_commitment == TransactionCommitment::ZERO;
+        let event_commitment_missing = header.event_commitment == EventCommitment::ZERO;
+        let starknet_version = header.starknet_version.clone();
+        let mut header = header.to_proto();
+        // The state diff commitment is not stored, so it is computed from the state update
+        // which peers verify the state diff they receive against.
+        if let Some(state_update) = tx.state_update(block_number.into())? {
+            header.state_diffs = Merkle {
+                n_leaves: (state_update.contract_updates.len()
+                    + state_update.system_contract_updates.len())
+                .try_into()
+                .context("Too many contract updates")?,
+                root: Hash(state_update.compute_state_diff_commitment().0),
+            };
+        }
+        // Blocks whose hash could not be verified are stored without transaction and event
+        // commitments, which peers reject unless the block is empty.
+        let transaction_data = if transaction_commitment_missing || event_commitment_missing {
+            tx.transaction_data_for_block(block_number.into())?
+        } else {
+            None
+        };
+        if let Some(transaction_data) = transaction_data {
+            let (transactions, receipts): (Vec<_>, Vec<_>) = transaction_data.into_iter().unzip();
+            if transaction_commitment_missing {
+                let final_hash_type =
+                    TransactionCommitmentFinalHashType::for_version(&starknet_version)?;
+                header.transactions.root =
+                    Hash(calculate_transaction_commitment(&transactions, final_hash_type)?.0);
+            }
+            if event_commitment_missing {
+                header.events.root = Hash(calculate_event_commitment(&receipts)?.0);
+            }
+        }
+
+        let block = BlockId {
+            number: header.number,
+            hash: header.hash,
+        };
+        parts.push(BlockHeadersResponsePart::Header(Box::new(header)));
+        // The sequencer's signature binds the state diff commitment to the block hash.
+        if let Some(signature) = tx.signature(block_number)? {
+            parts.push(BlockHeadersResponsePart::Signatures(Signatures {
+                block,
+                signatures: vec![ConsensusSignature {
+                    r: signature.r.0,
+                    s: signature.s.0,
+                }],
+            }));
+        }
         parts.push(BlockHeadersResponsePart::Fin(Fin::ok()));
 
         Ok(true)
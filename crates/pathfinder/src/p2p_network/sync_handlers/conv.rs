@@ -8,9 +8,8 @@ use p2p_proto::receipt::{
 };
 use p2p_proto::state::{ContractDiff, ContractStoredValue, StateDiff};
 use p2p_proto::transaction::AccountSignature;
-use pathfinder_common::{
-    event::Event, state_update::ContractUpdate, transaction::Transaction, BlockHeader, StateUpdate,
-};
+use pathfinder_common::transaction::{DataAvailabilityMode, ResourceBound, Transaction};
+use pathfinder_common::{event::Event, state_update::ContractUpdate, BlockHeader, StateUpdate};
 use pathfinder_common::{StateCommitment, TransactionHash};
 use pathfinder_crypto::Felt;
 use starknet_gateway_types::reply::transaction as gw;
@@ -41,8 +40,20 @@ impl ToProto<p2p_proto::block::BlockHeader> for BlockHeader {
             state_diffs: ZERO_MERKLE,
             state: ZERO_PATRICIA,
             proof_fact: Hash(Felt::ZERO),
-            transactions: ZERO_MERKLE,
-            events: ZERO_MERKLE,
+            transactions: Merkle {
+                n_leaves: self
+                    .transaction_count
+                    .try_into()
+                    .expect("too many transactions in block"),
+                root: Hash(self.transaction_commitment.0),
+            },
+            events: Merkle {
+                n_leaves: self
+                    .event_count
+                    .try_into()
+                    .expect("too many events in block"),
+                root: Hash(self.event_commitment.0),
+            },
             receipts: ZERO_MERKLE,
             // FIXME extra fields added to make sync work
             hash: Hash(self.hash.0),
@@ -108,7 +119,8 @@ impl ToProto<p2p_proto::transaction::Transaction> for Transaction {
     fn to_proto(self) -> p2p_proto::transaction::Transaction {
         use p2p_proto::transaction as proto;
         use pathfinder_common::transaction::TransactionVariant::{
-            DeclareV0, DeclareV1, DeclareV2, Deploy, DeployAccount, InvokeV0, InvokeV1, L1Handler,
+            DeclareV0, DeclareV1, DeclareV2, DeclareV3, Deploy, DeployAccount, DeployAccountV3,
+            InvokeV0, InvokeV1, InvokeV3, L1Handler,
         };
         match self.variant {
             DeclareV0(x) => proto::Transaction::DeclareV0(proto::DeclareV0 {
@@ -139,6 +151,26 @@ impl ToProto<p2p_proto::transaction::Transaction> for Transaction {
                 nonce: x.nonce.0,
                 compiled_class_hash: x.compiled_class_hash.0,
             }),
+            DeclareV3(x) => proto::Transaction::DeclareV3(proto::DeclareV3 {
+                sender: Address(x.sender_address.0),
+                signature: AccountSignature {
+                    parts: x.signature.into_iter().map(|s| s.0).collect(),
+                },
+                class_hash: Hash(x.class_hash.0),
+                nonce: x.nonce.0,
+                compiled_class_hash: x.compiled_class_hash.0,
+                l1_gas: x.resource_bounds.l1_gas.to_proto(),
+                l2_gas: x.resource_bounds.l2_gas.to_proto(),
+                tip: x.tip.0.into(),
+                paymaster_data: x.paymaster_data.into_iter().map(|p| p.0).collect(),
+                nonce_domain: x.nonce_data_availability_mode.to_proto(),
+                fee_domain: x.fee_data_availability_mode.to_proto(),
+                account_deployment_data: x
+                    .account_deployment_data
+                    .into_iter()
+                    .map(|a| a.0)
+                    .collect(),
+            }),
             Deploy(x) => proto::Transaction::Deploy(proto::Deploy {
                 class_hash: Hash(x.class_hash.0),
                 address_salt: x.contract_address_salt.0,
@@ -158,6 +190,22 @@ impl ToProto<p2p_proto::transaction::Transaction> for Transaction {
                 calldata: x.constructor_calldata.into_iter().map(|c| c.0).collect(),
                 address: Address(x.contract_address.0),
             }),
+            DeployAccountV3(x) => proto::Transaction::DeployAccountV3(proto::DeployAccountV3 {
+                signature: AccountSignature {
+                    parts: x.signature.into_iter().map(|s| s.0).collect(),
+                },
+                class_hash: Hash(x.class_hash.0),
+                nonce: x.nonce.0,
+                address_salt: x.contract_address_salt.0,
+                calldata: x.constructor_calldata.into_iter().map(|c| c.0).collect(),
+                l1_gas: x.resource_bounds.l1_gas.to_proto(),
+                l2_gas: x.resource_bounds.l2_gas.to_proto(),
+                tip: x.tip.0.into(),
+                paymaster_data: x.paymaster_data.into_iter().map(|p| p.0).collect(),
+                nonce_domain: x.nonce_data_availability_mode.to_proto(),
+                fee_domain: x.fee_data_availability_mode.to_proto(),
+                address: Address(x.contract_address.0),
+            }),
             InvokeV0(x) => proto::Transaction::InvokeV0(proto::InvokeV0 {
                 max_fee: x.max_fee.0,
                 signature: AccountSignature {
@@ -183,6 +231,25 @@ impl ToProto<p2p_proto::transaction::Transaction> for Transaction {
                 nonce: x.nonce.0,
                 calldata: x.calldata.into_iter().map(|c| c.0).collect(),
             }),
+            InvokeV3(x) => proto::Transaction::InvokeV3(proto::InvokeV3 {
+                sender: Address(x.sender_address.0),
+                signature: AccountSignature {
+                    parts: x.signature.into_iter().map(|s| s.0).collect(),
+                },
+                nonce: x.nonce.0,
+                calldata: x.calldata.into_iter().map(|c| c.0).collect(),
+                l1_gas: x.resource_bounds.l1_gas.to_proto(),
+                l2_gas: x.resource_bounds.l2_gas.to_proto(),
+                tip: x.tip.0.into(),
+                paymaster_data: x.paymaster_data.into_iter().map(|p| p.0).collect(),
+                nonce_domain: x.nonce_data_availability_mode.to_proto(),
+                fee_domain: x.fee_data_availability_mode.to_proto(),
+                account_deployment_data: x
+                    .account_deployment_data
+                    .into_iter()
+                    .map(|a| a.0)
+                    .collect(),
+            }),
             L1Handler(x) => proto::Transaction::L1HandlerV1(proto::L1HandlerV1 {
                 nonce: x.nonce.0,
                 address: Address(x.contract_address.0),
@@ -193,6 +260,25 @@ impl ToProto<p2p_proto::transaction::Transaction> for Transaction {
     }
 }
 
+impl ToProto<p2p_proto::transaction::ResourceLimits> for ResourceBound {
+    fn to_proto(self) -> p2p_proto::transaction::ResourceLimits {
+        p2p_proto::transaction::ResourceLimits {
+            max_amount: self.max_amount.0.into(),
+            max_price_per_unit: self.max_price_per_unit.0.into(),
+        }
+    }
+}
+
+impl ToProto<String> for DataAvailabilityMode {
+    fn to_proto(self) -> String {
+        match self {
+            DataAvailabilityMode::L1 => "L1",
+            DataAvailabilityMode::L2 => "L2",
+        }
+        .to_owned()
+    }
+}
+
 impl ToProto<p2p_proto::receipt::Receipt> for (gw::Transaction, gw::Receipt) {
     fn to_proto(self) -> p2p_proto::receipt::Receipt {
         use p2p_proto::receipt::Receipt::{Declare, Deploy, DeployAccount, Invoke, L1Handler};
@@ -275,7 +361,7 @@ impl ToProto<p2p_proto::receipt::Receipt> for (gw::Transaction, gw::Receipt) {
             }),
             gw::Transaction::DeployAccount(x) => DeployAccount(DeployAccountTransactionReceipt {
                 common,
-                contract_address: x.contract_address.0,
+                contract_address: x.contract_address().0,
             }),
             gw::Transaction::Invoke(_) => Invoke(InvokeTransactionReceipt { common }),
             gw::Transaction::L1Handler(_) => L1Handler(L1HandlerTransactionReceipt {
@@ -30,6 +30,54 @@ fn get_next_block_number(
     );
 }
 
+#[test]
+fn signed_headers_are_followed_by_their_signatures() {
+    use super::blocking;
+    use p2p_proto::block::{BlockHeadersRequest, BlockHeadersResponsePart, Signatures};
+    use p2p_proto::common::{BlockId, BlockNumberOrHash, ConsensusSignature, Fin, Hash, Iteration};
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::BlockCommitmentSignature;
+
+    let storage = pathfinder_storage::Storage::in_memory().unwrap();
+    let blocks = pathfinder_storage::fake::with_n_blocks(&storage, 2);
+    let mut connection = storage.connection().unwrap();
+    let tx = connection.transaction().unwrap();
+    let signature = BlockCommitmentSignature {
+        r: block_commitment_signature_elem_bytes!(b"r"),
+        s: block_commitment_signature_elem_bytes!(b"s"),
+    };
+    tx.insert_signature(BlockNumber::GENESIS, &signature)
+        .unwrap();
+
+    let request = BlockHeadersRequest {
+        iteration: Iteration {
+            start: BlockNumberOrHash::Number(0),
+            direction: Direction::Forward,
+            limit: 2,
+            step: 1.into(),
+        },
+    };
+    let parts = blocking::get_headers(tx, request).unwrap().parts;
+
+    assert_eq!(parts.len(), 5);
+    assert_eq!(
+        parts[1],
+        BlockHeadersResponsePart::Signatures(Signatures {
+            block: BlockId {
+                number: 0,
+                hash: Hash(blocks[0].0.hash.0),
+            },
+            signatures: vec![ConsensusSignature {
+                r: signature.r.0,
+                s: signature.s.0,
+            }],
+        })
+    );
+    assert_eq!(parts[2], BlockHeadersResponsePart::Fin(Fin::ok()));
+    // The second block is unsigned.
+    assert_eq!(parts[4], BlockHeadersResponsePart::Fin(Fin::ok()));
+}
+
 mod boundary_conditions {
     use super::I64_MAX;
     use crate::p2p_network::sync_handlers::{
@@ -335,7 +383,10 @@ mod prop {
             // Compute the overlapping set between the db and the request
             // These are the headers that we expect to be read from the db
             let expected = overlapping::get(in_db, start_block, limit, step, num_blocks, direction)
-                .into_iter().map(|(h, _, _, _, _)| h.into()).collect::<Vec<_>>();
+                .into_iter().map(|(h, _, state_update, _, _)| p2p_types::BlockHeader {
+                    state_diff_commitment: state_update.compute_state_diff_commitment(),
+                    ..h.into()
+                }).collect::<Vec<_>>();
             // Run the handler
             let request = BlockHeadersRequest { iteration: Iteration { start: BlockNumberOrHash::Number(start_block), limit, step, direction, } };
             let BlockHeadersResponse { parts } = blocking::get_headers(tx, request).unwrap();
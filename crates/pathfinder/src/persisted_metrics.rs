@@ -0,0 +1,270 @@
+//! Persists selected monotonic counters across restarts, so that long-horizon dashboards don't
+//! reset to zero after redeployments.
+//!
+//! [PersistingRecorder] wraps the actual recorder and keeps track of the totals of the
+//! [PERSISTED] counters. [persist] restores them from the database on startup and snapshots them
+//! to it periodically.
+//!
+//! Likewise, [persist_slow_queries] periodically adds the statistics of the slow queries collected
+//! by the storage to the database, where `pathfinder database analyze` reports them.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use metrics::{Counter, CounterFn, Gauge, Histogram, Key, KeyName, Recorder, SharedString, Unit};
+use pathfinder_storage::Storage;
+
+/// The counters which are persisted. Only their series without labels are.
+pub const PERSISTED: &[&str] = &["sync_blocks_total", "rpc_requests_total"];
+
+/// How often the counters are persisted.
+const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A persisted counter, which forwards to the counter of the wrapped recorder.
+struct PersistedCounter {
+    total: AtomicU64,
+    inner: Counter,
+}
+
+impl CounterFn for PersistedCounter {
+    fn increment(&self, value: u64) {
+        self.total.fetch_add(value, Ordering::Relaxed);
+        self.inner.increment(value);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.total.fetch_max(value, Ordering::Relaxed);
+        self.inner.absolute(value);
+    }
+}
+
+/// Handle to the [PERSISTED] counters of a [PersistingRecorder].
+#[derive(Clone)]
+pub struct PersistedCounters {
+    counters: Arc<HashMap<&'static str, Arc<PersistedCounter>>>,
+    restored: Arc<AtomicBool>,
+}
+
+impl PersistedCounters {
+    /// The current values of the counters.
+    pub fn snapshot(&self) -> Vec<(&'static str, u64)> {
+        self.counters
+            .iter()
+            .map(|(name, counter)| (*name, counter.total.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Adds the values of a previous run to the counters. Values of other counters are ignored.
+    pub fn restore(&self, values: &HashMap<String, u64>) {
+        for (name, counter) in self.counters.iter() {
+            if let Some(value) = values.get(*name) {
+                counter.increment(*value);
+            }
+        }
+        self.restored.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Wraps a recorder, keeping track of the [PERSISTED] counters' totals.
+pub struct PersistingRecorder<R> {
+    inner: R,
+    counters: PersistedCounters,
+}
+
+impl<R: Recorder> PersistingRecorder<R> {
+    pub fn new(inner: R) -> Self {
+        let counters = PERSISTED
+            .iter()
+            .map(|name| {
+                let counter = PersistedCounter {
+                    total: AtomicU64::new(0),
+                    inner: inner.register_counter(&Key::from_static_name(name)),
+                };
+                (*name, Arc::new(counter))
+            })
+            .collect();
+
+        Self {
+            inner,
+            counters: PersistedCounters {
+                counters: Arc::new(counters),
+                restored: Default::default(),
+            },
+        }
+    }
+
+    pub fn counters(&self) -> PersistedCounters {
+        self.counters.clone()
+    }
+}
+
+impl<R: Recorder> Recorder for PersistingRecorder<R> {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.inner.describe_counter(key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.inner.describe_gauge(key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.inner.describe_histogram(key, unit, description)
+    }
+
+    fn register_counter(&self, key: &Key) -> Counter {
+        match self.counters.counters.get(key.name()) {
+            Some(counter) if key.labels().next().is_none() => Counter::from_arc(counter.clone()),
+            _ => self.inner.register_counter(key),
+        }
+    }
+
+    fn register_gauge(&self, key: &Key) -> Gauge {
+        self.inner.register_gauge(key)
+    }
+
+    fn register_histogram(&self, key: &Key) -> Histogram {
+        self.inner.register_histogram(key)
+    }
+}
+
+/// Restores the counters from the database, and then persists them periodically, forever.
+pub async fn persist(storage: Storage, counters: PersistedCounters) -> anyhow::Result<()> {
+    let values = {
+        let storage = storage.clone();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            let mut db = storage.connection()?;
+            let tx = db.transaction()?;
+            tx.metric_counters()
+        })
+        .await
+        .context("Joining metric counters query")?
+        .context("Querying metric counters")?
+    };
+    counters.restore(&values);
+
+    let mut interval = tokio::time::interval(PERSIST_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+
+        let storage = storage.clone();
+        let counters = counters.clone();
+        tokio::task::spawn_blocking(move || snapshot(&storage, &counters))
+            .await
+            .context("Joining metric counters snapshot")??;
+    }
+}
+
+/// Persists the current values of the counters, unless they were not restored yet as the
+/// persisted values would be reset then.
+pub fn snapshot(storage: &Storage, counters: &PersistedCounters) -> anyhow::Result<()> {
+    if !counters.restored.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let mut db = storage.connection()?;
+    let tx = db.transaction()?;
+    tx.upsert_metric_counters(&counters.snapshot())
+        .context("Persisting metric counters")?;
+    tx.commit().context("Committing metric counters")
+}
+
+/// Persists the statistics of slow queries collected by the storage periodically, forever.
+pub async fn persist_slow_queries(storage: Storage) -> anyhow::Result<()> {
+    let mut interval = tokio::time::interval(PERSIST_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+
+        let storage = storage.clone();
+        tokio::task::spawn_blocking(move || record_slow_queries(&storage))
+            .await
+            .context("Joining slow queries snapshot")??;
+    }
+}
+
+/// Adds the statistics of the slow queries executed since the last call to the persisted ones.
+pub fn record_slow_queries(storage: &Storage) -> anyhow::Result<()> {
+    let queries = storage.take_slow_queries();
+    if queries.is_empty() {
+        return Ok(());
+    }
+
+    let mut db = storage.connection()?;
+    let tx = db.transaction()?;
+    tx.record_slow_queries(&queries)
+        .context("Persisting slow queries")?;
+    tx.commit().context("Committing slow queries")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    #[test]
+    fn snapshot_and_restore() {
+        let inner = PrometheusBuilder::new().build_recorder();
+        let handle = inner.handle();
+        let recorder = PersistingRecorder::new(inner);
+        let counters = recorder.counters();
+
+        counters.restore(&HashMap::from([
+            ("sync_blocks_total".to_owned(), 10),
+            ("unknown_total".to_owned(), 1),
+        ]));
+        recorder
+            .register_counter(&Key::from_static_name("sync_blocks_total"))
+            .increment(2);
+        // Labelled series are not persisted.
+        recorder
+            .register_counter(&Key::from_parts(
+                "sync_blocks_total",
+                vec![metrics::Label::new("a", "b")],
+            ))
+            .increment(100);
+
+        let mut snapshot = counters.snapshot();
+        snapshot.sort();
+        assert_eq!(
+            snapshot,
+            vec![("rpc_requests_total", 0), ("sync_blocks_total", 12)]
+        );
+
+        let rendered = handle.render();
+        assert!(rendered.contains("\nsync_blocks_total 12\n"), "{rendered}");
+    }
+
+    #[test]
+    fn persisted() {
+        let storage = Storage::in_memory().unwrap();
+        let recorder = PersistingRecorder::new(metrics::NoopRecorder);
+        let counters = recorder.counters();
+
+        recorder
+            .register_counter(&Key::from_static_name("rpc_requests_total"))
+            .increment(5);
+        // Not persisted before the previous values are restored.
+        snapshot(&storage, &counters).unwrap();
+        {
+            let mut db = storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            assert!(tx.metric_counters().unwrap().is_empty());
+        }
+
+        counters.restore(&HashMap::new());
+        snapshot(&storage, &counters).unwrap();
+
+        let mut db = storage.connection().unwrap();
+        let tx = db.transaction().unwrap();
+        assert_eq!(
+            tx.metric_counters().unwrap(),
+            HashMap::from([
+                ("rpc_requests_total".to_owned(), 5),
+                ("sync_blocks_total".to_owned(), 0),
+            ])
+        );
+    }
+}
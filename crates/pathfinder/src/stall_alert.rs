@@ -0,0 +1,226 @@
+//! Alerts on stalled block production or sync.
+//!
+//! [monitor] tracks when the pending block, the L2 head and the L1 head last changed. Once one of
+//! them stays unchanged for longer than its [threshold](StallThresholds), the
+//! `sync_stalled{head="..."}` gauge is set to 1 and the optional webhook is called. Both are
+//! reset once the head changes again.
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use pathfinder_common::BlockNumber;
+use pathfinder_rpc::PendingData;
+use pathfinder_storage::{BlockId, Storage};
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::alert::Alerts;
+
+/// How often the heads are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The durations after which an unchanged head is considered stalled. Heads without a threshold
+/// are not monitored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StallThresholds {
+    pub pending: Option<Duration>,
+    pub l2_head: Option<Duration>,
+    pub l1_head: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Head {
+    Pending,
+    L2,
+    L1,
+}
+
+impl Head {
+    fn label(&self) -> &'static str {
+        match self {
+            Head::Pending => "pending",
+            Head::L2 => "l2",
+            Head::L1 => "l1",
+        }
+    }
+}
+
+/// The body of webhook requests.
+#[derive(Debug, serde::Serialize)]
+struct Alert {
+    head: Head,
+    /// False once the head changed again.
+    stalled: bool,
+    /// The number of seconds since the head last changed.
+    unchanged_for: u64,
+}
+
+/// Tracks how long a head has been unchanged.
+struct Tracker<T> {
+    head: Head,
+    threshold: Duration,
+    value: Option<T>,
+    changed_at: Instant,
+    stalled: bool,
+}
+
+impl<T: PartialEq> Tracker<T> {
+    fn new(head: Head, threshold: Duration, value: Option<T>, now: Instant) -> Self {
+        Self {
+            head,
+            threshold,
+            value,
+            changed_at: now,
+            stalled: false,
+        }
+    }
+
+    /// Records the head's current value, and returns an alert if its stalled state flipped.
+    fn update(&mut self, value: Option<T>, now: Instant) -> Option<Alert> {
+        if value != self.value {
+            self.value = value;
+            self.changed_at = now;
+        }
+
+        let unchanged_for = now.saturating_duration_since(self.changed_at);
+        let stalled = unchanged_for > self.threshold;
+        if stalled == self.stalled {
+            return None;
+        }
+        self.stalled = stalled;
+
+        Some(Alert {
+            head: self.head,
+            stalled,
+            unchanged_for: unchanged_for.as_secs(),
+        })
+    }
+}
+
+/// Monitors the heads with a threshold, forever.
+///
+/// The pending block is considered changed whenever `pending` is updated.
+pub async fn monitor(
+    storage: Storage,
+    mut pending: watch::Receiver<Arc<PendingData>>,
+    thresholds: StallThresholds,
+    webhook: Option<reqwest::Url>,
+) -> anyhow::Result<()> {
+    let heads = [Head::Pending, Head::L2, Head::L1].map(|head| head.label());
+    let alerts = Alerts::new("sync_stalled", "head", &heads, webhook)?;
+
+    let now = Instant::now();
+    // Each pending update is counted, as consecutive pending blocks may be equal.
+    let mut pending_updates = 0u64;
+    let mut pending_tracker = thresholds
+        .pending
+        .map(|threshold| Tracker::new(Head::Pending, threshold, Some(pending_updates), now));
+    let mut l2_tracker = thresholds
+        .l2_head
+        .map(|threshold| Tracker::new(Head::L2, threshold, None, now));
+    let mut l1_tracker = thresholds
+        .l1_head
+        .map(|threshold| Tracker::new(Head::L1, threshold, None, now));
+
+    loop {
+        if pending.has_changed().unwrap_or_default() {
+            pending.borrow_and_update();
+            pending_updates += 1;
+        }
+
+        let heads = {
+            let storage = storage.clone();
+            tokio::task::spawn_blocking(move || heads(storage))
+                .await
+                .context("Joining heads query")
+                .and_then(|heads| heads.context("Querying heads"))
+        };
+        // A failing query, e.g. while the database is busy, must not stop the monitoring.
+        let (l2_head, l1_head) = match heads {
+            Ok(heads) => heads,
+            Err(error) => {
+                tracing::warn!(reason=?error, "Checking for stalled heads failed");
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                continue;
+            }
+        };
+
+        let now = Instant::now();
+        let changes = [
+            pending_tracker
+                .as_mut()
+                .and_then(|tracker| tracker.update(Some(pending_updates), now)),
+            l2_tracker
+                .as_mut()
+                .and_then(|tracker| tracker.update(l2_head, now)),
+            l1_tracker
+                .as_mut()
+                .and_then(|tracker| tracker.update(l1_head, now)),
+        ];
+
+        for alert in changes.into_iter().flatten() {
+            let head = alert.head.label();
+            if alert.stalled {
+                tracing::warn!(%head, unchanged_for=%alert.unchanged_for, "Head stalled");
+            } else {
+                tracing::info!(%head, "Head no longer stalled");
+            }
+            alerts.report(head, alert.stalled, &alert).await;
+        }
+
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+/// The latest L2 block, and the latest L2 block accepted on L1.
+fn heads(storage: Storage) -> anyhow::Result<(Option<BlockNumber>, Option<BlockNumber>)> {
+    let mut db = storage.connection()?;
+    let tx = db.transaction()?;
+
+    let l2_head = tx
+        .block_id(BlockId::Latest)
+        .context("Querying L2 head")?
+        .map(|(number, _)| number);
+    let l1_head = tx
+        .latest_l1_state()
+        .context("Querying L1 head")?
+        .map(|state| state.block_number);
+
+    Ok((l2_head, l1_head))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker() {
+        let start = Instant::now();
+        let threshold = Duration::from_secs(60);
+        let mut tracker = Tracker::new(Head::L2, threshold, Some(1), start);
+
+        assert!(tracker.update(Some(1), start + threshold).is_none());
+
+        let alert = tracker
+            .update(Some(1), start + threshold + Duration::from_secs(1))
+            .unwrap();
+        assert!(alert.stalled);
+        assert_eq!(alert.unchanged_for, 61);
+
+        // Only changes of the stalled state are alerted.
+        assert!(tracker.update(Some(1), start + threshold * 2).is_none());
+
+        let alert = tracker.update(Some(2), start + threshold * 3).unwrap();
+        assert!(!alert.stalled);
+        assert_eq!(alert.unchanged_for, 0);
+
+        assert!(tracker.update(Some(2), start + threshold * 4).is_none());
+        assert!(
+            tracker
+                .update(Some(2), start + threshold * 4 + Duration::from_secs(1))
+                .unwrap()
+                .stalled
+        );
+    }
+}
@@ -1,4 +1,7 @@
 pub mod block_hash;
+pub mod devnet;
+pub mod receipt;
+pub mod replay;
 mod sync;
 
-pub use sync::{l1, l2, sync, SyncContext};
+pub use sync::{l1, l2, sync, OverflowStrategy, PollIntervals, SyncContext, SyncError};
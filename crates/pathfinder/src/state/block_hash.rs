@@ -250,7 +250,7 @@ fn compute_final_hash_pre_0_7(
 
 /// This implements the final hashing step for post-0.7 blocks.
 #[allow(clippy::too_many_arguments)]
-fn compute_final_hash(
+pub(crate) fn compute_final_hash(
     block_number: BlockNumber,
     state_root: StateCommitment,
     sequencer_address: &SequencerAddress,
@@ -392,7 +392,7 @@ fn calculate_transaction_hash_with_signature(tx: &Transaction) -> Felt {
     let signature_hash = match tx {
         Transaction::Invoke(tx) => calculate_signature_hash(tx.signature()),
         Transaction::Declare(tx) => calculate_signature_hash(tx.signature()),
-        Transaction::DeployAccount(tx) => calculate_signature_hash(&tx.signature),
+        Transaction::DeployAccount(tx) => calculate_signature_hash(tx.signature()),
         Transaction::Deploy(_) | Transaction::L1Handler(_) => *HASH_OF_EMPTY_LIST,
     };
 
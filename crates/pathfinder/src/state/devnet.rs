@@ -0,0 +1,929 @@
+//! A local development network which produces blocks without an external sequencer.
+//!
+//! Transactions submitted to the gateway's `add_transaction` endpoint are executed immediately
+//! and committed in a block of their own. Together with the RPC server this makes pathfinder a
+//! lightweight local testing backend which serves the same API as a node on a real network.
+//!
+//! The network is intentionally minimal:
+//! - there is no fee token, so fees are computed but never charged,
+//! - transactions which revert are rejected instead of being included,
+//! - the compiled class hash of declared Sierra classes is trusted as submitted.
+//!
+//! A devnet can also [fork](Devnet::fork) a network at one of its blocks, similar to anvil's fork
+//! mode. Local blocks are then built on top of the fork block, and state which wasn't changed
+//! locally is read from the network's feeder gateway. The network's state tries are not available
+//! locally, so the state commitment of local blocks is that of the fork block.
+
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use pathfinder_common::{
+    BlockHash, BlockHeader, BlockNumber, BlockTimestamp, ChainId, ClassHash, ContractAddress, Fee,
+    GasPrice, StarknetVersion, StateCommitment, StateUpdate, TransactionHash, TransactionIndex,
+    TransactionVersion,
+};
+use pathfinder_crypto::Felt;
+use pathfinder_executor::types::{StateDiff, TransactionSimulation, TransactionTrace};
+use pathfinder_executor::{CallError, ExecutionState, RemoteState};
+use pathfinder_rpc::v02::types::request::deployed_contract_address;
+use pathfinder_storage::{BlockId, Connection, Storage, TransactionBehavior};
+use starknet_gateway_types::class_hash::{compute_class_hash, ComputedClassHash};
+use starknet_gateway_types::error::{KnownStarknetErrorCode, StarknetError, StarknetErrorCode};
+use starknet_gateway_types::reply::transaction as gateway;
+use starknet_gateway_types::request::add_transaction::{
+    AddTransaction, CairoContractDefinition, ContractDefinition, SierraContractDefinition,
+};
+use starknet_gateway_types::transaction_hash::compute_transaction_hash;
+use warp::Filter;
+
+use super::block_hash::{
+    calculate_event_commitment, calculate_transaction_commitment, compute_final_hash,
+    TransactionCommitmentFinalHashType,
+};
+use super::sync::update_starknet_state;
+
+mod fork;
+
+pub use fork::Fork;
+
+/// The gas price of every devnet block.
+pub const GAS_PRICE: GasPrice = GasPrice(1_000_000_000);
+
+/// Produces a block for each submitted transaction.
+#[derive(Clone)]
+pub struct Devnet(Arc<Inner>);
+
+struct Inner {
+    storage: Storage,
+    chain_id: ChainId,
+    /// Blocks are produced one at a time on this connection.
+    connection: Mutex<Connection>,
+    /// The forked network's state, if any.
+    remote_state: Option<Arc<dyn RemoteState>>,
+}
+
+/// A transaction which was committed in a new block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accepted {
+    pub block_number: BlockNumber,
+    pub transaction_hash: TransactionHash,
+    /// The class declared by a `DECLARE` transaction.
+    pub class_hash: Option<ClassHash>,
+    /// The account deployed by a `DEPLOY_ACCOUNT` transaction.
+    pub address: Option<ContractAddress>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The transaction is invalid or failed, no block was produced.
+    #[error(transparent)]
+    Rejected(#[from] StarknetError),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+fn rejected(code: KnownStarknetErrorCode, message: impl Into<String>) -> Error {
+    Error::Rejected(StarknetError {
+        code: code.into(),
+        message: message.into(),
+    })
+}
+
+impl Devnet {
+    pub fn new(storage: Storage, chain_id: ChainId) -> anyhow::Result<Self> {
+        let connection = storage
+            .connection()
+            .context("Creating database connection")?;
+
+        Ok(Self(Arc::new(Inner {
+            storage,
+            chain_id,
+            connection: Mutex::new(connection),
+            remote_state: None,
+        })))
+    }
+
+    /// A devnet which builds on top of the fork block.
+    ///
+    /// The fork block is inserted into an empty database. Otherwise the database must already
+    /// hold the same fork, and production continues from its latest block.
+    pub fn fork(storage: Storage, chain_id: ChainId, fork: Fork) -> anyhow::Result<Self> {
+        let mut connection = storage
+            .connection()
+            .context("Creating database connection")?;
+        let db = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .context("Create database transaction")?;
+
+        match db
+            .block_header(BlockId::Number(fork.header.number))
+            .context("Fetching fork block header")?
+        {
+            Some(header) => anyhow::ensure!(
+                header.hash == fork.header.hash,
+                "Database holds a fork of a different block {}",
+                header.hash
+            ),
+            None => {
+                anyhow::ensure!(
+                    db.block_id(BlockId::Latest)
+                        .context("Fetching latest block")?
+                        .is_none(),
+                    "Database holds a fork of a different block"
+                );
+
+                db.insert_block_header(&fork.header)
+                    .context("Inserting fork block header")?;
+                db.insert_transaction_data(
+                    fork.header.hash,
+                    fork.header.number,
+                    &fork.transactions,
+                )
+                .context("Inserting fork block transactions")?;
+                db.commit().context("Commit database transaction")?;
+            }
+        }
+
+        Ok(Self(Arc::new(Inner {
+            storage,
+            chain_id,
+            connection: Mutex::new(connection),
+            remote_state: Some(fork.state),
+        })))
+    }
+
+    /// Executes the transaction on top of the latest block, and commits it in a new block.
+    pub fn add_transaction(&self, transaction: AddTransaction) -> Result<Accepted, Error> {
+        let mut connection = self.0.connection.lock().unwrap();
+        let db = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .context("Create database transaction")?;
+
+        let parent = db
+            .block_header(BlockId::Latest)
+            .context("Fetching latest block header")?;
+        let header = next_header(parent.as_ref());
+
+        let mut accepted = Accepted {
+            block_number: header.number,
+            transaction_hash: TransactionHash::ZERO,
+            class_hash: None,
+            address: None,
+        };
+
+        let mut transaction = match transaction {
+            AddTransaction::Invoke(tx) => {
+                let transaction = if tx.version == TransactionVersion::ZERO {
+                    let entry_point_selector = tx.entry_point_selector.ok_or_else(|| {
+                        rejected(
+                            KnownStarknetErrorCode::MalformedRequest,
+                            "Invoke v0 requires an entry point selector",
+                        )
+                    })?;
+                    gateway::InvokeTransaction::V0(gateway::InvokeTransactionV0 {
+                        calldata: tx.calldata,
+                        sender_address: tx.sender_address,
+                        entry_point_selector,
+                        entry_point_type: None,
+                        max_fee: tx.max_fee,
+                        signature: tx.signature,
+                        transaction_hash: TransactionHash::ZERO,
+                    })
+                } else if tx.version == TransactionVersion::ONE {
+                    let nonce = tx.nonce.ok_or_else(|| {
+                        rejected(
+                            KnownStarknetErrorCode::MalformedRequest,
+                            "Invoke v1 requires a nonce",
+                        )
+                    })?;
+                    gateway::InvokeTransaction::V1(gateway::InvokeTransactionV1 {
+                        calldata: tx.calldata,
+                        sender_address: tx.sender_address,
+                        max_fee: tx.max_fee,
+                        signature: tx.signature,
+                        nonce,
+                        transaction_hash: TransactionHash::ZERO,
+                    })
+                } else {
+                    return Err(unsupported_version(tx.version));
+                };
+
+                gateway::Transaction::Invoke(transaction)
+            }
+            AddTransaction::DeployAccount(tx) => {
+                if tx.version != TransactionVersion::ONE {
+                    return Err(unsupported_version(tx.version));
+                }
+
+                let contract_address = deployed_contract_address(
+                    tx.contract_address_salt,
+                    tx.class_hash,
+                    &tx.constructor_calldata,
+                );
+                accepted.address = Some(contract_address);
+
+                gateway::Transaction::DeployAccount(gateway::DeployAccountTransaction::V0V1(
+                    gateway::DeployAccountTransactionV0V1 {
+                        contract_address,
+                        transaction_hash: TransactionHash::ZERO,
+                        max_fee: tx.max_fee,
+                        version: tx.version,
+                        signature: tx.signature,
+                        nonce: tx.nonce,
+                        contract_address_salt: tx.contract_address_salt,
+                        constructor_calldata: tx.constructor_calldata,
+                        class_hash: tx.class_hash,
+                    },
+                ))
+            }
+            AddTransaction::Declare(tx) => {
+                let definition = match tx.contract_class {
+                    ContractDefinition::Cairo(class) => cairo_definition(class),
+                    ContractDefinition::Sierra(class) => sierra_definition(class),
+                }
+                .map_err(|e| {
+                    rejected(
+                        KnownStarknetErrorCode::InvalidContractClass,
+                        format!("{e:#}"),
+                    )
+                })?;
+                let class_hash = compute_class_hash(&definition).map_err(|e| {
+                    rejected(
+                        KnownStarknetErrorCode::InvalidContractClass,
+                        format!("{e:#}"),
+                    )
+                })?;
+
+                if db
+                    .class_declaration_block(class_hash.hash())
+                    .context("Querying class declaration")?
+                    .is_some()
+                {
+                    return Err(rejected(
+                        KnownStarknetErrorCode::ClassAlreadyDeclared,
+                        format!("Class {} is already declared", class_hash.hash()),
+                    ));
+                }
+                accepted.class_hash = Some(class_hash.hash());
+
+                let transaction = match (class_hash, tx.compiled_class_hash) {
+                    (ComputedClassHash::Cairo(class_hash), None)
+                        if tx.version == TransactionVersion::ZERO
+                            || tx.version == TransactionVersion::ONE =>
+                    {
+                        db.insert_cairo_class(class_hash, &definition)
+                            .context("Inserting class definition")?;
+
+                        let transaction = gateway::DeclareTransactionV0V1 {
+                            class_hash,
+                            max_fee: tx.max_fee,
+                            nonce: tx.nonce,
+                            sender_address: tx.sender_address,
+                            signature: tx.signature,
+                            transaction_hash: TransactionHash::ZERO,
+                        };
+                        match tx.version == TransactionVersion::ZERO {
+                            true => gateway::DeclareTransaction::V0(transaction),
+                            false => gateway::DeclareTransaction::V1(transaction),
+                        }
+                    }
+                    (ComputedClassHash::Sierra(class_hash), Some(compiled_class_hash))
+                        if tx.version == TransactionVersion::TWO =>
+                    {
+                        let casm =
+                            pathfinder_compiler::compile_to_casm_with_latest_compiler(&definition)
+                                .map_err(|e| {
+                                    rejected(
+                                        KnownStarknetErrorCode::CompilationFailed,
+                                        format!("{e:#}"),
+                                    )
+                                })?;
+                        db.insert_sierra_class(
+                            &pathfinder_common::SierraHash(class_hash.0),
+                            &definition,
+                            &compiled_class_hash,
+                            &casm,
+                            pathfinder_compiler::COMPILER_VERSION,
+                        )
+                        .context("Inserting class definition")?;
+
+                        gateway::DeclareTransaction::V2(gateway::DeclareTransactionV2 {
+                            class_hash,
+                            max_fee: tx.max_fee,
+                            nonce: tx.nonce,
+                            sender_address: tx.sender_address,
+                            signature: tx.signature,
+                            transaction_hash: TransactionHash::ZERO,
+                            compiled_class_hash,
+                        })
+                    }
+                    _ => {
+                        return Err(rejected(
+                            KnownStarknetErrorCode::InvalidTransactionVersion,
+                            "Cairo classes are declared by v0 and v1, and Sierra classes by v2 \
+                            transactions with a compiled class hash",
+                        ))
+                    }
+                };
+
+                gateway::Transaction::Declare(transaction)
+            }
+        };
+
+        let transaction_hash = compute_transaction_hash(&transaction, self.0.chain_id);
+        set_transaction_hash(&mut transaction, transaction_hash);
+        accepted.transaction_hash = transaction_hash;
+
+        if db
+            .transaction(transaction_hash)
+            .context("Querying transaction")?
+            .is_some()
+        {
+            return Err(rejected(
+                KnownStarknetErrorCode::DuplicatedTransaction,
+                format!("Transaction {transaction_hash} was already added"),
+            ));
+        }
+
+        let executor_transaction = pathfinder_rpc::compose_executor_transaction(&transaction, &db)
+            .context("Converting transaction for execution")?;
+        let execution_state = ExecutionState::trace(&db, self.0.chain_id, header.clone(), None)
+            .with_remote_state(self.0.remote_state.clone());
+        let simulation = pathfinder_executor::simulate(
+            execution_state,
+            vec![executor_transaction],
+            false,
+            true,
+            false,
+        )
+        .map_err(|error| match error {
+            CallError::Internal(e) => Error::Internal(e),
+            CallError::Reverted(reason) => {
+                rejected(KnownStarknetErrorCode::TransactionFailed, reason)
+            }
+            CallError::ContractNotFound => rejected(
+                KnownStarknetErrorCode::UninitializedContract,
+                "Contract not found",
+            ),
+            CallError::InvalidMessageSelector => rejected(
+                KnownStarknetErrorCode::EntryPointNotFound,
+                "Entry point not found",
+            ),
+            CallError::ResourcesExceeded { resource, limit } => rejected(
+                KnownStarknetErrorCode::TransactionFailed,
+                format!("Execution exceeded the limit of {limit} {resource}"),
+            ),
+            CallError::Custom(e) => {
+                rejected(KnownStarknetErrorCode::TransactionFailed, format!("{e:#}"))
+            }
+        })?
+        .pop()
+        .context("Transaction was not executed")?;
+
+        let receipt = receipt(transaction_hash, &simulation)?;
+        let state_update = state_update(state_diff(&simulation.trace));
+
+        self.commit(db, header, transaction, receipt, state_update)?;
+
+        tracing::info!(block=%accepted.block_number, transaction=%transaction_hash, "Produced block");
+
+        Ok(accepted)
+    }
+
+    fn commit(
+        &self,
+        db: pathfinder_storage::Transaction<'_>,
+        header: BlockHeader,
+        transaction: gateway::Transaction,
+        receipt: gateway::Receipt,
+        state_update: StateUpdate,
+    ) -> anyhow::Result<()> {
+        let parent = match header.number.parent() {
+            Some(parent) => Some(
+                db.block_header(parent.into())
+                    .context("Fetching parent block header")?
+                    .context("Parent block header is missing")?,
+            ),
+            None => None,
+        };
+
+        let (storage_commitment, class_commitment, state_commitment) =
+            match (&self.0.remote_state, &parent) {
+                (Some(_), Some(parent)) => (
+                    parent.storage_commitment,
+                    parent.class_commitment,
+                    parent.state_commitment,
+                ),
+                _ => {
+                    let (storage_commitment, class_commitment) = update_starknet_state(
+                        &db,
+                        &state_update,
+                        false,
+                        header.number,
+                        self.0.storage.clone(),
+                    )
+                    .context("Updating Starknet state")?;
+                    let state_commitment =
+                        StateCommitment::calculate(storage_commitment, class_commitment);
+                    (storage_commitment, class_commitment, state_commitment)
+                }
+            };
+
+        let transaction_commitment = calculate_transaction_commitment(
+            std::slice::from_ref(&transaction),
+            TransactionCommitmentFinalHashType::for_version(&header.starknet_version)?,
+        )
+        .context("Calculating transaction commitment")?;
+        let event_commitment = calculate_event_commitment(std::slice::from_ref(&receipt))
+            .context("Calculating event commitment")?;
+        let event_count = receipt.events.len();
+
+        let hash = compute_final_hash(
+            header.number,
+            state_commitment,
+            &header.sequencer_address,
+            header.timestamp,
+            1,
+            transaction_commitment.0,
+            event_count as u64,
+            event_commitment.0,
+            header.parent_hash,
+        );
+
+        let parent_state_commitment = parent
+            .map(|parent| parent.state_commitment)
+            .unwrap_or(StateCommitment::ZERO);
+
+        let header = BlockHeader {
+            hash,
+            class_commitment,
+            event_commitment,
+            state_commitment,
+            storage_commitment,
+            transaction_commitment,
+            transaction_count: 1,
+            event_count,
+            ..header
+        };
+        let state_update = state_update
+            .with_block_hash(hash)
+            .with_state_commitment(state_commitment)
+            .with_parent_state_commitment(parent_state_commitment);
+
+        db.insert_block_header(&header)
+            .context("Inserting block header into database")?;
+        db.insert_transaction_data(header.hash, header.number, &[(transaction, receipt)])
+            .context("Insert transaction data into database")?;
+        db.insert_state_update(header.number, &state_update)
+            .context("Insert state update into database")?;
+
+        db.commit().context("Commit database transaction")
+    }
+}
+
+/// The header of the block following `parent`, without any of its commitments.
+fn next_header(parent: Option<&BlockHeader>) -> BlockHeader {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let builder = match parent {
+        Some(parent) => parent.child_builder(),
+        None => BlockHeader::builder(),
+    };
+
+    // Multiple blocks may be produced per second, and timestamps must not decrease.
+    let timestamp = parent
+        .map(|parent| parent.timestamp.get())
+        .unwrap_or_default()
+        .max(now);
+
+    builder
+        .with_timestamp(BlockTimestamp::new_or_panic(timestamp))
+        .with_gas_price(GAS_PRICE)
+        .with_starknet_version(StarknetVersion::new(0, 12, 2))
+        .finalize_with_hash(BlockHash::ZERO)
+}
+
+fn unsupported_version(version: TransactionVersion) -> Error {
+    rejected(
+        KnownStarknetErrorCode::InvalidTransactionVersion,
+        format!("Unsupported transaction version {version:?}"),
+    )
+}
+
+fn set_transaction_hash(transaction: &mut gateway::Transaction, hash: TransactionHash) {
+    use gateway::{DeclareTransaction, InvokeTransaction, Transaction};
+
+    match transaction {
+        Transaction::Declare(DeclareTransaction::V0(tx) | DeclareTransaction::V1(tx)) => {
+            tx.transaction_hash = hash
+        }
+        Transaction::Declare(DeclareTransaction::V2(tx)) => tx.transaction_hash = hash,
+        Transaction::Declare(DeclareTransaction::V3(tx)) => tx.transaction_hash = hash,
+        Transaction::DeployAccount(tx) => *tx.transaction_hash_mut() = hash,
+        Transaction::Invoke(InvokeTransaction::V0(tx)) => tx.transaction_hash = hash,
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => tx.transaction_hash = hash,
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => tx.transaction_hash = hash,
+        Transaction::Deploy(_) | Transaction::L1Handler(_) => {
+            unreachable!("Only account transactions are submitted")
+        }
+    }
+}
+
+/// Decompresses a gzip + base64 encoded JSON value.
+fn decompress(encoded: &str) -> anyhow::Result<serde_json::Value> {
+    let compressed = base64::decode(encoded).context("Decoding base64")?;
+    let mut decompressor = flate2::read::GzDecoder::new(compressed.as_slice());
+    let mut json = Vec::new();
+    decompressor
+        .read_to_end(&mut json)
+        .context("Decompressing program")?;
+
+    serde_json::from_slice(&json).context("Parsing program JSON")
+}
+
+/// The class definition as served by the feeder gateway.
+fn cairo_definition(class: CairoContractDefinition) -> anyhow::Result<Vec<u8>> {
+    let mut program = decompress(&class.program)?;
+
+    // Parsing the program for execution requires `debug_info` to be present.
+    program
+        .as_object_mut()
+        .context("Program is not an object")?
+        .entry("debug_info")
+        .or_insert_with(|| serde_json::json!(""));
+
+    let definition = serde_json::json!({
+        "program": program,
+        "entry_points_by_type": class.entry_points_by_type,
+        "abi": class.abi,
+    });
+
+    Ok(serde_json::to_vec(&definition)?)
+}
+
+/// The class definition as served by the feeder gateway.
+fn sierra_definition(class: SierraContractDefinition) -> anyhow::Result<Vec<u8>> {
+    let sierra_program = decompress(&class.sierra_program)?;
+
+    let definition = serde_json::json!({
+        "sierra_program": sierra_program,
+        "contract_class_version": class.contract_class_version,
+        "entry_points_by_type": class.entry_points_by_type,
+        "abi": class.abi,
+    });
+
+    Ok(serde_json::to_vec(&definition)?)
+}
+
+fn state_diff(trace: &TransactionTrace) -> &StateDiff {
+    match trace {
+        TransactionTrace::Declare(trace) => &trace.state_diff,
+        TransactionTrace::DeployAccount(trace) => &trace.state_diff,
+        TransactionTrace::Invoke(trace) => &trace.state_diff,
+        TransactionTrace::L1Handler(trace) => &trace.state_diff,
+    }
+}
+
+fn receipt(
+    transaction_hash: TransactionHash,
+    simulation: &TransactionSimulation,
+) -> anyhow::Result<gateway::Receipt> {
+    let mut actual_fee = [0u8; 32];
+    simulation
+        .fee_estimation
+        .overall_fee
+        .to_big_endian(&mut actual_fee);
+    let actual_fee = Felt::from_be_bytes(actual_fee).context("Fee overflow")?;
+
+    Ok(super::receipt::from_trace(
+        transaction_hash,
+        TransactionIndex::new_or_panic(0),
+        &simulation.trace,
+        Fee(actual_fee),
+    ))
+}
+
+fn state_update(diff: &StateDiff) -> StateUpdate {
+    let mut state_update = StateUpdate::default();
+
+    for (address, storage_diffs) in &diff.storage_diffs {
+        for diff in storage_diffs {
+            // The block hash contract is a system contract.
+            state_update = if *address == ContractAddress::ONE {
+                state_update.with_system_storage_update(*address, diff.key, diff.value)
+            } else {
+                state_update.with_storage_update(*address, diff.key, diff.value)
+            };
+        }
+    }
+    for contract in &diff.deployed_contracts {
+        state_update = state_update.with_deployed_contract(contract.address, contract.class_hash);
+    }
+    for class_hash in &diff.deprecated_declared_classes {
+        state_update = state_update.with_declared_cairo_class(*class_hash);
+    }
+    for class in &diff.declared_classes {
+        state_update =
+            state_update.with_declared_sierra_class(class.class_hash, class.compiled_class_hash);
+    }
+    for (address, nonce) in &diff.nonces {
+        state_update = state_update.with_contract_nonce(*address, *nonce);
+    }
+    for class in &diff.replaced_classes {
+        state_update = state_update.with_replaced_class(class.contract_address, class.class_hash);
+    }
+
+    state_update
+}
+
+/// Spawns a server which accepts transactions at the gateway's `/gateway/add_transaction`
+/// endpoint, producing a block for each of them.
+pub fn spawn_gateway(
+    devnet: Devnet,
+    addr: SocketAddr,
+) -> anyhow::Result<(tokio::task::JoinHandle<()>, SocketAddr)> {
+    let (addr, server) = warp::serve(gateway_route(devnet))
+        .try_bind_ephemeral(addr)
+        .context("Binding devnet gateway")?;
+
+    Ok((tokio::spawn(server), addr))
+}
+
+fn gateway_route(
+    devnet: Devnet,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path!("gateway" / "add_transaction"))
+        .and(warp::body::bytes())
+        .then(move |body: bytes::Bytes| {
+            let devnet = devnet.clone();
+            async move {
+                let result = match serde_json::from_slice::<AddTransaction>(&body) {
+                    Ok(transaction) => {
+                        tokio::task::spawn_blocking(move || devnet.add_transaction(transaction))
+                            .await
+                            .context("Joining block production task")
+                            .map_err(Error::Internal)
+                            .and_then(|result| result)
+                    }
+                    Err(e) => Err(rejected(
+                        KnownStarknetErrorCode::MalformedRequest,
+                        e.to_string(),
+                    )),
+                };
+
+                reply(result)
+            }
+        })
+}
+
+/// Replies like the gateway does, so that the gateway client can be used unchanged.
+fn reply(result: Result<Accepted, Error>) -> warp::reply::WithStatus<warp::reply::Json> {
+    use warp::http::StatusCode;
+
+    match result {
+        Ok(accepted) => {
+            let mut body = serde_json::json!({
+                "code": "TRANSACTION_RECEIVED",
+                "transaction_hash": accepted.transaction_hash,
+            });
+            if let Some(class_hash) = accepted.class_hash {
+                body["class_hash"] = serde_json::json!(class_hash);
+            }
+            if let Some(address) = accepted.address {
+                body["address"] = serde_json::json!(address);
+            }
+
+            warp::reply::with_status(warp::reply::json(&body), StatusCode::OK)
+        }
+        Err(Error::Rejected(error)) => {
+            tracing::debug!(%error, "Transaction rejected");
+            warp::reply::with_status(warp::reply::json(&error), StatusCode::BAD_REQUEST)
+        }
+        Err(Error::Internal(error)) => {
+            tracing::error!(error=%format!("{error:#}"), "Block production failed");
+            let error = StarknetError {
+                code: StarknetErrorCode::Unknown("StarknetErrorCode.INTERNAL_ERROR".to_owned()),
+                message: error.to_string(),
+            };
+            warp::reply::with_status(warp::reply::json(&error), StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pathfinder_common::{ContractAddressSalt, ContractNonce, StorageValue, TransactionNonce};
+    use starknet_gateway_test_fixtures::class_definitions::{
+        DUMMY_ACCOUNT, DUMMY_ACCOUNT_CLASS_HASH,
+    };
+    use starknet_gateway_types::request::add_transaction::{Declare, DeployAccount};
+
+    fn devnet() -> (Devnet, Storage) {
+        let storage = Storage::in_memory().unwrap();
+        let chain_id = ChainId(Felt::from_be_slice(b"SN_DEVNET").unwrap());
+        (Devnet::new(storage.clone(), chain_id).unwrap(), storage)
+    }
+
+    /// A `DECLARE` v0 of the dummy account, which does not require an account to exist.
+    fn declare_dummy_account() -> AddTransaction {
+        let mut definition = serde_json::from_slice::<serde_json::Value>(DUMMY_ACCOUNT).unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        serde_json::to_writer(&mut encoder, &definition["program"]).unwrap();
+        let program = base64::encode(encoder.finish().unwrap());
+
+        AddTransaction::Declare(Declare {
+            version: TransactionVersion::ZERO,
+            max_fee: Fee::ZERO,
+            signature: vec![],
+            contract_class: ContractDefinition::Cairo(CairoContractDefinition {
+                program,
+                entry_points_by_type: serde_json::from_value(
+                    definition["entry_points_by_type"].take(),
+                )
+                .unwrap(),
+                abi: Some(definition["abi"].take()),
+            }),
+            sender_address: ContractAddress::ONE,
+            nonce: TransactionNonce::ZERO,
+            compiled_class_hash: None,
+            v3_properties: None,
+        })
+    }
+
+    fn deploy_dummy_account() -> AddTransaction {
+        AddTransaction::DeployAccount(DeployAccount {
+            version: TransactionVersion::ONE,
+            max_fee: Fee::ZERO,
+            signature: vec![],
+            nonce: TransactionNonce::ZERO,
+            class_hash: DUMMY_ACCOUNT_CLASS_HASH,
+            contract_address_salt: ContractAddressSalt(Felt::from_u64(1)),
+            constructor_calldata: vec![],
+            v3_properties: None,
+        })
+    }
+
+    #[test]
+    fn produces_a_block_per_transaction() {
+        let (devnet, storage) = devnet();
+
+        let declared = devnet.add_transaction(declare_dummy_account()).unwrap();
+        assert_eq!(declared.block_number, BlockNumber::GENESIS);
+        assert_eq!(declared.class_hash, Some(DUMMY_ACCOUNT_CLASS_HASH));
+
+        let deployed = devnet.add_transaction(deploy_dummy_account()).unwrap();
+        assert_eq!(deployed.block_number, BlockNumber::new_or_panic(1));
+        let address = deployed.address.unwrap();
+
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let genesis = tx
+            .block_header(BlockNumber::GENESIS.into())
+            .unwrap()
+            .unwrap();
+        let latest = tx.block_header(BlockId::Latest).unwrap().unwrap();
+        assert_eq!(latest.number, deployed.block_number);
+        assert_eq!(latest.parent_hash, genesis.hash);
+        assert_eq!(latest.transaction_count, 1);
+
+        let state_update = tx
+            .state_update(BlockNumber::GENESIS.into())
+            .unwrap()
+            .unwrap();
+        assert!(state_update
+            .declared_cairo_classes
+            .contains(&DUMMY_ACCOUNT_CLASS_HASH));
+        assert_eq!(state_update.state_commitment, genesis.state_commitment);
+
+        let class_hash = tx
+            .contract_class_hash(BlockId::Latest, address)
+            .unwrap()
+            .unwrap();
+        assert_eq!(class_hash, DUMMY_ACCOUNT_CLASS_HASH);
+
+        let (transaction, receipt, block_hash) = tx
+            .transaction_with_receipt(deployed.transaction_hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.hash(), deployed.transaction_hash);
+        assert_eq!(
+            receipt.execution_status,
+            gateway::ExecutionStatus::Succeeded
+        );
+        assert_eq!(block_hash, latest.hash);
+    }
+
+    #[test]
+    fn rejected_transactions_produce_no_block() {
+        let (devnet, storage) = devnet();
+
+        // The account class is not declared yet.
+        let error = devnet.add_transaction(deploy_dummy_account()).unwrap_err();
+        assert_matches::assert_matches!(error, Error::Rejected(_));
+
+        devnet.add_transaction(declare_dummy_account()).unwrap();
+        let error = devnet.add_transaction(declare_dummy_account()).unwrap_err();
+        assert_matches::assert_matches!(
+            error,
+            Error::Rejected(e) => assert_eq!(e.code, KnownStarknetErrorCode::ClassAlreadyDeclared.into())
+        );
+
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+        let latest = tx.block_id(BlockId::Latest).unwrap().unwrap();
+        assert_eq!(latest.0, BlockNumber::GENESIS);
+    }
+
+    #[test]
+    fn fork_reads_remote_state() {
+        use pathfinder_common::macro_prelude::*;
+        use starknet_gateway_client::MockGatewayApi;
+        use starknet_gateway_types::error::SequencerError;
+        use starknet_gateway_types::reply::{Block, Status};
+
+        fn undeclared() -> Result<bytes::Bytes, SequencerError> {
+            Err(SequencerError::StarknetError(StarknetError {
+                code: KnownStarknetErrorCode::UndeclaredClass.into(),
+                message: String::new(),
+            }))
+        }
+
+        // The dummy account class is only declared on the forked network.
+        let mut gateway = MockGatewayApi::new();
+        gateway.expect_block().returning(|_| {
+            Ok(Block {
+                block_hash: block_hash!("0xf0"),
+                block_number: BlockNumber::new_or_panic(20),
+                gas_price: Some(GAS_PRICE),
+                parent_block_hash: block_hash!("0xef"),
+                sequencer_address: None,
+                state_commitment: state_commitment!("0xabc"),
+                status: Status::AcceptedOnL2,
+                timestamp: BlockTimestamp::new_or_panic(1000),
+                transaction_receipts: vec![],
+                transactions: vec![],
+                starknet_version: StarknetVersion::new(0, 12, 2),
+            }
+            .into())
+        });
+        gateway
+            .expect_block_header()
+            .returning(|block| match block {
+                pathfinder_common::BlockId::Number(number) => Ok((number, block_hash!("0x11"))),
+                _ => unreachable!(),
+            });
+        gateway.expect_class_by_hash().returning(|class_hash, _| {
+            match class_hash == DUMMY_ACCOUNT_CLASS_HASH {
+                true => Ok(bytes::Bytes::from_static(DUMMY_ACCOUNT)),
+                false => undeclared(),
+            }
+        });
+        gateway.expect_casm_by_hash().returning(|_, _| undeclared());
+        gateway.expect_class_hash_at().returning(|_, _| {
+            Err(SequencerError::StarknetError(StarknetError {
+                code: KnownStarknetErrorCode::UninitializedContract.into(),
+                message: String::new(),
+            }))
+        });
+        gateway
+            .expect_nonce()
+            .returning(|_, _| Ok(ContractNonce::ZERO));
+        gateway
+            .expect_storage_at()
+            .returning(|_, _, _| Ok(StorageValue::ZERO));
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let fork = runtime
+            .block_on(Fork::new(gateway, block_hash!("0xf0")))
+            .unwrap();
+
+        let storage = Storage::in_memory().unwrap();
+        let chain_id = ChainId(Felt::from_be_slice(b"SN_DEVNET").unwrap());
+        let devnet = Devnet::fork(storage.clone(), chain_id, fork).unwrap();
+
+        let deployed = devnet.add_transaction(deploy_dummy_account()).unwrap();
+        assert_eq!(deployed.block_number, BlockNumber::new_or_panic(21));
+
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+        let latest = tx.block_header(BlockId::Latest).unwrap().unwrap();
+        assert_eq!(latest.number, deployed.block_number);
+        assert_eq!(latest.parent_hash, block_hash!("0xf0"));
+        assert_eq!(latest.state_commitment, state_commitment!("0xabc"));
+
+        let class_hash = tx
+            .contract_class_hash(BlockId::Latest, deployed.address.unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(class_hash, DUMMY_ACCOUNT_CLASS_HASH);
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use pathfinder_common::{
+    BlockHash, BlockHeader, BlockId, BlockNumber, ClassHash, ContractAddress, ContractNonce,
+    StorageAddress, StorageValue,
+};
+use pathfinder_executor::RemoteState;
+use starknet_gateway_client::GatewayApi;
+use starknet_gateway_types::error::{KnownStarknetErrorCode, SequencerError};
+use starknet_gateway_types::reply::{transaction, MaybePendingBlock};
+
+/// A network's block on top of which a [Devnet](super::Devnet) produces its blocks.
+pub struct Fork {
+    pub(super) header: BlockHeader,
+    pub(super) transactions: Vec<(transaction::Transaction, transaction::Receipt)>,
+    pub(super) state: Arc<dyn RemoteState>,
+}
+
+impl Fork {
+    /// Fetches the block to fork from the network's feeder gateway.
+    ///
+    /// Must be called from within a Tokio runtime, which is then used to read the forked state.
+    pub async fn new<G>(gateway: G, block: BlockHash) -> anyhow::Result<Self>
+    where
+        G: GatewayApi + Send + 'static,
+    {
+        let block = match gateway
+            .block(block.into())
+            .await
+            .context("Fetching fork block")?
+        {
+            MaybePendingBlock::Block(block) => block,
+            MaybePendingBlock::Pending(_) => anyhow::bail!("Fork block is still pending"),
+        };
+
+        let transaction_count = block.transactions.len();
+        let event_count = block
+            .transaction_receipts
+            .iter()
+            .map(|r| r.events.len())
+            .sum();
+
+        // The remaining commitments aren't part of the gateway's block, and aren't required to
+        // build on top of it.
+        let header = BlockHeader {
+            hash: block.block_hash,
+            parent_hash: block.parent_block_hash,
+            number: block.block_number,
+            timestamp: block.timestamp,
+            gas_price: block.gas_price()?,
+            sequencer_address: block.sequencer_address()?,
+            starknet_version: block.starknet_version,
+            state_commitment: block.state_commitment,
+            transaction_count,
+            event_count,
+            ..Default::default()
+        };
+        let transactions = block
+            .transactions
+            .into_iter()
+            .zip(block.transaction_receipts)
+            .collect();
+
+        let state = GatewayState {
+            gateway,
+            block: header.number,
+            block_hash: header.hash,
+            runtime: tokio::runtime::Handle::current(),
+            cache: Default::default(),
+        };
+
+        Ok(Self {
+            header,
+            transactions,
+            state: Arc::new(state),
+        })
+    }
+
+    /// Reads the network's state at the fork block.
+    pub fn state(&self) -> Arc<dyn RemoteState> {
+        self.state.clone()
+    }
+}
+
+/// Reads the state at the fork block from the network's feeder gateway.
+///
+/// The forked state never changes, so values are cached once read.
+struct GatewayState<G> {
+    gateway: G,
+    block: BlockNumber,
+    block_hash: BlockHash,
+    runtime: tokio::runtime::Handle,
+    cache: Mutex<Cache>,
+}
+
+#[derive(Default)]
+struct Cache {
+    storage: HashMap<(ContractAddress, StorageAddress), StorageValue>,
+    nonces: HashMap<ContractAddress, ContractNonce>,
+    class_hashes: HashMap<ContractAddress, Option<ClassHash>>,
+}
+
+impl<G: GatewayApi> GatewayState<G> {
+    /// Blocks on the gateway request, mapping the given error codes to [None].
+    fn request<T>(
+        &self,
+        request: impl std::future::Future<Output = Result<T, SequencerError>>,
+        missing: &[KnownStarknetErrorCode],
+    ) -> anyhow::Result<Option<T>> {
+        match self.runtime.block_on(request) {
+            Ok(value) => Ok(Some(value)),
+            Err(SequencerError::StarknetError(e))
+                if missing.iter().any(|code| e.code == (*code).into()) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e).context("Reading forked state"),
+        }
+    }
+}
+
+impl<G: GatewayApi + Send> RemoteState for GatewayState<G> {
+    fn storage_value(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageAddress,
+    ) -> anyhow::Result<StorageValue> {
+        if let Some(value) = self
+            .cache
+            .lock()
+            .unwrap()
+            .storage
+            .get(&(contract_address, key))
+        {
+            return Ok(*value);
+        }
+
+        let value = self
+            .request(
+                self.gateway
+                    .storage_at(contract_address, key, self.block_hash.into()),
+                &[],
+            )?
+            .unwrap_or_default();
+
+        self.cache
+            .lock()
+            .unwrap()
+            .storage
+            .insert((contract_address, key), value);
+        Ok(value)
+    }
+
+    fn contract_nonce(&self, contract_address: ContractAddress) -> anyhow::Result<ContractNonce> {
+        if let Some(nonce) = self.cache.lock().unwrap().nonces.get(&contract_address) {
+            return Ok(*nonce);
+        }
+
+        let nonce = self
+            .request(
+                self.gateway.nonce(contract_address, self.block_hash.into()),
+                &[],
+            )?
+            .unwrap_or_default();
+
+        self.cache
+            .lock()
+            .unwrap()
+            .nonces
+            .insert(contract_address, nonce);
+        Ok(nonce)
+    }
+
+    fn contract_class_hash(
+        &self,
+        contract_address: ContractAddress,
+    ) -> anyhow::Result<Option<ClassHash>> {
+        if let Some(class_hash) = self
+            .cache
+            .lock()
+            .unwrap()
+            .class_hashes
+            .get(&contract_address)
+        {
+            return Ok(*class_hash);
+        }
+
+        let class_hash = self.request(
+            self.gateway
+                .class_hash_at(contract_address, self.block_hash.into()),
+            &[
+                KnownStarknetErrorCode::UninitializedContract,
+                KnownStarknetErrorCode::OutOfRangeContractAddress,
+            ],
+        )?;
+
+        self.cache
+            .lock()
+            .unwrap()
+            .class_hashes
+            .insert(contract_address, class_hash);
+        Ok(class_hash)
+    }
+
+    fn class_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>> {
+        let definition = self.request(
+            self.gateway
+                .class_by_hash(class_hash, self.block_hash.into()),
+            &[KnownStarknetErrorCode::UndeclaredClass],
+        )?;
+
+        Ok(definition.map(Vec::from))
+    }
+
+    fn casm_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>> {
+        // Cairo classes have no CASM definition, and the gateway reports these as undeclared.
+        let definition = self.request(
+            self.gateway
+                .casm_by_hash(class_hash, self.block_hash.into()),
+            &[KnownStarknetErrorCode::UndeclaredClass],
+        )?;
+
+        Ok(definition.map(Vec::from))
+    }
+
+    fn block_hash(&self, block: BlockNumber) -> anyhow::Result<Option<BlockHash>> {
+        if block > self.block {
+            return Ok(None);
+        }
+
+        let header = self.request(
+            self.gateway.block_header(BlockId::Number(block)),
+            &[KnownStarknetErrorCode::BlockNotFound],
+        )?;
+
+        Ok(header.map(|(_, hash)| hash))
+    }
+}
@@ -0,0 +1,424 @@
+//! Builds transaction receipts from the execution of transactions.
+//!
+//! Blocks synced from peers carry receipts which the peers took from the feeder gateway. The
+//! receipts of [executable](is_executable) blocks are instead [computed](execute) by executing the
+//! block's transactions on the state of its parent, and [verified](verify) against the received
+//! ones.
+
+use anyhow::Context;
+use pathfinder_common::{
+    BlockHeader, ChainId, EthereumAddress, EventData, EventKey, Fee, L1ToL2MessageNonce,
+    L1ToL2MessagePayloadElem, L2ToL1MessagePayloadElem, StarknetVersion, TransactionHash,
+    TransactionIndex,
+};
+use pathfinder_crypto::Felt;
+use pathfinder_executor::types::{ExecuteInvocation, FunctionInvocation, TransactionTrace};
+use pathfinder_executor::{CallError, ExecutionState};
+use primitive_types::H160;
+use starknet_gateway_types::reply::transaction as gateway;
+
+/// Whether the receipts of a block can be computed by executing it.
+///
+/// Blocks older than [VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY]
+/// can't be re-executed faithfully, and the executor does not support V3 transactions yet.
+///
+/// [VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY]: pathfinder_rpc::VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY
+pub fn is_executable(
+    starknet_version: &StarknetVersion,
+    transactions: &[gateway::Transaction],
+) -> bool {
+    use pathfinder_rpc::VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY as FIRST_EXECUTABLE_VERSION;
+
+    let recent = match starknet_version.parse_as_semver() {
+        Ok(Some(version)) => version >= FIRST_EXECUTABLE_VERSION,
+        Ok(None) | Err(_) => false,
+    };
+
+    recent
+        && !transactions.iter().any(|tx| {
+            matches!(
+                tx,
+                gateway::Transaction::Declare(gateway::DeclareTransaction::V3(_))
+                    | gateway::Transaction::DeployAccount(gateway::DeployAccountTransaction::V3(_))
+                    | gateway::Transaction::Invoke(gateway::InvokeTransaction::V3(_))
+            )
+        })
+}
+
+/// Executes the transactions of the block with `header` on the state of its parent, and returns
+/// their receipts.
+///
+/// The block's declared classes must already be stored.
+pub fn execute(
+    db: &pathfinder_storage::Transaction<'_>,
+    chain_id: ChainId,
+    header: BlockHeader,
+    transactions: &[gateway::Transaction],
+) -> anyhow::Result<Vec<gateway::Receipt>> {
+    let executor_transactions = transactions
+        .iter()
+        .map(|tx| pathfinder_rpc::compose_executor_transaction(tx, db))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context("Converting transactions")?;
+
+    let state = ExecutionState::trace(db, chain_id, header, None);
+    let executions =
+        pathfinder_executor::execute_block(state, executor_transactions).map_err(|e| match e {
+            CallError::Internal(e) | CallError::Custom(e) => e,
+            CallError::Reverted(reason) => anyhow::anyhow!("Transaction reverted: {reason}"),
+            other => anyhow::anyhow!("Executing transactions: {other:?}"),
+        })?;
+
+    transactions
+        .iter()
+        .zip(executions)
+        .enumerate()
+        .map(|(idx, (transaction, execution))| {
+            anyhow::ensure!(
+                transaction.hash() == execution.transaction_hash,
+                "Executed transaction {} instead of {}",
+                execution.transaction_hash,
+                transaction.hash()
+            );
+
+            let mut receipt = from_trace(
+                execution.transaction_hash,
+                TransactionIndex::new_or_panic(idx as u64),
+                &execution.trace,
+                Fee(Felt::from_u128(execution.actual_fee)),
+            );
+            receipt.execution_resources = Some(execution_resources(
+                execution.steps,
+                execution.memory_holes,
+                &execution.builtins,
+            ));
+            if let gateway::Transaction::L1Handler(tx) = transaction {
+                receipt.l1_to_l2_consumed_message = Some(consumed_message(tx)?);
+            }
+
+            Ok(receipt)
+        })
+        .collect()
+}
+
+/// Verifies the `executed` receipts of a block against the `received` ones, and returns the
+/// receipts to store.
+///
+/// Peers only provide the events of executable blocks, so their receipts lack the fee and the
+/// rest of the execution data. These are replaced by the executed receipts. Complete received
+/// receipts are kept, once their fee, messages and execution status match the execution's.
+pub fn verify(
+    received: &[gateway::Receipt],
+    executed: Vec<gateway::Receipt>,
+) -> anyhow::Result<Vec<gateway::Receipt>> {
+    anyhow::ensure!(
+        received.len() == executed.len(),
+        "Executed {} receipts instead of {}",
+        executed.len(),
+        received.len()
+    );
+
+    received
+        .iter()
+        .zip(executed)
+        .map(|(received, executed)| {
+            if received.actual_fee.is_none() {
+                return Ok(executed);
+            }
+
+            let hash = received.transaction_hash;
+            anyhow::ensure!(
+                received.actual_fee == executed.actual_fee,
+                "Fee mismatch for transaction {hash}: executed {:?}, received {:?}",
+                executed.actual_fee,
+                received.actual_fee
+            );
+            anyhow::ensure!(
+                received.execution_status == executed.execution_status,
+                "Execution status mismatch for transaction {hash}: executed {:?}, received {:?}",
+                executed.execution_status,
+                received.execution_status
+            );
+            anyhow::ensure!(
+                received.l2_to_l1_messages == executed.l2_to_l1_messages,
+                "Messages to L1 mismatch for transaction {hash}"
+            );
+            anyhow::ensure!(
+                received.l1_to_l2_consumed_message == executed.l1_to_l2_consumed_message,
+                "Consumed L1 message mismatch for transaction {hash}"
+            );
+
+            Ok(received.clone())
+        })
+        .collect()
+}
+
+/// The receipt of an executed transaction, without its execution resources.
+pub fn from_trace(
+    transaction_hash: TransactionHash,
+    transaction_index: TransactionIndex,
+    trace: &TransactionTrace,
+    actual_fee: Fee,
+) -> gateway::Receipt {
+    let mut events = Vec::new();
+    let mut messages = Vec::new();
+    for invocation in invocations(trace) {
+        // Events and messages are ordered within each top-level invocation.
+        let mut invocation_events = Vec::new();
+        let mut invocation_messages = Vec::new();
+        collect_outputs(invocation, &mut invocation_events, &mut invocation_messages);
+        invocation_events.sort_by_key(|(order, _)| *order);
+        invocation_messages.sort_by_key(|(order, _)| *order);
+
+        events.extend(invocation_events.into_iter().map(|(_, event)| event));
+        messages.extend(invocation_messages.into_iter().map(|(_, message)| message));
+    }
+
+    let revert_error = match trace {
+        TransactionTrace::Invoke(trace) => match &trace.execute_invocation {
+            ExecuteInvocation::RevertedReason(reason) => Some(reason.clone()),
+            ExecuteInvocation::FunctionInvocation(_) => None,
+        },
+        _ => None,
+    };
+    let execution_status = match revert_error {
+        Some(_) => gateway::ExecutionStatus::Reverted,
+        None => gateway::ExecutionStatus::Succeeded,
+    };
+
+    gateway::Receipt {
+        actual_fee: Some(actual_fee),
+        events,
+        execution_resources: None,
+        l1_to_l2_consumed_message: None,
+        l2_to_l1_messages: messages,
+        transaction_hash,
+        transaction_index,
+        execution_status,
+        revert_error,
+    }
+}
+
+/// The invocations of the transaction in execution order.
+fn invocations(trace: &TransactionTrace) -> Vec<&FunctionInvocation> {
+    let invocations = match trace {
+        TransactionTrace::Declare(trace) => vec![
+            trace.validate_invocation.as_ref(),
+            trace.fee_transfer_invocation.as_ref(),
+        ],
+        TransactionTrace::DeployAccount(trace) => vec![
+            trace.constructor_invocation.as_ref(),
+            trace.validate_invocation.as_ref(),
+            trace.fee_transfer_invocation.as_ref(),
+        ],
+        TransactionTrace::Invoke(trace) => vec![
+            trace.validate_invocation.as_ref(),
+            match &trace.execute_invocation {
+                ExecuteInvocation::FunctionInvocation(invocation) => invocation.as_ref(),
+                ExecuteInvocation::RevertedReason(_) => None,
+            },
+            trace.fee_transfer_invocation.as_ref(),
+        ],
+        TransactionTrace::L1Handler(trace) => vec![trace.function_invocation.as_ref()],
+    };
+
+    invocations.into_iter().flatten().collect()
+}
+
+fn collect_outputs(
+    invocation: &FunctionInvocation,
+    events: &mut Vec<(i64, pathfinder_common::event::Event)>,
+    messages: &mut Vec<(usize, gateway::L2ToL1Message)>,
+) {
+    events.extend(invocation.events.iter().map(|event| {
+        (
+            event.order,
+            pathfinder_common::event::Event {
+                data: event.data.iter().copied().map(EventData).collect(),
+                from_address: invocation.contract_address,
+                keys: event.keys.iter().copied().map(EventKey).collect(),
+            },
+        )
+    }));
+    messages.extend(invocation.messages.iter().map(|message| {
+        (
+            message.order,
+            gateway::L2ToL1Message {
+                from_address: invocation.contract_address,
+                payload: message
+                    .payload
+                    .iter()
+                    .copied()
+                    .map(L2ToL1MessagePayloadElem)
+                    .collect(),
+                to_address: EthereumAddress(H160::from_slice(
+                    &message.to_address.as_be_bytes()[12..],
+                )),
+            },
+        )
+    }));
+
+    for call in &invocation.internal_calls {
+        collect_outputs(call, events, messages);
+    }
+}
+
+/// The execution steps include the memory holes, which are reported separately.
+fn execution_resources(
+    steps: usize,
+    memory_holes: usize,
+    builtins: &std::collections::BTreeMap<String, usize>,
+) -> gateway::ExecutionResources {
+    let builtin = |name: &str| builtins.get(name).copied().unwrap_or_default() as u64;
+
+    gateway::ExecutionResources {
+        builtin_instance_counter: gateway::BuiltinCounters {
+            output_builtin: builtin("output_builtin"),
+            pedersen_builtin: builtin("pedersen_builtin"),
+            range_check_builtin: builtin("range_check_builtin"),
+            ecdsa_builtin: builtin("ecdsa_builtin"),
+            bitwise_builtin: builtin("bitwise_builtin"),
+            ec_op_builtin: builtin("ec_op_builtin"),
+            keccak_builtin: builtin("keccak_builtin"),
+            poseidon_builtin: builtin("poseidon_builtin"),
+            segment_arena_builtin: builtin("segment_arena_builtin"),
+        },
+        n_steps: steps.saturating_sub(memory_holes) as u64,
+        n_memory_holes: memory_holes as u64,
+    }
+}
+
+/// The message from L1 which an L1 handler transaction consumes. The sender is the first element
+/// of the calldata, followed by the message's payload.
+fn consumed_message(tx: &gateway::L1HandlerTransaction) -> anyhow::Result<gateway::L1ToL2Message> {
+    let (from_address, payload) = tx
+        .calldata
+        .split_first()
+        .context("L1 handler transaction without sender")?;
+
+    Ok(gateway::L1ToL2Message {
+        from_address: EthereumAddress(H160::from_slice(&from_address.0.as_be_bytes()[12..])),
+        payload: payload
+            .iter()
+            .map(|elem| L1ToL2MessagePayloadElem(elem.0))
+            .collect(),
+        selector: tx.entry_point_selector,
+        to_address: tx.contract_address,
+        nonce: Some(L1ToL2MessageNonce(tx.nonce.0)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fake::{Fake, Faker};
+
+    #[test]
+    fn executable_from_first_version_with_traces() {
+        let v1 = gateway::Transaction::Invoke(gateway::InvokeTransaction::V1(Faker.fake()));
+
+        assert!(is_executable(
+            &StarknetVersion::new(0, 12, 3),
+            &[v1.clone()]
+        ));
+        assert!(is_executable(
+            &StarknetVersion::new(0, 13, 0),
+            &[v1.clone()]
+        ));
+        assert!(!is_executable(&StarknetVersion::new(0, 12, 2), &[v1]));
+        assert!(!is_executable(&StarknetVersion::default(), &[]));
+    }
+
+    #[test]
+    fn not_executable_with_v3_transactions() {
+        let v1 = gateway::Transaction::Invoke(gateway::InvokeTransaction::V1(Faker.fake()));
+        let invoke_v3 = gateway::Transaction::Invoke(gateway::InvokeTransaction::V3(Faker.fake()));
+        let declare_v3 =
+            gateway::Transaction::Declare(gateway::DeclareTransaction::V3(Faker.fake()));
+        let version = StarknetVersion::new(0, 13, 0);
+
+        assert!(!is_executable(&version, &[v1.clone(), invoke_v3]));
+        assert!(!is_executable(&version, &[declare_v3, v1]));
+    }
+
+    #[test]
+    fn memory_holes_are_reported_separately() {
+        let resources = execution_resources(
+            10,
+            2,
+            &std::collections::BTreeMap::from([("pedersen_builtin".to_owned(), 3)]),
+        );
+
+        assert_eq!(resources.n_steps, 8);
+        assert_eq!(resources.n_memory_holes, 2);
+        assert_eq!(resources.builtin_instance_counter.pedersen_builtin, 3);
+    }
+
+    mod verify {
+        use super::*;
+
+        fn receipt() -> gateway::Receipt {
+            gateway::Receipt {
+                actual_fee: Some(Fee(Felt::from_u64(100))),
+                events: Vec::new(),
+                execution_resources: Some(execution_resources(10, 2, &Default::default())),
+                l1_to_l2_consumed_message: None,
+                l2_to_l1_messages: vec![Faker.fake()],
+                transaction_hash: TransactionHash(Felt::from_u64(1)),
+                transaction_index: TransactionIndex::new_or_panic(0),
+                execution_status: gateway::ExecutionStatus::Succeeded,
+                revert_error: None,
+            }
+        }
+
+        #[test]
+        fn missing_receipts_are_executed() {
+            let received = gateway::Receipt {
+                actual_fee: None,
+                execution_resources: None,
+                l2_to_l1_messages: Vec::new(),
+                ..receipt()
+            };
+            let executed = receipt();
+
+            let receipts = verify(&[received], vec![executed.clone()]).unwrap();
+            assert_eq!(receipts, vec![executed]);
+        }
+
+        #[test]
+        fn matching_receipts_are_kept() {
+            let received = receipt();
+            let executed = gateway::Receipt {
+                execution_resources: Some(execution_resources(12, 0, &Default::default())),
+                ..received.clone()
+            };
+
+            let receipts = verify(&[received.clone()], vec![executed]).unwrap();
+            assert_eq!(receipts, vec![received]);
+        }
+
+        #[test]
+        fn mismatches_are_rejected() {
+            let received = receipt();
+
+            let fee = gateway::Receipt {
+                actual_fee: Some(Fee(Felt::from_u64(99))),
+                ..received.clone()
+            };
+            let status = gateway::Receipt {
+                execution_status: gateway::ExecutionStatus::Reverted,
+                revert_error: Some("reverted".to_owned()),
+                ..received.clone()
+            };
+            let messages = gateway::Receipt {
+                l2_to_l1_messages: Vec::new(),
+                ..received.clone()
+            };
+
+            for executed in [fee, status, messages] {
+                verify(&[received.clone()], vec![executed]).unwrap_err();
+            }
+            verify(&[received], Vec::new()).unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Rebuilds the state tries from the stored state updates.
+//!
+//! This can be used to repair corrupted trie data, or to verify changes to the trie
+//! implementation against an existing database.
+
+use anyhow::Context;
+use pathfinder_common::{BlockNumber, StateCommitment};
+use pathfinder_storage::{Storage, TransactionBehavior};
+
+use super::sync::update_starknet_state;
+
+/// Re-applies the stored state updates of blocks `from..=to`, verifying the resulting state
+/// commitment of each block against its stored header.
+///
+/// The tries of the block preceding `from` are used as the starting point and are therefore
+/// trusted. Each block is committed once verified and replay stops at the first mismatch,
+/// leaving that block untouched.
+pub fn replay(
+    storage: Storage,
+    from: BlockNumber,
+    to: BlockNumber,
+    verify_hashes: bool,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(from <= to, "Invalid range: {from} is after {to}");
+
+    let mut connection = storage
+        .connection()
+        .context("Creating database connection")?;
+
+    let mut number = from;
+    while number <= to {
+        let span = tracing::info_span!("replay", block = %number);
+        let _enter = span.enter();
+
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .context("Create database transaction")?;
+
+        let header = transaction
+            .block_header(number.into())
+            .context("Fetching block header")?
+            .with_context(|| format!("Block {number} is missing"))?;
+        let state_update = transaction
+            .state_update(number.into())
+            .context("Fetching state update")?
+            .with_context(|| format!("State update for block {number} is missing"))?;
+
+        transaction
+            .purge_trie_roots(number)
+            .context("Purging trie roots")?;
+
+        let (storage_commitment, class_commitment) = update_starknet_state(
+            &transaction,
+            &state_update,
+            verify_hashes,
+            number,
+            storage.clone(),
+        )
+        .context("Updating Starknet state")?;
+        let state_commitment = StateCommitment::calculate(storage_commitment, class_commitment);
+
+        anyhow::ensure!(
+            state_commitment == header.state_commitment,
+            "State commitment mismatch at block {number}: expected {}, replay resulted in {}",
+            header.state_commitment,
+            state_commitment
+        );
+
+        transaction
+            .commit()
+            .context("Committing database transaction")?;
+
+        tracing::debug!("Block replayed");
+
+        number += 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::{BlockHash, BlockHeader, StateUpdate};
+
+    fn state_updates() -> Vec<StateUpdate> {
+        let contract = contract_address!("0x100");
+        let class = class_hash!("0x200");
+
+        vec![
+            StateUpdate::default()
+                .with_deployed_contract(contract, class)
+                .with_storage_update(contract, storage_address!("0x1"), storage_value!("0x11")),
+            StateUpdate::default().with_storage_update(
+                contract,
+                storage_address!("0x2"),
+                storage_value!("0x22"),
+            ),
+            StateUpdate::default().with_contract_nonce(contract, contract_nonce!("0x1")),
+        ]
+    }
+
+    /// Creates a database with the block headers and state updates but without any tries,
+    /// using the state commitments produced by `update_starknet_state` in a separate database.
+    ///
+    /// The state commitment of the `corrupt` block's header is replaced with garbage.
+    fn setup(corrupt: Option<BlockNumber>) -> Storage {
+        let reference = Storage::in_memory().unwrap();
+        let storage = Storage::in_memory().unwrap();
+
+        let mut reference_connection = reference.connection().unwrap();
+        let mut connection = storage.connection().unwrap();
+
+        let mut parent_hash = BlockHash::ZERO;
+        for (i, state_update) in state_updates().into_iter().enumerate() {
+            let number = BlockNumber::new_or_panic(i as u64);
+            let hash = BlockHash(pathfinder_crypto::Felt::from_u64(i as u64 + 1));
+
+            let state_commitment = {
+                let tx = reference_connection.transaction().unwrap();
+                let header = BlockHeader::builder()
+                    .with_number(number)
+                    .with_parent_hash(parent_hash)
+                    .finalize_with_hash(hash);
+                tx.insert_cairo_class(class_hash!("0x200"), &[]).unwrap();
+                tx.insert_block_header(&header).unwrap();
+                tx.insert_state_update(number, &state_update).unwrap();
+                let (storage_commitment, class_commitment) =
+                    update_starknet_state(&tx, &state_update, true, number, reference.clone())
+                        .unwrap();
+                tx.commit().unwrap();
+
+                StateCommitment::calculate(storage_commitment, class_commitment)
+            };
+
+            let state_commitment = match corrupt {
+                Some(corrupt) if corrupt == number => state_commitment!("0xdead"),
+                _ => state_commitment,
+            };
+
+            let tx = connection.transaction().unwrap();
+            let header = BlockHeader::builder()
+                .with_number(number)
+                .with_parent_hash(parent_hash)
+                .with_state_commitment(state_commitment)
+                .finalize_with_hash(hash);
+            tx.insert_cairo_class(class_hash!("0x200"), &[]).unwrap();
+            tx.insert_block_header(&header).unwrap();
+            tx.insert_state_update(number, &state_update).unwrap();
+            tx.commit().unwrap();
+
+            parent_hash = hash;
+        }
+
+        storage
+    }
+
+    #[test]
+    fn rebuilds_tries() {
+        let storage = setup(None);
+
+        replay(
+            storage.clone(),
+            BlockNumber::GENESIS,
+            BlockNumber::new_or_panic(2),
+            true,
+        )
+        .unwrap();
+
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+        let root = tx.storage_root_index(BlockNumber::new_or_panic(2)).unwrap();
+        assert!(root.is_some());
+    }
+
+    #[test]
+    fn replaying_again_is_idempotent() {
+        let storage = setup(None);
+        let to = BlockNumber::new_or_panic(2);
+
+        replay(storage.clone(), BlockNumber::GENESIS, to, true).unwrap();
+        replay(storage.clone(), BlockNumber::new_or_panic(1), to, true).unwrap();
+    }
+
+    #[test]
+    fn mismatch_is_an_error() {
+        let storage = setup(Some(BlockNumber::new_or_panic(1)));
+
+        let error = replay(
+            storage.clone(),
+            BlockNumber::GENESIS,
+            BlockNumber::new_or_panic(2),
+            true,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("block 1"), "{error}");
+
+        // The blocks before the mismatch are committed, the rest untouched.
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+        let root = tx.storage_root_index(BlockNumber::GENESIS).unwrap();
+        assert!(root.is_some());
+        let root = tx.storage_root_index(BlockNumber::new_or_panic(1)).unwrap();
+        assert_eq!(root, tx.storage_root_index(BlockNumber::GENESIS).unwrap());
+    }
+}
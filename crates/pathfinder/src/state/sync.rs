@@ -1,43 +1,67 @@
 mod class;
+mod da;
+mod error;
+mod events;
 pub mod l1;
 pub mod l2;
 mod pending;
+mod progress;
+mod restart;
 
 use anyhow::Context;
 use pathfinder_common::{
     BlockCommitmentSignature, BlockHash, BlockHeader, BlockNumber, CasmHash, Chain, ChainId,
-    ClassCommitment, ClassHash, EventCommitment, GasPrice, SequencerAddress, SierraHash,
-    StateCommitment, StateUpdate, StorageCommitment, TransactionCommitment,
+    ClassCommitment, ClassHash, EventCommitment, SierraHash, StateCommitment, StateUpdate,
+    StorageCommitment, TransactionCommitment, TransactionHash,
 };
 use pathfinder_crypto::Felt;
+use pathfinder_ethereum::blob::BlobReader;
 use pathfinder_ethereum::{EthereumApi, EthereumStateUpdate};
 use pathfinder_merkle_tree::contract_state::update_contract_state;
 use pathfinder_merkle_tree::{ClassCommitmentTree, StorageCommitmentTree};
 use pathfinder_rpc::PendingData;
 use pathfinder_rpc::{
+    response_cache::ResponseCache,
     v02::types::syncing::{self, NumberedBlock, Syncing},
     SyncState, TopicBroadcasters,
 };
 use pathfinder_storage::{Connection, Storage, Transaction, TransactionBehavior};
 use primitive_types::H160;
 use starknet_gateway_client::{GatewayApi, GossipApi};
+use starknet_gateway_types::reply::transaction::Receipt;
 use starknet_gateway_types::reply::Block;
 use starknet_gateway_types::reply::PendingBlock;
 
 use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
-use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::mpsc;
+use tracing::Instrument;
 
+pub use error::SyncError;
+pub use events::OverflowStrategy;
+
+/// The number of latest blocks whose sync timings are kept, see
+/// [Transaction::insert_block_sync_timings].
+pub const SYNC_TIMINGS_CAPACITY: u64 = 1000;
+
+use crate::state::block_hash::calculate_event_commitment;
 use crate::state::l1::L1SyncContext;
 use crate::state::l2::{BlockChain, L2SyncContext};
+use events::EventQueue;
+use restart::RestartPolicy;
 
 use tokio::sync::watch::Sender as WatchSender;
 
 #[derive(Debug)]
 pub enum SyncEvent {
     L1Update(EthereumStateUpdate),
+    /// An L1 reorg replaced the L1 blocks from this number onwards, invalidating the
+    /// [updates](SyncEvent::L1Update) read at them.
+    L1Reorg(u64),
     /// New L2 [block update](StateUpdate) found.
     Block(
         (Box<Block>, (TransactionCommitment, EventCommitment)),
@@ -63,6 +87,8 @@ pub enum SyncEvent {
     },
     /// A new L2 pending update was polled.
     Pending(Box<(PendingBlock, StateUpdate)>),
+    /// The pending block of the preceding [pending](SyncEvent::Pending) event was extended.
+    PendingDelta(Box<pending::PendingDelta>),
 }
 
 pub struct SyncContext<G, E> {
@@ -77,10 +103,53 @@ pub struct SyncContext<G, E> {
     pub pending_data: WatchSender<Arc<PendingData>>,
     pub pending_poll_interval: Option<Duration>,
     pub block_validation_mode: l2::BlockValidationMode,
+    pub transaction_validation_mode: l2::TransactionValidationMode,
     pub websocket_txs: Option<TopicBroadcasters>,
+    /// RPC responses, which are invalidated by reorgs.
+    pub response_cache: ResponseCache,
     pub block_cache_size: usize,
+    /// The delay before the first restart of a failed L2 sync task, which backs off with further
+    /// consecutive failures.
     pub restart_delay: Duration,
+    /// The number of consecutive L2 sync failures after which it is no longer restarted.
+    pub max_restarts: NonZeroUsize,
+    /// Cleared once L2 sync is no longer restarted, which marks the node unhealthy.
+    pub healthy: Arc<AtomicBool>,
     pub verify_tree_hashes: bool,
+    /// Verifies the state diffs posted to L1 against the local state if set.
+    pub blob_reader: Option<BlobReader>,
+    /// New L1 blocks, which trigger L1 sync instead of polling if set.
+    pub l1_new_heads: Option<tokio::sync::watch::Receiver<Option<u64>>>,
+    /// The number of events the producers can queue up for the consumer.
+    pub event_capacity: usize,
+    pub event_overflow: OverflowStrategy,
+    /// Computes the receipts of blocks by executing them instead of storing the received ones,
+    /// which for blocks synced from peers originate from the gateway.
+    pub receipts_from_execution: bool,
+}
+
+/// How often the chain head and the pending block are polled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollIntervals {
+    pub head: Duration,
+    pub pending: Duration,
+}
+
+impl PollIntervals {
+    /// The default intervals of a chain. Test networks are polled less often to save gateway
+    /// requests.
+    pub fn default_for(chain: Chain) -> Self {
+        match chain {
+            Chain::Mainnet | Chain::Custom => Self {
+                head: Duration::from_secs(5),
+                pending: Duration::from_secs(2),
+            },
+            Chain::Testnet | Chain::Testnet2 | Chain::Integration => Self {
+                head: Duration::from_secs(10),
+                pending: Duration::from_secs(5),
+            },
+        }
+    }
 }
 
 impl<G, E> From<&SyncContext<G, E>> for L1SyncContext<E>
@@ -93,6 +162,7 @@ where
             chain: value.chain,
             core_address: value.core_address,
             poll_interval: value.head_poll_interval,
+            new_heads: value.l1_new_heads.clone(),
         }
     }
 }
@@ -110,6 +180,7 @@ where
             head_poll_interval: value.head_poll_interval,
             pending_poll_interval: value.pending_poll_interval,
             block_validation_mode: value.block_validation_mode,
+            transaction_validation_mode: value.transaction_validation_mode,
             storage: value.storage.clone(),
         }
     }
@@ -125,8 +196,8 @@ pub async fn sync<Ethereum, SequencerClient, F1, F2, L1Sync, L2Sync>(
 where
     Ethereum: EthereumApi + Clone + Send + 'static,
     SequencerClient: GatewayApi + GossipApi + Clone + Send + Sync + 'static,
-    F1: Future<Output = anyhow::Result<()>> + Send + 'static,
-    F2: Future<Output = anyhow::Result<()>> + Send + 'static,
+    F1: Future<Output = Result<(), SyncError>> + Send + 'static,
+    F2: Future<Output = Result<(), SyncError>> + Send + 'static,
     L1Sync: FnMut(mpsc::Sender<SyncEvent>, L1SyncContext<Ethereum>) -> F1,
     L2Sync: FnOnce(
             mpsc::Sender<SyncEvent>,
@@ -151,18 +222,26 @@ where
         pending_data,
         pending_poll_interval: _,
         block_validation_mode: _,
+        transaction_validation_mode: _,
         websocket_txs: _,
+        response_cache: _,
         block_cache_size,
         restart_delay,
+        max_restarts,
+        healthy,
         verify_tree_hashes: _,
+        blob_reader: _,
+        l1_new_heads: _,
+        event_capacity,
+        event_overflow,
+        receipts_from_execution: _,
     } = context;
 
     let mut db_conn = storage
         .connection()
         .context("Creating database connection")?;
 
-    // TODO: consider increasing the capacity.
-    let (event_sender, event_receiver) = mpsc::channel(2);
+    let (event_sender, event_receiver) = EventQueue::new(event_capacity, event_overflow);
 
     let l2_head = tokio::task::block_in_place(|| -> anyhow::Result<_> {
         let tx = db_conn.transaction()?;
@@ -206,12 +285,19 @@ where
         l2_head,
         block_chain,
     ));
+    let mut l2_restarts = RestartPolicy::new(restart_delay, max_restarts);
+    // The L2 head the task was last (re)started at.
+    let mut l2_started_at = l2_head;
 
     let consumer_context = ConsumerContext {
         storage,
         state,
         pending_data,
         verify_tree_hashes: context.verify_tree_hashes,
+        websocket_txs: context.websocket_txs,
+        response_cache: context.response_cache,
+        blob_reader: context.blob_reader,
+        receipts_from_execution: context.receipts_from_execution.then_some(context.chain_id),
     };
     let mut consumer_handle = tokio::spawn(consumer(event_receiver, consumer_context));
 
@@ -241,14 +327,26 @@ where
                 });
             },
             l2_producer_result = &mut l2_handle => {
-                // L2 sync process failed; restart it.
+                // L2 sync process failed; restart it unless retrying cannot help.
                 match l2_producer_result.context("Join L2 sync process handle")? {
                     Ok(()) => {
                         tracing::error!("L2 sync process terminated without an error.");
                     }
-                    Err(e) => {
+                    Err(e) if e.is_retryable() => {
                         tracing::warn!("L2 sync process terminated with: {e:?}");
                     }
+                    Err(e) => {
+                        tracing::error!(reason=?e, "L2 sync process terminated with a non-retryable error");
+
+                        tracing::debug!("Shutting down L1 sync producer task");
+                        l1_handle.abort();
+                        let _ = l1_handle.await;
+                        // Let the consumer process the events queued before the failure.
+                        drop(event_sender);
+                        let _ = consumer_handle.await;
+
+                        return Err(anyhow::Error::from(e).context("L2 sync process failed"));
+                    }
                 }
 
                 let l2_head = tokio::task::block_in_place(|| {
@@ -258,15 +356,34 @@ where
                 .context("Query L2 head from database")?
                 .map(|block| (block.number, block.hash, block.state_commitment));
 
-                let latest_blocks = latest_n_blocks(&mut db_conn, block_cache_size).await.context("Fetching latest blocks from storage")?;
-                let block_chain = BlockChain::with_capacity(1_000, latest_blocks);
-                let fut = l2_sync(event_sender.clone(), l2_context.clone(), l2_head, block_chain);
+                // Failures are only consecutive if the task made no progress in between.
+                if l2_head != l2_started_at {
+                    l2_restarts.reset();
+                    l2_started_at = l2_head;
+                }
 
-                l2_handle = tokio::spawn(async move {
-                    tokio::time::sleep(restart_delay).await;
-                    fut.await
-                });
-                tracing::info!("L2 sync process restarted.");
+                match l2_restarts.failed() {
+                    Some(delay) => {
+                        let latest_blocks = latest_n_blocks(&mut db_conn, block_cache_size).await.context("Fetching latest blocks from storage")?;
+                        let block_chain = BlockChain::with_capacity(1_000, latest_blocks);
+                        let fut = l2_sync(event_sender.clone(), l2_context.clone(), l2_head, block_chain);
+
+                        l2_handle = tokio::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            fut.await
+                        });
+                        tracing::info!(?delay, "L2 sync process restarted.");
+                    }
+                    None => {
+                        tracing::error!(
+                            failures=%max_restarts,
+                            "L2 sync process failed repeatedly and is no longer restarted, restart the node to resume syncing"
+                        );
+                        healthy.store(false, Ordering::Relaxed);
+                        // Keep L1 sync and the consumer running, with an L2 handle which never completes.
+                        l2_handle = tokio::spawn(std::future::pending());
+                    }
+                }
             },
             consumer_result = &mut consumer_handle => {
                 match consumer_result {
@@ -330,20 +447,32 @@ struct ConsumerContext {
     pub state: Arc<SyncState>,
     pub pending_data: WatchSender<Arc<PendingData>>,
     pub verify_tree_hashes: bool,
+    pub websocket_txs: Option<TopicBroadcasters>,
+    pub response_cache: ResponseCache,
+    pub blob_reader: Option<BlobReader>,
+    /// The chain on which blocks are executed to compute their receipts, if set.
+    pub receipts_from_execution: Option<ChainId>,
 }
 
-async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) -> anyhow::Result<()> {
+async fn consumer(mut events: EventQueue, context: ConsumerContext) -> anyhow::Result<()> {
     let ConsumerContext {
         storage,
         state,
         pending_data,
         verify_tree_hashes,
+        websocket_txs,
+        response_cache,
+        blob_reader,
+        receipts_from_execution,
     } = context;
 
     let mut last_block_start = std::time::Instant::now();
     let mut block_time_avg = std::time::Duration::ZERO;
     const BLOCK_TIME_WEIGHT: f32 = 0.05;
 
+    const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_secs(30);
+    let mut progress = progress::ProgressReporter::new(PROGRESS_REPORT_INTERVAL);
+
     let mut db_conn = storage
         .connection()
         .context("Creating database connection")?;
@@ -362,12 +491,37 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
     })
     .context("Fetching latest block time")?;
 
+    // The latest pending block and state update, which pending deltas are applied to.
+    let mut current_pending: Option<(PendingBlock, StateUpdate)> = None;
+
     while let Some(event) = events.recv().await {
         use SyncEvent::*;
         match event {
             L1Update(update) => {
-                l1_update(&mut db_conn, &update).await?;
+                let l1_head = l1_update(&mut db_conn, &update).await?;
                 tracing::info!("L1 sync updated to block {}", update.block_number);
+
+                if let (Some(l1_head), Some(topics)) = (l1_head, &websocket_txs) {
+                    topics
+                        .l1_head
+                        .send_if_receiving(l1_head)
+                        .context("Broadcasting failed")?;
+                }
+
+                if let (Some(_), Some(reader)) = (l1_head, &blob_reader) {
+                    let block = update.block_number;
+                    let verification = da::verify(storage.clone(), reader.clone(), update);
+                    tokio::spawn(async move {
+                        if let Err(error) = verification.await {
+                            tracing::warn!(%block, %error, "Verifying L1 state diff failed");
+                        }
+                    });
+                }
+            }
+            L1Reorg(l1_block_number) => {
+                l1_reorg(&mut db_conn, l1_block_number).await?;
+                response_cache.invalidate_l1_accepted();
+                metrics::increment_counter!("l1_reorg_total");
             }
             Block((block, (tx_comm, ev_comm)), state_update, signature, timings) => {
                 if block.block_number < next_number {
@@ -378,13 +532,34 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
                 let block_number = block.block_number;
                 let block_hash = block.block_hash;
                 let block_timestamp = block.timestamp;
+                let transaction_count = block.transactions.len();
                 let storage_updates: usize = state_update
                     .contract_updates
                     .iter()
                     .map(|x| x.1.storage.len())
                     .sum();
+
+                // Durations are recorded in seconds once the block is processed.
+                let span = tracing::info_span!(
+                    "block",
+                    number = %block_number,
+                    hash = %block_hash,
+                    transaction_count,
+                    storage_updates,
+                    block_time = tracing::field::Empty,
+                    block_time_avg = tracing::field::Empty,
+                    processing = tracing::field::Empty,
+                    trie_update = tracing::field::Empty,
+                    commit = tracing::field::Empty,
+                    block_download = tracing::field::Empty,
+                    class_declaration = tracing::field::Empty,
+                    state_diff_download = tracing::field::Empty,
+                    signature_download = tracing::field::Empty,
+                    verification = tracing::field::Empty,
+                );
+
                 let update_t = std::time::Instant::now();
-                l2_update(
+                let update_timings = l2_update(
                     &mut db_conn,
                     *block,
                     tx_comm,
@@ -392,20 +567,24 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
                     *state_update,
                     *signature,
                     verify_tree_hashes,
+                    receipts_from_execution,
                     storage.clone(),
                 )
+                .instrument(span.clone())
                 .await
                 .with_context(|| format!("Update L2 state to {block_number}"))?;
                 let block_time = last_block_start.elapsed();
                 let update_t = update_t.elapsed();
                 last_block_start = std::time::Instant::now();
 
+                metrics::increment_counter!("sync_blocks_total");
+
                 block_time_avg = block_time_avg.mul_f32(1.0 - BLOCK_TIME_WEIGHT)
                     + block_time.mul_f32(BLOCK_TIME_WEIGHT);
 
                 // Update sync status
-                match &mut *state.status.write().await {
-                    Syncing::False(_) => {}
+                let highest = match &mut *state.status.write().await {
+                    Syncing::False(_) => None,
                     Syncing::Status(status) => {
                         status.current = NumberedBlock::from((block_hash, block_number));
 
@@ -415,8 +594,10 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
                             status.highest = status.current;
                             metrics::gauge!("highest_block", block_number.get() as f64);
                         }
+
+                        Some(status.highest.number)
                     }
-                }
+                };
 
                 let now_timestamp = time::OffsetDateTime::now_utc().unix_timestamp() as u64;
                 let latency = now_timestamp.saturating_sub(block_timestamp.get());
@@ -429,6 +610,11 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
 
                 metrics::gauge!("block_download", download_time);
                 metrics::gauge!("block_processing", update_t.as_secs_f64());
+                metrics::gauge!(
+                    "block_trie_update",
+                    update_timings.trie_update.as_secs_f64()
+                );
+                metrics::gauge!("block_commit", update_timings.commit.as_secs_f64());
                 metrics::gauge!("block_latency", latency as f64);
                 metrics::gauge!(
                     "block_time",
@@ -437,35 +623,69 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
                 latest_timestamp = block_timestamp;
                 next_number += 1;
 
-                // Give a simple log under INFO level, and a more verbose log
-                // with timing information under DEBUG+ level.
-                //
-                // This should be removed if we have a configurable log level.
-                // See the docs for LevelFilter for more information.
-                match tracing::level_filters::LevelFilter::current().into_level() {
-                    None => {}
-                    Some(level) if level <= tracing::Level::INFO => {
-                        tracing::info!("Updated Starknet state with block {}", block_number)
-                    }
-                    Some(_) => {
-                        tracing::debug!("Updated Starknet state with block {} after {:2}s ({:2}s avg). contracts ({:2}s), {} storage updates ({:2}s). Block downloaded in {:2}s, state diff in {:2}s, signature in {:2}s",
-                                    block_number,
-                                    block_time.as_secs_f32(),
-                                    block_time_avg.as_secs_f32(),
-                                    timings.class_declaration.as_secs_f32(),
-                                    storage_updates,
-                                    update_t.as_secs_f32(),
-                                    timings.block_download.as_secs_f32(),
-                                    timings.state_diff_download.as_secs_f32(),
-                                    timings.signature_download.as_secs_f32(),
-                                );
-                    }
+                let stages = progress::StageTimings {
+                    download: timings.block_download
+                        + timings.state_diff_download
+                        + timings.signature_download,
+                    classes: timings.class_declaration,
+                    tries: update_timings.trie_update,
+                    commit: update_timings.commit,
+                };
+                let catching_up = progress.record(block_number, highest, stages);
+
+                let sync_timings = pathfinder_storage::BlockSyncTimings {
+                    block_number,
+                    download: timings.block_download
+                        + timings.state_diff_download
+                        + timings.class_declaration
+                        + timings.signature_download,
+                    verification: timings.verification,
+                    trie_update: update_timings.trie_update,
+                    db_write: update_t.saturating_sub(update_timings.trie_update),
+                };
+                // The timings are diagnostics only, so failing to record them doesn't stop sync.
+                let recorded = tokio::task::block_in_place(|| {
+                    let tx = db_conn
+                        .transaction()
+                        .context("Creating database transaction")?;
+                    tx.insert_block_sync_timings(&sync_timings, SYNC_TIMINGS_CAPACITY)?;
+                    tx.commit().context("Committing database transaction")
+                });
+                if let Err(error) = recorded {
+                    tracing::warn!(%block_number, %error, "Recording block sync timings failed");
                 }
+
+                span.record("block_time", block_time.as_secs_f64());
+                span.record("block_time_avg", block_time_avg.as_secs_f64());
+                span.record("processing", update_t.as_secs_f64());
+                span.record("trie_update", update_timings.trie_update.as_secs_f64());
+                span.record("commit", update_timings.commit.as_secs_f64());
+                span.record("block_download", timings.block_download.as_secs_f64());
+                span.record("class_declaration", timings.class_declaration.as_secs_f64());
+                span.record(
+                    "state_diff_download",
+                    timings.state_diff_download.as_secs_f64(),
+                );
+                span.record(
+                    "signature_download",
+                    timings.signature_download.as_secs_f64(),
+                );
+                span.record("verification", timings.verification.as_secs_f64());
+
+                span.in_scope(|| {
+                    // While catching up, blocks are covered by the periodic progress report.
+                    if catching_up {
+                        tracing::debug!("Updated Starknet state with block {}", block_number);
+                    } else {
+                        tracing::info!("Updated Starknet state with block {}", block_number);
+                    }
+                });
             }
             Reorg(reorg_tail) => {
-                l2_reorg(&mut db_conn, reorg_tail)
+                let purged = l2_reorg(&mut db_conn, reorg_tail)
                     .await
                     .with_context(|| format!("Reorg L2 state to {reorg_tail:?}"))?;
+                response_cache.invalidate(&purged);
 
                 next_number = reorg_tail;
 
@@ -520,28 +740,34 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
                 tracing::debug!(sierra=%sierra_hash, casm=%casm_hash, "Inserted new Sierra class");
             }
             Pending(pending) => {
-                let (number, hash) = tokio::task::block_in_place(|| {
-                    let tx = db_conn
-                        .transaction()
-                        .context("Creating database transaction")?;
-                    let latest = tx
-                        .block_id(pathfinder_storage::BlockId::Latest)
-                        .context("Fetching latest block hash")?
-                        .unwrap_or_default();
+                let transaction_hashes = pending
+                    .0
+                    .transactions
+                    .iter()
+                    .map(|tx| tx.hash())
+                    .collect::<Vec<_>>();
+                let pending = current_pending.insert(*pending);
 
-                    anyhow::Ok(latest)
-                })
-                .context("Fetching latest block hash")?;
-
-                if pending.0.parent_hash == hash {
-                    let data = PendingData {
-                        block: pending.0,
-                        state_update: pending.1,
-                        number: number + 1,
-                    };
-                    pending_data.send_replace(Arc::new(data));
-                    tracing::debug!("Updated pending data");
+                update_pending(&mut db_conn, pending, &transaction_hashes, &pending_data)?;
+            }
+            PendingDelta(delta) => {
+                let transaction_hashes = delta
+                    .block
+                    .transactions
+                    .iter()
+                    .map(|tx| tx.hash())
+                    .collect::<Vec<_>>();
+
+                let Some(pending) = current_pending.as_mut() else {
+                    tracing::debug!("Ignoring pending delta without a preceding pending block");
+                    continue;
+                };
+                if !delta.apply_to(&mut pending.0, &mut pending.1) {
+                    tracing::debug!("Ignoring pending delta of a different pending block");
+                    continue;
                 }
+
+                update_pending(&mut db_conn, pending, &transaction_hashes, &pending_data)?;
             }
         }
     }
@@ -549,6 +775,66 @@ async fn consumer(mut events: Receiver<SyncEvent>, context: ConsumerContext) ->
     Ok(())
 }
 
+/// Publishes the pending data if it is on top of the latest block, and marks its new
+/// transactions as included.
+///
+/// All transactions are marked if pending data on top of the latest block wasn't published
+/// before, as earlier transactions were skipped. A write transaction is only opened if any of
+/// them were submitted via this node and await inclusion.
+fn update_pending(
+    connection: &mut Connection,
+    pending: &(PendingBlock, StateUpdate),
+    new_transactions: &[TransactionHash],
+    pending_data: &WatchSender<Arc<PendingData>>,
+) -> anyhow::Result<()> {
+    tokio::task::block_in_place(|| {
+        let tx = connection
+            .transaction()
+            .context("Creating database transaction")?;
+        let (number, hash) = tx
+            .block_id(pathfinder_storage::BlockId::Latest)
+            .context("Fetching latest block hash")?
+            .unwrap_or_default();
+
+        if pending.0.parent_hash != hash {
+            return Ok(());
+        }
+
+        let published = pending_data.borrow().block.parent_hash == hash;
+        let awaiting = if published {
+            tx.submitted_transactions_awaiting_inclusion(new_transactions)
+        } else {
+            let transactions = pending
+                .0
+                .transactions
+                .iter()
+                .map(|t| t.hash())
+                .collect::<Vec<_>>();
+            tx.submitted_transactions_awaiting_inclusion(&transactions)
+        }
+        .context("Querying submitted transactions")?;
+        drop(tx);
+
+        if !awaiting.is_empty() {
+            let tx = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .context("Creating database transaction")?;
+            mark_submitted_transactions_included(&tx, &awaiting)?;
+            tx.commit().context("Committing database transaction")?;
+        }
+
+        let data = PendingData {
+            block: pending.0.clone(),
+            state_update: pending.1.clone(),
+            number: number + 1,
+        };
+        pending_data.send_replace(Arc::new(data));
+        tracing::debug!("Updated pending data");
+
+        Ok(())
+    })
+}
+
 async fn latest_n_blocks(
     connection: &mut Connection,
     n: usize,
@@ -659,10 +945,11 @@ async fn propagate_head(
     *last_propagated = Instant::now();
 }
 
+/// Returns the block accepted on L1 by the update, if it matches the local chain.
 async fn l1_update(
     connection: &mut Connection,
     update: &EthereumStateUpdate,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<BlockNumber>> {
     tokio::task::block_in_place(move || {
         let transaction = connection
             .transaction_with_behavior(TransactionBehavior::Immediate)
@@ -677,12 +964,14 @@ async fn l1_update(
             .context("Fetching block hash")?
             .map(|(_, hash)| hash);
 
+        let mut l1_head = None;
         if let Some(l2_hash) = l2_hash {
             if l2_hash == update.block_hash {
                 transaction
                     .update_l1_l2_pointer(Some(update.block_number))
                     .context("Updating L1-L2 pointer")?;
                 tracing::info!(block=?update.block_number, "Updated L1/L2 match");
+                l1_head = Some(update.block_number);
             } else {
                 tracing::warn!(block_number=?update.block_number, L1=?update.block_hash, L2=?l2_hash, "L1/L2 block hash mismatch");
                 if let Some(matching_block_number) = transaction.l1_l2_pointer()? {
@@ -691,28 +980,126 @@ async fn l1_update(
             }
         }
 
-        transaction.commit().context("Commit database transaction")
+        transaction
+            .commit()
+            .context("Commit database transaction")?;
+
+        Ok(l1_head)
     })
 }
 
-/// Returns the new [StateCommitment] after the update.
+/// Reverts the L1 acceptance of the blocks accepted by updates read at or after the reorged L1
+/// block.
+async fn l1_reorg(connection: &mut Connection, l1_block_number: u64) -> anyhow::Result<()> {
+    tokio::task::block_in_place(move || {
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .context("Create database transaction")?;
+
+        let purged = transaction
+            .purge_l1_states_since(l1_block_number)
+            .context("Purging L1 updates")?;
+
+        // The remaining updates were read at earlier L1 blocks, which the reorg didn't affect.
+        let l1_state = transaction
+            .latest_l1_state()
+            .context("Querying latest L1 update")?
+            .map(|update| update.block_number);
+        let l1_l2_head = transaction.l1_l2_pointer().context("Query L1-L2 head")?;
+        let reverted_head = l1_l2_head.min(l1_state);
+        if reverted_head != l1_l2_head {
+            transaction
+                .update_l1_l2_pointer(reverted_head)
+                .context("Updating L1-L2 head")?;
+        }
+
+        transaction
+            .commit()
+            .context("Commit database transaction")?;
+
+        tracing::warn!(%l1_block_number, %purged, from=?l1_l2_head, to=?reverted_head, "Reverted L1 acceptance after L1 reorg");
+
+        Ok(())
+    })
+}
+
+/// Durations of the individual stages of [l2_update].
+struct L2UpdateTimings {
+    /// Time spent updating and persisting the state tries.
+    trie_update: Duration,
+    /// Time spent committing the database transaction.
+    commit: Duration,
+}
+
+/// Computes the receipts of the block by executing its transactions, and verifies them against
+/// the received receipts, see [verify](crate::state::receipt::verify).
+///
+/// The received events were verified against the event commitment of the block, which its hash
+/// commits to. No such commitment covers the rest of the receipts before Starknet 0.13.2, so
+/// executed receipts only replace received ones which lack them.
+fn execute_receipts(
+    transaction: &Transaction<'_>,
+    chain_id: ChainId,
+    block: &Block,
+) -> anyhow::Result<Vec<Receipt>> {
+    let header = BlockHeader {
+        hash: block.block_hash,
+        parent_hash: block.parent_block_hash,
+        number: block.block_number,
+        timestamp: block.timestamp,
+        gas_price: block.gas_price()?,
+        sequencer_address: block.sequencer_address()?,
+        starknet_version: block.starknet_version.clone(),
+        ..Default::default()
+    };
+    let receipts =
+        crate::state::receipt::execute(transaction, chain_id, header, &block.transactions)
+            .context("Executing block")?;
+
+    let expected = calculate_event_commitment(&block.transaction_receipts)?;
+    let commitment = calculate_event_commitment(&receipts)?;
+    anyhow::ensure!(
+        commitment == expected,
+        "Event commitment mismatch: executed {commitment}, received {expected}"
+    );
+
+    crate::state::receipt::verify(&block.transaction_receipts, receipts)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn l2_update(
     connection: &mut Connection,
-    block: Block,
+    mut block: Block,
     transaction_commitment: TransactionCommitment,
     event_commitment: EventCommitment,
     state_update: StateUpdate,
     signature: BlockCommitmentSignature,
     verify_tree_hashes: bool,
+    receipts_from_execution: Option<ChainId>,
     // we need this so that we can create extra read-only transactions for
     // parallel contract state updates
     storage: Storage,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<L2UpdateTimings> {
     tokio::task::block_in_place(move || {
         let transaction = connection
             .transaction_with_behavior(TransactionBehavior::Immediate)
             .context("Create database transaction")?;
+
+        // The block is executed on the state of its parent, before its state update is applied.
+        // The received receipts are kept for blocks which can't be executed, or whose execution
+        // does not match them.
+        if let Some(chain_id) = receipts_from_execution {
+            if crate::state::receipt::is_executable(&block.starknet_version, &block.transactions) {
+                match execute_receipts(&transaction, chain_id, &block) {
+                    Ok(receipts) => block.transaction_receipts = receipts,
+                    Err(error) => {
+                        tracing::warn!(block=%block.block_number, ?error, "Executing block failed, keeping received receipts");
+                    }
+                }
+            }
+        }
+
+        let trie_update = Instant::now();
         let (storage_commitment, class_commitment) = update_starknet_state(
             &transaction,
             &state_update,
@@ -721,6 +1108,7 @@ async fn l2_update(
             storage,
         )
         .context("Updating Starknet state")?;
+        let trie_update = trie_update.elapsed();
         let state_commitment = StateCommitment::calculate(storage_commitment, class_commitment);
 
         // Ensure that roots match.. what should we do if it doesn't? For now the whole sync process ends..
@@ -747,19 +1135,14 @@ async fn l2_update(
             .map(|r| r.events.len())
             .sum();
 
-        // Update L2 database. These types shouldn't be options at this level,
-        // but for now the unwraps are "safe" in that these should only ever be
-        // None for pending queries to the sequencer, but we aren't using those here.
+        // Update L2 database.
         let header = BlockHeader {
             hash: block.block_hash,
             parent_hash: block.parent_block_hash,
             number: block.block_number,
             timestamp: block.timestamp,
-            // Default value for cairo <0.8.2 is 0
-            gas_price: block.gas_price.unwrap_or(GasPrice::ZERO),
-            sequencer_address: block
-                .sequencer_address
-                .unwrap_or(SequencerAddress(Felt::ZERO)),
+            gas_price: block.gas_price()?,
+            sequencer_address: block.sequencer_address()?,
             starknet_version: block.starknet_version,
             class_commitment,
             event_commitment,
@@ -791,6 +1174,12 @@ async fn l2_update(
             .insert_transaction_data(header.hash, header.number, &transaction_data)
             .context("Insert transaction data into database")?;
 
+        let transaction_hashes = transaction_data
+            .iter()
+            .map(|(tx, _)| tx.hash())
+            .collect::<Vec<_>>();
+        mark_submitted_transactions_included(&transaction, &transaction_hashes)?;
+
         // Insert state updates
         transaction
             .insert_state_update(block.block_number, &state_update)
@@ -820,13 +1209,46 @@ async fn l2_update(
             }
         }
 
-        transaction.commit().context("Commit database transaction")
-    })?;
+        let commit = Instant::now();
+        transaction
+            .commit()
+            .context("Commit database transaction")?;
+
+        Ok(L2UpdateTimings {
+            trie_update,
+            commit: commit.elapsed(),
+        })
+    })
+}
+
+/// Marks the transactions submitted via this node which are among `transaction_hashes` as
+/// included, and records their time to inclusion.
+fn mark_submitted_transactions_included(
+    transaction: &Transaction<'_>,
+    transaction_hashes: &[TransactionHash],
+) -> anyhow::Result<()> {
+    let now = time::OffsetDateTime::now_utc().unix_timestamp() as u64;
+    let included = transaction
+        .mark_submitted_transactions_included(transaction_hashes, now)
+        .context("Marking submitted transactions as included")?;
+
+    for (transaction_hash, submitted) in included {
+        let time_to_inclusion = now.saturating_sub(submitted.submitted_at);
+        metrics::histogram!(
+            "submitted_transaction_time_to_inclusion",
+            time_to_inclusion as f64
+        );
+        tracing::debug!(%transaction_hash, %time_to_inclusion, "Submitted transaction included");
+    }
 
     Ok(())
 }
 
-async fn l2_reorg(connection: &mut Connection, reorg_tail: BlockNumber) -> anyhow::Result<()> {
+/// Purges the blocks from `reorg_tail` onwards, returning the hashes of the purged blocks.
+async fn l2_reorg(
+    connection: &mut Connection,
+    reorg_tail: BlockNumber,
+) -> anyhow::Result<Vec<BlockHash>> {
     tokio::task::block_in_place(move || {
         let transaction = connection
             .transaction_with_behavior(TransactionBehavior::Immediate)
@@ -846,7 +1268,13 @@ async fn l2_reorg(connection: &mut Connection, reorg_tail: BlockNumber) -> anyho
         // This is acceptable performance because reorgs are rare and need not be
         // 100% optimal. However a large reorg could cause a massive memory spike
         // which is not acceptable.
+        let mut purged = Vec::new();
         while head >= reorg_tail {
+            let (_, hash) = transaction
+                .block_id(head.into())
+                .with_context(|| format!("Querying hash of block {head}"))?
+                .with_context(|| format!("Block {head} is missing"))?;
+            purged.push(hash);
             transaction
                 .purge_block(head)
                 .with_context(|| format!("Purging block {head} from database"))?;
@@ -875,11 +1303,15 @@ async fn l2_reorg(connection: &mut Connection, reorg_tail: BlockNumber) -> anyho
             }
         }
 
-        transaction.commit().context("Commit database transaction")
+        transaction
+            .commit()
+            .context("Commit database transaction")?;
+
+        Ok(purged)
     })
 }
 
-fn update_starknet_state(
+pub(crate) fn update_starknet_state(
     transaction: &Transaction<'_>,
     state_update: &StateUpdate,
     verify_hashes: bool,
@@ -933,13 +1365,15 @@ fn update_starknet_state(
 
     let contract_update_results = recv.recv().context("Panic on rayon thread")??;
 
+    storage_commitment_tree
+        .set_many(
+            contract_update_results
+                .iter()
+                .map(|result| (result.contract_address, result.state_hash)),
+        )
+        .context("Updating storage commitment tree")?;
+
     for contract_update_result in contract_update_results.into_iter() {
-        storage_commitment_tree
-            .set(
-                contract_update_result.contract_address,
-                contract_update_result.state_hash,
-            )
-            .context("Updating storage commitment tree")?;
         contract_update_result
             .insert(block, transaction)
             .context("Inserting contract update result")?;
@@ -993,6 +1427,7 @@ fn update_starknet_state(
     }
     .with_verify_hashes(verify_hashes);
 
+    let mut class_leaves = Vec::with_capacity(state_update.declared_sierra_classes.len());
     for (sierra, casm) in &state_update.declared_sierra_classes {
         let leaf_hash = pathfinder_common::calculate_class_commitment_leaf_hash(*casm);
 
@@ -1000,11 +1435,13 @@ fn update_starknet_state(
             .insert_class_commitment_leaf(block, &leaf_hash, casm)
             .context("Adding class commitment leaf")?;
 
-        class_commitment_tree
-            .set(*sierra, leaf_hash)
-            .context("Update class commitment tree")?;
+        class_leaves.push((*sierra, leaf_hash));
     }
 
+    class_commitment_tree
+        .set_many(class_leaves)
+        .context("Update class commitment tree")?;
+
     // Apply all class commitment tree changes.
     let (class_commitment, nodes) = class_commitment_tree
         .commit()
@@ -1136,9 +1573,13 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
 
         let tx = connection.transaction().unwrap();
         for i in 0..num_blocks {
@@ -1180,9 +1621,13 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
 
         let tx = connection.transaction().unwrap();
         let genesis_exists = tx.block_exists(BlockNumber::GENESIS.into()).unwrap();
@@ -1199,6 +1644,73 @@ mod tests {
         assert!(!block_2_exists);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn l1_reorg() {
+        use pathfinder_ethereum::{EthereumStateUpdate, L1Block};
+        use primitive_types::H256;
+
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+
+        let (event_tx, event_rx) = tokio::sync::mpsc::channel(100);
+
+        let block_data = generate_block_data();
+        let l1_updates = block_data[1..]
+            .iter()
+            .zip([10, 20])
+            .map(
+                |(((block, _), _, _, _), l1_block_number)| EthereumStateUpdate {
+                    state_root: block.state_commitment,
+                    block_number: block.block_number,
+                    block_hash: block.block_hash,
+                    l1_transaction: None,
+                    previous_block_number: None,
+                    l1_block: Some(L1Block {
+                        number: l1_block_number,
+                        hash: H256::repeat_byte(l1_block_number as u8),
+                    }),
+                },
+            )
+            .collect::<Vec<_>>();
+
+        for (a, b, c, d) in block_data {
+            event_tx.send(SyncEvent::Block(a, b, c, d)).await.unwrap();
+        }
+        for update in l1_updates.clone() {
+            event_tx.send(SyncEvent::L1Update(update)).await.unwrap();
+        }
+        // Invalidates the update of block 2, which was read at L1 block 20.
+        event_tx.send(SyncEvent::L1Reorg(15)).await.unwrap();
+        drop(event_tx);
+
+        let (tx, _rx) = tokio::sync::watch::channel(Default::default());
+        let context = ConsumerContext {
+            storage,
+            state: Arc::new(SyncState::default()),
+            pending_data: tx,
+            verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
+        };
+
+        consumer(event_rx.into(), context).await.unwrap();
+
+        let tx = connection.transaction().unwrap();
+        assert_eq!(tx.latest_l1_state().unwrap(), Some(l1_updates[0].clone()));
+        assert_eq!(
+            tx.l1_l2_pointer().unwrap(),
+            Some(BlockNumber::new_or_panic(1))
+        );
+        assert!(tx
+            .block_is_l1_accepted(BlockNumber::new_or_panic(1).into())
+            .unwrap());
+        assert!(!tx
+            .block_is_l1_accepted(BlockNumber::new_or_panic(2).into())
+            .unwrap());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn blocks_are_not_skipped_after_a_reorg() {
         // A bug caused reorg'd block numbers to be skipped. This
@@ -1236,9 +1748,13 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
 
         let tx = connection.transaction().unwrap();
         let genesis_exists = tx.block_exists(BlockNumber::GENESIS.into()).unwrap();
@@ -1279,9 +1795,13 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
 
         let tx = connection.transaction().unwrap();
         let genesis_exists = tx.block_exists(BlockNumber::GENESIS.into()).unwrap();
@@ -1314,9 +1834,13 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
 
         let tx = connection.transaction().unwrap();
         let definition = tx.class_definition(class_hash).unwrap().unwrap();
@@ -1352,9 +1876,13 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
 
         let tx = connection.transaction().unwrap();
         let definition = tx.class_definition(ClassHash(class_hash)).unwrap().unwrap();
@@ -1362,6 +1890,98 @@ mod tests {
         assert_eq!(definition, expected_definition);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pending_deltas_are_applied() {
+        use starknet_gateway_types::reply::transaction::{L1HandlerTransaction, Transaction};
+
+        let storage = Storage::in_memory().unwrap();
+        let (event_tx, event_rx) = tokio::sync::mpsc::channel(100);
+
+        let (genesis, genesis_update, signature, timings) =
+            generate_block_data().into_iter().next().unwrap();
+        let genesis_hash = genesis.0.block_hash;
+        event_tx
+            .send(SyncEvent::Block(
+                genesis,
+                genesis_update,
+                signature,
+                timings,
+            ))
+            .await
+            .unwrap();
+
+        let transaction = |hash| {
+            Transaction::L1Handler(L1HandlerTransaction {
+                contract_address: contract_address!("0x1"),
+                entry_point_selector: entry_point!("0x55"),
+                nonce: transaction_nonce!("0x2"),
+                calldata: Vec::new(),
+                transaction_hash: hash,
+                version: pathfinder_common::TransactionVersion::ONE,
+            })
+        };
+        let block = reply::PendingBlock {
+            parent_hash: genesis_hash,
+            transactions: vec![transaction(transaction_hash!("0x1"))],
+            ..Default::default()
+        };
+        let state_update = StateUpdate::default().with_storage_update(
+            contract_address!("0x1"),
+            storage_address!("0x2"),
+            storage_value!("0x3"),
+        );
+        event_tx
+            .send(SyncEvent::Pending(Box::new((
+                block.clone(),
+                state_update.clone(),
+            ))))
+            .await
+            .unwrap();
+
+        let mut expected_block = block.clone();
+        expected_block
+            .transactions
+            .push(transaction(transaction_hash!("0x2")));
+        let expected_state_update = state_update.clone().with_storage_update(
+            contract_address!("0x1"),
+            storage_address!("0x4"),
+            storage_value!("0x5"),
+        );
+        let delta = super::pending::PendingDelta {
+            base_transaction_count: 1,
+            block: reply::PendingBlock {
+                parent_hash: genesis_hash,
+                transactions: vec![transaction(transaction_hash!("0x2"))],
+                ..Default::default()
+            },
+            state_update: expected_state_update.changes_since(&state_update),
+        };
+        event_tx
+            .send(SyncEvent::PendingDelta(Box::new(delta)))
+            .await
+            .unwrap();
+        drop(event_tx);
+
+        let (tx, rx) = tokio::sync::watch::channel(Default::default());
+        let context = ConsumerContext {
+            storage,
+            state: Arc::new(SyncState::default()),
+            pending_data: tx,
+            verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
+        };
+
+        consumer(event_rx.into(), context).await.unwrap();
+
+        let pending = rx.borrow();
+        assert_eq!(pending.block, expected_block);
+        assert_eq!(pending.state_update, expected_state_update);
+        assert_eq!(pending.number, BlockNumber::new_or_panic(1));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn consumer_should_ignore_duplicate_blocks() {
         let storage = Storage::in_memory().unwrap();
@@ -1387,8 +2007,26 @@ mod tests {
             state: Arc::new(SyncState::default()),
             pending_data: tx,
             verify_tree_hashes: false,
+            websocket_txs: None,
+            response_cache: Default::default(),
+            blob_reader: None,
+            receipts_from_execution: None,
         };
 
-        consumer(event_rx, context).await.unwrap();
+        consumer(event_rx.into(), context).await.unwrap();
+    }
+
+    #[test]
+    fn test_networks_are_polled_less_often() {
+        use super::PollIntervals;
+        use pathfinder_common::Chain;
+
+        let mainnet = PollIntervals::default_for(Chain::Mainnet);
+        for chain in [Chain::Testnet, Chain::Testnet2, Chain::Integration] {
+            let testnet = PollIntervals::default_for(chain);
+            assert!(testnet.head > mainnet.head);
+            assert!(testnet.pending > mainnet.pending);
+        }
+        assert_eq!(PollIntervals::default_for(Chain::Custom), mainnet);
     }
 }
@@ -1,6 +1,70 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use anyhow::Context;
 use pathfinder_common::{ClassHash, SierraHash, StarknetVersion};
 use starknet_gateway_client::GatewayApi;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::state::sync::SyncError;
+
+lazy_static::lazy_static!(
+    /// The classes being downloaded, shared by all sync paths, see [claim_download]. The flag of
+    /// a class is set once it has been downloaded and emitted.
+    static ref IN_FLIGHT: Mutex<HashMap<ClassHash, Arc<tokio::sync::Mutex<bool>>>> =
+        Default::default();
+);
+
+/// The exclusive right to download a class, see [claim_download].
+///
+/// Dropping the claim without [completing](DownloadClaim::complete) it lets the next waiting
+/// task download the class instead.
+pub struct DownloadClaim {
+    class_hash: ClassHash,
+    downloaded: OwnedMutexGuard<bool>,
+}
+
+impl DownloadClaim {
+    /// Marks the class as downloaded and emitted, so that waiting tasks skip it.
+    pub fn complete(mut self) {
+        *self.downloaded = true;
+    }
+}
+
+impl Drop for DownloadClaim {
+    fn drop(&mut self) {
+        let mut in_flight = IN_FLIGHT.lock().unwrap();
+        // Only the map and this claim reference the entry if no other task is waiting for it.
+        if in_flight
+            .get(&self.class_hash)
+            .is_some_and(|entry| Arc::strong_count(entry) == 2)
+        {
+            in_flight.remove(&self.class_hash);
+        }
+    }
+}
+
+/// Claims the download of a class, waiting while another task, e.g. the pending poller, is
+/// downloading it.
+///
+/// Returns [None] if the other task downloaded and emitted the class meanwhile, so that the
+/// gateway isn't queried for it twice.
+pub async fn claim_download(class_hash: ClassHash) -> Option<DownloadClaim> {
+    let entry = IN_FLIGHT
+        .lock()
+        .unwrap()
+        .entry(class_hash)
+        .or_default()
+        .clone();
+
+    let downloaded = entry.lock_owned().await;
+    let claim = DownloadClaim {
+        class_hash,
+        downloaded,
+    };
+
+    (!*claim.downloaded).then_some(claim)
+}
 
 pub enum DownloadedClass {
     Cairo {
@@ -46,12 +110,14 @@ pub async fn download_class<SequencerClient: GatewayApi>(
             Ok(DownloadedClass::Cairo { definition, hash })
         }
         starknet_gateway_types::class_hash::ComputedClassHash::Sierra(hash) => {
-            anyhow::ensure!(
-                class_hash == hash,
-                "Class hash mismatch, {} instead of {}",
-                hash,
-                class_hash.0
-            );
+            if class_hash != hash {
+                return Err(SyncError::Verification(anyhow::anyhow!(
+                    "Class hash mismatch, {} instead of {}",
+                    hash,
+                    class_hash.0
+                ))
+                .into());
+            }
 
             // FIXME(integration reset): work-around for integration containing Sierra classes
             // that are incompatible with production compiler. This will get "fixed" in the future
@@ -89,3 +155,32 @@ pub async fn download_class<SequencerClient: GatewayApi>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_downloads_are_deduplicated() {
+        let class_hash = class_hash_bytes!(b"deduplicated class");
+
+        let claim = claim_download(class_hash).await.unwrap();
+        let waiting = tokio::spawn(claim_download(class_hash));
+
+        // A failed download is retried by the waiting task.
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+        drop(claim);
+        let claim = waiting.await.unwrap().unwrap();
+
+        let waiting = tokio::spawn(claim_download(class_hash));
+        tokio::task::yield_now().await;
+        claim.complete();
+        assert!(waiting.await.unwrap().is_none());
+
+        // Nothing is in flight anymore.
+        assert!(!IN_FLIGHT.lock().unwrap().contains_key(&class_hash));
+    }
+}
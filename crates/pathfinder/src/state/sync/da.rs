@@ -0,0 +1,154 @@
+//! Verification of the local state against the state diffs which Starknet posts to Ethereum.
+use anyhow::Context;
+use pathfinder_common::{BlockNumber, ContractNonce, StorageValue};
+use pathfinder_ethereum::blob::{BlobReader, BlobStateDiff};
+use pathfinder_ethereum::EthereumStateUpdate;
+use pathfinder_storage::{Storage, Transaction};
+
+/// Checks the state diff posted by the L1 state update against the local state at the update's
+/// block, logging any mismatches.
+///
+/// The update must already have been matched against the local chain.
+pub(super) async fn verify(
+    storage: Storage,
+    reader: BlobReader,
+    update: EthereumStateUpdate,
+) -> anyhow::Result<()> {
+    let Some(l1_transaction) = update.l1_transaction else {
+        return Ok(());
+    };
+
+    let Some(diff) = reader
+        .state_diff(&l1_transaction)
+        .await
+        .context("Reading state diff blobs")?
+    else {
+        tracing::debug!(block=%update.block_number, "State update was not posted as blobs");
+        return Ok(());
+    };
+
+    let block = update.block_number;
+    let mismatches = tokio::task::spawn_blocking(move || {
+        let mut db = storage
+            .connection()
+            .context("Creating database connection")?;
+        let tx = db.transaction().context("Creating database transaction")?;
+        count_mismatches(&tx, block, &diff)
+    })
+    .await
+    .context("Joining verification task")??;
+
+    if mismatches == 0 {
+        tracing::debug!(%block, "Local state matches the state diff posted to L1");
+    } else {
+        tracing::warn!(%block, %mismatches, "Local state does not match the state diff posted to L1");
+    }
+
+    Ok(())
+}
+
+/// Compares the state diff with the local state at `block`, logging each mismatching value.
+fn count_mismatches(
+    tx: &Transaction<'_>,
+    block: BlockNumber,
+    diff: &BlobStateDiff,
+) -> anyhow::Result<usize> {
+    let mut mismatches = 0;
+
+    for contract in &diff.contracts {
+        let address = contract.address;
+
+        let nonce = tx
+            .contract_nonce(address, block.into())
+            .context("Querying contract nonce")?
+            .unwrap_or(ContractNonce::ZERO);
+        if nonce != contract.nonce {
+            tracing::warn!(%block, contract=%address, local=%nonce, l1=%contract.nonce, "Nonce mismatch");
+            mismatches += 1;
+        }
+
+        if let Some(class_hash) = contract.class_hash {
+            let local = tx
+                .contract_class_hash(block.into(), address)
+                .context("Querying contract class hash")?;
+            if local != Some(class_hash) {
+                tracing::warn!(%block, contract=%address, ?local, l1=%class_hash, "Class hash mismatch");
+                mismatches += 1;
+            }
+        }
+
+        for (key, value) in &contract.storage {
+            let local = tx
+                .storage_value(block.into(), address, *key)
+                .context("Querying storage value")?
+                .unwrap_or(StorageValue::ZERO);
+            if local != *value {
+                tracing::warn!(%block, contract=%address, %key, %local, l1=%value, "Storage value mismatch");
+                mismatches += 1;
+            }
+        }
+    }
+
+    for (class_hash, casm_hash) in &diff.declared_classes {
+        let local = tx
+            .casm_hash_at(block.into(), *class_hash)
+            .context("Querying compiled class hash")?;
+        if local != Some(*casm_hash) {
+            tracing::warn!(%block, class=%class_hash, ?local, l1=%casm_hash, "Compiled class hash mismatch");
+            mismatches += 1;
+        }
+    }
+
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::{BlockHeader, StateUpdate};
+    use pathfinder_ethereum::blob::ContractDiff;
+
+    use super::*;
+
+    #[test]
+    fn mismatches_are_counted() {
+        let storage = Storage::in_memory().unwrap();
+        let mut db = storage.connection().unwrap();
+        let tx = db.transaction().unwrap();
+
+        let header = BlockHeader::builder().finalize_with_hash(block_hash!("0x1"));
+        tx.insert_block_header(&header).unwrap();
+        let state_update = StateUpdate::default()
+            .with_deployed_contract(contract_address!("0x1"), class_hash!("0x10"))
+            .with_contract_nonce(contract_address!("0x1"), contract_nonce!("0x2"))
+            .with_storage_update(
+                contract_address!("0x1"),
+                storage_address!("0x3"),
+                storage_value!("0x4"),
+            );
+        tx.insert_state_update(header.number, &state_update)
+            .unwrap();
+
+        let mut diff = BlobStateDiff {
+            contracts: vec![ContractDiff {
+                address: contract_address!("0x1"),
+                nonce: contract_nonce!("0x2"),
+                class_hash: Some(class_hash!("0x10")),
+                storage: vec![(storage_address!("0x3"), storage_value!("0x4"))],
+            }],
+            declared_classes: vec![],
+        };
+        assert_eq!(count_mismatches(&tx, header.number, &diff).unwrap(), 0);
+
+        diff.contracts[0].nonce = contract_nonce!("0x3");
+        diff.contracts[0]
+            .storage
+            .push((storage_address!("0x5"), storage_value!("0x6")));
+        diff.contracts.push(ContractDiff {
+            address: contract_address!("0x2"),
+            class_hash: Some(class_hash!("0x20")),
+            ..Default::default()
+        });
+        assert_eq!(count_mismatches(&tx, header.number, &diff).unwrap(), 3);
+    }
+}
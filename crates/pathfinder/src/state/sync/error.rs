@@ -0,0 +1,94 @@
+use starknet_gateway_types::error::SequencerError;
+
+/// The reason an L1 or L2 sync task terminated, which decides whether it is restarted.
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    /// Requesting data from the gateway or Ethereum failed.
+    #[error(transparent)]
+    Network(anyhow::Error),
+    /// Data from the gateway failed verification, e.g. a block hash mismatch.
+    #[error(transparent)]
+    Verification(anyhow::Error),
+    /// Reading from or writing to the database failed.
+    #[error(transparent)]
+    Database(anyhow::Error),
+    /// Any other error, e.g. the event channel closing.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl SyncError {
+    /// Whether restarting the task can resolve the error.
+    ///
+    /// Verification failures persist as the gateway keeps serving the same data, and database
+    /// errors are shared with the consumer which writes to the same database.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SyncError::Network(_) | SyncError::Other(_) => true,
+            SyncError::Verification(_) | SyncError::Database(_) => false,
+        }
+    }
+}
+
+/// Classifies the error by the first typed error in its chain, keeping the context of the whole
+/// chain.
+impl From<anyhow::Error> for SyncError {
+    fn from(error: anyhow::Error) -> Self {
+        let kind = error.chain().find_map(|cause| {
+            if let Some(sync_error) = cause.downcast_ref::<SyncError>() {
+                let kind: fn(anyhow::Error) -> SyncError = match sync_error {
+                    SyncError::Network(_) => SyncError::Network,
+                    SyncError::Verification(_) => SyncError::Verification,
+                    SyncError::Database(_) => SyncError::Database,
+                    SyncError::Other(_) => SyncError::Other,
+                };
+                Some(kind)
+            } else if cause.is::<SequencerError>() || cause.is::<reqwest::Error>() {
+                Some(SyncError::Network)
+            } else {
+                None
+            }
+        });
+
+        kind.unwrap_or(SyncError::Other)(error)
+    }
+}
+
+impl From<SequencerError> for SyncError {
+    fn from(error: SequencerError) -> Self {
+        SyncError::Network(error.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+    use assert_matches::assert_matches;
+    use starknet_gateway_types::error::{KnownStarknetErrorCode, StarknetError};
+
+    #[test]
+    fn classified_by_chain() {
+        let sequencer_error = SequencerError::StarknetError(StarknetError {
+            code: KnownStarknetErrorCode::BlockNotFound.into(),
+            message: String::new(),
+        });
+        let error = Err::<(), _>(sequencer_error)
+            .context("Download block")
+            .unwrap_err();
+        assert_matches!(SyncError::from(error), SyncError::Network(_));
+
+        let error = Err::<(), _>(SyncError::Verification(anyhow!("Block hash mismatch")))
+            .context("L2 reorg")
+            .unwrap_err();
+        let error = SyncError::from(error);
+        // The context is kept.
+        assert_eq!(error.to_string(), "L2 reorg");
+        assert_matches!(error, SyncError::Verification(_));
+        assert!(!error.is_retryable());
+
+        let error = SyncError::from(anyhow!("Event channel closed"));
+        assert_matches!(error, SyncError::Other(_));
+        assert!(error.is_retryable());
+    }
+}
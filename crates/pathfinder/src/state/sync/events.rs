@@ -0,0 +1,167 @@
+//! The queue of [SyncEvent]s between the L1, L2 and pending producers and the consumer.
+//!
+//! The queue is bounded, so a slow consumer eventually blocks the producers (backpressure).
+//! This is required for blocks, reorgs and classes, which must all be processed in order.
+//! Pending events however only matter until the next one arrives. With
+//! [OverflowStrategy::CoalescePending] the consumer skips a pending event if it is directly
+//! followed by another one, which keeps pending data current while the consumer lags behind.
+//! Pending deltas are never skipped, as they build on the preceding pending event.
+//!
+//! The number of queued events is exported as the `sync_event_queue_depth` gauge, and skipped
+//! pending events are counted by `sync_pending_events_coalesced_total`.
+use pathfinder_common::StateUpdate;
+use starknet_gateway_types::reply::PendingBlock;
+use tokio::sync::mpsc::{self, error::TryRecvError, Receiver, Sender, WeakSender};
+
+use super::SyncEvent;
+
+/// How the consumer handles queued events it cannot keep up with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowStrategy {
+    /// All events are processed, producers wait for space in the queue.
+    #[default]
+    Backpressure,
+    /// Like [OverflowStrategy::Backpressure], but consecutive queued pending events are
+    /// coalesced into the latest one.
+    CoalescePending,
+}
+
+pub(super) struct EventQueue {
+    receiver: Receiver<SyncEvent>,
+    /// Used to measure the queue depth without keeping the queue open.
+    sender: Option<WeakSender<SyncEvent>>,
+    strategy: OverflowStrategy,
+    /// An event received while coalescing, to be returned next.
+    next: Option<SyncEvent>,
+}
+
+impl EventQueue {
+    /// Creates a queue holding up to `capacity` events, and the sender for its producers.
+    pub fn new(capacity: usize, strategy: OverflowStrategy) -> (Sender<SyncEvent>, Self) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let queue = Self {
+            receiver,
+            sender: Some(sender.downgrade()),
+            strategy,
+            next: None,
+        };
+
+        (sender, queue)
+    }
+
+    /// Receives the next event, or [None] once all producers are gone and the queue is empty.
+    pub async fn recv(&mut self) -> Option<SyncEvent> {
+        let event = match self.next.take() {
+            Some(event) => event,
+            None => self.receiver.recv().await?,
+        };
+
+        if let Some(depth) = self.depth() {
+            metrics::gauge!("sync_event_queue_depth", depth as f64);
+        }
+
+        match (event, self.strategy) {
+            (SyncEvent::Pending(pending), OverflowStrategy::CoalescePending) => {
+                Some(self.coalesce(pending))
+            }
+            (event, _) => Some(event),
+        }
+    }
+
+    /// Replaces the pending event with the latest of the directly following queued pending
+    /// events.
+    fn coalesce(&mut self, mut pending: Box<(PendingBlock, StateUpdate)>) -> SyncEvent {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(SyncEvent::Pending(newer)) => {
+                    metrics::increment_counter!("sync_pending_events_coalesced_total");
+                    pending = newer;
+                }
+                Ok(other) => {
+                    self.next = Some(other);
+                    break;
+                }
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+            }
+        }
+
+        SyncEvent::Pending(pending)
+    }
+
+    /// The number of queued events, if known.
+    fn depth(&self) -> Option<usize> {
+        let sender = self.sender.as_ref()?.upgrade()?;
+        let queued = sender.max_capacity() - sender.capacity();
+        Some(queued + usize::from(self.next.is_some()))
+    }
+}
+
+impl From<Receiver<SyncEvent>> for EventQueue {
+    /// A queue without depth metrics, applying backpressure.
+    fn from(receiver: Receiver<SyncEvent>) -> Self {
+        Self {
+            receiver,
+            sender: None,
+            strategy: OverflowStrategy::Backpressure,
+            next: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::{BlockNumber, BlockTimestamp};
+
+    use super::*;
+
+    /// A pending event identified by its timestamp.
+    fn pending(timestamp: u64) -> SyncEvent {
+        let block = PendingBlock {
+            timestamp: BlockTimestamp::new_or_panic(timestamp),
+            ..Default::default()
+        };
+        SyncEvent::Pending(Box::new((block, StateUpdate::default())))
+    }
+
+    fn timestamp(event: SyncEvent) -> u64 {
+        match event {
+            SyncEvent::Pending(pending) => pending.0.timestamp.get(),
+            other => panic!("Expected a pending event, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn coalesces_consecutive_pending_events() {
+        let (sender, mut queue) = EventQueue::new(10, OverflowStrategy::CoalescePending);
+        for event in [
+            pending(1),
+            pending(2),
+            SyncEvent::Reorg(BlockNumber::GENESIS),
+            pending(3),
+            pending(4),
+        ] {
+            sender.send(event).await.unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(timestamp(queue.recv().await.unwrap()), 2);
+        assert_matches::assert_matches!(queue.recv().await, Some(SyncEvent::Reorg(_)));
+        assert_eq!(timestamp(queue.recv().await.unwrap()), 4);
+        assert!(queue.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn backpressure_keeps_all_events() {
+        let (sender, mut queue) = EventQueue::new(10, OverflowStrategy::Backpressure);
+        for i in 1..=3 {
+            sender.send(pending(i)).await.unwrap();
+        }
+        assert_eq!(queue.depth(), Some(3));
+        drop(sender);
+
+        for i in 1..=3 {
+            assert_eq!(timestamp(queue.recv().await.unwrap()), i);
+        }
+        assert!(queue.recv().await.is_none());
+    }
+}
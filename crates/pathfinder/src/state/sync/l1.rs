@@ -1,12 +1,13 @@
 use std::{num::NonZeroU64, time::Duration};
 
+use anyhow::Context;
 use pathfinder_common::Chain;
 use pathfinder_ethereum::{EthereumApi, EthereumStateUpdate};
 use pathfinder_retry::Retry;
 use primitive_types::H160;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
-use crate::state::sync::SyncEvent;
+use crate::state::sync::{SyncError, SyncEvent};
 
 #[derive(Clone)]
 pub struct L1SyncContext<EthereumClient> {
@@ -15,14 +16,24 @@ pub struct L1SyncContext<EthereumClient> {
     /// The Starknet core contract address on Ethereum
     pub core_address: H160,
     pub poll_interval: Duration,
+    /// The latest L1 block of a [subscription](pathfinder_ethereum::watch_new_heads), which
+    /// triggers reading the state instead of polling while it is working.
+    pub new_heads: Option<watch::Receiver<Option<u64>>>,
 }
 
+/// How often the state is read while subscribed to new L1 blocks, in case the subscription
+/// misses blocks.
+const SUBSCRIBED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Syncs L1 state update logs. Emits [Ethereum state update](EthereumStateUpdate)
 /// which should be handled to update storage and respond to queries.
+///
+/// Emits an [L1 reorg](SyncEvent::L1Reorg) if the L1 block at which the latest update was read
+/// is no longer canonical, after which the current update is emitted again.
 pub async fn sync<T>(
     tx_event: mpsc::Sender<SyncEvent>,
     context: L1SyncContext<T>,
-) -> anyhow::Result<()>
+) -> Result<(), SyncError>
 where
     T: EthereumApi + Clone,
 {
@@ -31,11 +42,33 @@ where
         chain: _,
         core_address,
         poll_interval,
+        mut new_heads,
     } = context;
 
     let mut previous = EthereumStateUpdate::default();
 
     loop {
+        if let Some(l1_block) = previous.l1_block {
+            let hash = Retry::exponential(
+                || async { ethereum.get_block_hash(l1_block.number).await },
+                NonZeroU64::new(1).unwrap(),
+            )
+            .factor(NonZeroU64::new(2).unwrap())
+            .max_delay(poll_interval / 2)
+            .when(|_| true)
+            .await
+            .map_err(SyncError::Network)?;
+
+            if hash != Some(l1_block.hash) {
+                tracing::warn!(l1_block=%l1_block.number, "L1 reorg detected");
+                tx_event
+                    .send(SyncEvent::L1Reorg(l1_block.number))
+                    .await
+                    .context("Event channel closed")?;
+                previous = EthereumStateUpdate::default();
+            }
+        }
+
         let state_update = Retry::exponential(
             || async { ethereum.get_starknet_state(&core_address).await },
             NonZeroU64::new(1).unwrap(),
@@ -43,13 +76,34 @@ where
         .factor(NonZeroU64::new(2).unwrap())
         .max_delay(poll_interval / 2)
         .when(|_| true)
-        .await?;
+        .await
+        .map_err(SyncError::Network)?;
 
-        if previous != state_update {
+        if !is_same_update(&previous, &state_update) {
             previous = state_update.clone();
-            tx_event.send(SyncEvent::L1Update(state_update)).await?;
+            tx_event
+                .send(SyncEvent::L1Update(state_update))
+                .await
+                .context("Event channel closed")?;
         }
 
-        tokio::time::sleep(poll_interval).await;
+        match &mut new_heads {
+            Some(heads) if heads.borrow().is_some() => {
+                let _ = tokio::time::timeout(SUBSCRIBED_POLL_INTERVAL, heads.changed()).await;
+            }
+            _ => tokio::time::sleep(poll_interval).await,
+        }
+    }
+}
+
+/// Whether the updates are equal, regardless of the L1 block they were read at. This keeps the
+/// first L1 block an update was read at, as later blocks could be reorged without affecting it.
+fn is_same_update(a: &EthereumStateUpdate, b: &EthereumStateUpdate) -> bool {
+    EthereumStateUpdate {
+        l1_block: None,
+        ..a.clone()
+    } == EthereumStateUpdate {
+        l1_block: None,
+        ..b.clone()
     }
 }
@@ -1,6 +1,6 @@
 use crate::state::block_hash::{verify_block_hash, VerifyResult};
-use crate::state::sync::class::{download_class, DownloadedClass};
-use crate::state::sync::{pending, SyncEvent};
+use crate::state::sync::class::{claim_download, download_class, DownloadedClass};
+use crate::state::sync::{pending, SyncError, SyncEvent};
 use anyhow::{anyhow, Context};
 use pathfinder_common::state_update::ContractClassUpdate;
 use pathfinder_common::{
@@ -26,6 +26,8 @@ pub struct Timings {
     pub state_diff_download: Duration,
     pub class_declaration: Duration,
     pub signature_download: Duration,
+    /// Verifying the block and transaction hashes, which is not part of `block_download`.
+    pub verification: Duration,
 }
 
 /// A cache containing the last `N` blocks in the chain. Used to determine reorg extents
@@ -90,6 +92,7 @@ pub struct L2SyncContext<GatewayClient> {
     pub head_poll_interval: Duration,
     pub pending_poll_interval: Option<Duration>,
     pub block_validation_mode: BlockValidationMode,
+    pub transaction_validation_mode: TransactionValidationMode,
     pub storage: Storage,
 }
 
@@ -98,7 +101,7 @@ pub async fn sync<GatewayClient>(
     context: L2SyncContext<GatewayClient>,
     mut head: Option<(BlockNumber, BlockHash, StateCommitment)>,
     mut blocks: BlockChain,
-) -> anyhow::Result<()>
+) -> Result<(), SyncError>
 where
     GatewayClient: GatewayApi + Clone + Send + 'static,
 {
@@ -110,6 +113,7 @@ where
         head_poll_interval,
         pending_poll_interval,
         block_validation_mode,
+        transaction_validation_mode,
         storage,
     } = context;
 
@@ -125,7 +129,7 @@ where
         let mut next_block = None;
         let mut next_state_update = None;
 
-        let (block, commitments) = loop {
+        let (block, commitments, t_verify) = loop {
             match download_block(
                 next,
                 // Reuse the next full block if we got it for free when polling pending
@@ -135,10 +139,13 @@ where
                 head_meta.map(|h| h.1),
                 &sequencer,
                 block_validation_mode,
+                transaction_validation_mode,
             )
             .await?
             {
-                DownloadBlock::Block(block, commitments) => break (block, commitments),
+                DownloadBlock::Block(block, commitments, verification) => {
+                    break (block, commitments, verification)
+                }
                 DownloadBlock::AtHead => {
                     // Poll pending if it is enabled, otherwise just wait to poll head again.
                     match pending_poll_interval {
@@ -171,6 +178,7 @@ where
                             &tx_event,
                             &sequencer,
                             block_validation_mode,
+                            transaction_validation_mode,
                             &blocks,
                         )
                         .await
@@ -189,7 +197,7 @@ where
                 }
             }
         };
-        let t_block = t_block.elapsed();
+        let t_block = t_block.elapsed().saturating_sub(t_verify);
 
         if let Some(some_head) = &head {
             if some_head.1 != block.parent_block_hash {
@@ -200,6 +208,7 @@ where
                     &tx_event,
                     &sequencer,
                     block_validation_mode,
+                    transaction_validation_mode,
                     &blocks,
                 )
                 .await
@@ -228,18 +237,20 @@ where
                 .with_context(|| format!("Fetch state diff for block {next:?} from sequencer"))?,
         };
 
-        anyhow::ensure!(
-            state_update.block_hash != BlockHash::ZERO,
-            "Gateway returned `pending` state update"
-        );
+        if state_update.block_hash == BlockHash::ZERO {
+            return Err(SyncError::Verification(anyhow!(
+                "Gateway returned `pending` state update"
+            )));
+        }
 
         // An extra sanity check for the state update API.
-        anyhow::ensure!(
-            block_hash == state_update.block_hash,
-            "State update block hash mismatch, actual {:x}, expected {:x}",
-            block_hash.0,
-            state_update.block_hash.0
-        );
+        if block_hash != state_update.block_hash {
+            return Err(SyncError::Verification(anyhow!(
+                "State update block hash mismatch, actual {:x}, expected {:x}",
+                block_hash.0,
+                state_update.block_hash.0
+            )));
+        }
         let t_update = t_update.elapsed();
 
         // Download and emit newly declared classes.
@@ -263,12 +274,13 @@ where
         let t_signature = t_signature.elapsed();
 
         // An extra sanity check for the signature API.
-        anyhow::ensure!(
-            block_hash == signature.signature_input.block_hash,
-            "Signature block hash mismatch, actual {:x}, expected {:x}",
-            signature.signature_input.block_hash.0,
-            block_hash.0,
-        );
+        if block_hash != signature.signature_input.block_hash {
+            return Err(SyncError::Verification(anyhow!(
+                "Signature block hash mismatch, actual {:x}, expected {:x}",
+                signature.signature_input.block_hash.0,
+                block_hash.0,
+            )));
+        }
         let signature = signature.into();
 
         head = Some((next, block_hash, state_update.state_commitment));
@@ -333,6 +345,7 @@ where
             state_diff_download: t_update,
             class_declaration: t_declare,
             signature_download: t_signature,
+            verification: t_verify,
         };
 
         let block_header = BlockHeader::from(block.as_ref());
@@ -423,9 +436,16 @@ pub async fn download_new_classes(
     })
     .await
     .context("Joining database task")?
-    .context("Querying database for missing classes")?;
+    .context("Querying database for missing classes")
+    .map_err(SyncError::Database)?;
 
     for class_hash in require_downloading {
+        // Another sync path, e.g. the pending poller, may be downloading the class concurrently.
+        let Some(claim) = claim_download(class_hash).await else {
+            tracing::trace!(%class_hash, "Class was downloaded concurrently, skipping");
+            continue;
+        };
+
         let class = download_class(sequencer, class_hash, version.clone())
             .await
             .with_context(|| format!("Downloading class {}", class_hash.0))?;
@@ -472,13 +492,20 @@ pub async fn download_new_classes(
                     })?
             }
         }
+
+        claim.complete();
     }
 
     Ok(())
 }
 
 enum DownloadBlock {
-    Block(Box<Block>, (TransactionCommitment, EventCommitment)),
+    /// The block, its commitments and the time spent verifying it.
+    Block(
+        Box<Block>,
+        (TransactionCommitment, EventCommitment),
+        Duration,
+    ),
     AtHead,
     Reorg,
 }
@@ -492,6 +519,17 @@ pub enum BlockValidationMode {
     AllowMismatch,
 }
 
+/// How transactions are handled whose hash doesn't match the hash recomputed from their contents.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum TransactionValidationMode {
+    /// Rejects the block, as the gateway may have altered the transaction.
+    #[default]
+    Strict,
+    /// Only logs a warning.
+    AllowMismatch,
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn download_block(
     block_number: BlockNumber,
     // Poll pending could exit when it encountered a finalized block, so we'd like to reuse it
@@ -501,7 +539,8 @@ async fn download_block(
     prev_block_hash: Option<BlockHash>,
     sequencer: &impl GatewayApi,
     mode: BlockValidationMode,
-) -> anyhow::Result<DownloadBlock> {
+    transaction_mode: TransactionValidationMode,
+) -> Result<DownloadBlock, SyncError> {
     use starknet_gateway_types::{
         error::KnownStarknetErrorCode::BlockNotFound, reply::MaybePendingBlock,
     };
@@ -516,6 +555,7 @@ async fn download_block(
     let result = match result {
         Ok(MaybePendingBlock::Block(block)) => {
             let block = Box::new(block);
+            let t_verify = std::time::Instant::now();
             // Check if block hash is correct.
             let verify_hash = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
                 let block_number = block.block_number;
@@ -535,17 +575,25 @@ async fn download_block(
                     Status::AcceptedOnL1 | Status::AcceptedOnL2,
                     VerifyResult::Match(commitments),
                     _,
-                ) => Ok(DownloadBlock::Block(block, commitments)),
+                ) => Ok(DownloadBlock::Block(block, commitments, t_verify.elapsed())),
                 (Status::AcceptedOnL1 | Status::AcceptedOnL2, VerifyResult::NotVerifiable, _) => {
-                    Ok(DownloadBlock::Block(block, Default::default()))
+                    Ok(DownloadBlock::Block(
+                        block,
+                        Default::default(),
+                        t_verify.elapsed(),
+                    ))
                 }
                 (
                     Status::AcceptedOnL1 | Status::AcceptedOnL2,
                     VerifyResult::Mismatch,
                     BlockValidationMode::AllowMismatch,
-                ) => Ok(DownloadBlock::Block(block, Default::default())),
+                ) => Ok(DownloadBlock::Block(
+                    block,
+                    Default::default(),
+                    t_verify.elapsed(),
+                )),
                 (_, VerifyResult::Mismatch, BlockValidationMode::Strict) => {
-                    Err(anyhow!("Block hash mismatch"))
+                    Err(SyncError::Verification(anyhow!("Block hash mismatch")).into())
                 }
                 _ => Err(anyhow!(
                     "Rejecting block as its status is {}, and only accepted blocks are allowed",
@@ -553,7 +601,7 @@ async fn download_block(
                 )),
             }
         }
-        Ok(MaybePendingBlock::Pending(_)) => anyhow::bail!("Sequencer returned `pending` block"),
+        Ok(MaybePendingBlock::Pending(_)) => Err(anyhow!("Sequencer returned `pending` block")),
         Err(SequencerError::StarknetError(err)) if err.code == BlockNotFound.into() => {
             // This would occur if we queried past the head of the chain. We now need to check that
             // a reorg hasn't put us too far in the future. This does run into race conditions with
@@ -585,9 +633,10 @@ async fn download_block(
     };
 
     match result {
-        Ok(DownloadBlock::Block(block, commitments)) => {
+        Ok(DownloadBlock::Block(block, commitments, verification)) => {
             use rayon::prelude::*;
 
+            let t_verify = std::time::Instant::now();
             let (send, recv) = tokio::sync::oneshot::channel();
 
             rayon::scope(|s| {
@@ -595,10 +644,16 @@ async fn download_block(
                     let result = block.transactions.par_iter().enumerate().try_for_each(|(i, txn)| {
                         match verify(txn, chain_id, block_number) {
                             starknet_gateway_types::transaction_hash::VerifyResult::Match => {}
-                            starknet_gateway_types::transaction_hash::VerifyResult::Mismatch(actual) =>
-                                anyhow::bail!("Transaction hash mismatch: block {block_number} idx {i} expected {} calculated {}",
+                            starknet_gateway_types::transaction_hash::VerifyResult::Mismatch(actual) => match transaction_mode {
+                                TransactionValidationMode::Strict => return Err(SyncError::Verification(anyhow!("Transaction hash mismatch: block {block_number} idx {i} expected {} calculated {}",
+                                    txn.hash(),
+                                    actual))),
+                                TransactionValidationMode::AllowMismatch => tracing::warn!(
+                                    "Transaction hash mismatch: block {block_number} idx {i} expected {} calculated {}",
                                     txn.hash(),
-                                    actual),
+                                    actual
+                                ),
+                            },
                             starknet_gateway_types::transaction_hash::VerifyResult::NotVerifiable => {
                                 tracing::trace!(
                                     "Skipping transaction verification: block {block_number} idx {i} hash {}",
@@ -615,12 +670,17 @@ async fn download_block(
 
             recv.await.expect("Panic on rayon thread")?;
 
-            Ok(DownloadBlock::Block(block, commitments))
+            Ok(DownloadBlock::Block(
+                block,
+                commitments,
+                verification + t_verify.elapsed(),
+            ))
         }
-        Ok(DownloadBlock::AtHead | DownloadBlock::Reorg) | Err(_) => result,
+        Ok(DownloadBlock::AtHead | DownloadBlock::Reorg) | Err(_) => Ok(result?),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn reorg(
     head: &(BlockNumber, BlockHash, StateCommitment),
     chain: Chain,
@@ -628,6 +688,7 @@ async fn reorg(
     tx_event: &mpsc::Sender<SyncEvent>,
     sequencer: &impl GatewayApi,
     mode: BlockValidationMode,
+    transaction_mode: TransactionValidationMode,
     blocks: &BlockChain,
 ) -> anyhow::Result<Option<(BlockNumber, BlockHash, StateCommitment)>> {
     // Go back in history until we find an L2 block that does still exist.
@@ -652,11 +713,12 @@ async fn reorg(
             Some(previous.0),
             sequencer,
             mode,
+            transaction_mode,
         )
         .await
         .with_context(|| format!("Download block {previous_block_number} from sequencer"))?
         {
-            DownloadBlock::Block(block, _) if block.block_hash == previous.0 => {
+            DownloadBlock::Block(block, ..) if block.block_hash == previous.0 => {
                 break Some((previous_block_number, previous.0, previous.1));
             }
             _ => {}
@@ -687,7 +749,9 @@ mod tests {
         use pathfinder_common::BlockCommitmentSignature;
         use pathfinder_common::StateUpdate;
 
-        use super::super::{sync, BlockValidationMode, SyncEvent};
+        use super::super::{
+            sync, BlockValidationMode, SyncError, SyncEvent, TransactionValidationMode,
+        };
         use assert_matches::assert_matches;
         use pathfinder_common::{
             BlockHash, BlockId, BlockNumber, BlockTimestamp, Chain, ChainId, ClassHash,
@@ -867,7 +931,7 @@ mod tests {
         fn spawn_sync_default(
             tx_event: mpsc::Sender<SyncEvent>,
             sequencer: MockGatewayApi,
-        ) -> JoinHandle<anyhow::Result<()>> {
+        ) -> JoinHandle<Result<(), SyncError>> {
             let storage = Storage::in_memory().unwrap();
             let sequencer = std::sync::Arc::new(sequencer);
             let context = L2SyncContext {
@@ -878,6 +942,7 @@ mod tests {
                 head_poll_interval: Duration::ZERO,
                 pending_poll_interval: None,
                 block_validation_mode: MODE,
+                transaction_validation_mode: TransactionValidationMode::Strict,
                 storage,
             };
 
@@ -1236,6 +1301,7 @@ mod tests {
                     head_poll_interval: Duration::ZERO,
                     pending_poll_interval: None,
                     block_validation_mode: MODE,
+                    transaction_validation_mode: TransactionValidationMode::Strict,
                     storage: Storage::in_memory().unwrap(),
                 };
 
@@ -1281,6 +1347,55 @@ mod tests {
                     &error.to_string(),
                     "Rejecting block as its status is REVERTED, and only accepted blocks are allowed"
                 );
+                assert!(error.is_retryable());
+            }
+
+            #[tokio::test]
+            async fn gateway_error_is_retryable() {
+                let (tx_event, _rx_event) = tokio::sync::mpsc::channel(1);
+                let mut mock = MockGatewayApi::new();
+                let mut seq = mockall::Sequence::new();
+
+                expect_block(
+                    &mut mock,
+                    &mut seq,
+                    BLOCK0_NUMBER.into(),
+                    Err(SequencerError::StarknetError(StarknetError {
+                        code: KnownStarknetErrorCode::TransactionLimitExceeded.into(),
+                        message: String::new(),
+                    })),
+                );
+
+                let jh = spawn_sync_default(tx_event, mock);
+                let error = jh.await.unwrap().unwrap_err();
+                assert_matches!(error, SyncError::Network(_));
+                assert!(error.is_retryable());
+            }
+
+            #[tokio::test]
+            async fn state_update_mismatch_is_fatal() {
+                let (tx_event, _rx_event) = tokio::sync::mpsc::channel(1);
+                let mut mock = MockGatewayApi::new();
+                let mut seq = mockall::Sequence::new();
+
+                expect_block(
+                    &mut mock,
+                    &mut seq,
+                    BLOCK0_NUMBER.into(),
+                    Ok(BLOCK0.clone().into()),
+                );
+                // The state update of another block.
+                expect_state_update(
+                    &mut mock,
+                    &mut seq,
+                    BLOCK0_HASH.into(),
+                    Ok(STATE_UPDATE1.clone()),
+                );
+
+                let jh = spawn_sync_default(tx_event, mock);
+                let error = jh.await.unwrap().unwrap_err();
+                assert_matches!(error, SyncError::Verification(_));
+                assert!(!error.is_retryable());
             }
         }
 
@@ -2438,4 +2553,130 @@ mod tests {
             assert!(uut.get(&BlockNumber::new_or_panic(3)).is_none());
         }
     }
+
+    mod mock_sequencer {
+        use super::super::{
+            sync, BlockChain, BlockValidationMode, L2SyncContext, SyncEvent,
+            TransactionValidationMode,
+        };
+        use pathfinder_common::{BlockHash, BlockNumber, Chain, ChainId};
+        use pathfinder_storage::Storage;
+        use starknet_gateway_client::testing::MockSequencer;
+        use std::time::Duration;
+        use tokio::sync::mpsc;
+
+        /// Syncs from the sequencer and returns a receiver of the sync events.
+        fn spawn_sync(sequencer: MockSequencer) -> mpsc::Receiver<SyncEvent> {
+            let (tx_event, rx_event) = mpsc::channel(1);
+            let context = L2SyncContext {
+                broadcasters: None,
+                sequencer,
+                chain: Chain::Testnet,
+                chain_id: ChainId::TESTNET,
+                head_poll_interval: Duration::from_millis(1),
+                pending_poll_interval: None,
+                block_validation_mode: BlockValidationMode::AllowMismatch,
+                transaction_validation_mode: TransactionValidationMode::Strict,
+                storage: Storage::in_memory().unwrap(),
+            };
+
+            tokio::spawn(sync(
+                tx_event,
+                context,
+                None,
+                BlockChain::with_capacity(100, vec![]),
+            ));
+
+            rx_event
+        }
+
+        /// Receives the next event, which must be a block, and returns its number and hash.
+        async fn next_block(rx: &mut mpsc::Receiver<SyncEvent>) -> (BlockNumber, BlockHash) {
+            match rx.recv().await.unwrap() {
+                SyncEvent::Block((block, _), ..) => (block.block_number, block.block_hash),
+                other => panic!("Expected a block, got {other:?}"),
+            }
+        }
+
+        async fn next_reorg(rx: &mut mpsc::Receiver<SyncEvent>) -> BlockNumber {
+            match rx.recv().await.unwrap() {
+                SyncEvent::Reorg(tail) => tail,
+                other => panic!("Expected a reorg, got {other:?}"),
+            }
+        }
+
+        #[tokio::test]
+        async fn fork() {
+            let sequencer = MockSequencer::new();
+            let mut hashes = (0..3)
+                .map(|_| sequencer.produce_block())
+                .collect::<Vec<_>>();
+
+            let mut rx = spawn_sync(sequencer.clone());
+            for (i, hash) in hashes.iter().enumerate() {
+                assert_eq!(
+                    next_block(&mut rx).await,
+                    (BlockNumber::new_or_panic(i as u64), *hash)
+                );
+            }
+
+            // Replace blocks 1 and 2 with a longer fork.
+            sequencer.fork(BlockNumber::new_or_panic(1), 3);
+            hashes.truncate(1);
+            hashes.extend((1..4).map(|i| {
+                sequencer
+                    .block_at(BlockNumber::new_or_panic(i))
+                    .unwrap()
+                    .block
+                    .block_hash
+            }));
+
+            assert_eq!(next_reorg(&mut rx).await, BlockNumber::new_or_panic(1));
+            for (i, hash) in hashes.iter().enumerate().skip(1) {
+                assert_eq!(
+                    next_block(&mut rx).await,
+                    (BlockNumber::new_or_panic(i as u64), *hash)
+                );
+            }
+        }
+
+        #[tokio::test]
+        async fn reorg_to_genesis() {
+            let sequencer = MockSequencer::new();
+            for _ in 0..3 {
+                sequencer.produce_block();
+            }
+
+            let mut rx = spawn_sync(sequencer.clone());
+            for _ in 0..3 {
+                next_block(&mut rx).await;
+            }
+
+            sequencer.fork(BlockNumber::GENESIS, 1);
+            let genesis = sequencer.latest().unwrap();
+
+            assert_eq!(next_reorg(&mut rx).await, BlockNumber::GENESIS);
+            assert_eq!(next_block(&mut rx).await, genesis);
+        }
+
+        #[tokio::test]
+        async fn reorg_at_head() {
+            let sequencer = MockSequencer::new();
+            for _ in 0..2 {
+                sequencer.produce_block();
+            }
+
+            let mut rx = spawn_sync(sequencer.clone());
+            for _ in 0..2 {
+                next_block(&mut rx).await;
+            }
+
+            // Replace only the head, with a block at the same height.
+            sequencer.fork(BlockNumber::new_or_panic(1), 1);
+            let head = sequencer.latest().unwrap();
+
+            assert_eq!(next_reorg(&mut rx).await, BlockNumber::new_or_panic(1));
+            assert_eq!(next_block(&mut rx).await, head);
+        }
+    }
 }
@@ -1,14 +1,88 @@
 use anyhow::Context;
 use pathfinder_common::BlockId;
+use pathfinder_common::SierraHash;
 use pathfinder_common::StateUpdate;
+use pathfinder_common::TransactionHash;
 use pathfinder_storage::Storage;
 use starknet_gateway_client::GatewayApi;
+use starknet_gateway_types::reply::transaction::Transaction;
 use starknet_gateway_types::reply::Block;
 use starknet_gateway_types::reply::MaybePendingBlock;
+use starknet_gateway_types::reply::PendingBlock;
 use tokio::time::Instant;
+use tracing::Instrument;
 
 use crate::state::sync::SyncEvent;
 
+/// The number of times pending data is fetched within a poll while the block and state update are
+/// inconsistent, see [consistent].
+const MAX_INCONSISTENT_FETCHES: usize = 3;
+
+/// The growth of the pending block since the previous pending event.
+///
+/// The pending block only ever grows until it is replaced, so consumers can apply deltas instead
+/// of reprocessing the whole block on every poll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingDelta {
+    /// The number of transactions of the pending block this delta applies to.
+    pub base_transaction_count: usize,
+    /// The updated pending block, containing only the new transactions and receipts. The
+    /// candidate transactions are not a delta, they replace the previous ones.
+    pub block: PendingBlock,
+    /// The state changes since the previous pending state update.
+    pub state_update: StateUpdate,
+}
+
+impl PendingDelta {
+    /// The delta of a pending block whose first `base_transaction_count` transactions were
+    /// emitted before.
+    fn new(
+        mut block: PendingBlock,
+        base_transaction_count: usize,
+        state_update: StateUpdate,
+    ) -> Self {
+        let transactions = block.transactions.split_off(base_transaction_count);
+        let transaction_receipts = block
+            .transaction_receipts
+            .split_off(base_transaction_count.min(block.transaction_receipts.len()));
+
+        Self {
+            base_transaction_count,
+            block: PendingBlock {
+                transactions,
+                transaction_receipts,
+                ..block
+            },
+            state_update,
+        }
+    }
+
+    /// Applies the delta to the pending block and state update it was computed from.
+    ///
+    /// Returns false, leaving them unchanged, if they are not the delta's base.
+    pub fn apply_to(self, block: &mut PendingBlock, state_update: &mut StateUpdate) -> bool {
+        if block.parent_hash != self.block.parent_hash
+            || block.transactions.len() != self.base_transaction_count
+        {
+            return false;
+        }
+
+        let mut transactions = std::mem::take(&mut block.transactions);
+        let mut transaction_receipts = std::mem::take(&mut block.transaction_receipts);
+        transactions.extend(self.block.transactions);
+        transaction_receipts.extend(self.block.transaction_receipts);
+
+        *block = PendingBlock {
+            transactions,
+            transaction_receipts,
+            ..self.block
+        };
+        state_update.apply(self.state_update);
+
+        true
+    }
+}
+
 /// Poll's the Sequencer's pending block and emits [pending events](SyncEvent::Pending)
 /// until the pending block is no longer connected to our current head.
 ///
@@ -17,6 +91,10 @@ use crate::state::sync::SyncEvent;
 /// - `pending` is a fully formed block and not [PendingBlock](starknet_gateway_types::reply::MaybePendingBlock::Pending), or
 /// - the state update parent root does not match head.
 ///
+/// The first pending update is emitted in full, later updates are emitted as [deltas](PendingDelta)
+/// if they extend the previous one. Updates which only change the candidate transactions are
+/// emitted as deltas without transactions.
+///
 /// A full block or full state update can be returned from this function if it is encountered during polling.
 pub async fn poll_pending<S: GatewayApi + Clone + Send + 'static>(
     tx_event: tokio::sync::mpsc::Sender<SyncEvent>,
@@ -28,22 +106,34 @@ pub async fn poll_pending<S: GatewayApi + Clone + Send + 'static>(
     poll_interval: std::time::Duration,
     storage: Storage,
 ) -> anyhow::Result<(Option<Block>, Option<StateUpdate>)> {
-    // The transaction count of the last emitted pending block. This is used
-    // as a proxy for freshness of the pending data. Feeder gateways are not 100%
-    // in sync wrt pending data, and as a result it is possible for us to receive
-    // pending data which is older than the one we received previously.
-    let mut prev_tx_count = 0;
+    // The transactions, candidate transactions and state update of the last emitted pending
+    // block. The transaction count is used as a proxy for freshness of the pending data. Feeder
+    // gateways are not 100% in sync wrt pending data, and as a result it is possible for us to
+    // receive pending data which is older than the one we received previously.
+    let mut previous: Option<(Vec<TransactionHash>, Vec<TransactionHash>, StateUpdate)> = None;
 
     loop {
         let t_fetch = Instant::now();
+        let span = tracing::debug_span!(
+            "pending_poll",
+            transaction_count = tracing::field::Empty,
+            fetch = tracing::field::Empty,
+        );
 
-        // Fetches the pending block _and_ state update in a single request.
-        // Starknet 0.12.2 introduced a feeder gateway API for fetching both the block and the state update, so
-        // that we get _consistent_ data.
-        let (block, state_update) = sequencer
-            .state_update_with_block(BlockId::Pending)
-            .await
-            .context("Downloading pending block and state update")?;
+        let Some((block, state_update)) =
+            fetch_consistent(sequencer).instrument(span.clone()).await?
+        else {
+            span.in_scope(|| {
+                tracing::debug!("Pending block and state update remained inconsistent, skipping")
+            });
+            tokio::time::sleep_until(t_fetch + poll_interval).await;
+            continue;
+        };
+
+        span.record("fetch", t_fetch.elapsed().as_secs_f64());
+        if let MaybePendingBlock::Pending(pending) = &block {
+            span.record("transaction_count", pending.transactions.len());
+        }
 
         match block {
             MaybePendingBlock::Block(block) if block.block_hash == head.0 => {
@@ -70,28 +160,58 @@ pub async fn poll_pending<S: GatewayApi + Clone + Send + 'static>(
                 );
                 return Ok((None, None));
             }
-            MaybePendingBlock::Pending(block) if block.transactions.len() > prev_tx_count => {
+            MaybePendingBlock::Pending(block)
+                if is_newer(
+                    &block,
+                    previous.as_ref().map(|p| (p.0.as_slice(), p.1.as_slice())),
+                ) =>
+            {
+                let changes = previous
+                    .as_ref()
+                    .filter(|(transactions, ..)| extends(&block, transactions))
+                    .map(|(transactions, _, previous_state_update)| {
+                        (
+                            transactions.len(),
+                            state_update.changes_since(previous_state_update),
+                        )
+                    });
+
                 // Download, process and emit all missing classes. This can occasionally
                 // fail when querying a desync'd feeder gateway which isn't aware of the
                 // new pending classes. In this case, ignore the new pending data as it
                 // is incomplete.
                 if let Err(e) = super::l2::download_new_classes(
-                    &state_update,
+                    changes.as_ref().map(|c| &c.1).unwrap_or(&state_update),
                     sequencer,
                     &tx_event,
                     &block.starknet_version,
                     storage.clone(),
                 )
+                .instrument(span.clone())
                 .await
                 {
-                    tracing::debug!(reason=?e, "Failed to download pending classes");
+                    span.in_scope(
+                        || tracing::debug!(reason=?e, "Failed to download pending classes"),
+                    );
                 } else {
-                    prev_tx_count = block.transactions.len();
-                    tracing::trace!("Emitting a pending update");
-                    tx_event
-                        .send(SyncEvent::Pending(Box::new((block, state_update))))
-                        .await
-                        .context("Event channel closed")?;
+                    let transactions = block.transactions.iter().map(|tx| tx.hash()).collect();
+                    let candidates = candidate_hashes(&block).collect();
+                    let event = match changes {
+                        Some((base_transaction_count, changes)) => {
+                            span.in_scope(|| tracing::debug!("Emitting a pending delta"));
+                            SyncEvent::PendingDelta(Box::new(PendingDelta::new(
+                                block,
+                                base_transaction_count,
+                                changes,
+                            )))
+                        }
+                        None => {
+                            span.in_scope(|| tracing::debug!("Emitting a pending update"));
+                            SyncEvent::Pending(Box::new((block, state_update.clone())))
+                        }
+                    };
+                    previous = Some((transactions, candidates, state_update));
+                    tx_event.send(event).await.context("Event channel closed")?;
                 }
             }
             MaybePendingBlock::Pending(_) => {
@@ -104,6 +224,113 @@ pub async fn poll_pending<S: GatewayApi + Clone + Send + 'static>(
     }
 }
 
+/// Fetches the pending block and state update, re-fetching them up to [MAX_INCONSISTENT_FETCHES]
+/// times while they are [inconsistent](consistent).
+///
+/// Returns [None] if they remained inconsistent.
+async fn fetch_consistent<S: GatewayApi>(
+    sequencer: &S,
+) -> anyhow::Result<Option<(MaybePendingBlock, StateUpdate)>> {
+    for _ in 0..MAX_INCONSISTENT_FETCHES {
+        // Fetches the pending block _and_ state update in a single request.
+        // Starknet 0.12.2 introduced a feeder gateway API for fetching both the block and the state update, so
+        // that we get _consistent_ data. The client falls back to separate requests for older gateways, which
+        // is what the consistency check guards against.
+        let (block, state_update) = sequencer
+            .state_update_with_block(BlockId::Pending)
+            .await
+            .context("Downloading pending block and state update")?;
+
+        match &block {
+            MaybePendingBlock::Pending(pending) if !consistent(pending, &state_update) => {
+                tracing::debug!(
+                    transaction_count = pending.transactions.len(),
+                    "Pending block and state update are inconsistent, re-fetching"
+                );
+            }
+            _ => return Ok(Some((block, state_update))),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether the pending state update matches the pending block, i.e. they are from the same
+/// snapshot of the pending block.
+///
+/// The classes declared by the state update must be exactly those declared by the block's
+/// transactions, and the accounts deployed by the block's transactions must be deployed by the
+/// state update.
+fn consistent(block: &PendingBlock, state_update: &StateUpdate) -> bool {
+    let mut declared = 0;
+    for transaction in &block.transactions {
+        match transaction {
+            Transaction::Declare(declare) => {
+                let class_hash = declare.class_hash();
+                if !state_update.declared_cairo_classes.contains(&class_hash)
+                    && !state_update
+                        .declared_sierra_classes
+                        .contains_key(&SierraHash(class_hash.0))
+                {
+                    return false;
+                }
+                declared += 1;
+            }
+            Transaction::DeployAccount(deploy) => {
+                let deployed = state_update
+                    .contract_updates
+                    .get(&deploy.contract_address())
+                    .is_some_and(|update| update.class.is_some());
+                if !deployed {
+                    return false;
+                }
+            }
+            // Deploy transactions declared their class implicitly, so the declared classes can't
+            // be matched against the transactions.
+            Transaction::Deploy(_) => return true,
+            Transaction::Invoke(_) | Transaction::L1Handler(_) => {}
+        }
+    }
+
+    let declared_classes =
+        state_update.declared_cairo_classes.len() + state_update.declared_sierra_classes.len();
+    declared_classes <= declared
+}
+
+/// Whether the pending block starts with the given transactions.
+/// Whether the pending block is newer than the previously emitted one with the `previous`
+/// transactions and candidate transactions.
+///
+/// It is newer if it has more transactions, or the same transactions and different candidate
+/// transactions, as the sequencer replaces the candidates without executing further transactions.
+fn is_newer(
+    block: &PendingBlock,
+    previous: Option<(&[TransactionHash], &[TransactionHash])>,
+) -> bool {
+    let (transactions, candidates) = previous.unwrap_or_default();
+
+    match block.transactions.len().cmp(&transactions.len()) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Equal => {
+            extends(block, transactions) && candidate_hashes(block).ne(candidates.iter().copied())
+        }
+        std::cmp::Ordering::Less => false,
+    }
+}
+
+fn candidate_hashes(block: &PendingBlock) -> impl Iterator<Item = TransactionHash> + '_ {
+    block.candidate_transactions.iter().map(|tx| tx.hash())
+}
+
+fn extends(block: &PendingBlock, transactions: &[TransactionHash]) -> bool {
+    block.transactions.len() >= transactions.len()
+        && block
+            .transactions
+            .iter()
+            .zip(transactions)
+            .all(|(tx, hash)| tx.hash() == *hash)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -163,6 +390,7 @@ mod tests {
                     },
                 )
             ],
+            candidate_transactions: vec![],
             starknet_version: StarknetVersion::default(),
         };
     );
@@ -313,6 +541,48 @@ mod tests {
         assert_matches!(result, SyncEvent::Pending(x) if x.0 == *PENDING_BLOCK && x.1 == *PENDING_UPDATE);
     }
 
+    #[tokio::test]
+    async fn refetches_inconsistent_state_update() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut sequencer = MockGatewayApi::new();
+
+        // The first state update declares a class which is not declared by the block.
+        let count = std::sync::atomic::AtomicUsize::new(0);
+        sequencer
+            .expect_state_update_with_block()
+            .returning(move |_| {
+                let state_update = match count.fetch_add(1, std::sync::atomic::Ordering::Relaxed) {
+                    0 => PENDING_UPDATE
+                        .clone()
+                        .with_declared_cairo_class(class_hash!("0xdead")),
+                    _ => PENDING_UPDATE.clone(),
+                };
+                Ok((
+                    MaybePendingBlock::Pending(PENDING_BLOCK.clone()),
+                    state_update,
+                ))
+            });
+
+        let sequencer = Arc::new(sequencer);
+        let _jh = tokio::spawn(async move {
+            poll_pending(
+                tx,
+                &sequencer,
+                (PARENT_HASH, PARENT_ROOT),
+                std::time::Duration::ZERO,
+                Storage::in_memory().unwrap(),
+            )
+            .await
+        });
+
+        let result = tokio::time::timeout(TEST_TIMEOUT, rx.recv())
+            .await
+            .expect("Event should be emitted")
+            .unwrap();
+
+        assert_matches!(result, SyncEvent::Pending(x) if x.0 == *PENDING_BLOCK && x.1 == *PENDING_UPDATE);
+    }
+
     #[tokio::test]
     async fn ignores_inconsistent_gateway_blocks() {
         // In this test the gateway mock sends inconsistent block data.
@@ -396,6 +666,87 @@ mod tests {
             .expect("Event should be emitted")
             .unwrap();
 
-        assert_matches!(result2, SyncEvent::Pending(x) if x.0 == b1 && x.1 == *PENDING_UPDATE);
+        // The second update extends the first one, so only the new transaction is emitted.
+        let SyncEvent::PendingDelta(delta) = result2 else {
+            panic!("Expected a pending delta, got {result2:?}");
+        };
+        assert_eq!(delta.base_transaction_count, 2);
+        assert_eq!(delta.block.transactions, b1.transactions[2..]);
+        assert_eq!(delta.state_update.change_count(), 0);
+
+        let (mut block, mut state_update) = (b0, PENDING_UPDATE.clone());
+        assert!(delta.apply_to(&mut block, &mut state_update));
+        assert_eq!(block, b1);
+        assert_eq!(state_update, *PENDING_UPDATE);
+    }
+
+    #[tokio::test]
+    async fn emits_changed_candidates() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut sequencer = MockGatewayApi::new();
+
+        // Only the candidate transactions change after the second poll.
+        let mut with_candidate = PENDING_BLOCK.clone();
+        with_candidate.candidate_transactions.push(
+            starknet_gateway_types::reply::transaction::Transaction::L1Handler(
+                L1HandlerTransaction {
+                    contract_address: contract_address!("0x1"),
+                    entry_point_selector: entry_point!("0x55"),
+                    nonce: transaction_nonce!("0x3"),
+                    calldata: Vec::new(),
+                    transaction_hash: transaction_hash!("0x33"),
+                    version: TransactionVersion::ONE,
+                },
+            ),
+        );
+        let with_candidate_copy = with_candidate.clone();
+
+        let count = std::sync::atomic::AtomicUsize::new(0);
+        sequencer
+            .expect_state_update_with_block()
+            .returning(move |_| {
+                let block = match count.fetch_add(1, std::sync::atomic::Ordering::Relaxed) {
+                    0 | 1 => PENDING_BLOCK.clone(),
+                    _ => with_candidate_copy.clone(),
+                };
+                Ok((MaybePendingBlock::Pending(block), PENDING_UPDATE.clone()))
+            });
+
+        let sequencer = Arc::new(sequencer);
+        let _jh = tokio::spawn(async move {
+            poll_pending(
+                tx,
+                &sequencer,
+                (PARENT_HASH, PARENT_ROOT),
+                std::time::Duration::ZERO,
+                Storage::in_memory().unwrap(),
+            )
+            .await
+        });
+
+        let result1 = tokio::time::timeout(TEST_TIMEOUT, rx.recv())
+            .await
+            .expect("Event should be emitted")
+            .unwrap();
+        assert_matches!(result1, SyncEvent::Pending(x) if x.0 == *PENDING_BLOCK);
+
+        // The unchanged second poll is not emitted.
+        let result2 = tokio::time::timeout(TEST_TIMEOUT, rx.recv())
+            .await
+            .expect("Event should be emitted")
+            .unwrap();
+        let SyncEvent::PendingDelta(delta) = result2 else {
+            panic!("Expected a pending delta, got {result2:?}");
+        };
+        assert_eq!(delta.base_transaction_count, 1);
+        assert!(delta.block.transactions.is_empty());
+        assert_eq!(
+            delta.block.candidate_transactions,
+            with_candidate.candidate_transactions
+        );
+
+        let (mut block, mut state_update) = (PENDING_BLOCK.clone(), PENDING_UPDATE.clone());
+        assert!(delta.apply_to(&mut block, &mut state_update));
+        assert_eq!(block, with_candidate);
     }
 }
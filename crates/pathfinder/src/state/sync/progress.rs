@@ -0,0 +1,227 @@
+//! Aggregated progress reporting while catching up to the chain head.
+
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+
+use pathfinder_common::BlockNumber;
+
+/// Blocks behind the head above which sync is considered to be catching up, and per-block logs
+/// are replaced by the periodic progress report.
+const CATCHING_UP_THRESHOLD: u64 = 10;
+
+/// Time spent per sync stage.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) struct StageTimings {
+    /// Downloading the block, state diff and signature.
+    pub download: Duration,
+    /// Downloading and compiling declared classes.
+    pub classes: Duration,
+    pub tries: Duration,
+    pub commit: Duration,
+}
+
+impl StageTimings {
+    /// The stage which took the longest.
+    fn dominant(&self) -> &'static str {
+        [
+            (self.download, "download"),
+            (self.classes, "classes"),
+            (self.tries, "tries"),
+            (self.commit, "commit"),
+        ]
+        .into_iter()
+        .max_by_key(|(duration, _)| *duration)
+        .map(|(_, stage)| stage)
+        .unwrap_or("download")
+    }
+}
+
+impl AddAssign for StageTimings {
+    fn add_assign(&mut self, rhs: Self) {
+        self.download += rhs.download;
+        self.classes += rhs.classes;
+        self.tries += rhs.tries;
+        self.commit += rhs.commit;
+    }
+}
+
+/// Periodically logs a summary of sync progress while catching up to the chain head.
+pub(super) struct ProgressReporter {
+    interval: Duration,
+    window_start: Instant,
+    blocks: u64,
+    stages: StageTimings,
+}
+
+impl ProgressReporter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            window_start: Instant::now(),
+            blocks: 0,
+            stages: Default::default(),
+        }
+    }
+
+    /// Records a synced block, logging a report if the interval has elapsed.
+    ///
+    /// Returns `true` if sync is catching up, in which case the block is covered by the report
+    /// and should not be logged individually.
+    pub fn record(
+        &mut self,
+        number: BlockNumber,
+        highest: Option<BlockNumber>,
+        stages: StageTimings,
+    ) -> bool {
+        let Some(highest) = highest.filter(|h| h.get() > number.get() + CATCHING_UP_THRESHOLD)
+        else {
+            self.reset();
+            return false;
+        };
+
+        self.blocks += 1;
+        self.stages += stages;
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= self.interval {
+            let report = Report::new(number, highest, self.blocks, elapsed);
+            tracing::info!(
+                stage = self.stages.dominant(),
+                "Syncing block {number} of {highest} ({:.1}%) at {:.1} blocks/s, ETA {}",
+                report.percentage,
+                report.blocks_per_second,
+                report
+                    .eta
+                    .map(format_duration)
+                    .unwrap_or_else(|| "unknown".to_owned()),
+            );
+            tracing::debug!(
+                download = self.stages.download.as_secs_f64(),
+                classes = self.stages.classes.as_secs_f64(),
+                tries = self.stages.tries.as_secs_f64(),
+                commit = self.stages.commit.as_secs_f64(),
+                "Time spent per stage since the last report"
+            );
+            self.reset();
+        }
+
+        true
+    }
+
+    fn reset(&mut self) {
+        self.window_start = Instant::now();
+        self.blocks = 0;
+        self.stages = Default::default();
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Report {
+    percentage: f64,
+    blocks_per_second: f64,
+    eta: Option<Duration>,
+}
+
+impl Report {
+    fn new(number: BlockNumber, highest: BlockNumber, blocks: u64, elapsed: Duration) -> Self {
+        let percentage = match highest.get() {
+            0 => 100.0,
+            highest => number.get() as f64 * 100.0 / highest as f64,
+        };
+        let blocks_per_second = blocks as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        let remaining = highest.get().saturating_sub(number.get());
+        let eta = (blocks_per_second > 0.0)
+            .then(|| Duration::from_secs_f64(remaining as f64 / blocks_per_second));
+
+        Self {
+            percentage,
+            blocks_per_second,
+            eta,
+        }
+    }
+}
+
+/// Formats the duration with a resolution of seconds, e.g. `1d 2h 3m 4s`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes, seconds) = (
+        seconds / 86400,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    );
+
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{seconds}s"),
+        (0, 0, _) => format!("{minutes}m {seconds}s"),
+        (0, _, _) => format!("{hours}h {minutes}m {seconds}s"),
+        _ => format!("{days}d {hours}h {minutes}m {seconds}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let report = Report::new(
+            BlockNumber::new_or_panic(250),
+            BlockNumber::new_or_panic(1000),
+            50,
+            Duration::from_secs(10),
+        );
+
+        let expected = Report {
+            percentage: 25.0,
+            blocks_per_second: 5.0,
+            eta: Some(Duration::from_secs(150)),
+        };
+        assert_eq!(report, expected);
+    }
+
+    #[test]
+    fn duration_formatting() {
+        assert_eq!(format_duration(Duration::from_secs(5)), "5s");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1m 5s");
+        assert_eq!(format_duration(Duration::from_secs(3605)), "1h 0m 5s");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 86400 + 3 * 3600 + 4 * 60 + 5)),
+            "2d 3h 4m 5s"
+        );
+    }
+
+    #[test]
+    fn per_block_logging_at_head() {
+        let mut reporter = ProgressReporter::new(Duration::ZERO);
+        let stages = StageTimings::default();
+
+        let catching_up = reporter.record(
+            BlockNumber::new_or_panic(10),
+            Some(BlockNumber::new_or_panic(1000)),
+            stages,
+        );
+        assert!(catching_up);
+
+        let catching_up = reporter.record(
+            BlockNumber::new_or_panic(995),
+            Some(BlockNumber::new_or_panic(1000)),
+            stages,
+        );
+        assert!(!catching_up);
+
+        let catching_up = reporter.record(BlockNumber::new_or_panic(995), None, stages);
+        assert!(!catching_up);
+    }
+
+    #[test]
+    fn dominant_stage() {
+        let stages = StageTimings {
+            download: Duration::from_secs(1),
+            classes: Duration::from_secs(2),
+            tries: Duration::from_secs(3),
+            commit: Duration::ZERO,
+        };
+        assert_eq!(stages.dominant(), "tries");
+    }
+}
@@ -0,0 +1,87 @@
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use rand::Rng;
+
+/// The delay the exponential backoff is capped at, unless the base delay is longer.
+const MAX_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// Decides whether and when a failed sync task is restarted.
+///
+/// The delay doubles with each consecutive failure, starting at the base delay, and is jittered
+/// so that nodes sharing a gateway don't restart in lockstep. Once the maximum number of
+/// consecutive failures is reached the circuit breaker trips, and the task is no longer
+/// restarted.
+pub(super) struct RestartPolicy {
+    base_delay: Duration,
+    max_failures: NonZeroUsize,
+    failures: usize,
+}
+
+impl RestartPolicy {
+    pub fn new(base_delay: Duration, max_failures: NonZeroUsize) -> Self {
+        Self {
+            base_delay,
+            max_failures,
+            failures: 0,
+        }
+    }
+
+    /// Resets the consecutive failures, as the task made progress since its last restart.
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Records a failure, and returns the delay before restarting the task or [None] if the
+    /// circuit breaker tripped.
+    pub fn failed(&mut self) -> Option<Duration> {
+        self.failures += 1;
+        if self.failures >= self.max_failures.get() {
+            return None;
+        }
+
+        let exponent = (self.failures - 1).min(31) as u32;
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(MAX_DELAY.max(self.base_delay));
+
+        Some(delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff() {
+        let base = Duration::from_secs(60);
+        let mut policy = RestartPolicy::new(base, NonZeroUsize::new(100).unwrap());
+
+        for expected in [base, base * 2, base * 4, base * 8, MAX_DELAY, MAX_DELAY] {
+            let delay = policy.failed().unwrap();
+            assert!(delay <= expected, "{delay:?} exceeds {expected:?}");
+            assert!(
+                delay >= expected / 2,
+                "{delay:?} below half of {expected:?}"
+            );
+        }
+
+        policy.reset();
+        assert!(policy.failed().unwrap() <= base);
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let mut policy = RestartPolicy::new(Duration::ZERO, NonZeroUsize::new(3).unwrap());
+
+        assert_eq!(policy.failed(), Some(Duration::ZERO));
+        assert_eq!(policy.failed(), Some(Duration::ZERO));
+        assert_eq!(policy.failed(), None);
+
+        // Progress closes the circuit again.
+        policy.reset();
+        assert_eq!(policy.failed(), Some(Duration::ZERO));
+    }
+}
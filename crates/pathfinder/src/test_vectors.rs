@@ -0,0 +1,157 @@
+//! Generation of conformance test vectors, i.e. the JSON which the RPC API serves for a block, so
+//! that SDK authors can validate their deserializers against it.
+//!
+//! The responses are requested from an RPC server running on the node's storage and executor, so
+//! they are exactly what the node serves. Each response is written to its own file in the
+//! output directory, e.g. `starknet_getStateUpdate.json`, containing the method, its params and
+//! either its result or its error. Receipts are written to one file per transaction, e.g.
+//! `starknet_getTransactionReceipt.0.json`. Object keys are sorted, so that the files of
+//! different nodes and versions can be diffed.
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use pathfinder_common::BlockNumber;
+use pathfinder_rpc::context::RpcContext;
+use pathfinder_rpc::{DefaultVersion, RpcServer};
+use pathfinder_rpc_client::{Client, Error};
+use serde_json::{json, Value};
+
+/// The version of the RPC API the vectors are generated for.
+pub const RPC_VERSION: &str = "v0.5";
+
+/// The methods which are requested for the block itself.
+const BLOCK_METHODS: &[&str] = &[
+    "starknet_getBlockWithTxHashes",
+    "starknet_getBlockWithTxs",
+    "starknet_getStateUpdate",
+    "starknet_traceBlockTransactions",
+];
+
+/// Writes the test vectors of the block to the output directory, which is created if missing.
+///
+/// Returns the files written.
+pub async fn generate(
+    context: RpcContext,
+    block: BlockNumber,
+    output: &Path,
+) -> anyhow::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(output).context("Creating output directory")?;
+
+    let (server_handle, address) = RpcServer::new(
+        SocketAddr::from(([127, 0, 0, 1], 0)),
+        context,
+        DefaultVersion::V05,
+    )
+    .spawn()
+    .context("Starting RPC server")?;
+    let client = Client::new(
+        format!("http://{address}/rpc/{RPC_VERSION}")
+            .parse()
+            .context("Parsing RPC server URL")?,
+    );
+
+    let result = write_vectors(&client, block, output).await;
+    server_handle.abort();
+    result
+}
+
+async fn write_vectors(
+    client: &Client,
+    block: BlockNumber,
+    output: &Path,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let block_params = json!({ "block_id": { "block_number": block.get() } });
+    let mut files = Vec::new();
+
+    for method in BLOCK_METHODS {
+        let vector = request(client, method, block_params.clone()).await?;
+        files.push(write(output, &format!("{method}.json"), &vector)?);
+    }
+
+    let transactions: Vec<Value> = client
+        .request("starknet_getBlockWithTxHashes", block_params)
+        .await
+        .and_then(|block: Value| Ok(serde_json::from_value(block["transactions"].clone())?))
+        .with_context(|| format!("Fetching transaction hashes of block {block}"))?;
+    for (index, transaction_hash) in transactions.into_iter().enumerate() {
+        let params = json!({ "transaction_hash": transaction_hash });
+        let vector = request(client, "starknet_getTransactionReceipt", params).await?;
+        files.push(write(
+            output,
+            &format!("starknet_getTransactionReceipt.{index}.json"),
+            &vector,
+        )?);
+    }
+
+    Ok(files)
+}
+
+/// The test vector of the request. RPC errors are part of the vector, as SDKs need to
+/// deserialize those too.
+async fn request(client: &Client, method: &str, params: Value) -> anyhow::Result<Value> {
+    let mut vector = json!({ "method": method, "params": params });
+
+    match client.request::<Value>(method, params).await {
+        Ok(result) => vector["result"] = result,
+        Err(Error::Rpc(error)) => {
+            tracing::warn!(%method, %error, "Request failed, writing the error as its test vector");
+            vector["error"] = json!({
+                "code": error.code,
+                "message": error.message,
+                "data": error.data,
+            });
+        }
+        Err(error) => {
+            return Err(error).with_context(|| format!("Requesting {method}"));
+        }
+    }
+
+    Ok(vector)
+}
+
+fn write(output: &Path, name: &str, vector: &Value) -> anyhow::Result<PathBuf> {
+    let path = output.join(name);
+    let mut json = serde_json::to_vec_pretty(vector).context("Serializing test vector")?;
+    json.push(b'\n');
+    std::fs::write(&path, json).with_context(|| format!("Writing {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn vectors_are_generated() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let files = generate(
+            RpcContext::for_tests(),
+            BlockNumber::new_or_panic(1),
+            directory.path(),
+        )
+        .await
+        .unwrap();
+
+        let names = files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "starknet_getBlockWithTxHashes.json",
+                "starknet_getBlockWithTxs.json",
+                "starknet_getStateUpdate.json",
+                "starknet_traceBlockTransactions.json",
+                "starknet_getTransactionReceipt.0.json",
+                "starknet_getTransactionReceipt.1.json",
+            ]
+        );
+
+        let block: Value = serde_json::from_slice(&std::fs::read(&files[0]).unwrap()).unwrap();
+        assert_eq!(block["method"], "starknet_getBlockWithTxHashes");
+        assert_eq!(block["result"]["block_number"], 1);
+    }
+}
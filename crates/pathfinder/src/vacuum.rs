@@ -0,0 +1,150 @@
+//! Scheduled incremental vacuuming of the database.
+//!
+//! Space freed by deleting data (e.g. by reorgs or pruning) is only kept for reuse by Sqlite and
+//! not returned to the file system. [auto_vacuum] returns it in small steps during a daily
+//! [VacuumWindow], which should be chosen to cover a period of low RPC traffic.
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use pathfinder_storage::Storage;
+
+/// The number of pages freed per step, which keeps the write lock short.
+const PAGES_PER_STEP: u32 = 1024;
+/// The delay between steps, leaving room for other writers.
+const STEP_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the window is checked while idle.
+const IDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// A daily time window in UTC, given as `HH:MM-HH:MM`.
+///
+/// The window wraps around midnight if it ends before it starts, e.g. `22:00-04:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacuumWindow {
+    /// Minutes since midnight.
+    start: u32,
+    /// Minutes since midnight, exclusive.
+    end: u32,
+}
+
+impl VacuumWindow {
+    fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+impl std::str::FromStr for VacuumWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .context("Expected a window of the form HH:MM-HH:MM")?;
+        let start = parse_time(start).context("Parsing window start")?;
+        let end = parse_time(end).context("Parsing window end")?;
+        anyhow::ensure!(start != end, "The window must not be empty");
+
+        Ok(Self { start, end })
+    }
+}
+
+/// Parses `HH:MM` into minutes since midnight.
+fn parse_time(s: &str) -> anyhow::Result<u32> {
+    let (hours, minutes) = s.trim().split_once(':').context("Expected HH:MM")?;
+    let hours: u32 = hours.parse().context("Parsing hours")?;
+    let minutes: u32 = minutes.parse().context("Parsing minutes")?;
+    anyhow::ensure!(hours < 24 && minutes < 60, "Invalid time {s}");
+
+    Ok(hours * 60 + minutes)
+}
+
+/// The current UTC time in minutes since midnight.
+fn minute_of_day() -> u32 {
+    let seconds = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    ((seconds / 60) % MINUTES_PER_DAY as u64) as u32
+}
+
+/// Incrementally vacuums the database during the daily `window`, forever.
+///
+/// Does nothing if the database does not support incremental vacuuming yet, which requires a
+/// one-off `pathfinder database vacuum`.
+pub async fn auto_vacuum(storage: Storage, window: VacuumWindow) -> anyhow::Result<()> {
+    let enabled = {
+        let storage = storage.clone();
+        tokio::task::spawn_blocking(move || storage.connection()?.incremental_vacuum_enabled())
+            .await
+            .context("Joining vacuum check")??
+    };
+    if !enabled {
+        tracing::warn!(
+            "Scheduled vacuuming is disabled as the database does not support incremental vacuuming. Run `pathfinder database vacuum` once to enable it."
+        );
+        return Ok(());
+    }
+
+    loop {
+        let mut interval = IDLE_INTERVAL;
+
+        if window.contains(minute_of_day()) {
+            let storage = storage.clone();
+            let freed = tokio::task::spawn_blocking(move || {
+                storage.connection()?.incremental_vacuum(PAGES_PER_STEP)
+            })
+            .await
+            .context("Joining vacuum step")?;
+
+            match freed {
+                Ok(0) => {}
+                Ok(pages) => {
+                    tracing::trace!(%pages, "Vacuumed database pages");
+                    metrics::counter!("storage_vacuumed_pages_total", pages as u64);
+                    interval = STEP_INTERVAL;
+                }
+                Err(error) => tracing::warn!(%error, "Vacuuming database failed"),
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window() {
+        let window: VacuumWindow = "02:30-05:00".parse().unwrap();
+        assert!(!window.contains(2 * 60 + 29));
+        assert!(window.contains(2 * 60 + 30));
+        assert!(window.contains(4 * 60 + 59));
+        assert!(!window.contains(5 * 60));
+
+        let wrapping: VacuumWindow = "22:00-04:00".parse().unwrap();
+        assert!(wrapping.contains(23 * 60));
+        assert!(wrapping.contains(0));
+        assert!(!wrapping.contains(12 * 60));
+    }
+
+    #[test]
+    fn invalid_windows() {
+        for input in [
+            "",
+            "02:00",
+            "02:00-02:00",
+            "24:00-01:00",
+            "01:60-02:00",
+            "a:b-c:d",
+        ] {
+            assert!(input.parse::<VacuumWindow>().is_err(), "{input}");
+        }
+    }
+}
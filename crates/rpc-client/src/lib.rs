@@ -0,0 +1,735 @@
+//! A typed client for pathfinder's JSON-RPC API.
+//!
+//! The params and results are the types of the RPC server itself, see [pathfinder_rpc::types], so
+//! that the client can't drift from the server. Only compiled CASM classes, which the server
+//! returns as-is, are plain [serde_json::Value]s.
+//!
+//! The `starknet_*` methods are functions of [Client], and the `pathfinder_*` methods are
+//! functions of [Client::pathfinder].
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pathfinder_common::{
+    BlockId, BlockNumber, CallResultValue, ChainId, ClassHash, ContractAddress, ContractNonce,
+    StorageAddress, StorageValue, TransactionHash,
+};
+use pathfinder_rpc::types::pathfinder::{
+    BlockSyncTimings, GetChainStatsOutput, GetClassDeclarationBlockOutput,
+    GetContractDeploymentOutput, GetMessagesToL1Input, GetMessagesToL1Output, GetNonceHistoryInput,
+    GetNonceHistoryOutput, GetProofOutput, GetSubmittedTransactionStatusOutput,
+    GetTransactionsBySenderInput, GetTransactionsBySenderOutput, NonceUpdate, PrepareDeclareOutput,
+    TransactionStatus,
+};
+use pathfinder_rpc::types::{
+    AddDeclareTransactionOutput, AddDeployAccountTransactionOutput, AddInvokeTransactionOutput,
+    BlockHashAndNumber, BlockWithTxHashes, BlockWithTxs, BroadcastedDeclareTransaction,
+    BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction, BroadcastedTransaction,
+    ContractClass, EventFilter, FeeEstimate, FunctionCall, GetEventsResult,
+    GetTransactionStatusOutput, MsgFromL1, Receipt, SierraContractClass, SimulatedTransaction,
+    SimulationFlag, StateUpdate, SyncingOutput, Trace, TransactionTrace, TransactionWithHash,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The request could not be sent, or the server responded with an HTTP error.
+    #[error(transparent)]
+    Transport(#[from] reqwest::Error),
+    /// The server responded with a JSON-RPC error.
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    /// The response is not a valid JSON-RPC response, or its result doesn't have the expected
+    /// type.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+}
+
+/// A JSON-RPC error returned by the server.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, thiserror::Error)]
+#[error("RPC error {code}: {message}")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+#[derive(serde::Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// A client of a single RPC endpoint.
+#[derive(Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    url: reqwest::Url,
+    bearer_token: Option<String>,
+    next_id: AtomicU64,
+}
+
+impl Client {
+    /// A client of the endpoint at `url`, e.g. `http://127.0.0.1:9545/rpc/v0.5`.
+    ///
+    /// The client is written against the v0.5 API. Other versions accept most of the same
+    /// requests, but some of their results differ.
+    pub fn new(url: reqwest::Url) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+            bearer_token: None,
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Authenticates requests with the token, for servers which require authentication.
+    pub fn with_bearer_token(self, token: String) -> Self {
+        Self {
+            bearer_token: Some(token),
+            ..self
+        }
+    }
+
+    /// The `pathfinder_*` methods, which are also served by the `starknet_*` endpoints.
+    pub fn pathfinder(&self) -> PathfinderMethods<'_> {
+        PathfinderMethods(self)
+    }
+
+    /// Calls the method with named `params`, or without params if they are [Value::Null].
+    pub async fn request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<R, Error> {
+        let mut request = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+        });
+        if !params.is_null() {
+            request["params"] = params;
+        }
+
+        let mut request = self.http.post(self.url.clone()).json(&request);
+        if let Some(token) = &self.bearer_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?.error_for_status()?.bytes().await?;
+        let response: Response = serde_json::from_slice(&response)?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(error.into()),
+            (Some(result), None) => Ok(serde_json::from_value(result)?),
+            // A `null` result is indistinguishable from a missing one.
+            (None, None) => Ok(serde_json::from_value(Value::Null)?),
+        }
+    }
+
+    pub async fn block_hash_and_number(&self) -> Result<BlockHashAndNumber, Error> {
+        self.request("starknet_blockHashAndNumber", Value::Null)
+            .await
+    }
+
+    pub async fn block_number(&self) -> Result<BlockNumber, Error> {
+        self.request("starknet_blockNumber", Value::Null).await
+    }
+
+    pub async fn chain_id(&self) -> Result<ChainId, Error> {
+        self.request("starknet_chainId", Value::Null).await
+    }
+
+    pub async fn get_block_transaction_count(&self, block_id: BlockId) -> Result<u64, Error> {
+        self.request(
+            "starknet_getBlockTransactionCount",
+            json!({ "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn get_class(
+        &self,
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> Result<ContractClass, Error> {
+        self.request(
+            "starknet_getClass",
+            json!({ "block_id": block_id, "class_hash": class_hash }),
+        )
+        .await
+    }
+
+    pub async fn get_class_at(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> Result<ContractClass, Error> {
+        self.request(
+            "starknet_getClassAt",
+            json!({ "block_id": block_id, "contract_address": contract_address }),
+        )
+        .await
+    }
+
+    pub async fn get_class_hash_at(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> Result<ClassHash, Error> {
+        self.request(
+            "starknet_getClassHashAt",
+            json!({ "block_id": block_id, "contract_address": contract_address }),
+        )
+        .await
+    }
+
+    pub async fn get_nonce(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> Result<ContractNonce, Error> {
+        self.request(
+            "starknet_getNonce",
+            json!({ "block_id": block_id, "contract_address": contract_address }),
+        )
+        .await
+    }
+
+    pub async fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageAddress,
+        block_id: BlockId,
+    ) -> Result<StorageValue, Error> {
+        self.request(
+            "starknet_getStorageAt",
+            json!({ "contract_address": contract_address, "key": key, "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn get_events(&self, filter: &EventFilter) -> Result<GetEventsResult, Error> {
+        self.request("starknet_getEvents", json!({ "filter": filter }))
+            .await
+    }
+
+    pub async fn get_state_update(&self, block_id: BlockId) -> Result<StateUpdate, Error> {
+        self.request("starknet_getStateUpdate", json!({ "block_id": block_id }))
+            .await
+    }
+
+    pub async fn add_declare_transaction(
+        &self,
+        transaction: &BroadcastedDeclareTransaction,
+    ) -> Result<AddDeclareTransactionOutput, Error> {
+        self.request(
+            "starknet_addDeclareTransaction",
+            json!({ "declare_transaction": BroadcastedTransaction::Declare(transaction.clone()) }),
+        )
+        .await
+    }
+
+    pub async fn add_deploy_account_transaction(
+        &self,
+        transaction: &BroadcastedDeployAccountTransaction,
+    ) -> Result<AddDeployAccountTransactionOutput, Error> {
+        self.request(
+            "starknet_addDeployAccountTransaction",
+            json!({
+                "deploy_account_transaction":
+                    BroadcastedTransaction::DeployAccount(transaction.clone())
+            }),
+        )
+        .await
+    }
+
+    pub async fn add_invoke_transaction(
+        &self,
+        transaction: &BroadcastedInvokeTransaction,
+    ) -> Result<AddInvokeTransactionOutput, Error> {
+        self.request(
+            "starknet_addInvokeTransaction",
+            json!({ "invoke_transaction": BroadcastedTransaction::Invoke(transaction.clone()) }),
+        )
+        .await
+    }
+
+    pub async fn get_transaction_by_block_id_and_index(
+        &self,
+        block_id: BlockId,
+        index: u64,
+    ) -> Result<TransactionWithHash, Error> {
+        self.request(
+            "starknet_getTransactionByBlockIdAndIndex",
+            json!({ "block_id": block_id, "index": index }),
+        )
+        .await
+    }
+
+    pub async fn get_transaction_by_hash(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> Result<TransactionWithHash, Error> {
+        self.request(
+            "starknet_getTransactionByHash",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await
+    }
+
+    pub async fn get_transaction_receipt(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> Result<Receipt, Error> {
+        self.request(
+            "starknet_getTransactionReceipt",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await
+    }
+
+    /// The sync status, which is [SyncingOutput::False] if the node is not syncing.
+    pub async fn syncing(&self) -> Result<SyncingOutput, Error> {
+        self.request("starknet_syncing", Value::Null).await
+    }
+
+    /// The transactions of the pending block.
+    ///
+    /// This method was removed from the specification in v0.5, and is only served by the v0.3 and
+    /// v0.4 endpoints.
+    pub async fn pending_transactions(&self) -> Result<Vec<TransactionWithHash>, Error> {
+        self.request("starknet_pendingTransactions", Value::Null)
+            .await
+    }
+
+    pub async fn call(
+        &self,
+        request: &FunctionCall,
+        block_id: BlockId,
+    ) -> Result<Vec<CallResultValue>, Error> {
+        self.request(
+            "starknet_call",
+            json!({ "request": request, "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn estimate_fee(
+        &self,
+        request: &[BroadcastedTransaction],
+        block_id: BlockId,
+    ) -> Result<Vec<FeeEstimate>, Error> {
+        self.request(
+            "starknet_estimateFee",
+            json!({ "request": request, "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn estimate_message_fee(
+        &self,
+        message: &MsgFromL1,
+        block_id: BlockId,
+    ) -> Result<FeeEstimate, Error> {
+        self.request(
+            "starknet_estimateMessageFee",
+            json!({ "message": message, "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn get_block_with_tx_hashes(
+        &self,
+        block_id: BlockId,
+    ) -> Result<BlockWithTxHashes, Error> {
+        self.request(
+            "starknet_getBlockWithTxHashes",
+            json!({ "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn get_block_with_txs(&self, block_id: BlockId) -> Result<BlockWithTxs, Error> {
+        self.request("starknet_getBlockWithTxs", json!({ "block_id": block_id }))
+            .await
+    }
+
+    /// The CASM class compiled from the Sierra class, as JSON.
+    pub async fn get_compiled_casm(
+        &self,
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> Result<Value, Error> {
+        self.request(
+            "starknet_getCompiledCasm",
+            json!({ "block_id": block_id, "class_hash": class_hash }),
+        )
+        .await
+    }
+
+    pub async fn get_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> Result<GetTransactionStatusOutput, Error> {
+        self.request(
+            "starknet_getTransactionStatus",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await
+    }
+
+    pub async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: &[BroadcastedTransaction],
+        simulation_flags: &[SimulationFlag],
+    ) -> Result<Vec<SimulatedTransaction>, Error> {
+        self.request(
+            "starknet_simulateTransactions",
+            json!({
+                "block_id": block_id,
+                "transactions": transactions,
+                "simulation_flags": simulation_flags,
+            }),
+        )
+        .await
+    }
+
+    pub async fn spec_version(&self) -> Result<String, Error> {
+        self.request("starknet_specVersion", Value::Null).await
+    }
+
+    pub async fn trace_block_transactions(&self, block_id: BlockId) -> Result<Vec<Trace>, Error> {
+        self.request(
+            "starknet_traceBlockTransactions",
+            json!({ "block_id": block_id }),
+        )
+        .await
+    }
+
+    pub async fn trace_transaction(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> Result<TransactionTrace, Error> {
+        self.request(
+            "starknet_traceTransaction",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await
+    }
+}
+
+/// The `pathfinder_*` methods of a [Client].
+pub struct PathfinderMethods<'a>(&'a Client);
+
+impl PathfinderMethods<'_> {
+    /// The version of the node.
+    ///
+    /// Unlike the other `pathfinder_*` methods, this is only served by the
+    /// `/rpc/pathfinder/v0.1` endpoint.
+    pub async fn version(&self) -> Result<String, Error> {
+        self.0.request("pathfinder_version", Value::Null).await
+    }
+
+    pub async fn get_proof(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+        keys: &[StorageAddress],
+    ) -> Result<GetProofOutput, Error> {
+        self.0
+            .request(
+                "pathfinder_getProof",
+                json!({ "block_id": block_id, "contract_address": contract_address, "keys": keys }),
+            )
+            .await
+    }
+
+    pub async fn get_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> Result<TransactionStatus, Error> {
+        self.0
+            .request(
+                "pathfinder_getTransactionStatus",
+                json!({ "transaction_hash": transaction_hash }),
+            )
+            .await
+    }
+
+    pub async fn get_submitted_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> Result<GetSubmittedTransactionStatusOutput, Error> {
+        self.0
+            .request(
+                "pathfinder_getSubmittedTransactionStatus",
+                json!({ "transaction_hash": transaction_hash }),
+            )
+            .await
+    }
+
+    pub async fn get_class_declaration_block(
+        &self,
+        class_hash: ClassHash,
+    ) -> Result<GetClassDeclarationBlockOutput, Error> {
+        self.0
+            .request(
+                "pathfinder_getClassDeclarationBlock",
+                json!({ "class_hash": class_hash }),
+            )
+            .await
+    }
+
+    pub async fn get_contract_deployment(
+        &self,
+        contract_address: ContractAddress,
+    ) -> Result<GetContractDeploymentOutput, Error> {
+        self.0
+            .request(
+                "pathfinder_getContractDeployment",
+                json!({ "contract_address": contract_address }),
+            )
+            .await
+    }
+
+    pub async fn prepare_declare(
+        &self,
+        contract_class: &SierraContractClass,
+    ) -> Result<PrepareDeclareOutput, Error> {
+        self.0
+            .request(
+                "pathfinder_prepareDeclare",
+                json!({ "contract_class": contract_class }),
+            )
+            .await
+    }
+
+    pub async fn get_messages_to_l1(
+        &self,
+        input: &GetMessagesToL1Input,
+    ) -> Result<GetMessagesToL1Output, Error> {
+        self.0
+            .request("pathfinder_getMessagesToL1", serde_json::to_value(input)?)
+            .await
+    }
+
+    /// The time spent per sync stage on the latest `limit` blocks, latest first.
+    pub async fn get_block_sync_timings(&self, limit: u64) -> Result<Vec<BlockSyncTimings>, Error> {
+        self.0
+            .request("pathfinder_getBlockSyncTimings", json!({ "limit": limit }))
+            .await
+    }
+
+    pub async fn get_chain_stats(&self) -> Result<GetChainStatsOutput, Error> {
+        self.0
+            .request("pathfinder_getChainStats", Value::Null)
+            .await
+    }
+
+    pub async fn get_transactions_by_sender(
+        &self,
+        input: &GetTransactionsBySenderInput,
+    ) -> Result<GetTransactionsBySenderOutput, Error> {
+        self.0
+            .request(
+                "pathfinder_getTransactionsBySender",
+                serde_json::to_value(input)?,
+            )
+            .await
+    }
+
+    pub async fn get_nonce_history(
+        &self,
+        input: &GetNonceHistoryInput,
+    ) -> Result<GetNonceHistoryOutput, Error> {
+        self.0
+            .request("pathfinder_getNonceHistory", serde_json::to_value(input)?)
+            .await
+    }
+
+    /// The first block in which the contract's nonce reached `nonce`, or [None] if it hasn't yet.
+    pub async fn get_first_block_with_nonce(
+        &self,
+        contract_address: ContractAddress,
+        nonce: ContractNonce,
+    ) -> Result<Option<NonceUpdate>, Error> {
+        self.0
+            .request(
+                "pathfinder_getFirstBlockWithNonce",
+                json!({ "contract_address": contract_address, "nonce": nonce }),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_rpc::context::RpcContext;
+    use pathfinder_rpc::{DefaultVersion, RpcServer};
+
+    use super::*;
+
+    fn spawn_server() -> Client {
+        spawn_server_at(RpcContext::for_tests(), "/rpc/v0.5")
+    }
+
+    fn spawn_server_at(context: RpcContext, route: &str) -> Client {
+        let (_server_handle, address) =
+            RpcServer::new("127.0.0.1:0".parse().unwrap(), context, DefaultVersion::V05)
+                .spawn()
+                .unwrap();
+
+        Client::new(format!("http://{address}{route}").parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn starknet_methods() {
+        let client = spawn_server();
+
+        assert_eq!(
+            client.chain_id().await.unwrap(),
+            RpcContext::for_tests().chain_id
+        );
+        assert_eq!(
+            client.block_number().await.unwrap(),
+            BlockNumber::new_or_panic(2)
+        );
+
+        let block = client
+            .get_block_with_tx_hashes(BlockId::Number(BlockNumber::new_or_panic(1)))
+            .await
+            .unwrap();
+        assert_eq!(block.header.block_hash, Some(block_hash_bytes!(b"block 1")));
+
+        let nonce = client
+            .get_nonce(BlockId::Latest, contract_address_bytes!(b"contract 1"))
+            .await
+            .unwrap();
+        assert_eq!(nonce, contract_nonce!("0x10"));
+
+        assert_eq!(client.syncing().await.unwrap(), SyncingOutput::False);
+    }
+
+    #[tokio::test]
+    async fn blocks_and_transactions() {
+        use pathfinder_rpc::types::{
+            ExecutionStatus, FinalityStatus, ReceiptKind, TransactionExecutionStatus,
+        };
+
+        let client = spawn_server();
+
+        let block = client
+            .get_block_with_txs(BlockId::Number(BlockNumber::GENESIS))
+            .await
+            .unwrap();
+        assert_eq!(block.header.block_hash, Some(block_hash_bytes!(b"genesis")));
+        assert_eq!(
+            block.transactions[0].transaction_hash,
+            transaction_hash_bytes!(b"txn 0")
+        );
+
+        let transaction = client
+            .get_transaction_by_hash(transaction_hash_bytes!(b"txn 0"))
+            .await
+            .unwrap();
+        assert_eq!(transaction, block.transactions[0]);
+
+        let transaction = client
+            .get_transaction_by_block_id_and_index(BlockId::Number(BlockNumber::GENESIS), 0)
+            .await
+            .unwrap();
+        assert_eq!(transaction, block.transactions[0]);
+
+        let receipt = client
+            .get_transaction_receipt(transaction_hash_bytes!(b"txn 0"))
+            .await
+            .unwrap();
+        assert_eq!(receipt.transaction_hash, transaction_hash_bytes!(b"txn 0"));
+        assert_eq!(receipt.kind, ReceiptKind::Invoke);
+        assert_eq!(receipt.execution_status, ExecutionStatus::Succeeded);
+        let receipt_block = receipt.block.unwrap();
+        assert_eq!(receipt_block.hash, block_hash_bytes!(b"genesis"));
+        assert_eq!(receipt_block.finality_status, FinalityStatus::AcceptedOnL1);
+
+        let status = client
+            .get_transaction_status(transaction_hash_bytes!(b"txn 0"))
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            GetTransactionStatusOutput::AcceptedOnL1(TransactionExecutionStatus::Succeeded)
+        );
+    }
+
+    #[tokio::test]
+    async fn classes() {
+        let client = spawn_server();
+
+        let class = client
+            .get_class(BlockId::Latest, class_hash_bytes!(b"class 0 hash"))
+            .await
+            .unwrap();
+        assert!(matches!(class, ContractClass::Cairo(_)));
+
+        let class = client
+            .get_class(BlockId::Latest, class_hash_bytes!(b"class 2 hash (sierra)"))
+            .await
+            .unwrap();
+        assert!(matches!(class, ContractClass::Sierra(_)));
+    }
+
+    #[tokio::test]
+    async fn pending_transactions() {
+        let context = RpcContext::for_tests_with_pending().await;
+        let client = spawn_server_at(context, "/rpc/v0.4");
+
+        let transactions = client.pending_transactions().await.unwrap();
+        let hashes = transactions
+            .iter()
+            .map(|tx| tx.transaction_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hashes,
+            vec![
+                transaction_hash_bytes!(b"pending tx hash 0"),
+                transaction_hash_bytes!(b"pending tx hash 1"),
+                transaction_hash_bytes!(b"pending reverted"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pathfinder_methods() {
+        let client = spawn_server();
+
+        let deployment = client
+            .pathfinder()
+            .get_contract_deployment(contract_address_bytes!(b"contract 1"))
+            .await
+            .unwrap();
+        assert_eq!(deployment.block_hash, block_hash_bytes!(b"block 1"));
+    }
+
+    #[tokio::test]
+    async fn version() {
+        let client = spawn_server_at(RpcContext::for_tests(), "/rpc/pathfinder/v0.1");
+
+        let version = client.pathfinder().version().await.unwrap();
+        assert_eq!(version, pathfinder_common::consts::VERGEN_GIT_DESCRIBE);
+    }
+
+    #[tokio::test]
+    async fn rpc_error() {
+        let client = spawn_server();
+
+        let error = client
+            .get_block_with_tx_hashes(BlockId::Hash(block_hash_bytes!(b"invalid")))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Rpc(RpcError { code: 24, .. })));
+    }
+}
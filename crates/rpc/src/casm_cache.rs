@@ -0,0 +1,74 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use pathfinder_common::cache::{CacheRegistry, MemoryAccounted};
+use pathfinder_common::ClassHash;
+
+/// A size-limited cache of CASM definitions which were compiled on demand.
+///
+/// CASM definitions are normally compiled and stored during sync. This cache covers classes
+/// for which that did not happen, so that they are not recompiled for every request.
+#[derive(Clone)]
+pub struct CasmCache(Arc<Shared>);
+
+struct Shared(Mutex<Inner>);
+
+struct Inner {
+    cache: lru::LruCache<ClassHash, Arc<Vec<u8>>>,
+    /// Total size of the cached definitions.
+    bytes: usize,
+}
+
+impl CasmCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        let shared = Arc::new(Shared(Mutex::new(Inner {
+            cache: lru::LruCache::new(capacity),
+            bytes: 0,
+        })));
+
+        let accounted: Arc<dyn MemoryAccounted> = shared.clone();
+        CacheRegistry::global().register("rpc_casm", &accounted);
+
+        Self(shared)
+    }
+
+    pub fn get(&self, class_hash: &ClassHash) -> Option<Arc<Vec<u8>>> {
+        self.0.lock().cache.get(class_hash).cloned()
+    }
+
+    pub fn insert(&self, class_hash: ClassHash, definition: Arc<Vec<u8>>) {
+        let mut inner = self.0.lock();
+        inner.bytes += definition.len();
+        if let Some((_, evicted)) = inner.cache.push(class_hash, definition) {
+            inner.bytes -= evicted.len();
+        }
+    }
+}
+
+impl Default for CasmCache {
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(128).unwrap())
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.0.lock().unwrap()
+    }
+}
+
+impl MemoryAccounted for Shared {
+    fn memory_usage(&self) -> usize {
+        self.lock().bytes
+    }
+
+    fn shrink_to(&self, bytes: usize) {
+        let mut inner = self.lock();
+        while inner.bytes > bytes {
+            let Some((_, evicted)) = inner.cache.pop_lru() else {
+                break;
+            };
+            inner.bytes -= evicted.len();
+        }
+    }
+}
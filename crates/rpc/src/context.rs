@@ -1,13 +1,32 @@
+use crate::casm_cache::CasmCache;
+use crate::fee_estimate_cache::FeeEstimateCache;
 use crate::gas_price;
-pub use crate::jsonrpc::websocket::WebsocketContext;
+pub use crate::jsonrpc::websocket::{LagPolicy, WebsocketContext};
+use crate::jsonrpc::ParamsStrictness;
+use crate::middleware::load_shedding::{LoadShedder, LoadSheddingConfig};
 use crate::pending::PendingData;
 use crate::pending::PendingWatcher;
+use crate::response_cache::ResponseCache;
 use crate::SyncState;
 use pathfinder_common::ChainId;
+use pathfinder_executor::{ExecutionLimits, RemoteState};
 use pathfinder_storage::Storage;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
+/// The resources a single request may spend executing transactions, by class of method.
+///
+/// Traces are not limited, as they replay transactions which were already accepted.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestExecutionLimits {
+    /// Limits of `starknet_call`.
+    pub call: ExecutionLimits,
+    /// Limits of `starknet_estimateFee` and `starknet_estimateMessageFee`.
+    pub estimate_fee: ExecutionLimits,
+    /// Limits of `starknet_simulateTransactions`.
+    pub simulate: ExecutionLimits,
+}
+
 type SequencerClient = starknet_gateway_client::Client;
 use tokio::sync::watch as tokio_watch;
 
@@ -19,9 +38,20 @@ pub struct RpcContext {
     pub sync_status: Arc<SyncState>,
     pub chain_id: ChainId,
     pub eth_gas_price: gas_price::Cached,
+    pub gas_price_source: gas_price::GasPriceSource,
     pub sequencer: SequencerClient,
     pub websocket: Option<WebsocketContext>,
     pub batch_concurrency_limit: NonZeroUsize,
+    pub params_strictness: ParamsStrictness,
+    /// The maximum size of a method's serialized result, see [RpcContext::with_max_response_bytes].
+    pub max_response_bytes: Option<NonZeroUsize>,
+    pub casm_cache: CasmCache,
+    pub fee_estimate_cache: FeeEstimateCache,
+    pub response_cache: ResponseCache,
+    pub execution_limits: RequestExecutionLimits,
+    pub load_shedder: LoadShedder,
+    /// State read by the executor wherever the database has none, e.g. a forked network's.
+    pub remote_state: Option<Arc<dyn RemoteState>>,
 }
 
 impl RpcContext {
@@ -42,9 +72,18 @@ impl RpcContext {
             chain_id,
             pending_data,
             eth_gas_price: gas_price::Cached::new(sequencer.clone()),
+            gas_price_source: Default::default(),
             sequencer,
             websocket: None,
             batch_concurrency_limit,
+            params_strictness: Default::default(),
+            max_response_bytes: None,
+            casm_cache: Default::default(),
+            fee_estimate_cache: Default::default(),
+            response_cache: Default::default(),
+            execution_limits: Default::default(),
+            load_shedder: Default::default(),
+            remote_state: None,
         }
     }
 
@@ -105,6 +144,60 @@ impl RpcContext {
         context.with_pending_data(rx)
     }
 
+    pub fn with_remote_state(self, remote_state: Arc<dyn RemoteState>) -> Self {
+        Self {
+            remote_state: Some(remote_state),
+            ..self
+        }
+    }
+
+    /// Results larger than `max_response_bytes` once serialized are replaced by a
+    /// [ResponseTooLarge](crate::error::ApplicationError::ResponseTooLarge) error.
+    pub fn with_max_response_bytes(self, max_response_bytes: NonZeroUsize) -> Self {
+        Self {
+            max_response_bytes: Some(max_response_bytes),
+            ..self
+        }
+    }
+
+    /// Caches up to `capacity` results of queries addressed by block hash, see [ResponseCache].
+    pub fn with_response_cache_capacity(self, capacity: NonZeroUsize) -> Self {
+        Self {
+            response_cache: ResponseCache::new(capacity),
+            ..self
+        }
+    }
+
+    pub fn with_execution_limits(self, execution_limits: RequestExecutionLimits) -> Self {
+        Self {
+            execution_limits,
+            ..self
+        }
+    }
+
+    /// Rejects low-priority methods once the load exceeds the thresholds, see
+    /// [load_shedding](crate::middleware::load_shedding).
+    pub fn with_load_shedding(self, config: LoadSheddingConfig) -> Self {
+        Self {
+            load_shedder: LoadShedder::new(config),
+            ..self
+        }
+    }
+
+    pub fn with_gas_price_source(self, gas_price_source: gas_price::GasPriceSource) -> Self {
+        Self {
+            gas_price_source,
+            ..self
+        }
+    }
+
+    pub fn with_params_strictness(self, params_strictness: ParamsStrictness) -> Self {
+        Self {
+            params_strictness,
+            ..self
+        }
+    }
+
     pub fn with_websockets(self, websockets: WebsocketContext) -> Self {
         Self {
             websocket: Some(websockets),
@@ -0,0 +1,56 @@
+//! Canonical representations of objects returned by several RPC versions.
+//!
+//! Methods build the canonical object once and return it [versioned](IntoRpcVersioned) for the
+//! specification they implement. The object's serializer then decides which fields the version
+//! includes and how they are named. Supporting a new specification version only requires
+//! handling it in the serializers of the objects that changed, instead of copying the types of
+//! the previous version.
+//!
+//! Types which don't differ between versions are plain [serde::Serialize] implementations, and
+//! are shared as-is.
+mod receipt;
+mod transaction;
+
+pub use receipt::*;
+pub use transaction::*;
+
+/// The specification versions served by [IntoRpcVersioned] objects.
+///
+/// The `v02` methods are served by the `v0.3` API, and serialize as [RpcVersion::V03].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RpcVersion {
+    V03,
+    V04,
+    V05,
+}
+
+/// A canonical object which is serialized according to a specification version.
+pub trait IntoRpcVersioned: Sized {
+    fn serialize_versioned<S>(&self, version: RpcVersion, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer;
+
+    /// Binds the object to `version`, which is used by its [serde::Serialize] implementation.
+    fn into_versioned(self, version: RpcVersion) -> Versioned<Self> {
+        Versioned {
+            version,
+            value: self,
+        }
+    }
+}
+
+/// An [IntoRpcVersioned] object bound to the version it is serialized for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Versioned<T> {
+    pub version: RpcVersion,
+    pub value: T,
+}
+
+impl<T: IntoRpcVersioned> serde::Serialize for Versioned<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize_versioned(self.version, serializer)
+    }
+}
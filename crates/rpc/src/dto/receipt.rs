@@ -0,0 +1,682 @@
+use pathfinder_common::{
+    BlockHash, BlockNumber, ContractAddress, EthereumAddress, EventData, EventKey, Fee,
+    L2ToL1MessagePayloadElem, TransactionHash,
+};
+use pathfinder_ethereum::L1Transaction;
+use pathfinder_serde::{u64_as_hex_str, EthereumAddressAsHexStr, H256AsNoLeadingZerosHexStr};
+use primitive_types::H256;
+use serde::ser::SerializeMap;
+use serde::Serialize;
+use serde_with::serde_as;
+use starknet_gateway_types::reply::transaction as gateway;
+
+use super::{IntoRpcVersioned, RpcVersion};
+use crate::felt::{RpcFelt, RpcFelt251};
+
+/// A transaction receipt, as returned by `starknet_getTransactionReceipt`.
+///
+/// | field                   | v0.3 | v0.4 | v0.5 |
+/// |-------------------------|------|------|------|
+/// | `status`                | ✓    |      |      |
+/// | `revert_reason`         |      | ✓    | ✓    |
+/// | `execution_status`      |      | ✓    | ✓    |
+/// | `finality_status`       |      | ✓    | ✓    |
+/// | `execution_resources`   |      |      | ✓    |
+/// | `message_hash`          |      |      | ✓    |
+/// | `l1_acceptance`         |      |      | ✓    |
+///
+/// Pending receipts have no block and status fields prior to v0.4.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Receipt {
+    pub transaction_hash: TransactionHash,
+    pub actual_fee: Fee,
+    pub kind: ReceiptKind,
+    pub messages_sent: Vec<MessageToL1>,
+    pub events: Vec<Event>,
+    pub execution_status: ExecutionStatus,
+    pub revert_reason: Option<String>,
+    pub execution_resources: ExecutionResourcesProperties,
+    /// [None] for pending receipts.
+    pub block: Option<ReceiptBlock>,
+}
+
+/// The transaction type specific parts of a [Receipt].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReceiptKind {
+    Declare,
+    // FIXME regenesis: remove Deploy receipt type after regenesis
+    // We are keeping this type of receipt until regenesis
+    // only to support older pre-0.11.0 blocks
+    Deploy { contract_address: ContractAddress },
+    DeployAccount { contract_address: ContractAddress },
+    Invoke,
+    L1Handler { message_hash: H256 },
+}
+
+impl ReceiptKind {
+    fn name(&self) -> &'static str {
+        match self {
+            ReceiptKind::Declare => "DECLARE",
+            ReceiptKind::Deploy { .. } => "DEPLOY",
+            ReceiptKind::DeployAccount { .. } => "DEPLOY_ACCOUNT",
+            ReceiptKind::Invoke => "INVOKE",
+            ReceiptKind::L1Handler { .. } => "L1_HANDLER",
+        }
+    }
+}
+
+impl From<&gateway::Transaction> for ReceiptKind {
+    fn from(transaction: &gateway::Transaction) -> Self {
+        use gateway::Transaction::*;
+        match transaction {
+            Declare(_) => Self::Declare,
+            Deploy(tx) => Self::Deploy {
+                contract_address: tx.contract_address,
+            },
+            DeployAccount(tx) => Self::DeployAccount {
+                contract_address: tx.contract_address(),
+            },
+            Invoke(_) => Self::Invoke,
+            L1Handler(tx) => Self::L1Handler {
+                message_hash: tx.calculate_message_hash(),
+            },
+        }
+    }
+}
+
+/// The block of a non-pending [Receipt].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReceiptBlock {
+    pub hash: BlockHash,
+    pub number: BlockNumber,
+    pub finality_status: FinalityStatus,
+    pub l1_transaction: Option<L1Transaction>,
+}
+
+impl Receipt {
+    pub fn pending(receipt: gateway::Receipt, transaction: &gateway::Transaction) -> Self {
+        Self {
+            transaction_hash: receipt.transaction_hash,
+            actual_fee: receipt
+                .actual_fee
+                .unwrap_or_else(|| Fee(Default::default())),
+            kind: transaction.into(),
+            messages_sent: receipt
+                .l2_to_l1_messages
+                .into_iter()
+                .map(MessageToL1::from)
+                .collect(),
+            events: receipt.events.into_iter().map(Event::from).collect(),
+            execution_status: receipt.execution_status.into(),
+            revert_reason: receipt.revert_error,
+            execution_resources: receipt.execution_resources.unwrap_or_default().into(),
+            block: None,
+        }
+    }
+
+    pub fn with_block(
+        receipt: gateway::Receipt,
+        transaction: &gateway::Transaction,
+        block: ReceiptBlock,
+    ) -> Self {
+        Self {
+            block: Some(block),
+            ..Self::pending(receipt, transaction)
+        }
+    }
+}
+
+impl IntoRpcVersioned for Receipt {
+    fn serialize_versioned<S>(&self, version: RpcVersion, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", self.kind.name())?;
+        map.serialize_entry("transaction_hash", &RpcFelt(self.transaction_hash.0))?;
+        map.serialize_entry("actual_fee", &self.actual_fee)?;
+        if let Some(block) = &self.block {
+            if version == RpcVersion::V03 {
+                map.serialize_entry("status", &block.finality_status)?;
+            }
+            map.serialize_entry("block_hash", &RpcFelt(block.hash.0))?;
+            map.serialize_entry("block_number", &block.number)?;
+        }
+        map.serialize_entry("messages_sent", &self.messages_sent)?;
+        map.serialize_entry("events", &self.events)?;
+
+        if version >= RpcVersion::V04 {
+            if let Some(revert_reason) = &self.revert_reason {
+                map.serialize_entry("revert_reason", revert_reason)?;
+            }
+            if version >= RpcVersion::V05 {
+                map.serialize_entry("execution_resources", &self.execution_resources)?;
+            }
+            map.serialize_entry("execution_status", &self.execution_status)?;
+            let finality_status = self
+                .block
+                .as_ref()
+                .map_or(FinalityStatus::AcceptedOnL2, |block| block.finality_status);
+            map.serialize_entry("finality_status", &finality_status)?;
+        }
+
+        if version >= RpcVersion::V05 {
+            if let Some(l1_transaction) = self.block.as_ref().and_then(|b| b.l1_transaction) {
+                map.serialize_entry("l1_acceptance", &L1Acceptance::from(l1_transaction))?;
+            }
+        }
+
+        match &self.kind {
+            ReceiptKind::Deploy { contract_address }
+            | ReceiptKind::DeployAccount { contract_address } => {
+                map.serialize_entry("contract_address", &RpcFelt251::from(*contract_address))?;
+            }
+            ReceiptKind::L1Handler { message_hash } if version >= RpcVersion::V05 => {
+                map.serialize_entry("message_hash", &MessageHash(*message_hash))?;
+            }
+            _ => {}
+        }
+
+        map.end()
+    }
+}
+
+/// Deserializes the receipt as served by the latest version.
+#[cfg(any(test, feature = "rpc-full-serde"))]
+impl<'de> serde::Deserialize<'de> for Receipt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        #[serde_as]
+        #[derive(serde::Deserialize)]
+        struct Fields {
+            #[serde(rename = "type")]
+            kind: String,
+            #[serde_as(as = "RpcFelt")]
+            transaction_hash: TransactionHash,
+            actual_fee: Fee,
+            #[serde_as(as = "Option<RpcFelt>")]
+            #[serde(default)]
+            block_hash: Option<BlockHash>,
+            #[serde(default)]
+            block_number: Option<BlockNumber>,
+            messages_sent: Vec<MessageToL1>,
+            events: Vec<Event>,
+            #[serde(default)]
+            revert_reason: Option<String>,
+            execution_resources: ExecutionResourcesProperties,
+            execution_status: ExecutionStatus,
+            finality_status: FinalityStatus,
+            #[serde(default)]
+            l1_acceptance: Option<L1Acceptance>,
+            #[serde_as(as = "Option<RpcFelt251>")]
+            #[serde(default)]
+            contract_address: Option<ContractAddress>,
+            #[serde_as(as = "Option<H256AsNoLeadingZerosHexStr>")]
+            #[serde(default)]
+            message_hash: Option<H256>,
+        }
+
+        let fields = <Fields as serde::Deserialize>::deserialize(deserializer)?;
+
+        let contract_address = || {
+            fields
+                .contract_address
+                .ok_or_else(|| D::Error::missing_field("contract_address"))
+        };
+        let kind = match fields.kind.as_str() {
+            "DECLARE" => ReceiptKind::Declare,
+            "DEPLOY" => ReceiptKind::Deploy {
+                contract_address: contract_address()?,
+            },
+            "DEPLOY_ACCOUNT" => ReceiptKind::DeployAccount {
+                contract_address: contract_address()?,
+            },
+            "INVOKE" => ReceiptKind::Invoke,
+            "L1_HANDLER" => ReceiptKind::L1Handler {
+                message_hash: fields
+                    .message_hash
+                    .ok_or_else(|| D::Error::missing_field("message_hash"))?,
+            },
+            other => {
+                return Err(D::Error::unknown_variant(
+                    other,
+                    &[
+                        "DECLARE",
+                        "DEPLOY",
+                        "DEPLOY_ACCOUNT",
+                        "INVOKE",
+                        "L1_HANDLER",
+                    ],
+                ))
+            }
+        };
+
+        let block = match (fields.block_hash, fields.block_number) {
+            (Some(hash), Some(number)) => Some(ReceiptBlock {
+                hash,
+                number,
+                finality_status: fields.finality_status,
+                l1_transaction: fields.l1_acceptance.map(|acceptance| L1Transaction {
+                    hash: acceptance.transaction_hash,
+                    timestamp: acceptance.timestamp,
+                }),
+            }),
+            _ => None,
+        };
+
+        Ok(Self {
+            transaction_hash: fields.transaction_hash,
+            actual_fee: fields.actual_fee,
+            kind,
+            messages_sent: fields.messages_sent,
+            events: fields.events,
+            execution_status: fields.execution_status,
+            revert_reason: fields.revert_reason,
+            execution_resources: fields.execution_resources,
+            block,
+        })
+    }
+}
+
+#[serde_as]
+#[derive(Serialize)]
+struct MessageHash(#[serde_as(as = "H256AsNoLeadingZerosHexStr")] H256);
+
+#[derive(Copy, Clone, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionStatus {
+    Succeeded,
+    Reverted,
+}
+
+impl From<gateway::ExecutionStatus> for ExecutionStatus {
+    fn from(value: gateway::ExecutionStatus) -> Self {
+        match value {
+            gateway::ExecutionStatus::Succeeded => Self::Succeeded,
+            gateway::ExecutionStatus::Reverted => Self::Reverted,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinalityStatus {
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+/// Message sent from L2 to L1.
+#[serde_as]
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct MessageToL1 {
+    #[serde_as(as = "RpcFelt251")]
+    pub from_address: ContractAddress,
+    #[serde_as(as = "EthereumAddressAsHexStr")]
+    pub to_address: EthereumAddress,
+    #[serde_as(as = "Vec<RpcFelt>")]
+    pub payload: Vec<L2ToL1MessagePayloadElem>,
+}
+
+impl From<gateway::L2ToL1Message> for MessageToL1 {
+    fn from(msg: gateway::L2ToL1Message) -> Self {
+        Self {
+            from_address: msg.from_address,
+            to_address: msg.to_address,
+            payload: msg.payload,
+        }
+    }
+}
+
+/// Event emitted as a part of a transaction.
+#[serde_as]
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct Event {
+    #[serde_as(as = "RpcFelt251")]
+    pub from_address: ContractAddress,
+    #[serde_as(as = "Vec<RpcFelt>")]
+    pub keys: Vec<EventKey>,
+    #[serde_as(as = "Vec<RpcFelt>")]
+    pub data: Vec<EventData>,
+}
+
+impl From<pathfinder_common::event::Event> for Event {
+    fn from(e: pathfinder_common::event::Event) -> Self {
+        Self {
+            from_address: e.from_address,
+            keys: e.keys,
+            data: e.data,
+        }
+    }
+}
+
+/// Similar to [`gateway::ExecutionResources`], with irrelevant properties stripped.
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct ExecutionResourcesProperties {
+    // All these properties are actually strings in the spec, hence the serde attributes.
+    #[serde(with = "u64_as_hex_str")]
+    pub steps: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub memory_holes: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub range_check_builtin_applications: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub pedersen_builtin_applications: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub poseidon_builtin_applications: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub ec_op_builtin_applications: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub ecdsa_builtin_applications: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub bitwise_builtin_applications: u64,
+    #[serde(with = "u64_as_hex_str")]
+    pub keccak_builtin_applications: u64,
+}
+
+impl From<gateway::ExecutionResources> for ExecutionResourcesProperties {
+    fn from(value: gateway::ExecutionResources) -> Self {
+        let gateway::ExecutionResources {
+            builtin_instance_counter:
+                gateway::BuiltinCounters {
+                    // Absent from the OpenRPC spec
+                    output_builtin: _,
+                    pedersen_builtin,
+                    range_check_builtin,
+                    ecdsa_builtin,
+                    bitwise_builtin,
+                    ec_op_builtin,
+                    keccak_builtin,
+                    poseidon_builtin,
+                    // Absent from the OpenRPC spec
+                    segment_arena_builtin: _,
+                },
+            n_steps,
+            n_memory_holes,
+        } = value;
+
+        Self {
+            steps: n_steps,
+            memory_holes: n_memory_holes,
+            range_check_builtin_applications: range_check_builtin,
+            pedersen_builtin_applications: pedersen_builtin,
+            poseidon_builtin_applications: poseidon_builtin,
+            ec_op_builtin_applications: ec_op_builtin,
+            ecdsa_builtin_applications: ecdsa_builtin,
+            bitwise_builtin_applications: bitwise_builtin,
+            keccak_builtin_applications: keccak_builtin,
+        }
+    }
+}
+
+/// The L1 transaction which accepted a block.
+///
+/// This is an extension to the specification, and is only present once the transaction is known.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct L1Acceptance {
+    pub transaction_hash: H256,
+    /// The timestamp of the L1 block containing the transaction.
+    pub timestamp: u64,
+}
+
+impl From<L1Transaction> for L1Acceptance {
+    fn from(transaction: L1Transaction) -> Self {
+        Self {
+            transaction_hash: transaction.hash,
+            timestamp: transaction.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    fn receipt(transaction_hash: TransactionHash, kind: ReceiptKind) -> Receipt {
+        Receipt {
+            transaction_hash,
+            actual_fee: fee!("0x1"),
+            kind,
+            messages_sent: vec![MessageToL1 {
+                from_address: contract_address!("0x789"),
+                to_address: EthereumAddress(primitive_types::H160::from_low_u64_be(0x55)),
+                payload: vec![l2_to_l1_message_payload_elem!("0x6")],
+            }],
+            events: vec![Event {
+                from_address: contract_address!("0xe6"),
+                keys: vec![event_key!("0xe7")],
+                data: vec![event_data!("0xe8")],
+            }],
+            execution_status: ExecutionStatus::Succeeded,
+            revert_reason: None,
+            execution_resources: Default::default(),
+            block: Some(ReceiptBlock {
+                hash: block_hash!("0xaaa"),
+                number: BlockNumber::new_or_panic(3),
+                finality_status: FinalityStatus::AcceptedOnL1,
+                l1_transaction: None,
+            }),
+        }
+    }
+
+    fn pending(transaction_hash: TransactionHash, kind: ReceiptKind) -> Receipt {
+        Receipt {
+            actual_fee: fee!("0x2"),
+            messages_sent: vec![MessageToL1 {
+                from_address: contract_address!("0x789"),
+                to_address: EthereumAddress(primitive_types::H160::from_low_u64_be(0x5)),
+                payload: vec![l2_to_l1_message_payload_elem!("0x6")],
+            }],
+            events: vec![Event {
+                from_address: contract_address!("0xa6"),
+                keys: vec![event_key!("0xa7")],
+                data: vec![event_data!("0xa8")],
+            }],
+            block: None,
+            ..receipt(transaction_hash, kind)
+        }
+    }
+
+    #[test]
+    fn v03_fixture() {
+        let data = vec![
+            // All fields populated
+            receipt(transaction_hash!("0xdeadbeef"), ReceiptKind::Invoke),
+            // All optional are None
+            Receipt {
+                messages_sent: vec![],
+                events: vec![],
+                ..receipt(transaction_hash!("0xdeadbeef"), ReceiptKind::Invoke)
+            },
+            // Somewhat redundant, but want to exhaust the variants
+            receipt(transaction_hash!("0xdeaf01"), ReceiptKind::Declare),
+            receipt(
+                transaction_hash!("0xdeaf02"),
+                ReceiptKind::L1Handler {
+                    message_hash: H256::zero(),
+                },
+            ),
+            receipt(
+                transaction_hash!("0xdeaf03"),
+                ReceiptKind::Deploy {
+                    contract_address: contract_address!("0xcc"),
+                },
+            ),
+            pending(transaction_hash!("0xdeaf11"), ReceiptKind::Invoke),
+            pending(transaction_hash!("0xdeaf12"), ReceiptKind::Declare),
+            pending(
+                transaction_hash!("0xdeaf13"),
+                ReceiptKind::L1Handler {
+                    message_hash: H256::zero(),
+                },
+            ),
+            pending(
+                transaction_hash!("0xdeaf14"),
+                ReceiptKind::Deploy {
+                    contract_address: contract_address!("0xdd"),
+                },
+            ),
+        ]
+        .into_iter()
+        .map(|receipt| receipt.into_versioned(RpcVersion::V03))
+        .collect::<Vec<_>>();
+
+        let fixture = include_str!("../../fixtures/0.44.0/receipt.json").replace([' ', '\n'], "");
+
+        pretty_assertions::assert_eq!(serde_json::to_string(&data).unwrap(), fixture);
+    }
+
+    #[test]
+    fn version_specific_fields() {
+        let l1_transaction = L1Transaction {
+            hash: H256::repeat_byte(0xab),
+            timestamp: 1700000000,
+        };
+        let mut receipt = receipt(
+            transaction_hash!("0x1"),
+            ReceiptKind::L1Handler {
+                message_hash: H256::from_low_u64_be(0x10),
+            },
+        );
+        receipt.execution_status = ExecutionStatus::Reverted;
+        receipt.revert_reason = Some("Reverted because".to_owned());
+        receipt.block.as_mut().unwrap().l1_transaction = Some(l1_transaction);
+
+        let fields = |version| {
+            let json = serde_json::to_value(receipt.clone().into_versioned(version)).unwrap();
+            json.as_object().unwrap().clone()
+        };
+
+        let v03 = fields(RpcVersion::V03);
+        assert_eq!(v03["status"], "ACCEPTED_ON_L1");
+        for field in ["revert_reason", "execution_status", "finality_status"] {
+            assert!(!v03.contains_key(field), "{field}");
+        }
+
+        let v04 = fields(RpcVersion::V04);
+        assert!(!v04.contains_key("status"));
+        assert_eq!(v04["revert_reason"], "Reverted because");
+        assert_eq!(v04["execution_status"], "REVERTED");
+        assert_eq!(v04["finality_status"], "ACCEPTED_ON_L1");
+        for field in ["execution_resources", "message_hash", "l1_acceptance"] {
+            assert!(!v04.contains_key(field), "{field}");
+        }
+
+        let v05 = fields(RpcVersion::V05);
+        assert_eq!(v05["message_hash"], "0x10");
+        assert_eq!(v05["execution_resources"]["steps"], "0x0");
+        assert_eq!(
+            v05["l1_acceptance"],
+            serde_json::to_value(L1Acceptance::from(l1_transaction)).unwrap()
+        );
+    }
+
+    #[test]
+    fn pending_finality_status() {
+        let receipt = pending(transaction_hash!("0x1"), ReceiptKind::Invoke);
+
+        let v03 = serde_json::to_value(receipt.clone().into_versioned(RpcVersion::V03)).unwrap();
+        assert!(v03.get("status").is_none());
+        assert!(v03.get("block_hash").is_none());
+
+        let v04 = serde_json::to_value(receipt.into_versioned(RpcVersion::V04)).unwrap();
+        assert_eq!(v04["finality_status"], "ACCEPTED_ON_L2");
+    }
+
+    #[test]
+    fn v05_roundtrip() {
+        let mut l1_accepted = receipt(
+            transaction_hash!("0x1"),
+            ReceiptKind::L1Handler {
+                message_hash: H256::from_low_u64_be(0x10),
+            },
+        );
+        l1_accepted.revert_reason = Some("Reverted because".to_owned());
+        l1_accepted.block.as_mut().unwrap().l1_transaction = Some(L1Transaction {
+            hash: H256::repeat_byte(0xab),
+            timestamp: 1700000000,
+        });
+
+        let receipts = [
+            l1_accepted,
+            receipt(
+                transaction_hash!("0x2"),
+                ReceiptKind::DeployAccount {
+                    contract_address: contract_address!("0xdac"),
+                },
+            ),
+            pending(transaction_hash!("0x3"), ReceiptKind::Declare),
+        ];
+
+        for receipt in receipts {
+            let json =
+                serde_json::to_value(receipt.clone().into_versioned(RpcVersion::V05)).unwrap();
+            let parsed = serde_json::from_value::<Receipt>(json).unwrap();
+
+            assert_eq!(parsed, receipt);
+        }
+    }
+
+    #[test]
+    fn execution_resources_properties_into() {
+        let original = gateway::ExecutionResources {
+            builtin_instance_counter: gateway::BuiltinCounters {
+                output_builtin: 0,
+                pedersen_builtin: 1,
+                range_check_builtin: 2,
+                ecdsa_builtin: 3,
+                bitwise_builtin: 4,
+                ec_op_builtin: 5,
+                keccak_builtin: 6,
+                poseidon_builtin: 7,
+                segment_arena_builtin: 8,
+            },
+            n_steps: 9,
+            n_memory_holes: 10,
+        };
+
+        let into = ExecutionResourcesProperties::from(original);
+
+        assert_eq!(into.steps, original.n_steps);
+        assert_eq!(into.memory_holes, original.n_memory_holes);
+        assert_eq!(
+            into.range_check_builtin_applications,
+            original.builtin_instance_counter.range_check_builtin
+        );
+        assert_eq!(
+            into.pedersen_builtin_applications,
+            original.builtin_instance_counter.pedersen_builtin
+        );
+        assert_eq!(
+            into.poseidon_builtin_applications,
+            original.builtin_instance_counter.poseidon_builtin
+        );
+        assert_eq!(
+            into.ec_op_builtin_applications,
+            original.builtin_instance_counter.ec_op_builtin
+        );
+        assert_eq!(
+            into.ecdsa_builtin_applications,
+            original.builtin_instance_counter.ecdsa_builtin
+        );
+        assert_eq!(
+            into.bitwise_builtin_applications,
+            original.builtin_instance_counter.bitwise_builtin
+        );
+        assert_eq!(
+            into.keccak_builtin_applications,
+            original.builtin_instance_counter.keccak_builtin
+        );
+    }
+}
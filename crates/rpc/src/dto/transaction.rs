@@ -0,0 +1,931 @@
+use pathfinder_common::transaction::{
+    DeclareTransactionV0V1, DeclareTransactionV2, DeclareTransactionV3, DeployAccountTransaction,
+    DeployAccountTransactionV3, DeployTransaction, InvokeTransactionV0, InvokeTransactionV1,
+    InvokeTransactionV3, L1HandlerTransaction,
+};
+use pathfinder_common::{Tip, TransactionHash, TransactionVersion};
+use serde::ser::SerializeStruct;
+use serde::Serialize;
+
+use starknet_gateway_types::reply::transaction::Transaction as GatewayTransaction;
+
+use crate::dto::{IntoRpcVersioned, RpcVersion};
+use crate::v02::types::{DataAvailabilityMode, ResourceBounds};
+
+/// Equivalent to the TXN type from the specification.
+#[derive(PartialEq, Debug, Clone, Eq)]
+pub struct Transaction(pub pathfinder_common::transaction::TransactionVariant);
+
+/// A transaction and its hash, a common structure used in the spec.
+///
+/// Serializes as the `v0.4` specification, which is unchanged by `v0.5`. The `v0.3` methods
+/// return it [versioned](IntoRpcVersioned) instead.
+#[derive(serde::Serialize, PartialEq, Debug, Clone, Eq)]
+pub struct TransactionWithHash {
+    pub transaction_hash: TransactionHash,
+    #[serde(flatten)]
+    pub txn: Transaction,
+}
+
+impl From<pathfinder_common::transaction::Transaction> for TransactionWithHash {
+    fn from(value: pathfinder_common::transaction::Transaction) -> Self {
+        Self {
+            transaction_hash: value.hash,
+            txn: Transaction(value.variant),
+        }
+    }
+}
+
+impl From<GatewayTransaction> for TransactionWithHash {
+    fn from(value: GatewayTransaction) -> Self {
+        pathfinder_common::transaction::Transaction::from(value).into()
+    }
+}
+
+impl IntoRpcVersioned for TransactionWithHash {
+    fn serialize_versioned<S>(&self, version: RpcVersion, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match version {
+            // Unlike later versions, v0.3 includes the (zero) nonce of v0 declare and invoke
+            // transactions.
+            RpcVersion::V03 => {
+                let transaction = pathfinder_common::transaction::Transaction {
+                    hash: self.transaction_hash,
+                    variant: self.txn.0.clone(),
+                };
+                crate::v02::types::reply::Transaction::from(GatewayTransaction::from(transaction))
+                    .serialize(serializer)
+            }
+            RpcVersion::V04 | RpcVersion::V05 => self.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Transaction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use pathfinder_common::transaction::TransactionVariant;
+        match &self.0 {
+            TransactionVariant::DeclareV0(x) => DeclareV0Helper(x).serialize(serializer),
+            TransactionVariant::DeclareV1(x) => DeclareV1Helper(x).serialize(serializer),
+            TransactionVariant::DeclareV2(x) => DeclareV2Helper(x).serialize(serializer),
+            TransactionVariant::DeclareV3(x) => DeclareV3Helper(x).serialize(serializer),
+            TransactionVariant::Deploy(x) => DeployHelper(x).serialize(serializer),
+            TransactionVariant::DeployAccount(x) => DeployAccountHelper(x).serialize(serializer),
+            TransactionVariant::DeployAccountV3(x) => {
+                DeployAccountV3Helper(x).serialize(serializer)
+            }
+            TransactionVariant::InvokeV0(x) => InvokeV0Helper(x).serialize(serializer),
+            TransactionVariant::InvokeV1(x) => InvokeV1Helper(x).serialize(serializer),
+            TransactionVariant::InvokeV3(x) => InvokeV3Helper(x).serialize(serializer),
+            TransactionVariant::L1Handler(x) => L1HandlerHelper(x).serialize(serializer),
+        }
+    }
+}
+
+/// Deserializes the transaction as [the reply of older versions](crate::v02::types::reply::Transaction),
+/// which has the same fields.
+#[cfg(any(test, feature = "rpc-full-serde"))]
+impl<'de> serde::Deserialize<'de> for TransactionWithHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use crate::v02::types::reply;
+        use crate::v02::types::request::deployed_contract_address;
+        use pathfinder_common::transaction::TransactionVariant;
+        use pathfinder_common::CallParam;
+        use serde::Deserialize;
+
+        let transaction = reply::Transaction::deserialize(deserializer)?;
+        let transaction_hash = transaction.hash();
+
+        let variant = match transaction {
+            reply::Transaction::Declare(reply::DeclareTransaction::V0(tx)) => {
+                TransactionVariant::DeclareV0(DeclareTransactionV0V1 {
+                    class_hash: tx.class_hash,
+                    max_fee: tx.common.max_fee,
+                    nonce: tx.common.nonce,
+                    sender_address: tx.sender_address,
+                    signature: tx.common.signature,
+                })
+            }
+            reply::Transaction::Declare(reply::DeclareTransaction::V1(tx)) => {
+                TransactionVariant::DeclareV1(DeclareTransactionV0V1 {
+                    class_hash: tx.class_hash,
+                    max_fee: tx.common.max_fee,
+                    nonce: tx.common.nonce,
+                    sender_address: tx.sender_address,
+                    signature: tx.common.signature,
+                })
+            }
+            reply::Transaction::Declare(reply::DeclareTransaction::V2(tx)) => {
+                TransactionVariant::DeclareV2(DeclareTransactionV2 {
+                    class_hash: tx.class_hash,
+                    max_fee: tx.common.max_fee,
+                    nonce: tx.common.nonce,
+                    sender_address: tx.sender_address,
+                    signature: tx.common.signature,
+                    compiled_class_hash: tx.compiled_class_hash,
+                })
+            }
+            reply::Transaction::Declare(reply::DeclareTransaction::V3(tx)) => {
+                TransactionVariant::DeclareV3(DeclareTransactionV3 {
+                    class_hash: tx.class_hash,
+                    nonce: tx.common.nonce,
+                    nonce_data_availability_mode: tx.common.nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: tx.common.fee_data_availability_mode.into(),
+                    resource_bounds: tx.common.resource_bounds.into(),
+                    tip: tx.common.tip,
+                    paymaster_data: tx.common.paymaster_data,
+                    signature: tx.common.signature,
+                    account_deployment_data: tx.account_deployment_data,
+                    sender_address: tx.sender_address,
+                    compiled_class_hash: tx.compiled_class_hash,
+                })
+            }
+            reply::Transaction::Deploy(tx) => {
+                let calldata = tx
+                    .constructor_calldata
+                    .iter()
+                    .map(|param| CallParam(param.0))
+                    .collect::<Vec<_>>();
+                TransactionVariant::Deploy(DeployTransaction {
+                    contract_address: deployed_contract_address(
+                        tx.contract_address_salt,
+                        tx.class_hash,
+                        &calldata,
+                    ),
+                    contract_address_salt: tx.contract_address_salt,
+                    class_hash: tx.class_hash,
+                    constructor_calldata: tx.constructor_calldata,
+                    version: tx.version,
+                })
+            }
+            reply::Transaction::DeployAccount(reply::DeployAccountTransaction::V0V1(tx)) => {
+                TransactionVariant::DeployAccount(DeployAccountTransaction {
+                    contract_address: deployed_contract_address(
+                        tx.contract_address_salt,
+                        tx.class_hash,
+                        &tx.constructor_calldata,
+                    ),
+                    max_fee: tx.common.max_fee,
+                    version: tx.common.version,
+                    signature: tx.common.signature,
+                    nonce: tx.common.nonce,
+                    contract_address_salt: tx.contract_address_salt,
+                    constructor_calldata: tx.constructor_calldata,
+                    class_hash: tx.class_hash,
+                })
+            }
+            reply::Transaction::DeployAccount(reply::DeployAccountTransaction::V3(tx)) => {
+                TransactionVariant::DeployAccountV3(DeployAccountTransactionV3 {
+                    contract_address: deployed_contract_address(
+                        tx.contract_address_salt,
+                        tx.class_hash,
+                        &tx.constructor_calldata,
+                    ),
+                    signature: tx.common.signature,
+                    nonce: tx.common.nonce,
+                    nonce_data_availability_mode: tx.common.nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: tx.common.fee_data_availability_mode.into(),
+                    resource_bounds: tx.common.resource_bounds.into(),
+                    tip: tx.common.tip,
+                    paymaster_data: tx.common.paymaster_data,
+                    contract_address_salt: tx.contract_address_salt,
+                    constructor_calldata: tx.constructor_calldata,
+                    class_hash: tx.class_hash,
+                })
+            }
+            reply::Transaction::Invoke(reply::InvokeTransaction::V0(tx)) => {
+                TransactionVariant::InvokeV0(InvokeTransactionV0 {
+                    calldata: tx.calldata,
+                    sender_address: tx.contract_address,
+                    entry_point_selector: tx.entry_point_selector,
+                    entry_point_type: None,
+                    max_fee: tx.common.max_fee,
+                    signature: tx.common.signature,
+                })
+            }
+            reply::Transaction::Invoke(reply::InvokeTransaction::V1(tx)) => {
+                TransactionVariant::InvokeV1(InvokeTransactionV1 {
+                    calldata: tx.calldata,
+                    sender_address: tx.sender_address,
+                    max_fee: tx.common.max_fee,
+                    signature: tx.common.signature,
+                    nonce: tx.common.nonce,
+                })
+            }
+            reply::Transaction::Invoke(reply::InvokeTransaction::V3(tx)) => {
+                TransactionVariant::InvokeV3(InvokeTransactionV3 {
+                    signature: tx.common.signature,
+                    nonce: tx.common.nonce,
+                    nonce_data_availability_mode: tx.common.nonce_data_availability_mode.into(),
+                    fee_data_availability_mode: tx.common.fee_data_availability_mode.into(),
+                    resource_bounds: tx.common.resource_bounds.into(),
+                    tip: tx.common.tip,
+                    paymaster_data: tx.common.paymaster_data,
+                    account_deployment_data: tx.account_deployment_data,
+                    calldata: tx.calldata,
+                    sender_address: tx.sender_address,
+                })
+            }
+            reply::Transaction::L1Handler(tx) => {
+                TransactionVariant::L1Handler(L1HandlerTransaction {
+                    contract_address: tx.contract_address,
+                    entry_point_selector: tx.entry_point_selector,
+                    nonce: tx.nonce,
+                    calldata: tx.calldata,
+                    version: tx.version,
+                })
+            }
+        };
+
+        Ok(Self {
+            transaction_hash,
+            txn: Transaction(variant),
+        })
+    }
+}
+
+struct DeclareV0Helper<'a>(&'a DeclareTransactionV0V1);
+struct DeclareV1Helper<'a>(&'a DeclareTransactionV0V1);
+struct DeclareV2Helper<'a>(&'a DeclareTransactionV2);
+struct DeclareV3Helper<'a>(&'a DeclareTransactionV3);
+struct DeployHelper<'a>(&'a DeployTransaction);
+struct DeployAccountHelper<'a>(&'a DeployAccountTransaction);
+struct DeployAccountV3Helper<'a>(&'a DeployAccountTransactionV3);
+struct InvokeV0Helper<'a>(&'a InvokeTransactionV0);
+struct InvokeV1Helper<'a>(&'a InvokeTransactionV1);
+struct InvokeV3Helper<'a>(&'a InvokeTransactionV3);
+struct L1HandlerHelper<'a>(&'a L1HandlerTransaction);
+struct TransactionVersionHelper<'a>(&'a TransactionVersion);
+struct TipHelper<'a>(&'a Tip);
+
+impl Serialize for DeclareV0Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("DeclareV0", 6)?;
+        s.serialize_field("type", "DECLARE")?;
+        s.serialize_field("sender_address", &self.0.sender_address)?;
+        s.serialize_field("max_fee", &self.0.max_fee)?;
+        s.serialize_field("version", "0x0")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.end()
+    }
+}
+
+impl Serialize for DeclareV1Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("DeclareV1", 7)?;
+        s.serialize_field("type", "DECLARE")?;
+        s.serialize_field("sender_address", &self.0.sender_address)?;
+        s.serialize_field("max_fee", &self.0.max_fee)?;
+        s.serialize_field("version", "0x1")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.end()
+    }
+}
+
+impl Serialize for DeclareV2Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("DeclareV2", 8)?;
+        s.serialize_field("type", "DECLARE")?;
+        s.serialize_field("sender_address", &self.0.sender_address)?;
+        s.serialize_field("compiled_class_hash", &self.0.compiled_class_hash)?;
+        s.serialize_field("max_fee", &self.0.max_fee)?;
+        s.serialize_field("version", "0x2")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.end()
+    }
+}
+
+impl Serialize for DeclareV3Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("DeclareV3", 13)?;
+        s.serialize_field("type", "DECLARE")?;
+        s.serialize_field("sender_address", &self.0.sender_address)?;
+        s.serialize_field("compiled_class_hash", &self.0.compiled_class_hash)?;
+        s.serialize_field("version", "0x3")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.serialize_field(
+            "resource_bounds",
+            &ResourceBounds::from(self.0.resource_bounds),
+        )?;
+        s.serialize_field("tip", &TipHelper(&self.0.tip))?;
+        s.serialize_field("paymaster_data", &self.0.paymaster_data)?;
+        s.serialize_field("account_deployment_data", &self.0.account_deployment_data)?;
+        s.serialize_field(
+            "nonce_data_availability_mode",
+            &DataAvailabilityMode::from(self.0.nonce_data_availability_mode),
+        )?;
+        s.serialize_field(
+            "fee_data_availability_mode",
+            &DataAvailabilityMode::from(self.0.fee_data_availability_mode),
+        )?;
+        s.end()
+    }
+}
+
+impl Serialize for DeployHelper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("Deploy", 5)?;
+        s.serialize_field("version", &TransactionVersionHelper(&self.0.version))?;
+        s.serialize_field("type", "DEPLOY")?;
+        s.serialize_field("contract_address_salt", &self.0.contract_address_salt)?;
+        s.serialize_field("constructor_calldata", &self.0.constructor_calldata)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.end()
+    }
+}
+
+impl Serialize for DeployAccountHelper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("DeployAccount", 8)?;
+        s.serialize_field("type", "DEPLOY_ACCOUNT")?;
+        s.serialize_field("max_fee", &self.0.max_fee)?;
+        s.serialize_field("version", &TransactionVersionHelper(&self.0.version))?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field("contract_address_salt", &self.0.contract_address_salt)?;
+        s.serialize_field("constructor_calldata", &self.0.constructor_calldata)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.end()
+    }
+}
+
+impl Serialize for DeployAccountV3Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("DeployAccountV3", 12)?;
+        s.serialize_field("type", "DEPLOY_ACCOUNT")?;
+        s.serialize_field("version", "0x3")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field("contract_address_salt", &self.0.contract_address_salt)?;
+        s.serialize_field("constructor_calldata", &self.0.constructor_calldata)?;
+        s.serialize_field("class_hash", &self.0.class_hash)?;
+        s.serialize_field(
+            "resource_bounds",
+            &ResourceBounds::from(self.0.resource_bounds),
+        )?;
+        s.serialize_field("tip", &TipHelper(&self.0.tip))?;
+        s.serialize_field("paymaster_data", &self.0.paymaster_data)?;
+        s.serialize_field(
+            "nonce_data_availability_mode",
+            &DataAvailabilityMode::from(self.0.nonce_data_availability_mode),
+        )?;
+        s.serialize_field(
+            "fee_data_availability_mode",
+            &DataAvailabilityMode::from(self.0.fee_data_availability_mode),
+        )?;
+        s.end()
+    }
+}
+
+impl Serialize for InvokeV0Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("InvokeV0", 7)?;
+        s.serialize_field("type", "INVOKE")?;
+        s.serialize_field("max_fee", &self.0.max_fee)?;
+        s.serialize_field("version", "0x0")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("contract_address", &self.0.sender_address)?;
+        s.serialize_field("entry_point_selector", &self.0.entry_point_selector)?;
+        s.serialize_field("calldata", &self.0.calldata)?;
+        s.end()
+    }
+}
+
+impl Serialize for InvokeV1Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("InvokeV1", 7)?;
+        s.serialize_field("type", "INVOKE")?;
+        s.serialize_field("sender_address", &self.0.sender_address)?;
+        s.serialize_field("calldata", &self.0.calldata)?;
+        s.serialize_field("max_fee", &self.0.max_fee)?;
+        s.serialize_field("version", "0x1")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.end()
+    }
+}
+
+impl Serialize for InvokeV3Helper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("InvokeV3", 12)?;
+        s.serialize_field("type", "INVOKE")?;
+        s.serialize_field("sender_address", &self.0.sender_address)?;
+        s.serialize_field("calldata", &self.0.calldata)?;
+        s.serialize_field("version", "0x3")?;
+        s.serialize_field("signature", &self.0.signature)?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field(
+            "resource_bounds",
+            &ResourceBounds::from(self.0.resource_bounds),
+        )?;
+        s.serialize_field("tip", &TipHelper(&self.0.tip))?;
+        s.serialize_field("paymaster_data", &self.0.paymaster_data)?;
+        s.serialize_field("account_deployment_data", &self.0.account_deployment_data)?;
+        s.serialize_field(
+            "nonce_data_availability_mode",
+            &DataAvailabilityMode::from(self.0.nonce_data_availability_mode),
+        )?;
+        s.serialize_field(
+            "fee_data_availability_mode",
+            &DataAvailabilityMode::from(self.0.fee_data_availability_mode),
+        )?;
+        s.end()
+    }
+}
+
+impl Serialize for L1HandlerHelper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("L1Handler", 6)?;
+        s.serialize_field("version", &TransactionVersionHelper(&self.0.version))?;
+        s.serialize_field("type", "L1_HANDLER")?;
+        s.serialize_field("nonce", &self.0.nonce)?;
+        s.serialize_field("contract_address", &self.0.contract_address)?;
+        s.serialize_field("entry_point_selector", &self.0.entry_point_selector)?;
+        s.serialize_field("calldata", &self.0.calldata)?;
+        s.end()
+    }
+}
+
+impl Serialize for TransactionVersionHelper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use pathfinder_serde::bytes_to_hex_str;
+        serializer.serialize_str(&bytes_to_hex_str(self.0 .0.as_bytes()))
+    }
+}
+
+impl Serialize for TipHelper<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&format!("{:#x}", self.0 .0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pathfinder_common::macro_prelude::*;
+
+    mod serialization {
+        use super::*;
+        use pathfinder_common::transaction::*;
+        // The common types, rather than their RPC counterparts of the parent module.
+        use pathfinder_common::transaction::{DataAvailabilityMode, ResourceBound, ResourceBounds};
+        use pathfinder_common::{ResourceAmount, ResourcePricePerUnit, Tip, TransactionVersion};
+        use pretty_assertions::assert_eq;
+        use serde_json::json;
+
+        #[test]
+        fn declare_v0() {
+            let original = DeclareTransactionV0V1 {
+                class_hash: class_hash!("0x123"),
+                max_fee: fee!("0x1111"),
+                nonce: transaction_nonce!("0xaabbcc"),
+                sender_address: contract_address!("0xabc"),
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+            };
+
+            let expected = json!({
+                "type": "DECLARE",
+                "version": "0x0",
+                "sender_address": "0xabc",
+                "max_fee": "0x1111",
+                "signature": ["0xa1b1", "0x1a1b"],
+                "class_hash": "0x123",
+            });
+            let uut = Transaction(TransactionVariant::DeclareV0(original));
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn declare_v1() {
+            let original = DeclareTransactionV0V1 {
+                class_hash: class_hash!("0x123"),
+                max_fee: fee!("0x1111"),
+                nonce: transaction_nonce!("0xaabbcc"),
+                sender_address: contract_address!("0xabc"),
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+            };
+
+            let expected = json!({
+                "type": "DECLARE",
+                "version": "0x1",
+                "sender_address": "0xabc",
+                "max_fee": "0x1111",
+                "signature": ["0xa1b1", "0x1a1b"],
+                "class_hash": "0x123",
+                "nonce": "0xaabbcc",
+            });
+            let uut = Transaction(TransactionVariant::DeclareV1(original));
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn declare_v2() {
+            let original: TransactionVariant = DeclareTransactionV2 {
+                class_hash: class_hash!("0x123"),
+                max_fee: fee!("0x1111"),
+                nonce: transaction_nonce!("0xaabbcc"),
+                sender_address: contract_address!("0xabc"),
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+                compiled_class_hash: casm_hash!("0xbbbbb"),
+            }
+            .into();
+
+            let expected = json!({
+                "type": "DECLARE",
+                "version": "0x2",
+                "sender_address": "0xabc",
+                "max_fee": "0x1111",
+                "signature": ["0xa1b1", "0x1a1b"],
+                "class_hash": "0x123",
+                "nonce": "0xaabbcc",
+                "compiled_class_hash": "0xbbbbb",
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn deploy() {
+            let original: TransactionVariant = DeployTransaction {
+                contract_address: contract_address!("0xabc"),
+                contract_address_salt: contract_address_salt!("0xeeee"),
+                class_hash: class_hash!("0x123"),
+                constructor_calldata: vec![
+                    constructor_param!("0xbbb0"),
+                    constructor_param!("0xbbb1"),
+                ],
+                version: TransactionVersion::TWO,
+            }
+            .into();
+
+            let expected = json!({
+                "type": "DEPLOY",
+                "contract_address_salt": "0xeeee",
+                "class_hash": "0x123",
+                "constructor_calldata": ["0xbbb0","0xbbb1"],
+                "version": "0x2",
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn deploy_account() {
+            let original: TransactionVariant = DeployAccountTransaction {
+                contract_address: contract_address!("0xabc"),
+                max_fee: fee!("0x1111"),
+                version: TransactionVersion::TWO,
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+                nonce: transaction_nonce!("0xaabbcc"),
+                contract_address_salt: contract_address_salt!("0xeeee"),
+                constructor_calldata: vec![call_param!("0xbbb0"), call_param!("0xbbb1")],
+                class_hash: class_hash!("0x123"),
+            }
+            .into();
+
+            let expected = json!({
+                "type": "DEPLOY_ACCOUNT",
+                "max_fee": "0x1111",
+                "version": "0x2",
+                "signature": ["0xa1b1", "0x1a1b"],
+                "nonce": "0xaabbcc",
+                "contract_address_salt": "0xeeee",
+                "constructor_calldata": ["0xbbb0","0xbbb1"],
+                "class_hash": "0x123",
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn invoke_v0() {
+            let original: TransactionVariant = InvokeTransactionV0 {
+                calldata: vec![call_param!("0xfff1"), call_param!("0xfff0")],
+                sender_address: contract_address!("0xabc"),
+                entry_point_selector: entry_point!("0xdead"),
+                entry_point_type: Some(EntryPointType::External),
+                max_fee: fee!("0x1111"),
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+            }
+            .into();
+
+            let expected = json!({
+                "type": "INVOKE",
+                "version": "0x0",
+                "calldata": ["0xfff1","0xfff0"],
+                "contract_address": "0xabc",
+                "entry_point_selector": "0xdead",
+                "max_fee": "0x1111",
+                "signature": ["0xa1b1", "0x1a1b"],
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn invoke_v1() {
+            let original: TransactionVariant = InvokeTransactionV1 {
+                calldata: vec![call_param!("0xfff1"), call_param!("0xfff0")],
+                sender_address: contract_address!("0xabc"),
+                max_fee: fee!("0x1111"),
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+                nonce: transaction_nonce!("0xaabbcc"),
+            }
+            .into();
+
+            let expected = json!({
+                "type": "INVOKE",
+                "version": "0x1",
+                "calldata": ["0xfff1","0xfff0"],
+                "sender_address": "0xabc",
+                "max_fee": "0x1111",
+                "signature": ["0xa1b1", "0x1a1b"],
+                "nonce": "0xaabbcc",
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn invoke_v3() {
+            let original: TransactionVariant = InvokeTransactionV3 {
+                signature: vec![
+                    transaction_signature_elem!("0xa1b1"),
+                    transaction_signature_elem!("0x1a1b"),
+                ],
+                nonce: transaction_nonce!("0xaabbcc"),
+                nonce_data_availability_mode: DataAvailabilityMode::L1,
+                fee_data_availability_mode: DataAvailabilityMode::L2,
+                resource_bounds: ResourceBounds {
+                    l1_gas: ResourceBound {
+                        max_amount: ResourceAmount(0x1111),
+                        max_price_per_unit: ResourcePricePerUnit(0x2222),
+                    },
+                    l2_gas: ResourceBound {
+                        max_amount: ResourceAmount(0),
+                        max_price_per_unit: ResourcePricePerUnit(0),
+                    },
+                },
+                tip: Tip(0x1234),
+                paymaster_data: vec![paymaster_data_elem!("0xaaaa")],
+                account_deployment_data: vec![account_deployment_data_elem!("0xbbbb")],
+                calldata: vec![call_param!("0xfff1"), call_param!("0xfff0")],
+                sender_address: contract_address!("0xabc"),
+            }
+            .into();
+
+            let expected = json!({
+                "type": "INVOKE",
+                "version": "0x3",
+                "calldata": ["0xfff1","0xfff0"],
+                "sender_address": "0xabc",
+                "signature": ["0xa1b1", "0x1a1b"],
+                "nonce": "0xaabbcc",
+                "resource_bounds": {
+                    "l1_gas": {"max_amount": "0x1111", "max_price_per_unit": "0x2222"},
+                    "l2_gas": {"max_amount": "0x0", "max_price_per_unit": "0x0"},
+                },
+                "tip": "0x1234",
+                "paymaster_data": ["0xaaaa"],
+                "account_deployment_data": ["0xbbbb"],
+                "nonce_data_availability_mode": "L1",
+                "fee_data_availability_mode": "L2",
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn l1_handler() {
+            let original: TransactionVariant = L1HandlerTransaction {
+                contract_address: contract_address!("0xabc"),
+                entry_point_selector: entry_point!("0xdead"),
+                nonce: transaction_nonce!("0xaabbcc"),
+                calldata: vec![call_param!("0xfff1"), call_param!("0xfff0")],
+                version: TransactionVersion::TWO,
+            }
+            .into();
+
+            let expected = json!({
+                "type": "L1_HANDLER",
+                "contract_address": "0xabc",
+                "entry_point_selector": "0xdead",
+                "nonce": "0xaabbcc",
+                "calldata": ["0xfff1","0xfff0"],
+                "version": "0x2",
+            });
+            let uut = Transaction(original);
+            let result = serde_json::to_value(uut).unwrap();
+
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn versioned() {
+        use pathfinder_common::transaction::{InvokeTransactionV0, TransactionVariant};
+        use serde_json::json;
+
+        let transaction = TransactionWithHash {
+            transaction_hash: transaction_hash!("0x1"),
+            txn: Transaction(TransactionVariant::InvokeV0(InvokeTransactionV0 {
+                calldata: vec![call_param!("0xfff1")],
+                sender_address: contract_address!("0xabc"),
+                entry_point_selector: entry_point!("0xdead"),
+                entry_point_type: None,
+                max_fee: fee!("0x1111"),
+                signature: vec![],
+            })),
+        };
+
+        let v04 = json!({
+            "transaction_hash": "0x1",
+            "type": "INVOKE",
+            "version": "0x0",
+            "calldata": ["0xfff1"],
+            "contract_address": "0xabc",
+            "entry_point_selector": "0xdead",
+            "max_fee": "0x1111",
+            "signature": [],
+        });
+        let mut v03 = v04.clone();
+        v03["nonce"] = json!("0x0");
+
+        for (version, expected) in [
+            (RpcVersion::V03, v03),
+            (RpcVersion::V04, v04.clone()),
+            (RpcVersion::V05, v04),
+        ] {
+            let result = serde_json::to_value(transaction.clone().into_versioned(version)).unwrap();
+            pretty_assertions::assert_eq!(result, expected, "{version:?}");
+        }
+    }
+
+    mod deserialization {
+        use super::*;
+        use pathfinder_common::transaction::*;
+        use pathfinder_common::transaction::{DataAvailabilityMode, ResourceBound, ResourceBounds};
+        use pathfinder_common::{ResourceAmount, ResourcePricePerUnit, Tip, TransactionVersion};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn roundtrip() {
+            let salt = contract_address_salt!("0xeeee");
+            let calldata = vec![call_param!("0xbbb0"), call_param!("0xbbb1")];
+            let deployed = crate::v02::types::request::deployed_contract_address(
+                salt,
+                class_hash!("0x123"),
+                &calldata,
+            );
+
+            let variants: Vec<TransactionVariant> = vec![
+                // V0 transactions don't serialize their nonce.
+                DeclareTransactionV0V1 {
+                    class_hash: class_hash!("0x123"),
+                    max_fee: fee!("0x1111"),
+                    nonce: Default::default(),
+                    sender_address: contract_address!("0xabc"),
+                    signature: vec![transaction_signature_elem!("0xa1b1")],
+                }
+                .into(),
+                DeployAccountTransaction {
+                    contract_address: deployed,
+                    max_fee: fee!("0x1111"),
+                    version: TransactionVersion::ONE,
+                    signature: vec![transaction_signature_elem!("0xa1b1")],
+                    nonce: transaction_nonce!("0xaabbcc"),
+                    contract_address_salt: salt,
+                    constructor_calldata: calldata.clone(),
+                    class_hash: class_hash!("0x123"),
+                }
+                .into(),
+                InvokeTransactionV3 {
+                    signature: vec![transaction_signature_elem!("0xa1b1")],
+                    nonce: transaction_nonce!("0xaabbcc"),
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L2,
+                    resource_bounds: ResourceBounds {
+                        l1_gas: ResourceBound {
+                            max_amount: ResourceAmount(0x1111),
+                            max_price_per_unit: ResourcePricePerUnit(0x2222),
+                        },
+                        l2_gas: Default::default(),
+                    },
+                    tip: Tip(0x1234),
+                    paymaster_data: vec![paymaster_data_elem!("0xaaaa")],
+                    account_deployment_data: vec![account_deployment_data_elem!("0xbbbb")],
+                    calldata,
+                    sender_address: contract_address!("0xabc"),
+                }
+                .into(),
+                L1HandlerTransaction {
+                    contract_address: contract_address!("0xabc"),
+                    entry_point_selector: entry_point!("0xdead"),
+                    nonce: transaction_nonce!("0xaabbcc"),
+                    calldata: vec![call_param!("0xfff1")],
+                    version: TransactionVersion::ZERO,
+                }
+                .into(),
+            ];
+
+            for variant in variants {
+                let transaction = TransactionWithHash {
+                    transaction_hash: transaction_hash!("0x1"),
+                    txn: Transaction(variant),
+                };
+                let json = serde_json::to_value(&transaction).unwrap();
+                let result = serde_json::from_value::<TransactionWithHash>(json).unwrap();
+
+                assert_eq!(result, transaction);
+            }
+        }
+    }
+}
@@ -76,6 +76,19 @@ pub enum ApplicationError {
     UnexpectedError { data: String },
     #[error("Too many storage keys requested")]
     ProofLimitExceeded { limit: u32, requested: u32 },
+    #[error("Response too large, reduce the page size")]
+    ResponseTooLarge { limit: usize },
+    #[error("Execution resources exceeded")]
+    ExecutionResourcesExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
+    /// The database stayed busy, e.g. while the sync process held a write lock for long.
+    #[error("Node is busy, retry later")]
+    NodeBusy,
+    /// The client's token doesn't allow calling the method.
+    #[error("Method not allowed")]
+    MethodNotAllowed,
     #[error("Internal error")]
     GatewayError(starknet_gateway_types::error::StarknetError),
     /// Internal errors are errors whose details we don't want to show to the end user.
@@ -126,6 +139,10 @@ impl ApplicationError {
             ApplicationError::UnexpectedError { .. } => 63,
             // doc/rpc/pathfinder_rpc_api.json
             ApplicationError::ProofLimitExceeded { .. } => 10000,
+            ApplicationError::ResponseTooLarge { .. } => 10001,
+            ApplicationError::ExecutionResourcesExceeded { .. } => 10002,
+            ApplicationError::NodeBusy => 10003,
+            ApplicationError::MethodNotAllowed => 10004,
             // https://www.jsonrpc.org/specification#error_object
             ApplicationError::GatewayError(_)
             | ApplicationError::Internal(_)
@@ -164,6 +181,8 @@ impl ApplicationError {
             ApplicationError::CompiledClassHashMismatch => None,
             ApplicationError::UnsupportedTxVersion => None,
             ApplicationError::UnsupportedContractClassVersion => None,
+            ApplicationError::NodeBusy => None,
+            ApplicationError::MethodNotAllowed => None,
             ApplicationError::GatewayError(error) => Some(json!({
                 "error": error,
             })),
@@ -195,6 +214,13 @@ impl ApplicationError {
                 "limit": limit,
                 "requested": requested,
             })),
+            ApplicationError::ResponseTooLarge { limit } => Some(json!({
+                "limit": limit,
+            })),
+            ApplicationError::ExecutionResourcesExceeded { resource, limit } => Some(json!({
+                "resource": resource,
+                "limit": limit,
+            })),
         }
     }
 }
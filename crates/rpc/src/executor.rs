@@ -17,12 +17,63 @@ impl From<anyhow::Error> for ExecutionStateError {
     }
 }
 
+/// A token for [ExecutionState::with_cancellation](pathfinder_executor::ExecutionState::with_cancellation)
+/// which is cancelled once the returned guard is dropped.
+///
+/// Methods hold the guard across the blocking execution, so that executions of requests which
+/// are dropped because the client disconnected or the request timed out stop early.
+pub(crate) fn cancel_on_drop() -> (
+    pathfinder_executor::CancellationToken,
+    pathfinder_executor::DropGuard,
+) {
+    let cancellation = pathfinder_executor::CancellationToken::default();
+    let guard = cancellation.drop_guard();
+    (cancellation, guard)
+}
+
+/// Describes an exceeded [ExecutionLimits](pathfinder_executor::ExecutionLimits) limit, for
+/// errors which can't report it as
+/// [ExecutionResourcesExceeded](crate::error::ApplicationError::ExecutionResourcesExceeded).
+pub(crate) fn resources_exceeded_error(resource: &str, limit: usize) -> anyhow::Error {
+    anyhow::anyhow!("Execution exceeded the limit of {limit} {resource}")
+}
+
 pub const VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY:
     semver::Version = semver::Version::new(0, 12, 3);
 
+/// The executor's blockifier version predates V3 transactions.
+const V3_NOT_SUPPORTED: &str = "Executing V3 transactions is not yet supported";
+
+/// Maps a broadcasted transaction to the executor's representation.
+///
+/// V3 transactions can't be executed yet, and are rejected with an error reported to the client
+/// instead of an internal error.
 pub(crate) fn map_broadcasted_transaction(
     transaction: &BroadcastedTransaction,
     chain_id: ChainId,
+) -> Result<pathfinder_executor::Transaction, pathfinder_executor::CallError> {
+    use crate::v02::types::request::{
+        BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
+        BroadcastedInvokeTransaction,
+    };
+
+    if matches!(
+        transaction,
+        BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(_))
+            | BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(_))
+            | BroadcastedTransaction::DeployAccount(BroadcastedDeployAccountTransaction::V3(_))
+    ) {
+        return Err(pathfinder_executor::CallError::Custom(anyhow::anyhow!(
+            V3_NOT_SUPPORTED
+        )));
+    }
+
+    map_transaction(transaction, chain_id).map_err(pathfinder_executor::CallError::Internal)
+}
+
+fn map_transaction(
+    transaction: &BroadcastedTransaction,
+    chain_id: ChainId,
 ) -> anyhow::Result<pathfinder_executor::Transaction> {
     match transaction {
         BroadcastedTransaction::Declare(tx) => match tx {
@@ -164,6 +215,9 @@ pub(crate) fn map_broadcasted_transaction(
 
                 Ok(tx)
             }
+            crate::v02::types::request::BroadcastedDeclareTransaction::V3(_) => {
+                Err(anyhow::anyhow!(V3_NOT_SUPPORTED))
+            }
         },
         BroadcastedTransaction::Invoke(tx) => match tx {
             crate::v02::types::request::BroadcastedInvokeTransaction::V0(tx) => {
@@ -243,8 +297,16 @@ pub(crate) fn map_broadcasted_transaction(
 
                 Ok(tx)
             }
+            crate::v02::types::request::BroadcastedInvokeTransaction::V3(_) => {
+                Err(anyhow::anyhow!(V3_NOT_SUPPORTED))
+            }
         },
-        BroadcastedTransaction::DeployAccount(tx) => {
+        BroadcastedTransaction::DeployAccount(
+            crate::v02::types::request::BroadcastedDeployAccountTransaction::V3(_),
+        ) => Err(anyhow::anyhow!(V3_NOT_SUPPORTED)),
+        BroadcastedTransaction::DeployAccount(
+            crate::v02::types::request::BroadcastedDeployAccountTransaction::V0V1(tx),
+        ) => {
             let transaction_hash = transaction.transaction_hash(chain_id, None);
 
             let version = tx.version;
@@ -415,11 +477,19 @@ pub fn compose_executor_transaction(
 
                 Ok(tx)
             }
+            starknet_gateway_types::reply::transaction::DeclareTransaction::V3(_) => {
+                Err(anyhow::anyhow!(V3_NOT_SUPPORTED))
+            }
         },
         starknet_gateway_types::reply::transaction::Transaction::Deploy(_) => Err(anyhow::anyhow!(
             "Deploy transactions are not yet supported in blockifier"
         )),
-        starknet_gateway_types::reply::transaction::Transaction::DeployAccount(tx) => {
+        starknet_gateway_types::reply::transaction::Transaction::DeployAccount(
+            starknet_gateway_types::reply::transaction::DeployAccountTransaction::V3(_),
+        ) => Err(anyhow::anyhow!(V3_NOT_SUPPORTED)),
+        starknet_gateway_types::reply::transaction::Transaction::DeployAccount(
+            starknet_gateway_types::reply::transaction::DeployAccountTransaction::V0V1(tx),
+        ) => {
             let contract_address = starknet_api::core::ContractAddress(
                 PatriciaKey::try_from(tx.contract_address.get().into_starkfelt())
                     .expect("No contract address overflow expected"),
@@ -527,6 +597,9 @@ pub fn compose_executor_transaction(
 
                 Ok(tx)
             }
+            starknet_gateway_types::reply::transaction::InvokeTransaction::V3(_) => {
+                Err(anyhow::anyhow!(V3_NOT_SUPPORTED))
+            }
         },
         starknet_gateway_types::reply::transaction::Transaction::L1Handler(tx) => {
             let tx = starknet_api::transaction::L1HandlerTransaction {
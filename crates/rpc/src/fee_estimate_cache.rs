@@ -0,0 +1,190 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use pathfinder_common::cache::{CacheRegistry, MemoryAccounted};
+use pathfinder_common::{BlockHash, ChainId, Fee, GasPrice};
+
+use crate::v02::types::request::{
+    BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
+    BroadcastedInvokeTransaction, BroadcastedTransaction,
+};
+use crate::v05::method::estimate_fee::FeeEstimate;
+
+/// How long a fee estimate is served from the cache.
+const TTL: Duration = Duration::from_secs(10);
+
+/// A short-lived cache of fee estimates, for wallets which estimate the same transactions
+/// repeatedly.
+///
+/// Estimates are keyed by the block they were estimated on and the content of the estimated
+/// transactions. Estimates on the pending block are keyed by its parent and the number of
+/// its transactions, so that they are invalidated once the pending block advances.
+#[derive(Clone)]
+pub struct FeeEstimateCache(Arc<Shared>);
+
+struct Shared {
+    cache: Mutex<lru::LruCache<FeeEstimateKey, Entry>>,
+    /// Keys the hashes of the transactions, so that colliding requests can't be crafted.
+    hasher: RandomState,
+}
+
+struct Entry {
+    inserted: Instant,
+    estimates: Vec<FeeEstimate>,
+}
+
+/// The block a fee estimate was made on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EstimateBlock {
+    Block(BlockHash),
+    Pending {
+        parent_hash: BlockHash,
+        transaction_count: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FeeEstimateKey {
+    block: EstimateBlock,
+    gas_price: GasPrice,
+    transactions: u64,
+}
+
+impl FeeEstimateCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        let shared = Arc::new(Shared {
+            cache: Mutex::new(lru::LruCache::new(capacity)),
+            hasher: RandomState::new(),
+        });
+
+        let accounted: Arc<dyn MemoryAccounted> = shared.clone();
+        CacheRegistry::global().register("rpc_fee_estimates", &accounted);
+
+        Self(shared)
+    }
+
+    /// The key of estimating `transactions` on `block` at `gas_price`, which differs from the
+    /// block's own if it is [overridden](crate::gas_price::GasPriceSource).
+    ///
+    /// Transactions are identified by their hash, which covers their content apart from the
+    /// signature and, for some versions, the max fee. Both of these affect the estimate as well,
+    /// so they are included separately. V3 transactions have no max fee, their resource bounds
+    /// are covered by the hash.
+    pub fn key(
+        &self,
+        block: EstimateBlock,
+        gas_price: GasPrice,
+        transactions: &[BroadcastedTransaction],
+        chain_id: ChainId,
+    ) -> anyhow::Result<FeeEstimateKey> {
+        let mut hasher = self.0.hasher.build_hasher();
+        for transaction in transactions {
+            let (class_hash, max_fee, signature) = match transaction {
+                BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V0(tx)) => (
+                    Some(tx.contract_class.class_hash()?.hash()),
+                    tx.max_fee,
+                    &tx.signature,
+                ),
+                BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V1(tx)) => (
+                    Some(tx.contract_class.class_hash()?.hash()),
+                    tx.max_fee,
+                    &tx.signature,
+                ),
+                BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V2(tx)) => (
+                    Some(tx.contract_class.class_hash()?.hash()),
+                    tx.max_fee,
+                    &tx.signature,
+                ),
+                BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(tx)) => (
+                    Some(tx.contract_class.class_hash()?.hash()),
+                    Fee::ZERO,
+                    &tx.signature,
+                ),
+                BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V0(tx)) => {
+                    (None, tx.max_fee, &tx.signature)
+                }
+                BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(tx)) => {
+                    (None, tx.max_fee, &tx.signature)
+                }
+                BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(tx)) => {
+                    (None, Fee::ZERO, &tx.signature)
+                }
+                BroadcastedTransaction::DeployAccount(
+                    BroadcastedDeployAccountTransaction::V0V1(tx),
+                ) => (None, tx.max_fee, &tx.signature),
+                BroadcastedTransaction::DeployAccount(BroadcastedDeployAccountTransaction::V3(
+                    tx,
+                )) => (None, Fee::ZERO, &tx.signature),
+            };
+            transaction
+                .transaction_hash(chain_id, class_hash)
+                .hash(&mut hasher);
+            max_fee.hash(&mut hasher);
+            signature.hash(&mut hasher);
+        }
+
+        Ok(FeeEstimateKey {
+            block,
+            gas_price,
+            transactions: hasher.finish(),
+        })
+    }
+
+    pub fn get(&self, key: &FeeEstimateKey) -> Option<Vec<FeeEstimate>> {
+        let mut cache = self.0.lock();
+        match cache.get(key) {
+            Some(entry) if entry.inserted.elapsed() < TTL => Some(entry.estimates.clone()),
+            Some(_) => {
+                cache.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: FeeEstimateKey, estimates: Vec<FeeEstimate>) {
+        let entry = Entry {
+            inserted: Instant::now(),
+            estimates,
+        };
+        self.0.lock().push(key, entry);
+    }
+}
+
+impl Default for FeeEstimateCache {
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(1024).unwrap())
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, lru::LruCache<FeeEstimateKey, Entry>> {
+        self.cache.lock().unwrap()
+    }
+}
+
+impl Entry {
+    fn size(&self) -> usize {
+        std::mem::size_of::<(FeeEstimateKey, Entry)>() + std::mem::size_of_val(&*self.estimates)
+    }
+}
+
+impl MemoryAccounted for Shared {
+    fn memory_usage(&self) -> usize {
+        self.lock().iter().map(|(_, entry)| entry.size()).sum()
+    }
+
+    fn shrink_to(&self, bytes: usize) {
+        let mut cache = self.lock();
+        let mut usage: usize = cache.iter().map(|(_, entry)| entry.size()).sum();
+        while usage > bytes {
+            let Some((_, evicted)) = cache.pop_lru() else {
+                break;
+            };
+            usage -= evicted.size();
+        }
+    }
+}
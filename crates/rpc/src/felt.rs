@@ -21,11 +21,11 @@
 //! ```
 
 use pathfinder_common::{
-    BlockHash, CallParam, CallResultValue, CasmHash, ChainId, ClassHash, ConstructorParam,
-    ContractAddress, ContractAddressSalt, ContractNonce, EntryPoint, EventData, EventKey,
-    L1ToL2MessagePayloadElem, L2ToL1MessagePayloadElem, SequencerAddress, SierraHash,
-    StateCommitment, StorageAddress, StorageValue, TransactionHash, TransactionNonce,
-    TransactionSignatureElem,
+    AccountDeploymentDataElem, BlockHash, CallParam, CallResultValue, CasmHash, ChainId, ClassHash,
+    ConstructorParam, ContractAddress, ContractAddressSalt, ContractNonce, EntryPoint, EventData,
+    EventKey, L1ToL2MessagePayloadElem, L2ToL1MessagePayloadElem, PaymasterDataElem,
+    SequencerAddress, SierraHash, StateCommitment, StorageAddress, StorageValue, TransactionHash,
+    TransactionNonce, TransactionSignatureElem,
 };
 use pathfinder_crypto::Felt;
 
@@ -166,6 +166,7 @@ macro_rules! rpc_felt_251_serde {
 }
 
 rpc_felt_serde!(
+    AccountDeploymentDataElem,
     CallParam,
     CallResultValue,
     CasmHash,
@@ -179,6 +180,7 @@ rpc_felt_serde!(
     EventData,
     L1ToL2MessagePayloadElem,
     L2ToL1MessagePayloadElem,
+    PaymasterDataElem,
     SequencerAddress,
     SierraHash,
     BlockHash,
@@ -278,3 +280,121 @@ mod deserialization {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Any valid [Felt].
+    fn felt() -> impl Strategy<Value = Felt> {
+        any::<[u8; 32]>().prop_filter_map("Not less than the field modulus", |mut bytes| {
+            bytes[0] &= 0x0f;
+            Felt::from_be_bytes(bytes).ok()
+        })
+    }
+
+    /// Any [Felt] of at most 251 bits.
+    fn felt_251() -> impl Strategy<Value = Felt> {
+        any::<[u8; 32]>().prop_map(|mut bytes| {
+            bytes[0] &= 0x07;
+            Felt::from_be_bytes(bytes).unwrap()
+        })
+    }
+
+    /// Any [Felt] of 252 bits, i.e. in `2^251..p`.
+    fn felt_252() -> impl Strategy<Value = Felt> {
+        any::<[u8; 32]>().prop_map(|mut bytes| {
+            // p = 0x0800000000000011000000000000000000000000000000000000000000000001
+            bytes[0] = 0x08;
+            bytes[1..7].fill(0);
+            bytes[7] %= 0x11;
+            Felt::from_be_bytes(bytes).unwrap()
+        })
+    }
+
+    /// The hex digits of the felt without prefix or leading zeros.
+    fn digits(felt: &Felt) -> String {
+        felt.to_hex_str().trim_start_matches("0x").to_owned()
+    }
+
+    fn parse(s: &str) -> Result<Felt, serde_json::Error> {
+        serde_json::from_value::<RpcFelt>(serde_json::Value::String(s.to_owned())).map(|f| f.0)
+    }
+
+    fn parse_251(s: &str) -> Result<Felt, serde_json::Error> {
+        serde_json::from_value::<RpcFelt251>(serde_json::Value::String(s.to_owned()))
+            .map(|f| f.0 .0)
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(felt in felt()) {
+            let json = serde_json::to_value(RpcFelt(felt)).unwrap();
+            prop_assert_eq!(&json, &serde_json::Value::String(felt.to_hex_str().into_owned()));
+
+            let parsed = serde_json::from_value::<RpcFelt>(json).unwrap();
+            prop_assert_eq!(parsed.0, felt);
+        }
+
+        #[test]
+        fn leading_zeros_are_ignored(felt in felt(), zeros in 0usize..64) {
+            let digits = digits(&felt);
+            let zeros = zeros.min(64 - digits.len());
+
+            prop_assert_eq!(parse(&format!("0x{}{digits}", "0".repeat(zeros))).unwrap(), felt);
+        }
+
+        #[test]
+        fn digits_are_case_insensitive(felt in felt()) {
+            prop_assert_eq!(parse(&format!("0x{}", digits(&felt).to_uppercase())).unwrap(), felt);
+        }
+
+        #[test]
+        fn missing_prefix_is_rejected(felt in felt()) {
+            prop_assert!(parse(&digits(&felt)).is_err());
+            prop_assert!(parse(&format!("0X{}", digits(&felt))).is_err());
+        }
+
+        #[test]
+        fn more_than_64_digits_are_rejected(felt in felt()) {
+            let digits = digits(&felt);
+            prop_assert!(parse(&format!("0x{}{digits}", "0".repeat(65 - digits.len()))).is_err());
+        }
+
+        #[test]
+        fn overflow_is_rejected(mut bytes in any::<[u8; 32]>()) {
+            // At least 2^252, which exceeds the field modulus.
+            bytes[0] |= 0x10;
+            prop_assert!(parse(&format!("0x{}", hex::encode(bytes))).is_err());
+        }
+
+        #[test]
+        fn round_trip_251(felt in felt_251()) {
+            let json = serde_json::to_value(RpcFelt251(RpcFelt(felt))).unwrap();
+            let parsed = serde_json::from_value::<RpcFelt251>(json).unwrap();
+            prop_assert_eq!(parsed.0 .0, felt);
+        }
+
+        #[test]
+        fn more_than_251_bits_are_rejected(felt in felt_252()) {
+            let s = felt.to_hex_str();
+            prop_assert_eq!(parse(&s).unwrap(), felt);
+            prop_assert!(parse_251(&s).is_err());
+        }
+    }
+
+    #[test]
+    fn boundaries() {
+        const MAX_251: &str = "0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+        const MIN_252: &str = "0x800000000000000000000000000000000000000000000000000000000000000";
+        const MAX: &str = "0x800000000000011000000000000000000000000000000000000000000000000";
+        const MODULUS: &str = "0x800000000000011000000000000000000000000000000000000000000000001";
+
+        assert!(parse_251(MAX_251).is_ok());
+        assert!(parse_251(MIN_252).is_err());
+        assert!(parse(MIN_252).is_ok());
+        assert!(parse(MAX).is_ok());
+        assert!(parse(MODULUS).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Entry points for the fuzz targets in `fuzz/`, which can only reach the public API.
+//!
+//! Only compiled with the `fuzz` feature. Each function panics if the input reveals a bug, and
+//! must otherwise accept any input.
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::felt::{RpcFelt, RpcFelt251};
+use crate::jsonrpc::{ParamsStrictness, RpcError, RpcRequest};
+
+/// Parses `data` as the string of an [RpcFelt], and checks that accepted values serialize to a
+/// string which parses back to the same felt.
+pub fn rpc_felt(data: &[u8]) {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(felt) = serde_json::from_value::<RpcFelt>(Value::String(s.to_owned())) else {
+        return;
+    };
+
+    assert!(s.starts_with("0x"), "Accepted {s} without prefix");
+
+    let json = serde_json::to_value(&felt).unwrap();
+    let reparsed = serde_json::from_value::<RpcFelt>(json.clone()).unwrap();
+    assert_eq!(felt.0, reparsed.0, "{s} did not round-trip via {json}");
+
+    // The serialized form is the input without leading zeros.
+    let digits = s[2..].trim_start_matches('0').to_lowercase();
+    let expected = if digits.is_empty() { "0" } else { &digits };
+    assert_eq!(json, Value::String(format!("0x{expected}")));
+}
+
+/// Parses `data` as the string of an [RpcFelt251], and checks that it agrees with [RpcFelt]
+/// about which values are valid.
+pub fn rpc_felt_251(data: &[u8]) {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let felt = serde_json::from_value::<RpcFelt>(Value::String(s.to_owned()));
+    let felt_251 = serde_json::from_value::<RpcFelt251>(Value::String(s.to_owned()));
+
+    match (felt, felt_251) {
+        (Ok(felt), Ok(felt_251)) => {
+            assert!(!felt.0.has_more_than_251_bits(), "Accepted {s}");
+            assert_eq!(felt.0, felt_251.0 .0);
+
+            let json = serde_json::to_value(felt_251).unwrap();
+            assert_eq!(json, serde_json::to_value(felt).unwrap());
+        }
+        (Ok(felt), Err(_)) => assert!(felt.0.has_more_than_251_bits(), "Rejected {s}"),
+        (Err(_), Ok(_)) => panic!("Accepted {s} which is not a felt"),
+        (Err(_), Err(_)) => {}
+    }
+}
+
+/// Runs `data` through the parsing steps of an HTTP request body: the split into single or batch
+/// requests, each request, and its params. Errors must be reported as JSON-RPC errors.
+pub fn jsonrpc_request(data: &[u8]) {
+    let requests = if data.first() == Some(&b'[') {
+        serde_json::from_slice::<Vec<&RawValue>>(data)
+    } else {
+        serde_json::from_slice::<&RawValue>(data).map(|request| vec![request])
+    };
+    let Ok(requests) = requests else {
+        return;
+    };
+
+    /// An input with nested fields, enums and felts, like those of the RPC methods.
+    #[derive(serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Input {
+        block_id: pathfinder_common::BlockId,
+        keys: Vec<Vec<RpcFelt>>,
+        address: Option<RpcFelt251>,
+    }
+
+    for request in requests {
+        let Ok(request) = serde_json::from_str::<RpcRequest<'_>>(request.get()) else {
+            continue;
+        };
+        let params = request.params;
+
+        for strictness in [ParamsStrictness::Strict, ParamsStrictness::Lenient] {
+            let _ = params.is_empty();
+
+            if let Err(e) = params.deserialize_with::<Value>(strictness) {
+                serde_json::to_value(e).unwrap();
+            }
+
+            if let Err(e) = params.deserialize_with::<Input>(strictness) {
+                serde_json::to_value(&e).unwrap();
+                if let RpcError::InvalidParamsValue { pointer, .. } = e {
+                    assert!(pointer.starts_with("/params"), "Invalid pointer {pointer}");
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use anyhow::Context;
+use pathfinder_common::GasPrice;
+use pathfinder_ethereum::EthereumClient;
 use primitive_types::U256;
 use starknet_gateway_client::GatewayApi;
 use starknet_gateway_types::reply::MaybePendingBlock;
@@ -12,10 +15,18 @@ use starknet_gateway_types::reply::MaybePendingBlock;
 #[derive(Clone)]
 pub struct Cached {
     value: Arc<tokio::sync::RwLock<Option<Value>>>,
-    gateway: starknet_gateway_client::Client,
+    origin: Origin,
     horizon: Duration,
 }
 
+#[derive(Clone)]
+enum Origin {
+    /// The gas price of the gateway's pending block.
+    Gateway(starknet_gateway_client::Client),
+    /// The gas price of L1.
+    Ethereum(EthereumClient),
+}
+
 #[derive(Clone)]
 struct Value {
     gas_price: U256,
@@ -26,7 +37,16 @@ impl Cached {
     pub fn new(gateway: starknet_gateway_client::Client) -> Self {
         Cached {
             value: Default::default(),
-            gateway,
+            origin: Origin::Gateway(gateway),
+            horizon: Duration::from_secs(60),
+        }
+    }
+
+    /// Caches the gas price of L1 instead of the gateway's.
+    pub fn ethereum(ethereum: EthereumClient) -> Self {
+        Cached {
+            value: Default::default(),
+            origin: Origin::Ethereum(ethereum),
             horizon: Duration::from_secs(60),
         }
     }
@@ -66,8 +86,20 @@ impl Cached {
     }
 
     async fn gas_price(&self) -> Option<U256> {
-        match self
-            .gateway
+        let gateway = match &self.origin {
+            Origin::Gateway(gateway) => gateway,
+            Origin::Ethereum(ethereum) => {
+                return match ethereum.gas_price().await {
+                    Ok(gas_price) => Some(gas_price),
+                    Err(reason) => {
+                        tracing::debug!(%reason, "Failed to fetch L1 gas price");
+                        None
+                    }
+                };
+            }
+        };
+
+        match gateway
             // Don't indefinitely retry as this could block the RPC request.
             .block_without_retry(pathfinder_common::BlockId::Pending)
             .await
@@ -88,3 +120,32 @@ impl Cached {
         None
     }
 }
+
+/// Where the gas price of fee estimates comes from.
+#[derive(Clone, Default)]
+pub enum GasPriceSource {
+    /// The gas price of the block the estimate is executed on, as reported by the gateway. This
+    /// is the pending block's at `pending`, and the block's own otherwise.
+    #[default]
+    Gateway,
+    /// The current gas price of L1, e.g. for devnets whose gateway reports a zero gas price.
+    L1(Cached),
+    /// A fixed gas price.
+    Fixed(GasPrice),
+}
+
+impl GasPriceSource {
+    /// The gas price overriding the one of the block executed on, if any.
+    pub(crate) async fn gas_price(&self) -> anyhow::Result<Option<GasPrice>> {
+        match self {
+            GasPriceSource::Gateway => Ok(None),
+            GasPriceSource::L1(cached) => {
+                let gas_price = cached.get().await.context("L1 gas price is unavailable")?;
+                let gas_price = u128::try_from(gas_price)
+                    .map_err(|_| anyhow::anyhow!("L1 gas price {gas_price} is out of range"))?;
+                Ok(Some(GasPrice(gas_price)))
+            }
+            GasPriceSource::Fixed(gas_price) => Ok(Some(*gas_price)),
+        }
+    }
+}
@@ -1,13 +1,20 @@
 mod error;
+mod openrpc;
 mod request;
 mod response;
 mod router;
 pub mod websocket;
 
 pub use error::RpcError;
-pub use request::RpcRequest;
-pub use response::{RpcResponse, RpcResult};
-pub use router::{rpc_handler, IntoRpcMethod, RpcMethodHandler, RpcRouter, RpcRouterBuilder};
+pub use request::{ParamsStrictness, RpcRequest};
+pub use response::{RawRpcResult, RpcResponse, RpcResult};
+pub use router::{
+    openrpc_handler, rpc_handler, IntoRpcMethod, MethodFilter, RpcMethodHandler, RpcRouter,
+    RpcRouterBuilder,
+};
+
+pub(crate) use request::RawParams;
+pub(crate) use router::matches_method;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum RequestId<'a> {
@@ -21,4 +28,14 @@ impl RequestId<'_> {
     pub fn is_notification(&self) -> bool {
         self == &RequestId::Notification
     }
+
+    /// Copies the borrowed string ID, so that the ID outlives the request.
+    pub fn into_owned(self) -> RequestId<'static> {
+        match self {
+            RequestId::Number(x) => RequestId::Number(x),
+            RequestId::String(x) => RequestId::String(std::borrow::Cow::Owned(x.into_owned())),
+            RequestId::Null => RequestId::Null,
+            RequestId::Notification => RequestId::Notification,
+        }
+    }
 }
@@ -9,18 +9,41 @@ pub enum RpcError {
     InvalidRequest,
     MethodNotFound,
     InvalidParams,
+    /// Invalid params due to a field which is not part of the method's input.
+    UnknownParamsField(String),
+    /// Invalid params due to a value which could not be deserialized.
+    InvalidParamsValue {
+        /// A JSON pointer to the value, e.g. `/params/block_id/block_number`.
+        pointer: String,
+        reason: String,
+    },
     InternalError(anyhow::Error),
     ApplicationError(crate::error::ApplicationError),
     WebsocketSubscriptionClosed {
         subscription_id: u32,
         reason: String,
     },
+    /// The connection already has the maximum number of subscriptions.
+    WebsocketSubscriptionLimit {
+        limit: usize,
+    },
 }
 
 impl PartialEq for RpcError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::InternalError(l0), Self::InternalError(r0)) => l0.to_string() == r0.to_string(),
+            (Self::UnknownParamsField(l0), Self::UnknownParamsField(r0)) => l0 == r0,
+            (
+                Self::InvalidParamsValue {
+                    pointer: l_pointer,
+                    reason: l_reason,
+                },
+                Self::InvalidParamsValue {
+                    pointer: r_pointer,
+                    reason: r_reason,
+                },
+            ) => l_pointer == r_pointer && l_reason == r_reason,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -33,10 +56,13 @@ impl RpcError {
             RpcError::ParseError => -32700,
             RpcError::InvalidRequest => -32600,
             RpcError::MethodNotFound { .. } => -32601,
-            RpcError::InvalidParams => -32602,
+            RpcError::InvalidParams
+            | RpcError::UnknownParamsField(_)
+            | RpcError::InvalidParamsValue { .. } => -32602,
             RpcError::InternalError(_) => -32603,
             RpcError::ApplicationError(err) => err.code(),
             RpcError::WebsocketSubscriptionClosed { .. } => -32099,
+            RpcError::WebsocketSubscriptionLimit { .. } => -32098,
         }
     }
 
@@ -45,10 +71,15 @@ impl RpcError {
             RpcError::ParseError => "Parse error".into(),
             RpcError::InvalidRequest => "Invalid Request".into(),
             RpcError::MethodNotFound { .. } => "Method not found".into(),
-            RpcError::InvalidParams => "Invalid params".into(),
+            RpcError::InvalidParams
+            | RpcError::UnknownParamsField(_)
+            | RpcError::InvalidParamsValue { .. } => "Invalid params".into(),
             RpcError::InternalError(_) => "Internal error".into(),
             RpcError::ApplicationError(e) => e.to_string().into(),
             RpcError::WebsocketSubscriptionClosed { .. } => "Websocket subscription closed".into(),
+            RpcError::WebsocketSubscriptionLimit { .. } => {
+                "Too many websocket subscriptions".into()
+            }
         }
     }
 
@@ -61,6 +92,16 @@ impl RpcError {
                 "id": subscription_id,
                 "reason": reason,
             })),
+            RpcError::WebsocketSubscriptionLimit { limit } => Some(json!({
+                "limit": limit,
+            })),
+            RpcError::UnknownParamsField(field) => Some(json!({
+                "reason": format!("unknown field `{field}`"),
+            })),
+            RpcError::InvalidParamsValue { pointer, reason } => Some(json!({
+                "pointer": pointer,
+                "reason": format!("{pointer}: {reason}"),
+            })),
             RpcError::ApplicationError(e) => e.data(),
             RpcError::InternalError(_) => None,
             RpcError::ParseError => None,
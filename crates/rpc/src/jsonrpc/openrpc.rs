@@ -0,0 +1,315 @@
+//! Describes the methods registered with an [RpcRouter](super::RpcRouter) as an
+//! [OpenRPC](https://spec.open-rpc.org) document.
+//!
+//! The document is generated from the router itself, so it always matches the methods served.
+//! Params are taken from the fields of each method's input type. Params which are felts, or
+//! options or arrays of them, are described by the felt's schema. The schemas of other params
+//! and of the method results are left open, see the starknet-specs for their definitions.
+use std::collections::HashMap;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{json, Value};
+
+use super::router::RpcMethod;
+
+const OPENRPC_VERSION: &str = "1.2.6";
+
+/// Generates the OpenRPC document of the methods served under the given version.
+pub(super) fn document(
+    version: &'static str,
+    methods: &HashMap<&'static str, Box<dyn RpcMethod>>,
+) -> Value {
+    let mut methods = methods
+        .iter()
+        .map(|(name, method)| {
+            let params = method
+                .params()
+                .into_iter()
+                .map(|(name, schema)| json!({ "name": name, "schema": schema }))
+                .collect::<Vec<_>>();
+
+            json!({
+                "name": name,
+                "params": params,
+                "paramStructure": "either",
+                "result": { "name": "result", "schema": {} },
+            })
+        })
+        .collect::<Vec<_>>();
+    methods.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "Pathfinder Starknet RPC API",
+            "version": version,
+        },
+        "methods": methods,
+    })
+}
+
+/// The names and schemas of the params accepted by an input type, in order.
+///
+/// These are the field names of a struct deriving [Deserialize](serde::Deserialize). Other
+/// types, e.g. tuple structs or structs with flattened fields, have no names to report.
+pub(super) fn params<T: DeserializeOwned>() -> Vec<(&'static str, Value)> {
+    let mut names = Vec::new();
+    // Deserialization always fails, once the names are known.
+    let _ = T::deserialize(FieldNames(&mut names));
+
+    names
+        .into_iter()
+        .map(|name| {
+            let mut schema = None;
+            let _ = T::deserialize(FieldSchema {
+                field: name,
+                schema: &mut schema,
+            });
+            (name, schema.unwrap_or_else(|| json!({})))
+        })
+        .collect()
+}
+
+/// A [Deserializer](serde::Deserializer) which records the fields of the struct it is asked for,
+/// without producing any values.
+struct FieldNames<'a>(&'a mut Vec<&'static str>);
+
+#[derive(Debug)]
+struct NoValue;
+
+impl std::fmt::Display for NoValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("no value")
+    }
+}
+
+impl std::error::Error for NoValue {}
+
+impl serde::de::Error for NoValue {
+    fn custom<T: std::fmt::Display>(_msg: T) -> Self {
+        NoValue
+    }
+}
+
+impl<'de, 'a> serde::Deserializer<'de> for FieldNames<'a> {
+    type Error = NoValue;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(NoValue)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.extend_from_slice(fields);
+        Err(NoValue)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// A [Deserializer](serde::Deserializer) which feeds the struct it is asked for only the given
+/// field, in order to record the field's schema.
+struct FieldSchema<'a> {
+    field: &'static str,
+    schema: &'a mut Option<Value>,
+}
+
+impl<'de, 'a> serde::Deserializer<'de> for FieldSchema<'a> {
+    type Error = NoValue;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(NoValue)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(SingleField {
+            field: Some(self.field),
+            schema: self.schema,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+struct SingleField<'a> {
+    field: Option<&'static str>,
+    schema: &'a mut Option<Value>,
+}
+
+impl<'de, 'a> MapAccess<'de> for SingleField<'a> {
+    type Error = NoValue;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.field
+            .take()
+            .map(|field| seed.deserialize(BorrowedStrDeserializer::new(field)))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(ValueSchema(&mut *self.schema))
+    }
+}
+
+/// A [Deserializer](serde::Deserializer) which records the schema of the value it is asked for,
+/// if that is a felt newtype or an option or array of one.
+struct ValueSchema<'a>(&'a mut Option<Value>);
+
+impl<'de, 'a> serde::Deserializer<'de> for ValueSchema<'a> {
+    type Error = NoValue;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(NoValue)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = pathfinder_common::felt_newtype_schema(name)
+            .and_then(|schema| serde_json::to_value(schema).ok());
+        Err(NoValue)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut items = None;
+        let _ = visitor.visit_seq(SingleElement(&mut items));
+        *self.0 = items.map(|items| json!({ "type": "array", "items": items }));
+        Err(NoValue)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct SingleElement<'a>(&'a mut Option<Value>);
+
+impl<'de, 'a> SeqAccess<'de> for SingleElement<'a> {
+    type Error = NoValue;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        seed.deserialize(ValueSchema(&mut *self.0)).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::{BlockHash, ContractAddress, StorageAddress};
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::context::RpcContext;
+    use crate::jsonrpc::RpcRouter;
+
+    #[test]
+    fn document_lists_registered_methods() {
+        crate::error::generate_rpc_error_subset!(ExampleError:);
+
+        #[derive(Deserialize)]
+        struct SubtractInput {
+            minuend: i32,
+            #[serde(rename = "other")]
+            subtrahend: i32,
+        }
+        async fn subtract(input: SubtractInput) -> Result<i32, ExampleError> {
+            Ok(input.minuend - input.subtrahend)
+        }
+
+        async fn version() -> Result<&'static str, ExampleError> {
+            Ok("v1")
+        }
+
+        #[derive(Deserialize)]
+        struct StorageInput {
+            contract_address: ContractAddress,
+            keys: Vec<StorageAddress>,
+            block_hash: Option<BlockHash>,
+        }
+        async fn storage(_input: StorageInput) -> Result<u64, ExampleError> {
+            Ok(0)
+        }
+
+        let router = RpcRouter::builder("vTEST")
+            .register("version", version)
+            .register("subtract", subtract)
+            .register("storage", storage)
+            .build(RpcContext::for_tests());
+
+        let felt = json!({
+            "type": "string",
+            "pattern": "^0x(0|[a-fA-F1-9]{1}[a-fA-F0-9]{0,62})$",
+        });
+
+        let expected = json!({
+            "openrpc": "1.2.6",
+            "info": {
+                "title": "Pathfinder Starknet RPC API",
+                "version": "vTEST",
+            },
+            "methods": [
+                {
+                    "name": "storage",
+                    "params": [
+                        { "name": "contract_address", "schema": felt },
+                        { "name": "keys", "schema": { "type": "array", "items": felt } },
+                        { "name": "block_hash", "schema": felt },
+                    ],
+                    "paramStructure": "either",
+                    "result": { "name": "result", "schema": {} },
+                },
+                {
+                    "name": "subtract",
+                    "params": [
+                        { "name": "minuend", "schema": {} },
+                        { "name": "other", "schema": {} },
+                    ],
+                    "paramStructure": "either",
+                    "result": { "name": "result", "schema": {} },
+                },
+                {
+                    "name": "version",
+                    "params": [],
+                    "paramStructure": "either",
+                    "result": { "name": "result", "schema": {} },
+                },
+            ],
+        });
+
+        assert_eq!(router.openrpc(), expected);
+    }
+}
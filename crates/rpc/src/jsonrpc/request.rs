@@ -12,7 +12,20 @@ pub struct RpcRequest<'a> {
     pub id: RequestId<'a>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// Determines how fields in the request params which are unknown to the method are handled.
+///
+/// This is applied uniformly to all methods of a server, instead of relying on each input
+/// type to opt into `#[serde(deny_unknown_fields)]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParamsStrictness {
+    /// Unknown fields are rejected with an error naming the offending field.
+    #[default]
+    Strict,
+    /// Unknown fields are silently ignored.
+    Lenient,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
 pub struct RawParams<'a>(#[serde(borrow)] pub Option<&'a RawValue>);
 
 impl<'a> RawParams<'a> {
@@ -46,9 +59,101 @@ impl<'a> RawParams<'a> {
     }
 
     pub fn deserialize<T: Deserialize<'a>>(self) -> Result<T, RpcError> {
+        self.deserialize_with(ParamsStrictness::Strict)
+    }
+
+    /// Deserializes the params, handling unknown fields according to `strictness`.
+    ///
+    /// Note that unknown fields can only be detected where serde does not buffer the input,
+    /// i.e. not inside of `#[serde(flatten)]`, internally tagged or untagged types. Such types
+    /// must still opt into `#[serde(deny_unknown_fields)]` themselves.
+    pub fn deserialize_with<T: Deserialize<'a>>(
+        self,
+        strictness: ParamsStrictness,
+    ) -> Result<T, RpcError> {
         let s = self.0.map(|x| x.get()).unwrap_or_default();
+        let mut deserializer = serde_json::Deserializer::from_str(s);
+
+        let (params, unknown_field) = match strictness {
+            ParamsStrictness::Lenient => {
+                let params =
+                    serde_path_to_error::deserialize(&mut deserializer).map_err(invalid_params)?;
+                (params, None)
+            }
+            ParamsStrictness::Strict => {
+                let mut unknown_field = None;
+                let deserializer = serde_ignored::Deserializer::new(&mut deserializer, |path| {
+                    unknown_field.get_or_insert_with(|| path.to_string());
+                });
+
+                let params =
+                    serde_path_to_error::deserialize(deserializer).map_err(invalid_params)?;
+                (params, unknown_field)
+            }
+        };
+        deserializer.end().map_err(|_| RpcError::InvalidParams)?;
 
-        serde_json::from_str::<T>(s).map_err(|_| RpcError::InvalidParams)
+        match unknown_field {
+            Some(field) => Err(RpcError::UnknownParamsField(field)),
+            None => Ok(params),
+        }
+    }
+}
+
+/// Maps a deserialization error to [RpcError::InvalidParamsValue], locating the offending value
+/// with a JSON pointer into the request, e.g. `/params/block_id/block_number`.
+fn invalid_params(error: serde_path_to_error::Error<serde_json::Error>) -> RpcError {
+    use serde_path_to_error::Segment;
+
+    let mut pointer = String::from("/params");
+    for segment in error.path().iter() {
+        match segment {
+            Segment::Seq { index } => {
+                pointer.push('/');
+                pointer.push_str(&index.to_string());
+            }
+            Segment::Map { key } | Segment::Enum { variant: key } => {
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+            }
+            Segment::Unknown => {}
+        }
+    }
+
+    let inner = error.inner();
+    let message = inner.to_string();
+    // The position is of no use to the caller, as the params are only a part of the request.
+    let position = format!(" at line {} column {}", inner.line(), inner.column());
+    let message = message.strip_suffix(&position).unwrap_or(&message);
+
+    // Serde describes the expected value in terms of Rust types, e.g. `u64` or `a sequence`.
+    let reason = match message.rsplit_once(", expected ") {
+        Some((found, expected)) => match json_type(expected) {
+            Some(expected) => format!("{found}, expected {expected}"),
+            None => message.to_owned(),
+        },
+        None => message.to_owned(),
+    };
+
+    RpcError::InvalidParamsValue { pointer, reason }
+}
+
+/// The JSON type of a value which serde describes as `expected`, if it is one of serde's
+/// primitive descriptions.
+fn json_type(expected: &str) -> Option<&'static str> {
+    match expected {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" => Some("integer"),
+        "f32" | "f64" => Some("number"),
+        "a string" | "a borrowed string" | "a character" => Some("string"),
+        "a boolean" => Some("boolean"),
+        "a sequence" => Some("array"),
+        "a map" => Some("object"),
+        other if other.starts_with("a tuple") || other.starts_with("tuple struct ") => {
+            Some("array")
+        }
+        other if other.starts_with("struct ") => Some("object"),
+        _ => None,
     }
 }
 
@@ -237,5 +342,138 @@ mod tests {
 
             assert!(!uut.is_empty());
         }
+
+        mod strictness {
+            use super::*;
+
+            #[derive(Deserialize, Debug, PartialEq)]
+            struct Input {
+                a: u32,
+                inner: Inner,
+            }
+
+            #[derive(Deserialize, Debug, PartialEq)]
+            struct Inner {
+                b: u32,
+            }
+
+            const UNKNOWN_TOP: &str = r#"{"a": 1, "inner": {"b": 2}, "c": 3}"#;
+            const UNKNOWN_NESTED: &str = r#"{"a": 1, "inner": {"b": 2, "d": 4}}"#;
+
+            fn deserialize(s: &str, strictness: ParamsStrictness) -> Result<Input, RpcError> {
+                let raw_value = RawValue::from_string(s.to_owned()).unwrap();
+                RawParams(Some(&raw_value)).deserialize_with(strictness)
+            }
+
+            #[rstest::rstest]
+            #[case::top_level(UNKNOWN_TOP, "c")]
+            #[case::nested(UNKNOWN_NESTED, "inner.d")]
+            fn strict_rejects_unknown(#[case] s: &str, #[case] field: &str) {
+                let err = deserialize(s, ParamsStrictness::Strict).unwrap_err();
+                assert_eq!(err, RpcError::UnknownParamsField(field.to_owned()));
+                assert_eq!(err.code(), RpcError::InvalidParams.code());
+            }
+
+            #[rstest::rstest]
+            #[case::top_level(UNKNOWN_TOP)]
+            #[case::nested(UNKNOWN_NESTED)]
+            fn lenient_ignores_unknown(#[case] s: &str) {
+                let input = deserialize(s, ParamsStrictness::Lenient).unwrap();
+                assert_eq!(
+                    input,
+                    Input {
+                        a: 1,
+                        inner: Inner { b: 2 }
+                    }
+                );
+            }
+
+            #[test]
+            fn positional_is_unaffected() {
+                let input = deserialize(r#"[1, {"b": 2}]"#, ParamsStrictness::Strict).unwrap();
+                assert_eq!(
+                    input,
+                    Input {
+                        a: 1,
+                        inner: Inner { b: 2 }
+                    }
+                );
+            }
+        }
+
+        mod invalid_params {
+            use super::*;
+
+            #[derive(Deserialize, Debug)]
+            #[allow(dead_code)]
+            struct Input {
+                block_id: BlockId,
+                keys: Vec<String>,
+            }
+
+            #[derive(Deserialize, Debug)]
+            #[allow(dead_code)]
+            enum BlockId {
+                #[serde(rename = "block_number")]
+                Number(u64),
+            }
+
+            fn pointer_and_reason(s: &str) -> (String, String) {
+                let raw_value = RawValue::from_string(s.to_owned()).unwrap();
+                let err = RawParams(Some(&raw_value))
+                    .deserialize::<Input>()
+                    .unwrap_err();
+                assert_eq!(err.code(), RpcError::InvalidParams.code());
+
+                match err {
+                    RpcError::InvalidParamsValue { pointer, reason } => (pointer, reason),
+                    other => panic!("Unexpected error: {other:?}"),
+                }
+            }
+
+            #[rstest::rstest]
+            #[case::named(
+                r#"{"block_id": {"block_number": "abc"}, "keys": []}"#,
+                "/params/block_id/block_number",
+                "expected integer"
+            )]
+            #[case::positional(r#"[{"block_number": 1}, [1]]"#, "/params/1/0", "expected string")]
+            #[case::wrong_container(
+                r#"{"block_id": {"block_number": 1}, "keys": {}}"#,
+                "/params/keys",
+                "expected array"
+            )]
+            #[case::missing_field(
+                r#"{"block_id": {"block_number": 1}}"#,
+                "/params",
+                "missing field `keys`"
+            )]
+            fn reports_pointer(#[case] s: &str, #[case] pointer: &str, #[case] reason: &str) {
+                let (actual_pointer, actual_reason) = pointer_and_reason(s);
+                assert_eq!(actual_pointer, pointer);
+                assert!(actual_reason.ends_with(reason), "{actual_reason}");
+                assert!(!actual_reason.contains(" at line "), "{actual_reason}");
+            }
+
+            #[test]
+            fn pointer_is_escaped() {
+                #[derive(Deserialize, Debug)]
+                #[allow(dead_code)]
+                struct Input {
+                    #[serde(rename = "a/b~c")]
+                    field: u32,
+                }
+
+                let raw_value = RawValue::from_string(r#"{"a/b~c": true}"#.to_owned()).unwrap();
+                let err = RawParams(Some(&raw_value))
+                    .deserialize::<Input>()
+                    .unwrap_err();
+
+                assert_eq!(
+                    err.data().unwrap()["pointer"],
+                    serde_json::json!("/params/a~1b~0c")
+                );
+            }
+        }
     }
 }
@@ -1,17 +1,32 @@
 use crate::error::ApplicationError;
 use axum::response::IntoResponse;
 use serde::Serialize;
+use serde_json::value::RawValue;
 use serde_json::Value;
+use std::io::Write;
 
 use crate::jsonrpc::error::RpcError;
 use crate::jsonrpc::RequestId;
+use crate::middleware::signature::BodyDigest;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct RpcResponse<'a> {
-    pub output: RpcResult,
+    pub output: RawRpcResult,
     pub id: RequestId<'a>,
 }
 
+impl PartialEq for RpcResponse<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let output = match (&self.output, &other.output) {
+            (Ok(a), Ok(b)) => a.get() == b.get(),
+            (Err(a), Err(b)) => a == b,
+            _ => false,
+        };
+
+        output && self.id == other.id
+    }
+}
+
 impl<'a> RpcResponse<'a> {
     pub const PARSE_ERROR: Self = Self {
         output: Err(RpcError::ParseError),
@@ -40,6 +55,12 @@ impl<'a> RpcResponse<'a> {
 
 pub type RpcResult = Result<Value, RpcError>;
 
+/// The serialized result of a method.
+///
+/// Methods serialize their output once into its JSON text, which is what is cached and written to
+/// the response, so that large results such as class definitions are never held as a [Value] tree.
+pub type RawRpcResult = Result<Box<RawValue>, RpcError>;
+
 impl Serialize for RpcResponse<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -66,9 +87,9 @@ impl Serialize for RpcResponse<'_> {
     }
 }
 
-impl IntoResponse for RpcResponse<'_> {
-    fn into_response(self) -> axum::response::Response {
-        // Log internal errors.
+impl RpcResponse<'_> {
+    /// Logs internal errors, which are not exposed to the client.
+    pub(crate) fn log_error(&self) {
         match &self.output {
             Err(RpcError::InternalError(e))
             | Err(RpcError::ApplicationError(ApplicationError::Internal(e))) => {
@@ -79,13 +100,139 @@ impl IntoResponse for RpcResponse<'_> {
             }
             _ => {}
         }
+    }
+}
+
+impl RpcResponse<'_> {
+    /// Copies the borrowed request ID, so that the response outlives the request.
+    pub fn into_owned(self) -> RpcResponse<'static> {
+        RpcResponse {
+            output: self.output,
+            id: self.id.into_owned(),
+        }
+    }
+}
+
+impl IntoResponse for RpcResponse<'_> {
+    fn into_response(self) -> axum::response::Response {
+        self.log_error();
+        json_body(self.into_owned())
+    }
+}
+
+/// Responses larger than this are streamed to the client in chunks of this size, instead of
+/// being serialized into a single buffer first.
+///
+/// Class definitions can be several megabytes of JSON, which would otherwise be held in memory
+/// twice per request: once as the serialized result and once as the response body.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+/// The number of serialized chunks buffered per streamed response before serialization waits
+/// for the client to catch up.
+const STREAM_BUFFERED_CHUNKS: usize = 4;
+
+/// Serializes the value as the response body.
+///
+/// Values larger than [STREAM_CHUNK_SIZE] are serialized on a blocking thread and streamed, which
+/// bounds the memory of the serialization to [STREAM_BUFFERED_CHUNKS] chunks. If the response is
+/// signed, the value is serialized into its [BodyDigest] first.
+pub(crate) fn json_body<T>(value: T) -> axum::response::Response
+where
+    T: Serialize + Send + 'static,
+{
+    if !exceeds_size(&value, STREAM_CHUNK_SIZE) {
+        return serde_json::to_vec(&value).unwrap().into_response();
+    }
+
+    let (digest_sender, digest) = BodyDigest::channel().unzip();
+    let (sender, receiver) = tokio::sync::mpsc::channel(STREAM_BUFFERED_CHUNKS);
+    tokio::task::spawn_blocking(move || {
+        if let Some(digest_sender) = digest_sender {
+            use sha2::{Digest, Sha256};
+
+            let mut hasher = Sha256::new();
+            if let Err(error) = serde_json::to_writer(&mut hasher, &value) {
+                tracing::warn!(%error, "Hashing response failed");
+                return;
+            }
+            // The signer has gone away if the response was dropped.
+            let _ = digest_sender.send(hasher.finalize().into());
+        }
+
+        let mut writer = ChunkWriter {
+            chunk: Vec::with_capacity(STREAM_CHUNK_SIZE),
+            sender,
+        };
+        // Serialization only fails once the client has gone away, so there is no one to tell.
+        if let Err(error) = serde_json::to_writer(&mut writer, &value) {
+            tracing::debug!(%error, "Streaming response aborted");
+            return;
+        }
+        let _ = writer.flush();
+    });
 
-        serde_json::to_vec(&self).unwrap().into_response()
+    let mut response =
+        axum::body::StreamBody::new(tokio_stream::wrappers::ReceiverStream::new(receiver))
+            .into_response();
+    if let Some(digest) = digest {
+        response.extensions_mut().insert(digest);
     }
+    response
+}
+
+/// Sends what is written to it in chunks of [STREAM_CHUNK_SIZE] bytes.
+struct ChunkWriter {
+    chunk: Vec<u8>,
+    sender: tokio::sync::mpsc::Sender<std::io::Result<axum::body::Bytes>>,
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.chunk.extend_from_slice(buf);
+        if self.chunk.len() >= STREAM_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(STREAM_CHUNK_SIZE));
+        self.sender
+            .blocking_send(Ok(chunk.into()))
+            .map_err(|_| std::io::ErrorKind::BrokenPipe.into())
+    }
+}
+
+/// Whether the value is larger than `limit` bytes once serialized, without serializing more
+/// than `limit` bytes of it.
+pub(crate) fn exceeds_size<T: Serialize + ?Sized>(value: &T, limit: usize) -> bool {
+    struct Counter {
+        remaining: usize,
+    }
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.remaining = self
+                .remaining
+                .checked_sub(buf.len())
+                .ok_or(std::io::ErrorKind::WriteZero)?;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    serde_json::to_writer(Counter { remaining: limit }, value).is_err()
 }
 
 #[cfg(test)]
 mod tests {
+    use hyper::body::HttpBody;
     use serde_json::json;
 
     use super::*;
@@ -113,7 +260,7 @@ mod tests {
     #[test]
     fn output_is_ok() {
         let serialized = serde_json::to_value(&RpcResponse {
-            output: Ok(Value::String("foobar".to_owned())),
+            output: Ok(serde_json::value::to_raw_value("foobar").unwrap()),
             id: RequestId::Number(1),
         })
         .unwrap();
@@ -126,4 +273,34 @@ mod tests {
 
         assert_eq!(serialized, expected);
     }
+
+    #[tokio::test]
+    async fn large_output_is_streamed() {
+        let response = RpcResponse {
+            output: Ok(
+                serde_json::value::to_raw_value(&"a".repeat(3 * STREAM_CHUNK_SIZE)).unwrap(),
+            ),
+            id: RequestId::Number(1),
+        };
+        let expected = serde_json::to_vec(&response).unwrap();
+
+        let body = response.into_response().into_body();
+        // Streamed bodies have no known length.
+        assert_eq!(body.size_hint().exact(), None);
+
+        let body = hyper::body::to_bytes(body).await.unwrap();
+        assert_eq!(body, expected);
+    }
+
+    #[tokio::test]
+    async fn small_output_is_not_streamed() {
+        let response = RpcResponse {
+            output: Ok(serde_json::value::to_raw_value("foobar").unwrap()),
+            id: RequestId::String("id".into()),
+        };
+        let expected = serde_json::to_vec(&response).unwrap();
+
+        let body = response.into_response().into_body();
+        assert_eq!(body.size_hint().exact(), Some(expected.len() as u64));
+    }
 }
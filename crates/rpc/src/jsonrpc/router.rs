@@ -13,20 +13,96 @@ use serde_json::value::RawValue;
 use serde_json::Value;
 
 use crate::context::RpcContext;
+use crate::error::ApplicationError;
 use crate::jsonrpc::error::RpcError;
+use crate::jsonrpc::openrpc;
 use crate::jsonrpc::request::{RawParams, RpcRequest};
-use crate::jsonrpc::response::{RpcResponse, RpcResult};
+use crate::jsonrpc::response::{exceeds_size, json_body, RawRpcResult, RpcResponse, RpcResult};
+use crate::middleware::auth::MethodAllowlist;
+use crate::response_cache::addressed_block;
 
 #[derive(Clone)]
 pub struct RpcRouter {
     context: RpcContext,
     methods: &'static HashMap<&'static str, Box<dyn RpcMethod>>,
     version: &'static str,
+    /// The methods served by this router's listener.
+    filter: MethodFilter,
+    /// The methods the client may call, if restricted by [authentication](crate::middleware::auth).
+    allowlist: Option<MethodAllowlist>,
 }
 
 pub struct RpcRouterBuilder {
     methods: HashMap<&'static str, Box<dyn RpcMethod>>,
     version: &'static str,
+    filter: MethodFilter,
+}
+
+/// Selects the methods a listener serves, see [RpcRouterBuilder::filter].
+///
+/// Patterns are method names, prefixes ending in `*` such as `starknet_trace*`, or one of the
+/// method groups:
+/// - `write`: methods which submit transactions, i.e. `starknet_add*Transaction`,
+/// - `trace`: methods which re-execute transactions, i.e. `starknet_trace*` and
+///   `starknet_simulateTransaction*`,
+/// - `admin`: methods which expose the node's diagnostics to its operators, i.e.
+///   `pathfinder_getBlockSyncTimings`,
+/// - `read`: all other methods.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MethodFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl MethodFilter {
+    /// Serves only the methods matching one of the patterns. All methods are served if this is
+    /// empty.
+    pub fn allow(self, patterns: Vec<String>) -> Self {
+        Self {
+            allow: patterns,
+            ..self
+        }
+    }
+
+    /// Does not serve the methods matching one of the patterns, even if they are allowed.
+    pub fn deny(self, patterns: Vec<String>) -> Self {
+        Self {
+            deny: patterns,
+            ..self
+        }
+    }
+
+    pub(crate) fn allows(&self, method: &str) -> bool {
+        let allowed = self.allow.is_empty() || self.allow.iter().any(|p| matches_method(p, method));
+        allowed && !self.deny.iter().any(|p| matches_method(p, method))
+    }
+}
+
+/// Whether the method matches the pattern, as described for [MethodFilter].
+pub(crate) fn matches_method(pattern: &str, method: &str) -> bool {
+    fn is_write(method: &str) -> bool {
+        method.starts_with("starknet_add") && method.ends_with("Transaction")
+    }
+
+    fn is_trace(method: &str) -> bool {
+        // v0.3 names the simulation method `starknet_simulateTransaction`.
+        method.starts_with("starknet_trace") || method.starts_with("starknet_simulateTransaction")
+    }
+
+    fn is_admin(method: &str) -> bool {
+        method == "pathfinder_getBlockSyncTimings"
+    }
+
+    match pattern {
+        "write" => is_write(method),
+        "trace" => is_trace(method),
+        "admin" => is_admin(method),
+        "read" => !is_write(method) && !is_trace(method) && !is_admin(method),
+        pattern => match pattern.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => method == pattern,
+        },
+    }
 }
 
 impl RpcRouterBuilder {
@@ -40,6 +116,15 @@ impl RpcRouterBuilder {
         self
     }
 
+    /// Removes the methods which the filter does not allow, so that calling them fails as if
+    /// they did not exist.
+    pub fn filter(mut self, filter: &MethodFilter) -> Self {
+        self.methods
+            .retain(|method_name, _| filter.allows(method_name));
+        self.filter = filter.clone();
+        self
+    }
+
     pub fn build(self, context: RpcContext) -> RpcRouter {
         // Intentionally leak the hashmap to give it a static lifetime.
         //
@@ -51,6 +136,8 @@ impl RpcRouterBuilder {
             context,
             methods,
             version: self.version,
+            filter: self.filter,
+            allowlist: None,
         }
     }
 
@@ -58,6 +145,7 @@ impl RpcRouterBuilder {
         RpcRouterBuilder {
             methods: Default::default(),
             version,
+            filter: Default::default(),
         }
     }
 }
@@ -67,8 +155,34 @@ impl RpcRouter {
         RpcRouterBuilder::new(version)
     }
 
+    /// The [OpenRPC](https://spec.open-rpc.org) document describing this router's methods.
+    pub fn openrpc(&self) -> Value {
+        openrpc::document(self.version, self.methods)
+    }
+
+    pub(crate) fn context(&self) -> &RpcContext {
+        &self.context
+    }
+
+    /// Restricts the methods which may be called to the allowlist, if any.
+    pub(crate) fn with_allowlist(self, allowlist: Option<MethodAllowlist>) -> Self {
+        Self { allowlist, ..self }
+    }
+
+    /// Whether the method is served by this router's listener and may be called by the client.
+    ///
+    /// Registered methods which are not served are removed by [RpcRouterBuilder::filter], this
+    /// covers methods which are handled outside of the router, such as subscriptions.
+    pub(crate) fn is_allowed(&self, method: &str) -> bool {
+        self.filter.allows(method)
+            && self
+                .allowlist
+                .as_ref()
+                .map_or(true, |allowlist| allowlist.allows(method))
+    }
+
     /// Parses and executes a request. Returns [None] if its a notification.
-    async fn run_request<'a>(&self, request: &'a str) -> Option<RpcResponse<'a>> {
+    pub(crate) async fn run_request<'a>(&self, request: &'a str) -> Option<RpcResponse<'a>> {
         let Ok(request) = serde_json::from_str::<RpcRequest<'_>>(request) else {
             return Some(RpcResponse::INVALID_REQUEST);
         };
@@ -78,27 +192,81 @@ impl RpcRouter {
             return None;
         }
 
+        metrics::increment_counter!("rpc_requests_total");
+
         // Also grab the method_name as it is a static str, which is required by the metrics.
         let Some((&method_name, method)) = self.methods.get_key_value(request.method.as_ref())
         else {
             return Some(RpcResponse::method_not_found(request.id));
         };
 
+        if !self.is_allowed(method_name) {
+            return Some(RpcResponse {
+                output: Err(ApplicationError::MethodNotAllowed.into()),
+                id: request.id,
+            });
+        }
+
+        let Some(_load) = self.context.load_shedder.admit(method_name) else {
+            metrics::increment_counter!("rpc_requests_shed_total", "method" => method_name, "version" => self.version);
+            tracing::debug!(method=%request.method, "Shedding RPC request under load");
+            return Some(RpcResponse {
+                output: Err(ApplicationError::NodeBusy.into()),
+                id: request.id,
+            });
+        };
+
         metrics::increment_counter!("rpc_method_calls_total", "method" => method_name, "version" => self.version);
 
-        let method = method.invoke(self.context.clone(), request.params);
-        let result = std::panic::AssertUnwindSafe(method).catch_unwind().await;
+        let cache_key = self
+            .context
+            .response_cache
+            .key(self.version, method_name, &request.params);
+        if let Some(result) = cache_key
+            .as_ref()
+            .and_then(|key| self.context.response_cache.get(key))
+        {
+            return Some(RpcResponse {
+                output: Ok(result),
+                id: request.id,
+            });
+        }
+
+        let mut retries = 0;
+        let output = loop {
+            let method = method.invoke(self.context.clone(), request.params);
+            let result = std::panic::AssertUnwindSafe(method).catch_unwind().await;
+
+            let output = match result {
+                Ok(output) => output,
+                Err(e) => {
+                    tracing::warn!(method=%request.method, backtrace=?e, "RPC method panic'd");
+                    Err(RpcError::InternalError(anyhow::anyhow!(
+                        "RPC method panic'd"
+                    )))
+                }
+            };
 
-        let output = match result {
-            Ok(output) => output,
-            Err(e) => {
-                tracing::warn!(method=%request.method, backtrace=?e, "RPC method panic'd");
-                Err(RpcError::InternalError(anyhow::anyhow!(
-                    "RPC method panic'd"
-                )))
+            // Busy database errors are transient, so the request is retried a few times before
+            // the client is asked to retry it later.
+            match output {
+                Err(e) if is_database_busy(&e) => {
+                    metrics::increment_counter!("rpc_database_busy_total", "method" => method_name, "version" => self.version);
+                    if retries == BUSY_RETRIES {
+                        tracing::debug!(method=%request.method, reason=?e, "Database busy");
+                        break Err(ApplicationError::NodeBusy.into());
+                    }
+                    retries += 1;
+                    tokio::time::sleep(BUSY_RETRY_DELAY * retries).await;
+                }
+                output => break output,
             }
         };
 
+        if let (Some(key), Ok(result)) = (cache_key, &output) {
+            self.context.response_cache.insert(key, result);
+        }
+
         if output.is_err() {
             metrics::increment_counter!("rpc_method_calls_failed_total", "method" => method_name, "version" => self.version);
         }
@@ -110,6 +278,21 @@ impl RpcRouter {
     }
 }
 
+/// The number of times a request is retried while the database is busy.
+const BUSY_RETRIES: u32 = 3;
+/// The delay before the first retry of a request which failed due to a busy database. Each
+/// further retry waits one delay longer.
+const BUSY_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+fn is_database_busy(error: &RpcError) -> bool {
+    match error {
+        RpcError::InternalError(e) | RpcError::ApplicationError(ApplicationError::Internal(e)) => {
+            pathfinder_storage::is_busy(e)
+        }
+        _ => false,
+    }
+}
+
 // A slight variation on the axum json extractor.
 fn is_utf8_encoded_json(headers: http::HeaderMap) -> bool {
     let Some(content_type) = headers.get(http::header::CONTENT_TYPE) else {
@@ -142,13 +325,16 @@ fn is_utf8_encoded_json(headers: http::HeaderMap) -> bool {
 #[axum::debug_handler]
 pub async fn rpc_handler(
     State(state): State<RpcRouter>,
+    allowlist: Option<axum::Extension<MethodAllowlist>>,
     headers: http::HeaderMap,
     body: axum::body::Bytes,
 ) -> impl axum::response::IntoResponse {
+    let if_none_match = headers.get(http::header::IF_NONE_MATCH).cloned();
     // Only utf8 json content allowed.
     if !is_utf8_encoded_json(headers) {
         return StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
     }
+    let state = state.with_allowlist(allowlist.map(|axum::Extension(allowlist)| allowlist));
 
     #[inline]
     /// Helper to scope the responses so we can set the content-type afterwards
@@ -156,6 +342,7 @@ pub async fn rpc_handler(
     async fn handle(
         state: RpcRouter,
         body: axum::body::Bytes,
+        if_none_match: Option<HeaderValue>,
     ) -> impl axum::response::IntoResponse {
         // Unfortunately due to this https://github.com/serde-rs/json/issues/497
         // we cannot use an enum with borrowed raw values inside to do a single deserialization
@@ -166,7 +353,15 @@ pub async fn rpc_handler(
                 return RpcResponse::PARSE_ERROR.into_response();
             };
 
+            // Results which only depend on a block addressed by its hash are immutable, so that
+            // clients can revalidate them cheaply.
+            let is_immutable = serde_json::from_str::<RpcRequest<'_>>(request.get())
+                .ok()
+                .and_then(|request| addressed_block(&request.method, &request.params))
+                .is_some();
+
             match state.run_request(request.get()).await {
+                Some(response) if is_immutable => with_etag(response, if_none_match.as_ref()),
                 Some(response) => response.into_response(),
                 None => ().into_response(),
             }
@@ -193,11 +388,15 @@ pub async fn rpc_handler(
                 return ().into_response();
             }
 
-            serde_json::to_string(&responses).unwrap().into_response()
+            let responses = responses
+                .into_iter()
+                .map(RpcResponse::into_owned)
+                .collect::<Vec<_>>();
+            json_body(responses)
         }
     }
 
-    let mut response = handle(state, body).await.into_response();
+    let mut response = handle(state, body, if_none_match).await.into_response();
 
     use http::header::CONTENT_TYPE;
     static APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json");
@@ -207,9 +406,73 @@ pub async fn rpc_handler(
     response
 }
 
+/// Adds a weak ETag of the result to the response, or responds with `304 Not Modified` if the
+/// ETag matches the request's `If-None-Match` header.
+///
+/// The ETag only covers the result, so that it is the same for all request IDs.
+fn with_etag(
+    response: RpcResponse<'_>,
+    if_none_match: Option<&HeaderValue>,
+) -> axum::response::Response {
+    use sha2::{Digest, Sha256};
+
+    let Ok(result) = &response.output else {
+        return response.into_response();
+    };
+
+    let tag = hex::encode(Sha256::digest(result.get()));
+    let etag = HeaderValue::from_str(&format!("W/\"{tag}\""))
+        .expect("Hex strings are valid header values");
+
+    // ETags are compared weakly, i.e. ignoring whether they are weak.
+    let not_modified = if_none_match
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value.split(',').any(|candidate| {
+                let candidate = candidate.trim();
+                candidate == "*" || candidate.trim_start_matches("W/").trim_matches('"') == tag
+            })
+        });
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        response.into_response()
+    };
+    response.headers_mut().insert(http::header::ETAG, etag);
+    response
+}
+
+/// Serves the [OpenRPC](https://spec.open-rpc.org) document of the router's methods.
+pub async fn openrpc_handler(State(state): State<RpcRouter>) -> impl IntoResponse {
+    axum::Json(state.openrpc())
+}
+
+/// Serializes a method's output into its result, failing with
+/// [ApplicationError::ResponseTooLarge] instead of building a result larger than
+/// [RpcContext::max_response_bytes].
+///
+/// The output's size is measured by serializing it without buffering, which stops once the limit
+/// is exceeded.
+fn serialize_output<T: Serialize>(output: T, state: &RpcContext) -> RawRpcResult {
+    if let Some(limit) = state.max_response_bytes {
+        if exceeds_size(&output, limit.get()) {
+            tracing::debug!(%limit, "RPC response too large");
+            return Err(ApplicationError::ResponseTooLarge { limit: limit.get() }.into());
+        }
+    }
+
+    serde_json::value::to_raw_value(&output).map_err(|e| RpcError::InternalError(e.into()))
+}
+
 #[axum::async_trait]
 pub trait RpcMethod: Send + Sync {
-    async fn invoke<'a>(&self, state: RpcContext, input: RawParams<'a>) -> RpcResult;
+    async fn invoke<'a>(&self, state: RpcContext, input: RawParams<'a>) -> RawRpcResult;
+
+    /// The names and schemas of the method's params, see [openrpc::params].
+    fn params(&self) -> Vec<(&'static str, Value)> {
+        Vec::new()
+    }
 }
 
 /// Utility trait which automates the serde of an RPC methods input and output.
@@ -289,10 +552,18 @@ mod sealed {
                 Error: Into<RpcError> + Send + Sync,
                 Fut: Future<Output = Result<Output, Error>> + Send,
             {
-                async fn invoke<'a>(&self, state: RpcContext, input: RawParams<'a>) -> RpcResult {
-                    let input = input.deserialize()?;
+                async fn invoke<'a>(
+                    &self,
+                    state: RpcContext,
+                    input: RawParams<'a>,
+                ) -> RawRpcResult {
+                    let input = input.deserialize_with(state.params_strictness)?;
                     let output = (self.f)(state, input).await.map_err(Into::into)?;
-                    serde_json::to_value(output).map_err(|e| RpcError::InternalError(e.into()))
+                    serialize_output(output, &state)
+                }
+
+                fn params(&self) -> Vec<(&'static str, Value)> {
+                    openrpc::params::<Input>()
                 }
             }
 
@@ -330,10 +601,18 @@ mod sealed {
                 Error: Into<RpcError> + Send + Sync,
                 Fut: Future<Output = Result<Output, Error>> + Send,
             {
-                async fn invoke<'a>(&self, _state: RpcContext, input: RawParams<'a>) -> RpcResult {
-                    let input = input.deserialize()?;
+                async fn invoke<'a>(
+                    &self,
+                    state: RpcContext,
+                    input: RawParams<'a>,
+                ) -> RawRpcResult {
+                    let input = input.deserialize_with(state.params_strictness)?;
                     let output = (self.f)(input).await.map_err(Into::into)?;
-                    serde_json::to_value(output).map_err(|e| RpcError::InternalError(e.into()))
+                    serialize_output(output, &state)
+                }
+
+                fn params(&self) -> Vec<(&'static str, Value)> {
+                    openrpc::params::<Input>()
                 }
             }
 
@@ -369,12 +648,16 @@ mod sealed {
                 Error: Into<RpcError> + Send + Sync,
                 Fut: Future<Output = Result<Output, Error>> + Send,
             {
-                async fn invoke<'a>(&self, state: RpcContext, input: RawParams<'a>) -> RpcResult {
+                async fn invoke<'a>(
+                    &self,
+                    state: RpcContext,
+                    input: RawParams<'a>,
+                ) -> RawRpcResult {
                     if !input.is_empty() {
                         return Err(RpcError::InvalidParams);
                     }
                     let output = (self.f)(state).await.map_err(Into::into)?;
-                    serde_json::to_value(output).map_err(|e| RpcError::InternalError(e.into()))
+                    serialize_output(output, &state)
                 }
             }
 
@@ -410,12 +693,16 @@ mod sealed {
                 Error: Into<RpcError> + Send + Sync,
                 Fut: Future<Output = Result<Output, Error>> + Send,
             {
-                async fn invoke<'a>(&self, _state: RpcContext, input: RawParams<'a>) -> RpcResult {
+                async fn invoke<'a>(
+                    &self,
+                    state: RpcContext,
+                    input: RawParams<'a>,
+                ) -> RawRpcResult {
                     if !input.is_empty() {
                         return Err(RpcError::InvalidParams);
                     }
                     let output = (self.f)().await.map_err(Into::into)?;
-                    serde_json::to_value(output).map_err(|e| RpcError::InternalError(e.into()))
+                    serialize_output(output, &state)
                 }
             }
 
@@ -444,12 +731,16 @@ mod sealed {
             where
                 F: Fn() -> &'static str + Sync + Send,
             {
-                async fn invoke<'a>(&self, _state: RpcContext, input: RawParams<'a>) -> RpcResult {
+                async fn invoke<'a>(
+                    &self,
+                    state: RpcContext,
+                    input: RawParams<'a>,
+                ) -> RawRpcResult {
                     if !input.is_empty() {
                         return Err(RpcError::InvalidParams);
                     }
                     let output = (self.f)();
-                    serde_json::to_value(output).map_err(|e| RpcError::InternalError(e.into()))
+                    serialize_output(output, &state)
                 }
             }
             Box::new(Helper { f: self })
@@ -569,6 +860,33 @@ mod tests {
             .unwrap()
     }
 
+    #[test]
+    fn method_filter() {
+        let filter = MethodFilter::default();
+        assert!(filter.allows("starknet_addInvokeTransaction"));
+
+        let filter = MethodFilter::default()
+            .allow(vec!["read".to_owned(), "starknet_trace*".to_owned()])
+            .deny(vec!["starknet_traceBlockTransactions".to_owned()]);
+        assert!(filter.allows("starknet_getBlockWithTxs"));
+        assert!(filter.allows("pathfinder_getProof"));
+        assert!(filter.allows("starknet_traceTransaction"));
+        assert!(!filter.allows("starknet_traceBlockTransactions"));
+        assert!(!filter.allows("starknet_simulateTransactions"));
+        assert!(!filter.allows("starknet_addDeclareTransaction"));
+        assert!(!filter.allows("pathfinder_getBlockSyncTimings"));
+
+        let filter = MethodFilter::default().deny(vec!["write".to_owned(), "trace".to_owned()]);
+        assert!(filter.allows("starknet_estimateFee"));
+        assert!(!filter.allows("starknet_addDeployAccountTransaction"));
+        assert!(!filter.allows("starknet_simulateTransactions"));
+        assert!(!filter.allows("starknet_simulateTransaction"));
+
+        let filter = MethodFilter::default().deny(vec!["admin".to_owned()]);
+        assert!(filter.allows("pathfinder_getChainStats"));
+        assert!(!filter.allows("pathfinder_getBlockSyncTimings"));
+    }
+
     mod specification_tests {
         //! Test cases lifted directly from the [RPC specification](https://www.jsonrpc.org/specification).
         use super::*;
@@ -954,6 +1272,90 @@ mod tests {
         assert_eq!(content_type, "application/json");
     }
 
+    #[rstest::rstest]
+    #[case::at_limit(9, json!({"jsonrpc": "2.0", "result": "Success", "id": 1}))]
+    #[case::above_limit(8, json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": 10001,
+            "message": "Response too large, reduce the page size",
+            "data": { "limit": 8 },
+        },
+        "id": 1,
+    }))]
+    #[tokio::test]
+    async fn max_response_bytes(#[case] limit: usize, #[case] expected: Value) {
+        fn always_success() -> &'static str {
+            "Success"
+        }
+
+        let context =
+            RpcContext::for_tests().with_max_response_bytes(NonZeroUsize::new(limit).unwrap());
+        let router = RpcRouter::builder("vTEST")
+            .register("success", always_success)
+            .build(context);
+
+        let res = serve_and_query(
+            router,
+            json!({"jsonrpc": "2.0", "method": "success", "id": 1}),
+        )
+        .await;
+
+        assert_eq!(res, expected);
+    }
+
+    #[tokio::test]
+    async fn busy_database_is_retried() {
+        use anyhow::Context;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+
+        async fn busy_once(_ctx: RpcContext) -> RpcResult {
+            match CALLS.fetch_add(1, Ordering::Relaxed) {
+                0 => Err(RpcError::InternalError(
+                    pathfinder_storage::test_utils::busy_error(),
+                )),
+                _ => Ok(json!("Success")),
+            }
+        }
+
+        async fn always_busy(_ctx: RpcContext) -> RpcResult {
+            Err(ApplicationError::Internal(
+                pathfinder_storage::test_utils::busy_error().context("Querying block"),
+            )
+            .into())
+        }
+
+        let router = RpcRouter::builder("vTEST")
+            .register("busy_once", busy_once)
+            .register("always_busy", always_busy)
+            .build(RpcContext::for_tests());
+
+        let res = serve_and_query(
+            router.clone(),
+            json!({"jsonrpc": "2.0", "method": "busy_once", "id": 1}),
+        )
+        .await;
+        assert_eq!(res, json!({"jsonrpc": "2.0", "result": "Success", "id": 1}));
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+
+        let res = serve_and_query(
+            router,
+            json!({"jsonrpc": "2.0", "method": "always_busy", "id": 1}),
+        )
+        .await;
+        let expected = json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": 10003,
+                "message": "Node is busy, retry later",
+            },
+            "id": 1,
+        });
+        assert_eq!(res, expected);
+    }
+
     mod concurrent_futures {
         use super::*;
         use std::cmp::max;
@@ -1117,4 +1519,58 @@ mod tests {
             max_simultaneous
         }
     }
+
+    mod etag {
+        use super::*;
+
+        #[tokio::test]
+        async fn immutable_results_can_be_revalidated() {
+            crate::error::generate_rpc_error_subset!(ExampleError:);
+
+            #[derive(Debug, Deserialize)]
+            struct Input {
+                block_id: Value,
+            }
+            async fn get_block(input: Input) -> Result<Value, ExampleError> {
+                Ok(input.block_id)
+            }
+
+            let router = RpcRouter::builder("vTest")
+                .register("starknet_getStateUpdate", get_block)
+                .register("starknet_getBlockTransactionCount", get_block)
+                .build(RpcContext::for_tests());
+            let url = spawn_server(router).await;
+
+            let query = |method: &str, if_none_match: Option<&str>| {
+                let request = reqwest::Client::new().post(url.clone()).json(&json!({
+                    "jsonrpc": "2.0",
+                    "method": method,
+                    "params": [{"block_hash": "0x1"}],
+                    "id": 1
+                }));
+                let request = match if_none_match {
+                    Some(etag) => request.header("If-None-Match", etag),
+                    None => request,
+                };
+                async move { request.send().await.unwrap() }
+            };
+
+            let response = query("starknet_getStateUpdate", None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let etag = response.headers()["etag"].to_str().unwrap().to_owned();
+            assert!(etag.starts_with("W/\""));
+
+            let response = query("starknet_getStateUpdate", Some(&etag)).await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(response.headers()["etag"], etag.as_str());
+
+            let response = query("starknet_getStateUpdate", Some("W/\"other\"")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            // Results of other methods may change, so they are not tagged.
+            let response = query("starknet_getBlockTransactionCount", None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().get("etag").is_none());
+        }
+    }
 }
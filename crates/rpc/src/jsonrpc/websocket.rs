@@ -12,8 +12,34 @@
 //! < {"jsonrpc":"2.0","result":0,"id":1}
 //! < {"jsonrpc":"2.0","method":"pathfinder_subscription","result":{"truncated":""}}
 //! ```
+//!
+//! A subscription may start at an earlier block by passing `from_block`, e.g.
+//! `{"kind": "newHeads", "from_block": 1000}`. Headers from that block onwards are first replayed
+//! from storage, after which the subscription switches over to live headers without duplicates.
+//!
+//! The status of a transaction is subscribed to with
+//! `{"kind": "transactionStatus", "transaction_hash": "0x123"}`. Its `finality_status` is sent
+//! whenever it changes, i.e. `RECEIVED`, `PENDING`, `ACCEPTED_ON_L2` and `ACCEPTED_ON_L1`, or
+//! `REJECTED`, along with its `execution_status` once executed. The subscription ends with a final
+//! status. A `PENDING` transaction which the sequencer exposes as a candidate of the pending
+//! block, but has not executed yet, is flagged with `"candidate": true`.
+//!
+//! All methods of the default RPC version can be called over the same connection. Their
+//! responses carry the id of the request, and may arrive in a different order than the requests
+//! were sent, interleaved with subscription notifications:
+//! ```bash
+//! > {"jsonrpc":"2.0", "id": 2, "method": "starknet_blockNumber"}
+//! < {"jsonrpc":"2.0","result":1000,"id":2}
+//! ```
+//!
+//! Where websockets are blocked, e.g. by proxies, new heads are also streamed as server-sent
+//! events from `/sse/newHeads`, optionally starting at `?from_block=1000`. Each header is sent as
+//! a `newHeads` event. A lagging stream either receives a `missed` event or is ended by a
+//! `closed` event, depending on the configured lag policy.
 mod data;
 mod logic;
+mod sse;
 
 pub use data::*;
 pub use logic::*;
+pub use sse::*;
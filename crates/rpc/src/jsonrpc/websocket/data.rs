@@ -8,19 +8,28 @@ use pathfinder_common::GasPrice;
 use pathfinder_common::SequencerAddress;
 use pathfinder_common::StarknetVersion;
 use pathfinder_common::StateCommitment;
+use pathfinder_common::TransactionHash;
 use serde::ser::Error;
 use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 use serde_json::Value;
 use std::borrow::Cow;
 use std::sync::Arc;
 
 use pathfinder_serde::GasPriceAsHexStr;
+use starknet_gateway_types::reply::transaction::ExecutionStatus;
 use starknet_gateway_types::reply::{Block, Status};
 
 #[derive(serde::Deserialize, Serialize)]
 pub(super) struct Kind<'a> {
     #[serde(borrow)]
     pub(super) kind: Cow<'a, str>,
+    /// Items from this block onwards are replayed from storage before live items are streamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) from_block: Option<BlockNumber>,
+    /// The transaction whose status is subscribed to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) transaction_hash: Option<TransactionHash>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -28,6 +37,46 @@ pub(super) struct SubscriptionId {
     pub(super) id: u32,
 }
 
+/// Notifies a subscriber of the number of items it missed by lagging behind.
+#[derive(Serialize)]
+pub(super) struct Missed {
+    pub(super) missed: u64,
+}
+
+/// The status of a transaction, sent whenever it changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(super) struct TransactionStatusUpdate {
+    pub(super) transaction_hash: TransactionHash,
+    pub(super) finality_status: FinalityStatus,
+    /// Only known once the transaction has been executed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) execution_status: Option<ExecutionStatus>,
+    /// Set for pending transactions which are only candidates of the pending block, i.e. not
+    /// executed yet and possibly still dropped by the sequencer.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(super) candidate: bool,
+}
+
+impl TransactionStatusUpdate {
+    /// No further updates follow a final status.
+    pub(super) fn is_final(&self) -> bool {
+        matches!(
+            self.finality_status,
+            FinalityStatus::AcceptedOnL1 | FinalityStatus::Rejected
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(super) enum FinalityStatus {
+    Received,
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    Rejected,
+}
+
 pub(super) struct SubscriptionItem<T> {
     pub(super) subscription_id: u32,
     pub(super) item: T,
@@ -102,21 +151,31 @@ pub(super) enum ResponseEvent {
         reason: String,
     },
     InvalidRequest,
-    InvalidMethod(OwnedRequestId),
+    /// The serialized response to a call of a regular RPC method.
+    MethodResponse(Box<RawValue>),
     InvalidParams(OwnedRequestId),
+    TooManySubscriptions {
+        limit: usize,
+        request_id: OwnedRequestId,
+    },
     Header(SubscriptionItem<Arc<Value>>),
+    TransactionStatus(SubscriptionItem<TransactionStatusUpdate>),
+    Missed(SubscriptionItem<Missed>),
 }
 
 impl ResponseEvent {
     pub(super) fn kind(&self) -> &'static str {
         match self {
             ResponseEvent::InvalidRequest => "InvalidRequest",
-            ResponseEvent::InvalidMethod(_) => "InvalidMethod",
+            ResponseEvent::MethodResponse(_) => "MethodResponse",
             ResponseEvent::Header(_) => "BlockHeader",
+            ResponseEvent::TransactionStatus(_) => "TransactionStatus",
             ResponseEvent::Subscribed { .. } => "Subscribed",
             ResponseEvent::Unsubscribed { .. } => "Unsubscribed",
             ResponseEvent::SubscriptionClosed { .. } => "SubscriptionClosed",
             ResponseEvent::InvalidParams(_) => "InvalidParams",
+            ResponseEvent::TooManySubscriptions { .. } => "TooManySubscriptions",
+            ResponseEvent::Missed(_) => "Missed",
         }
     }
 }
@@ -128,13 +187,18 @@ impl Serialize for ResponseEvent {
     {
         match self {
             ResponseEvent::InvalidRequest => RpcResponse::INVALID_REQUEST.serialize(serializer),
-            ResponseEvent::InvalidMethod(id) => {
-                RpcResponse::method_not_found(id.into()).serialize(serializer)
-            }
+            ResponseEvent::MethodResponse(response) => response.serialize(serializer),
             ResponseEvent::InvalidParams(id) => {
                 RpcResponse::invalid_params(id.into()).serialize(serializer)
             }
+            ResponseEvent::TooManySubscriptions { limit, request_id } => RpcResponse {
+                output: Err(RpcError::WebsocketSubscriptionLimit { limit: *limit }),
+                id: request_id.into(),
+            }
+            .serialize(serializer),
             ResponseEvent::Header(header) => header.serialize(serializer),
+            ResponseEvent::TransactionStatus(status) => status.serialize(serializer),
+            ResponseEvent::Missed(missed) => missed.serialize(serializer),
             ResponseEvent::Subscribed {
                 subscription_id,
                 request_id,
@@ -169,7 +233,7 @@ pub(super) fn successful_response<'a, P>(
 where
     P: Serialize,
 {
-    let payload = serde_json::to_value(payload)?;
+    let payload = serde_json::value::to_raw_value(payload)?;
     Ok(RpcResponse {
         output: Ok(payload),
         id: request_id,
@@ -200,6 +264,27 @@ pub struct BlockHeader {
     pub starknet_version: StarknetVersion,
 }
 
+impl BlockHeader {
+    /// The header of a block read from storage.
+    pub(super) fn from_stored(header: pathfinder_common::BlockHeader, l1_accepted: bool) -> Self {
+        Self {
+            block_hash: header.hash,
+            block_number: header.number,
+            gas_price: Some(header.gas_price),
+            parent_block_hash: header.parent_hash,
+            sequencer_address: Some(header.sequencer_address),
+            state_commitment: header.state_commitment,
+            status: if l1_accepted {
+                Status::AcceptedOnL1
+            } else {
+                Status::AcceptedOnL2
+            },
+            timestamp: header.timestamp,
+            starknet_version: header.starknet_version,
+        }
+    }
+}
+
 impl From<&Block> for BlockHeader {
     fn from(b: &Block) -> Self {
         Self {
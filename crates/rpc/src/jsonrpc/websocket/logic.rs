@@ -6,8 +6,15 @@ use std::num::NonZeroUsize;
 use std::ops::ControlFlow;
 use std::sync::Arc;
 
+use anyhow::Context;
+use pathfinder_common::{BlockNumber, TransactionHash};
+use pathfinder_storage::Storage;
+
+use crate::context::RpcContext;
+use crate::error::ApplicationError;
 use crate::jsonrpc::request::RawParams;
-use crate::jsonrpc::{RequestId, RpcRequest};
+use crate::jsonrpc::{RequestId, RpcRequest, RpcResponse, RpcRouter};
+use crate::middleware::auth::MethodAllowlist;
 use axum::extract::ws::{Message, WebSocket};
 use axum::extract::{State, WebSocketUpgrade};
 use axum::response::IntoResponse;
@@ -16,19 +23,36 @@ use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use serde::Serialize;
 use serde_json::Value;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::{broadcast, mpsc, OwnedSemaphorePermit, Semaphore};
 use tracing::error;
 
-use crate::jsonrpc::websocket::data::{Kind, ResponseEvent, SubscriptionId, SubscriptionItem};
+use crate::jsonrpc::websocket::data::{
+    FinalityStatus, Kind, Missed, ResponseEvent, SubscriptionId, SubscriptionItem,
+    TransactionStatusUpdate,
+};
 use crate::BlockHeader;
 
-const SUBSCRIBE_METHOD: &str = "pathfinder_subscribe";
+pub(super) const SUBSCRIBE_METHOD: &str = "pathfinder_subscribe";
 const UNSUBSCRIBE_METHOD: &str = "pathfinder_unsubscribe";
-const NEW_HEADS_TOPIC: &str = "newHeads";
+pub(super) const NEW_HEADS_TOPIC: &str = "newHeads";
+const TRANSACTION_STATUS_TOPIC: &str = "transactionStatus";
+
+/// How a subscription which can't keep up with its topic is handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// The subscription is closed with an error notification.
+    #[default]
+    Disconnect,
+    /// The oldest items are dropped, and the subscriber is notified of the number of items it
+    /// missed before receiving the next one.
+    DropOldest,
+}
 
 #[derive(Clone)]
 pub struct WebsocketContext {
-    socket_buffer_capacity: NonZeroUsize,
+    pub(super) socket_buffer_capacity: NonZeroUsize,
+    max_subscriptions: NonZeroUsize,
+    pub(super) lag_policy: LagPolicy,
     pub broadcasters: TopicBroadcasters,
 }
 
@@ -39,8 +63,22 @@ impl WebsocketContext {
         Self {
             socket_buffer_capacity,
             broadcasters: senders,
+            ..Default::default()
+        }
+    }
+
+    /// Limits the number of active subscriptions of each connection. Further subscriptions are
+    /// rejected until one is unsubscribed.
+    pub fn with_max_subscriptions(self, max_subscriptions: NonZeroUsize) -> Self {
+        Self {
+            max_subscriptions,
+            ..self
         }
     }
+
+    pub fn with_lag_policy(self, lag_policy: LagPolicy) -> Self {
+        Self { lag_policy, ..self }
+    }
 }
 
 impl Default for WebsocketContext {
@@ -48,6 +86,9 @@ impl Default for WebsocketContext {
         Self {
             socket_buffer_capacity: NonZeroUsize::new(100)
                 .expect("Invalid socket buffer capacity default value"),
+            max_subscriptions: NonZeroUsize::new(100)
+                .expect("Invalid max subscriptions default value"),
+            lag_policy: LagPolicy::default(),
             broadcasters: TopicBroadcasters::default(),
         }
     }
@@ -55,9 +96,11 @@ impl Default for WebsocketContext {
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
-    State(state): State<WebsocketContext>,
+    State(router): State<RpcRouter>,
+    allowlist: Option<axum::Extension<MethodAllowlist>>,
 ) -> impl IntoResponse {
-    let mut upgrade_response = ws.on_upgrade(|socket| handle_socket(socket, state));
+    let router = router.with_allowlist(allowlist.map(|axum::Extension(allowlist)| allowlist));
+    let mut upgrade_response = ws.on_upgrade(|socket| handle_socket(socket, router));
 
     static APPLICATION_JSON: http::HeaderValue = http::HeaderValue::from_static("application/json");
     upgrade_response
@@ -67,7 +110,11 @@ pub async fn websocket_handler(
     upgrade_response
 }
 
-async fn handle_socket(socket: WebSocket, context: WebsocketContext) {
+/// Methods other than the subscription ones are served by `router`. Subscriptions read the
+/// node's state from its [RpcContext], e.g. to replay stored headers.
+async fn handle_socket(socket: WebSocket, router: RpcRouter) {
+    let context = router.context().clone();
+    let websocket = context.websocket.clone().unwrap_or_default();
     let (ws_sender, ws_receiver) = socket.split();
 
     let (response_sender, response_receiver) = mpsc::channel(10);
@@ -75,9 +122,18 @@ async fn handle_socket(socket: WebSocket, context: WebsocketContext) {
     tokio::spawn(write(
         ws_sender,
         response_receiver,
-        context.socket_buffer_capacity,
+        websocket.socket_buffer_capacity,
+    ));
+    let subscription_manager =
+        SubscriptionManager::new(websocket.max_subscriptions, websocket.lag_policy, context);
+
+    tokio::spawn(read(
+        ws_receiver,
+        response_sender,
+        websocket.broadcasters,
+        subscription_manager,
+        router,
     ));
-    tokio::spawn(read(ws_receiver, response_sender, context.broadcasters));
 }
 
 async fn write(
@@ -122,11 +178,15 @@ async fn read(
     mut receiver: SplitStream<WebSocket>,
     response_sender: mpsc::Sender<ResponseEvent>,
     source: TopicBroadcasters,
+    mut subscription_manager: SubscriptionManager,
+    router: RpcRouter,
 ) {
-    let mut subscription_manager = SubscriptionManager::default();
+    let method_calls = Arc::new(Semaphore::new(
+        router.context().batch_concurrency_limit.get(),
+    ));
 
     loop {
-        let request = match receiver.next().await {
+        let data = match receiver.next().await {
             Some(Ok(x)) => x.into_data(),
             // Both of these are client disconnects according to the axum example
             // https://docs.rs/axum/0.6.20/axum/extract/ws/index.html#example
@@ -140,7 +200,7 @@ async fn read(
             }
         };
 
-        let Ok(request) = serde_json::from_slice::<RpcRequest<'_>>(&request) else {
+        let Ok(request) = serde_json::from_slice::<RpcRequest<'_>>(&data) else {
             match response_sender.try_send(ResponseEvent::InvalidRequest) {
                 Ok(_) => continue,
                 Err(e) => {
@@ -150,6 +210,29 @@ async fn read(
             }
         };
 
+        // Subscriptions are handled here rather than by the router, so their methods are checked
+        // here as well.
+        if !router.is_allowed(&request.method) {
+            let response = RpcResponse {
+                output: Err(ApplicationError::MethodNotAllowed.into()),
+                id: request.id,
+            };
+            let response = match serde_json::value::to_raw_value(&response) {
+                Ok(response) => ResponseEvent::MethodResponse(response),
+                Err(error) => {
+                    tracing::warn!(%error, "Encoding method response failed");
+                    break;
+                }
+            };
+            match response_sender.try_send(response) {
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::debug!(reason=%e, "Failed to send response");
+                    break;
+                }
+            }
+        }
+
         // Handle request.
         let response = match request.method.as_ref() {
             SUBSCRIBE_METHOD => subscription_manager.subscribe(
@@ -163,7 +246,20 @@ async fn read(
                     .unsubscribe(request.id, request.params)
                     .await
             }
-            _ => ResponseEvent::InvalidMethod(request.id.into()),
+            _ => {
+                // Method calls run concurrently, so that slow calls don't hold up the others. The
+                // connection stops reading further requests while all permits are taken.
+                let Ok(permit) = method_calls.clone().acquire_owned().await else {
+                    break;
+                };
+                tokio::spawn(method_call(
+                    router.clone(),
+                    data,
+                    response_sender.clone(),
+                    permit,
+                ));
+                continue;
+            }
         };
 
         if let Err(e) = response_sender.try_send(response) {
@@ -178,14 +274,55 @@ async fn read(
     subscription_manager.abort_all();
 }
 
+/// Executes a call of a regular RPC method, and sends its response unless the call is a
+/// notification.
+async fn method_call(
+    router: RpcRouter,
+    request: Vec<u8>,
+    response_sender: mpsc::Sender<ResponseEvent>,
+    _permit: OwnedSemaphorePermit,
+) {
+    let response = match std::str::from_utf8(&request) {
+        Ok(request) => match router.run_request(request).await {
+            Some(response) => {
+                response.log_error();
+                match serde_json::value::to_raw_value(&response) {
+                    Ok(response) => ResponseEvent::MethodResponse(response),
+                    Err(error) => {
+                        tracing::warn!(%error, "Encoding method response failed");
+                        return;
+                    }
+                }
+            }
+            None => return,
+        },
+        Err(_) => ResponseEvent::InvalidRequest,
+    };
+
+    // The connection is closing if the response can't be sent.
+    let _ = response_sender.send(response).await;
+}
+
 /// Manages the subscription for a single connection
-#[derive(Default)]
 struct SubscriptionManager {
     next_id: u32,
     subscriptions: HashMap<u32, tokio::task::JoinHandle<()>>,
+    max_subscriptions: NonZeroUsize,
+    lag_policy: LagPolicy,
+    context: RpcContext,
 }
 
 impl SubscriptionManager {
+    fn new(max_subscriptions: NonZeroUsize, lag_policy: LagPolicy, context: RpcContext) -> Self {
+        Self {
+            next_id: 0,
+            subscriptions: Default::default(),
+            max_subscriptions,
+            lag_policy,
+            context,
+        }
+    }
+
     async fn unsubscribe(
         &mut self,
         request_id: RequestId<'_>,
@@ -223,18 +360,45 @@ impl SubscriptionManager {
             return ResponseEvent::InvalidParams(request_id.into());
         };
 
+        // Subscriptions which ended on their own, e.g. due to a lagging stream, no longer count.
+        self.subscriptions.retain(|_, handle| !handle.is_finished());
+        if self.subscriptions.len() >= self.max_subscriptions.get() {
+            return ResponseEvent::TooManySubscriptions {
+                limit: self.max_subscriptions.get(),
+                request_id: request_id.into(),
+            };
+        }
+
         let subscription_id = self.next_id;
-        self.next_id += 1;
-        let receiver = websocket_source.new_head.subscribe();
-        let handle = match kind.kind.as_ref() {
-            NEW_HEADS_TOPIC => tokio::spawn(header_subscription(
-                response_sender,
-                receiver,
-                subscription_id,
-            )),
+        let handle = match (kind.kind.as_ref(), kind.transaction_hash) {
+            (NEW_HEADS_TOPIC, None) => {
+                let replay = kind.from_block.map(|from_block| Replay {
+                    storage: self.context.storage.clone(),
+                    from_block,
+                });
+                // Subscribe before replaying, so that no live item is missed while replaying.
+                let receiver = websocket_source.new_head.subscribe();
+                tokio::spawn(header_subscription(
+                    response_sender,
+                    receiver,
+                    subscription_id,
+                    self.lag_policy,
+                    replay,
+                ))
+            }
+            (TRANSACTION_STATUS_TOPIC, Some(transaction_hash)) if kind.from_block.is_none() => {
+                tokio::spawn(transaction_status_subscription(
+                    response_sender,
+                    self.context.clone(),
+                    websocket_source,
+                    subscription_id,
+                    transaction_hash,
+                ))
+            }
             _ => return ResponseEvent::InvalidParams(request_id.into()),
         };
 
+        self.next_id += 1;
         self.subscriptions.insert(subscription_id, handle);
 
         ResponseEvent::Subscribed {
@@ -250,31 +414,87 @@ impl SubscriptionManager {
     }
 }
 
-async fn header_subscription(
+pub(super) async fn header_subscription(
     msg_sender: mpsc::Sender<ResponseEvent>,
     mut headers: broadcast::Receiver<Arc<Value>>,
     subscription_id: u32,
+    lag_policy: LagPolicy,
+    replay: Option<Replay>,
 ) {
     use broadcast::error::RecvError;
+
+    // The next block to send while switching over from replayed to live headers.
+    let mut switchover = None;
+    if let Some(replay) = &replay {
+        match replay
+            .send_headers(&msg_sender, subscription_id, replay.from_block, None)
+            .await
+        {
+            ControlFlow::Continue(next) => switchover = Some(next),
+            ControlFlow::Break(()) => return,
+        }
+    }
+
     loop {
         let response = match headers.recv().await {
-            Ok(header) => ResponseEvent::Header(SubscriptionItem {
-                subscription_id,
-                item: header,
-            }),
-            Err(RecvError::Closed) => break,
-            Err(RecvError::Lagged(amount)) => {
-                tracing::info!(
-                    amount,
-                    "Lagging header stream, missed some events, closing subscription"
-                );
+            Ok(header) => {
+                if let (Some(next), Some(replay)) = (switchover.take(), &replay) {
+                    let number = header["block_number"]
+                        .as_u64()
+                        .map(BlockNumber::new_or_panic);
+                    match number {
+                        // Received while replaying, and therefore already sent.
+                        Some(number) if number < next => {
+                            switchover = Some(next);
+                            continue;
+                        }
+                        // Stored after replaying, but broadcast before subscribing.
+                        Some(number) if number > next => {
+                            if replay
+                                .send_headers(&msg_sender, subscription_id, next, Some(number - 1))
+                                .await
+                                .is_break()
+                            {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
 
-                ResponseEvent::SubscriptionClosed {
+                ResponseEvent::Header(SubscriptionItem {
                     subscription_id,
-                    reason: "Lagging stream, some headers were skipped. Closing subscription."
-                        .to_owned(),
-                }
+                    item: header,
+                })
             }
+            Err(RecvError::Closed) => break,
+            Err(RecvError::Lagged(amount)) => match lag_policy {
+                LagPolicy::Disconnect => {
+                    tracing::info!(
+                        amount,
+                        "Lagging header stream, missed some events, closing subscription"
+                    );
+
+                    // The subscription ends once the client has been notified.
+                    let _ = msg_sender
+                        .send(ResponseEvent::SubscriptionClosed {
+                            subscription_id,
+                            reason:
+                                "Lagging stream, some headers were skipped. Closing subscription."
+                                    .to_owned(),
+                        })
+                        .await;
+                    break;
+                }
+                LagPolicy::DropOldest => {
+                    tracing::debug!(amount, "Lagging header stream, missed some events");
+
+                    ResponseEvent::Missed(SubscriptionItem {
+                        subscription_id,
+                        item: Missed { missed: amount },
+                    })
+                }
+            },
         };
 
         if msg_sender.send(response).await.is_err() {
@@ -283,6 +503,274 @@ async fn header_subscription(
     }
 }
 
+/// Sends the status of a transaction whenever it changes, until it is final.
+///
+/// The status is checked again whenever the pending block is updated, a block is added or a
+/// block is accepted on L1.
+async fn transaction_status_subscription(
+    msg_sender: mpsc::Sender<ResponseEvent>,
+    mut context: RpcContext,
+    source: TopicBroadcasters,
+    subscription_id: u32,
+    transaction_hash: TransactionHash,
+) {
+    use broadcast::error::RecvError;
+
+    // Subscribe before the first check, so that no change is missed.
+    let mut headers = source.new_head.subscribe();
+    let mut l1_heads = source.l1_head.subscribe();
+    let mut pending_open = true;
+
+    let mut last = None;
+    loop {
+        let status = match transaction_status(context.clone(), transaction_hash).await {
+            Ok(status) => status,
+            Err(error) => {
+                tracing::warn!(%error, "Querying transaction status failed, closing subscription");
+                let _ = msg_sender
+                    .send(ResponseEvent::SubscriptionClosed {
+                        subscription_id,
+                        reason: "Querying transaction status failed. Closing subscription."
+                            .to_owned(),
+                    })
+                    .await;
+                break;
+            }
+        };
+
+        if let Some(status) = status.filter(|status| last.as_ref() != Some(status)) {
+            let is_final = status.is_final();
+            let response = ResponseEvent::TransactionStatus(SubscriptionItem {
+                subscription_id,
+                item: status.clone(),
+            });
+            if msg_sender.send(response).await.is_err() || is_final {
+                break;
+            }
+            last = Some(status);
+        }
+
+        // Missed items are irrelevant, as the status is checked again either way.
+        tokio::select! {
+            result = context.pending_data.changed(), if pending_open => {
+                pending_open = result.is_ok();
+            }
+            result = headers.recv() => {
+                if let Err(RecvError::Closed) = result {
+                    break;
+                }
+            }
+            result = l1_heads.recv() => {
+                if let Err(RecvError::Closed) = result {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// The status of a transaction as known to this node, or to the gateway if it has not been
+/// added to a block yet.
+///
+/// [None] if the transaction is not known yet.
+async fn transaction_status(
+    context: RpcContext,
+    transaction_hash: TransactionHash,
+) -> anyhow::Result<Option<TransactionStatusUpdate>> {
+    let storage = context.storage.clone();
+    let pending_data = context.pending_data.clone();
+    let db_status = tokio::task::spawn_blocking(move || {
+        let mut db = storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let pending = pending_data.get(&db_tx).context("Querying pending data")?;
+        if let Some(receipt) = pending
+            .block
+            .transaction_receipts
+            .iter()
+            .find(|receipt| receipt.transaction_hash == transaction_hash)
+        {
+            return Ok(Some(TransactionStatusUpdate {
+                transaction_hash,
+                finality_status: FinalityStatus::Pending,
+                execution_status: Some(receipt.execution_status.clone()),
+                candidate: false,
+            }));
+        }
+
+        if pending.is_candidate(transaction_hash) {
+            return Ok(Some(TransactionStatusUpdate {
+                transaction_hash,
+                finality_status: FinalityStatus::Pending,
+                execution_status: None,
+                candidate: true,
+            }));
+        }
+
+        let Some((_, receipt, block_hash)) = db_tx
+            .transaction_with_receipt(transaction_hash)
+            .context("Fetching receipt from database")?
+        else {
+            return anyhow::Ok(None);
+        };
+
+        let l1_accepted = db_tx
+            .block_is_l1_accepted(block_hash.into())
+            .context("Querying block's status")?;
+
+        Ok(Some(TransactionStatusUpdate {
+            transaction_hash,
+            finality_status: if l1_accepted {
+                FinalityStatus::AcceptedOnL1
+            } else {
+                FinalityStatus::AcceptedOnL2
+            },
+            execution_status: Some(receipt.execution_status),
+            candidate: false,
+        }))
+    })
+    .await
+    .context("Joining database task")??;
+
+    if db_status.is_some() {
+        return Ok(db_status);
+    }
+
+    // Only the gateway knows of received and rejected transactions. Any later status is taken
+    // from storage once synced, so that it is consistent with the rest of the node's API.
+    use starknet_gateway_client::GatewayApi;
+    use starknet_gateway_types::reply::transaction_status::{
+        ExecutionStatus as GatewayExecutionStatus, FinalityStatus as GatewayFinalityStatus,
+    };
+    let finality_status = match context.sequencer.transaction(transaction_hash).await {
+        Ok(tx) => match (tx.finality_status, tx.execution_status) {
+            (_, GatewayExecutionStatus::Rejected) => Some(FinalityStatus::Rejected),
+            (GatewayFinalityStatus::Received, _) => Some(FinalityStatus::Received),
+            _ => None,
+        },
+        Err(error) => {
+            tracing::debug!(%error, ?transaction_hash, "Fetching transaction from gateway failed");
+            None
+        }
+    };
+
+    Ok(
+        finality_status.map(|finality_status| TransactionStatusUpdate {
+            transaction_hash,
+            finality_status,
+            execution_status: None,
+            candidate: false,
+        }),
+    )
+}
+
+/// Replays stored items to a subscription before it streams live items.
+pub(super) struct Replay {
+    pub(super) storage: Storage,
+    pub(super) from_block: BlockNumber,
+}
+
+impl Replay {
+    /// The number of headers read from storage at once.
+    const BATCH_SIZE: usize = 1000;
+
+    /// Sends the stored headers from `from` up to and including `to`, or the latest block.
+    ///
+    /// Continues with the number of the block following the last header sent, and breaks if
+    /// the subscription has ended.
+    async fn send_headers(
+        &self,
+        msg_sender: &mpsc::Sender<ResponseEvent>,
+        subscription_id: u32,
+        from: BlockNumber,
+        to: Option<BlockNumber>,
+    ) -> ControlFlow<(), BlockNumber> {
+        let mut next = from;
+        loop {
+            let storage = self.storage.clone();
+            let batch = tokio::task::spawn_blocking(move || {
+                read_headers(storage, next, to, Self::BATCH_SIZE)
+            })
+            .await
+            .context("Joining database task")
+            .and_then(|batch| batch);
+
+            let batch = match batch {
+                Ok(batch) => batch,
+                Err(error) => {
+                    tracing::warn!(%error, "Replaying headers failed, closing subscription");
+                    let _ = msg_sender
+                        .send(ResponseEvent::SubscriptionClosed {
+                            subscription_id,
+                            reason: "Replaying headers failed. Closing subscription.".to_owned(),
+                        })
+                        .await;
+                    return ControlFlow::Break(());
+                }
+            };
+
+            let done = batch.len() < Self::BATCH_SIZE;
+            for header in batch {
+                next = header.block_number + 1;
+
+                let item = match serde_json::to_value(header) {
+                    Ok(item) => Arc::new(item),
+                    Err(error) => {
+                        tracing::warn!(%error, "Encoding replayed header failed");
+                        return ControlFlow::Break(());
+                    }
+                };
+                let response = ResponseEvent::Header(SubscriptionItem {
+                    subscription_id,
+                    item,
+                });
+                if msg_sender.send(response).await.is_err() {
+                    return ControlFlow::Break(());
+                }
+            }
+
+            if done {
+                return ControlFlow::Continue(next);
+            }
+        }
+    }
+}
+
+/// Reads up to `limit` consecutive headers from `from` up to and including `to`, stopping at
+/// the latest block.
+fn read_headers(
+    storage: Storage,
+    from: BlockNumber,
+    to: Option<BlockNumber>,
+    limit: usize,
+) -> anyhow::Result<Vec<BlockHeader>> {
+    let mut db = storage
+        .connection()
+        .context("Opening database connection")?;
+    let tx = db.transaction().context("Creating database transaction")?;
+
+    let l1_accepted = tx.l1_l2_pointer().context("Querying L1-L2 pointer")?;
+
+    let mut headers = Vec::new();
+    let mut number = from;
+    while headers.len() < limit && to.map_or(true, |to| number <= to) {
+        let Some(header) = tx
+            .block_header(number.into())
+            .context("Querying block header")?
+        else {
+            break;
+        };
+
+        let is_l1_accepted = l1_accepted.map_or(false, |l1| number <= l1);
+        headers.push(BlockHeader::from_stored(header, is_l1_accepted));
+        number += 1;
+    }
+
+    Ok(headers)
+}
+
 /// A Tokio broadcast sender pre-serializing the value once for all subscribers.
 /// Relies on `Arc`s to flatten the cloning costs inherent to Tokio broadcast channels.
 #[derive(Debug, Clone)]
@@ -324,6 +812,8 @@ where
 #[derive(Debug, Clone)]
 pub struct TopicBroadcasters {
     pub new_head: JsonBroadcaster<BlockHeader>,
+    /// The latest block accepted on L1, as its L1 state update is synced.
+    pub l1_head: JsonBroadcaster<BlockNumber>,
 }
 
 impl TopicBroadcasters {
@@ -333,6 +823,10 @@ impl TopicBroadcasters {
                 sender: broadcast::channel(capacity.get()).0,
                 item_type: PhantomData {},
             },
+            l1_head: JsonBroadcaster {
+                sender: broadcast::channel(capacity.get()).0,
+                item_type: PhantomData {},
+            },
         }
     }
 }
@@ -357,6 +851,7 @@ mod tests {
     use serde::Serialize;
     use serde_json::value::RawValue;
     use serde_json::{json, Number, Value};
+    use starknet_gateway_types::reply::transaction::ExecutionStatus;
     use starknet_gateway_types::reply::Status;
     use std::borrow::Cow;
     use std::time::Duration;
@@ -398,6 +893,8 @@ mod tests {
                 method: Cow::from(SUBSCRIBE_METHOD),
                 params: RawParams(Some(&value(&Kind {
                     kind: NEW_HEADS_TOPIC.into(),
+                    from_block: None,
+                    transaction_hash: None,
                 }))),
                 id: req_id.clone(),
             })
@@ -448,10 +945,335 @@ mod tests {
         client.destroy().await;
     }
 
+    #[tokio::test]
+    async fn subscription_limit() {
+        let context = WebsocketContext::default().with_max_subscriptions(NonZeroUsize::MIN);
+        let mut client = Client::with_context(context).await;
+
+        client.send_subscribe(RequestId::Number(1)).await;
+        client
+            .expect_response(&successful_response(&0, RequestId::Number(1)).unwrap())
+            .await;
+
+        client.send_subscribe(RequestId::Number(2)).await;
+        client
+            .expect_response(&RpcResponse {
+                output: Err(RpcError::WebsocketSubscriptionLimit { limit: 1 }),
+                id: RequestId::Number(2),
+            })
+            .await;
+
+        client
+            .send_request(&RpcRequest {
+                method: Cow::from(UNSUBSCRIBE_METHOD),
+                params: RawParams(Some(&value(&SubscriptionId { id: 0 }))),
+                id: RequestId::Number(3),
+            })
+            .await;
+        client
+            .expect_response(&successful_response(&true, RequestId::Number(3)).unwrap())
+            .await;
+
+        client.send_subscribe(RequestId::Number(4)).await;
+        client
+            .expect_response(&successful_response(&1, RequestId::Number(4)).unwrap())
+            .await;
+
+        client.destroy().await;
+    }
+
+    #[tokio::test]
+    async fn lagging_subscription_drops_oldest() {
+        let context = WebsocketContext::new(NonZeroUsize::new(100).unwrap(), NonZeroUsize::MIN)
+            .with_lag_policy(LagPolicy::DropOldest);
+        let mut client = Client::with_context(context).await;
+        client.subscribe_to_heads().await;
+
+        // The topic only holds the latest header, so the subscription misses the first two.
+        let headers = (0..3)
+            .map(|i| BlockHeader {
+                block_number: pathfinder_common::BlockNumber::new_or_panic(i),
+                ..header_sample()
+            })
+            .collect::<Vec<_>>();
+        for header in &headers {
+            client
+                .head_sender
+                .send_if_receiving(header.clone())
+                .unwrap();
+        }
+
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 0,
+                item: Missed { missed: 2 },
+            })
+            .await;
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 0,
+                item: headers[2].clone(),
+            })
+            .await;
+
+        client.destroy().await;
+    }
+
+    #[tokio::test]
+    async fn lagging_subscription_is_closed() {
+        let context = WebsocketContext::new(NonZeroUsize::new(100).unwrap(), NonZeroUsize::MIN);
+        let mut client = Client::with_context(context).await;
+        client.subscribe_to_heads().await;
+
+        for _ in 0..3 {
+            client
+                .head_sender
+                .send_if_receiving(header_sample())
+                .unwrap();
+        }
+
+        client
+            .expect_response(&RpcResponse {
+                output: Err(RpcError::WebsocketSubscriptionClosed {
+                    subscription_id: 0,
+                    reason: "Lagging stream, some headers were skipped. Closing subscription."
+                        .to_owned(),
+                }),
+                id: RequestId::Null,
+            })
+            .await;
+
+        client
+            .head_sender
+            .send_if_receiving(header_sample())
+            .unwrap();
+        client.expect_no_response().await;
+
+        client.destroy().await;
+    }
+
+    #[tokio::test]
+    async fn replays_stored_headers() {
+        let context = RpcContext::for_tests();
+        let stored = read_headers(
+            context.storage.clone(),
+            BlockNumber::new_or_panic(1),
+            None,
+            10,
+        )
+        .unwrap();
+        assert_eq!(stored.len(), 2);
+
+        let mut client = Client::with_rpc_context(context).await;
+
+        client
+            .send_request(&RpcRequest {
+                method: Cow::from(SUBSCRIBE_METHOD),
+                params: RawParams(Some(&value(&Kind {
+                    kind: NEW_HEADS_TOPIC.into(),
+                    from_block: Some(BlockNumber::new_or_panic(1)),
+                    transaction_hash: None,
+                }))),
+                id: RequestId::Number(0),
+            })
+            .await;
+        client
+            .expect_response(&successful_response(&0, RequestId::Number(0)).unwrap())
+            .await;
+
+        for header in &stored {
+            client
+                .expect_response(&SubscriptionItem {
+                    subscription_id: 0,
+                    item: header.clone(),
+                })
+                .await;
+        }
+
+        // The latest block was already replayed, and is not sent twice.
+        let latest = stored.last().unwrap().clone();
+        let next = BlockHeader {
+            block_number: latest.block_number + 1,
+            parent_block_hash: latest.block_hash,
+            ..header_sample()
+        };
+        client.head_sender.send_if_receiving(latest).unwrap();
+        client.head_sender.send_if_receiving(next.clone()).unwrap();
+
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 0,
+                item: next,
+            })
+            .await;
+        client.expect_no_response().await;
+
+        client.destroy().await;
+    }
+
+    #[tokio::test]
+    async fn transaction_status_updates() {
+        use pathfinder_common::macro_prelude::*;
+
+        let context = RpcContext::for_tests_with_pending().await;
+        let storage = context.storage.clone();
+        let mut client = Client::with_rpc_context(context).await;
+
+        let pending_hash = transaction_hash_bytes!(b"pending tx hash 0");
+        client.subscribe_to_status(0, pending_hash).await;
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 0,
+                item: TransactionStatusUpdate {
+                    transaction_hash: pending_hash,
+                    finality_status: FinalityStatus::Pending,
+                    execution_status: Some(ExecutionStatus::Succeeded),
+                    candidate: false,
+                },
+            })
+            .await;
+
+        // Block 1 is accepted on L2 only.
+        let hash = transaction_hash_bytes!(b"txn 1");
+        client.subscribe_to_status(1, hash).await;
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 1,
+                item: TransactionStatusUpdate {
+                    transaction_hash: hash,
+                    finality_status: FinalityStatus::AcceptedOnL2,
+                    execution_status: Some(ExecutionStatus::Succeeded),
+                    candidate: false,
+                },
+            })
+            .await;
+
+        // Unchanged statuses are not sent again.
+        client
+            .head_sender
+            .send_if_receiving(header_sample())
+            .unwrap();
+        client.expect_no_response().await;
+
+        let l1_head = BlockNumber::new_or_panic(1);
+        let mut db = storage.connection().unwrap();
+        let tx = db.transaction().unwrap();
+        tx.update_l1_l2_pointer(Some(l1_head)).unwrap();
+        tx.commit().unwrap();
+        client.l1_sender.send_if_receiving(l1_head).unwrap();
+
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 1,
+                item: TransactionStatusUpdate {
+                    transaction_hash: hash,
+                    finality_status: FinalityStatus::AcceptedOnL1,
+                    execution_status: Some(ExecutionStatus::Succeeded),
+                    candidate: false,
+                },
+            })
+            .await;
+
+        // The subscription ends with the final status.
+        client.l1_sender.send_if_receiving(l1_head).unwrap();
+        client.expect_no_response().await;
+
+        let candidate_hash = transaction_hash_bytes!(b"pending candidate");
+        client.subscribe_to_status(2, candidate_hash).await;
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 2,
+                item: TransactionStatusUpdate {
+                    transaction_hash: candidate_hash,
+                    finality_status: FinalityStatus::Pending,
+                    execution_status: None,
+                    candidate: true,
+                },
+            })
+            .await;
+
+        client.destroy().await;
+    }
+
+    #[tokio::test]
+    async fn transaction_status_requires_hash() {
+        let mut client = Client::new().await;
+
+        client
+            .send_request(&RpcRequest {
+                method: Cow::from(SUBSCRIBE_METHOD),
+                params: RawParams(Some(&value(&Kind {
+                    kind: TRANSACTION_STATUS_TOPIC.into(),
+                    from_block: None,
+                    transaction_hash: None,
+                }))),
+                id: RequestId::Number(0),
+            })
+            .await;
+        client
+            .expect_response(&RpcResponse {
+                output: Err(RpcError::InvalidParams),
+                id: RequestId::Number(0),
+            })
+            .await;
+
+        client.destroy().await;
+    }
+
+    #[tokio::test]
+    async fn serves_rpc_methods() {
+        let mut client = Client::new().await;
+        client.subscribe_to_heads().await;
+
+        client
+            .send_request(&RpcRequest {
+                method: Cow::from("starknet_chainId"),
+                params: Default::default(),
+                id: RequestId::Number(1),
+            })
+            .await;
+        client
+            .expect_response(
+                &successful_response(
+                    &pathfinder_common::ChainId::TESTNET.to_hex_str(),
+                    RequestId::Number(1),
+                )
+                .unwrap(),
+            )
+            .await;
+
+        client
+            .send_request(&RpcRequest {
+                method: Cow::from("starknet_unknownMethod"),
+                params: Default::default(),
+                id: RequestId::String("two".into()),
+            })
+            .await;
+        client
+            .expect_response(&RpcResponse {
+                output: Err(RpcError::MethodNotFound),
+                id: RequestId::String("two".into()),
+            })
+            .await;
+
+        // Subscriptions continue alongside method calls.
+        client
+            .head_sender
+            .send_if_receiving(header_sample())
+            .unwrap();
+        client
+            .expect_response(&SubscriptionItem {
+                subscription_id: 0,
+                item: header_sample(),
+            })
+            .await;
+
+        client.destroy().await;
+    }
+
     // TODO Prevent duplicate subscriptions?
     // This is actually tolerated by Alchemy, you can subscribe multiple times
     // to the same topic and receive duplicated messages as a result.
-    // TODO Subscription limit?
 
     fn value<S>(payload: &S) -> Box<RawValue>
     where
@@ -479,16 +1301,32 @@ mod tests {
         receiver: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
         server_handle: JoinHandle<()>,
         head_sender: JsonBroadcaster<BlockHeader>,
+        l1_sender: JsonBroadcaster<BlockNumber>,
     }
 
     impl Client {
         async fn new() -> Client {
-            let context = WebsocketContext::default();
-            let head_sender = context.broadcasters.new_head.clone();
+            Self::with_context(WebsocketContext::default()).await
+        }
+
+        async fn with_context(context: WebsocketContext) -> Client {
+            Self::with_rpc_context(RpcContext::for_tests().with_websockets(context)).await
+        }
+
+        /// Serves the websocket of the given context, which defaults to a [WebsocketContext]
+        /// if it has none.
+        async fn with_rpc_context(context: RpcContext) -> Client {
+            let context = match context.websocket {
+                Some(_) => context,
+                None => context.with_websockets(WebsocketContext::default()),
+            };
+            let broadcasters = context.websocket.as_ref().unwrap().broadcasters.clone();
+            let head_sender = broadcasters.new_head;
+            let l1_sender = broadcasters.l1_head;
 
             let router = axum::Router::new()
                 .route("/ws", get(websocket_handler))
-                .with_state(context)
+                .with_state(crate::v05::register_routes().build(context))
                 .layer(tower::ServiceBuilder::new());
 
             let listener = std::net::TcpListener::bind("127.0.0.1:0")
@@ -512,12 +1350,49 @@ mod tests {
 
             Client {
                 head_sender,
+                l1_sender,
                 sender,
                 receiver,
                 server_handle,
             }
         }
 
+        async fn send_subscribe(&mut self, id: RequestId<'_>) {
+            self.send_request(&RpcRequest {
+                method: Cow::from(SUBSCRIBE_METHOD),
+                params: RawParams(Some(&value(&Kind {
+                    kind: NEW_HEADS_TOPIC.into(),
+                    from_block: None,
+                    transaction_hash: None,
+                }))),
+                id,
+            })
+            .await;
+        }
+
+        /// Subscribes to new heads, expecting the subscription to be the connection's first.
+        async fn subscribe_to_heads(&mut self) {
+            self.send_subscribe(RequestId::Number(0)).await;
+            self.expect_response(&successful_response(&0, RequestId::Number(0)).unwrap())
+                .await;
+        }
+
+        /// Subscribes to the status of a transaction, expecting the given subscription id.
+        async fn subscribe_to_status(&mut self, id: u32, transaction_hash: TransactionHash) {
+            self.send_request(&RpcRequest {
+                method: Cow::from(SUBSCRIBE_METHOD),
+                params: RawParams(Some(&value(&Kind {
+                    kind: TRANSACTION_STATUS_TOPIC.into(),
+                    from_block: None,
+                    transaction_hash: Some(transaction_hash),
+                }))),
+                id: RequestId::Number(id.into()),
+            })
+            .await;
+            self.expect_response(&successful_response(&id, RequestId::Number(id.into())).unwrap())
+                .await;
+        }
+
         async fn send_request(&mut self, request: &RpcRequest<'_>) {
             let id = match &request.id {
                 RequestId::Number(n) => Value::Number(Number::from(*n)),
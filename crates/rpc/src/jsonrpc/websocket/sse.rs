@@ -0,0 +1,193 @@
+//! See [the parent module documentation](super)
+
+use std::convert::Infallible;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::{Stream, StreamExt};
+use pathfinder_common::BlockNumber;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::jsonrpc::websocket::data::ResponseEvent;
+use crate::jsonrpc::websocket::logic::{
+    header_subscription, Replay, NEW_HEADS_TOPIC, SUBSCRIBE_METHOD,
+};
+use crate::jsonrpc::RpcRouter;
+use crate::middleware::auth::MethodAllowlist;
+
+#[derive(Debug, Deserialize)]
+pub struct NewHeadsParams {
+    /// Headers from this block onwards are replayed from storage before live headers are sent.
+    from_block: Option<u64>,
+}
+
+/// Streams new heads as server-sent events, for clients which can't use websockets.
+///
+/// Consumes the same broadcast as the websocket `newHeads` subscription, and follows the same
+/// [lag policy](super::LagPolicy).
+pub async fn new_heads_handler(
+    State(router): State<RpcRouter>,
+    allowlist: Option<axum::Extension<MethodAllowlist>>,
+    Query(params): Query<NewHeadsParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    // The stream is equivalent to a websocket subscription.
+    let router = router.with_allowlist(allowlist.map(|axum::Extension(allowlist)| allowlist));
+    if !router.is_allowed(SUBSCRIBE_METHOD) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let context = router.context();
+    let websocket = context.websocket.clone().unwrap_or_default();
+
+    let replay = params
+        .from_block
+        .map(|from_block| {
+            BlockNumber::new(from_block)
+                .map(|from_block| Replay {
+                    storage: context.storage.clone(),
+                    from_block,
+                })
+                .ok_or(StatusCode::BAD_REQUEST)
+        })
+        .transpose()?;
+
+    let (sender, receiver) = mpsc::channel(websocket.socket_buffer_capacity.get());
+    // Subscribe before replaying, so that no live header is missed while replaying.
+    let headers = websocket.broadcasters.new_head.subscribe();
+    // The subscription ends once the client disconnects and the receiver is dropped.
+    tokio::spawn(header_subscription(
+        sender,
+        headers,
+        0,
+        websocket.lag_policy,
+        replay,
+    ));
+
+    let events = ReceiverStream::new(receiver).filter_map(|response| async move {
+        let event = match response {
+            ResponseEvent::Header(header) => Event::default()
+                .event(NEW_HEADS_TOPIC)
+                .json_data(&*header.item),
+            ResponseEvent::Missed(missed) => {
+                Event::default().event("missed").json_data(missed.item)
+            }
+            ResponseEvent::SubscriptionClosed { reason, .. } => {
+                Ok(Event::default().event("closed").data(reason))
+            }
+            _ => return None,
+        };
+
+        match event {
+            Ok(event) => Some(Ok(event)),
+            Err(error) => {
+                tracing::warn!(%error, "Encoding server-sent event failed");
+                None
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::get;
+    use pathfinder_common::BlockHash;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use super::*;
+    use crate::context::RpcContext;
+    use crate::jsonrpc::websocket::{BlockHeader, WebsocketContext};
+
+    /// Sends a request to the SSE endpoint and returns the connection once the response headers
+    /// were received.
+    async fn connect(context: RpcContext, query: &str) -> TcpStream {
+        let router = axum::Router::new()
+            .route("/sse/newHeads", get(new_heads_handler))
+            .with_state(crate::v05::register_routes().build(context));
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = axum::Server::from_tcp(listener).unwrap();
+        tokio::spawn(async move { server.serve(router.into_make_service()).await.unwrap() });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET /sse/newHeads{query} HTTP/1.1\r\nHost: {addr}\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let head = read_until(&mut stream, "\r\n\r\n").await;
+        assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+        assert!(head.contains("text/event-stream"), "{head}");
+        stream
+    }
+
+    async fn read_until(stream: &mut TcpStream, pattern: &str) -> String {
+        let mut received = Vec::new();
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !String::from_utf8_lossy(&received).contains(pattern) {
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0, "Connection closed");
+                received.extend_from_slice(&buf[..n]);
+            }
+        })
+        .await
+        .expect("Pattern to be received");
+        String::from_utf8(received).unwrap()
+    }
+
+    fn header(number: u64) -> BlockHeader {
+        BlockHeader {
+            block_hash: BlockHash::default(),
+            block_number: BlockNumber::new_or_panic(number),
+            gas_price: None,
+            parent_block_hash: BlockHash::default(),
+            sequencer_address: None,
+            state_commitment: Default::default(),
+            status: starknet_gateway_types::reply::Status::AcceptedOnL2,
+            timestamp: Default::default(),
+            starknet_version: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_new_heads() {
+        let context = RpcContext::for_tests().with_websockets(WebsocketContext::default());
+        let head_sender = context
+            .websocket
+            .as_ref()
+            .unwrap()
+            .broadcasters
+            .new_head
+            .clone();
+        let mut stream = connect(context, "").await;
+
+        let header = header(7);
+        head_sender.send_if_receiving(header.clone()).unwrap();
+
+        let received = read_until(&mut stream, "}\n\n").await;
+        assert!(received.contains("event: newHeads\n"), "{received}");
+        let data = received
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(data).unwrap(),
+            serde_json::to_value(&header).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_stored_heads() {
+        let context = RpcContext::for_tests().with_websockets(WebsocketContext::default());
+        let mut stream = connect(context, "?from_block=0").await;
+
+        let received = read_until(&mut stream, "\"block_number\":2").await;
+        assert!(received.contains("\"block_number\":0"), "{received}");
+        assert!(received.contains("\"block_number\":1"), "{received}");
+    }
+}
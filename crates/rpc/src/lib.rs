@@ -1,26 +1,39 @@
 //! Starknet node JSON-RPC related modules.
+pub mod casm_cache;
 pub mod context;
+mod dto;
 mod error;
 mod executor;
+pub mod fee_estimate_cache;
 mod felt;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
 pub mod gas_price;
 mod jsonrpc;
 pub mod middleware;
 mod pathfinder;
 mod pending;
+pub mod response_cache;
+#[cfg(test)]
+mod spec_compliance;
 #[cfg(test)]
 mod test_setup;
+pub mod types;
 pub mod v02;
 pub mod v03;
 pub mod v04;
 pub mod v05;
 
-pub use executor::compose_executor_transaction;
+pub use executor::{
+    compose_executor_transaction,
+    VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY,
+};
+pub use jsonrpc::{MethodFilter, ParamsStrictness};
 pub use pending::PendingData;
 
-use crate::jsonrpc::rpc_handler;
-use crate::jsonrpc::websocket::websocket_handler;
+use crate::jsonrpc::websocket::{new_heads_handler, websocket_handler};
 pub use crate::jsonrpc::websocket::{BlockHeader, TopicBroadcasters};
+use crate::jsonrpc::{openrpc_handler, rpc_handler};
 use crate::v02::types::syncing::Syncing;
 use anyhow::Context;
 use axum::error_handling::HandleErrorLayer;
@@ -37,6 +50,8 @@ use tokio::task::JoinHandle;
 use tower_http::cors::CorsLayer;
 
 const DEFAULT_MAX_CONNECTIONS: usize = 1024;
+// TODO: make this configurable
+const REQUEST_MAX_SIZE: usize = 10 * 1024 * 1024;
 
 pub enum DefaultVersion {
     V03,
@@ -49,6 +64,10 @@ pub struct RpcServer {
     context: RpcContext,
     max_connections: usize,
     cors: Option<CorsLayer>,
+    signer: Option<middleware::signature::ResponseSigner>,
+    authenticator: Option<middleware::auth::Authenticator>,
+    method_filter: MethodFilter,
+    internal_addr: Option<SocketAddr>,
     default_version: DefaultVersion,
 }
 
@@ -59,6 +78,10 @@ impl RpcServer {
             context,
             max_connections: DEFAULT_MAX_CONNECTIONS,
             cors: None,
+            signer: None,
+            authenticator: None,
+            method_filter: MethodFilter::default(),
+            internal_addr: None,
             default_version,
         }
     }
@@ -75,27 +98,79 @@ impl RpcServer {
         }
     }
 
-    /// Starts the HTTP-RPC server.
-    pub fn spawn(self) -> Result<(JoinHandle<anyhow::Result<()>>, SocketAddr), anyhow::Error> {
-        use axum::routing::{get, post};
+    /// Signs responses with the key, see [middleware::signature].
+    pub fn with_response_signing(self, key: ed25519_dalek::SigningKey) -> Self {
+        Self {
+            signer: Some(middleware::signature::ResponseSigner::new(key)),
+            ..self
+        }
+    }
 
-        // TODO: make this configurable
-        const REQUEST_MAX_SIZE: usize = 10 * 1024 * 1024;
-        // TODO: make this configurable
-        const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+    /// Requires requests to be authenticated, see [middleware::auth].
+    pub fn with_authentication(self, authenticator: middleware::auth::Authenticator) -> Self {
+        Self {
+            authenticator: Some(authenticator),
+            ..self
+        }
+    }
 
-        let listener = match std::net::TcpListener::bind(self.addr) {
-            Ok(listener) => listener,
-            Err(e) => return Err(e).context(format!("RPC address {} is already in use.
-    
-            Hint: This usually means you are already running another instance of pathfinder.
-            Hint: If this happens when upgrading, make sure to shut down the first one first.
-            Hint: If you are looking to run two instances of pathfinder, you must configure them with different http rpc addresses.", self.addr)),
-        };
+    /// Serves only the methods allowed by the filter at the main address.
+    pub fn with_method_filter(self, method_filter: MethodFilter) -> Self {
+        Self {
+            method_filter,
+            ..self
+        }
+    }
+
+    /// Additionally serves all methods at the internal address, regardless of the
+    /// [method filter](Self::with_method_filter).
+    pub fn with_internal_address(self, internal_addr: SocketAddr) -> Self {
+        Self {
+            internal_addr: Some(internal_addr),
+            ..self
+        }
+    }
+
+    /// Starts the HTTP-RPC server.
+    ///
+    /// Returns the local address of the main listener.
+    pub fn spawn(self) -> Result<(JoinHandle<anyhow::Result<()>>, SocketAddr), anyhow::Error> {
+        let listener = bind(self.addr)?;
         let addr = listener
             .local_addr()
             .context("Getting local address from listener")?;
         let server = axum::Server::from_tcp(listener).context("Binding server to tcp listener")?;
+        let router = self.router(&self.method_filter);
+
+        let internal_server = match self.internal_addr {
+            Some(internal_addr) => {
+                let listener = bind(internal_addr)?;
+                let server = axum::Server::from_tcp(listener)
+                    .context("Binding internal server to tcp listener")?;
+                let router = self.router(&MethodFilter::default());
+                Some(server.serve(router.into_make_service()))
+            }
+            None => None,
+        };
+
+        let server_handle = tokio::spawn(async move {
+            let server = server.serve(router.into_make_service());
+            match internal_server {
+                Some(internal_server) => tokio::try_join!(server, internal_server).map(|_| ()),
+                None => server.await,
+            }
+            .map_err(Into::into)
+        });
+
+        Ok((server_handle, addr))
+    }
+
+    /// The routes serving the methods allowed by the filter, with all middleware applied.
+    fn router(&self, method_filter: &MethodFilter) -> axum::Router {
+        use axum::routing::{get, post};
+
+        // TODO: make this configurable
+        const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
 
         async fn handle_middleware_errors(err: axum::BoxError) -> (http::StatusCode, String) {
             use http::StatusCode;
@@ -124,7 +199,7 @@ impl RpcServer {
             .layer(DefaultBodyLimit::max(REQUEST_MAX_SIZE))
             .timeout(REQUEST_TIMEOUT)
             .layer(tower_http::trace::TraceLayer::new_for_http())
-            .option_layer(self.cors);
+            .option_layer(self.cors.clone());
 
         /// Returns success for requests with an empty body without reading
         /// the entire body.
@@ -137,10 +212,18 @@ impl RpcServer {
             }
         }
 
-        let v03_routes = v03::register_routes().build(self.context.clone());
-        let v04_routes = v04::register_routes().build(self.context.clone());
-        let v05_routes = v05::register_routes().build(self.context.clone());
-        let pathfinder_routes = pathfinder::register_routes().build(self.context.clone());
+        let v03_routes = v03::register_routes()
+            .filter(method_filter)
+            .build(self.context.clone());
+        let v04_routes = v04::register_routes()
+            .filter(method_filter)
+            .build(self.context.clone());
+        let v05_routes = v05::register_routes()
+            .filter(method_filter)
+            .build(self.context.clone());
+        let pathfinder_routes = pathfinder::register_routes()
+            .filter(method_filter)
+            .build(self.context.clone());
 
         let default_router = match self.default_version {
             DefaultVersion::V03 => v03_routes.clone(),
@@ -152,37 +235,52 @@ impl RpcServer {
             // Also return success for get's with an empty body. These are often
             // used by monitoring bots to check service health.
             .route("/", get(empty_body).post(rpc_handler))
-            .with_state(default_router)
+            .route("/rpc/openrpc.json", get(openrpc_handler))
+            .with_state(default_router.clone())
             .route("/rpc/v0.3", post(rpc_handler))
             .route("/rpc/v0_3", post(rpc_handler))
+            .route("/rpc/v0.3/openrpc.json", get(openrpc_handler))
+            .route("/rpc/v0_3/openrpc.json", get(openrpc_handler))
             .with_state(v03_routes)
             .route("/rpc/v0.4", post(rpc_handler))
             .route("/rpc/v0_4", post(rpc_handler))
+            .route("/rpc/v0.4/openrpc.json", get(openrpc_handler))
+            .route("/rpc/v0_4/openrpc.json", get(openrpc_handler))
             .with_state(v04_routes)
             .route("/rpc/v0.5", post(rpc_handler))
             .route("/rpc/v0_5", post(rpc_handler))
+            .route("/rpc/v0.5/openrpc.json", get(openrpc_handler))
+            .route("/rpc/v0_5/openrpc.json", get(openrpc_handler))
             .with_state(v05_routes)
             .route("/rpc/pathfinder/v0.1", post(rpc_handler))
+            .route("/rpc/pathfinder/v0.1/openrpc.json", get(openrpc_handler))
             .with_state(pathfinder_routes);
 
         let router = if self.context.websocket.is_some() {
-            router.route("/ws", get(websocket_handler))
+            router
+                .route("/ws", get(websocket_handler))
+                .route("/sse/newHeads", get(new_heads_handler))
         } else {
             router
         };
 
-        let router = router
-            .with_state(self.context.websocket.clone().unwrap_or_default())
-            .layer(middleware);
-
-        let server_handle = tokio::spawn(async move {
-            server
-                .serve(router.into_make_service())
-                .await
-                .map_err(Into::into)
-        });
-
-        Ok((server_handle, addr))
+        let router = router.with_state(default_router);
+        let router = match self.signer.clone() {
+            Some(signer) => router.layer(axum::middleware::from_fn_with_state(
+                signer,
+                middleware::signature::sign_response,
+            )),
+            None => router,
+        };
+        // Authentication wraps signing, so that unauthenticated requests are rejected first.
+        let router = match self.authenticator.clone() {
+            Some(authenticator) => router.layer(axum::middleware::from_fn_with_state(
+                authenticator,
+                middleware::auth::authenticate,
+            )),
+            None => router,
+        };
+        router.layer(middleware)
     }
 
     pub fn get_topic_broadcasters(&self) -> Option<&TopicBroadcasters> {
@@ -191,6 +289,18 @@ impl RpcServer {
             .as_ref()
             .map(|websocket| &websocket.broadcasters)
     }
+
+    pub fn get_response_cache(&self) -> &response_cache::ResponseCache {
+        &self.context.response_cache
+    }
+}
+
+fn bind(addr: SocketAddr) -> anyhow::Result<std::net::TcpListener> {
+    std::net::TcpListener::bind(addr).context(format!("RPC address {} is already in use.
+    
+            Hint: This usually means you are already running another instance of pathfinder.
+            Hint: If this happens when upgrading, make sure to shut down the first one first.
+            Hint: If you are looking to run two instances of pathfinder, you must configure them with different http rpc addresses.", addr))
 }
 
 pub struct SyncState {
@@ -210,8 +320,9 @@ pub mod test_utils {
     use pathfinder_common::event::Event;
     use pathfinder_common::{macro_prelude::*, Fee};
     use pathfinder_common::{
-        BlockHeader, BlockNumber, BlockTimestamp, ContractAddress, EntryPoint, EthereumAddress,
-        GasPrice, SierraHash, StarknetVersion, StateUpdate, TransactionIndex, TransactionVersion,
+        BlockHash, BlockHeader, BlockNumber, BlockTimestamp, ClassHash, ContractAddress,
+        ContractNonce, EntryPoint, EthereumAddress, GasPrice, SierraHash, StarknetVersion,
+        StateUpdate, StorageAddress, StorageValue, TransactionIndex, TransactionVersion,
     };
     use pathfinder_crypto::Felt;
     use pathfinder_merkle_tree::StorageCommitmentTree;
@@ -689,6 +800,16 @@ pub mod test_utils {
             timestamp: BlockTimestamp::new_or_panic(1234567),
             transaction_receipts,
             transactions,
+            candidate_transactions: vec![InvokeTransaction::V0(InvokeTransactionV0 {
+                calldata: vec![],
+                sender_address: contract_address_bytes!(b"pending contract addr 0"),
+                entry_point_selector: entry_point_bytes!(b"entry point 0"),
+                entry_point_type: Some(EntryPointType::External),
+                max_fee: Fee::ZERO,
+                signature: vec![],
+                transaction_hash: transaction_hash_bytes!(b"pending candidate"),
+            })
+            .into()],
             starknet_version: StarknetVersion::new(0, 11, 0),
         };
 
@@ -750,6 +871,57 @@ pub mod test_utils {
             number: latest.number + 1,
         }
     }
+
+    /// The state of a forked network with a single contract, which is deployed at
+    /// `contract_address_bytes!(b"remote contract")` with a Cairo class and nonce 7, and stores
+    /// `storage_value_bytes!(b"remote value")` at `storage_address_bytes!(b"remote key")`.
+    pub struct FixedRemoteState;
+
+    impl pathfinder_executor::RemoteState for FixedRemoteState {
+        fn storage_value(
+            &self,
+            contract_address: ContractAddress,
+            key: StorageAddress,
+        ) -> anyhow::Result<StorageValue> {
+            let value = (contract_address == contract_address_bytes!(b"remote contract")
+                && key == storage_address_bytes!(b"remote key"))
+            .then_some(storage_value_bytes!(b"remote value"));
+            Ok(value.unwrap_or_default())
+        }
+
+        fn contract_nonce(
+            &self,
+            contract_address: ContractAddress,
+        ) -> anyhow::Result<ContractNonce> {
+            let nonce = (contract_address == contract_address_bytes!(b"remote contract"))
+                .then_some(contract_nonce!("0x7"));
+            Ok(nonce.unwrap_or_default())
+        }
+
+        fn contract_class_hash(
+            &self,
+            contract_address: ContractAddress,
+        ) -> anyhow::Result<Option<ClassHash>> {
+            Ok(
+                (contract_address == contract_address_bytes!(b"remote contract"))
+                    .then_some(class_hash_bytes!(b"remote class")),
+            )
+        }
+
+        fn class_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok((class_hash == class_hash_bytes!(b"remote class")).then(|| {
+                starknet_gateway_test_fixtures::class_definitions::CONTRACT_DEFINITION.to_vec()
+            }))
+        }
+
+        fn casm_definition(&self, _: ClassHash) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(None)
+        }
+
+        fn block_hash(&self, _: BlockNumber) -> anyhow::Result<Option<BlockHash>> {
+            Ok(None)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -822,6 +994,238 @@ mod tests {
         assert!(!status.is_success());
     }
 
+    #[tokio::test]
+    async fn methods_are_filtered_per_listener() {
+        // Reserve a free port for the internal listener.
+        let internal_addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let filter = MethodFilter::default()
+            .allow(vec!["read".to_owned()])
+            .deny(vec!["starknet_syncing".to_owned()]);
+        let (_jh, addr) = RpcServer::new(
+            "127.0.0.1:0".parse().unwrap(),
+            RpcContext::for_tests(),
+            DefaultVersion::V05,
+        )
+        .with_method_filter(filter)
+        .with_internal_address(internal_addr)
+        .spawn()
+        .unwrap();
+
+        let error_code = |addr: SocketAddr, method: &'static str| async move {
+            let response: serde_json::Value = reqwest::Client::new()
+                .post(format!("http://{addr}/rpc/v0.5"))
+                .json(&json!({"jsonrpc": "2.0", "method": method, "id": 0}))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            response["error"]["code"].as_i64()
+        };
+
+        const METHOD_NOT_FOUND: Option<i64> = Some(-32601);
+        assert_eq!(error_code(addr, "starknet_chainId").await, None);
+        assert_eq!(error_code(addr, "starknet_syncing").await, METHOD_NOT_FOUND);
+        assert_eq!(
+            error_code(addr, "starknet_addInvokeTransaction").await,
+            METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            error_code(addr, "starknet_traceTransaction").await,
+            METHOD_NOT_FOUND
+        );
+
+        // The internal listener serves all methods.
+        assert_eq!(error_code(internal_addr, "starknet_chainId").await, None);
+        assert_ne!(
+            error_code(internal_addr, "starknet_addInvokeTransaction").await,
+            METHOD_NOT_FOUND
+        );
+
+        // Filtered methods are not described by the OpenRPC document.
+        let document: serde_json::Value =
+            reqwest::get(format!("http://{addr}/rpc/v0.5/openrpc.json"))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+        let methods = document["methods"].as_array().unwrap();
+        assert!(methods.iter().any(|m| m["name"] == "starknet_chainId"));
+        assert!(!methods.iter().any(|m| m["name"] == "starknet_syncing"));
+    }
+
+    fn declare_transaction() -> serde_json::Value {
+        json!({
+            "type": "DECLARE",
+            "version": "0x2",
+            "max_fee": "0x1",
+            "signature": [],
+            "nonce": "0x0",
+            "compiled_class_hash": "0x1",
+            "sender_address": "0x1",
+            "contract_class": {
+                "sierra_program": [],
+                "contract_class_version": "0.1.0",
+                "entry_points_by_type": {"CONSTRUCTOR": [], "EXTERNAL": [], "L1_HANDLER": []},
+                "abi": "",
+            },
+        })
+    }
+
+    fn deploy_account_transaction() -> serde_json::Value {
+        json!({
+            "type": "DEPLOY_ACCOUNT",
+            "version": "0x1",
+            "max_fee": "0x1",
+            "signature": [],
+            "nonce": "0x0",
+            "contract_address_salt": "0x1",
+            "constructor_calldata": [],
+            "class_hash": "0x1",
+        })
+    }
+
+    fn invoke_transaction() -> serde_json::Value {
+        json!({
+            "type": "INVOKE",
+            "version": "0x1",
+            "max_fee": "0x1",
+            "signature": [],
+            "nonce": "0x0",
+            "sender_address": "0x1",
+            "calldata": [],
+        })
+    }
+
+    fn misspelled_function_call() -> serde_json::Value {
+        json!({
+            "contract_address": "0x1",
+            "entry_point_selector": "0x1",
+            "calldata": [],
+            "calldta": [],
+        })
+    }
+
+    /// The input types leave rejecting unknown fields to [ParamsStrictness](jsonrpc::ParamsStrictness)
+    /// instead of `deny_unknown_fields`, which would reject them on lenient servers too. This
+    /// checks that a misspelled field is rejected for each of them, i.e. that none is buffered
+    /// by serde, where the strictness can't see its fields.
+    #[rstest::rstest]
+    #[case::v03_add_declare_transaction("/rpc/v0.3", "starknet_addDeclareTransaction", json!({"declare_transaction": declare_transaction(), "tokn": ""}), "tokn")]
+    #[case::v03_add_deploy_account_transaction("/rpc/v0.3", "starknet_addDeployAccountTransaction", json!({"deploy_account_transaction": deploy_account_transaction(), "transaction": 1}), "transaction")]
+    #[case::v03_add_invoke_transaction("/rpc/v0.3", "starknet_addInvokeTransaction", json!({"invoke_transaction": invoke_transaction(), "transaction": 1}), "transaction")]
+    #[case::v03_get_block("/rpc/v0.3", "starknet_getBlockWithTxHashes", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v03_get_block_transaction_count("/rpc/v0.3", "starknet_getBlockTransactionCount", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v03_get_class("/rpc/v0.3", "starknet_getClass", json!({"block_id": "latest", "class_hash": "0x1", "classhash": 1}), "classhash")]
+    #[case::v03_get_class_at("/rpc/v0.3", "starknet_getClassAt", json!({"block_id": "latest", "contract_address": "0x1", "address": 1}), "address")]
+    #[case::v03_get_class_hash_at("/rpc/v0.3", "starknet_getClassHashAt", json!({"block_id": "latest", "contract_address": "0x1", "address": 1}), "address")]
+    #[case::v03_get_nonce("/rpc/v0.3", "starknet_getNonce", json!({"block_id": "latest", "contract_address": "0x1", "address": 1}), "address")]
+    #[case::v03_get_storage_at("/rpc/v0.3", "starknet_getStorageAt", json!({"block_id": "latest", "contract_address": "0x1", "key": "0x1", "keys": 1}), "keys")]
+    #[case::v03_get_transaction_by_block_id_and_index("/rpc/v0.3", "starknet_getTransactionByBlockIdAndIndex", json!({"block_id": "latest", "index": 0, "idx": 0}), "idx")]
+    #[case::v03_get_transaction_by_hash("/rpc/v0.3", "starknet_getTransactionByHash", json!({"transaction_hash": "0x1", "hash": 1}), "hash")]
+    #[case::v03_get_transaction_receipt("/rpc/v0.3", "starknet_getTransactionReceipt", json!({"transaction_hash": "0x1", "hash": 1}), "hash")]
+    #[case::v03_estimate_message_fee("/rpc/v0.3", "starknet_estimateMessageFee", json!({"message": misspelled_function_call(), "sender_address": "0x0000000000000000000000000000000000000000", "block_id": "latest"}), "message.calldta")]
+    #[case::v03_get_events("/rpc/v0.3", "starknet_getEvents", json!({"filter": {"chunk_size": 1, "chunksize": 1}}), "filter.chunksize")]
+    #[case::v03_get_state_update("/rpc/v0.3", "starknet_getStateUpdate", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v03_simulate_transaction("/rpc/v0.3", "starknet_simulateTransaction", json!({"block_id": "latest", "transactions": [], "simulation_flags": [], "flags": 1}), "flags")]
+    #[case::v04_add_declare_transaction("/rpc/v0.4", "starknet_addDeclareTransaction", json!({"declare_transaction": declare_transaction(), "tokn": ""}), "tokn")]
+    #[case::v04_add_deploy_account_transaction("/rpc/v0.4", "starknet_addDeployAccountTransaction", json!({"deploy_account_transaction": deploy_account_transaction(), "transaction": 1}), "transaction")]
+    #[case::v04_add_invoke_transaction("/rpc/v0.4", "starknet_addInvokeTransaction", json!({"invoke_transaction": invoke_transaction(), "transaction": 1}), "transaction")]
+    #[case::v04_get_block_with_txs("/rpc/v0.4", "starknet_getBlockWithTxs", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v04_get_transaction_receipt("/rpc/v0.4", "starknet_getTransactionReceipt", json!({"transaction_hash": "0x1", "hash": 1}), "hash")]
+    #[case::v04_simulate_transactions("/rpc/v0.4", "starknet_simulateTransactions", json!({"block_id": "latest", "transactions": [], "simulation_flags": [], "flags": 1}), "flags")]
+    #[case::v04_trace_block_transactions("/rpc/v0.4", "starknet_traceBlockTransactions", json!({"block_hash": "0x1", "hash": 1}), "hash")]
+    #[case::v04_trace_transaction("/rpc/v0.4", "starknet_traceTransaction", json!({"transaction_hash": "0x1", "hash": 1}), "hash")]
+    #[case::v05_call("/rpc/v0.5", "starknet_call", json!({"request": misspelled_function_call(), "block_id": "latest"}), "request.calldta")]
+    #[case::v05_estimate_fee("/rpc/v0.5", "starknet_estimateFee", json!({"request": [], "block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v05_estimate_message_fee("/rpc/v0.5", "starknet_estimateMessageFee", json!({"message": {"from_address": "0x0000000000000000000000000000000000000000", "to_address": "0x1", "entry_point_selector": "0x1", "payload": []}, "block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v05_get_block_with_tx_hashes("/rpc/v0.5", "starknet_getBlockWithTxHashes", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v05_get_block_with_txs("/rpc/v0.5", "starknet_getBlockWithTxs", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v05_get_transaction_receipt("/rpc/v0.5", "starknet_getTransactionReceipt", json!({"transaction_hash": "0x1", "hash": 1}), "hash")]
+    #[case::v05_simulate_transactions("/rpc/v0.5", "starknet_simulateTransactions", json!({"block_id": "latest", "transactions": [], "simulation_flags": [], "flags": 1}), "flags")]
+    #[case::v05_trace_block_transactions("/rpc/v0.5", "starknet_traceBlockTransactions", json!({"block_id": "latest", "blockid": 1}), "blockid")]
+    #[case::v05_trace_transaction("/rpc/v0.5", "starknet_traceTransaction", json!({"transaction_hash": "0x1", "hash": 1}), "hash")]
+    #[tokio::test]
+    async fn unknown_params_fields(
+        #[case] route: &'static str,
+        #[case] method: &'static str,
+        #[case] params: serde_json::Value,
+        #[case] field: &'static str,
+    ) {
+        let (_jh, addr) = RpcServer::new(
+            "127.0.0.1:0".parse().unwrap(),
+            RpcContext::for_tests(),
+            DefaultVersion::V05,
+        )
+        .spawn()
+        .unwrap();
+
+        let response: serde_json::Value = reqwest::Client::new()
+            .post(format!("http://{addr}{route}"))
+            .json(&json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 0}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let error = &response["error"];
+        assert_eq!(error["code"], json!(-32602), "{error}");
+        assert_eq!(
+            error["data"]["reason"],
+            json!(format!("unknown field `{field}`"))
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::root("/rpc/openrpc.json", "v0.4")]
+    #[case::v03("/rpc/v0.3/openrpc.json", "v0.3")]
+    #[case::v04("/rpc/v0_4/openrpc.json", "v0.4")]
+    #[case::v05("/rpc/v0.5/openrpc.json", "v0.5")]
+    #[case::pathfinder("/rpc/pathfinder/v0.1/openrpc.json", "v0.1")]
+    #[tokio::test]
+    async fn openrpc_document(#[case] route: &'static str, #[case] version: &'static str) {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let context = RpcContext::for_tests();
+        let (_jh, addr) = RpcServer::new(addr, context, DefaultVersion::V04)
+            .spawn()
+            .unwrap();
+
+        let document: serde_json::Value = reqwest::get(format!("http://{addr}{route}"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(document["info"]["version"], version);
+
+        let method = document["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|method| method["name"] == "pathfinder_getProof")
+            .unwrap();
+        let felt = json!({
+            "type": "string",
+            "pattern": "^0x(0|[a-fA-F1-9]{1}[a-fA-F0-9]{0,62})$",
+        });
+        assert_eq!(
+            method["params"],
+            json!([
+                { "name": "block_id", "schema": {} },
+                { "name": "contract_address", "schema": felt },
+                { "name": "keys", "schema": { "type": "array", "items": felt } },
+            ])
+        );
+    }
+
     #[rustfmt::skip]
     #[rstest::rstest]
     #[case::root_api  ("/", "v04/starknet_api_openrpc.json",       &[])]
@@ -1 +1,4 @@
+pub mod auth;
 pub mod cors;
+pub mod load_shedding;
+pub mod signature;
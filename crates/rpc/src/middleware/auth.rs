@@ -0,0 +1,243 @@
+//! Authenticates requests using bearer tokens, which are either signed JWTs or static tokens.
+//!
+//! JWTs must be signed with the configured secret using HS256, and must have an `exp` claim. A
+//! `methods` claim restricts the methods the token may call. Static tokens are loaded from a file
+//! mapping each token to the methods it may call, see [load_tokens].
+//!
+//! Method patterns are the same as for [MethodFilter](crate::MethodFilter), e.g. method names,
+//! prefixes ending in `*` such as `starknet_*`, or method groups such as `read`.
+//! Methods which are not allowed fail with [ApplicationError::MethodNotAllowed]. Health checks,
+//! i.e. `GET /`, are not authenticated.
+//!
+//! [ApplicationError::MethodNotAllowed]: crate::error::ApplicationError::MethodNotAllowed
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::State;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::{Method, Request, StatusCode};
+use sha2::{Digest, Sha256};
+
+/// The methods an authenticated client may call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodAllowlist(Arc<[String]>);
+
+impl MethodAllowlist {
+    /// Allows all methods.
+    pub fn all() -> Self {
+        Self(Arc::new(["*".to_owned()]))
+    }
+
+    pub(crate) fn allows(&self, method: &str) -> bool {
+        self.0
+            .iter()
+            .any(|pattern| crate::jsonrpc::matches_method(pattern, method))
+    }
+}
+
+impl From<Vec<String>> for MethodAllowlist {
+    fn from(patterns: Vec<String>) -> Self {
+        Self(patterns.into())
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Claims {
+    methods: Option<Vec<String>>,
+}
+
+/// Validates bearer tokens, see the [module documentation](self).
+#[derive(Clone, Default)]
+pub struct Authenticator {
+    jwt_secret: Option<Arc<jsonwebtoken::DecodingKey>>,
+    /// Static tokens are looked up by their hash, so that the lookup's timing doesn't reveal
+    /// parts of the tokens.
+    tokens: Arc<HashMap<[u8; 32], MethodAllowlist>>,
+}
+
+impl Authenticator {
+    pub fn with_jwt_secret(self, secret: &[u8]) -> Self {
+        Self {
+            jwt_secret: Some(Arc::new(jsonwebtoken::DecodingKey::from_secret(secret))),
+            ..self
+        }
+    }
+
+    pub fn with_tokens(self, tokens: HashMap<String, MethodAllowlist>) -> Self {
+        let tokens = tokens
+            .into_iter()
+            .map(|(token, allowlist)| (hash(&token), allowlist))
+            .collect();
+
+        Self {
+            tokens: Arc::new(tokens),
+            ..self
+        }
+    }
+
+    /// The methods the token may call, or [None] if the token is not valid.
+    fn authenticate(&self, token: &str) -> Option<MethodAllowlist> {
+        if let Some(allowlist) = self.tokens.get(&hash(token)) {
+            return Some(allowlist.clone());
+        }
+
+        let secret = self.jwt_secret.as_ref()?;
+        let validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
+        match jsonwebtoken::decode::<Claims>(token, secret, &validation) {
+            Ok(token) => Some(
+                token
+                    .claims
+                    .methods
+                    .map(MethodAllowlist::from)
+                    .unwrap_or_else(MethodAllowlist::all),
+            ),
+            Err(error) => {
+                tracing::debug!(%error, "Invalid JWT");
+                None
+            }
+        }
+    }
+}
+
+fn hash(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Loads static tokens from a JSON file, which maps each token to a list of the method patterns
+/// it may call, e.g. `{"<token>": ["starknet_*", "pathfinder_getProof"]}`.
+pub fn load_tokens(path: &Path) -> anyhow::Result<HashMap<String, MethodAllowlist>> {
+    let tokens = std::fs::read(path).context("Reading tokens file")?;
+    let tokens: HashMap<String, Vec<String>> =
+        serde_json::from_slice(&tokens).context("Parsing tokens file")?;
+
+    Ok(tokens
+        .into_iter()
+        .map(|(token, methods)| (token, methods.into()))
+        .collect())
+}
+
+pub(crate) async fn authenticate<B>(
+    State(authenticator): State<Authenticator>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if request.method() == Method::GET && request.uri().path() == "/" {
+        return next.run(request).await;
+    }
+
+    let allowlist = request
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| authenticator.authenticate(token.trim()));
+    let Some(allowlist) = allowlist else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(http::header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    };
+
+    request.extensions_mut().insert(allowlist);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{context::RpcContext, DefaultVersion, RpcServer};
+
+    #[test]
+    fn allowlist() {
+        let allowlist = MethodAllowlist::from(vec![
+            "starknet_*".to_owned(),
+            "pathfinder_version".to_owned(),
+        ]);
+
+        assert!(allowlist.allows("starknet_chainId"));
+        assert!(allowlist.allows("pathfinder_version"));
+        assert!(!allowlist.allows("pathfinder_getProof"));
+        assert!(MethodAllowlist::all().allows("pathfinder_getProof"));
+    }
+
+    #[tokio::test]
+    async fn requests_are_authenticated() {
+        let secret = b"secret";
+        let exp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let jwt = jsonwebtoken::encode(
+            &Default::default(),
+            &serde_json::json!({"exp": exp, "methods": ["starknet_chainId"]}),
+            &jsonwebtoken::EncodingKey::from_secret(secret),
+        )
+        .unwrap();
+
+        let authenticator = Authenticator::default()
+            .with_jwt_secret(secret)
+            .with_tokens(HashMap::from([(
+                "static".to_owned(),
+                MethodAllowlist::from(vec!["starknet_chain*".to_owned()]),
+            )]));
+        let (_server_handle, address) = RpcServer::new(
+            "127.0.0.1:0".parse().unwrap(),
+            RpcContext::for_tests(),
+            DefaultVersion::V05,
+        )
+        .with_authentication(authenticator)
+        .spawn()
+        .unwrap();
+
+        let call = |token: Option<&str>, method: &str| {
+            let request = reqwest::Client::new()
+                .post(format!("http://{address}/rpc/v0.5"))
+                .json(&serde_json::json!({"jsonrpc": "2.0", "method": method, "id": 0}));
+            let request = match token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            };
+            async move {
+                let response = request.send().await.unwrap();
+                let status = response.status();
+                let body = response.json::<serde_json::Value>().await.ok();
+                (status, body.map(|body| body["error"]["code"].clone()))
+            }
+        };
+
+        let (status, _) = call(None, "starknet_chainId").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call(Some("wrong"), "starknet_chainId").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let not_allowed = serde_json::json!(10004);
+        assert_eq!(
+            call(Some("static"), "starknet_chainId").await,
+            (StatusCode::OK, Some(serde_json::Value::Null))
+        );
+        assert_eq!(
+            call(Some("static"), "starknet_blockNumber").await,
+            (StatusCode::OK, Some(not_allowed.clone()))
+        );
+        assert_eq!(
+            call(Some(&jwt), "starknet_chainId").await,
+            (StatusCode::OK, Some(serde_json::Value::Null))
+        );
+        assert_eq!(
+            call(Some(&jwt), "starknet_blockNumber").await,
+            (StatusCode::OK, Some(not_allowed))
+        );
+
+        // Health checks are not authenticated.
+        let status = reqwest::get(format!("http://{address}/"))
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -14,7 +14,16 @@ pub fn with_allowed_origins(allowed_origins: AllowedOrigins) -> CorsLayer {
     CorsLayer::new()
         .allow_methods([hyper::Method::POST])
         .allow_origin(allowed_origins)
-        .allow_headers([hyper::header::CONTENT_TYPE])
+        .allow_headers([
+            hyper::header::CONTENT_TYPE,
+            hyper::header::AUTHORIZATION,
+            hyper::header::IF_NONE_MATCH,
+        ])
+        .expose_headers([
+            hyper::header::ETAG,
+            http::HeaderName::from_static(super::signature::SIGNATURE_HEADER),
+            http::HeaderName::from_static(super::signature::PUBLIC_KEY_HEADER),
+        ])
 }
 
 #[cfg(test)]
@@ -57,7 +66,9 @@ mod tests {
 
             assert_eq!(
                 h.get("access-control-allow-headers"),
-                allowed.and(Some(&HeaderValue::from_static("content-type"))),
+                allowed.and(Some(&HeaderValue::from_static(
+                    "content-type,authorization,if-none-match"
+                ))),
                 "line: {line}"
             );
             assert_eq!(
@@ -0,0 +1,158 @@
+//! Rejects low-priority methods while the node is overloaded, so that head queries and writes
+//! stay responsive during load spikes.
+//!
+//! The [LoadShedder] tracks the number of requests in flight and the number of requests running
+//! the executor, which queue up on the blocking thread pool. Once either reaches its
+//! [threshold](LoadSheddingConfig), traces and simulations are rejected with the retryable
+//! [NodeBusy](crate::error::ApplicationError::NodeBusy) error until the load drops again.
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::jsonrpc::matches_method;
+
+/// The methods which run the executor.
+const EXECUTOR_METHODS: &[&str] = &[
+    "starknet_call",
+    "starknet_estimateFee",
+    "starknet_estimateMessageFee",
+    "starknet_simulateTransaction",
+    "starknet_simulateTransactions",
+    "starknet_traceTransaction",
+    "starknet_traceBlockTransactions",
+];
+
+/// The thresholds above which low-priority methods are rejected. Thresholds which are not set
+/// are never exceeded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadSheddingConfig {
+    /// The number of requests in flight.
+    pub max_in_flight: Option<NonZeroUsize>,
+    /// The number of requests running the executor.
+    pub max_executions: Option<NonZeroUsize>,
+}
+
+/// Tracks the load of the RPC server, see the [module](self) documentation.
+#[derive(Clone, Default)]
+pub struct LoadShedder(Arc<Shared>);
+
+#[derive(Default)]
+struct Shared {
+    config: LoadSheddingConfig,
+    in_flight: AtomicUsize,
+    executions: AtomicUsize,
+}
+
+/// Accounts for an admitted request until it is dropped.
+pub(crate) struct LoadGuard {
+    shared: Arc<Shared>,
+    execution: bool,
+}
+
+impl Drop for LoadGuard {
+    fn drop(&mut self) {
+        self.shared.in_flight.fetch_sub(1, Ordering::Relaxed);
+        if self.execution {
+            self.shared.executions.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl LoadShedder {
+    pub fn new(config: LoadSheddingConfig) -> Self {
+        Self(Arc::new(Shared {
+            config,
+            ..Default::default()
+        }))
+    }
+
+    /// Admits a request for the method, or returns [None] if it is shed.
+    pub(crate) fn admit(&self, method: &str) -> Option<LoadGuard> {
+        let shared = &self.0;
+
+        if is_low_priority(method) && self.overloaded() {
+            return None;
+        }
+
+        let execution = EXECUTOR_METHODS.contains(&method);
+        shared.in_flight.fetch_add(1, Ordering::Relaxed);
+        if execution {
+            shared.executions.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Some(LoadGuard {
+            shared: shared.clone(),
+            execution,
+        })
+    }
+
+    fn overloaded(&self) -> bool {
+        let exceeded = |limit: Option<NonZeroUsize>, count: &AtomicUsize| {
+            limit.is_some_and(|limit| count.load(Ordering::Relaxed) >= limit.get())
+        };
+
+        exceeded(self.0.config.max_in_flight, &self.0.in_flight)
+            || exceeded(self.0.config.max_executions, &self.0.executions)
+    }
+}
+
+/// Traces and simulations, which are expensive and not needed to follow the chain.
+fn is_low_priority(method: &str) -> bool {
+    matches_method("trace", method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let shedder = LoadShedder::default();
+        let _guards = (0..100)
+            .map(|_| shedder.admit("starknet_traceTransaction").unwrap())
+            .collect::<Vec<_>>();
+    }
+
+    #[test]
+    fn in_flight() {
+        let shedder = LoadShedder::new(LoadSheddingConfig {
+            max_in_flight: NonZeroUsize::new(2),
+            max_executions: None,
+        });
+
+        let read = shedder.admit("starknet_getBlockWithTxs").unwrap();
+        let _trace = shedder.admit("starknet_traceTransaction").unwrap();
+
+        assert!(shedder.admit("starknet_traceTransaction").is_none());
+        assert!(shedder.admit("starknet_simulateTransactions").is_none());
+        // Other methods are never shed.
+        let others = [
+            "starknet_blockNumber",
+            "starknet_addInvokeTransaction",
+            "starknet_call",
+        ]
+        .map(|method| shedder.admit(method).unwrap());
+
+        drop(read);
+        drop(others);
+        shedder.admit("starknet_traceTransaction").unwrap();
+    }
+
+    #[test]
+    fn executions() {
+        let shedder = LoadShedder::new(LoadSheddingConfig {
+            max_in_flight: None,
+            max_executions: NonZeroUsize::new(1),
+        });
+
+        // Not an execution.
+        let _read = shedder.admit("starknet_getStateUpdate").unwrap();
+
+        let call = shedder.admit("starknet_call").unwrap();
+        assert!(shedder.admit("starknet_simulateTransaction").is_none());
+        assert!(shedder.admit("starknet_traceBlockTransactions").is_none());
+
+        drop(call);
+        shedder.admit("starknet_traceBlockTransactions").unwrap();
+    }
+}
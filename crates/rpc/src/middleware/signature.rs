@@ -0,0 +1,311 @@
+//! Signs responses with the node's ed25519 key, so that clients can verify that a response came
+//! from a specific trusted node.
+//!
+//! The signature binds the response to the request it answers, so that a signed response can't be
+//! replayed as the answer to a different request. The signed message is the 64 bytes
+//!
+//! ```text
+//! SHA-256(request body) || SHA-256(response body)
+//! ```
+//!
+//! The signature is sent hex encoded in the [SIGNATURE_HEADER], together with the hex encoded
+//! public key in the [PUBLIC_KEY_HEADER] so that clients can tell which key to verify against
+//! while keys are rotated. Only JSON responses are signed, streaming responses such as websockets
+//! and server-sent events are not.
+//!
+//! Large JSON responses are streamed to the client, and come with the [BodyDigest] of their body.
+//! They are signed using the digest and passed on as they are, so that they are never buffered.
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::body::{Body, Bytes, HttpBody};
+use axum::extract::State;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use ed25519_dalek::{Signer, SigningKey};
+use http::{HeaderValue, Request, StatusCode};
+use sha2::{Digest, Sha256};
+use tokio::sync::oneshot;
+
+pub const SIGNATURE_HEADER: &str = "pathfinder-signature";
+pub const PUBLIC_KEY_HEADER: &str = "pathfinder-public-key";
+
+tokio::task_local! {
+    /// Set while handling a request whose response is signed.
+    static SIGNING: ();
+}
+
+/// The SHA-256 digest of a streamed response body, which is attached to the response as an
+/// extension so that the body can be signed without buffering it.
+pub(crate) struct BodyDigest(oneshot::Receiver<[u8; 32]>);
+
+impl BodyDigest {
+    /// A channel for the digest of a streamed response body, or [None] if the response is not
+    /// going to be signed.
+    pub(crate) fn channel() -> Option<(oneshot::Sender<[u8; 32]>, Self)> {
+        SIGNING.try_with(|_| ()).ok()?;
+        let (sender, receiver) = oneshot::channel();
+        Some((sender, Self(receiver)))
+    }
+}
+
+/// Signs responses using a [key](SigningKey), see the [module documentation](self).
+#[derive(Clone)]
+pub struct ResponseSigner {
+    key: Arc<SigningKey>,
+    public_key: HeaderValue,
+}
+
+impl ResponseSigner {
+    pub fn new(key: SigningKey) -> Self {
+        let public_key = HeaderValue::from_str(&public_key_hex(&key))
+            .expect("Hex strings are valid header values");
+
+        Self {
+            key: Arc::new(key),
+            public_key,
+        }
+    }
+
+    /// The hex encoded signature of the response to the request, given the digests of their
+    /// bodies, see the [module documentation](self).
+    fn sign(&self, request_digest: [u8; 32], response_digest: [u8; 32]) -> HeaderValue {
+        let signature = self.key.sign(&message(request_digest, response_digest));
+        HeaderValue::from_str(&hex::encode(signature.to_bytes()))
+            .expect("Hex strings are valid header values")
+    }
+}
+
+/// The message which is signed for a response, see the [module documentation](self).
+pub fn signed_message(request_body: &[u8], response_body: &[u8]) -> [u8; 64] {
+    message(
+        Sha256::digest(request_body).into(),
+        Sha256::digest(response_body).into(),
+    )
+}
+
+fn message(request_digest: [u8; 32], response_digest: [u8; 32]) -> [u8; 64] {
+    let mut message = [0u8; 64];
+    message[..32].copy_from_slice(&request_digest);
+    message[32..].copy_from_slice(&response_digest);
+    message
+}
+
+/// Generates a new random signing key.
+pub fn generate_key() -> SigningKey {
+    use rand::Rng;
+    SigningKey::from_bytes(&rand::rngs::OsRng.gen())
+}
+
+/// The hex encoded public key of the signing key.
+pub fn public_key_hex(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().as_bytes())
+}
+
+/// Reads a signing key written by [write_key].
+pub fn read_key(path: &Path) -> anyhow::Result<SigningKey> {
+    let key = std::fs::read_to_string(path).context("Reading key file")?;
+    let key = hex::decode(key.trim()).context("Decoding key")?;
+    let key = key
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("The key must be 32 bytes long"))?;
+
+    Ok(SigningKey::from_bytes(&key))
+}
+
+/// Writes the signing key hex encoded to a new file, which only the current user can read.
+pub fn write_key(path: &Path, key: &SigningKey) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).context("Creating key file")?;
+    writeln!(file, "{}", hex::encode(key.to_bytes())).context("Writing key file")?;
+    file.sync_all().context("Syncing key file")
+}
+
+/// Reads the body, failing once it exceeds `limit` bytes.
+async fn read_body(mut body: Body, limit: usize) -> Result<Bytes, StatusCode> {
+    let mut buffer = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if buffer.len() + chunk.len() > limit {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+
+    Ok(buffer.into())
+}
+
+pub(crate) async fn sign_response(
+    State(signer): State<ResponseSigner>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    // The request body is read here so that the response can be bound to it.
+    let (request_parts, request_body) = request.into_parts();
+    let request_body = match read_body(request_body, crate::REQUEST_MAX_SIZE).await {
+        Ok(body) => body,
+        Err(status) => return status.into_response(),
+    };
+    let request = Request::from_parts(request_parts, Body::from(request_body.clone()));
+
+    let response = SIGNING.scope((), next.run(request)).await;
+
+    let is_json = response
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type.as_bytes().starts_with(b"application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let (response_digest, body) = match parts.extensions.remove::<BodyDigest>() {
+        Some(BodyDigest(digest)) => match digest.await {
+            Ok(digest) => (digest, body),
+            Err(_) => {
+                tracing::warn!("Computing streamed response digest for signing failed");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        },
+        None => match hyper::body::to_bytes(body).await {
+            Ok(body) => (
+                Sha256::digest(&body).into(),
+                axum::body::boxed(axum::body::Full::new(body)),
+            ),
+            Err(error) => {
+                tracing::warn!(%error, "Reading response body for signing failed");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        },
+    };
+
+    let request_digest = Sha256::digest(&request_body).into();
+    parts.headers.insert(
+        SIGNATURE_HEADER,
+        signer.sign(request_digest, response_digest),
+    );
+    parts
+        .headers
+        .insert(PUBLIC_KEY_HEADER, signer.public_key.clone());
+
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    use super::*;
+    use crate::{context::RpcContext, DefaultVersion, RpcServer};
+
+    #[test]
+    fn key_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signing.key");
+        let key = generate_key();
+
+        write_key(&path, &key).unwrap();
+        assert_eq!(read_key(&path).unwrap().to_bytes(), key.to_bytes());
+
+        // Existing keys are never overwritten.
+        write_key(&path, &generate_key()).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn responses_are_signed() {
+        let key = generate_key();
+        let verifying_key = key.verifying_key();
+
+        let context = RpcContext::for_tests();
+        let (_server_handle, address) =
+            RpcServer::new("127.0.0.1:0".parse().unwrap(), context, DefaultVersion::V05)
+                .with_response_signing(key)
+                .spawn()
+                .unwrap();
+
+        let request = serde_json::to_vec(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "starknet_chainId",
+            "id": 0
+        }))
+        .unwrap();
+        let response = reqwest::Client::new()
+            .post(format!("http://{address}/rpc/v0.5"))
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(request.clone())
+            .send()
+            .await
+            .unwrap();
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await.unwrap();
+
+        let public_key = hex::decode(headers.get(PUBLIC_KEY_HEADER).unwrap()).unwrap();
+        assert_eq!(public_key, verifying_key.as_bytes());
+
+        let signature = hex::decode(headers.get(SIGNATURE_HEADER).unwrap()).unwrap();
+        let signature = Signature::from_slice(&signature).unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public_key.try_into().unwrap()).unwrap();
+        verifying_key
+            .verify(&signed_message(&request, &body), &signature)
+            .unwrap();
+
+        // The signature does not hold for a different request.
+        verifying_key
+            .verify(&signed_message(b"{}", &body), &signature)
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn streamed_responses_are_signed_without_buffering() {
+        use crate::jsonrpc::{RequestId, RpcResponse};
+        use tower::ServiceExt;
+
+        async fn large_result() -> impl IntoResponse {
+            let result = serde_json::value::to_raw_value(&"a".repeat(1024 * 1024)).unwrap();
+            let response = RpcResponse {
+                output: Ok(result),
+                id: RequestId::Number(1),
+            };
+            ([(http::header::CONTENT_TYPE, "application/json")], response)
+        }
+
+        let key = generate_key();
+        let verifying_key = key.verifying_key();
+        let router = axum::Router::new()
+            .route("/", axum::routing::post(large_result))
+            .layer(axum::middleware::from_fn_with_state(
+                ResponseSigner::new(key),
+                sign_response,
+            ));
+
+        let request = b"{}".to_vec();
+        let response = router
+            .oneshot(
+                Request::post("/")
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(request.clone()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let (parts, body) = response.into_parts();
+        // The body is still streamed.
+        assert_eq!(body.size_hint().exact(), None);
+        let body = hyper::body::to_bytes(body).await.unwrap();
+
+        let signature = hex::decode(parts.headers.get(SIGNATURE_HEADER).unwrap()).unwrap();
+        let signature = Signature::from_slice(&signature).unwrap();
+        verifying_key
+            .verify(&signed_message(&request, &body), &signature)
+            .unwrap();
+    }
+}
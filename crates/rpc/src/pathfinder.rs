@@ -5,7 +5,17 @@ pub(crate) mod methods;
 #[rustfmt::skip]
 pub fn register_routes() -> RpcRouterBuilder {
     RpcRouter::builder("v0.1")
-        .register("pathfinder_version",              || { pathfinder_common::consts::VERGEN_GIT_DESCRIBE })
-        .register("pathfinder_getProof",             methods::get_proof)
-        .register("pathfinder_getTransactionStatus", methods::get_transaction_status)
+        .register("pathfinder_version",                       || { pathfinder_common::consts::VERGEN_GIT_DESCRIBE })
+        .register("pathfinder_getProof",                      methods::get_proof)
+        .register("pathfinder_getTransactionStatus",          methods::get_transaction_status)
+        .register("pathfinder_getSubmittedTransactionStatus", methods::get_submitted_transaction_status)
+        .register("pathfinder_getClassDeclarationBlock",      methods::get_class_declaration_block)
+        .register("pathfinder_getContractDeployment",         methods::get_contract_deployment)
+        .register("pathfinder_prepareDeclare",                methods::prepare_declare)
+        .register("pathfinder_getMessagesToL1",               methods::get_messages_to_l1)
+        .register("pathfinder_getChainStats",                 methods::get_chain_stats)
+        .register("pathfinder_getTransactionsBySender",       methods::get_transactions_by_sender)
+        .register("pathfinder_getNonceHistory",               methods::get_nonce_history)
+        .register("pathfinder_getFirstBlockWithNonce",        methods::get_first_block_with_nonce)
+        .register("pathfinder_getBlockSyncTimings",           methods::get_block_sync_timings)
 }
@@ -1,5 +1,28 @@
-mod get_proof;
-mod get_transaction_status;
+pub(crate) mod get_block_sync_timings;
+pub(crate) mod get_chain_stats;
+pub(crate) mod get_class_declaration_block;
+pub(crate) mod get_contract_deployment;
+mod get_first_block_with_nonce;
+pub(crate) mod get_messages_to_l1;
+pub(crate) mod get_nonce_history;
+pub(crate) mod get_proof;
+pub(crate) mod get_submitted_transaction_status;
+pub(crate) mod get_transaction_status;
+pub(crate) mod get_transactions_by_sender;
+mod pagination;
+pub(crate) mod prepare_declare;
 
+pub(crate) use get_block_sync_timings::get_block_sync_timings;
+pub(crate) use get_chain_stats::get_chain_stats;
+pub(crate) use get_class_declaration_block::get_class_declaration_block;
+pub(crate) use get_contract_deployment::get_contract_deployment;
+pub(crate) use get_first_block_with_nonce::get_first_block_with_nonce;
+pub(crate) use get_messages_to_l1::get_messages_to_l1;
+pub(crate) use get_nonce_history::get_nonce_history;
 pub(crate) use get_proof::get_proof;
+pub(crate) use get_submitted_transaction_status::{
+    get_submitted_transaction_status, record_submitted_transaction,
+};
 pub(crate) use get_transaction_status::get_transaction_status;
+pub(crate) use get_transactions_by_sender::get_transactions_by_sender;
+pub(crate) use prepare_declare::prepare_declare;
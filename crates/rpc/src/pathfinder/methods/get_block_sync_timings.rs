@@ -0,0 +1,123 @@
+use anyhow::Context;
+use pathfinder_common::BlockNumber;
+
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockSyncTimingsInput {
+    /// The number of latest blocks to return the timings of.
+    pub limit: u64,
+}
+
+/// The milliseconds spent per stage on syncing a block.
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct BlockSyncTimings {
+    pub block_number: BlockNumber,
+    pub download_ms: u64,
+    pub verification_ms: u64,
+    pub trie_update_ms: u64,
+    pub db_write_ms: u64,
+}
+
+impl From<pathfinder_storage::BlockSyncTimings> for BlockSyncTimings {
+    fn from(timings: pathfinder_storage::BlockSyncTimings) -> Self {
+        fn millis(duration: std::time::Duration) -> u64 {
+            duration.as_millis().try_into().unwrap_or(u64::MAX)
+        }
+
+        Self {
+            block_number: timings.block_number,
+            download_ms: millis(timings.download),
+            verification_ms: millis(timings.verification),
+            trie_update_ms: millis(timings.trie_update),
+            db_write_ms: millis(timings.db_write),
+        }
+    }
+}
+
+crate::error::generate_rpc_error_subset!(GetBlockSyncTimingsError:);
+
+/// Returns the time spent per sync stage on the latest blocks, latest first.
+///
+/// Only the timings of the blocks synced by this node are known, and only of a limited number of
+/// the latest blocks.
+pub async fn get_block_sync_timings(
+    context: RpcContext,
+    input: GetBlockSyncTimingsInput,
+) -> Result<Vec<BlockSyncTimings>, GetBlockSyncTimingsError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let timings = db_tx
+            .block_sync_timings(input.limit)
+            .context("Querying block sync timings")?;
+
+        Ok(timings.into_iter().map(Into::into).collect())
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn timings() {
+        let context = RpcContext::for_tests();
+        {
+            let mut db = context.storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            for block in 0..3 {
+                tx.insert_block_sync_timings(
+                    &pathfinder_storage::BlockSyncTimings {
+                        block_number: BlockNumber::new_or_panic(block),
+                        download: Duration::from_millis(10),
+                        verification: Duration::from_millis(2),
+                        trie_update: Duration::from_secs(1),
+                        db_write: Duration::from_micros(1500),
+                    },
+                    100,
+                )
+                .unwrap();
+            }
+            tx.commit().unwrap();
+        }
+
+        let input = GetBlockSyncTimingsInput { limit: 2 };
+        let output = get_block_sync_timings(context, input).await.unwrap();
+
+        assert_eq!(
+            output,
+            vec![
+                BlockSyncTimings {
+                    block_number: BlockNumber::new_or_panic(2),
+                    download_ms: 10,
+                    verification_ms: 2,
+                    trie_update_ms: 1000,
+                    db_write_ms: 1,
+                },
+                BlockSyncTimings {
+                    block_number: BlockNumber::new_or_panic(1),
+                    download_ms: 10,
+                    verification_ms: 2,
+                    trie_update_ms: 1000,
+                    db_write_ms: 1,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::Context;
+use pathfinder_common::{BlockNumber, GasPrice};
+use pathfinder_serde::GasPriceAsHexStr;
+use serde_with::serde_as;
+
+use crate::context::RpcContext;
+
+#[serde_as]
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetChainStatsOutput {
+    pub block_number: BlockNumber,
+    pub window_start: BlockNumber,
+    pub transaction_count: u64,
+    pub active_contract_count: u64,
+    #[serde_as(as = "GasPriceAsHexStr")]
+    pub average_gas_price: GasPrice,
+    pub declared_class_count: u64,
+}
+
+crate::error::generate_rpc_error_subset!(GetChainStatsError: NoBlocks);
+
+/// Returns aggregates of the blocks within a day of the latest block, and the total number of
+/// declared classes.
+///
+/// The aggregates are maintained while syncing, so that they don't require scanning the chain.
+pub async fn get_chain_stats(
+    context: RpcContext,
+) -> Result<GetChainStatsOutput, GetChainStatsError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let statistics = db_tx
+            .chain_statistics()
+            .context("Querying chain statistics")?
+            .ok_or(GetChainStatsError::NoBlocks)?;
+
+        Ok(GetChainStatsOutput {
+            block_number: statistics.block_number,
+            window_start: statistics.window_start,
+            transaction_count: statistics.transaction_count,
+            active_contract_count: statistics.active_contract_count,
+            average_gas_price: statistics.average_gas_price,
+            declared_class_count: statistics.declared_class_count,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use pathfinder_storage::Storage;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn stats() {
+        let context = RpcContext::for_tests();
+
+        let output = get_chain_stats(context).await.unwrap();
+        // The test blocks are all within a day of each other.
+        assert_eq!(output.block_number, BlockNumber::new_or_panic(2));
+        assert_eq!(output.window_start, BlockNumber::GENESIS);
+        assert_eq!(output.average_gas_price, GasPrice(1));
+    }
+
+    #[tokio::test]
+    async fn no_blocks() {
+        let context = RpcContext::for_tests().with_storage(Storage::in_memory().unwrap());
+
+        let error = get_chain_stats(context).await.unwrap_err();
+        assert_matches!(error, GetChainStatsError::NoBlocks);
+    }
+}
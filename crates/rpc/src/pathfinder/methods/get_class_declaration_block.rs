@@ -0,0 +1,99 @@
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber, ClassHash};
+
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct GetClassDeclarationBlockInput {
+    class_hash: ClassHash,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetClassDeclarationBlockOutput {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+}
+
+crate::error::generate_rpc_error_subset!(GetClassDeclarationBlockError: ClassHashNotFound);
+
+/// Returns the canonical block in which a class was declared.
+///
+/// Classes which are only declared in the pending block are not found.
+pub async fn get_class_declaration_block(
+    context: RpcContext,
+    input: GetClassDeclarationBlockInput,
+) -> Result<GetClassDeclarationBlockOutput, GetClassDeclarationBlockError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let (block_number, block_hash) = db_tx
+            .class_declaration_block(input.class_hash)
+            .context("Querying class declaration block")?
+            .ok_or(GetClassDeclarationBlockError::ClassHashNotFound)?;
+
+        Ok(GetClassDeclarationBlockOutput {
+            block_number,
+            block_hash,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn declared() {
+        let context = RpcContext::for_tests();
+        let input = GetClassDeclarationBlockInput {
+            class_hash: class_hash_bytes!(b"class 2 hash (sierra)"),
+        };
+
+        let output = get_class_declaration_block(context, input).await.unwrap();
+        let expected = GetClassDeclarationBlockOutput {
+            block_number: BlockNumber::new_or_panic(2),
+            block_hash: block_hash_bytes!(b"latest"),
+        };
+        assert_eq!(output, expected);
+    }
+
+    #[tokio::test]
+    async fn pending_only() {
+        let context = RpcContext::for_tests_with_pending().await;
+        let input = GetClassDeclarationBlockInput {
+            class_hash: class_hash_bytes!(b"pending class 0 hash"),
+        };
+
+        let error = get_class_declaration_block(context, input)
+            .await
+            .unwrap_err();
+        assert_matches!(error, GetClassDeclarationBlockError::ClassHashNotFound);
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let context = RpcContext::for_tests();
+        let input = GetClassDeclarationBlockInput {
+            class_hash: class_hash_bytes!(b"invalid"),
+        };
+
+        let error = get_class_declaration_block(context, input)
+            .await
+            .unwrap_err();
+        assert_matches!(error, GetClassDeclarationBlockError::ClassHashNotFound);
+    }
+}
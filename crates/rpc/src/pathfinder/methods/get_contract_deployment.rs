@@ -0,0 +1,88 @@
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber, ClassHash, ContractAddress, TransactionHash};
+
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct GetContractDeploymentInput {
+    contract_address: ContractAddress,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetContractDeploymentOutput {
+    pub class_hash: ClassHash,
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    /// Only known for contracts deployed by a `DEPLOY` or `DEPLOY_ACCOUNT` transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<TransactionHash>,
+}
+
+crate::error::generate_rpc_error_subset!(GetContractDeploymentError: ContractNotFound);
+
+/// Returns the class, block and transaction with which a contract was deployed.
+///
+/// Contracts which are only deployed in the pending block are not found.
+pub async fn get_contract_deployment(
+    context: RpcContext,
+    input: GetContractDeploymentInput,
+) -> Result<GetContractDeploymentOutput, GetContractDeploymentError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let deployment = db_tx
+            .contract_deployment(input.contract_address)
+            .context("Querying contract deployment")?
+            .ok_or(GetContractDeploymentError::ContractNotFound)?;
+
+        Ok(GetContractDeploymentOutput {
+            class_hash: deployment.class_hash,
+            block_number: deployment.block_number,
+            block_hash: deployment.block_hash,
+            transaction_hash: deployment.transaction_hash,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn deployed() {
+        let context = RpcContext::for_tests();
+        let input = GetContractDeploymentInput {
+            contract_address: contract_address_bytes!(b"contract 1"),
+        };
+
+        let output = get_contract_deployment(context, input).await.unwrap();
+        assert_eq!(output.class_hash, class_hash_bytes!(b"class 1 hash"));
+        assert_eq!(output.block_number, BlockNumber::new_or_panic(1));
+        assert_eq!(output.block_hash, block_hash_bytes!(b"block 1"));
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let context = RpcContext::for_tests();
+        let input = GetContractDeploymentInput {
+            contract_address: contract_address_bytes!(b"invalid"),
+        };
+
+        let error = get_contract_deployment(context, input).await.unwrap_err();
+        assert_matches!(error, GetContractDeploymentError::ContractNotFound);
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::Context;
+use pathfinder_common::{ContractAddress, ContractNonce};
+
+use super::get_nonce_history::NonceUpdate;
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct GetFirstBlockWithNonceInput {
+    contract_address: ContractAddress,
+    nonce: ContractNonce,
+}
+
+crate::error::generate_rpc_error_subset!(GetFirstBlockWithNonceError);
+
+/// Returns the first block after which a contract's nonce was at least the given nonce, or
+/// `null` if the nonce wasn't reached yet.
+///
+/// Wallets use this to find out when a transaction with a given nonce was included, e.g. to
+/// recover transactions whose hash was lost. The pending block is not considered.
+pub async fn get_first_block_with_nonce(
+    context: RpcContext,
+    input: GetFirstBlockWithNonceInput,
+) -> Result<Option<NonceUpdate>, GetFirstBlockWithNonceError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let update = db_tx
+            .first_nonce_update_reaching(input.contract_address, input.nonce)
+            .context("Querying nonce updates")?;
+
+        Ok(update.map(Into::into))
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::BlockNumber;
+
+    use super::*;
+
+    async fn first_block(nonce: ContractNonce) -> Option<BlockNumber> {
+        let context = RpcContext::for_tests();
+        let input = GetFirstBlockWithNonceInput {
+            contract_address: contract_address_bytes!(b"contract 1"),
+            nonce,
+        };
+
+        let output = get_first_block_with_nonce(context, input).await.unwrap();
+        output.map(|update| update.block_number)
+    }
+
+    #[tokio::test]
+    async fn reached() {
+        assert_eq!(
+            first_block(contract_nonce!("0x1")).await,
+            Some(BlockNumber::new_or_panic(2))
+        );
+        assert_eq!(
+            first_block(contract_nonce!("0x10")).await,
+            Some(BlockNumber::new_or_panic(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn not_reached() {
+        assert_eq!(first_block(contract_nonce!("0x11")).await, None);
+    }
+}
@@ -0,0 +1,185 @@
+use std::num::NonZeroUsize;
+
+use anyhow::Context;
+use pathfinder_common::{
+    BlockHash, BlockNumber, ContractAddress, EthereumAddress, L2ToL1MessagePayloadElem,
+    TransactionHash,
+};
+use primitive_types::H256;
+
+use super::pagination::{Page, PageError};
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+pub struct GetMessagesToL1Input {
+    #[serde(default)]
+    pub from_block: Option<BlockNumber>,
+    #[serde(default)]
+    pub to_block: Option<BlockNumber>,
+    /// The L1 recipient of the messages.
+    #[serde(default)]
+    pub to_address: Option<EthereumAddress>,
+    pub chunk_size: NonZeroUsize,
+    /// Offset, measured in messages, which points to the requested chunk.
+    #[serde(default)]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetMessagesToL1Output {
+    pub messages: Vec<MessageToL1>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct MessageToL1 {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub transaction_hash: TransactionHash,
+    pub from_address: ContractAddress,
+    pub to_address: EthereumAddress,
+    pub payload: Vec<L2ToL1MessagePayloadElem>,
+    pub message_hash: H256,
+}
+
+impl From<pathfinder_storage::MessageToL1> for MessageToL1 {
+    fn from(message: pathfinder_storage::MessageToL1) -> Self {
+        Self {
+            block_number: message.block_number,
+            block_hash: message.block_hash,
+            transaction_hash: message.transaction_hash,
+            from_address: message.from_address,
+            to_address: message.to_address,
+            payload: message.payload,
+            message_hash: message.message_hash,
+        }
+    }
+}
+
+crate::error::generate_rpc_error_subset!(
+    GetMessagesToL1Error: PageSizeTooBig,
+    InvalidContinuationToken
+);
+
+impl From<PageError> for GetMessagesToL1Error {
+    fn from(error: PageError) -> Self {
+        match error {
+            PageError::PageSizeTooBig => Self::PageSizeTooBig,
+            PageError::InvalidContinuationToken => Self::InvalidContinuationToken,
+        }
+    }
+}
+
+/// Returns the messages sent to L1 by transactions in the given block range, optionally only
+/// those to a given L1 recipient.
+///
+/// Messages are ordered by block, transaction and the order in which they were sent. Messages
+/// of the pending block are not returned, as they are only consumable on L1 once their block is
+/// accepted on L1.
+pub async fn get_messages_to_l1(
+    context: RpcContext,
+    input: GetMessagesToL1Input,
+) -> Result<GetMessagesToL1Output, GetMessagesToL1Error> {
+    let page = Page::new(input.chunk_size, input.continuation_token.as_deref())?;
+
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let filter = pathfinder_storage::MessageToL1Filter {
+            from_block: input.from_block,
+            to_block: input.to_block,
+            to_address: input.to_address,
+            page_size: page.size,
+            offset: page.offset,
+        };
+        let result = db_tx
+            .messages_to_l1(&filter)
+            .context("Querying messages to L1")?;
+
+        let continuation_token = page.next_token(result.messages.len(), result.is_last_page);
+
+        Ok(GetMessagesToL1Output {
+            messages: result.messages.into_iter().map(Into::into).collect(),
+            continuation_token,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use primitive_types::H160;
+
+    use super::*;
+
+    fn input(to_address: Option<EthereumAddress>) -> GetMessagesToL1Input {
+        GetMessagesToL1Input {
+            from_block: None,
+            to_block: None,
+            to_address,
+            chunk_size: NonZeroUsize::new(10).unwrap(),
+            continuation_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn messages() {
+        let context = RpcContext::for_tests();
+
+        let output = get_messages_to_l1(context, input(None)).await.unwrap();
+        assert_eq!(output.continuation_token, None);
+        assert_eq!(output.messages.len(), 1);
+
+        let message = &output.messages[0];
+        assert_eq!(message.block_number, BlockNumber::new_or_panic(2));
+        assert_eq!(message.block_hash, block_hash_bytes!(b"latest"));
+        assert_eq!(message.transaction_hash, transaction_hash_bytes!(b"txn 6"));
+        assert_eq!(message.from_address, contract_address!("0xcafebabe"));
+        assert_eq!(message.to_address, EthereumAddress(H160::zero()));
+        assert_eq!(
+            message.payload,
+            vec![
+                l2_to_l1_message_payload_elem!("0x1"),
+                l2_to_l1_message_payload_elem!("0x2"),
+                l2_to_l1_message_payload_elem!("0x3"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn filtered_by_recipient() {
+        let context = RpcContext::for_tests();
+        let recipient = EthereumAddress(H160::from_low_u64_be(1));
+
+        let output = get_messages_to_l1(context, input(Some(recipient)))
+            .await
+            .unwrap();
+        assert!(output.messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn filtered_by_block_range() {
+        let context = RpcContext::for_tests();
+        let input = GetMessagesToL1Input {
+            to_block: Some(BlockNumber::new_or_panic(1)),
+            ..input(None)
+        };
+
+        let output = get_messages_to_l1(context, input).await.unwrap();
+        assert!(output.messages.is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use std::num::NonZeroUsize;
+
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber, ContractAddress, ContractNonce};
+
+use super::pagination::{Page, PageError};
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+pub struct GetNonceHistoryInput {
+    pub contract_address: ContractAddress,
+    #[serde(default)]
+    pub from_block: Option<BlockNumber>,
+    #[serde(default)]
+    pub to_block: Option<BlockNumber>,
+    pub chunk_size: NonZeroUsize,
+    /// Offset, measured in nonce updates, which points to the requested chunk.
+    #[serde(default)]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetNonceHistoryOutput {
+    pub updates: Vec<NonceUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct NonceUpdate {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub nonce: ContractNonce,
+}
+
+impl From<pathfinder_storage::NonceUpdate> for NonceUpdate {
+    fn from(update: pathfinder_storage::NonceUpdate) -> Self {
+        Self {
+            block_number: update.block_number,
+            block_hash: update.block_hash,
+            nonce: update.nonce,
+        }
+    }
+}
+
+crate::error::generate_rpc_error_subset!(
+    GetNonceHistoryError: PageSizeTooBig,
+    InvalidContinuationToken
+);
+
+impl From<PageError> for GetNonceHistoryError {
+    fn from(error: PageError) -> Self {
+        match error {
+            PageError::PageSizeTooBig => Self::PageSizeTooBig,
+            PageError::InvalidContinuationToken => Self::InvalidContinuationToken,
+        }
+    }
+}
+
+/// Returns the blocks in the given block range which changed a contract's nonce, together with
+/// the nonce after each block.
+///
+/// The nonce at any block is therefore that of the last update at or before it. Updates of the
+/// pending block are not returned.
+pub async fn get_nonce_history(
+    context: RpcContext,
+    input: GetNonceHistoryInput,
+) -> Result<GetNonceHistoryOutput, GetNonceHistoryError> {
+    let page = Page::new(input.chunk_size, input.continuation_token.as_deref())?;
+
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let filter = pathfinder_storage::NonceHistoryFilter {
+            contract_address: input.contract_address,
+            from_block: input.from_block,
+            to_block: input.to_block,
+            page_size: page.size,
+            offset: page.offset,
+        };
+        let result = db_tx
+            .nonce_history(&filter)
+            .context("Querying nonce history")?;
+
+        let continuation_token = page.next_token(result.updates.len(), result.is_last_page);
+
+        Ok(GetNonceHistoryOutput {
+            updates: result.updates.into_iter().map(Into::into).collect(),
+            continuation_token,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    fn input(contract_address: ContractAddress) -> GetNonceHistoryInput {
+        GetNonceHistoryInput {
+            contract_address,
+            from_block: None,
+            to_block: None,
+            chunk_size: NonZeroUsize::new(10).unwrap(),
+            continuation_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn history() {
+        let context = RpcContext::for_tests();
+
+        let output = get_nonce_history(context, input(contract_address_bytes!(b"contract 1")))
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            GetNonceHistoryOutput {
+                updates: vec![NonceUpdate {
+                    block_number: BlockNumber::new_or_panic(2),
+                    block_hash: block_hash_bytes!(b"latest"),
+                    nonce: contract_nonce!("0x10"),
+                }],
+                continuation_token: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn filtered_by_block_range() {
+        let context = RpcContext::for_tests();
+        let input = GetNonceHistoryInput {
+            to_block: Some(BlockNumber::new_or_panic(1)),
+            ..input(contract_address_bytes!(b"contract 1"))
+        };
+
+        let output = get_nonce_history(context, input).await.unwrap();
+        assert!(output.updates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unknown_contract() {
+        let context = RpcContext::for_tests();
+
+        let output = get_nonce_history(context, input(contract_address_bytes!(b"unknown")))
+            .await
+            .unwrap();
+        assert!(output.updates.is_empty());
+    }
+}
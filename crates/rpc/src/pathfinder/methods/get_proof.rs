@@ -43,14 +43,15 @@ impl From<GetProofError> for crate::error::ApplicationError {
 
 /// Utility struct used for serializing.
 #[derive(Debug, Serialize)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(Deserialize))]
 struct PathWrapper {
     value: Felt,
     len: usize,
 }
 
 /// Wrapper around [`Vec<TrieNode>`] as we don't control [TrieNode] in this crate.
-#[derive(Debug)]
-pub struct ProofNodes(Vec<TrieNode>);
+#[derive(Debug, PartialEq)]
+pub struct ProofNodes(pub Vec<TrieNode>);
 
 impl Serialize for ProofNodes {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -104,40 +105,78 @@ impl Serialize for ProofNodes {
     }
 }
 
+#[cfg(any(test, feature = "rpc-full-serde"))]
+impl<'de> Deserialize<'de> for ProofNodes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum ProofNode {
+            Binary { left: Felt, right: Felt },
+            Edge { path: PathWrapper, child: Felt },
+        }
+
+        Vec::<ProofNode>::deserialize(deserializer)?
+            .into_iter()
+            .map(|node| match node {
+                ProofNode::Binary { left, right } => Ok(TrieNode::Binary { left, right }),
+                ProofNode::Edge { path, child } => {
+                    // The path is the `len` least significant bits of its value.
+                    let bits = path.value.view_bits();
+                    let start = bits.len().checked_sub(path.len).ok_or_else(|| {
+                        D::Error::custom(format!("edge path length {} is too long", path.len))
+                    })?;
+                    Ok(TrieNode::Edge {
+                        child,
+                        path: bits[start..].to_bitvec(),
+                    })
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(ProofNodes)
+    }
+}
+
 /// Holds the data and proofs for a specific contract.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, PartialEq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(Deserialize))]
 pub struct ContractData {
     /// Required to verify the contract state hash to contract root calculation.
-    class_hash: ClassHash,
+    pub class_hash: ClassHash,
     /// Required to verify the contract state hash to contract root calculation.
-    nonce: ContractNonce,
+    pub nonce: ContractNonce,
 
     /// Root of the Contract state tree
-    root: ContractRoot,
+    pub root: ContractRoot,
 
     /// This is currently just a constant = 0, however it might change in the future.
-    contract_state_hash_version: Felt,
+    pub contract_state_hash_version: Felt,
 
     /// The proofs associated with the queried storage values
-    storage_proofs: Vec<ProofNodes>,
+    pub storage_proofs: Vec<ProofNodes>,
 }
 
 /// Holds the membership/non-membership of a contract and its associated contract contract if the contract exists.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, PartialEq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(Deserialize))]
 #[skip_serializing_none]
 pub struct GetProofOutput {
     /// The global state commitment for Starknet 0.11.0 blocks onwards, if absent the hash
     /// of the first node in the [contract_proof](GetProofOutput#contract_proof) is the global state commitment.
-    state_commitment: Option<StateCommitment>,
+    pub state_commitment: Option<StateCommitment>,
     /// Required to verify that the hash of the class commitment and the root of the [contract_proof](GetProofOutput::contract_proof)
     /// matches the [state_commitment](Self#state_commitment). Present only for Starknet blocks 0.11.0 onwards.
-    class_commitment: Option<ClassCommitment>,
+    pub class_commitment: Option<ClassCommitment>,
 
     /// Membership / Non-membership proof for the queried contract
-    contract_proof: ProofNodes,
+    pub contract_proof: ProofNodes,
 
     /// Additional contract data if it exists.
-    contract_data: Option<ContractData>,
+    pub contract_data: Option<ContractData>,
 }
 
 /// Returns all the necessary data to trustlessly verify storage slots for a particular contract.
@@ -278,4 +317,29 @@ mod tests {
         let err = get_proof(context, input).await.unwrap_err();
         assert_matches::assert_matches!(err, GetProofError::ProofLimitExceeded { .. });
     }
+
+    #[test]
+    fn proof_nodes_roundtrip() {
+        let path = Felt::from_u64(0b101);
+        let nodes = ProofNodes(vec![
+            TrieNode::Binary {
+                left: felt!("0x1"),
+                right: felt!("0x2"),
+            },
+            TrieNode::Edge {
+                child: felt!("0x3"),
+                path: path.view_bits()[256 - 5..].to_bitvec(),
+            },
+        ]);
+
+        let json = serde_json::to_value(&nodes).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"binary": {"left": "0x1", "right": "0x2"}},
+                {"edge": {"path": {"value": "0x5", "len": 5}, "child": "0x3"}},
+            ])
+        );
+        assert_eq!(serde_json::from_value::<ProofNodes>(json).unwrap(), nodes);
+    }
 }
@@ -0,0 +1,210 @@
+use anyhow::Context;
+use pathfinder_common::TransactionHash;
+use starknet_gateway_types::reply::transaction::ExecutionStatus;
+
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct GetSubmittedTransactionStatusInput {
+    transaction_hash: TransactionHash,
+}
+
+#[derive(Copy, Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub enum SubmittedTransactionStatus {
+    /// Accepted by the gateway but not yet seen in a block.
+    #[serde(rename = "SUBMITTED")]
+    Submitted,
+    #[serde(rename = "PENDING")]
+    Pending,
+    #[serde(rename = "ACCEPTED_ON_L2")]
+    AcceptedOnL2,
+    #[serde(rename = "ACCEPTED_ON_L1")]
+    AcceptedOnL1,
+    #[serde(rename = "REVERTED")]
+    Reverted,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetSubmittedTransactionStatusOutput {
+    pub status: SubmittedTransactionStatus,
+    /// Unix timestamp in seconds at which the transaction was submitted via this node.
+    pub submitted_at: u64,
+    /// Unix timestamp in seconds at which the transaction was first seen in a (pending) block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub included_at: Option<u64>,
+}
+
+crate::error::generate_rpc_error_subset!(GetSubmittedTransactionStatusError: TxnHashNotFoundV04);
+
+/// Returns the lifecycle of a transaction which was submitted via this node's write API.
+pub async fn get_submitted_transaction_status(
+    context: RpcContext,
+    input: GetSubmittedTransactionStatusInput,
+) -> Result<GetSubmittedTransactionStatusOutput, GetSubmittedTransactionStatusError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let submitted = db_tx
+            .submitted_transaction(input.transaction_hash)
+            .context("Querying submitted transaction")?
+            .ok_or(GetSubmittedTransactionStatusError::TxnHashNotFoundV04)?;
+
+        let status = status(&context, &db_tx, input.transaction_hash)?;
+
+        Ok(GetSubmittedTransactionStatusOutput {
+            status,
+            submitted_at: submitted.submitted_at,
+            included_at: submitted.included_at,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+fn status(
+    context: &RpcContext,
+    db_tx: &pathfinder_storage::Transaction<'_>,
+    transaction_hash: TransactionHash,
+) -> anyhow::Result<SubmittedTransactionStatus> {
+    let pending = context
+        .pending_data
+        .get(db_tx)
+        .context("Querying pending data")?;
+    let pending_receipt = pending
+        .block
+        .transaction_receipts
+        .iter()
+        .find(|rx| rx.transaction_hash == transaction_hash);
+    if let Some(receipt) = pending_receipt {
+        return Ok(match receipt.execution_status {
+            ExecutionStatus::Reverted => SubmittedTransactionStatus::Reverted,
+            ExecutionStatus::Succeeded => SubmittedTransactionStatus::Pending,
+        });
+    }
+
+    let Some((_, receipt, block_hash)) = db_tx
+        .transaction_with_receipt(transaction_hash)
+        .context("Fetching receipt from database")?
+    else {
+        return Ok(SubmittedTransactionStatus::Submitted);
+    };
+
+    if receipt.execution_status == ExecutionStatus::Reverted {
+        return Ok(SubmittedTransactionStatus::Reverted);
+    }
+
+    let l1_accepted = db_tx
+        .block_is_l1_accepted(block_hash.into())
+        .context("Querying block's status")?;
+
+    if l1_accepted {
+        Ok(SubmittedTransactionStatus::AcceptedOnL1)
+    } else {
+        Ok(SubmittedTransactionStatus::AcceptedOnL2)
+    }
+}
+
+/// Records a transaction which was accepted by the gateway, so that its lifecycle can be
+/// queried via `pathfinder_getSubmittedTransactionStatus`.
+///
+/// Failures are only logged, since the transaction has already been submitted at this point.
+pub(crate) async fn record_submitted_transaction(
+    context: &RpcContext,
+    transaction_hash: TransactionHash,
+) {
+    let storage = context.storage.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let submitted_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .context("System time is before the unix epoch")?
+            .as_secs();
+
+        let mut db = storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+        db_tx
+            .insert_submitted_transaction(transaction_hash, submitted_at)
+            .context("Inserting submitted transaction")?;
+        db_tx.commit().context("Committing database transaction")
+    })
+    .await
+    .context("Joining database task")
+    .and_then(|x| x);
+
+    if let Err(e) = result {
+        tracing::warn!(%transaction_hash, error=%e, "Failed to record submitted transaction");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn not_submitted() {
+        let context = RpcContext::for_tests();
+        let input = GetSubmittedTransactionStatusInput {
+            transaction_hash: transaction_hash_bytes!(b"txn 0"),
+        };
+
+        let error = get_submitted_transaction_status(context, input)
+            .await
+            .unwrap_err();
+        assert_matches::assert_matches!(
+            error,
+            GetSubmittedTransactionStatusError::TxnHashNotFoundV04
+        );
+    }
+
+    #[tokio::test]
+    async fn lifecycle() {
+        let context = RpcContext::for_tests_with_pending().await;
+
+        let cases = [
+            (
+                transaction_hash!("0xdeadbeef"),
+                SubmittedTransactionStatus::Submitted,
+            ),
+            (
+                transaction_hash_bytes!(b"pending tx hash 0"),
+                SubmittedTransactionStatus::Pending,
+            ),
+            (
+                transaction_hash_bytes!(b"txn 1"),
+                SubmittedTransactionStatus::AcceptedOnL2,
+            ),
+            (
+                transaction_hash_bytes!(b"txn 0"),
+                SubmittedTransactionStatus::AcceptedOnL1,
+            ),
+            (
+                transaction_hash_bytes!(b"txn reverted"),
+                SubmittedTransactionStatus::Reverted,
+            ),
+        ];
+
+        for (transaction_hash, expected) in cases {
+            record_submitted_transaction(&context, transaction_hash).await;
+
+            let input = GetSubmittedTransactionStatusInput { transaction_hash };
+            let output = get_submitted_transaction_status(context.clone(), input)
+                .await
+                .unwrap();
+            assert_eq!(output.status, expected, "{transaction_hash}");
+            assert_eq!(output.included_at, None);
+        }
+    }
+}
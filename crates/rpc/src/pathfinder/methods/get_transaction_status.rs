@@ -1,9 +1,9 @@
 use anyhow::Context;
 use pathfinder_common::TransactionHash;
 use starknet_gateway_types::reply::transaction::ExecutionStatus;
-use starknet_gateway_types::reply::PendingBlock;
 
 use crate::context::RpcContext;
+use crate::pending::PendingData;
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
 pub struct GetGatewayTransactionInput {
@@ -32,7 +32,7 @@ pub async fn get_transaction_status(
             .pending_data
             .get(&db_tx)
             .context("Querying pending data")?;
-        if let Some(status) = pending_status(&pending.block, &input.transaction_hash) {
+        if let Some(status) = pending_status(&pending, &input.transaction_hash) {
             return Ok(Some(status));
         }
 
@@ -75,8 +75,8 @@ pub async fn get_transaction_status(
         .map_err(GetGatewayTransactionError::Internal)
 }
 
-fn pending_status(pending: &PendingBlock, tx_hash: &TransactionHash) -> Option<TransactionStatus> {
-    pending.transaction_receipts.iter().find_map(|rx| {
+fn pending_status(pending: &PendingData, tx_hash: &TransactionHash) -> Option<TransactionStatus> {
+    let status = pending.block.transaction_receipts.iter().find_map(|rx| {
         if &rx.transaction_hash == tx_hash {
             if rx.execution_status == ExecutionStatus::Reverted {
                 Some(TransactionStatus::Reverted)
@@ -86,10 +86,17 @@ fn pending_status(pending: &PendingBlock, tx_hash: &TransactionHash) -> Option<T
         } else {
             None
         }
+    });
+
+    status.or_else(|| {
+        pending
+            .is_candidate(*tx_hash)
+            .then_some(TransactionStatus::Candidate)
     })
 }
 
 #[derive(Copy, Clone, Debug, serde::Serialize, PartialEq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub enum TransactionStatus {
     #[serde(rename = "NOT_RECEIVED")]
     NotReceived,
@@ -107,6 +114,9 @@ pub enum TransactionStatus {
     Reverted,
     #[serde(rename = "ABORTED")]
     Aborted,
+    /// A candidate transaction of the pending block, which is not executed yet.
+    #[serde(rename = "CANDIDATE")]
+    Candidate,
 }
 
 impl From<starknet_gateway_types::reply::Status> for TransactionStatus {
@@ -170,6 +180,17 @@ mod tests {
         assert_eq!(status, TransactionStatus::AcceptedOnL2);
     }
 
+    #[tokio::test]
+    async fn candidate() {
+        let context = RpcContext::for_tests_with_pending().await;
+        let input = GetGatewayTransactionInput {
+            transaction_hash: transaction_hash_bytes!(b"pending candidate"),
+        };
+        let status = get_transaction_status(context, input).await.unwrap();
+
+        assert_eq!(status, TransactionStatus::Candidate);
+    }
+
     #[tokio::test]
     async fn rejected() {
         let input = GetGatewayTransactionInput {
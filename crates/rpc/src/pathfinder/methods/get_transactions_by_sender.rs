@@ -0,0 +1,207 @@
+use std::num::NonZeroUsize;
+
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber, ContractAddress, TransactionHash};
+
+use super::pagination::{Page, PageError};
+use crate::context::RpcContext;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+pub struct GetTransactionsBySenderInput {
+    /// The account which sent the transactions.
+    pub sender_address: ContractAddress,
+    #[serde(default)]
+    pub from_block: Option<BlockNumber>,
+    #[serde(default)]
+    pub to_block: Option<BlockNumber>,
+    pub chunk_size: NonZeroUsize,
+    /// Offset, measured in transactions, which points to the requested chunk.
+    #[serde(default)]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct GetTransactionsBySenderOutput {
+    pub transactions: Vec<SenderTransaction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct SenderTransaction {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub transaction_hash: TransactionHash,
+}
+
+impl From<pathfinder_storage::SenderTransaction> for SenderTransaction {
+    fn from(transaction: pathfinder_storage::SenderTransaction) -> Self {
+        Self {
+            block_number: transaction.block_number,
+            block_hash: transaction.block_hash,
+            transaction_hash: transaction.transaction_hash,
+        }
+    }
+}
+
+crate::error::generate_rpc_error_subset!(
+    GetTransactionsBySenderError: PageSizeTooBig,
+    InvalidContinuationToken
+);
+
+impl From<PageError> for GetTransactionsBySenderError {
+    fn from(error: PageError) -> Self {
+        match error {
+            PageError::PageSizeTooBig => Self::PageSizeTooBig,
+            PageError::InvalidContinuationToken => Self::InvalidContinuationToken,
+        }
+    }
+}
+
+/// Returns the transactions sent by an account in the given block range.
+///
+/// Transactions are ordered by block and their position in the block. Only `INVOKE`, `DECLARE`
+/// and `DEPLOY_ACCOUNT` transactions have a sender. Transactions of the pending block are not
+/// returned.
+pub async fn get_transactions_by_sender(
+    context: RpcContext,
+    input: GetTransactionsBySenderInput,
+) -> Result<GetTransactionsBySenderOutput, GetTransactionsBySenderError> {
+    let page = Page::new(input.chunk_size, input.continuation_token.as_deref())?;
+
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let db_tx = db.transaction().context("Creating database transaction")?;
+
+        let filter = pathfinder_storage::SenderTransactionFilter {
+            sender_address: input.sender_address,
+            from_block: input.from_block,
+            to_block: input.to_block,
+            page_size: page.size,
+            offset: page.offset,
+        };
+        let result = db_tx
+            .sender_transactions(&filter)
+            .context("Querying transactions by sender")?;
+
+        let continuation_token = page.next_token(result.transactions.len(), result.is_last_page);
+
+        Ok(GetTransactionsBySenderOutput {
+            transactions: result.transactions.into_iter().map(Into::into).collect(),
+            continuation_token,
+        })
+    })
+    .await
+    .context("Joining database task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+
+    fn input(continuation_token: Option<&str>) -> GetTransactionsBySenderInput {
+        GetTransactionsBySenderInput {
+            sender_address: contract_address_bytes!(b"contract 1"),
+            from_block: None,
+            to_block: None,
+            chunk_size: NonZeroUsize::new(10).unwrap(),
+            continuation_token: continuation_token.map(ToOwned::to_owned),
+        }
+    }
+
+    #[tokio::test]
+    async fn transactions() {
+        let context = RpcContext::for_tests();
+
+        let output = get_transactions_by_sender(context, input(None))
+            .await
+            .unwrap();
+        assert_eq!(output.continuation_token, None);
+        assert_eq!(
+            output.transactions,
+            vec![
+                SenderTransaction {
+                    block_number: BlockNumber::new_or_panic(1),
+                    block_hash: block_hash_bytes!(b"block 1"),
+                    transaction_hash: transaction_hash_bytes!(b"txn 1"),
+                },
+                SenderTransaction {
+                    block_number: BlockNumber::new_or_panic(1),
+                    block_hash: block_hash_bytes!(b"block 1"),
+                    transaction_hash: transaction_hash_bytes!(b"txn 2"),
+                },
+                SenderTransaction {
+                    block_number: BlockNumber::new_or_panic(2),
+                    block_hash: block_hash_bytes!(b"latest"),
+                    transaction_hash: transaction_hash_bytes!(b"txn 3"),
+                },
+                SenderTransaction {
+                    block_number: BlockNumber::new_or_panic(2),
+                    block_hash: block_hash_bytes!(b"latest"),
+                    transaction_hash: transaction_hash_bytes!(b"txn 6"),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn filtered_by_block_range() {
+        let context = RpcContext::for_tests();
+        let input = GetTransactionsBySenderInput {
+            from_block: Some(BlockNumber::new_or_panic(2)),
+            ..input(None)
+        };
+
+        let output = get_transactions_by_sender(context, input).await.unwrap();
+        let hashes = output
+            .transactions
+            .iter()
+            .map(|transaction| transaction.transaction_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hashes,
+            vec![
+                transaction_hash_bytes!(b"txn 3"),
+                transaction_hash_bytes!(b"txn 6")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn paging() {
+        let context = RpcContext::for_tests();
+        let first = GetTransactionsBySenderInput {
+            chunk_size: NonZeroUsize::new(3).unwrap(),
+            ..input(None)
+        };
+
+        let output = get_transactions_by_sender(context.clone(), first)
+            .await
+            .unwrap();
+        assert_eq!(output.transactions.len(), 3);
+        assert_eq!(output.continuation_token, Some("3".to_owned()));
+
+        let second = GetTransactionsBySenderInput {
+            chunk_size: NonZeroUsize::new(3).unwrap(),
+            ..input(Some("3"))
+        };
+        let output = get_transactions_by_sender(context, second).await.unwrap();
+        assert_eq!(output.continuation_token, None);
+        assert_eq!(
+            output.transactions[0].transaction_hash,
+            transaction_hash_bytes!(b"txn 6")
+        );
+    }
+}
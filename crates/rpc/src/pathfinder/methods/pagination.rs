@@ -0,0 +1,131 @@
+//! Offset based paging shared by the methods which return their results in chunks.
+//!
+//! The continuation token is the offset, measured in items, of the requested chunk. A
+//! `chunk_size` of zero is rejected as invalid params while deserializing the input, as the
+//! methods take it as a [NonZeroUsize].
+use std::num::NonZeroUsize;
+
+/// The maximum number of items returned per request.
+pub(crate) const MAX_CHUNK_SIZE: usize = 1_024;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PageError {
+    PageSizeTooBig,
+    InvalidContinuationToken,
+}
+
+/// The chunk requested by a `chunk_size` and `continuation_token` pair.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Page {
+    pub size: usize,
+    pub offset: usize,
+}
+
+impl Page {
+    pub fn new(
+        chunk_size: NonZeroUsize,
+        continuation_token: Option<&str>,
+    ) -> Result<Self, PageError> {
+        let size = chunk_size.get();
+        if size > MAX_CHUNK_SIZE {
+            return Err(PageError::PageSizeTooBig);
+        }
+
+        let offset = match continuation_token {
+            Some(token) => token
+                .parse::<usize>()
+                .map_err(|_| PageError::InvalidContinuationToken)?,
+            None => 0,
+        };
+
+        Ok(Self { size, offset })
+    }
+
+    /// The token of the chunk following this one, given the number of items returned in this
+    /// one. `None` if this is the last chunk.
+    pub fn next_token(&self, returned: usize, is_last_page: bool) -> Option<String> {
+        (!is_last_page).then(|| (self.offset + returned).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::pathfinder::methods::get_messages_to_l1::GetMessagesToL1Input;
+    use crate::pathfinder::methods::get_nonce_history::GetNonceHistoryInput;
+    use crate::pathfinder::methods::get_transactions_by_sender::GetTransactionsBySenderInput;
+
+    fn chunk_size(size: usize) -> NonZeroUsize {
+        NonZeroUsize::new(size).unwrap()
+    }
+
+    #[test]
+    fn first_page() {
+        let page = Page::new(chunk_size(10), None).unwrap();
+        assert_eq!(
+            page,
+            Page {
+                size: 10,
+                offset: 0
+            }
+        );
+    }
+
+    #[test]
+    fn offset_from_continuation_token() {
+        let page = Page::new(chunk_size(10), Some("30")).unwrap();
+        assert_eq!(
+            page,
+            Page {
+                size: 10,
+                offset: 30
+            }
+        );
+    }
+
+    #[test]
+    fn page_size_too_big() {
+        let page = Page::new(chunk_size(MAX_CHUNK_SIZE), None).unwrap();
+        assert_eq!(page.size, MAX_CHUNK_SIZE);
+
+        let error = Page::new(chunk_size(MAX_CHUNK_SIZE + 1), None).unwrap_err();
+        assert_eq!(error, PageError::PageSizeTooBig);
+    }
+
+    #[test]
+    fn invalid_continuation_token() {
+        for token in ["invalid", "-1", "0x10", ""] {
+            let error = Page::new(chunk_size(10), Some(token)).unwrap_err();
+            assert_eq!(error, PageError::InvalidContinuationToken, "{token}");
+        }
+    }
+
+    #[test]
+    fn next_token() {
+        let page = Page::new(chunk_size(10), Some("30")).unwrap();
+        assert_eq!(page.next_token(10, false), Some("40".to_owned()));
+        assert_eq!(page.next_token(4, true), None);
+    }
+
+    #[test]
+    fn zero_chunk_size_is_rejected() {
+        let contract = "0x1";
+
+        serde_json::from_value::<GetMessagesToL1Input>(json!({ "chunk_size": 0 })).unwrap_err();
+        serde_json::from_value::<GetTransactionsBySenderInput>(
+            json!({ "sender_address": contract, "chunk_size": 0 }),
+        )
+        .unwrap_err();
+        serde_json::from_value::<GetNonceHistoryInput>(
+            json!({ "contract_address": contract, "chunk_size": 0 }),
+        )
+        .unwrap_err();
+
+        serde_json::from_value::<GetNonceHistoryInput>(
+            json!({ "contract_address": contract, "chunk_size": 1 }),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Context;
+use pathfinder_common::{CasmHash, ClassHash};
+
+use crate::context::RpcContext;
+use crate::v02::types::SierraContractClass;
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct PrepareDeclareInput {
+    contract_class: SierraContractClass,
+}
+
+#[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+pub struct PrepareDeclareOutput {
+    pub class_hash: ClassHash,
+    pub compiled_class_hash: CasmHash,
+    pub compiler_version: String,
+}
+
+crate::error::generate_rpc_error_subset!(PrepareDeclareError: CompilationFailed);
+
+/// Returns the hashes a DECLARE v2 or v3 transaction of the Sierra class commits to.
+///
+/// The compiled class hash is computed with the node's own compiler, so that it matches the
+/// CASM class the node executes once the class is declared.
+pub async fn prepare_declare(
+    _context: RpcContext,
+    input: PrepareDeclareInput,
+) -> Result<PrepareDeclareOutput, PrepareDeclareError> {
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _g = span.enter();
+
+        let class_hash = input
+            .contract_class
+            .class_hash()
+            .context("Computing class hash")?
+            .hash();
+
+        let definition = input
+            .contract_class
+            .serialize_to_json()
+            .context("Serializing Sierra class definition")?;
+        let (_, compiled_class_hash) = pathfinder_compiler::compile_and_hash_with_latest_compiler(
+            &definition,
+        )
+        .map_err(|e| {
+            tracing::debug!(%class_hash, error=%e, "Compiling class to CASM failed");
+            PrepareDeclareError::CompilationFailed
+        })?;
+
+        Ok(PrepareDeclareOutput {
+            class_hash,
+            compiled_class_hash,
+            compiler_version: pathfinder_compiler::COMPILER_VERSION.to_owned(),
+        })
+    })
+    .await
+    .context("Joining compilation task")?
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+    use crate::v02::types::ContractClass;
+
+    fn contract_class() -> SierraContractClass {
+        let definition = include_bytes!("../../../fixtures/contracts/storage_access.json");
+        ContractClass::from_definition_bytes(definition)
+            .unwrap()
+            .as_sierra()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn hashes() {
+        let input = PrepareDeclareInput {
+            contract_class: contract_class(),
+        };
+
+        let output = prepare_declare(RpcContext::for_tests(), input)
+            .await
+            .unwrap();
+        let expected = PrepareDeclareOutput {
+            class_hash: class_hash!(
+                "0544b92d358447cb9e50b65092b7169f931d29e05c1404a2cd08c6fd7e32ba90"
+            ),
+            compiled_class_hash: casm_hash!(
+                "0x069032ff71f77284e1a0864a573007108ca5cc08089416af50f03260f5d6d4d8"
+            ),
+            compiler_version: pathfinder_compiler::COMPILER_VERSION.to_owned(),
+        };
+        assert_eq!(output, expected);
+    }
+
+    #[tokio::test]
+    async fn invalid_program() {
+        let mut contract_class = contract_class();
+        contract_class.sierra_program.truncate(1);
+        let input = PrepareDeclareInput { contract_class };
+
+        let error = prepare_declare(RpcContext::for_tests(), input)
+            .await
+            .unwrap_err();
+        assert_matches!(error, PrepareDeclareError::CompilationFailed);
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Context;
-use pathfinder_common::{BlockHeader, BlockNumber, StateUpdate};
+use pathfinder_common::{BlockHeader, BlockNumber, StateUpdate, TransactionHash};
 use pathfinder_storage::Transaction;
 use starknet_gateway_types::reply::{PendingBlock, Status};
 
@@ -41,6 +41,15 @@ impl PendingData {
             event_count: Default::default(),
         }
     }
+
+    /// Whether the transaction is one of the pending block's candidate transactions, which are
+    /// not executed yet and may still be dropped by the sequencer.
+    pub fn is_candidate(&self, transaction_hash: TransactionHash) -> bool {
+        self.block
+            .candidate_transactions
+            .iter()
+            .any(|tx| tx.hash() == transaction_hash)
+    }
 }
 
 impl PendingWatcher {
@@ -52,7 +61,8 @@ impl PendingWatcher {
     /// available in storage.
     ///
     /// Returns an empty block with gas price and timestamp taken from the latest
-    /// block if no valid pending data is available. The block number is also incremented.
+    /// block if no valid pending data is available. The block number is also incremented,
+    /// and its state update is empty on top of the latest state commitment.
     pub fn get(&self, tx: &Transaction<'_>) -> anyhow::Result<Arc<PendingData>> {
         let latest = tx
             .block_header(pathfinder_storage::BlockId::Latest)
@@ -74,7 +84,9 @@ impl PendingWatcher {
                     status: Status::Pending,
                     ..Default::default()
                 },
-                state_update: StateUpdate::default(),
+                // The empty pending block's state diff still applies on top of the latest state.
+                state_update: StateUpdate::default()
+                    .with_parent_state_commitment(latest.state_commitment),
                 number: latest.number + 1,
             };
 
@@ -82,6 +94,12 @@ impl PendingWatcher {
         }
     }
 
+    /// Waits for new [PendingData] to be received, which is not necessarily valid for the
+    /// latest block in storage yet.
+    pub(crate) async fn changed(&mut self) -> Result<(), tokio::sync::watch::error::RecvError> {
+        self.0.changed().await
+    }
+
     #[cfg(test)]
     pub fn get_unchecked(&self) -> Arc<PendingData> {
         self.0.borrow().clone()
@@ -158,6 +176,7 @@ mod tests {
             .child_builder()
             .with_gas_price(GasPrice(1234))
             .with_timestamp(BlockTimestamp::new_or_panic(6777))
+            .with_state_commitment(state_commitment_bytes!(b"latest state commitment"))
             .finalize_with_hash(block_hash_bytes!(b"latest hash"));
 
         let tx = storage.transaction().unwrap();
@@ -173,6 +192,7 @@ mod tests {
         expected.block.starknet_version = latest.starknet_version;
         expected.block.status = Status::Pending;
         expected.number = latest.number + 1;
+        expected.state_update.parent_state_commitment = latest.state_commitment;
 
         pretty_assertions::assert_eq!(*result, expected);
     }
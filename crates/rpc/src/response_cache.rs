@@ -0,0 +1,303 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use pathfinder_common::cache::{CacheRegistry, MemoryAccounted};
+use pathfinder_common::BlockHash;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+use crate::jsonrpc::RawParams;
+
+/// The methods whose responses are cached if they address a block by its hash.
+const CACHED_METHODS: &[&str] = &[
+    "starknet_getBlockWithTxHashes",
+    "starknet_getBlockWithTxs",
+    "starknet_getStateUpdate",
+    "starknet_traceBlockTransactions",
+];
+
+/// A size-limited cache of the results of queries which address a block by its hash.
+///
+/// Such results never change while the block is part of the chain, apart from the block's
+/// status. Blocks are therefore only cached once they are accepted on L1. Results are
+/// invalidated once their block is removed by a reorg, or once an L1 reorg reverts the L1
+/// acceptance of blocks.
+#[derive(Clone)]
+pub struct ResponseCache(Arc<Shared>);
+
+struct Shared(Mutex<lru::LruCache<ResponseKey, Entry>>);
+
+struct Entry {
+    block_hash: BlockHash,
+    l1_accepted: bool,
+    result: Box<RawValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResponseKey {
+    version: &'static str,
+    method: &'static str,
+    params: Box<str>,
+    block_hash: BlockHash,
+}
+
+impl ResponseCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        let shared = Arc::new(Shared(Mutex::new(lru::LruCache::new(capacity))));
+
+        let accounted: Arc<dyn MemoryAccounted> = shared.clone();
+        CacheRegistry::global().register("rpc_responses", &accounted);
+
+        Self(shared)
+    }
+
+    /// The key of the request, or [None] if its response can't be cached.
+    pub(crate) fn key(
+        &self,
+        version: &'static str,
+        method: &'static str,
+        params: &RawParams<'_>,
+    ) -> Option<ResponseKey> {
+        Some(ResponseKey {
+            version,
+            method,
+            params: params.0?.get().into(),
+            block_hash: addressed_block(method, params)?,
+        })
+    }
+
+    pub(crate) fn get(&self, key: &ResponseKey) -> Option<Box<RawValue>> {
+        let result = self.0.lock().get(key).map(|entry| entry.result.clone());
+        match result {
+            Some(_) => {
+                metrics::increment_counter!("rpc_response_cache_hits_total", "method" => key.method)
+            }
+            None => {
+                metrics::increment_counter!("rpc_response_cache_misses_total", "method" => key.method)
+            }
+        }
+        result
+    }
+
+    pub(crate) fn insert(&self, key: ResponseKey, result: &RawValue) {
+        let l1_accepted = match status(result).as_deref() {
+            Some("ACCEPTED_ON_L1") => true,
+            // The status of the block is still going to change.
+            Some(_) => return,
+            None => false,
+        };
+
+        let entry = Entry {
+            block_hash: key.block_hash,
+            l1_accepted,
+            result: result.to_owned(),
+        };
+        self.0.lock().push(key, entry);
+    }
+
+    /// Removes the results of the blocks, e.g. because they were removed by a reorg.
+    pub fn invalidate(&self, block_hashes: &[BlockHash]) {
+        self.0
+            .retain(|entry| !block_hashes.contains(&entry.block_hash));
+    }
+
+    /// Removes the results which depend on the L1 acceptance of their block.
+    pub fn invalidate_l1_accepted(&self) {
+        self.0.retain(|entry| !entry.l1_accepted);
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(1024).unwrap())
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, lru::LruCache<ResponseKey, Entry>> {
+        self.0.lock().unwrap()
+    }
+
+    fn retain(&self, keep: impl Fn(&Entry) -> bool) {
+        let mut cache = self.lock();
+        let removed = cache
+            .iter()
+            .filter(|(_, entry)| !keep(entry))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in removed {
+            cache.pop(&key);
+        }
+    }
+}
+
+impl Entry {
+    fn size(&self) -> usize {
+        std::mem::size_of::<(ResponseKey, Entry)>() + self.result.get().len()
+    }
+}
+
+impl MemoryAccounted for Shared {
+    fn memory_usage(&self) -> usize {
+        self.lock().iter().map(|(_, entry)| entry.size()).sum()
+    }
+
+    fn shrink_to(&self, bytes: usize) {
+        let mut cache = self.lock();
+        let mut usage: usize = cache.iter().map(|(_, entry)| entry.size()).sum();
+        while usage > bytes {
+            let Some((_, evicted)) = cache.pop_lru() else {
+                break;
+            };
+            usage -= evicted.size();
+        }
+    }
+}
+
+/// The hash of the block a request addresses, if its method's results only depend on that
+/// block.
+///
+/// The block is either the first positional param, or the named `block_id` or `block_hash`
+/// param. It is given either as a hash, or as a block ID object with a `block_hash` field.
+pub(crate) fn addressed_block(method: &str, params: &RawParams<'_>) -> Option<BlockHash> {
+    if !CACHED_METHODS.contains(&method) {
+        return None;
+    }
+
+    let params: Value = serde_json::from_str(params.0?.get()).ok()?;
+    let block = match &params {
+        Value::Array(params) => params.first()?,
+        Value::Object(params) => params
+            .get("block_id")
+            .or_else(|| params.get("block_hash"))?,
+        _ => return None,
+    };
+    let hash = match block {
+        Value::Object(block_id) => block_id.get("block_hash")?,
+        hash => hash,
+    };
+
+    BlockHash::deserialize(hash).ok()
+}
+
+/// The `status` of an object result, which is read without building the rest of the result.
+fn status(result: &RawValue) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Status {
+        status: Option<String>,
+    }
+
+    // Arrays would otherwise be deserialized into the struct by position.
+    if !result.get().starts_with('{') {
+        return None;
+    }
+    serde_json::from_str::<Status>(result.get()).ok()?.status
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use serde_json::json;
+
+    use super::*;
+
+    fn key(cache: &ResponseCache, method: &'static str, params: &str) -> Option<ResponseKey> {
+        let params = RawValue::from_string(params.to_owned()).unwrap();
+        cache.key("v0.5", method, &RawParams(Some(&*params)))
+    }
+
+    fn insert(cache: &ResponseCache, key: &ResponseKey, result: Value) {
+        let result = serde_json::value::to_raw_value(&result).unwrap();
+        cache.insert(key.clone(), &result);
+    }
+
+    fn get(cache: &ResponseCache, key: &ResponseKey) -> Option<Value> {
+        cache
+            .get(key)
+            .map(|result| serde_json::from_str(result.get()).unwrap())
+    }
+
+    #[test]
+    fn only_hash_addressed_queries_are_cached() {
+        let cache = ResponseCache::default();
+        let by_id = r#"[{"block_hash": "0x1"}]"#;
+
+        assert!(key(&cache, "starknet_getBlockWithTxs", by_id).is_some());
+        assert!(key(
+            &cache,
+            "starknet_getStateUpdate",
+            r#"{"block_id": {"block_hash": "0x1"}}"#
+        )
+        .is_some());
+        assert!(key(
+            &cache,
+            "starknet_traceBlockTransactions",
+            r#"{"block_hash": "0x1"}"#
+        )
+        .is_some());
+
+        assert!(key(&cache, "starknet_getBlockWithTxs", r#"["latest"]"#).is_none());
+        assert!(key(
+            &cache,
+            "starknet_getBlockWithTxs",
+            r#"[{"block_number": 1}]"#
+        )
+        .is_none());
+        assert!(key(&cache, "starknet_call", by_id).is_none());
+    }
+
+    #[test]
+    fn invalidation() {
+        let cache = ResponseCache::default();
+        let block1 = key(
+            &cache,
+            "starknet_getStateUpdate",
+            r#"[{"block_hash": "0x1"}]"#,
+        )
+        .unwrap();
+        let block2 = key(
+            &cache,
+            "starknet_getBlockWithTxs",
+            r#"[{"block_hash": "0x2"}]"#,
+        )
+        .unwrap();
+        let block3 = key(
+            &cache,
+            "starknet_getBlockWithTxs",
+            r#"[{"block_hash": "0x3"}]"#,
+        )
+        .unwrap();
+
+        insert(&cache, &block1, json!({"block_hash": "0x1"}));
+        insert(&cache, &block2, json!({"status": "ACCEPTED_ON_L1"}));
+        // Blocks which are not accepted on L1 yet are not cached, as their status will change.
+        insert(&cache, &block3, json!({"status": "ACCEPTED_ON_L2"}));
+        assert_eq!(get(&cache, &block1), Some(json!({"block_hash": "0x1"})));
+        assert_eq!(
+            get(&cache, &block2),
+            Some(json!({"status": "ACCEPTED_ON_L1"}))
+        );
+        assert_eq!(get(&cache, &block3), None);
+
+        cache.invalidate_l1_accepted();
+        assert!(get(&cache, &block1).is_some());
+        assert_eq!(get(&cache, &block2), None);
+
+        cache.invalidate(&[block_hash_bytes!(b"other"), block_hash!("0x1")]);
+        assert_eq!(get(&cache, &block1), None);
+    }
+
+    #[test]
+    fn status_of_results() {
+        let status_of = |result: Value| status(&serde_json::value::to_raw_value(&result).unwrap());
+
+        assert_eq!(
+            status_of(json!({"block_hash": "0x1", "status": "ACCEPTED_ON_L1"})),
+            Some("ACCEPTED_ON_L1".to_owned())
+        );
+        assert_eq!(status_of(json!({"block_hash": "0x1"})), None);
+        // Traces are arrays, which have no status.
+        assert_eq!(status_of(json!(["ACCEPTED_ON_L2"])), None);
+    }
+}
@@ -0,0 +1,488 @@
+//! Validates the responses of each RPC version against the starknet-specs documents in
+//! `doc/rpc`, to catch serialization drift such as missing or renamed fields.
+//!
+//! Each method of a version's specification documents is either queried with the
+//! [fixture requests](requests) over the usual test storage and pending data, or explicitly
+//! [excluded](EXCLUDED) with the reason why. A method added to the specification must be given
+//! one or the other.
+//!
+//! Results are validated against the method's result schema, supporting the subset of JSON
+//! Schema used by the specifications. The following deviate from JSON Schema, to match the
+//! specifications' intent:
+//!
+//! - `oneOf` accepts a value matching several alternatives, as alternatives often only differ
+//!   by optional fields, e.g. `PENDING_TXN_RECEIPT`.
+//! - `additionalProperties: false` permits properties declared by `allOf` alternatives, and not
+//!   only by sibling `properties`.
+//! - `additionalProperties: false` permits pathfinder's [EXTENSIONS] of the specifications.
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use pathfinder_common::macro_prelude::*;
+use serde_json::{json, Value};
+
+use crate::context::RpcContext;
+use crate::{DefaultVersion, RpcServer};
+
+/// Properties which pathfinder adds to results in addition to those of the specifications.
+const EXTENSIONS: &[&str] = &[
+    // Of pending blocks.
+    "candidate_transactions",
+];
+
+/// Methods which aren't queried, with the reason why.
+const EXCLUDED: &[(&str, &str)] = &[
+    ("starknet_call", "requires executable contracts"),
+    ("starknet_estimateFee", "requires executable contracts"),
+    (
+        "starknet_estimateMessageFee",
+        "requires executable contracts",
+    ),
+    (
+        "starknet_simulateTransaction",
+        "requires executable contracts",
+    ),
+    (
+        "starknet_simulateTransactions",
+        "requires executable contracts",
+    ),
+    ("starknet_traceTransaction", "requires executable contracts"),
+    (
+        "starknet_traceBlockTransactions",
+        "requires executable contracts",
+    ),
+    ("starknet_addInvokeTransaction", "submits to the gateway"),
+    ("starknet_addDeclareTransaction", "submits to the gateway"),
+    (
+        "starknet_addDeployAccountTransaction",
+        "submits to the gateway",
+    ),
+    ("pathfinder_version", "only served by the pathfinder API"),
+    ("pathfinder_getTransactionStatus", "queries the gateway"),
+    (
+        "pathfinder_getSubmittedTransactionStatus",
+        "queries the gateway",
+    ),
+    (
+        "pathfinder_prepareDeclare",
+        "requires a Sierra class to compile",
+    ),
+];
+
+/// The requests queried for each method, each of which must succeed.
+///
+/// Requests for methods that are not part of a version's specification are skipped.
+fn requests() -> Vec<(&'static str, Value)> {
+    let genesis = block_hash_bytes!(b"genesis");
+    let contract = contract_address_bytes!(b"contract 1");
+    let sierra_contract = contract_address_bytes!(b"contract 2 (sierra)");
+    let class = class_hash_bytes!(b"class 0 hash");
+    let sierra_class = class_hash_bytes!(b"class 2 hash (sierra)");
+    let key = storage_address_bytes!(b"storage addr 0");
+    let transactions = [
+        transaction_hash_bytes!(b"txn 0"),
+        transaction_hash_bytes!(b"txn 6"),
+        transaction_hash_bytes!(b"txn reverted"),
+        transaction_hash_bytes!(b"pending tx hash 0"),
+        transaction_hash_bytes!(b"pending tx hash 1"),
+        transaction_hash_bytes!(b"pending reverted"),
+    ];
+    let blocks = [
+        json!({ "block_hash": genesis }),
+        json!({ "block_number": 1 }),
+        json!("latest"),
+        json!("pending"),
+    ];
+
+    let mut requests = vec![
+        ("starknet_specVersion", json!([])),
+        ("starknet_blockNumber", json!([])),
+        ("starknet_blockHashAndNumber", json!([])),
+        ("starknet_chainId", json!([])),
+        ("starknet_syncing", json!([])),
+        ("starknet_pendingTransactions", json!([])),
+        (
+            "starknet_getStorageAt",
+            json!({ "contract_address": contract, "key": key, "block_id": "latest" }),
+        ),
+        (
+            "starknet_getNonce",
+            json!({ "block_id": "latest", "contract_address": contract }),
+        ),
+        (
+            "starknet_getClassHashAt",
+            json!({ "block_id": "latest", "contract_address": contract }),
+        ),
+        (
+            "starknet_getTransactionByBlockIdAndIndex",
+            json!({ "block_id": "latest", "index": 0 }),
+        ),
+        (
+            "starknet_getEvents",
+            json!({ "filter": { "chunk_size": 100 } }),
+        ),
+        (
+            "pathfinder_getProof",
+            json!({ "block_id": "latest", "contract_address": contract, "keys": [key] }),
+        ),
+        (
+            "pathfinder_getClassDeclarationBlock",
+            json!({ "class_hash": sierra_class }),
+        ),
+        (
+            "pathfinder_getContractDeployment",
+            json!({ "contract_address": contract }),
+        ),
+        ("pathfinder_getMessagesToL1", json!({ "chunk_size": 100 })),
+        ("pathfinder_getChainStats", json!([])),
+        (
+            "pathfinder_getTransactionsBySender",
+            json!({ "sender_address": contract, "chunk_size": 100 }),
+        ),
+        (
+            "pathfinder_getNonceHistory",
+            json!({ "contract_address": contract, "chunk_size": 100 }),
+        ),
+        (
+            "pathfinder_getFirstBlockWithNonce",
+            json!({ "contract_address": contract, "nonce": "0x1" }),
+        ),
+        ("pathfinder_getBlockSyncTimings", json!({ "limit": 10 })),
+    ];
+
+    for block in blocks {
+        requests.extend([
+            (
+                "starknet_getBlockWithTxHashes",
+                json!({ "block_id": block }),
+            ),
+            ("starknet_getBlockWithTxs", json!({ "block_id": block })),
+            ("starknet_getStateUpdate", json!({ "block_id": block })),
+            (
+                "starknet_getBlockTransactionCount",
+                json!({ "block_id": block }),
+            ),
+        ]);
+    }
+
+    for transaction in transactions {
+        requests.extend([
+            (
+                "starknet_getTransactionByHash",
+                json!({ "transaction_hash": transaction }),
+            ),
+            (
+                "starknet_getTransactionReceipt",
+                json!({ "transaction_hash": transaction }),
+            ),
+            (
+                "starknet_getTransactionStatus",
+                json!({ "transaction_hash": transaction }),
+            ),
+        ]);
+    }
+
+    for class in [class, sierra_class] {
+        requests.push((
+            "starknet_getClass",
+            json!({ "block_id": "latest", "class_hash": class }),
+        ));
+    }
+
+    for contract in [contract, sierra_contract] {
+        requests.push((
+            "starknet_getClassAt",
+            json!({ "block_id": "latest", "contract_address": contract }),
+        ));
+    }
+
+    requests
+}
+
+/// The specification documents of a version, by file name.
+struct Specification {
+    documents: HashMap<String, Value>,
+}
+
+impl Specification {
+    /// Loads the documents of `doc/rpc/<version>`, and the version independent pathfinder
+    /// extensions.
+    fn load(version: &str) -> Self {
+        let directory = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("..")
+            .join("doc")
+            .join("rpc");
+
+        let files = std::fs::read_dir(directory.join(version))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .chain(std::iter::once(directory.join("pathfinder_rpc_api.json")));
+
+        let documents = files
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                let document = std::fs::read(&path).unwrap();
+                let document = serde_json::from_slice::<Value>(&document)
+                    .unwrap_or_else(|e| panic!("Parsing {}: {e}", path.display()));
+                (name, document)
+            })
+            .collect();
+
+        Self { documents }
+    }
+
+    /// The methods of all documents, with the name of the document they are specified in.
+    fn methods(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.documents.iter().flat_map(|(file, document)| {
+            document["methods"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(move |method| (file.as_str(), method))
+        })
+    }
+
+    /// Resolves a `$ref` relative to the document it occurs in.
+    ///
+    /// References to other documents are resolved by file name only, as the specifications
+    /// don't agree on their relative location.
+    fn resolve<'a>(&'a self, file: &'a str, reference: &str) -> (&'a str, &'a Value) {
+        let (path, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+
+        let file = match path.rsplit('/').next() {
+            Some(name) if !name.is_empty() => self
+                .documents
+                .get_key_value(name)
+                .unwrap_or_else(|| panic!("Unknown document in reference {reference}"))
+                .0
+                .as_str(),
+            _ => file,
+        };
+
+        let schema = self.documents[file]
+            .pointer(pointer)
+            .unwrap_or_else(|| panic!("Unresolved reference {reference} in {file}"));
+
+        (file, schema)
+    }
+
+    /// Validates the value against the schema, returning the violations found.
+    fn validate(&self, file: &str, schema: &Value, value: &Value, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let (file, schema) = self.resolve(file, reference);
+            errors.extend(self.validate(file, schema, value, path));
+        }
+
+        for schema in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            errors.extend(self.validate(file, schema, value, path));
+        }
+
+        if let Some(alternatives) = schema.get("oneOf").and_then(Value::as_array) {
+            let violations = alternatives
+                .iter()
+                .map(|schema| self.validate(file, schema, value, path))
+                .collect::<Vec<_>>();
+
+            if violations.iter().all(|violations| !violations.is_empty()) {
+                errors.push(format!(
+                    "{path}: matches none of the alternatives {violations:#?}"
+                ));
+            }
+        }
+
+        if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+            let matches = match expected {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "string" => value.is_string(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "boolean" => value.is_boolean(),
+                "null" => value.is_null(),
+                other => panic!("Unsupported type {other} in {file}"),
+            };
+
+            if !matches {
+                errors.push(format!("{path}: expected {expected}, got {value}"));
+                return errors;
+            }
+        }
+
+        if let Some(variants) = schema.get("enum").and_then(Value::as_array) {
+            if !variants.contains(value) {
+                errors.push(format!("{path}: {value} is not one of {variants:?}"));
+            }
+        }
+
+        if let (Some(pattern), Some(string)) = (
+            schema.get("pattern").and_then(Value::as_str),
+            value.as_str(),
+        ) {
+            if !regex::Regex::new(pattern).unwrap().is_match(string) {
+                errors.push(format!("{path}: {string} does not match {pattern}"));
+            }
+        }
+
+        if let (Some(minimum), Some(number)) = (
+            schema.get("minimum").and_then(Value::as_f64),
+            value.as_f64(),
+        ) {
+            if number < minimum {
+                errors.push(format!("{path}: {number} is less than {minimum}"));
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            for field in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(field) {
+                    errors.push(format!("{path}: missing field {field}"));
+                }
+            }
+
+            for (name, schema) in schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(value) = object.get(name) {
+                    errors.extend(self.validate(file, schema, value, &format!("{path}.{name}")));
+                }
+            }
+
+            if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                let mut declared = HashSet::new();
+                self.declared_properties(file, schema, &mut declared);
+
+                for name in object.keys() {
+                    if !declared.contains(name.as_str()) && !EXTENSIONS.contains(&name.as_str()) {
+                        errors.push(format!("{path}: unexpected field {name}"));
+                    }
+                }
+            }
+        }
+
+        if let (Some(schema), Some(items)) = (schema.get("items"), value.as_array()) {
+            for (i, item) in items.iter().enumerate() {
+                errors.extend(self.validate(file, schema, item, &format!("{path}[{i}]")));
+            }
+        }
+
+        errors
+    }
+
+    /// The properties declared by the schema, including those of its references and
+    /// alternatives.
+    fn declared_properties<'a>(
+        &'a self,
+        file: &'a str,
+        schema: &'a Value,
+        declared: &mut HashSet<&'a str>,
+    ) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let (file, schema) = self.resolve(file, reference);
+            self.declared_properties(file, schema, declared);
+        }
+
+        for key in ["allOf", "oneOf"] {
+            for schema in schema
+                .get(key)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                self.declared_properties(file, schema, declared);
+            }
+        }
+
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            declared.extend(properties.keys().map(String::as_str));
+        }
+    }
+}
+
+#[rstest::rstest]
+#[case::v03("v03", "/rpc/v0.3")]
+#[case::v04("v04", "/rpc/v0.4")]
+#[case::v05("v05", "/rpc/v0.5")]
+#[tokio::test]
+async fn responses_match_specification(#[case] version: &str, #[case] route: &str) {
+    let specification = Specification::load(version);
+    let requests = requests();
+
+    let uncovered = specification
+        .methods()
+        .map(|(_, method)| method["name"].as_str().unwrap())
+        .filter(|name| {
+            !requests.iter().any(|(method, _)| method == name)
+                && !EXCLUDED.iter().any(|(method, _)| method == name)
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        uncovered.is_empty(),
+        "{uncovered:?} have neither fixture requests nor an exclusion"
+    );
+
+    let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let context = RpcContext::for_tests_with_pending().await;
+    let (_jh, addr) = RpcServer::new(addr, context, DefaultVersion::V05)
+        .spawn()
+        .unwrap();
+    let url = format!("http://{addr}{route}");
+    let client = reqwest::Client::new();
+
+    let mut failures = Vec::new();
+    for (name, params) in requests {
+        let Some((file, method)) = specification
+            .methods()
+            .find(|(_, method)| method["name"] == name)
+        else {
+            continue;
+        };
+
+        let response: Value = client
+            .post(&url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 0, "method": name, "params": params }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let Some(result) = response.get("result") else {
+            failures.push(format!("{name}({params}): {}", response["error"]));
+            continue;
+        };
+
+        let mut descriptor = &method["result"];
+        let mut file = file;
+        if let Some(reference) = descriptor.get("$ref").and_then(Value::as_str) {
+            (file, descriptor) = specification.resolve(file, reference);
+        }
+
+        let errors = specification.validate(file, &descriptor["schema"], result, "result");
+        if !errors.is_empty() {
+            failures.push(format!("{name}({params}): {errors:#?}"));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Responses don't match the {version} specification: {failures:#?}"
+    );
+}
@@ -0,0 +1,64 @@
+//! The params and results of the RPC methods which are shared with clients such as
+//! `pathfinder-rpc-client`, so that they don't need to redefine them.
+//!
+//! Params can only be serialized, and results only be deserialized, with the `rpc-full-serde`
+//! feature.
+
+pub use crate::dto::{
+    Event, ExecutionResourcesProperties, ExecutionStatus, FinalityStatus, MessageToL1, Receipt,
+    ReceiptBlock, ReceiptKind, Transaction, TransactionWithHash,
+};
+pub use crate::v02::method::block_hash_and_number::BlockHashAndNumber;
+pub use crate::v02::types::reply::BlockStatus;
+pub use crate::v02::types::request::{
+    BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
+    BroadcastedInvokeTransaction, BroadcastedTransaction,
+};
+pub use crate::v02::types::{CairoContractClass, ContractClass, SierraContractClass};
+pub use crate::v03::method::get_events::types::{EmittedEvent, GetEventsResult};
+pub use crate::v03::method::get_events::EventFilter;
+pub use crate::v03::method::get_state_update::types::StateUpdate;
+pub use crate::v04::method::add_declare_transaction::AddDeclareTransactionOutput;
+pub use crate::v04::method::add_deploy_account_transaction::AddDeployAccountTransactionOutput;
+pub use crate::v04::method::add_invoke_transaction::AddInvokeTransactionOutput;
+pub use crate::v04::method::syncing::{SyncingOutput, SyncingStatus};
+pub use crate::v05::method::call::FunctionCall;
+pub use crate::v05::method::estimate_fee::FeeEstimate;
+pub use crate::v05::method::estimate_message_fee::MsgFromL1;
+pub use crate::v05::method::get_block_with_tx_hashes::types::Block as BlockWithTxHashes;
+pub use crate::v05::method::get_block_with_txs::types::Block as BlockWithTxs;
+pub use crate::v05::method::get_transaction_status::{
+    ExecutionStatus as TransactionExecutionStatus, GetTransactionStatusOutput,
+};
+pub use crate::v05::method::simulate_transactions::dto::{
+    CallType, DeclareTxnTrace, DeployAccountTxnTrace, EntryPointType, ExecuteInvocation,
+    FunctionInvocation, InvokeTxnTrace, L1HandlerTxnTrace, OrderedEvent, OrderedMsgToL1,
+    SimulatedTransaction, SimulationFlag, TransactionTrace,
+};
+pub use crate::v05::method::trace_block_transactions::Trace;
+pub use crate::v05::types::{BlockHeader, L1Acceptance, ResourcePrice};
+
+/// The params and results of the `pathfinder_*` methods.
+pub mod pathfinder {
+    pub use crate::pathfinder::methods::get_block_sync_timings::{
+        BlockSyncTimings, GetBlockSyncTimingsInput,
+    };
+    pub use crate::pathfinder::methods::get_chain_stats::GetChainStatsOutput;
+    pub use crate::pathfinder::methods::get_class_declaration_block::GetClassDeclarationBlockOutput;
+    pub use crate::pathfinder::methods::get_contract_deployment::GetContractDeploymentOutput;
+    pub use crate::pathfinder::methods::get_messages_to_l1::{
+        GetMessagesToL1Input, GetMessagesToL1Output, MessageToL1,
+    };
+    pub use crate::pathfinder::methods::get_nonce_history::{
+        GetNonceHistoryInput, GetNonceHistoryOutput, NonceUpdate,
+    };
+    pub use crate::pathfinder::methods::get_proof::{ContractData, GetProofOutput, ProofNodes};
+    pub use crate::pathfinder::methods::get_submitted_transaction_status::{
+        GetSubmittedTransactionStatusOutput, SubmittedTransactionStatus,
+    };
+    pub use crate::pathfinder::methods::get_transaction_status::TransactionStatus;
+    pub use crate::pathfinder::methods::get_transactions_by_sender::{
+        GetTransactionsBySenderInput, GetTransactionsBySenderOutput, SenderTransaction,
+    };
+    pub use crate::pathfinder::methods::prepare_declare::PrepareDeclareOutput;
+}
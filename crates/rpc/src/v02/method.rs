@@ -1,7 +1,7 @@
 mod add_declare_transaction;
 mod add_deploy_account_transaction;
 mod add_invoke_transaction;
-mod block_hash_and_number;
+pub(crate) mod block_hash_and_number;
 pub(crate) mod call;
 mod chain_id;
 mod get_block;
@@ -1,7 +1,7 @@
 use crate::context::RpcContext;
 use crate::felt::RpcFelt;
 use crate::v02::types::request::BroadcastedInvokeTransaction;
-use pathfinder_common::TransactionHash;
+use pathfinder_common::{Fee, TransactionHash};
 use starknet_gateway_client::GatewayApi;
 use starknet_gateway_types::error::{SequencerError, StarknetError};
 
@@ -13,7 +13,6 @@ pub enum Transaction {
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct AddInvokeTransactionInput {
     invoke_transaction: Transaction,
 }
@@ -56,6 +55,7 @@ pub async fn add_invoke_transaction(
                 v0.contract_address,
                 Some(v0.entry_point_selector),
                 v0.calldata,
+                None,
             )
             .await
             .map_err(|e| match e {
@@ -73,14 +73,39 @@ pub async fn add_invoke_transaction(
                 v1.sender_address,
                 None,
                 v1.calldata,
+                None,
             )
             .await
             .map_err(|e| match e {
                 SequencerError::StarknetError(e) => AddInvokeTransactionError::GatewayError(e),
                 other => AddInvokeTransactionError::Internal(other.into()),
             })?,
+
+        BroadcastedInvokeTransaction::V3(v3) => {
+            let v3_properties = v3.v3_properties();
+            context
+                .sequencer
+                .add_invoke_transaction(
+                    v3.version,
+                    Fee::ZERO,
+                    v3.signature,
+                    Some(v3.nonce),
+                    v3.sender_address,
+                    None,
+                    v3.calldata,
+                    Some(v3_properties),
+                )
+                .await
+                .map_err(|e| match e {
+                    SequencerError::StarknetError(e) => AddInvokeTransactionError::GatewayError(e),
+                    other => AddInvokeTransactionError::Internal(other.into()),
+                })?
+        }
     };
 
+    crate::pathfinder::methods::record_submitted_transaction(&context, response.transaction_hash)
+        .await;
+
     Ok(AddInvokeTransactionOutput {
         transaction_hash: response.transaction_hash,
     })
@@ -6,6 +6,7 @@ use pathfinder_storage::BlockId;
 
 #[serde_with::serde_as]
 #[derive(serde::Serialize)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct BlockHashAndNumber {
     #[serde_as(as = "RpcFelt")]
     pub block_hash: BlockHash,
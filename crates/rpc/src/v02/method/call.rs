@@ -12,6 +12,9 @@ impl From<crate::v05::method::call::CallError> for CallError {
             crate::v05::method::call::CallError::ContractErrorV05 { revert_error } => {
                 Self::Custom(anyhow::anyhow!("Transaction reverted: {}", revert_error))
             }
+            crate::v05::method::call::CallError::ExecutionResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             crate::v05::method::call::CallError::Custom(e) => Self::Custom(e),
         }
     }
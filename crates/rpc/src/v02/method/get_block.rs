@@ -1,4 +1,5 @@
 use crate::context::RpcContext;
+use crate::dto::{IntoRpcVersioned, RpcVersion, TransactionWithHash};
 use crate::v02::types::reply::BlockStatus;
 use anyhow::Context;
 use pathfinder_common::{BlockId, BlockNumber};
@@ -6,7 +7,6 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[cfg_attr(test, derive(Copy, Clone))]
-#[serde(deny_unknown_fields)]
 pub struct GetBlockInput {
     block_id: BlockId,
 }
@@ -114,15 +114,16 @@ fn get_block_transactions(
         types::BlockResponseScope::FullTransactions => Ok(types::Transactions::Full(
             transactions_receipts
                 .into_iter()
-                .map(|(t, _)| t.into())
+                .map(|(t, _)| TransactionWithHash::from(t).into_versioned(RpcVersion::V03))
                 .collect(),
         )),
     }
 }
 
 mod types {
+    use crate::dto::{IntoRpcVersioned, RpcVersion, TransactionWithHash, Versioned};
     use crate::felt::RpcFelt;
-    use crate::v02::types::reply::{BlockStatus, Transaction};
+    use crate::v02::types::reply::BlockStatus;
     use pathfinder_common::{
         BlockHash, BlockHeader, BlockNumber, BlockTimestamp, SequencerAddress, StateCommitment,
         TransactionHash,
@@ -144,7 +145,7 @@ mod types {
     #[serde(deny_unknown_fields)]
     #[serde(untagged)]
     pub enum Transactions {
-        Full(Vec<Transaction>),
+        Full(Vec<Versioned<TransactionWithHash>>),
         HashesOnly(TransactionHashes),
     }
 
@@ -214,7 +215,13 @@ mod types {
                     Transactions::HashesOnly(hashes)
                 }
                 BlockResponseScope::FullTransactions => {
-                    let transactions = block.transactions().iter().map(|t| t.into()).collect();
+                    let transactions = block
+                        .transactions()
+                        .iter()
+                        .map(|t| {
+                            TransactionWithHash::from(t.clone()).into_versioned(RpcVersion::V03)
+                        })
+                        .collect();
                     Transactions::Full(transactions)
                 }
             };
@@ -3,7 +3,6 @@ use anyhow::Context;
 use pathfinder_common::BlockId;
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetBlockTransactionCountInput {
     block_id: BlockId,
 }
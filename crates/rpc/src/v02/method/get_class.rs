@@ -6,7 +6,6 @@ use pathfinder_common::{BlockId, ClassHash};
 crate::error::generate_rpc_error_subset!(GetClassError: BlockNotFound, ClassHashNotFound);
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetClassInput {
     block_id: BlockId,
     class_hash: ClassHash,
@@ -57,6 +56,14 @@ pub async fn get_class(
         }
         .context("Fetching class definition")?;
 
+        // The local blocks of a fork are built on top of the remote network's state.
+        let definition = match (definition, &context.remote_state) {
+            (None, Some(remote_state)) => remote_state
+                .class_definition(input.class_hash)
+                .context("Fetching remote class definition")?,
+            (definition, _) => definition,
+        };
+
         let Some(definition) = definition else {
             return Err(GetClassError::ClassHashNotFound);
         };
@@ -376,4 +383,32 @@ mod tests {
         .unwrap_err();
         assert_matches!(error, GetClassError::BlockNotFound);
     }
+
+    #[tokio::test]
+    async fn falls_back_to_remote_state() {
+        let context = RpcContext::for_tests()
+            .with_remote_state(std::sync::Arc::new(crate::test_utils::FixedRemoteState));
+
+        let class = super::get_class(
+            context.clone(),
+            GetClassInput {
+                block_id: BlockId::Latest,
+                class_hash: class_hash_bytes!(b"remote class"),
+            },
+        )
+        .await
+        .unwrap();
+        assert_matches!(class, ContractClass::Cairo(_));
+
+        let error = super::get_class(
+            context,
+            GetClassInput {
+                block_id: BlockId::Latest,
+                class_hash: class_hash_bytes!(b"undeclared"),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_matches!(error, GetClassError::ClassHashNotFound);
+    }
 }
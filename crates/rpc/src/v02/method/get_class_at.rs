@@ -6,7 +6,6 @@ use pathfinder_common::{BlockId, ContractAddress};
 crate::error::generate_rpc_error_subset!(GetClassAtError: BlockNotFound, ContractNotFound);
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetClassAtInput {
     block_id: BlockId,
     contract_address: ContractAddress,
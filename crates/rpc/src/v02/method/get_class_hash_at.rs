@@ -6,7 +6,6 @@ use pathfinder_common::{BlockId, ClassHash, ContractAddress};
 crate::error::generate_rpc_error_subset!(GetClassHashAtError: BlockNotFound, ContractNotFound);
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetClassHashAtInput {
     block_id: BlockId,
     contract_address: ContractAddress,
@@ -54,8 +53,19 @@ pub async fn get_class_hash_at(
             return Err(GetClassHashAtError::BlockNotFound);
         }
 
-        tx.contract_class_hash(block_id, input.contract_address)
-            .context("Fetching class hash from database")?
+        let class_hash = tx
+            .contract_class_hash(block_id, input.contract_address)
+            .context("Fetching class hash from database")?;
+
+        // The local blocks of a fork are built on top of the remote network's state.
+        let class_hash = match (class_hash, &context.remote_state) {
+            (None, Some(remote_state)) => remote_state
+                .contract_class_hash(input.contract_address)
+                .context("Querying remote contract class hash")?,
+            (class_hash, _) => class_hash,
+        };
+
+        class_hash
             .ok_or(GetClassHashAtError::ContractNotFound)
             .map(GetClassHashOutput)
     });
@@ -230,4 +240,25 @@ mod tests {
         let result = get_class_hash_at(context, input).await;
         assert_matches!(result, Err(GetClassHashAtError::ContractNotFound));
     }
+
+    #[tokio::test]
+    async fn falls_back_to_remote_state() {
+        let context = RpcContext::for_tests()
+            .with_remote_state(std::sync::Arc::new(crate::test_utils::FixedRemoteState));
+
+        let input = GetClassHashAtInput {
+            block_id: BlockId::Latest,
+            contract_address: contract_address_bytes!(b"remote contract"),
+        };
+        let result = get_class_hash_at(context.clone(), input).await.unwrap();
+        assert_eq!(result.0, class_hash_bytes!(b"remote class"));
+
+        // Contracts deployed locally take precedence.
+        let input = GetClassHashAtInput {
+            block_id: BlockId::Latest,
+            contract_address: contract_address_bytes!(b"contract 0"),
+        };
+        let result = get_class_hash_at(context, input).await.unwrap();
+        assert_eq!(result.0, class_hash_bytes!(b"class 0 hash"));
+    }
 }
@@ -4,7 +4,6 @@ use anyhow::Context;
 use pathfinder_common::{BlockId, ContractAddress, ContractNonce};
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetNonceInput {
     block_id: BlockId,
     contract_address: ContractAddress,
@@ -68,6 +67,21 @@ pub async fn get_nonce(
             .contract_exists(contract_address, block_id)
             .context("Checking contract exists")?;
 
+        // The local blocks of a fork are built on top of the remote network's state, which holds
+        // the nonces of the contracts the local blocks did not update.
+        if let Some(remote_state) = &context.remote_state {
+            let remote_contract_exists = remote_state
+                .contract_class_hash(contract_address)
+                .context("Querying remote contract class hash")?
+                .is_some();
+            if contract_exists || remote_contract_exists {
+                let nonce = remote_state
+                    .contract_nonce(contract_address)
+                    .context("Querying remote contract nonce")?;
+                return Ok(GetNonceOutput(nonce));
+            }
+        }
+
         if contract_exists {
             Ok(GetNonceOutput(ContractNonce::ZERO))
         } else {
@@ -233,4 +247,24 @@ mod tests {
         let nonce = get_nonce(context, input).await.unwrap();
         assert_eq!(nonce.0, ContractNonce::ZERO);
     }
+
+    #[tokio::test]
+    async fn falls_back_to_remote_state() {
+        let context = RpcContext::for_tests()
+            .with_remote_state(std::sync::Arc::new(crate::test_utils::FixedRemoteState));
+
+        let input = GetNonceInput {
+            block_id: BlockId::Latest,
+            contract_address: contract_address_bytes!(b"remote contract"),
+        };
+        let nonce = get_nonce(context.clone(), input).await.unwrap();
+        assert_eq!(nonce.0, contract_nonce!("0x7"));
+
+        let input = GetNonceInput {
+            block_id: BlockId::Latest,
+            contract_address: contract_address_bytes!(b"undeployed"),
+        };
+        let result = get_nonce(context, input).await;
+        assert_matches::assert_matches!(result, Err(GetNonceError::ContractNotFound));
+    }
 }
@@ -5,7 +5,6 @@ use pathfinder_common::{BlockId, ContractAddress, StorageAddress, StorageValue};
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetStorageAtInput {
     pub contract_address: ContractAddress,
     pub key: StorageAddress,
@@ -60,16 +59,32 @@ pub async fn get_storage_at(
             .storage_value(block_id, input.contract_address, input.key)
             .context("Querying storage value")?;
 
-        match value {
-            Some(value) => Ok(GetStorageOutput(value)),
-            None => {
-                if tx.contract_exists(input.contract_address, block_id)? {
-                    Ok(GetStorageOutput(StorageValue::ZERO))
-                } else {
-                    Err(GetStorageAtError::ContractNotFound)
-                }
+        if let Some(value) = value {
+            return Ok(GetStorageOutput(value));
+        }
+
+        let contract_exists = tx.contract_exists(input.contract_address, block_id)?;
+
+        // The local blocks of a fork are built on top of the remote network's state, which holds
+        // the storage the local blocks did not write.
+        if let Some(remote_state) = &context.remote_state {
+            let remote_contract_exists = remote_state
+                .contract_class_hash(input.contract_address)
+                .context("Querying remote contract class hash")?
+                .is_some();
+            if contract_exists || remote_contract_exists {
+                let value = remote_state
+                    .storage_value(input.contract_address, input.key)
+                    .context("Querying remote storage value")?;
+                return Ok(GetStorageOutput(value));
             }
         }
+
+        if contract_exists {
+            Ok(GetStorageOutput(StorageValue::ZERO))
+        } else {
+            Err(GetStorageAtError::ContractNotFound)
+        }
     });
 
     jh.await.context("Database read panic or shutting down")?
@@ -329,4 +344,46 @@ mod tests {
 
         assert_matches!(result, Err(GetStorageAtError::BlockNotFound));
     }
+
+    #[tokio::test]
+    async fn falls_back_to_remote_state() {
+        let ctx = RpcContext::for_tests()
+            .with_remote_state(std::sync::Arc::new(crate::test_utils::FixedRemoteState));
+
+        let result = get_storage_at(
+            ctx.clone(),
+            GetStorageAtInput {
+                contract_address: contract_address_bytes!(b"remote contract"),
+                key: storage_address_bytes!(b"remote key"),
+                block_id: BlockId::Latest,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.0, storage_value_bytes!(b"remote value"));
+
+        // Values stored locally take precedence.
+        let result = get_storage_at(
+            ctx.clone(),
+            GetStorageAtInput {
+                contract_address: contract_address_bytes!(b"contract 1"),
+                key: storage_address_bytes!(b"storage addr 0"),
+                block_id: BlockId::Latest,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.0, storage_value_bytes!(b"storage value 2"));
+
+        let result = get_storage_at(
+            ctx,
+            GetStorageAtInput {
+                contract_address: contract_address_bytes!(b"undeployed"),
+                key: storage_address_bytes!(b"remote key"),
+                block_id: BlockId::Latest,
+            },
+        )
+        .await;
+        assert_matches!(result, Err(GetStorageAtError::ContractNotFound));
+    }
 }
@@ -1,11 +1,10 @@
 use crate::context::RpcContext;
-use crate::v02::types::reply::Transaction;
+use crate::dto::{IntoRpcVersioned, RpcVersion, TransactionWithHash, Versioned};
 use anyhow::Context;
 use pathfinder_common::{BlockId, TransactionIndex};
 use starknet_gateway_types::reply::transaction::Transaction as GatewayTransaction;
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetTransactionByBlockIdAndIndexInput {
     block_id: BlockId,
     index: TransactionIndex,
@@ -42,7 +41,7 @@ pub async fn get_transaction_by_block_id_and_index_impl(
                 let result = context
                     .pending_data
                     .get(&db_tx)
-                    .context("Querying pending dat")?
+                    .context("Querying pending data")?
                     .block
                     .transactions
                     .get(index)
@@ -80,10 +79,10 @@ pub async fn get_transaction_by_block_id_and_index_impl(
 pub async fn get_transaction_by_block_id_and_index(
     context: RpcContext,
     input: GetTransactionByBlockIdAndIndexInput,
-) -> Result<Transaction, GetTransactionByBlockIdAndIndexError> {
+) -> Result<Versioned<TransactionWithHash>, GetTransactionByBlockIdAndIndexError> {
     get_transaction_by_block_id_and_index_impl(context, input)
         .await
-        .map(Into::into)
+        .map(|tx| TransactionWithHash::from(tx).into_versioned(RpcVersion::V03))
 }
 
 #[cfg(test)]
@@ -168,6 +167,28 @@ mod tests {
                 Err(GetTransactionByBlockIdAndIndexError::InvalidTxnIndex)
             );
         }
+
+        #[tokio::test]
+        async fn invalid_index_pending() {
+            let context = RpcContext::for_tests_with_pending().await;
+            let pending_tx_count = context
+                .pending_data
+                .get_unchecked()
+                .block
+                .transactions
+                .len();
+            let input = GetTransactionByBlockIdAndIndexInput {
+                block_id: BlockId::Pending,
+                index: TransactionIndex::new_or_panic(pending_tx_count.try_into().unwrap()),
+            };
+
+            let result = get_transaction_by_block_id_and_index(context, input).await;
+
+            assert_matches::assert_matches!(
+                result,
+                Err(GetTransactionByBlockIdAndIndexError::InvalidTxnIndex)
+            );
+        }
     }
 
     #[tokio::test]
@@ -181,7 +202,10 @@ mod tests {
         let result = get_transaction_by_block_id_and_index(context, input)
             .await
             .unwrap();
-        assert_eq!(result.hash(), transaction_hash_bytes!(b"txn 0"));
+        assert_eq!(
+            result.value.transaction_hash,
+            transaction_hash_bytes!(b"txn 0")
+        );
     }
 
     #[tokio::test]
@@ -195,7 +219,10 @@ mod tests {
         let result = get_transaction_by_block_id_and_index(context, input)
             .await
             .unwrap();
-        assert_eq!(result.hash(), transaction_hash_bytes!(b"txn 0"));
+        assert_eq!(
+            result.value.transaction_hash,
+            transaction_hash_bytes!(b"txn 0")
+        );
     }
 
     #[tokio::test]
@@ -209,7 +236,10 @@ mod tests {
         let result = get_transaction_by_block_id_and_index(context, input)
             .await
             .unwrap();
-        assert_eq!(result.hash(), transaction_hash_bytes!(b"txn 3"));
+        assert_eq!(
+            result.value.transaction_hash,
+            transaction_hash_bytes!(b"txn 3")
+        );
     }
 
     #[tokio::test]
@@ -219,8 +249,9 @@ mod tests {
         const TX_IDX: usize = 1;
         let expected = context.pending_data.get_unchecked();
 
-        assert!(TX_IDX <= expected.block.transactions.len());
-        let expected: Transaction = expected.block.transactions.get(TX_IDX).unwrap().into();
+        assert!(TX_IDX < expected.block.transactions.len());
+        let expected = TransactionWithHash::from(expected.block.transactions[TX_IDX].clone())
+            .into_versioned(RpcVersion::V03);
 
         let input = GetTransactionByBlockIdAndIndexInput {
             block_id: BlockId::Pending,
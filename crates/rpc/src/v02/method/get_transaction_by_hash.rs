@@ -1,12 +1,11 @@
 use crate::context::RpcContext;
-use crate::v02::types::reply::Transaction;
+use crate::dto::{IntoRpcVersioned, RpcVersion, TransactionWithHash, Versioned};
 use anyhow::Context;
 use pathfinder_common::TransactionHash;
 
 use starknet_gateway_types::reply::transaction::Transaction as GatewayTransaction;
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetTransactionByHashInput {
     transaction_hash: TransactionHash,
 }
@@ -54,10 +53,10 @@ pub async fn get_transaction_by_hash_impl(
 pub async fn get_transaction_by_hash(
     context: RpcContext,
     input: GetTransactionByHashInput,
-) -> Result<Transaction, GetTransactionByHashError> {
+) -> Result<Versioned<TransactionWithHash>, GetTransactionByHashError> {
     get_transaction_by_hash_impl(context, input)
         .await?
-        .map(Into::into)
+        .map(|tx| TransactionWithHash::from(tx).into_versioned(RpcVersion::V03))
         .ok_or(GetTransactionByHashError::TxnHashNotFoundV03)
 }
 
@@ -128,9 +127,8 @@ mod tests {
 
         let result = get_transaction_by_hash(context, input).await.unwrap();
         use crate::v02::types::reply;
-        assert_eq!(
-            result,
-            Transaction::Invoke(reply::InvokeTransaction::V0(reply::InvokeTransactionV0 {
+        let expected =
+            reply::Transaction::Invoke(reply::InvokeTransaction::V0(reply::InvokeTransactionV0 {
                 common: reply::CommonDeclareInvokeTransactionProperties {
                     hash: transaction_hash_bytes!(b"txn 0"),
                     max_fee: Fee::ZERO,
@@ -140,8 +138,12 @@ mod tests {
                 contract_address: contract_address_bytes!(b"contract 0"),
                 entry_point_selector: EntryPoint(Felt::ZERO),
                 calldata: vec![],
-            }))
-        )
+            }));
+        // Served as v0.3, which includes the nonce of v0 invoke transactions.
+        assert_eq!(
+            serde_json::to_value(result).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
     }
 
     #[tokio::test]
@@ -154,9 +156,8 @@ mod tests {
 
         let result = get_transaction_by_hash(context, input).await.unwrap();
         use crate::v02::types::reply;
-        assert_eq!(
-            result,
-            Transaction::Invoke(reply::InvokeTransaction::V0(reply::InvokeTransactionV0 {
+        let expected =
+            reply::Transaction::Invoke(reply::InvokeTransaction::V0(reply::InvokeTransactionV0 {
                 common: reply::CommonDeclareInvokeTransactionProperties {
                     hash: transaction_hash_bytes!(b"pending tx hash 0"),
                     max_fee: Fee::ZERO,
@@ -166,7 +167,10 @@ mod tests {
                 contract_address: contract_address_bytes!(b"pending contract addr 0"),
                 entry_point_selector: entry_point_bytes!(b"entry point 0"),
                 calldata: vec![],
-            }))
-        )
+            }));
+        assert_eq!(
+            serde_json::to_value(result).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
     }
 }
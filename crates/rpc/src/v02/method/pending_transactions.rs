@@ -1,11 +1,13 @@
 use anyhow::Context;
 
 use crate::context::RpcContext;
-use crate::v02::types::reply::Transaction;
+use crate::dto::{IntoRpcVersioned, RpcVersion, TransactionWithHash, Versioned};
 
 crate::error::generate_rpc_error_subset!(GetNonceError:);
 
-pub async fn pending_transactions(context: RpcContext) -> Result<Vec<Transaction>, GetNonceError> {
+pub async fn pending_transactions(
+    context: RpcContext,
+) -> Result<Vec<Versioned<TransactionWithHash>>, GetNonceError> {
     let storage = context.storage.clone();
     let span = tracing::Span::current();
 
@@ -24,7 +26,7 @@ pub async fn pending_transactions(context: RpcContext) -> Result<Vec<Transaction
             .block
             .transactions
             .iter()
-            .map(Transaction::from)
+            .map(|tx| TransactionWithHash::from(tx.clone()).into_versioned(RpcVersion::V03))
             .collect();
 
         Ok(transactions)
@@ -38,7 +40,7 @@ mod tests {
     use super::*;
     use crate::v02::types::reply::{
         CommonDeclareInvokeTransactionProperties, DeployTransaction, InvokeTransaction,
-        InvokeTransactionV0,
+        InvokeTransactionV0, Transaction,
     };
     use pathfinder_common::{macro_prelude::*, Fee};
     use pathfinder_common::{TransactionNonce, TransactionVersion};
@@ -87,6 +89,9 @@ mod tests {
         let context = RpcContext::for_tests_with_pending().await;
         let result = pending_transactions(context).await.unwrap();
 
-        assert_eq!(result, expected);
+        assert_eq!(
+            serde_json::to_value(result).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
     }
 }
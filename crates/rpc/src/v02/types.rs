@@ -3,20 +3,27 @@
 pub(crate) mod class;
 pub use class::*;
 pub mod syncing;
+pub(crate) mod v3;
+pub use v3::*;
 
 /// Groups all strictly input types of the RPC API.
 pub mod request {
     use std::ops::Rem;
 
     use pathfinder_common::{
-        CallParam, CasmHash, ChainId, ClassHash, ContractAddress, ContractAddressSalt, EntryPoint,
-        Fee, TransactionHash, TransactionNonce, TransactionSignatureElem, TransactionVersion,
+        AccountDeploymentDataElem, CallParam, CasmHash, ChainId, ClassHash, ContractAddress,
+        ContractAddressSalt, EntryPoint, Fee, PaymasterDataElem, Tip, TransactionHash,
+        TransactionNonce, TransactionSignatureElem, TransactionVersion,
     };
     use pathfinder_crypto::{hash::HashChain, Felt};
-    use pathfinder_serde::TransactionVersionAsHexStr;
+    use pathfinder_serde::{TipAsHexStr, TransactionVersionAsHexStr};
     use serde::Deserialize;
     use serde_with::serde_as;
-    use starknet_gateway_types::transaction_hash::compute_txn_hash;
+    use starknet_gateway_types::reply::transaction as gateway;
+    use starknet_gateway_types::request::add_transaction::V3Properties;
+    use starknet_gateway_types::transaction_hash::{compute_transaction_hash, compute_txn_hash};
+
+    use super::{DataAvailabilityMode, ResourceBounds};
 
     /// "Broadcasted" L2 transaction in requests the RPC API.
     ///
@@ -76,13 +83,20 @@ pub mod request {
                         BroadcastedDeclareTransaction::V2(tx) => {
                             tx.transaction_hash(chain_id, class_hash)
                         }
+                        BroadcastedDeclareTransaction::V3(tx) => {
+                            tx.transaction_hash(chain_id, class_hash)
+                        }
                     }
                 }
                 BroadcastedTransaction::Invoke(tx) => match tx {
                     BroadcastedInvokeTransaction::V0(tx) => tx.transaction_hash(chain_id),
                     BroadcastedInvokeTransaction::V1(tx) => tx.transaction_hash(chain_id),
+                    BroadcastedInvokeTransaction::V3(tx) => tx.transaction_hash(chain_id),
+                },
+                BroadcastedTransaction::DeployAccount(tx) => match tx {
+                    BroadcastedDeployAccountTransaction::V0V1(tx) => tx.transaction_hash(chain_id),
+                    BroadcastedDeployAccountTransaction::V3(tx) => tx.transaction_hash(chain_id),
                 },
-                BroadcastedTransaction::DeployAccount(tx) => tx.transaction_hash(chain_id),
             }
         }
     }
@@ -97,6 +111,7 @@ pub mod request {
         V0(BroadcastedDeclareTransactionV0),
         V1(BroadcastedDeclareTransactionV1),
         V2(BroadcastedDeclareTransactionV2),
+        V3(BroadcastedDeclareTransactionV3),
     }
 
     impl<'de> serde::Deserialize<'de> for BroadcastedDeclareTransaction {
@@ -125,7 +140,10 @@ pub mod request {
                 2 => Ok(Self::V2(
                     BroadcastedDeclareTransactionV2::deserialize(&v).map_err(de::Error::custom)?,
                 )),
-                _v => Err(de::Error::custom("version must be 0, 1 or 2")),
+                3 => Ok(Self::V3(
+                    BroadcastedDeclareTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _v => Err(de::Error::custom("version must be 0, 1, 2 or 3")),
             }
         }
     }
@@ -256,7 +274,118 @@ pub mod request {
     #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
     #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
     #[serde(deny_unknown_fields)]
-    pub struct BroadcastedDeployAccountTransaction {
+    pub struct BroadcastedDeclareTransactionV3 {
+        #[serde_as(as = "TransactionVersionAsHexStr")]
+        pub version: TransactionVersion,
+        pub signature: Vec<TransactionSignatureElem>,
+        pub nonce: TransactionNonce,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+
+        pub compiled_class_hash: CasmHash,
+        pub contract_class: super::SierraContractClass,
+        pub sender_address: ContractAddress,
+    }
+
+    impl BroadcastedDeclareTransactionV3 {
+        pub fn v3_properties(&self) -> V3Properties {
+            V3Properties {
+                resource_bounds: self.resource_bounds.into(),
+                tip: self.tip,
+                paymaster_data: self.paymaster_data.clone(),
+                nonce_data_availability_mode: self.nonce_data_availability_mode.into(),
+                fee_data_availability_mode: self.fee_data_availability_mode.into(),
+                account_deployment_data: Some(self.account_deployment_data.clone()),
+            }
+        }
+
+        pub fn transaction_hash(
+            &self,
+            chain_id: ChainId,
+            class_hash: ClassHash,
+        ) -> TransactionHash {
+            let transaction = gateway::DeclareTransactionV3 {
+                class_hash,
+                nonce: self.nonce,
+                nonce_data_availability_mode: self.nonce_data_availability_mode.into(),
+                fee_data_availability_mode: self.fee_data_availability_mode.into(),
+                resource_bounds: self.resource_bounds.into(),
+                tip: self.tip,
+                paymaster_data: self.paymaster_data.clone(),
+                sender_address: self.sender_address,
+                signature: self.signature.clone(),
+                transaction_hash: TransactionHash::ZERO,
+                compiled_class_hash: self.compiled_class_hash,
+                account_deployment_data: self.account_deployment_data.clone(),
+            };
+
+            compute_transaction_hash(
+                &gateway::Transaction::Declare(gateway::DeclareTransaction::V3(transaction)),
+                chain_id,
+            )
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(
+        any(test, feature = "rpc-full-serde"),
+        derive(serde::Serialize),
+        serde(untagged)
+    )]
+    pub enum BroadcastedDeployAccountTransaction {
+        V0V1(BroadcastedDeployAccountTransactionV0V1),
+        V3(BroadcastedDeployAccountTransactionV3),
+    }
+
+    impl BroadcastedDeployAccountTransaction {
+        pub fn deployed_contract_address(&self) -> ContractAddress {
+            match self {
+                Self::V0V1(tx) => tx.deployed_contract_address(),
+                Self::V3(tx) => tx.deployed_contract_address(),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for BroadcastedDeployAccountTransaction {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            use serde::de;
+
+            #[serde_as]
+            #[derive(Deserialize)]
+            struct Version {
+                #[serde_as(as = "TransactionVersionAsHexStr")]
+                pub version: TransactionVersion,
+            }
+
+            let v = serde_json::Value::deserialize(deserializer)?;
+            let version = Version::deserialize(&v).map_err(de::Error::custom)?;
+            match version.version.without_query_version() {
+                0 | 1 => Ok(Self::V0V1(
+                    BroadcastedDeployAccountTransactionV0V1::deserialize(&v)
+                        .map_err(de::Error::custom)?,
+                )),
+                3 => Ok(Self::V3(
+                    BroadcastedDeployAccountTransactionV3::deserialize(&v)
+                        .map_err(de::Error::custom)?,
+                )),
+                _ => Err(de::Error::custom("version must be 0, 1 or 3")),
+            }
+        }
+    }
+
+    #[serde_as]
+    #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+    #[serde(deny_unknown_fields)]
+    pub struct BroadcastedDeployAccountTransactionV0V1 {
         // Fields from BROADCASTED_TXN_COMMON_PROPERTIES
         #[serde_as(as = "TransactionVersionAsHexStr")]
         pub version: TransactionVersion,
@@ -270,46 +399,13 @@ pub mod request {
         pub class_hash: ClassHash,
     }
 
-    impl BroadcastedDeployAccountTransaction {
+    impl BroadcastedDeployAccountTransactionV0V1 {
         pub fn deployed_contract_address(&self) -> ContractAddress {
-            let constructor_calldata_hash = self
-                .constructor_calldata
-                .iter()
-                .fold(HashChain::default(), |mut h, param| {
-                    h.update(param.0);
-                    h
-                })
-                .finalize();
-
-            let contract_address = [
-                Felt::from_be_slice(b"STARKNET_CONTRACT_ADDRESS").expect("prefix is convertible"),
-                Felt::ZERO,
-                self.contract_address_salt.0,
-                self.class_hash.0,
-                constructor_calldata_hash,
-            ]
-            .into_iter()
-            .fold(HashChain::default(), |mut h, e| {
-                h.update(e);
-                h
-            })
-            .finalize();
-
-            // Contract addresses are _less than_ 2**251 - 256
-            let contract_address =
-                primitive_types::U256::from_big_endian(contract_address.as_be_bytes());
-            let max_address = primitive_types::U256::from_str_radix(
-                "0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00",
-                16,
+            deployed_contract_address(
+                self.contract_address_salt,
+                self.class_hash,
+                &self.constructor_calldata,
             )
-            .unwrap();
-
-            let contract_address = contract_address.rem(max_address);
-            let mut b = [0u8; 32];
-            contract_address.to_big_endian(&mut b);
-            let contract_address = Felt::from_be_slice(&b).unwrap();
-
-            ContractAddress::new_or_panic(contract_address)
         }
 
         pub fn transaction_hash(&self, chain_id: ChainId) -> TransactionHash {
@@ -341,6 +437,118 @@ pub mod request {
         }
     }
 
+    /// The address of a contract deployed with the given salt, class and constructor calldata.
+    pub fn deployed_contract_address(
+        contract_address_salt: ContractAddressSalt,
+        class_hash: ClassHash,
+        constructor_calldata: &[CallParam],
+    ) -> ContractAddress {
+        let constructor_calldata_hash = constructor_calldata
+            .iter()
+            .fold(HashChain::default(), |mut h, param| {
+                h.update(param.0);
+                h
+            })
+            .finalize();
+
+        let contract_address = [
+            Felt::from_be_slice(b"STARKNET_CONTRACT_ADDRESS").expect("prefix is convertible"),
+            Felt::ZERO,
+            contract_address_salt.0,
+            class_hash.0,
+            constructor_calldata_hash,
+        ]
+        .into_iter()
+        .fold(HashChain::default(), |mut h, e| {
+            h.update(e);
+            h
+        })
+        .finalize();
+
+        // Contract addresses are _less than_ 2**251 - 256
+        let contract_address =
+            primitive_types::U256::from_big_endian(contract_address.as_be_bytes());
+        let max_address = primitive_types::U256::from_str_radix(
+            "0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00",
+            16,
+        )
+        .unwrap();
+
+        let contract_address = contract_address.rem(max_address);
+        let mut b = [0u8; 32];
+        contract_address.to_big_endian(&mut b);
+        let contract_address = Felt::from_be_slice(&b).unwrap();
+
+        ContractAddress::new_or_panic(contract_address)
+    }
+
+    #[serde_as]
+    #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+    #[serde(deny_unknown_fields)]
+    pub struct BroadcastedDeployAccountTransactionV3 {
+        #[serde_as(as = "TransactionVersionAsHexStr")]
+        pub version: TransactionVersion,
+        pub signature: Vec<TransactionSignatureElem>,
+        pub nonce: TransactionNonce,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+
+        pub contract_address_salt: ContractAddressSalt,
+        pub constructor_calldata: Vec<CallParam>,
+        pub class_hash: ClassHash,
+    }
+
+    impl BroadcastedDeployAccountTransactionV3 {
+        pub fn v3_properties(&self) -> V3Properties {
+            V3Properties {
+                resource_bounds: self.resource_bounds.into(),
+                tip: self.tip,
+                paymaster_data: self.paymaster_data.clone(),
+                nonce_data_availability_mode: self.nonce_data_availability_mode.into(),
+                fee_data_availability_mode: self.fee_data_availability_mode.into(),
+                account_deployment_data: None,
+            }
+        }
+
+        pub fn deployed_contract_address(&self) -> ContractAddress {
+            deployed_contract_address(
+                self.contract_address_salt,
+                self.class_hash,
+                &self.constructor_calldata,
+            )
+        }
+
+        pub fn transaction_hash(&self, chain_id: ChainId) -> TransactionHash {
+            let transaction = gateway::DeployAccountTransactionV3 {
+                nonce: self.nonce,
+                nonce_data_availability_mode: self.nonce_data_availability_mode.into(),
+                fee_data_availability_mode: self.fee_data_availability_mode.into(),
+                resource_bounds: self.resource_bounds.into(),
+                tip: self.tip,
+                paymaster_data: self.paymaster_data.clone(),
+                sender_address: self.deployed_contract_address(),
+                signature: self.signature.clone(),
+                transaction_hash: TransactionHash::ZERO,
+                version: self.version,
+                contract_address_salt: self.contract_address_salt,
+                constructor_calldata: self.constructor_calldata.clone(),
+                class_hash: self.class_hash,
+            };
+
+            compute_transaction_hash(
+                &gateway::Transaction::DeployAccount(gateway::DeployAccountTransaction::V3(
+                    transaction,
+                )),
+                chain_id,
+            )
+        }
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq)]
     #[cfg_attr(
         any(test, feature = "rpc-full-serde"),
@@ -350,6 +558,7 @@ pub mod request {
     pub enum BroadcastedInvokeTransaction {
         V0(BroadcastedInvokeTransactionV0),
         V1(BroadcastedInvokeTransactionV1),
+        V3(BroadcastedInvokeTransactionV3),
     }
 
     impl BroadcastedInvokeTransaction {
@@ -391,7 +600,10 @@ pub mod request {
                 1 => Ok(Self::V1(
                     BroadcastedInvokeTransactionV1::deserialize(&v).map_err(de::Error::custom)?,
                 )),
-                _ => Err(de::Error::custom("version must be 0 or 1")),
+                3 => Ok(Self::V3(
+                    BroadcastedInvokeTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _ => Err(de::Error::custom("version must be 0, 1 or 3")),
             }
         }
     }
@@ -482,6 +694,61 @@ pub mod request {
         }
     }
 
+    #[serde_as]
+    #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
+    #[serde(deny_unknown_fields)]
+    pub struct BroadcastedInvokeTransactionV3 {
+        #[serde_as(as = "TransactionVersionAsHexStr")]
+        pub version: TransactionVersion,
+        pub signature: Vec<TransactionSignatureElem>,
+        pub nonce: TransactionNonce,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
+
+        pub sender_address: ContractAddress,
+        pub calldata: Vec<CallParam>,
+    }
+
+    impl BroadcastedInvokeTransactionV3 {
+        pub fn v3_properties(&self) -> V3Properties {
+            V3Properties {
+                resource_bounds: self.resource_bounds.into(),
+                tip: self.tip,
+                paymaster_data: self.paymaster_data.clone(),
+                nonce_data_availability_mode: self.nonce_data_availability_mode.into(),
+                fee_data_availability_mode: self.fee_data_availability_mode.into(),
+                account_deployment_data: Some(self.account_deployment_data.clone()),
+            }
+        }
+
+        pub fn transaction_hash(&self, chain_id: ChainId) -> TransactionHash {
+            let transaction = gateway::InvokeTransactionV3 {
+                nonce: self.nonce,
+                nonce_data_availability_mode: self.nonce_data_availability_mode.into(),
+                fee_data_availability_mode: self.fee_data_availability_mode.into(),
+                resource_bounds: self.resource_bounds.into(),
+                tip: self.tip,
+                paymaster_data: self.paymaster_data.clone(),
+                sender_address: self.sender_address,
+                signature: self.signature.clone(),
+                transaction_hash: TransactionHash::ZERO,
+                calldata: self.calldata.clone(),
+                account_deployment_data: self.account_deployment_data.clone(),
+            };
+
+            compute_transaction_hash(
+                &gateway::Transaction::Invoke(gateway::InvokeTransaction::V3(transaction)),
+                chain_id,
+            )
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         macro_rules! fixture {
@@ -503,11 +770,11 @@ pub mod request {
         mod serde {
             use super::super::*;
             use crate::v02::types::{
-                CairoContractClass, ContractEntryPoints, SierraContractClass, SierraEntryPoint,
-                SierraEntryPoints,
+                CairoContractClass, ContractEntryPoints, DataAvailabilityMode, ResourceBound,
+                ResourceBounds, SierraContractClass, SierraEntryPoint, SierraEntryPoints,
             };
-            use pathfinder_common::felt;
             use pathfinder_common::macro_prelude::*;
+            use pathfinder_common::{felt, ResourceAmount, ResourcePricePerUnit};
             use pretty_assertions::assert_eq;
 
             #[test]
@@ -585,6 +852,27 @@ pub mod request {
                             calldata: vec![call_param!("0xff")],
                         },
                     )),
+                    BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(
+                        BroadcastedInvokeTransactionV3 {
+                            version: TransactionVersion::THREE,
+                            signature: vec![transaction_signature_elem!("0x7")],
+                            nonce: transaction_nonce!("0x8"),
+                            resource_bounds: ResourceBounds {
+                                l1_gas: ResourceBound {
+                                    max_amount: ResourceAmount(0x1111),
+                                    max_price_per_unit: ResourcePricePerUnit(0x2222),
+                                },
+                                l2_gas: Default::default(),
+                            },
+                            tip: Tip(0x1234),
+                            paymaster_data: vec![],
+                            account_deployment_data: vec![],
+                            nonce_data_availability_mode: DataAvailabilityMode::L1,
+                            fee_data_availability_mode: DataAvailabilityMode::L2,
+                            sender_address: contract_address!("0xaaa"),
+                            calldata: vec![call_param!("0xff")],
+                        },
+                    )),
                 ];
 
                 let json_fixture = fixture!("broadcasted_transactions.json");
@@ -729,7 +1017,7 @@ pub mod request {
                 let class_hash = class_hash!(
                     "0x25ec026985a3bf9d0cc1fe17326b245dfdc3ff89b8fde106542a3ea56c5a918"
                 );
-                let tx = BroadcastedDeployAccountTransaction {
+                let tx = BroadcastedDeployAccountTransactionV0V1 {
                     version: TransactionVersion::ONE,
                     max_fee: fee!("0x15e1e7c9a7a0"),
                     signature: vec![
@@ -774,7 +1062,9 @@ pub mod request {
                     )
                 );
 
-                let transaction = BroadcastedTransaction::DeployAccount(tx);
+                let transaction = BroadcastedTransaction::DeployAccount(
+                    BroadcastedDeployAccountTransaction::V0V1(tx),
+                );
                 assert_eq!(
                     transaction.transaction_hash(ChainId::TESTNET, Some(class_hash)),
                     transaction_hash!(
@@ -789,13 +1079,14 @@ pub mod request {
 /// Groups all strictly output types of the RPC API.
 pub mod reply {
     // At the moment both reply types are the same for get_code, hence the re-export
+    use super::{DataAvailabilityMode, ResourceBounds};
     use crate::felt::{RpcFelt, RpcFelt251};
     use pathfinder_common::{
-        CallParam, CasmHash, ClassHash, ConstructorParam, ContractAddress, ContractAddressSalt,
-        EntryPoint, Fee, TransactionHash, TransactionNonce, TransactionSignatureElem,
-        TransactionVersion,
+        AccountDeploymentDataElem, CallParam, CasmHash, ClassHash, ConstructorParam,
+        ContractAddress, ContractAddressSalt, EntryPoint, Fee, PaymasterDataElem, Tip,
+        TransactionHash, TransactionNonce, TransactionSignatureElem, TransactionVersion,
     };
-    use pathfinder_serde::TransactionVersionAsHexStr;
+    use pathfinder_serde::{TipAsHexStr, TransactionVersionAsHexStr};
     use serde::{Deserialize, Serialize};
     use serde_with::serde_as;
     use starknet_gateway_types::reply::transaction::Transaction as GatewayTransaction;
@@ -827,10 +1118,17 @@ pub mod reply {
                 Transaction::Declare(DeclareTransaction::V0(declare)) => declare.common.hash,
                 Transaction::Declare(DeclareTransaction::V1(declare)) => declare.common.hash,
                 Transaction::Declare(DeclareTransaction::V2(declare)) => declare.common.hash,
+                Transaction::Declare(DeclareTransaction::V3(declare)) => declare.common.hash,
                 Transaction::Invoke(InvokeTransaction::V0(invoke)) => invoke.common.hash,
                 Transaction::Invoke(InvokeTransaction::V1(invoke)) => invoke.common.hash,
+                Transaction::Invoke(InvokeTransaction::V3(invoke)) => invoke.common.hash,
                 Transaction::Deploy(deploy) => deploy.hash,
-                Transaction::DeployAccount(deploy_account) => deploy_account.common.hash,
+                Transaction::DeployAccount(DeployAccountTransaction::V0V1(deploy_account)) => {
+                    deploy_account.common.hash
+                }
+                Transaction::DeployAccount(DeployAccountTransaction::V3(deploy_account)) => {
+                    deploy_account.common.hash
+                }
                 Transaction::L1Handler(l1_handler) => l1_handler.hash,
             }
         }
@@ -861,6 +1159,8 @@ pub mod reply {
         V1(DeclareTransactionV0V1),
         #[serde(rename = "0x2")]
         V2(DeclareTransactionV2),
+        #[serde(rename = "0x3")]
+        V3(DeclareTransactionV3),
     }
 
     #[cfg(any(test, feature = "rpc-full-serde"))]
@@ -900,7 +1200,10 @@ pub mod reply {
                 TransactionVersion(x) if x == H256::from_low_u64_be(2) => Ok(Self::V2(
                     DeclareTransactionV2::deserialize(&v).map_err(de::Error::custom)?,
                 )),
-                _v => Err(de::Error::custom("version must be 0, 1 or 2")),
+                TransactionVersion(x) if x == H256::from_low_u64_be(3) => Ok(Self::V3(
+                    DeclareTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _v => Err(de::Error::custom("version must be 0, 1, 2 or 3")),
             }
         }
     }
@@ -939,7 +1242,34 @@ pub mod reply {
     #[serde_as]
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
     #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
-    pub struct DeployAccountTransaction {
+    pub struct DeclareTransactionV3 {
+        #[serde(flatten)]
+        pub common: CommonV3TransactionProperties,
+
+        // DECLARE_TXN_V3
+        #[serde_as(as = "RpcFelt")]
+        pub class_hash: ClassHash,
+        #[serde_as(as = "RpcFelt251")]
+        pub sender_address: ContractAddress,
+        #[serde_as(as = "RpcFelt")]
+        pub compiled_class_hash: CasmHash,
+        #[serde_as(as = "Vec<RpcFelt>")]
+        pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+    }
+
+    /// The version is part of both variants, so there is no need to tag them.
+    #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+    #[serde(untagged)]
+    pub enum DeployAccountTransaction {
+        V0V1(DeployAccountTransactionV0V1),
+        V3(DeployAccountTransactionV3),
+    }
+
+    #[serde_as]
+    #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+    pub struct DeployAccountTransactionV0V1 {
         #[serde(flatten)]
         pub common: CommonTransactionProperties,
 
@@ -952,6 +1282,24 @@ pub mod reply {
         pub class_hash: ClassHash,
     }
 
+    #[serde_as]
+    #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+    pub struct DeployAccountTransactionV3 {
+        #[serde(flatten)]
+        pub common: CommonV3TransactionProperties,
+        #[serde_as(as = "TransactionVersionAsHexStr")]
+        pub version: TransactionVersion,
+
+        // DEPLOY_ACCOUNT_TXN_V3
+        #[serde_as(as = "RpcFelt")]
+        pub contract_address_salt: ContractAddressSalt,
+        #[serde_as(as = "Vec<RpcFelt>")]
+        pub constructor_calldata: Vec<CallParam>,
+        #[serde_as(as = "RpcFelt")]
+        pub class_hash: ClassHash,
+    }
+
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
     #[serde(tag = "version")]
     pub enum InvokeTransaction {
@@ -959,6 +1307,8 @@ pub mod reply {
         V0(InvokeTransactionV0),
         #[serde(rename = "0x1")]
         V1(InvokeTransactionV1),
+        #[serde(rename = "0x3")]
+        V3(InvokeTransactionV3),
     }
 
     #[cfg(any(test, feature = "rpc-full-serde"))]
@@ -996,7 +1346,10 @@ pub mod reply {
                 TransactionVersion(x) if x == H256::from_low_u64_be(1) => Ok(Self::V1(
                     InvokeTransactionV1::deserialize(&v).map_err(de::Error::custom)?,
                 )),
-                _v => Err(de::Error::custom("version must be 0 or 1")),
+                TransactionVersion(x) if x == H256::from_low_u64_be(3) => Ok(Self::V3(
+                    InvokeTransactionV3::deserialize(&v).map_err(de::Error::custom)?,
+                )),
+                _v => Err(de::Error::custom("version must be 0, 1 or 3")),
             }
         }
     }
@@ -1031,6 +1384,22 @@ pub mod reply {
         pub calldata: Vec<CallParam>,
     }
 
+    #[serde_as]
+    #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+    pub struct InvokeTransactionV3 {
+        #[serde(flatten)]
+        pub common: CommonV3TransactionProperties,
+
+        // INVOKE_TXN_V3
+        #[serde_as(as = "RpcFelt251")]
+        pub sender_address: ContractAddress,
+        #[serde_as(as = "Vec<RpcFelt>")]
+        pub calldata: Vec<CallParam>,
+        #[serde_as(as = "Vec<RpcFelt>")]
+        pub account_deployment_data: Vec<AccountDeploymentDataElem>,
+    }
+
     #[serde_as]
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
     #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
@@ -1044,8 +1413,32 @@ pub mod reply {
         pub max_fee: Fee,
         #[serde_as(as = "Vec<RpcFelt>")]
         pub signature: Vec<TransactionSignatureElem>,
+        /// Omitted by later versions for v0 transactions, which have no nonce.
+        #[serde_as(as = "RpcFelt")]
+        #[serde(default)]
+        pub nonce: TransactionNonce,
+    }
+
+    #[serde_as]
+    #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+    // The properties v3 transactions have in common. The `max_fee` of earlier versions is replaced
+    // by the resource bounds.
+    pub struct CommonV3TransactionProperties {
+        #[serde(rename = "transaction_hash")]
+        #[serde_as(as = "RpcFelt")]
+        pub hash: TransactionHash,
+        #[serde_as(as = "Vec<RpcFelt>")]
+        pub signature: Vec<TransactionSignatureElem>,
         #[serde_as(as = "RpcFelt")]
         pub nonce: TransactionNonce,
+        pub resource_bounds: ResourceBounds,
+        #[serde_as(as = "TipAsHexStr")]
+        pub tip: Tip,
+        #[serde_as(as = "Vec<RpcFelt>")]
+        pub paymaster_data: Vec<PaymasterDataElem>,
+        pub nonce_data_availability_mode: DataAvailabilityMode,
+        pub fee_data_availability_mode: DataAvailabilityMode,
     }
 
     #[serde_as]
@@ -1099,6 +1492,7 @@ pub mod reply {
     impl From<&GatewayTransaction> for Transaction {
         fn from(txn: &GatewayTransaction) -> Self {
             use starknet_gateway_types::reply::transaction::DeclareTransaction as GatewayDeclare;
+            use starknet_gateway_types::reply::transaction::DeployAccountTransaction as GatewayDeployAccount;
             match txn {
                 GatewayTransaction::Invoke(txn) => {
                     match txn {
@@ -1128,6 +1522,27 @@ pub mod reply {
                                 calldata: txn.calldata.clone(),
                             }))
                         }
+                        starknet_gateway_types::reply::transaction::InvokeTransaction::V3(txn) => {
+                            Self::Invoke(InvokeTransaction::V3(InvokeTransactionV3 {
+                                common: CommonV3TransactionProperties {
+                                    hash: txn.transaction_hash,
+                                    signature: txn.signature.clone(),
+                                    nonce: txn.nonce,
+                                    resource_bounds: txn.resource_bounds.into(),
+                                    tip: txn.tip,
+                                    paymaster_data: txn.paymaster_data.clone(),
+                                    nonce_data_availability_mode: txn
+                                        .nonce_data_availability_mode
+                                        .into(),
+                                    fee_data_availability_mode: txn
+                                        .fee_data_availability_mode
+                                        .into(),
+                                },
+                                sender_address: txn.sender_address,
+                                calldata: txn.calldata.clone(),
+                                account_deployment_data: txn.account_deployment_data.clone(),
+                            }))
+                        }
                     }
                 }
                 GatewayTransaction::Declare(GatewayDeclare::V0(txn)) => {
@@ -1167,6 +1582,24 @@ pub mod reply {
                         compiled_class_hash: txn.compiled_class_hash,
                     }))
                 }
+                GatewayTransaction::Declare(GatewayDeclare::V3(txn)) => {
+                    Self::Declare(DeclareTransaction::V3(DeclareTransactionV3 {
+                        common: CommonV3TransactionProperties {
+                            hash: txn.transaction_hash,
+                            signature: txn.signature.clone(),
+                            nonce: txn.nonce,
+                            resource_bounds: txn.resource_bounds.into(),
+                            tip: txn.tip,
+                            paymaster_data: txn.paymaster_data.clone(),
+                            nonce_data_availability_mode: txn.nonce_data_availability_mode.into(),
+                            fee_data_availability_mode: txn.fee_data_availability_mode.into(),
+                        },
+                        class_hash: txn.class_hash,
+                        sender_address: txn.sender_address,
+                        compiled_class_hash: txn.compiled_class_hash,
+                        account_deployment_data: txn.account_deployment_data.clone(),
+                    }))
+                }
                 GatewayTransaction::Deploy(txn) => Self::Deploy(DeployTransaction {
                     hash: txn.transaction_hash,
                     class_hash: txn.class_hash,
@@ -1174,19 +1607,39 @@ pub mod reply {
                     contract_address_salt: txn.contract_address_salt,
                     constructor_calldata: txn.constructor_calldata.clone(),
                 }),
-                GatewayTransaction::DeployAccount(txn) => {
-                    Self::DeployAccount(DeployAccountTransaction {
-                        common: CommonTransactionProperties {
+                GatewayTransaction::DeployAccount(GatewayDeployAccount::V0V1(txn)) => {
+                    Self::DeployAccount(DeployAccountTransaction::V0V1(
+                        DeployAccountTransactionV0V1 {
+                            common: CommonTransactionProperties {
+                                hash: txn.transaction_hash,
+                                max_fee: txn.max_fee,
+                                version: txn.version,
+                                signature: txn.signature.clone(),
+                                nonce: txn.nonce,
+                            },
+                            contract_address_salt: txn.contract_address_salt,
+                            constructor_calldata: txn.constructor_calldata.clone(),
+                            class_hash: txn.class_hash,
+                        },
+                    ))
+                }
+                GatewayTransaction::DeployAccount(GatewayDeployAccount::V3(txn)) => {
+                    Self::DeployAccount(DeployAccountTransaction::V3(DeployAccountTransactionV3 {
+                        common: CommonV3TransactionProperties {
                             hash: txn.transaction_hash,
-                            max_fee: txn.max_fee,
-                            version: txn.version,
                             signature: txn.signature.clone(),
                             nonce: txn.nonce,
+                            resource_bounds: txn.resource_bounds.into(),
+                            tip: txn.tip,
+                            paymaster_data: txn.paymaster_data.clone(),
+                            nonce_data_availability_mode: txn.nonce_data_availability_mode.into(),
+                            fee_data_availability_mode: txn.fee_data_availability_mode.into(),
                         },
+                        version: txn.version,
                         contract_address_salt: txn.contract_address_salt,
                         constructor_calldata: txn.constructor_calldata.clone(),
                         class_hash: txn.class_hash,
-                    })
+                    }))
                 }
                 GatewayTransaction::L1Handler(txn) => Self::L1Handler(L1HandlerTransaction {
                     hash: txn.transaction_hash,
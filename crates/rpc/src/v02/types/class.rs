@@ -21,50 +21,60 @@ impl ContractClass {
     /// - [SierraContractClass] does not compress its `sierra_program` and represents it as a list of
     /// felts, as required by `BROADCASTED_DECLARE_TXN_V2`.
     pub fn from_definition_bytes(data: &[u8]) -> anyhow::Result<ContractClass> {
+        /// Only checks for the `sierra_program`, without building the rest of the class.
+        #[derive(Deserialize)]
+        struct Probe {
+            sierra_program: Option<serde::de::IgnoredAny>,
+        }
+
+        let probe = serde_json::from_slice::<Probe>(data).context("Parsing json")?;
+        // Sierra classes are parsed straight from the bytes, as they are already in the RPC
+        // format and can be several megabytes large.
+        if probe.sierra_program.is_some() {
+            return Ok(ContractClass::Sierra(
+                serde_json::from_slice(data).context("Parsing sierra class")?,
+            ));
+        }
+
         let mut json = serde_json::from_slice::<serde_json::Value>(data).context("Parsing json")?;
         let json_obj = json
             .as_object_mut()
             .context("Class definition is not a json object")?;
-        if json_obj.contains_key("sierra_program") {
-            Ok(ContractClass::Sierra(
-                serde_json::from_value(json).context("Parsing sierra class")?,
-            ))
-        } else {
-            let entry = json_obj
-                .get_mut("entry_points_by_type")
-                .context("entry_points_by_type property is missing")?
-                .take();
-            let entry = serde_json::from_value::<ContractEntryPoints>(entry)
-                .context("Parsing entry points")?;
-
-            // ABI is optional.
-            let abi = json_obj.get_mut("abi").and_then(|json| {
-                let json = json.take();
-                // ABIs are set by users and not verified by starknet, therefore ABIs
-                // can fail to parse (and just be nonsense). Discard these ABIs.
-                serde_json::from_value::<Vec<ContractAbiEntry>>(json).ok()
-            });
-
-            let program = json_obj
-                .get_mut("program")
-                .context("program property is missing")?;
-
-            // Program is expected to be a gzip-compressed then base64 encoded representation of the JSON.
-            let mut gzip_encoder =
-                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
-            serde_json::to_writer(&mut gzip_encoder, &program).context("Compressing program")?;
-            let compressed_program = gzip_encoder
-                .finish()
-                .context("Finalizing program compression")?;
-            let encoded_program = base64::encode(compressed_program);
-            let program = encoded_program;
-
-            Ok(ContractClass::Cairo(CairoContractClass {
-                program,
-                entry_points_by_type: entry,
-                abi,
-            }))
-        }
+
+        let entry = json_obj
+            .get_mut("entry_points_by_type")
+            .context("entry_points_by_type property is missing")?
+            .take();
+        let entry =
+            serde_json::from_value::<ContractEntryPoints>(entry).context("Parsing entry points")?;
+
+        // ABI is optional.
+        let abi = json_obj.get_mut("abi").and_then(|json| {
+            let json = json.take();
+            // ABIs are set by users and not verified by starknet, therefore ABIs
+            // can fail to parse (and just be nonsense). Discard these ABIs.
+            serde_json::from_value::<Vec<ContractAbiEntry>>(json).ok()
+        });
+
+        let program = json_obj
+            .get_mut("program")
+            .context("program property is missing")?;
+
+        // Program is expected to be a gzip-compressed then base64 encoded representation of the JSON.
+        let mut gzip_encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        serde_json::to_writer(&mut gzip_encoder, &program).context("Compressing program")?;
+        let compressed_program = gzip_encoder
+            .finish()
+            .context("Finalizing program compression")?;
+        let encoded_program = base64::encode(compressed_program);
+        let program = encoded_program;
+
+        Ok(ContractClass::Cairo(CairoContractClass {
+            program,
+            entry_points_by_type: entry,
+            abi,
+        }))
     }
 
     pub fn as_cairo(self) -> Option<CairoContractClass> {
@@ -0,0 +1,143 @@
+//! The properties of v3 transactions, which replace the `max_fee` of earlier versions with
+//! per-resource bounds.
+use pathfinder_common::transaction as common;
+use pathfinder_common::{ResourceAmount, ResourcePricePerUnit};
+use pathfinder_serde::{ResourceAmountAsHexStr, ResourcePricePerUnitAsHexStr};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet_gateway_types::reply::transaction as gateway;
+
+/// The `RESOURCE_BOUNDS_MAPPING` of the specification.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceBounds {
+    pub l1_gas: ResourceBound,
+    pub l2_gas: ResourceBound,
+}
+
+#[serde_as]
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceBound {
+    #[serde_as(as = "ResourceAmountAsHexStr")]
+    pub max_amount: ResourceAmount,
+    #[serde_as(as = "ResourcePricePerUnitAsHexStr")]
+    pub max_price_per_unit: ResourcePricePerUnit,
+}
+
+/// The `DA_MODE` of the specification.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum DataAvailabilityMode {
+    #[default]
+    L1,
+    L2,
+}
+
+impl From<common::ResourceBounds> for ResourceBounds {
+    fn from(value: common::ResourceBounds) -> Self {
+        Self {
+            l1_gas: value.l1_gas.into(),
+            l2_gas: value.l2_gas.into(),
+        }
+    }
+}
+
+impl From<ResourceBounds> for common::ResourceBounds {
+    fn from(value: ResourceBounds) -> Self {
+        Self {
+            l1_gas: value.l1_gas.into(),
+            l2_gas: value.l2_gas.into(),
+        }
+    }
+}
+
+impl From<common::ResourceBound> for ResourceBound {
+    fn from(value: common::ResourceBound) -> Self {
+        Self {
+            max_amount: value.max_amount,
+            max_price_per_unit: value.max_price_per_unit,
+        }
+    }
+}
+
+impl From<ResourceBound> for common::ResourceBound {
+    fn from(value: ResourceBound) -> Self {
+        Self {
+            max_amount: value.max_amount,
+            max_price_per_unit: value.max_price_per_unit,
+        }
+    }
+}
+
+impl From<common::DataAvailabilityMode> for DataAvailabilityMode {
+    fn from(value: common::DataAvailabilityMode) -> Self {
+        match value {
+            common::DataAvailabilityMode::L1 => Self::L1,
+            common::DataAvailabilityMode::L2 => Self::L2,
+        }
+    }
+}
+
+impl From<DataAvailabilityMode> for common::DataAvailabilityMode {
+    fn from(value: DataAvailabilityMode) -> Self {
+        match value {
+            DataAvailabilityMode::L1 => Self::L1,
+            DataAvailabilityMode::L2 => Self::L2,
+        }
+    }
+}
+
+impl From<gateway::ResourceBounds> for ResourceBounds {
+    fn from(value: gateway::ResourceBounds) -> Self {
+        common::ResourceBounds::from(value).into()
+    }
+}
+
+impl From<ResourceBounds> for gateway::ResourceBounds {
+    fn from(value: ResourceBounds) -> Self {
+        common::ResourceBounds::from(value).into()
+    }
+}
+
+impl From<gateway::DataAvailabilityMode> for DataAvailabilityMode {
+    fn from(value: gateway::DataAvailabilityMode) -> Self {
+        common::DataAvailabilityMode::from(value).into()
+    }
+}
+
+impl From<DataAvailabilityMode> for gateway::DataAvailabilityMode {
+    fn from(value: DataAvailabilityMode) -> Self {
+        common::DataAvailabilityMode::from(value).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialization() {
+        let bounds = ResourceBounds {
+            l1_gas: ResourceBound {
+                max_amount: ResourceAmount(0x186a0),
+                max_price_per_unit: ResourcePricePerUnit(0x5af3107a4000),
+            },
+            l2_gas: Default::default(),
+        };
+        let expected = json!({
+            "l1_gas": {"max_amount": "0x186a0", "max_price_per_unit": "0x5af3107a4000"},
+            "l2_gas": {"max_amount": "0x0", "max_price_per_unit": "0x0"},
+        });
+
+        assert_eq!(serde_json::to_value(bounds).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_value::<ResourceBounds>(expected).unwrap(),
+            bounds
+        );
+        assert_eq!(
+            serde_json::to_value(DataAvailabilityMode::L2).unwrap(),
+            json!("L2")
+        );
+    }
+}
@@ -37,4 +37,14 @@ pub fn register_routes() -> RpcRouterBuilder {
 
         .register("pathfinder_getProof"                      ,crate::pathfinder::methods::get_proof)
         .register("pathfinder_getTransactionStatus"          ,crate::pathfinder::methods::get_transaction_status)
+        .register("pathfinder_getSubmittedTransactionStatus" ,crate::pathfinder::methods::get_submitted_transaction_status)
+        .register("pathfinder_getClassDeclarationBlock"      ,crate::pathfinder::methods::get_class_declaration_block)
+        .register("pathfinder_getContractDeployment"         ,crate::pathfinder::methods::get_contract_deployment)
+        .register("pathfinder_prepareDeclare"                ,crate::pathfinder::methods::prepare_declare)
+        .register("pathfinder_getMessagesToL1"               ,crate::pathfinder::methods::get_messages_to_l1)
+        .register("pathfinder_getChainStats"                 ,crate::pathfinder::methods::get_chain_stats)
+        .register("pathfinder_getTransactionsBySender"       ,crate::pathfinder::methods::get_transactions_by_sender)
+        .register("pathfinder_getNonceHistory"               ,crate::pathfinder::methods::get_nonce_history)
+        .register("pathfinder_getFirstBlockWithNonce"        ,crate::pathfinder::methods::get_first_block_with_nonce)
+        .register("pathfinder_getBlockSyncTimings"           ,crate::pathfinder::methods::get_block_sync_timings)
 }
@@ -1,6 +1,6 @@
 pub(crate) mod estimate_fee;
 pub(crate) mod estimate_message_fee;
-mod get_events;
+pub(crate) mod get_events;
 pub(crate) mod get_state_update;
 pub(crate) mod simulate_transaction;
 
@@ -19,6 +19,9 @@ impl From<crate::v05::method::estimate_fee::EstimateFeeError> for EstimateFeeErr
             ContractErrorV05 { revert_error } => {
                 Self::Custom(anyhow::anyhow!("Transaction reverted: {}", revert_error))
             }
+            ExecutionResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             Custom(e) => Self::Custom(e),
         }
     }
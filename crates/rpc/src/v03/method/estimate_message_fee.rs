@@ -3,7 +3,6 @@ use pathfinder_common::{BlockId, EthereumAddress};
 use crate::{context::RpcContext, v05::method::call::FunctionCall};
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct EstimateMessageFeeInput {
     pub message: FunctionCall,
     pub sender_address: EthereumAddress,
@@ -29,6 +28,9 @@ impl From<crate::v05::method::estimate_message_fee::EstimateMessageFeeError>
             ContractErrorV05 { revert_error } => {
                 Self::Internal(anyhow::anyhow!("Transaction reverted: {}", revert_error))
             }
+            ExecutionResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             Custom(error) => Self::Custom(error),
         }
     }
@@ -42,7 +42,6 @@ impl From<GetEventsError> for crate::error::ApplicationError {
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
 #[cfg_attr(test, derive(Clone))]
-#[serde(deny_unknown_fields)]
 pub struct GetEventsInput {
     filter: EventFilter,
 }
@@ -50,7 +49,7 @@ pub struct GetEventsInput {
 /// Contains event filter parameters passed to `starknet_getEvents`.
 #[serde_with::skip_serializing_none]
 #[derive(Default, Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
 pub struct EventFilter {
     #[serde(default)]
     pub from_block: Option<BlockId>,
@@ -92,6 +91,12 @@ pub async fn get_events(
     //  c) else if empty / partially full -> append events from start of pending
     //      if there are more pending events return a continuation token
     //      with the appropriate offset within the pending block
+    //
+    // Pending events are ordered like the events of a committed block, i.e. by transaction and
+    // then by their index within the transaction. Since the pending block only grows until it is
+    // committed, a continuation token's offset into the pending block remains valid as long as
+    // it is pending. Once it is committed, the same offset continues with its remaining events
+    // from the database instead.
 
     use BlockId::*;
 
@@ -140,12 +145,22 @@ pub async fn get_events(
                     .pending_data
                     .get(&transaction)
                     .context("Querying pending data")?;
-                return get_pending_events(&request, &pending, continuation_token);
+                match continuation_token {
+                    // The pending block of the previous page has since been committed, so its
+                    // remaining events are queried from the database like in (4).
+                    Some(token) if token.block_number < pending.number => {}
+                    _ => return get_pending_events(&request, &pending, continuation_token),
+                }
             }
             _ => {}
         }
 
-        let from_block = map_from_block_to_number(&transaction, request.from_block)?;
+        // The block a moving tag refers to may have advanced since the previous page, in which
+        // case it no longer bounds the continuation token.
+        let from_block = match (request.from_block, continuation_token) {
+            (Some(Pending | Latest), Some(_)) => None,
+            (from_block, _) => map_from_block_to_number(&transaction, from_block)?,
+        };
         let to_block = map_to_block_to_number(&transaction, request.to_block)?;
 
         let (from_block, requested_offset) = match continuation_token {
@@ -227,7 +242,7 @@ pub async fn get_events(
 
                 let current_offset = match continuation_token {
                     Some(continuation_token) => {
-                        continuation_token.offset_in_block(pending.number)?
+                        continuation_token.offset_in_pending_block(pending.number)?
                     }
                     None => 0,
                 };
@@ -493,6 +508,22 @@ impl ContinuationToken {
         }
     }
 
+    /// The offset of the next event in the pending block, after the events of the database were
+    /// queried from this token.
+    ///
+    /// Tokens of earlier blocks were used up by the database query, including tokens which
+    /// pointed into a pending block that has since been committed.
+    fn offset_in_pending_block(
+        &self,
+        pending_block_number: BlockNumber,
+    ) -> Result<usize, GetEventsError> {
+        match self.block_number.cmp(&pending_block_number) {
+            std::cmp::Ordering::Less => Ok(0),
+            std::cmp::Ordering::Equal => Ok(self.offset),
+            std::cmp::Ordering::Greater => Err(GetEventsError::InvalidContinuationToken),
+        }
+    }
+
     fn start_block_and_offset(
         &self,
         from_block: Option<BlockNumber>,
@@ -530,7 +561,7 @@ fn check_continuation_token_validity(
     }
 }
 
-mod types {
+pub(crate) mod types {
     use pathfinder_common::{
         BlockHash, BlockNumber, ContractAddress, EventData, EventKey, TransactionHash,
     };
@@ -538,6 +569,7 @@ mod types {
 
     /// Describes an emitted event returned by starknet_getEvents
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     #[serde(deny_unknown_fields)]
     pub struct EmittedEvent {
         pub data: Vec<EventData>,
@@ -566,6 +598,7 @@ mod types {
     // Result type for starknet_getEvents
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     #[serde(deny_unknown_fields)]
     pub struct GetEventsResult {
         pub events: Vec<EmittedEvent>,
@@ -1007,6 +1040,38 @@ mod tests {
             assert_eq!(error, GetEventsError::InvalidContinuationToken);
         }
 
+        #[tokio::test]
+        async fn pending_block_committed_between_pages() {
+            let context = RpcContext::for_tests_with_pending().await;
+
+            // The remaining events of a committed block, followed by the events of the new
+            // pending block.
+            let input = GetEventsInput {
+                filter: EventFilter {
+                    from_block: Some(BlockId::Number(BlockNumber::new_or_panic(2))),
+                    to_block: Some(BlockId::Pending),
+                    chunk_size: 1024,
+                    ..Default::default()
+                },
+            };
+            let expected = get_events(context.clone(), input).await.unwrap();
+
+            // A token of block 2, which was pending when the previous page was returned.
+            for from_block in [BlockId::Pending, BlockId::Latest] {
+                let input = GetEventsInput {
+                    filter: EventFilter {
+                        from_block: Some(from_block),
+                        to_block: Some(BlockId::Pending),
+                        chunk_size: 1024,
+                        continuation_token: Some("2-0".to_string()),
+                        ..Default::default()
+                    },
+                };
+                let result = get_events(context.clone(), input).await.unwrap();
+                assert_eq!(result, expected, "{from_block:?}");
+            }
+        }
+
         #[tokio::test]
         async fn paging_with_no_more_matching_events_in_pending() {
             let context = RpcContext::for_tests_with_pending().await;
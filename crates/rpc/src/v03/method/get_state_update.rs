@@ -3,7 +3,6 @@ use anyhow::Context;
 use pathfinder_common::BlockId;
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct GetStateUpdateInput {
     block_id: BlockId,
 }
@@ -582,4 +581,60 @@ mod tests {
 
         sort_assert_eq(result, expected);
     }
+
+    #[tokio::test]
+    async fn pending_has_no_block_hash_or_new_root() {
+        use crate::pending::PendingData;
+        use pathfinder_common::StateUpdate as CommonStateUpdate;
+
+        let context = RpcContext::for_tests();
+        let latest = {
+            let mut db = context.storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            tx.block_header(pathfinder_storage::BlockId::Latest)
+                .unwrap()
+                .unwrap()
+        };
+
+        let state_update = CommonStateUpdate::default()
+            .with_parent_state_commitment(latest.state_commitment)
+            .with_declared_sierra_class(sierra_hash!("0x1"), casm_hash!("0x2"))
+            .with_declared_cairo_class(class_hash!("0x3"))
+            .with_replaced_class(contract_address!("0x4"), class_hash!("0x5"));
+        let pending = PendingData {
+            block: starknet_gateway_types::reply::PendingBlock {
+                parent_hash: latest.hash,
+                ..Default::default()
+            },
+            state_update,
+            number: latest.number + 1,
+        };
+        let (_tx, rx) = tokio::sync::watch::channel(std::sync::Arc::new(pending));
+        let context = context.with_pending_data(rx);
+
+        let result = get_state_update(
+            context,
+            GetStateUpdateInput {
+                block_id: BlockId::Pending,
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = serde_json::to_value(result).unwrap();
+        pretty_assertions::assert_eq!(
+            result,
+            json!({
+                "old_root": crate::felt::RpcFelt(latest.state_commitment.0),
+                "state_diff": {
+                    "storage_diffs": [],
+                    "deprecated_declared_classes": ["0x3"],
+                    "declared_classes": [{"class_hash": "0x1", "compiled_class_hash": "0x2"}],
+                    "deployed_contracts": [],
+                    "replaced_classes": [{"contract_address": "0x4", "class_hash": "0x5"}],
+                    "nonces": []
+                }
+            })
+        );
+    }
 }
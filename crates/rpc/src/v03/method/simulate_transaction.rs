@@ -7,7 +7,6 @@ use pathfinder_executor::{types::TransactionSimulation, CallError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct SimulateTransactionInput {
     block_id: BlockId,
     // `transactions` used to be called `transaction` in the JSON-RPC 0.3.0 specification.
@@ -34,6 +33,9 @@ impl From<CallError> for SimulateTransactionError {
             Reverted(revert_error) => {
                 Self::Custom(anyhow::anyhow!("Transaction reverted: {}", revert_error))
             }
+            ResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             Internal(e) => Self::Internal(e),
             Custom(e) => Self::Custom(e),
         }
@@ -56,6 +58,7 @@ pub async fn simulate_transaction(
 ) -> Result<SimulateTransactionOutput, SimulateTransactionError> {
     let span = tracing::Span::current();
 
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -93,15 +96,20 @@ pub async fn simulate_transaction(
         };
 
         let state =
-            pathfinder_executor::ExecutionState::simulation(&db, context.chain_id, header, pending);
-
-        let transactions = input
-            .transactions
-            .iter()
-            .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let txs = pathfinder_executor::simulate(state, transactions, skip_validate, false)?;
+            pathfinder_executor::ExecutionState::simulation(&db, context.chain_id, header, pending)
+                .with_remote_state(context.remote_state.clone())
+                .with_cancellation(cancellation)
+                .with_limits(context.execution_limits.simulate);
+
+        let txs = state.run_in_chunks(|state| {
+            let transactions = input
+                .transactions
+                .iter()
+                .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            pathfinder_executor::simulate(state, transactions, skip_validate, false, false)
+        })?;
         let txs = txs.into_iter().map(Into::into).collect();
         Ok(SimulateTransactionOutput(txs))
     })
@@ -418,14 +426,16 @@ mod tests {
     use pathfinder_common::{macro_prelude::*, StorageAddress};
     use starknet_gateway_test_fixtures::class_definitions::DUMMY_ACCOUNT_CLASS_HASH;
 
-    use crate::v02::types::request::BroadcastedDeployAccountTransaction;
+    use crate::v02::types::request::{
+        BroadcastedDeployAccountTransaction, BroadcastedDeployAccountTransactionV0V1,
+    };
     use crate::v05::method::call::FunctionCall;
 
     use super::*;
 
     #[tokio::test]
     async fn test_simulate_transaction() {
-        let transaction = BroadcastedDeployAccountTransaction {
+        let transaction = BroadcastedDeployAccountTransactionV0V1 {
             contract_address_salt: contract_address_salt!(
                 "0x46c0d4abf0192a788aca261e58d7031576f7d8ea5229f452b0f23e691dd5971"
             ),
@@ -455,7 +465,9 @@ mod tests {
         let input_json = serde_json::json!({
             "block_id": {"block_number": 1},
             "transaction": [
-                BroadcastedTransaction::DeployAccount(transaction),
+                BroadcastedTransaction::DeployAccount(
+                    BroadcastedDeployAccountTransaction::V0V1(transaction)
+                ),
             ],
             "simulation_flags": []
         });
@@ -1,6 +1,6 @@
-mod add_declare_transaction;
-mod add_deploy_account_transaction;
-mod add_invoke_transaction;
+pub(crate) mod add_declare_transaction;
+pub(crate) mod add_deploy_account_transaction;
+pub(crate) mod add_invoke_transaction;
 mod estimate_message_fee;
 mod get_block_with_txs;
 mod get_transaction_by_block_and_index;
@@ -8,7 +8,7 @@ mod get_transaction_by_hash;
 mod get_transaction_receipt;
 mod pending_transactions;
 pub(crate) mod simulate_transactions;
-mod syncing;
+pub(crate) mod syncing;
 mod trace_block_transactions;
 mod trace_transaction;
 
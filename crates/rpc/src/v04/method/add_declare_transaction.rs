@@ -1,7 +1,7 @@
 use crate::context::RpcContext;
 use crate::felt::RpcFelt;
 use crate::v02::types::request::BroadcastedDeclareTransaction;
-use pathfinder_common::{ClassHash, TransactionHash};
+use pathfinder_common::{ClassHash, Fee, TransactionHash};
 use starknet_gateway_client::GatewayApi;
 use starknet_gateway_types::error::SequencerError;
 use starknet_gateway_types::request::add_transaction::{
@@ -121,7 +121,6 @@ pub enum Transaction {
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct AddDeclareTransactionInput {
     declare_transaction: Transaction,
     // An undocumented parameter that we forward to the sequencer API
@@ -132,11 +131,12 @@ pub struct AddDeclareTransactionInput {
 
 #[serde_with::serde_as]
 #[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct AddDeclareTransactionOutput {
     #[serde_as(as = "RpcFelt")]
-    transaction_hash: TransactionHash,
+    pub transaction_hash: TransactionHash,
     #[serde_as(as = "RpcFelt")]
-    class_hash: ClassHash,
+    pub class_hash: ClassHash,
 }
 
 pub async fn add_declare_transaction(
@@ -163,10 +163,17 @@ pub async fn add_declare_transaction(
                     ContractDefinition::Cairo(contract_definition),
                     tx.sender_address,
                     None,
+                    None,
                     input.token,
                 )
                 .await?;
 
+            crate::pathfinder::methods::record_submitted_transaction(
+                &context,
+                response.transaction_hash,
+            )
+            .await;
+
             Ok(AddDeclareTransactionOutput {
                 transaction_hash: response.transaction_hash,
                 class_hash: response.class_hash,
@@ -188,10 +195,50 @@ pub async fn add_declare_transaction(
                     ContractDefinition::Sierra(contract_definition),
                     tx.sender_address,
                     Some(tx.compiled_class_hash),
+                    None,
+                    input.token,
+                )
+                .await?;
+
+            crate::pathfinder::methods::record_submitted_transaction(
+                &context,
+                response.transaction_hash,
+            )
+            .await;
+
+            Ok(AddDeclareTransactionOutput {
+                transaction_hash: response.transaction_hash,
+                class_hash: response.class_hash,
+            })
+        }
+        Transaction::Declare(BroadcastedDeclareTransaction::V3(tx)) => {
+            let v3_properties = tx.v3_properties();
+            let contract_definition: SierraContractDefinition = tx
+                .contract_class
+                .try_into()
+                .map_err(|e| anyhow::anyhow!("Failed to convert contract definition: {}", e))?;
+
+            let response = context
+                .sequencer
+                .add_declare_transaction(
+                    tx.version,
+                    Fee::ZERO,
+                    tx.signature,
+                    tx.nonce,
+                    ContractDefinition::Sierra(contract_definition),
+                    tx.sender_address,
+                    Some(tx.compiled_class_hash),
+                    Some(v3_properties),
                     input.token,
                 )
                 .await?;
 
+            crate::pathfinder::methods::record_submitted_transaction(
+                &context,
+                response.transaction_hash,
+            )
+            .await;
+
             Ok(AddDeclareTransactionOutput {
                 transaction_hash: response.transaction_hash,
                 class_hash: response.class_hash,
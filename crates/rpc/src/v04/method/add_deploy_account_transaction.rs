@@ -1,7 +1,7 @@
 use crate::context::RpcContext;
 use crate::felt::{RpcFelt, RpcFelt251};
 use crate::v02::types::request::BroadcastedDeployAccountTransaction;
-use pathfinder_common::{ContractAddress, TransactionHash};
+use pathfinder_common::{ContractAddress, Fee, TransactionHash};
 use starknet_gateway_client::GatewayApi;
 use starknet_gateway_types::error::SequencerError;
 
@@ -13,18 +13,18 @@ pub enum Transaction {
 }
 
 #[derive(Debug, serde::Deserialize, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct AddDeployAccountTransactionInput {
     deploy_account_transaction: Transaction,
 }
 
 #[serde_with::serde_as]
 #[derive(serde::Serialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct AddDeployAccountTransactionOutput {
     #[serde_as(as = "RpcFelt")]
-    transaction_hash: TransactionHash,
+    pub transaction_hash: TransactionHash,
     #[serde_as(as = "RpcFelt251")]
-    contract_address: ContractAddress,
+    pub contract_address: ContractAddress,
 }
 
 #[derive(Debug)]
@@ -99,18 +99,42 @@ pub async fn add_deploy_account_transaction(
     input: AddDeployAccountTransactionInput,
 ) -> Result<AddDeployAccountTransactionOutput, AddDeployAccountTransactionError> {
     let Transaction::DeployAccount(tx) = input.deploy_account_transaction;
-    let response = context
-        .sequencer
-        .add_deploy_account(
-            tx.version,
-            tx.max_fee,
-            tx.signature,
-            tx.nonce,
-            tx.contract_address_salt,
-            tx.class_hash,
-            tx.constructor_calldata,
-        )
-        .await?;
+    let response = match tx {
+        BroadcastedDeployAccountTransaction::V0V1(tx) => {
+            context
+                .sequencer
+                .add_deploy_account(
+                    tx.version,
+                    tx.max_fee,
+                    tx.signature,
+                    tx.nonce,
+                    tx.contract_address_salt,
+                    tx.class_hash,
+                    tx.constructor_calldata,
+                    None,
+                )
+                .await
+        }
+        BroadcastedDeployAccountTransaction::V3(tx) => {
+            let v3_properties = tx.v3_properties();
+            context
+                .sequencer
+                .add_deploy_account(
+                    tx.version,
+                    Fee::ZERO,
+                    tx.signature,
+                    tx.nonce,
+                    tx.contract_address_salt,
+                    tx.class_hash,
+                    tx.constructor_calldata,
+                    Some(v3_properties),
+                )
+                .await
+        }
+    }?;
+
+    crate::pathfinder::methods::record_submitted_transaction(&context, response.transaction_hash)
+        .await;
 
     Ok(AddDeployAccountTransactionOutput {
         transaction_hash: response.transaction_hash,
@@ -121,6 +145,7 @@ pub async fn add_deploy_account_transaction(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::v02::types::request::BroadcastedDeployAccountTransactionV0V1;
     use pathfinder_common::macro_prelude::*;
     use pathfinder_common::{TransactionNonce, TransactionVersion};
 
@@ -161,29 +186,31 @@ mod tests {
     fn get_input() -> AddDeployAccountTransactionInput {
         AddDeployAccountTransactionInput {
             deploy_account_transaction: Transaction::DeployAccount(
-                BroadcastedDeployAccountTransaction {
-                    version: TransactionVersion::ONE,
-                    max_fee: fee!("0xbf391377813"),
-                    signature: vec![
-                        transaction_signature_elem!(
-                            "07dd3a55d94a0de6f3d6c104d7e6c88ec719a82f4e2bbc12587c8c187584d3d5"
+                BroadcastedDeployAccountTransaction::V0V1(
+                    BroadcastedDeployAccountTransactionV0V1 {
+                        version: TransactionVersion::ONE,
+                        max_fee: fee!("0xbf391377813"),
+                        signature: vec![
+                            transaction_signature_elem!(
+                                "07dd3a55d94a0de6f3d6c104d7e6c88ec719a82f4e2bbc12587c8c187584d3d5"
+                            ),
+                            transaction_signature_elem!(
+                                "071456dded17015d1234779889d78f3e7c763ddcfd2662b19e7843c7542614f8"
+                            ),
+                        ],
+                        nonce: TransactionNonce::ZERO,
+
+                        contract_address_salt: contract_address_salt!(
+                            "06d44a6aecb4339e23a9619355f101cf3cb9baec289fcd9fd51486655c1bb8a8"
                         ),
-                        transaction_signature_elem!(
-                            "071456dded17015d1234779889d78f3e7c763ddcfd2662b19e7843c7542614f8"
+                        constructor_calldata: vec![call_param!(
+                            "0677bb1cdc050e8d63855e8743ab6e09179138def390676cc03c484daf112ba1"
+                        )],
+                        class_hash: class_hash!(
+                            "01fac3074c9d5282f0acc5c69a4781a1c711efea5e73c550c5d9fb253cf7fd3d"
                         ),
-                    ],
-                    nonce: TransactionNonce::ZERO,
-
-                    contract_address_salt: contract_address_salt!(
-                        "06d44a6aecb4339e23a9619355f101cf3cb9baec289fcd9fd51486655c1bb8a8"
-                    ),
-                    constructor_calldata: vec![call_param!(
-                        "0677bb1cdc050e8d63855e8743ab6e09179138def390676cc03c484daf112ba1"
-                    )],
-                    class_hash: class_hash!(
-                        "01fac3074c9d5282f0acc5c69a4781a1c711efea5e73c550c5d9fb253cf7fd3d"
-                    ),
-                },
+                    },
+                ),
             ),
         }
     }
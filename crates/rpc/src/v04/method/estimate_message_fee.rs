@@ -20,6 +20,9 @@ impl From<crate::v05::method::estimate_message_fee::EstimateMessageFeeError>
             ContractErrorV05 { revert_error } => {
                 Self::Internal(anyhow::anyhow!("Transaction reverted: {}", revert_error))
             }
+            ExecutionResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             Custom(error) => Self::Custom(error),
         }
     }
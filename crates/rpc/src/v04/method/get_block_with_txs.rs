@@ -1,6 +1,6 @@
 use crate::context::RpcContext;
+use crate::dto::TransactionWithHash;
 use crate::v02::types::reply::BlockStatus;
-use crate::v04::types::TransactionWithHash;
 
 use anyhow::Context;
 use pathfinder_common::{BlockId, BlockNumber};
@@ -8,7 +8,6 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[cfg_attr(test, derive(Copy, Clone))]
-#[serde(deny_unknown_fields)]
 pub struct GetBlockInput {
     block_id: BlockId,
 }
@@ -86,9 +85,9 @@ fn get_block_transactions(
 }
 
 mod types {
+    use crate::dto::TransactionWithHash;
     use crate::felt::RpcFelt;
     use crate::v02::types::reply::BlockStatus;
-    use crate::v04::types::TransactionWithHash;
     use pathfinder_common::{
         BlockHash, BlockHeader, BlockNumber, BlockTimestamp, SequencerAddress, StateCommitment,
     };
@@ -1,9 +1,9 @@
 use crate::context::RpcContext;
+use crate::dto::TransactionWithHash;
 use crate::v02::method::get_transaction_by_block_id_and_index::{
     get_transaction_by_block_id_and_index_impl, GetTransactionByBlockIdAndIndexError,
     GetTransactionByBlockIdAndIndexInput,
 };
-use crate::v04::types::TransactionWithHash;
 
 pub async fn get_transaction_by_block_id_and_index(
     context: RpcContext,
@@ -1,5 +1,5 @@
 use crate::context::RpcContext;
-use crate::v04::types::TransactionWithHash;
+use crate::dto::TransactionWithHash;
 
 use crate::v02::method::get_transaction_by_hash as v02_get_transaction_by_hash;
 
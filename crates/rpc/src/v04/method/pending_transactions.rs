@@ -1,7 +1,7 @@
 use anyhow::Context;
 
 use crate::context::RpcContext;
-use crate::v04::types::TransactionWithHash;
+use crate::dto::TransactionWithHash;
 
 crate::error::generate_rpc_error_subset!(PendingTransactionsError:);
 
@@ -40,7 +40,7 @@ pub async fn pending_transactions(
 
 #[cfg(test)]
 mod tests {
-    use crate::v04::types::Transaction;
+    use crate::dto::Transaction;
 
     use super::*;
     use pathfinder_common::macro_prelude::*;
@@ -11,7 +11,6 @@ use starknet_gateway_types::reply::transaction::Transaction as GatewayTransactio
 use starknet_gateway_types::trace as gateway_trace;
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct SimulateTransactionInput {
     block_id: BlockId,
     transactions: Vec<BroadcastedTransaction>,
@@ -36,6 +35,9 @@ impl From<CallError> for SimulateTransactionError {
             Reverted(revert_error) => {
                 Self::Custom(anyhow::anyhow!("Transaction reverted: {}", revert_error))
             }
+            ResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             Internal(e) => Self::Internal(e),
             Custom(e) => Self::Custom(e),
         }
@@ -56,6 +58,7 @@ pub async fn simulate_transactions(
     input: SimulateTransactionInput,
 ) -> Result<SimulateTransactionOutput, SimulateTransactionError> {
     let span = tracing::Span::current();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -99,16 +102,26 @@ pub async fn simulate_transactions(
         };
 
         let state =
-            pathfinder_executor::ExecutionState::simulation(&db, context.chain_id, header, pending);
-
-        let transactions = input
-            .transactions
-            .iter()
-            .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let txs =
-            pathfinder_executor::simulate(state, transactions, skip_validate, skip_fee_charge)?;
+            pathfinder_executor::ExecutionState::simulation(&db, context.chain_id, header, pending)
+                .with_remote_state(context.remote_state.clone())
+                .with_cancellation(cancellation)
+                .with_limits(context.execution_limits.simulate);
+
+        let txs = state.run_in_chunks(|state| {
+            let transactions = input
+                .transactions
+                .iter()
+                .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            pathfinder_executor::simulate(
+                state,
+                transactions,
+                skip_validate,
+                skip_fee_charge,
+                false,
+            )
+        })?;
         let txs = txs.into_iter().map(Into::into).collect();
         Ok(SimulateTransactionOutput(txs))
     })
@@ -46,19 +46,39 @@ impl serde::Serialize for SyncingOutput {
     }
 }
 
+/// Accepts `false` as a boolean too, as required by the specification.
+#[cfg(any(test, feature = "rpc-full-serde"))]
+impl<'de> serde::Deserialize<'de> for SyncingOutput {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde::Deserialize;
+
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Bool(false) => Ok(SyncingOutput::False),
+            serde_json::Value::String(s) if s == "false" => Ok(SyncingOutput::False),
+            other => SyncingStatus::deserialize(other)
+                .map(SyncingOutput::Status)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
 #[serde_with::serde_as]
 #[derive(Clone, Copy, Debug, serde::Serialize, PartialEq, Eq)]
 #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct SyncingStatus {
-    starting_block_num: BlockNumber,
-    current_block_num: BlockNumber,
-    highest_block_num: BlockNumber,
+    pub starting_block_num: BlockNumber,
+    pub current_block_num: BlockNumber,
+    pub highest_block_num: BlockNumber,
     #[serde_as(as = "RpcFelt")]
-    starting_block_hash: BlockHash,
+    pub starting_block_hash: BlockHash,
     #[serde_as(as = "RpcFelt")]
-    current_block_hash: BlockHash,
+    pub current_block_hash: BlockHash,
     #[serde_as(as = "RpcFelt")]
-    highest_block_hash: BlockHash,
+    pub highest_block_hash: BlockHash,
 }
 
 #[cfg(test)]
@@ -73,6 +93,11 @@ mod tests {
         fn not_syncing() {
             let json = serde_json::to_string(&SyncingOutput::False).unwrap();
             assert_eq!(json, r#""false""#);
+
+            for json in [json.as_str(), "false"] {
+                let output = serde_json::from_str::<SyncingOutput>(json).unwrap();
+                assert_eq!(output, SyncingOutput::False);
+            }
         }
 
         #[test]
@@ -101,6 +126,10 @@ mod tests {
             });
 
             assert_eq!(json, expected);
+            assert_eq!(
+                serde_json::from_value::<SyncingOutput>(json).unwrap(),
+                SyncingOutput::Status(status)
+            );
         }
     }
 
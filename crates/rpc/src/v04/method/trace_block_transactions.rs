@@ -12,7 +12,6 @@ use crate::{compose_executor_transaction, context::RpcContext, executor::Executi
 use super::simulate_transactions::dto::TransactionTrace;
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct TraceBlockTransactionsInput {
     block_hash: BlockHash,
 }
@@ -47,6 +46,9 @@ impl From<CallError> for TraceBlockTransactionsError {
                 Self::Custom(anyhow::anyhow!("Invalid message selector"))
             }
             CallError::Reverted(reason) => Self::Custom(anyhow::anyhow!("Reverted: {reason}")),
+            CallError::ResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             CallError::Internal(e) => Self::Internal(e),
             CallError::Custom(e) => Self::Custom(e),
         }
@@ -65,6 +67,7 @@ pub async fn trace_block_transactions(
     let span = tracing::Span::current();
 
     let storage = context.storage.clone();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let traces = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -95,7 +98,9 @@ pub async fn trace_block_transactions(
             .map(|transaction| compose_executor_transaction(transaction, &db))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let state = ExecutionState::trace(&db, context.chain_id, header, None);
+        let state = ExecutionState::trace(&db, context.chain_id, header, None)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation);
         let traces = pathfinder_executor::trace_all(state, transactions, true, true)?;
 
         let result = traces
@@ -214,9 +219,39 @@ pub(crate) mod tests {
                         ),
                     )
                 }
-                crate::v02::types::request::BroadcastedTransaction::DeployAccount(deploy) => {
-                    starknet_gateway_types::reply::transaction::Transaction::DeployAccount(
-                        gateway::transaction::DeployAccountTransaction {
+                crate::v02::types::request::BroadcastedTransaction::Declare(
+                    crate::v02::types::request::BroadcastedDeclareTransaction::V3(declare),
+                ) => {
+                    let class_hash = declare.contract_class.class_hash().unwrap().hash();
+                    let transaction_hash = declare.transaction_hash(ChainId::TESTNET, class_hash);
+                    starknet_gateway_types::reply::transaction::Transaction::Declare(
+                        gateway::transaction::DeclareTransaction::V3(
+                            gateway::transaction::DeclareTransactionV3 {
+                                class_hash,
+                                nonce: declare.nonce,
+                                nonce_data_availability_mode: declare
+                                    .nonce_data_availability_mode
+                                    .into(),
+                                fee_data_availability_mode: declare
+                                    .fee_data_availability_mode
+                                    .into(),
+                                resource_bounds: declare.resource_bounds.into(),
+                                tip: declare.tip,
+                                paymaster_data: declare.paymaster_data,
+                                sender_address: declare.sender_address,
+                                signature: declare.signature,
+                                transaction_hash,
+                                compiled_class_hash: declare.compiled_class_hash,
+                                account_deployment_data: declare.account_deployment_data,
+                            },
+                        ),
+                    )
+                }
+                crate::v02::types::request::BroadcastedTransaction::DeployAccount(
+                    crate::v02::types::request::BroadcastedDeployAccountTransaction::V0V1(deploy),
+                ) => starknet_gateway_types::reply::transaction::Transaction::DeployAccount(
+                    gateway::transaction::DeployAccountTransaction::V0V1(
+                        gateway::transaction::DeployAccountTransactionV0V1 {
                             contract_address: deploy.deployed_contract_address(),
                             transaction_hash: deploy.transaction_hash(ChainId::TESTNET),
                             max_fee: deploy.max_fee,
@@ -227,6 +262,34 @@ pub(crate) mod tests {
                             constructor_calldata: deploy.constructor_calldata,
                             class_hash: deploy.class_hash,
                         },
+                    ),
+                ),
+                crate::v02::types::request::BroadcastedTransaction::DeployAccount(
+                    crate::v02::types::request::BroadcastedDeployAccountTransaction::V3(deploy),
+                ) => {
+                    let transaction_hash = deploy.transaction_hash(ChainId::TESTNET);
+                    starknet_gateway_types::reply::transaction::Transaction::DeployAccount(
+                        gateway::transaction::DeployAccountTransaction::V3(
+                            gateway::transaction::DeployAccountTransactionV3 {
+                                sender_address: deploy.deployed_contract_address(),
+                                nonce: deploy.nonce,
+                                nonce_data_availability_mode: deploy
+                                    .nonce_data_availability_mode
+                                    .into(),
+                                fee_data_availability_mode: deploy
+                                    .fee_data_availability_mode
+                                    .into(),
+                                resource_bounds: deploy.resource_bounds.into(),
+                                tip: deploy.tip,
+                                paymaster_data: deploy.paymaster_data,
+                                signature: deploy.signature,
+                                transaction_hash,
+                                version: deploy.version,
+                                contract_address_salt: deploy.contract_address_salt,
+                                constructor_calldata: deploy.constructor_calldata,
+                                class_hash: deploy.class_hash,
+                            },
+                        ),
                     )
                 }
                 crate::v02::types::request::BroadcastedTransaction::Invoke(
@@ -264,6 +327,32 @@ pub(crate) mod tests {
                         ),
                     )
                 }
+                crate::v02::types::request::BroadcastedTransaction::Invoke(
+                    crate::v02::types::request::BroadcastedInvokeTransaction::V3(invoke),
+                ) => {
+                    let transaction_hash = invoke.transaction_hash(ChainId::TESTNET);
+                    starknet_gateway_types::reply::transaction::Transaction::Invoke(
+                        gateway::transaction::InvokeTransaction::V3(
+                            gateway::transaction::InvokeTransactionV3 {
+                                nonce: invoke.nonce,
+                                nonce_data_availability_mode: invoke
+                                    .nonce_data_availability_mode
+                                    .into(),
+                                fee_data_availability_mode: invoke
+                                    .fee_data_availability_mode
+                                    .into(),
+                                resource_bounds: invoke.resource_bounds.into(),
+                                tip: invoke.tip,
+                                paymaster_data: invoke.paymaster_data,
+                                sender_address: invoke.sender_address,
+                                signature: invoke.signature,
+                                transaction_hash,
+                                calldata: invoke.calldata,
+                                account_deployment_data: invoke.account_deployment_data,
+                            },
+                        ),
+                    )
+                }
             }
         }
     }
@@ -16,7 +16,6 @@ use crate::{
 use super::simulate_transactions::dto::TransactionTrace;
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct TraceTransactionInput {
     pub transaction_hash: TransactionHash,
 }
@@ -49,6 +48,9 @@ impl From<CallError> for TraceTransactionError {
                 Self::Custom(anyhow::anyhow!("Invalid message selector"))
             }
             CallError::Reverted(reason) => Self::Custom(anyhow::anyhow!("Reverted: {reason}")),
+            CallError::ResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             CallError::Internal(e) => Self::Internal(e),
             CallError::Custom(e) => Self::Custom(e),
         }
@@ -85,6 +87,7 @@ pub async fn trace_transaction(
     }
 
     let span = tracing::Span::current();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let local = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -173,7 +176,9 @@ pub async fn trace_transaction(
             )
         };
 
-        let state = ExecutionState::trace(&db, context.chain_id, header, None);
+        let state = ExecutionState::trace(&db, context.chain_id, header, None)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation);
 
         let transactions = transactions
             .iter()
@@ -1,3 +0,0 @@
-mod transaction;
-
-pub use transaction::{Transaction, TransactionWithHash};
@@ -1,7 +1,7 @@
 use crate::jsonrpc::{RpcRouter, RpcRouterBuilder};
 
 pub mod method;
-mod types;
+pub(crate) mod types;
 
 use crate::v02::method as v02_method;
 use crate::v03::method as v03_method;
@@ -36,6 +36,7 @@ pub fn register_routes() -> RpcRouterBuilder {
         .register("starknet_estimateMessageFee"              , method::estimate_message_fee)
         .register("starknet_getBlockWithTxHashes"            , method::get_block_with_tx_hashes)
         .register("starknet_getBlockWithTxs"                 , method::get_block_with_txs)
+        .register("starknet_getCompiledCasm"                 , method::get_compiled_casm)
         .register("starknet_getTransactionStatus"            , method::get_transaction_status)
         .register("starknet_simulateTransactions"            , method::simulate_transactions)
         .register("starknet_specVersion"                     , || "0.5.1")
@@ -44,4 +45,14 @@ pub fn register_routes() -> RpcRouterBuilder {
 
         .register("pathfinder_getProof"                      , crate::pathfinder::methods::get_proof)
         .register("pathfinder_getTransactionStatus"          , crate::pathfinder::methods::get_transaction_status)
+        .register("pathfinder_getSubmittedTransactionStatus" , crate::pathfinder::methods::get_submitted_transaction_status)
+        .register("pathfinder_getClassDeclarationBlock"      , crate::pathfinder::methods::get_class_declaration_block)
+        .register("pathfinder_getContractDeployment"         , crate::pathfinder::methods::get_contract_deployment)
+        .register("pathfinder_prepareDeclare"                , crate::pathfinder::methods::prepare_declare)
+        .register("pathfinder_getMessagesToL1"               , crate::pathfinder::methods::get_messages_to_l1)
+        .register("pathfinder_getChainStats"                 , crate::pathfinder::methods::get_chain_stats)
+        .register("pathfinder_getTransactionsBySender"       , crate::pathfinder::methods::get_transactions_by_sender)
+        .register("pathfinder_getNonceHistory"               , crate::pathfinder::methods::get_nonce_history)
+        .register("pathfinder_getFirstBlockWithNonce"        , crate::pathfinder::methods::get_first_block_with_nonce)
+        .register("pathfinder_getBlockSyncTimings"           , crate::pathfinder::methods::get_block_sync_timings)
 }
@@ -1,12 +1,13 @@
 pub(crate) mod call;
 pub(crate) mod estimate_fee;
 pub(crate) mod estimate_message_fee;
-mod get_block_with_tx_hashes;
-mod get_block_with_txs;
+pub(crate) mod get_block_with_tx_hashes;
+pub(crate) mod get_block_with_txs;
+mod get_compiled_casm;
 mod get_transaction_receipt;
-mod get_transaction_status;
-mod simulate_transactions;
-mod trace_block_transactions;
+pub(crate) mod get_transaction_status;
+pub(crate) mod simulate_transactions;
+pub(crate) mod trace_block_transactions;
 mod trace_transaction;
 
 pub(crate) use call::call;
@@ -14,6 +15,7 @@ pub(crate) use estimate_fee::estimate_fee;
 pub(crate) use estimate_message_fee::estimate_message_fee;
 pub(crate) use get_block_with_tx_hashes::get_block_with_tx_hashes;
 pub(crate) use get_block_with_txs::get_block_with_txs;
+pub(crate) use get_compiled_casm::get_compiled_casm;
 pub(crate) use get_transaction_receipt::get_transaction_receipt;
 pub(crate) use get_transaction_status::get_transaction_status;
 pub(crate) use simulate_transactions::simulate_transactions;
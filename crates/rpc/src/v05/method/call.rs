@@ -11,7 +11,13 @@ pub enum CallError {
     Custom(anyhow::Error),
     BlockNotFound,
     ContractNotFound,
-    ContractErrorV05 { revert_error: String },
+    ContractErrorV05 {
+        revert_error: String,
+    },
+    ExecutionResourcesExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
 }
 
 impl From<anyhow::Error> for CallError {
@@ -27,6 +33,9 @@ impl From<pathfinder_executor::CallError> for CallError {
             ContractNotFound => Self::ContractNotFound,
             InvalidMessageSelector => Self::Custom(anyhow::anyhow!("Invalid message selector")),
             Reverted(revert_error) => Self::ContractErrorV05 { revert_error },
+            ResourcesExceeded { resource, limit } => {
+                Self::ExecutionResourcesExceeded { resource, limit }
+            }
             Internal(e) => Self::Internal(e),
             Custom(e) => Self::Custom(e),
         }
@@ -51,6 +60,9 @@ impl From<CallError> for ApplicationError {
             CallError::ContractErrorV05 { revert_error } => {
                 ApplicationError::ContractErrorV05 { revert_error }
             }
+            CallError::ExecutionResourcesExceeded { resource, limit } => {
+                ApplicationError::ExecutionResourcesExceeded { resource, limit }
+            }
             CallError::Internal(e) => ApplicationError::Internal(e),
             CallError::Custom(e) => ApplicationError::Custom(e),
         }
@@ -58,14 +70,12 @@ impl From<CallError> for ApplicationError {
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct CallInput {
     pub request: FunctionCall,
     pub block_id: BlockId,
 }
 
 #[derive(Clone, serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct FunctionCall {
     pub contract_address: ContractAddress,
     pub entry_point_selector: EntryPoint,
@@ -78,6 +88,7 @@ pub struct CallOutput(#[serde_as(as = "Vec<RpcFelt>")] pub Vec<CallResultValue>)
 
 pub async fn call(context: RpcContext, input: CallInput) -> Result<CallOutput, CallError> {
     let span = tracing::Span::current();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let result = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -107,14 +118,19 @@ pub async fn call(context: RpcContext, input: CallInput) -> Result<CallOutput, C
             }
         };
 
-        let state = ExecutionState::simulation(&db, context.chain_id, header, pending);
-
-        let result = pathfinder_executor::call(
-            state,
-            input.request.contract_address,
-            input.request.entry_point_selector,
-            input.request.calldata,
-        )?;
+        let state = ExecutionState::simulation(&db, context.chain_id, header, pending)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation)
+            .with_limits(context.execution_limits.call);
+
+        let result = state.run_in_chunks(|state| {
+            pathfinder_executor::call(
+                state,
+                input.request.contract_address,
+                input.request.entry_point_selector,
+                input.request.calldata.clone(),
+            )
+        })?;
 
         Ok(result)
     })
@@ -177,7 +193,7 @@ mod tests {
         use std::sync::Arc;
 
         use super::*;
-
+        use crate::context::RequestExecutionLimits;
         use crate::pending::PendingData;
         use pathfinder_common::{
             felt, BlockHash, BlockHeader, BlockNumber, BlockTimestamp, ClassHash, ContractAddress,
@@ -268,6 +284,123 @@ mod tests {
             assert_eq!(result, CallOutput(vec![CallResultValue(test_value.0)]));
         }
 
+        #[tokio::test]
+        async fn cancelled_execution_stops() {
+            let (context, last_block_header, contract_address, test_key, _test_value) =
+                test_context().await;
+
+            let mut db = context.storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+
+            let cancellation = pathfinder_executor::CancellationToken::default();
+            drop(cancellation.drop_guard());
+            assert!(cancellation.is_cancelled());
+
+            let state = ExecutionState::simulation(&tx, context.chain_id, last_block_header, None)
+                .with_cancellation(cancellation);
+            let error = pathfinder_executor::call(
+                state,
+                contract_address,
+                EntryPoint::hashed(b"get_value"),
+                vec![CallParam(*test_key.get())],
+            )
+            .unwrap_err();
+            assert!(
+                format!("{error:?}").contains("Execution cancelled"),
+                "{error:?}"
+            );
+        }
+
+        #[tokio::test]
+        async fn chunked_execution_completes() {
+            let (context, last_block_header, contract_address, test_key, test_value) =
+                test_context().await;
+
+            let mut db = context.storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+
+            let mut attempts = 0;
+            let result = ExecutionState::simulation(&tx, context.chain_id, last_block_header, None)
+                .with_step_chunk(1)
+                .run_in_chunks(|state| {
+                    attempts += 1;
+                    pathfinder_executor::call(
+                        state,
+                        contract_address,
+                        EntryPoint::hashed(b"get_value"),
+                        vec![CallParam(*test_key.get())],
+                    )
+                })
+                .unwrap();
+            assert_eq!(result, vec![CallResultValue(test_value.0)]);
+            assert!(attempts > 1);
+        }
+
+        #[tokio::test]
+        async fn cancelled_during_long_running_call() {
+            let (context, last_block_header, contract_address, test_key, _test_value) =
+                test_context().await;
+
+            let mut db = context.storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+
+            let cancellation = pathfinder_executor::CancellationToken::default();
+
+            // A step chunk of one makes the call run longer than its first chunk.
+            let mut attempts = 0;
+            let error = ExecutionState::simulation(&tx, context.chain_id, last_block_header, None)
+                .with_cancellation(cancellation.clone())
+                .with_step_chunk(1)
+                .run_in_chunks(|state| {
+                    attempts += 1;
+                    let result = pathfinder_executor::call(
+                        state,
+                        contract_address,
+                        EntryPoint::hashed(b"get_value"),
+                        vec![CallParam(*test_key.get())],
+                    );
+                    // The client goes away while the call is running.
+                    cancellation.cancel();
+                    result
+                })
+                .unwrap_err();
+            assert!(
+                format!("{error:?}").contains("Execution cancelled"),
+                "{error:?}"
+            );
+            assert_eq!(attempts, 1);
+        }
+
+        #[tokio::test]
+        async fn step_limit_exceeded() {
+            let (context, _last_block_header, contract_address, test_key, _test_value) =
+                test_context().await;
+            let context = context.with_execution_limits(RequestExecutionLimits {
+                call: pathfinder_executor::ExecutionLimits {
+                    max_steps: Some(1),
+                    max_builtins: None,
+                },
+                ..Default::default()
+            });
+
+            let input = CallInput {
+                request: FunctionCall {
+                    contract_address,
+                    entry_point_selector: EntryPoint::hashed(b"get_value"),
+                    calldata: vec![CallParam(*test_key.get())],
+                },
+                block_id: BlockId::Latest,
+            };
+            let error = call(context, input).await.unwrap_err();
+            assert_matches::assert_matches!(
+                error,
+                CallError::ExecutionResourcesExceeded {
+                    resource: "steps",
+                    limit: 1
+                }
+            );
+        }
+
         #[tokio::test]
         async fn storage_updated_in_pending() {
             let (context, last_block_header, contract_address, test_key, test_value) =
@@ -403,6 +536,7 @@ mod tests {
                     timestamp: BlockTimestamp::new_or_panic(last_block_header.timestamp.get() + 1),
                     transaction_receipts: vec![],
                     transactions: vec![],
+                    candidate_transactions: vec![],
                     starknet_version: last_block_header.starknet_version,
                 },
                 state_update,
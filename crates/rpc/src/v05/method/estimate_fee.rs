@@ -2,13 +2,13 @@ use anyhow::Context;
 use pathfinder_executor::ExecutionState;
 use serde_with::serde_as;
 
+use crate::fee_estimate_cache::EstimateBlock;
 use crate::{
     context::RpcContext, error::ApplicationError, v02::types::request::BroadcastedTransaction,
 };
-use pathfinder_common::BlockId;
+use pathfinder_common::{BlockHeader, BlockId};
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct EstimateFeeInput {
     pub request: Vec<BroadcastedTransaction>,
     pub block_id: BlockId,
@@ -20,7 +20,13 @@ pub enum EstimateFeeError {
     Custom(anyhow::Error),
     BlockNotFound,
     ContractNotFound,
-    ContractErrorV05 { revert_error: String },
+    ContractErrorV05 {
+        revert_error: String,
+    },
+    ExecutionResourcesExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
 }
 
 impl From<anyhow::Error> for EstimateFeeError {
@@ -36,6 +42,9 @@ impl From<pathfinder_executor::CallError> for EstimateFeeError {
             ContractNotFound => Self::ContractNotFound,
             InvalidMessageSelector => Self::Internal(anyhow::anyhow!("Invalid message selector")),
             Reverted(revert_error) => Self::ContractErrorV05 { revert_error },
+            ResourcesExceeded { resource, limit } => {
+                Self::ExecutionResourcesExceeded { resource, limit }
+            }
             Internal(e) => Self::Internal(e),
             Custom(e) => Self::Custom(e),
         }
@@ -60,6 +69,9 @@ impl From<EstimateFeeError> for ApplicationError {
             EstimateFeeError::ContractErrorV05 { revert_error } => {
                 ApplicationError::ContractErrorV05 { revert_error }
             }
+            EstimateFeeError::ExecutionResourcesExceeded { resource, limit } => {
+                ApplicationError::ExecutionResourcesExceeded { resource, limit }
+            }
             EstimateFeeError::Internal(e) => ApplicationError::Internal(e),
             EstimateFeeError::Custom(e) => ApplicationError::Custom(e),
         }
@@ -68,6 +80,7 @@ impl From<EstimateFeeError> for ApplicationError {
 
 #[serde_as]
 #[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct FeeEstimate {
     #[serde_as(as = "pathfinder_serde::U256AsHexStr")]
     pub gas_consumed: primitive_types::U256,
@@ -93,6 +106,9 @@ pub async fn estimate_fee(
 ) -> Result<Vec<FeeEstimate>, EstimateFeeError> {
     let span = tracing::Span::current();
 
+    let gas_price = context.gas_price_source.gas_price().await?;
+
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let result = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
         let mut db = context
@@ -101,14 +117,19 @@ pub async fn estimate_fee(
             .context("Creating database connection")?;
         let db = db.transaction().context("Creating database transaction")?;
 
-        let (header, pending) = match input.block_id {
+        let (header, pending, block) = match input.block_id {
             BlockId::Pending => {
                 let pending = context
                     .pending_data
                     .get(&db)
                     .context("Querying pending data")?;
 
-                (pending.header(), Some(pending.state_update.clone()))
+                let block = EstimateBlock::Pending {
+                    parent_hash: pending.block.parent_hash,
+                    transaction_count: pending.block.transactions.len(),
+                };
+
+                (pending.header(), Some(pending.state_update.clone()), block)
             }
             other => {
                 let block_id = other.try_into().expect("Only pending cast should fail");
@@ -117,26 +138,57 @@ pub async fn estimate_fee(
                     .context("Querying block header")?
                     .ok_or(EstimateFeeError::BlockNotFound)?;
 
-                (header, None)
+                let block = EstimateBlock::Block(header.hash);
+
+                (header, None, block)
             }
         };
 
-        let state = ExecutionState::simulation(&db, context.chain_id, header, pending);
+        let header = match gas_price {
+            Some(gas_price) => BlockHeader {
+                gas_price,
+                ..header
+            },
+            None => header,
+        };
 
-        let transactions = input
-            .request
-            .iter()
-            .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
-            .collect::<Result<Vec<_>, _>>()?;
+        let cache_key = context.fee_estimate_cache.key(
+            block,
+            header.gas_price,
+            &input.request,
+            context.chain_id,
+        )?;
+        if let Some(estimates) = context.fee_estimate_cache.get(&cache_key) {
+            return Ok(estimates);
+        }
+
+        let state = ExecutionState::simulation(&db, context.chain_id, header, pending)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation)
+            .with_limits(context.execution_limits.estimate_fee);
+
+        let result = state
+            .run_in_chunks(|state| {
+                let transactions = input
+                    .request
+                    .iter()
+                    .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
+                    .collect::<Result<Vec<_>, _>>()?;
 
-        let result = pathfinder_executor::estimate(state, transactions)?;
+                pathfinder_executor::estimate(state, transactions)
+            })?
+            .into_iter()
+            .map(FeeEstimate::from)
+            .collect::<Vec<_>>();
+
+        context.fee_estimate_cache.insert(cache_key, result.clone());
 
         Ok::<_, EstimateFeeError>(result)
     })
     .await
     .context("Executing transaction")??;
 
-    Ok(result.into_iter().map(Into::into).collect())
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -227,10 +279,11 @@ pub(crate) mod tests {
 
         use super::*;
 
-        use pathfinder_common::{macro_prelude::*, EntryPoint};
+        use pathfinder_common::{macro_prelude::*, EntryPoint, GasPrice};
 
         use pathfinder_common::felt;
 
+        use crate::gas_price::GasPriceSource;
         use crate::v02::types::request::{
             BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV2,
             BroadcastedInvokeTransactionV0, BroadcastedInvokeTransactionV1,
@@ -371,5 +424,124 @@ pub(crate) mod tests {
                 ]
             );
         }
+
+        #[tokio::test]
+        async fn cached_estimates_are_served() {
+            let (context, last_block_header, account_contract_address, _) =
+                crate::test_setup::test_context().await;
+
+            let invoke = |signature| {
+                BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(
+                    BroadcastedInvokeTransactionV1 {
+                        nonce: transaction_nonce!("0x1"),
+                        version: TransactionVersion::ONE_WITH_QUERY_VERSION,
+                        max_fee: Fee::default(),
+                        signature,
+                        sender_address: account_contract_address,
+                        calldata: vec![],
+                    },
+                ))
+            };
+            let input = EstimateFeeInput {
+                request: vec![invoke(vec![])],
+                block_id: BlockId::Number(last_block_header.number),
+            };
+
+            let block = EstimateBlock::Block(last_block_header.hash);
+            let cache = &context.fee_estimate_cache;
+            let gas_price = last_block_header.gas_price;
+            let key = cache
+                .key(block, gas_price, &input.request, context.chain_id)
+                .unwrap();
+            let signed_key = cache
+                .key(
+                    block,
+                    gas_price,
+                    &[invoke(vec![transaction_signature_elem!("0x1")])],
+                    context.chain_id,
+                )
+                .unwrap();
+            assert_ne!(key, signed_key);
+
+            // Served without executing the transaction, which would produce a different estimate.
+            let cached = vec![FeeEstimate {
+                gas_consumed: 1.into(),
+                gas_price: 1.into(),
+                overall_fee: 1.into(),
+            }];
+            cache.insert(key, cached.clone());
+
+            let result = estimate_fee(context, input).await.unwrap();
+            assert_eq!(result, cached);
+        }
+
+        #[tokio::test]
+        async fn v3_transactions_are_rejected() {
+            let (context, last_block_header, account_contract_address, _) =
+                crate::test_setup::test_context().await;
+
+            let input = EstimateFeeInput {
+                request: vec![BroadcastedTransaction::Invoke(
+                    BroadcastedInvokeTransaction::V3(
+                        crate::v02::types::request::BroadcastedInvokeTransactionV3 {
+                            version: TransactionVersion::THREE_WITH_QUERY_VERSION,
+                            signature: vec![],
+                            nonce: transaction_nonce!("0x1"),
+                            resource_bounds: Default::default(),
+                            tip: Default::default(),
+                            paymaster_data: vec![],
+                            account_deployment_data: vec![],
+                            nonce_data_availability_mode: Default::default(),
+                            fee_data_availability_mode: Default::default(),
+                            sender_address: account_contract_address,
+                            calldata: vec![],
+                        },
+                    ),
+                )],
+                block_id: BlockId::Number(last_block_header.number),
+            };
+
+            let error = estimate_fee(context, input).await.unwrap_err();
+            assert_matches::assert_matches!(error, EstimateFeeError::Custom(_));
+        }
+
+        #[tokio::test]
+        async fn fixed_gas_price_overrides_block() {
+            let (context, last_block_header, account_contract_address, _) =
+                crate::test_setup::test_context().await;
+            let fixed = GasPrice(7);
+            assert_ne!(last_block_header.gas_price, fixed);
+            let context = context.with_gas_price_source(GasPriceSource::Fixed(fixed));
+
+            let input = EstimateFeeInput {
+                request: vec![BroadcastedTransaction::Invoke(
+                    BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1 {
+                        nonce: transaction_nonce!("0x1"),
+                        version: TransactionVersion::ONE_WITH_QUERY_VERSION,
+                        max_fee: Fee::default(),
+                        signature: vec![],
+                        sender_address: account_contract_address,
+                        calldata: vec![],
+                    }),
+                )],
+                block_id: BlockId::Number(last_block_header.number),
+            };
+
+            // Only an estimate at the fixed gas price is served from the cache.
+            let block = EstimateBlock::Block(last_block_header.hash);
+            let cache = &context.fee_estimate_cache;
+            let key = cache
+                .key(block, fixed, &input.request, context.chain_id)
+                .unwrap();
+            let cached = vec![FeeEstimate {
+                gas_consumed: 1.into(),
+                gas_price: 7.into(),
+                overall_fee: 7.into(),
+            }];
+            cache.insert(key, cached.clone());
+
+            let result = estimate_fee(context, input).await.unwrap();
+            assert_eq!(result, cached);
+        }
     }
 }
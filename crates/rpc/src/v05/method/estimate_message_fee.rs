@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use pathfinder_common::{
-    felt, BlockId, CallParam, ChainId, ContractAddress, EntryPoint, EthereumAddress,
+    felt, BlockHeader, BlockId, CallParam, ChainId, ContractAddress, EntryPoint, EthereumAddress,
     TransactionHash, TransactionNonce, TransactionVersion,
 };
 use pathfinder_crypto::Felt;
@@ -16,7 +16,13 @@ pub enum EstimateMessageFeeError {
     Internal(anyhow::Error),
     BlockNotFound,
     ContractNotFound,
-    ContractErrorV05 { revert_error: String },
+    ContractErrorV05 {
+        revert_error: String,
+    },
+    ExecutionResourcesExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
     Custom(anyhow::Error),
 }
 
@@ -33,6 +39,9 @@ impl From<pathfinder_executor::CallError> for EstimateMessageFeeError {
             InvalidMessageSelector => Self::Custom(anyhow::anyhow!("Invalid message selector")),
             ContractNotFound => Self::ContractNotFound,
             Reverted(revert_error) => Self::ContractErrorV05 { revert_error },
+            ResourcesExceeded { resource, limit } => {
+                Self::ExecutionResourcesExceeded { resource, limit }
+            }
             Internal(e) => Self::Internal(e),
             Custom(e) => Self::Custom(e),
         }
@@ -57,6 +66,9 @@ impl From<EstimateMessageFeeError> for ApplicationError {
             EstimateMessageFeeError::ContractErrorV05 { revert_error } => {
                 ApplicationError::ContractErrorV05 { revert_error }
             }
+            EstimateMessageFeeError::ExecutionResourcesExceeded { resource, limit } => {
+                ApplicationError::ExecutionResourcesExceeded { resource, limit }
+            }
             EstimateMessageFeeError::Internal(e) => ApplicationError::Internal(e),
             EstimateMessageFeeError::Custom(e) => ApplicationError::Custom(e),
         }
@@ -64,13 +76,13 @@ impl From<EstimateMessageFeeError> for ApplicationError {
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
-#[serde(deny_unknown_fields)]
 pub struct EstimateMessageFeeInput {
     pub message: MsgFromL1,
     pub block_id: BlockId,
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
 pub struct MsgFromL1 {
     pub from_address: EthereumAddress,
     pub to_address: ContractAddress,
@@ -84,6 +96,9 @@ pub async fn estimate_message_fee(
 ) -> Result<FeeEstimate, EstimateMessageFeeError> {
     let span = tracing::Span::current();
 
+    let gas_price = context.gas_price_source.gas_price().await?;
+
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let mut result = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
         let mut db = context
@@ -112,11 +127,25 @@ pub async fn estimate_message_fee(
             }
         };
 
-        let state = ExecutionState::simulation(&db, context.chain_id, header, pending);
+        let header = match gas_price {
+            Some(gas_price) => BlockHeader {
+                gas_price,
+                ..header
+            },
+            None => header,
+        };
+
+        let state = ExecutionState::simulation(&db, context.chain_id, header, pending)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation)
+            .with_limits(context.execution_limits.estimate_fee);
 
-        let transaction = create_executor_transaction(input, context.chain_id)?;
+        let result = state.run_in_chunks(|state| {
+            let transaction = create_executor_transaction(&input, context.chain_id)
+                .map_err(pathfinder_executor::CallError::Internal)?;
 
-        let result = pathfinder_executor::estimate(state, vec![transaction])?;
+            pathfinder_executor::estimate(state, vec![transaction])
+        })?;
 
         Ok::<_, EstimateMessageFeeError>(result)
     })
@@ -139,16 +168,16 @@ pub async fn estimate_message_fee(
 }
 
 fn create_executor_transaction(
-    input: EstimateMessageFeeInput,
+    input: &EstimateMessageFeeInput,
     chain_id: ChainId,
 ) -> anyhow::Result<pathfinder_executor::Transaction> {
-    let transaction_hash = calculate_transaction_hash(&input, chain_id);
+    let transaction_hash = calculate_transaction_hash(input, chain_id);
 
     // prepend sender address to calldata
     let sender_address = Felt::from_be_slice(input.message.from_address.0.as_bytes())
         .expect("Ethereum address is 160 bits");
     let calldata = std::iter::once(pathfinder_common::CallParam(sender_address))
-        .chain(input.message.payload)
+        .chain(input.message.payload.iter().copied())
         .map(|p| p.0.into_starkfelt())
         .collect();
 
@@ -7,7 +7,6 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[cfg_attr(test, derive(Copy, Clone))]
-#[serde(deny_unknown_fields)]
 pub struct GetBlockInput {
     block_id: BlockId,
 }
@@ -57,50 +56,80 @@ pub async fn get_block_with_tx_hashes(
         } else {
             BlockStatus::AcceptedOnL2
         };
+        let l1_transaction = transaction
+            .l1_transaction(header.number.into())
+            .context("Querying L1 transaction")?;
 
         let transactions = transaction
             .transaction_hashes_for_block(header.number.into())
             .context("Reading transaction hashes")?
             .context("Missing block")?;
 
-        Ok(types::Block::from_parts(header, block_status, transactions))
+        Ok(types::Block::from_parts(
+            header,
+            block_status,
+            l1_transaction,
+            transactions,
+        ))
     })
     .await
     .context("Database read panic or shutting down")?
 }
 
-mod types {
+pub(crate) mod types {
     use crate::v02::types::reply::BlockStatus;
     use pathfinder_common::{BlockHeader, TransactionHash};
+    use pathfinder_ethereum::L1Transaction;
     use serde::Serialize;
+    use starknet_gateway_types::reply::MaybePendingBlock;
 
     /// L2 Block as returned by the RPC API.
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct Block {
         #[serde(flatten)]
         pub header: crate::v05::types::BlockHeader,
         pub status: BlockStatus,
         pub transactions: Vec<TransactionHash>,
+        /// The candidate transactions of a pending block, which are not executed yet. This is an
+        /// extension of the specification, and omitted if there are none.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub candidate_transactions: Vec<TransactionHash>,
     }
 
     impl Block {
         pub fn from_parts(
             header: BlockHeader,
             status: BlockStatus,
+            l1_transaction: Option<L1Transaction>,
             transactions: Vec<TransactionHash>,
         ) -> Self {
             Self {
-                header: header.into(),
+                header: crate::v05::types::BlockHeader {
+                    l1_acceptance: l1_transaction.map(Into::into),
+                    ..header.into()
+                },
                 status,
                 transactions,
+                candidate_transactions: Vec::new(),
             }
         }
 
         /// Constructs [Block] from [sequencer's block representation](starknet_gateway_types::reply::Block)
-        pub fn from_sequencer(block: starknet_gateway_types::reply::MaybePendingBlock) -> Self {
+        pub fn from_sequencer(block: MaybePendingBlock) -> Self {
+            let candidate_transactions = match &block {
+                MaybePendingBlock::Pending(pending) => pending
+                    .candidate_transactions
+                    .iter()
+                    .map(|t| t.hash())
+                    .collect(),
+                MaybePendingBlock::Block(_) => Vec::new(),
+            };
+
             Self {
                 status: block.status().into(),
                 transactions: block.transactions().iter().map(|t| t.hash()).collect(),
+                candidate_transactions,
                 header: crate::v05::types::BlockHeader::from_sequencer(block),
             }
         }
@@ -145,6 +174,10 @@ mod tests {
         .unwrap();
 
         assert_eq!(result.header.parent_hash, block_hash_bytes!(b"latest"));
+        assert_eq!(
+            result.candidate_transactions,
+            vec![transaction_hash_bytes!(b"pending candidate")]
+        );
     }
 
     #[tokio::test]
@@ -1,6 +1,6 @@
 use crate::context::RpcContext;
+use crate::dto::TransactionWithHash;
 use crate::v02::types::reply::BlockStatus;
-use crate::v04::types::TransactionWithHash;
 
 use anyhow::Context;
 use pathfinder_common::{BlockId, BlockNumber};
@@ -8,7 +8,6 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[cfg_attr(test, derive(Copy, Clone))]
-#[serde(deny_unknown_fields)]
 pub struct GetBlockInput {
     block_id: BlockId,
 }
@@ -58,10 +57,18 @@ pub async fn get_block_with_txs(
         } else {
             BlockStatus::AcceptedOnL2
         };
+        let l1_transaction = transaction
+            .l1_transaction(header.number.into())
+            .context("Querying L1 transaction")?;
 
         let transactions = get_block_transactions(&transaction, header.number)?;
 
-        Ok(types::Block::from_parts(header, block_status, transactions))
+        Ok(types::Block::from_parts(
+            header,
+            block_status,
+            l1_transaction,
+            transactions,
+        ))
     })
     .await
     .context("Database read panic or shutting down")?
@@ -83,40 +90,68 @@ fn get_block_transactions(
     Ok(txs)
 }
 
-mod types {
+pub(crate) mod types {
+    use crate::dto::TransactionWithHash;
     use crate::v02::types::reply::BlockStatus;
-    use crate::v04::types::TransactionWithHash;
     use pathfinder_common::BlockHeader;
+    use pathfinder_ethereum::L1Transaction;
     use serde::Serialize;
     use serde_with::{serde_as, skip_serializing_none};
+    use starknet_gateway_types::reply::MaybePendingBlock;
 
     /// L2 Block as returned by the RPC API.
     #[serde_as]
     #[skip_serializing_none]
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct Block {
         #[serde(flatten)]
         pub header: crate::v05::types::BlockHeader,
-        #[serde(skip_serializing_if = "BlockStatus::is_pending")]
+        /// Omitted for pending blocks.
+        #[serde(default = "pending", skip_serializing_if = "BlockStatus::is_pending")]
         pub status: BlockStatus,
         pub transactions: Vec<TransactionWithHash>,
+        /// The candidate transactions of a pending block, which are not executed yet. This is an
+        /// extension of the specification, and omitted if there are none.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub candidate_transactions: Vec<TransactionWithHash>,
+    }
+
+    #[cfg(any(test, feature = "rpc-full-serde"))]
+    fn pending() -> BlockStatus {
+        BlockStatus::Pending
     }
 
     impl Block {
         pub fn from_parts(
             header: BlockHeader,
             status: BlockStatus,
+            l1_transaction: Option<L1Transaction>,
             transactions: Vec<TransactionWithHash>,
         ) -> Self {
             Self {
-                header: header.into(),
+                header: crate::v05::types::BlockHeader {
+                    l1_acceptance: l1_transaction.map(Into::into),
+                    ..header.into()
+                },
                 status,
                 transactions,
+                candidate_transactions: Vec::new(),
             }
         }
 
         /// Constructs [Block] from [sequencer's block representation](starknet_gateway_types::reply::Block)
-        pub fn from_sequencer(block: starknet_gateway_types::reply::MaybePendingBlock) -> Self {
+        pub fn from_sequencer(block: MaybePendingBlock) -> Self {
+            let candidate_transactions = match &block {
+                MaybePendingBlock::Pending(pending) => pending
+                    .candidate_transactions
+                    .iter()
+                    .cloned()
+                    .map(Into::into)
+                    .collect(),
+                MaybePendingBlock::Block(_) => Vec::new(),
+            };
+
             Self {
                 status: block.status().into(),
                 transactions: block
@@ -125,6 +160,7 @@ mod types {
                     .cloned()
                     .map(Into::into)
                     .collect(),
+                candidate_transactions,
                 header: crate::v05::types::BlockHeader::from_sequencer(block),
             }
         }
@@ -169,6 +205,11 @@ mod tests {
         .unwrap();
 
         assert_eq!(result.header.parent_hash, block_hash_bytes!(b"latest"));
+        assert_eq!(result.candidate_transactions.len(), 1);
+        assert_eq!(
+            result.candidate_transactions[0].transaction_hash,
+            transaction_hash_bytes!(b"pending candidate")
+        );
     }
 
     #[tokio::test]
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use pathfinder_common::{BlockId, ClassHash};
+use serde_json::value::RawValue;
+
+use crate::context::RpcContext;
+use crate::v02::types::ContractClass;
+
+crate::error::generate_rpc_error_subset!(
+    GetCompiledCasmError: BlockNotFound,
+    ClassHashNotFound,
+    CompilationFailed
+);
+
+#[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct GetCompiledCasmInput {
+    block_id: BlockId,
+    class_hash: ClassHash,
+}
+
+/// Returns the CASM definition of a Sierra class declared at the given block.
+///
+/// Definitions are served from storage. Classes without a stored CASM definition are compiled
+/// using the latest compiler and cached in memory.
+pub async fn get_compiled_casm(
+    context: RpcContext,
+    input: GetCompiledCasmInput,
+) -> Result<Box<RawValue>, GetCompiledCasmError> {
+    let span = tracing::Span::current();
+    let jh = tokio::task::spawn_blocking(move || -> Result<Arc<Vec<u8>>, GetCompiledCasmError> {
+        let _g = span.enter();
+        let mut db = context
+            .storage
+            .connection()
+            .context("Opening database connection")?;
+        let tx = db.transaction().context("Creating database transaction")?;
+
+        let is_pending = if input.block_id.is_pending() {
+            context
+                .pending_data
+                .get(&tx)
+                .context("Querying pending data")?
+                .state_update
+                .class_is_declared(input.class_hash)
+        } else {
+            false
+        };
+
+        // Map block id to the storage variant.
+        let block_id = match input.block_id {
+            BlockId::Pending => pathfinder_storage::BlockId::Latest,
+            other => other.try_into().expect("Only pending cast should fail"),
+        };
+
+        let block_exists = tx.block_exists(block_id)?;
+        if !block_exists {
+            return Err(GetCompiledCasmError::BlockNotFound);
+        }
+
+        // If the class is declared in the pending block, then we shouldn't check the class's
+        // declaration point.
+        let casm = if is_pending {
+            tx.casm_definition(input.class_hash)
+        } else {
+            tx.casm_definition_at(block_id, input.class_hash)
+        }
+        .context("Fetching CASM definition")?;
+
+        match casm {
+            Some(casm) if !casm.is_empty() => return Ok(Arc::new(casm)),
+            _ => {}
+        }
+
+        let definition = if is_pending {
+            tx.class_definition(input.class_hash)
+        } else {
+            tx.class_definition_at(block_id, input.class_hash)
+        }
+        .context("Fetching class definition")?
+        .ok_or(GetCompiledCasmError::ClassHashNotFound)?;
+
+        // The class is known to be declared at this point, so a cached definition is valid.
+        if let Some(casm) = context.casm_cache.get(&input.class_hash) {
+            return Ok(casm);
+        }
+
+        // Cairo 0 classes are not compiled to CASM.
+        let class = ContractClass::from_definition_bytes(&definition)
+            .context("Parsing class definition")?;
+        if let ContractClass::Cairo(_) = class {
+            return Err(GetCompiledCasmError::ClassHashNotFound);
+        }
+
+        let casm = pathfinder_compiler::compile_to_casm_with_latest_compiler(&definition).map_err(
+            |e| {
+                let class_hash = input.class_hash;
+                tracing::warn!(%class_hash, error=%e, "Compiling class to CASM failed");
+                GetCompiledCasmError::CompilationFailed
+            },
+        )?;
+        let casm = Arc::new(casm);
+        context.casm_cache.insert(input.class_hash, casm.clone());
+
+        Ok(casm)
+    });
+
+    let casm = jh
+        .await
+        .context("Reading CASM definition from database")??;
+    let casm = serde_json::from_slice::<Box<RawValue>>(&casm).context("Parsing CASM definition")?;
+
+    Ok(casm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::BlockNumber;
+
+    mod parsing {
+        use super::*;
+        use serde_json::json;
+
+        #[test]
+        fn positional_args() {
+            let positional = json!([
+                { "block_hash": "0xabcde" },
+                "0x12345"
+            ]);
+
+            let input = serde_json::from_value::<GetCompiledCasmInput>(positional).unwrap();
+            let expected = GetCompiledCasmInput {
+                block_id: block_hash!("0xabcde").into(),
+                class_hash: class_hash!("0x12345"),
+            };
+            assert_eq!(input, expected);
+        }
+
+        #[test]
+        fn named_args() {
+            let named = json!({
+                "block_id": { "block_hash": "0xabcde" },
+                "class_hash": "0x12345"
+            });
+
+            let input = serde_json::from_value::<GetCompiledCasmInput>(named).unwrap();
+            let expected = GetCompiledCasmInput {
+                block_id: block_hash!("0xabcde").into(),
+                class_hash: class_hash!("0x12345"),
+            };
+            assert_eq!(input, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn compiled_on_demand_and_cached() {
+        let context = RpcContext::for_tests();
+        let class_hash = class_hash_bytes!(b"class 2 hash (sierra)");
+
+        let casm = get_compiled_casm(
+            context.clone(),
+            GetCompiledCasmInput {
+                block_id: BlockId::Latest,
+                class_hash,
+            },
+        )
+        .await
+        .unwrap();
+
+        let casm = serde_json::from_str::<serde_json::Value>(casm.get()).unwrap();
+        assert!(casm.get("bytecode").is_some());
+        assert!(context.casm_cache.get(&class_hash).is_some());
+    }
+
+    #[tokio::test]
+    async fn not_yet_declared() {
+        let context = RpcContext::for_tests();
+
+        let error = get_compiled_casm(
+            context,
+            GetCompiledCasmInput {
+                block_id: BlockNumber::new_or_panic(1).into(),
+                class_hash: class_hash_bytes!(b"class 2 hash (sierra)"),
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert_matches!(error, GetCompiledCasmError::ClassHashNotFound);
+    }
+
+    #[tokio::test]
+    async fn cairo_class() {
+        let context = RpcContext::for_tests();
+
+        let error = get_compiled_casm(
+            context,
+            GetCompiledCasmInput {
+                block_id: BlockId::Latest,
+                class_hash: class_hash_bytes!(b"class 0 hash"),
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert_matches!(error, GetCompiledCasmError::ClassHashNotFound);
+    }
+
+    #[tokio::test]
+    async fn block_not_found() {
+        let context = RpcContext::for_tests();
+
+        let error = get_compiled_casm(
+            context,
+            GetCompiledCasmInput {
+                block_id: block_hash_bytes!(b"non-existent").into(),
+                class_hash: class_hash_bytes!(b"class 2 hash (sierra)"),
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert_matches!(error, GetCompiledCasmError::BlockNotFound);
+    }
+}
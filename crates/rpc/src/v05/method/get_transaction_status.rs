@@ -52,7 +52,43 @@ impl serde::Serialize for GetTransactionStatusOutput {
     }
 }
 
+#[cfg(any(test, feature = "rpc-full-serde"))]
+impl<'de> serde::Deserialize<'de> for GetTransactionStatusOutput {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct Status {
+            finality_status: String,
+            execution_status: Option<ExecutionStatus>,
+        }
+
+        let status = <Status as serde::Deserialize>::deserialize(deserializer)?;
+        let execution_status = || {
+            status
+                .execution_status
+                .clone()
+                .ok_or_else(|| D::Error::missing_field("execution_status"))
+        };
+
+        match status.finality_status.as_str() {
+            "RECEIVED" => Ok(Self::Received),
+            "REJECTED" => Ok(Self::Rejected),
+            "ACCEPTED_ON_L1" => Ok(Self::AcceptedOnL1(execution_status()?)),
+            "ACCEPTED_ON_L2" => Ok(Self::AcceptedOnL2(execution_status()?)),
+            other => Err(D::Error::unknown_variant(
+                other,
+                &["RECEIVED", "REJECTED", "ACCEPTED_ON_L1", "ACCEPTED_ON_L2"],
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExecutionStatus {
     Succeeded,
@@ -177,6 +213,9 @@ mod tests {
     fn output_serialization(#[case] output: GetTransactionStatusOutput, #[case] expected: &str) {
         let json = serde_json::to_string(&output).unwrap();
         assert_eq!(json, expected);
+
+        let parsed = serde_json::from_str::<GetTransactionStatusOutput>(&json).unwrap();
+        assert_eq!(parsed, output);
     }
 
     #[tokio::test]
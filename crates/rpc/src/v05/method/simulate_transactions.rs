@@ -9,7 +9,6 @@ use pathfinder_executor::{types::TransactionSimulation, CallError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct SimulateTransactionInput {
     block_id: BlockId,
     transactions: Vec<BroadcastedTransaction>,
@@ -25,7 +24,13 @@ pub enum SimulateTransactionError {
     Custom(anyhow::Error),
     BlockNotFound,
     ContractNotFound,
-    ContractErrorV05 { revert_error: String },
+    ContractErrorV05 {
+        revert_error: String,
+    },
+    ExecutionResourcesExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
 }
 
 impl From<anyhow::Error> for SimulateTransactionError {
@@ -44,6 +49,9 @@ impl From<SimulateTransactionError> for crate::error::ApplicationError {
             SimulateTransactionError::ContractErrorV05 { revert_error } => {
                 Self::ContractErrorV05 { revert_error }
             }
+            SimulateTransactionError::ExecutionResourcesExceeded { resource, limit } => {
+                Self::ExecutionResourcesExceeded { resource, limit }
+            }
         }
     }
 }
@@ -55,6 +63,9 @@ impl From<CallError> for SimulateTransactionError {
             ContractNotFound => Self::ContractNotFound,
             InvalidMessageSelector => Self::Custom(anyhow::anyhow!("Invalid message selector")),
             Reverted(revert_error) => Self::ContractErrorV05 { revert_error },
+            ResourcesExceeded { resource, limit } => {
+                Self::ExecutionResourcesExceeded { resource, limit }
+            }
             Internal(e) => Self::Internal(e),
             Custom(e) => Self::Custom(e),
         }
@@ -75,6 +86,7 @@ pub async fn simulate_transactions(
     input: SimulateTransactionInput,
 ) -> Result<SimulateTransactionOutput, SimulateTransactionError> {
     let span = tracing::Span::current();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -90,6 +102,12 @@ pub async fn simulate_transactions(
             .iter()
             .any(|flag| flag == &dto::SimulationFlag::SkipFeeCharge);
 
+        let skip_balance_check = input
+            .simulation_flags
+            .0
+            .iter()
+            .any(|flag| flag == &dto::SimulationFlag::SkipDeployAccountBalanceCheck);
+
         let mut db = context
             .storage
             .connection()
@@ -118,16 +136,26 @@ pub async fn simulate_transactions(
         };
 
         let state =
-            pathfinder_executor::ExecutionState::simulation(&db, context.chain_id, header, pending);
-
-        let transactions = input
-            .transactions
-            .iter()
-            .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let txs =
-            pathfinder_executor::simulate(state, transactions, skip_validate, skip_fee_charge)?;
+            pathfinder_executor::ExecutionState::simulation(&db, context.chain_id, header, pending)
+                .with_remote_state(context.remote_state.clone())
+                .with_cancellation(cancellation)
+                .with_limits(context.execution_limits.simulate);
+
+        let txs = state.run_in_chunks(|state| {
+            let transactions = input
+                .transactions
+                .iter()
+                .map(|tx| crate::executor::map_broadcasted_transaction(tx, context.chain_id))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            pathfinder_executor::simulate(
+                state,
+                transactions,
+                skip_validate,
+                skip_fee_charge,
+                skip_balance_check,
+            )
+        })?;
         let txs = txs.into_iter().map(Into::into).collect();
         Ok(SimulateTransactionOutput(txs))
     })
@@ -151,7 +179,7 @@ pub mod dto {
 
     #[serde_as]
     #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
-    // #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     #[serde(deny_unknown_fields)]
     pub struct FeeEstimate {
         /// The Ethereum gas cost of the transaction
@@ -176,14 +204,21 @@ pub mod dto {
     }
 
     #[derive(Debug, Deserialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Serialize))]
     pub enum SimulationFlag {
         #[serde(rename = "SKIP_FEE_CHARGE")]
         SkipFeeCharge,
         #[serde(rename = "SKIP_VALIDATE")]
         SkipValidate,
+        /// Pathfinder specific: credit the accounts deployed by DEPLOY_ACCOUNT transactions with
+        /// their max fee, so that the deployment of unfunded accounts can be simulated with the
+        /// fee charged.
+        #[serde(rename = "SKIP_DEPLOY_ACCOUNT_BALANCE_CHECK")]
+        SkipDeployAccountBalanceCheck,
     }
 
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub enum CallType {
         #[serde(rename = "CALL")]
         Call,
@@ -206,8 +241,8 @@ pub mod dto {
     #[serde_with::serde_as]
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct FunctionInvocation {
-        #[serde(default)]
         pub call_type: CallType,
         #[serde(default)]
         #[serde_as(as = "RpcFelt")]
@@ -217,7 +252,6 @@ pub mod dto {
         #[serde(default)]
         #[serde_as(as = "Option<RpcFelt>")]
         pub class_hash: Option<Felt>,
-        #[serde(default)]
         pub entry_point_type: EntryPointType,
         #[serde(default)]
         pub events: Vec<OrderedEvent>,
@@ -251,6 +285,7 @@ pub mod dto {
     }
 
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub enum EntryPointType {
         #[serde(rename = "CONSTRUCTOR")]
         Constructor,
@@ -273,6 +308,7 @@ pub mod dto {
 
     #[serde_with::serde_as]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct OrderedMsgToL1 {
         pub order: usize,
         #[serde_as(as = "Vec<RpcFelt>")]
@@ -296,6 +332,7 @@ pub mod dto {
 
     #[serde_with::serde_as]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct OrderedEvent {
         pub order: i64,
         #[serde_as(as = "Vec<RpcFelt>")]
@@ -315,6 +352,7 @@ pub mod dto {
     }
 
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     #[serde(tag = "type")]
     pub enum TransactionTrace {
         #[serde(rename = "DECLARE")]
@@ -341,6 +379,7 @@ pub mod dto {
 
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct DeclareTxnTrace {
         #[serde(default)]
         pub fee_transfer_invocation: Option<FunctionInvocation>,
@@ -361,6 +400,7 @@ pub mod dto {
 
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct DeployAccountTxnTrace {
         #[serde(default)]
         pub constructor_invocation: Option<FunctionInvocation>,
@@ -383,6 +423,7 @@ pub mod dto {
     }
 
     #[derive(Clone, Debug, Default, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     #[serde(untagged)]
     pub enum ExecuteInvocation {
         #[default]
@@ -390,11 +431,25 @@ pub mod dto {
         FunctionInvocation(FunctionInvocation),
         RevertedReason {
             revert_reason: String,
+            /// The revert reason's felts decoded into strings, a pathfinder extension.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            decoded_revert_reason: Option<String>,
         },
     }
 
+    impl ExecuteInvocation {
+        pub fn reverted(revert_reason: String) -> Self {
+            let decoded_revert_reason = crate::v05::types::revert_reason::decode(&revert_reason);
+            Self::RevertedReason {
+                revert_reason,
+                decoded_revert_reason,
+            }
+        }
+    }
+
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct InvokeTxnTrace {
         #[serde(default)]
         pub execute_invocation: ExecuteInvocation,
@@ -418,7 +473,7 @@ pub mod dto {
                     }
                     pathfinder_executor::types::ExecuteInvocation::RevertedReason(
                         revert_reason,
-                    ) => ExecuteInvocation::RevertedReason { revert_reason },
+                    ) => ExecuteInvocation::reverted(revert_reason),
                 },
                 fee_transfer_invocation: trace.fee_transfer_invocation.map(Into::into),
                 state_diff: Some(trace.state_diff.into()),
@@ -428,6 +483,7 @@ pub mod dto {
 
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct L1HandlerTxnTrace {
         #[serde(default)]
         pub function_invocation: Option<FunctionInvocation>,
@@ -445,10 +501,9 @@ pub mod dto {
 
     #[serde_with::skip_serializing_none]
     #[derive(Clone, Debug, Serialize, Eq, PartialEq)]
+    #[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
     pub struct SimulatedTransaction {
-        #[serde(default)]
         pub fee_estimation: FeeEstimate,
-        #[serde(default)]
         pub transaction_trace: TransactionTrace,
     }
 
@@ -640,6 +695,49 @@ pub(crate) mod tests {
         pretty_assertions::assert_eq!(result.0, expected);
     }
 
+    #[tokio::test]
+    async fn deploy_account_with_skip_deploy_account_balance_check() {
+        let (context, _, _, _) = crate::test_setup::test_context().await;
+
+        let input = |simulation_flags: serde_json::Value| {
+            let input_json = serde_json::json!({
+                "block_id": {"block_number": 1},
+                "transactions": [
+                    {
+                        "contract_address_salt": "0x46c0d4abf0192a788aca261e58d7031576f7d8ea5229f452b0f23e691dd5971",
+                        "max_fee": "0x100000",
+                        "signature": [],
+                        "class_hash": DUMMY_ACCOUNT_CLASS_HASH,
+                        "nonce": "0x0",
+                        "version": TransactionVersion::ONE_WITH_QUERY_VERSION,
+                        "constructor_calldata": [],
+                        "type": "DEPLOY_ACCOUNT"
+                    }
+                ],
+                "simulation_flags": simulation_flags
+            });
+            SimulateTransactionInput::deserialize(&input_json).unwrap()
+        };
+
+        // The deployed address holds no fee token, so the fee can't be charged.
+        simulate_transactions(context.clone(), input(serde_json::json!([])))
+            .await
+            .unwrap_err();
+
+        let result = simulate_transactions(
+            context,
+            input(serde_json::json!(["SKIP_DEPLOY_ACCOUNT_BALANCE_CHECK"])),
+        )
+        .await
+        .unwrap();
+
+        let dto::TransactionTrace::DeployAccount(trace) = &result.0[0].transaction_trace else {
+            panic!("Expected a DEPLOY_ACCOUNT trace");
+        };
+        assert!(trace.fee_transfer_invocation.is_some());
+        assert!(result.0[0].fee_estimation.overall_fee > 0.into());
+    }
+
     #[tokio::test]
     async fn declare_cairo_v0_class() {
         pub const CAIRO0_DEFINITION: &[u8] =
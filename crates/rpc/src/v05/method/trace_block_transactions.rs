@@ -1,5 +1,5 @@
 use anyhow::Context;
-use pathfinder_common::{BlockId, TransactionHash};
+use pathfinder_common::{BlockId, ContractAddress, TransactionHash};
 use pathfinder_executor::{CallError, ExecutionState};
 use serde::{Deserialize, Serialize};
 use starknet_gateway_client::GatewayApi;
@@ -16,12 +16,18 @@ use starknet_gateway_types::reply::transaction::Transaction as GatewayTransactio
 use super::simulate_transactions::dto::TransactionTrace;
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct TraceBlockTransactionsInput {
     block_id: BlockId,
+    /// Pathfinder extension which restricts the traces to the calls touching this contract.
+    ///
+    /// Transactions which don't call the contract are omitted, and the traces of the others only
+    /// contain the calls to the contract along with the calls leading to them.
+    #[serde(default)]
+    contract_address: Option<ContractAddress>,
 }
 
 #[derive(Debug, Serialize, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct Trace {
     pub transaction_hash: TransactionHash,
     pub trace_root: TransactionTrace,
@@ -74,6 +80,9 @@ impl From<CallError> for TraceBlockTransactionsError {
                 Self::Custom(anyhow::anyhow!("Invalid message selector"))
             }
             CallError::Reverted(revert_error) => Self::ContractErrorV05 { revert_error },
+            CallError::ResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             CallError::Internal(e) => Self::Internal(e),
             CallError::Custom(e) => Self::Custom(e),
         }
@@ -106,7 +115,7 @@ pub(crate) fn map_gateway_trace(
         }
         GatewayTransaction::Invoke(_) => TransactionTrace::Invoke(InvokeTxnTrace {
             execute_invocation: if let Some(revert_reason) = trace.revert_error {
-                ExecuteInvocation::RevertedReason { revert_reason }
+                ExecuteInvocation::reverted(revert_reason)
             } else {
                 trace
                     .function_invocation
@@ -136,6 +145,7 @@ pub async fn trace_block_transactions(
     let span = tracing::Span::current();
 
     let storage = context.storage.clone();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let traces = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -195,14 +205,19 @@ pub async fn trace_block_transactions(
             .map(|transaction| compose_executor_transaction(transaction, &db))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let state = ExecutionState::trace(&db, context.chain_id, header, None);
+        let state = ExecutionState::trace(&db, context.chain_id, header, None)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation);
         let traces = pathfinder_executor::trace_all(state, transactions, true, true)?;
 
         let result = traces
             .into_iter()
-            .map(|(hash, trace)| Trace {
-                transaction_hash: hash,
-                trace_root: trace.into(),
+            .filter_map(|(hash, mut trace)| match input.contract_address {
+                Some(address) if !trace.retain_calls_touching(address) => None,
+                _ => Some(Trace {
+                    transaction_hash: hash,
+                    trace_root: trace.into(),
+                }),
             })
             .collect();
 
@@ -216,6 +231,14 @@ pub async fn trace_block_transactions(
         LocalExecution::Unsupported(transactions) => transactions,
     };
 
+    // Traces fetched from the gateway are not filtered, as they are not constructed locally.
+    if input.contract_address.is_some() {
+        return Err(TraceBlockTransactionsError::Custom(anyhow::anyhow!(
+            "Filtering by contract address is only available for blocks of Starknet {} or later",
+            VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY
+        )));
+    }
+
     context
         .sequencer
         .block_traces(input.block_id)
@@ -365,6 +388,7 @@ pub(crate) mod tests {
 
         let input = TraceBlockTransactionsInput {
             block_id: next_block_header.hash.into(),
+            contract_address: None,
         };
         let output = trace_block_transactions(context, input).await.unwrap();
         let expected = TraceBlockTransactionsOutput(traces);
@@ -373,6 +397,46 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_contract_address_filter() -> anyhow::Result<()> {
+        use crate::v05::method::simulate_transactions::dto::FunctionInvocation;
+
+        let (context, next_block_header, traces) = setup_multi_tx_trace_test().await?;
+
+        // The second transaction calls the universal deployer from the account's __execute__.
+        let TransactionTrace::Invoke(InvokeTxnTrace {
+            execute_invocation: ExecuteInvocation::FunctionInvocation(execute),
+            state_diff,
+            ..
+        }) = &traces[1].trace_root
+        else {
+            panic!("Expected an invoke trace");
+        };
+        let deployer_call = execute.calls[0].clone();
+
+        let input = TraceBlockTransactionsInput {
+            block_id: next_block_header.hash.into(),
+            contract_address: Some(deployer_call.function_call.contract_address),
+        };
+        let output = trace_block_transactions(context, input).await.unwrap();
+
+        let expected = TraceBlockTransactionsOutput(vec![Trace {
+            transaction_hash: traces[1].transaction_hash,
+            trace_root: TransactionTrace::Invoke(InvokeTxnTrace {
+                validate_invocation: None,
+                execute_invocation: ExecuteInvocation::FunctionInvocation(FunctionInvocation {
+                    calls: vec![deployer_call],
+                    ..execute.clone()
+                }),
+                fee_transfer_invocation: None,
+                state_diff: state_diff.clone(),
+            }),
+        }]);
+
+        pretty_assertions::assert_eq!(output, expected);
+        Ok(())
+    }
+
     pub(crate) async fn setup_multi_tx_trace_pending_test(
     ) -> anyhow::Result<(RpcContext, Vec<Trace>)> {
         use super::super::simulate_transactions::tests::fixtures;
@@ -448,6 +512,7 @@ pub(crate) mod tests {
                 timestamp: last_block_header.timestamp,
                 transaction_receipts,
                 transactions: transactions.iter().cloned().map(Into::into).collect(),
+                candidate_transactions: vec![],
                 starknet_version: last_block_header.starknet_version,
             };
 
@@ -492,6 +557,7 @@ pub(crate) mod tests {
 
         let input = TraceBlockTransactionsInput {
             block_id: BlockId::Pending,
+            contract_address: None,
         };
         let output = trace_block_transactions(context, input).await.unwrap();
         let expected = TraceBlockTransactionsOutput(traces);
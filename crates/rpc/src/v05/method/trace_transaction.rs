@@ -1,5 +1,6 @@
 use anyhow::Context;
-use pathfinder_common::TransactionHash;
+use pathfinder_common::{ContractAddress, TransactionHash};
+use pathfinder_crypto::Felt;
 use pathfinder_executor::{CallError, ExecutionState};
 use serde::{Deserialize, Serialize};
 use starknet_gateway_client::GatewayApi;
@@ -13,16 +14,88 @@ use crate::{
     executor::ExecutionStateError,
 };
 
-use super::simulate_transactions::dto::TransactionTrace;
+use crate::felt::RpcFelt;
+
+use super::simulate_transactions::dto::{CallType, EntryPointType, TransactionTrace};
 
 #[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
 pub struct TraceTransactionInput {
     pub transaction_hash: TransactionHash,
+    /// Pathfinder extension selecting the output format.
+    #[serde(default)]
+    pub format: TraceFormat,
+}
+
+/// The format a trace is returned in. All formats are generated from the same trace of the
+/// executor.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TraceFormat {
+    /// The trace as defined by the specification.
+    #[default]
+    Json,
+    /// A list of all calls, depth-first in execution order.
+    FlatCalls,
+    /// Folded stacks weighted by Cairo steps, as consumed by flamegraph tooling.
+    FoldedStacks,
+}
+
+#[derive(Debug, Serialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum TraceTransactionOutput {
+    Json(TransactionTrace),
+    FlatCalls(Vec<FlatCall>),
+    FoldedStacks(String),
 }
 
+impl TraceTransactionOutput {
+    fn new(trace: pathfinder_executor::types::TransactionTrace, format: TraceFormat) -> Self {
+        match format {
+            TraceFormat::Json => Self::Json(trace.into()),
+            TraceFormat::FlatCalls => Self::FlatCalls(
+                trace
+                    .flattened_calls()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            ),
+            TraceFormat::FoldedStacks => Self::FoldedStacks(trace.folded_stacks()),
+        }
+    }
+}
+
+#[serde_with::serde_as]
 #[derive(Debug, Serialize, Eq, PartialEq)]
-pub struct TraceTransactionOutput(pub TransactionTrace);
+pub struct FlatCall {
+    pub phase: &'static str,
+    pub depth: usize,
+    pub contract_address: ContractAddress,
+    #[serde_as(as = "RpcFelt")]
+    pub entry_point_selector: Felt,
+    #[serde_as(as = "Option<RpcFelt>")]
+    pub class_hash: Option<Felt>,
+    #[serde_as(as = "RpcFelt")]
+    pub caller_address: Felt,
+    pub call_type: CallType,
+    pub entry_point_type: EntryPointType,
+    pub steps: usize,
+}
+
+impl From<pathfinder_executor::types::FlattenedCall> for FlatCall {
+    fn from(call: pathfinder_executor::types::FlattenedCall) -> Self {
+        Self {
+            phase: call.phase,
+            depth: call.depth,
+            contract_address: call.contract_address,
+            entry_point_selector: call.selector,
+            class_hash: call.class_hash,
+            caller_address: call.caller_address,
+            call_type: call.call_type.into(),
+            entry_point_type: call.entry_point_type.into(),
+            steps: call.steps,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum TraceTransactionError {
@@ -50,6 +123,9 @@ impl From<CallError> for TraceTransactionError {
                 Self::Custom(anyhow::anyhow!("Invalid message selector"))
             }
             CallError::Reverted(revert_error) => Self::ContractErrorV05 { revert_error },
+            CallError::ResourcesExceeded { resource, limit } => {
+                Self::Custom(crate::executor::resources_exceeded_error(resource, limit))
+            }
             CallError::Internal(e) => Self::Internal(e),
             CallError::Custom(e) => Self::Custom(e),
         }
@@ -96,11 +172,12 @@ pub async fn trace_transaction(
 ) -> Result<TraceTransactionOutput, TraceTransactionError> {
     #[allow(clippy::large_enum_variant)]
     enum LocalExecution {
-        Success(TransactionTrace),
+        Success(TraceTransactionOutput),
         Unsupported(starknet_gateway_types::reply::transaction::Transaction),
     }
 
     let span = tracing::Span::current();
+    let (cancellation, _cancel_on_drop) = crate::executor::cancel_on_drop();
     let local = tokio::task::spawn_blocking(move || {
         let _g = span.enter();
 
@@ -189,7 +266,9 @@ pub async fn trace_transaction(
             )
         };
 
-        let state = ExecutionState::trace(&db, context.chain_id, header, None);
+        let state = ExecutionState::trace(&db, context.chain_id, header, None)
+            .with_remote_state(context.remote_state.clone())
+            .with_cancellation(cancellation);
 
         let transactions = transactions
             .iter()
@@ -198,16 +277,25 @@ pub async fn trace_transaction(
 
         pathfinder_executor::trace_one(state, transactions, input.transaction_hash, true, true)
             .map_err(TraceTransactionError::from)
-            .map(|x| LocalExecution::Success(x.into()))
+            .map(|x| LocalExecution::Success(TraceTransactionOutput::new(x, input.format)))
     })
     .await
     .context("trace_transaction: execution")??;
 
     let transaction = match local {
-        LocalExecution::Success(trace) => return Ok(TraceTransactionOutput(trace)),
+        LocalExecution::Success(output) => return Ok(output),
         LocalExecution::Unsupported(x) => x,
     };
 
+    // Traces fetched from the gateway don't carry the executor's call details.
+    if input.format != TraceFormat::Json {
+        return Err(TraceTransactionError::Custom(anyhow::anyhow!(
+            "The {:?} format is only available for transactions of Starknet {} or later",
+            input.format,
+            VERSIONS_LOWER_THAN_THIS_SHOULD_FALL_BACK_TO_FETCHING_TRACE_FROM_GATEWAY
+        )));
+    }
+
     let trace = context
         .sequencer
         .transaction_trace(input.transaction_hash)
@@ -216,7 +304,7 @@ pub async fn trace_transaction(
 
     let trace = map_gateway_trace(transaction, trace);
 
-    Ok(TraceTransactionOutput(trace))
+    Ok(TraceTransactionOutput::Json(trace))
 }
 
 #[cfg(test)]
@@ -233,15 +321,100 @@ pub mod tests {
         for trace in traces {
             let input = TraceTransactionInput {
                 transaction_hash: trace.transaction_hash,
+                format: TraceFormat::Json,
             };
             let output = trace_transaction(context.clone(), input).await.unwrap();
-            let expected = TraceTransactionOutput(trace.trace_root);
+            let expected = TraceTransactionOutput::Json(trace.trace_root);
             pretty_assertions::assert_eq!(output, expected);
         }
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_flat_calls_and_folded_stacks() -> anyhow::Result<()> {
+        use crate::v05::method::simulate_transactions::dto::{
+            ExecuteInvocation, FunctionInvocation,
+        };
+
+        fn flatten(
+            depth: usize,
+            invocation: &FunctionInvocation,
+            calls: &mut Vec<(usize, ContractAddress, Felt)>,
+        ) {
+            calls.push((
+                depth,
+                invocation.function_call.contract_address,
+                invocation.function_call.entry_point_selector.0,
+            ));
+            for call in &invocation.calls {
+                flatten(depth + 1, call, calls);
+            }
+        }
+
+        let (context, _, traces) = setup_multi_tx_trace_test().await?;
+
+        for trace in traces {
+            let invocations = match &trace.trace_root {
+                TransactionTrace::Invoke(trace) => [
+                    trace.validate_invocation.as_ref(),
+                    match &trace.execute_invocation {
+                        ExecuteInvocation::FunctionInvocation(invocation) => Some(invocation),
+                        _ => None,
+                    },
+                    trace.fee_transfer_invocation.as_ref(),
+                ],
+                TransactionTrace::Declare(trace) => [
+                    trace.validate_invocation.as_ref(),
+                    trace.fee_transfer_invocation.as_ref(),
+                    None,
+                ],
+                other => panic!("Unexpected trace {other:?}"),
+            };
+            let mut expected = Vec::new();
+            for invocation in invocations.into_iter().flatten() {
+                flatten(0, invocation, &mut expected);
+            }
+
+            let input = TraceTransactionInput {
+                transaction_hash: trace.transaction_hash,
+                format: TraceFormat::FlatCalls,
+            };
+            let TraceTransactionOutput::FlatCalls(calls) =
+                trace_transaction(context.clone(), input).await.unwrap()
+            else {
+                panic!("Expected flat calls");
+            };
+            let calls = calls
+                .iter()
+                .map(|call| (call.depth, call.contract_address, call.entry_point_selector))
+                .collect::<Vec<_>>();
+            pretty_assertions::assert_eq!(calls, expected);
+
+            let input = TraceTransactionInput {
+                transaction_hash: trace.transaction_hash,
+                format: TraceFormat::FoldedStacks,
+            };
+            let TraceTransactionOutput::FoldedStacks(stacks) =
+                trace_transaction(context.clone(), input).await.unwrap()
+            else {
+                panic!("Expected folded stacks");
+            };
+            assert!(!stacks.is_empty());
+            for line in stacks.lines() {
+                let (stack, steps) = line.rsplit_once(' ').unwrap();
+                assert!(steps.parse::<usize>().unwrap() > 0);
+                let phase = stack.split(';').next().unwrap();
+                assert!(
+                    ["validate", "execute", "fee_transfer"].contains(&phase),
+                    "{line}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_multiple_pending_transactions() -> anyhow::Result<()> {
         let (context, traces) = setup_multi_tx_trace_pending_test().await?;
@@ -249,9 +422,10 @@ pub mod tests {
         for trace in traces {
             let input = TraceTransactionInput {
                 transaction_hash: trace.transaction_hash,
+                format: TraceFormat::Json,
             };
             let output = trace_transaction(context.clone(), input).await.unwrap();
-            let expected = TraceTransactionOutput(trace.trace_root);
+            let expected = TraceTransactionOutput::Json(trace.trace_root);
             pretty_assertions::assert_eq!(output, expected);
         }
 
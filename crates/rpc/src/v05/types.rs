@@ -1,3 +1,6 @@
+pub use crate::dto::L1Acceptance;
+pub(crate) mod revert_reason;
+
 use crate::felt::RpcFelt;
 use pathfinder_common::GasPrice;
 use pathfinder_common::{
@@ -9,6 +12,7 @@ use serde_with::{serde_as, skip_serializing_none};
 
 #[serde_as]
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct ResourcePrice {
     #[serde_as(as = "pathfinder_serde::GasPriceAsHexStr")]
     pub price_in_wei: GasPrice,
@@ -25,6 +29,7 @@ impl From<GasPrice> for ResourcePrice {
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "rpc-full-serde"), derive(serde::Deserialize))]
 pub struct BlockHeader {
     #[serde_as(as = "Option<RpcFelt>")]
     pub block_hash: Option<BlockHash>,
@@ -38,6 +43,7 @@ pub struct BlockHeader {
     pub sequencer_address: SequencerAddress,
     pub l1_gas_price: ResourcePrice,
     pub starknet_version: StarknetVersion,
+    pub l1_acceptance: Option<L1Acceptance>,
 }
 
 impl From<pathfinder_common::BlockHeader> for BlockHeader {
@@ -51,6 +57,7 @@ impl From<pathfinder_common::BlockHeader> for BlockHeader {
             sequencer_address: header.sequencer_address,
             l1_gas_price: header.gas_price.into(),
             starknet_version: header.starknet_version,
+            l1_acceptance: None,
         }
     }
 }
@@ -72,6 +79,7 @@ impl BlockHeader {
                     .unwrap_or(SequencerAddress(Felt::ZERO)),
                 l1_gas_price: block.gas_price.unwrap_or_default().into(),
                 starknet_version: block.starknet_version,
+                l1_acceptance: None,
             },
             MaybePendingBlock::Pending(pending) => Self {
                 block_hash: None,
@@ -82,6 +90,7 @@ impl BlockHeader {
                 sequencer_address: pending.sequencer_address,
                 l1_gas_price: pending.gas_price.into(),
                 starknet_version: pending.starknet_version,
+                l1_acceptance: None,
             },
         }
     }
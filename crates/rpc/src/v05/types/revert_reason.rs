@@ -0,0 +1,203 @@
+//! Decoding of the felts in revert reasons.
+//!
+//! Contracts fail with an array of felts, which the executor renders as hex in the revert
+//! reason. Most contracts follow one of the standard conventions for these felts, which are
+//! decoded here into readable strings:
+//!
+//! - short strings, e.g. `'ERC20: insufficient balance'`, as used by most libraries,
+//! - byte arrays, as produced by Cairo's `panic!` with a formatted message.
+//!
+//! Felts which follow neither convention, e.g. numeric error codes, are kept as hex.
+
+use pathfinder_crypto::Felt;
+
+/// The felt which prefixes a serialized byte array in panic data.
+const BYTE_ARRAY_MAGIC: &str = "0x46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3";
+
+/// The number of bytes stored in each full word of a byte array.
+const BYTES_PER_WORD: usize = 31;
+
+/// What precedes the felts of a failure in the executor's revert reason.
+const FAILURE_REASON: &str = "Failure reason:";
+
+/// Decodes the failure felts of a revert reason, one line per failure.
+///
+/// Returns [None] if the revert reason contains no felt which could be decoded.
+pub fn decode(revert_reason: &str) -> Option<String> {
+    let mut decoded_any = false;
+
+    let failures = revert_reason
+        .split(FAILURE_REASON)
+        .skip(1)
+        .map(|failure| {
+            // The failure data ends with the line.
+            let failure = failure.lines().next().unwrap_or_default();
+            let felts = hex_felts(failure);
+
+            let (elements, decoded) = decode_felts(&felts);
+            decoded_any |= decoded;
+            elements.join(", ")
+        })
+        .collect::<Vec<_>>();
+
+    decoded_any.then(|| failures.join("\n"))
+}
+
+/// Decodes each felt, or each byte array, into a string. Felts which can't be decoded are kept
+/// as they were written. The flag is set if any element was decoded.
+fn decode_felts(felts: &[(&str, Felt)]) -> (Vec<String>, bool) {
+    let magic = Felt::from_hex_str(BYTE_ARRAY_MAGIC).expect("Valid felt");
+
+    let mut elements = Vec::new();
+    let mut decoded = false;
+    let mut rest = felts;
+    while let Some(((hex, first), tail)) = rest.split_first() {
+        if *first == magic {
+            let words = tail.iter().map(|(_, felt)| *felt).collect::<Vec<_>>();
+            if let Some((string, consumed)) = byte_array(&words) {
+                elements.push(format!("'{string}'"));
+                decoded = true;
+                rest = &tail[consumed..];
+                continue;
+            }
+        }
+
+        match short_string(first) {
+            Some(string) => {
+                elements.push(format!("'{string}'"));
+                decoded = true;
+            }
+            None => elements.push(hex.to_string()),
+        }
+        rest = tail;
+    }
+
+    (elements, decoded)
+}
+
+/// Extracts the hex-encoded felts in `text`, together with their text.
+fn hex_felts(text: &str) -> Vec<(&str, Felt)> {
+    text.match_indices("0x")
+        .filter_map(|(start, _)| {
+            let end = text[start + 2..]
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .map_or(text.len(), |end| start + 2 + end);
+            let hex = &text[start..end];
+            if hex.len() == 2 {
+                return None;
+            }
+            Felt::from_hex_str(hex).ok().map(|felt| (hex, felt))
+        })
+        .collect()
+}
+
+/// The printable ASCII string a felt encodes, if any.
+fn short_string(felt: &Felt) -> Option<String> {
+    let bytes = felt.to_be_bytes();
+    let start = bytes.iter().position(|b| *b != 0)?;
+    let bytes = &bytes[start..];
+
+    bytes
+        .iter()
+        .all(|b| (b' '..=b'~').contains(b))
+        .then(|| String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Decodes a serialized byte array, i.e. the number of full words, the full words, the
+/// pending word and the length of the pending word. Returns the string and the number of felts
+/// it was serialized as.
+fn byte_array(felts: &[Felt]) -> Option<(String, usize)> {
+    let (count, felts) = felts.split_first()?;
+    let count = usize::try_from(felt_to_u64(count)?).ok()?;
+    // The count is untrusted, and may be close to the maximum.
+    if felts.len().saturating_sub(2) < count {
+        return None;
+    }
+    let (words, felts) = felts.split_at(count);
+    let (pending, felts) = felts.split_first()?;
+    let (pending_len, _) = felts.split_first()?;
+    let pending_len = usize::try_from(felt_to_u64(pending_len)?).ok()?;
+    if pending_len >= BYTES_PER_WORD {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(count * BYTES_PER_WORD + pending_len);
+    for word in words {
+        bytes.extend_from_slice(&word.to_be_bytes()[32 - BYTES_PER_WORD..]);
+    }
+    bytes.extend_from_slice(&pending.to_be_bytes()[32 - pending_len..]);
+
+    let string = String::from_utf8(bytes).ok()?;
+    Some((string, count + 3))
+}
+
+fn felt_to_u64(felt: &Felt) -> Option<u64> {
+    let bytes = felt.to_be_bytes();
+    let (high, low) = bytes.split_at(24);
+    high.iter()
+        .all(|b| *b == 0)
+        .then(|| u64::from_be_bytes(low.try_into().expect("8 bytes")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_strings() {
+        let reason = "Error in the called contract (0x0123):\n\
+            Execution failed. Failure reason: 0x496e73756666696369656e742062616c616e6365.";
+
+        assert_eq!(decode(reason), Some("'Insufficient balance'".to_owned()));
+    }
+
+    #[test]
+    fn mixed_with_error_codes() {
+        let reason = "Execution failed. Failure reason: (0x4f7574206f6620676173, 0x7, 0x0).";
+
+        assert_eq!(decode(reason), Some("'Out of gas', 0x7, 0x0".to_owned()));
+    }
+
+    #[test]
+    fn byte_arrays() {
+        // "This message is longer than thirty-one bytes!", i.e. one full word and a 14 byte
+        // pending word.
+        let text = b"This message is longer than thirty-one bytes!";
+        let word = Felt::from_be_slice(&text[..31]).unwrap();
+        let pending = Felt::from_be_slice(&text[31..]).unwrap();
+        let reason = format!(
+            "Execution failed. Failure reason: ({BYTE_ARRAY_MAGIC}, 0x1, {word}, {pending}, 0xe)."
+        );
+
+        assert_eq!(
+            decode(&reason),
+            Some("'This message is longer than thirty-one bytes!'".to_owned())
+        );
+    }
+
+    #[test]
+    fn huge_byte_array_length() {
+        let reason = format!(
+            "Execution failed. Failure reason: ({BYTE_ARRAY_MAGIC}, 0xffffffffffffffff, 0x61, 0x0)."
+        );
+
+        assert_eq!(
+            decode(&reason),
+            Some(format!("{BYTE_ARRAY_MAGIC}, 0xffffffffffffffff, 'a', 0x0"))
+        );
+    }
+
+    #[test]
+    fn nested_failures() {
+        let reason = "Execution failed. Failure reason: 0x61.\n\
+            Execution failed. Failure reason: 0x1234.";
+
+        assert_eq!(decode(reason), Some("'a'\n0x1234".to_owned()));
+    }
+
+    #[test]
+    fn undecodable() {
+        assert_eq!(decode("Execution failed. Failure reason: 0x1234."), None);
+        assert_eq!(decode("Contract not deployed at address 0x616263."), None);
+    }
+}
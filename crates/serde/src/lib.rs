@@ -3,7 +3,8 @@
 use num_bigint::BigUint;
 use pathfinder_common::{
     BlockNumber, CallParam, ConstructorParam, EthereumAddress, GasPrice, L1ToL2MessagePayloadElem,
-    L2ToL1MessagePayloadElem, TransactionSignatureElem, TransactionVersion,
+    L2ToL1MessagePayloadElem, ResourceAmount, ResourcePricePerUnit, Tip, TransactionSignatureElem,
+    TransactionVersion,
 };
 use pathfinder_crypto::{Felt, HexParseError, OverflowError};
 use primitive_types::{H160, H256, U256};
@@ -224,6 +225,27 @@ serde_with::serde_conv!(
     |s: Cow<'_, str>| bytes_from_hex_str::<{ H256::len_bytes() }>(&s).map(|b| TransactionVersion(H256::from(b)))
 );
 
+serde_with::serde_conv!(
+    pub TipAsHexStr,
+    Tip,
+    |tip: &Tip| bytes_to_hex_str(&tip.0.to_be_bytes()),
+    |s: &str| bytes_from_hex_str::<8>(s).map(|b| Tip(u64::from_be_bytes(b)))
+);
+
+serde_with::serde_conv!(
+    pub ResourceAmountAsHexStr,
+    ResourceAmount,
+    |amount: &ResourceAmount| bytes_to_hex_str(&amount.0.to_be_bytes()),
+    |s: &str| bytes_from_hex_str::<8>(s).map(|b| ResourceAmount(u64::from_be_bytes(b)))
+);
+
+serde_with::serde_conv!(
+    pub ResourcePricePerUnitAsHexStr,
+    ResourcePricePerUnit,
+    |price: &ResourcePricePerUnit| bytes_to_hex_str(&price.0.to_be_bytes()),
+    |s: &str| bytes_from_hex_str::<16>(s).map(|b| ResourcePricePerUnit(u128::from_be_bytes(b)))
+);
+
 serde_with::serde_conv!(
     pub U256AsHexStr,
     primitive_types::U256,
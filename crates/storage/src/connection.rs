@@ -1,21 +1,40 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
+mod analysis;
 mod block;
 mod class;
+mod contract_deployment;
 mod ethereum;
 mod event;
+mod message;
+mod metric_counters;
+mod nonce;
 mod reference;
+mod sender;
 mod signature;
+mod slow_query;
 mod state_update;
+mod statistics;
+mod submitted_transaction;
+mod sync_timings;
 mod transaction;
 mod trie;
 
 // Re-export this so users don't require rusqlite as a direct dep.
 pub use rusqlite::TransactionBehavior;
 
+pub use analysis::{DatabaseAnalysis, IndexAnalysis, TableAnalysis};
+pub use contract_deployment::ContractDeployment;
 pub use event::KEY_FILTER_LIMIT as EVENT_KEY_FILTER_LIMIT;
 pub use event::*;
+pub use message::{MessageToL1, MessageToL1Filter, PageOfMessagesToL1};
+pub use nonce::{NonceHistoryFilter, NonceUpdate, PageOfNonceUpdates};
+pub use sender::{PageOfSenderTransactions, SenderTransaction, SenderTransactionFilter};
+pub use statistics::{ChainStatistics, STATISTICS_WINDOW};
 
+pub use submitted_transaction::SubmittedTransaction;
+pub use sync_timings::BlockSyncTimings;
 pub use transaction::TransactionStatus;
 
 pub use trie::{Child, Node, StoredNode};
@@ -27,23 +46,33 @@ use pathfinder_common::{
     TransactionHash,
 };
 use pathfinder_crypto::Felt;
-use pathfinder_ethereum::EthereumStateUpdate;
+use pathfinder_ethereum::{EthereumStateUpdate, L1Transaction};
 use starknet_gateway_types::reply::transaction as gateway;
 
-use crate::BlockId;
+use crate::slow_query::SlowQueryLog;
+use crate::trie_cache::{TransactionTrieCache, TrieNodeCache};
+use crate::{BlockId, SlowQueryStats};
 
 type PooledConnection = r2d2::PooledConnection<r2d2_sqlite::SqliteConnectionManager>;
 
-pub struct Connection(PooledConnection);
+pub struct Connection(PooledConnection, TrieNodeCache, Option<SlowQueryLog>);
 
 impl Connection {
-    pub(crate) fn from_inner(inner: PooledConnection) -> Self {
-        Self(inner)
+    pub(crate) fn from_inner(
+        inner: PooledConnection,
+        trie_cache: TrieNodeCache,
+        slow_query_log: Option<SlowQueryLog>,
+    ) -> Self {
+        Self(inner, trie_cache, slow_query_log)
     }
 
     pub fn transaction(&mut self) -> anyhow::Result<Transaction<'_>> {
         let tx = self.0.transaction()?;
-        Ok(Transaction(tx))
+        Ok(Transaction(
+            tx,
+            TransactionTrieCache::new(self.1.clone()),
+            self.2.clone(),
+        ))
     }
 
     pub fn transaction_with_behavior(
@@ -51,11 +80,44 @@ impl Connection {
         behavior: TransactionBehavior,
     ) -> anyhow::Result<Transaction<'_>> {
         let tx = self.0.transaction_with_behavior(behavior)?;
-        Ok(Transaction(tx))
+        Ok(Transaction(
+            tx,
+            TransactionTrieCache::new(self.1.clone()),
+            self.2.clone(),
+        ))
+    }
+
+    /// Whether the database supports [Connection::incremental_vacuum], which requires a one-off
+    /// [vacuum](crate::StorageManager::vacuum).
+    pub fn incremental_vacuum_enabled(&self) -> anyhow::Result<bool> {
+        // 2 is INCREMENTAL, see https://sqlite.org/pragma.html#pragma_auto_vacuum.
+        let mode = self
+            .0
+            .query_row("PRAGMA auto_vacuum", [], |row| row.get::<_, u8>(0))?;
+        Ok(mode == 2)
+    }
+
+    /// Returns up to `pages` free pages to the file system, and the number of pages this freed.
+    pub fn incremental_vacuum(&self, pages: u32) -> anyhow::Result<u32> {
+        let free_pages = || {
+            self.0
+                .query_row("PRAGMA freelist_count", [], |row| row.get::<_, u32>(0))
+        };
+
+        let before = free_pages()?;
+        self.0
+            .execute_batch(&format!("PRAGMA incremental_vacuum({pages})"))?;
+        let after = free_pages()?;
+
+        Ok(before.saturating_sub(after))
     }
 }
 
-pub struct Transaction<'inner>(rusqlite::Transaction<'inner>);
+pub struct Transaction<'inner>(
+    rusqlite::Transaction<'inner>,
+    TransactionTrieCache,
+    Option<SlowQueryLog>,
+);
 
 impl<'inner> Transaction<'inner> {
     // The implementations here are intentionally kept as simple wrappers. This lets the real implementations
@@ -63,7 +125,38 @@ impl<'inner> Transaction<'inner> {
 
     #[cfg(test)]
     pub(crate) fn from_inner(tx: rusqlite::Transaction<'inner>) -> Self {
-        Self(tx)
+        Self(
+            tx,
+            TransactionTrieCache::new(TrieNodeCache::default()),
+            None,
+        )
+    }
+
+    /// Runs the query of the wrapper `name`, logging it and counting it in the
+    /// `storage_slow_queries_total` metric if it takes longer than the slow query threshold.
+    ///
+    /// `params` summarizes the query's parameters, and is only evaluated for slow queries.
+    fn timed<T>(
+        &self,
+        name: &'static str,
+        params: impl FnOnce() -> String,
+        query: impl FnOnce() -> T,
+    ) -> T {
+        let Some(log) = &self.2 else {
+            return query();
+        };
+
+        let started = Instant::now();
+        let result = query();
+        let elapsed = started.elapsed();
+
+        if elapsed > log.threshold() {
+            tracing::warn!(query=%name, params=%params(), ?elapsed, "Slow database query");
+            metrics::increment_counter!("storage_slow_queries_total", "query" => name);
+            log.record(name, elapsed);
+        }
+
+        result
     }
 
     pub fn insert_contract_state_hash(
@@ -72,7 +165,15 @@ impl<'inner> Transaction<'inner> {
         contract: ContractAddress,
         state_hash: ContractStateHash,
     ) -> anyhow::Result<()> {
-        trie::insert_contract_state_hash(self, block_number, contract, state_hash)
+        self.timed(
+            "insert_contract_state_hash",
+            || {
+                format!(
+                    "block_number={block_number}, contract={contract:?}, state_hash={state_hash:?}"
+                )
+            },
+            || trie::insert_contract_state_hash(self, block_number, contract, state_hash),
+        )
     }
 
     pub fn contract_state_hash(
@@ -80,57 +181,131 @@ impl<'inner> Transaction<'inner> {
         block: BlockNumber,
         contract: ContractAddress,
     ) -> anyhow::Result<Option<ContractStateHash>> {
-        trie::contract_state_hash(self, block, contract)
+        self.timed(
+            "contract_state_hash",
+            || format!("block={block}, contract={contract:?}"),
+            || trie::contract_state_hash(self, block, contract),
+        )
     }
 
     pub fn insert_block_header(&self, header: &BlockHeader) -> anyhow::Result<()> {
-        block::insert_block_header(self, header)
+        self.timed(
+            "insert_block_header",
+            || format!("block_number={}", header.number),
+            || block::insert_block_header(self, header),
+        )
     }
 
     pub fn block_header(&self, block: BlockId) -> anyhow::Result<Option<BlockHeader>> {
-        block::block_header(self, block)
+        self.timed(
+            "block_header",
+            || format!("block={block:?}"),
+            || block::block_header(self, block),
+        )
     }
 
     /// Removes all data related to this block.
     ///
     /// This includes block header, block body and state update information.
     pub fn purge_block(&self, block: BlockNumber) -> anyhow::Result<()> {
-        block::purge_block(self, block)
+        self.timed(
+            "purge_block",
+            || format!("block={block}"),
+            || block::purge_block(self, block),
+        )
+    }
+
+    /// Removes this block's references to the state tries, i.e. its trie roots, contract state
+    /// hashes and class commitment leaves.
+    ///
+    /// The trie nodes themselves are kept. This is used to re-apply a block's state update.
+    pub fn purge_trie_roots(&self, block: BlockNumber) -> anyhow::Result<()> {
+        self.timed(
+            "purge_trie_roots",
+            || format!("block={block}"),
+            || block::purge_trie_roots(self, block),
+        )
     }
 
     pub fn block_id(&self, block: BlockId) -> anyhow::Result<Option<(BlockNumber, BlockHash)>> {
-        block::block_id(self, block)
+        self.timed(
+            "block_id",
+            || format!("block={block:?}"),
+            || block::block_id(self, block),
+        )
     }
 
     pub fn block_exists(&self, block: BlockId) -> anyhow::Result<bool> {
-        block::block_exists(self, block)
+        self.timed(
+            "block_exists",
+            || format!("block={block:?}"),
+            || block::block_exists(self, block),
+        )
     }
 
     pub fn block_is_l1_accepted(&self, block: BlockId) -> anyhow::Result<bool> {
-        block::block_is_l1_accepted(self, block)
+        self.timed(
+            "block_is_l1_accepted",
+            || format!("block={block:?}"),
+            || block::block_is_l1_accepted(self, block),
+        )
     }
 
     pub fn update_l1_l2_pointer(&self, block: Option<BlockNumber>) -> anyhow::Result<()> {
-        reference::update_l1_l2_pointer(self, block)
+        self.timed(
+            "update_l1_l2_pointer",
+            || format!("block={block:?}"),
+            || reference::update_l1_l2_pointer(self, block),
+        )
     }
 
     pub fn l1_l2_pointer(&self) -> anyhow::Result<Option<BlockNumber>> {
-        reference::l1_l2_pointer(self)
+        self.timed("l1_l2_pointer", String::new, || {
+            reference::l1_l2_pointer(self)
+        })
     }
 
     pub fn upsert_l1_state(&self, update: &EthereumStateUpdate) -> anyhow::Result<()> {
-        ethereum::upsert_l1_state(self, update)
+        self.timed(
+            "upsert_l1_state",
+            || format!("block_number={}", update.block_number),
+            || ethereum::upsert_l1_state(self, update),
+        )
     }
 
     pub fn l1_state_at_number(
         &self,
         block: BlockNumber,
     ) -> anyhow::Result<Option<EthereumStateUpdate>> {
-        ethereum::l1_state_at_number(self, block)
+        self.timed(
+            "l1_state_at_number",
+            || format!("block={block}"),
+            || ethereum::l1_state_at_number(self, block),
+        )
     }
 
     pub fn latest_l1_state(&self) -> anyhow::Result<Option<EthereumStateUpdate>> {
-        ethereum::latest_l1_state(self)
+        self.timed("latest_l1_state", String::new, || {
+            ethereum::latest_l1_state(self)
+        })
+    }
+
+    /// Deletes the L1 state updates read at or after the given L1 block, returning their count.
+    pub fn purge_l1_states_since(&self, l1_block_number: u64) -> anyhow::Result<usize> {
+        self.timed(
+            "purge_l1_states_since",
+            || format!("l1_block_number={l1_block_number}"),
+            || ethereum::purge_l1_states_since(self, l1_block_number),
+        )
+    }
+
+    /// The L1 transaction which accepted the block, if known.
+    pub fn l1_transaction(&self, block: BlockId) -> anyhow::Result<Option<L1Transaction>> {
+        self.timed(
+            "l1_transaction",
+            || format!("block={block:?}"),
+            || ethereum::l1_transaction(self, block),
+        )
     }
 
     /// Inserts the transaction, receipt and event data.
@@ -140,28 +315,49 @@ impl<'inner> Transaction<'inner> {
         block_number: BlockNumber,
         transaction_data: &[(gateway::Transaction, gateway::Receipt)],
     ) -> anyhow::Result<()> {
-        transaction::insert_transactions(self, block_hash, block_number, transaction_data)
+        self.timed(
+            "insert_transaction_data",
+            || {
+                format!(
+                    "block_hash={block_hash:?}, block_number={block_number}, transaction_data={}",
+                    transaction_data.len()
+                )
+            },
+            || transaction::insert_transactions(self, block_hash, block_number, transaction_data),
+        )
     }
 
     pub fn transaction_block_hash(
         &self,
         hash: TransactionHash,
     ) -> anyhow::Result<Option<BlockHash>> {
-        transaction::transaction_block_hash(self, hash)
+        self.timed(
+            "transaction_block_hash",
+            || format!("hash={hash:?}"),
+            || transaction::transaction_block_hash(self, hash),
+        )
     }
 
     pub fn transaction(
         &self,
         hash: TransactionHash,
     ) -> anyhow::Result<Option<gateway::Transaction>> {
-        transaction::transaction(self, hash)
+        self.timed(
+            "transaction",
+            || format!("hash={hash:?}"),
+            || transaction::transaction(self, hash),
+        )
     }
 
     pub fn transaction_with_receipt(
         &self,
         hash: TransactionHash,
     ) -> anyhow::Result<Option<(gateway::Transaction, gateway::Receipt, BlockHash)>> {
-        transaction::transaction_with_receipt(self, hash)
+        self.timed(
+            "transaction_with_receipt",
+            || format!("hash={hash:?}"),
+            || transaction::transaction_with_receipt(self, hash),
+        )
     }
 
     pub fn transaction_at_block(
@@ -169,43 +365,101 @@ impl<'inner> Transaction<'inner> {
         block: BlockId,
         index: usize,
     ) -> anyhow::Result<Option<gateway::Transaction>> {
-        transaction::transaction_at_block(self, block, index)
+        self.timed(
+            "transaction_at_block",
+            || format!("block={block:?}, index={index}"),
+            || transaction::transaction_at_block(self, block, index),
+        )
     }
 
     pub fn transaction_data_for_block(
         &self,
         block: BlockId,
     ) -> anyhow::Result<Option<Vec<(gateway::Transaction, gateway::Receipt)>>> {
-        transaction::transaction_data_for_block(self, block)
+        self.timed(
+            "transaction_data_for_block",
+            || format!("block={block:?}"),
+            || transaction::transaction_data_for_block(self, block),
+        )
     }
 
     pub fn transactions_for_block(
         &self,
         block: BlockId,
     ) -> anyhow::Result<Option<Vec<gateway::Transaction>>> {
-        transaction::transactions_for_block(self, block)
+        self.timed(
+            "transactions_for_block",
+            || format!("block={block:?}"),
+            || transaction::transactions_for_block(self, block),
+        )
     }
 
     pub fn transaction_hashes_for_block(
         &self,
         block: BlockId,
     ) -> anyhow::Result<Option<Vec<TransactionHash>>> {
-        transaction::transaction_hashes_for_block(self, block)
+        self.timed(
+            "transaction_hashes_for_block",
+            || format!("block={block:?}"),
+            || transaction::transaction_hashes_for_block(self, block),
+        )
     }
 
     pub fn transaction_count(&self, block: BlockId) -> anyhow::Result<usize> {
-        transaction::transaction_count(self, block)
+        self.timed(
+            "transaction_count",
+            || format!("block={block:?}"),
+            || transaction::transaction_count(self, block),
+        )
     }
 
     pub fn events(
         &self,
         filter: &EventFilter<impl KeyFilter>,
     ) -> Result<PageOfEvents, EventFilterError> {
-        event::get_events(self, filter)
+        self.timed(
+            "events",
+            || {
+                format!(
+                    "from_block={:?}, to_block={:?}, contract_address={:?}, page_size={}, offset={}",
+                    filter.from_block,
+                    filter.to_block,
+                    filter.contract_address,
+                    filter.page_size,
+                    filter.offset
+                )
+            },
+            || event::get_events(self, filter),
+        )
     }
 
     pub fn event_count_for_block(&self, block: BlockId) -> anyhow::Result<usize> {
-        event::event_count_for_block(self, block)
+        self.timed(
+            "event_count_for_block",
+            || format!("block={block:?}"),
+            || event::event_count_for_block(self, block),
+        )
+    }
+
+    /// The messages sent to L1 by canonical blocks which match the filter.
+    pub fn messages_to_l1(&self, filter: &MessageToL1Filter) -> anyhow::Result<PageOfMessagesToL1> {
+        self.timed(
+            "messages_to_l1",
+            || format!("{filter:?}"),
+            || message::messages_to_l1(self, filter),
+        )
+    }
+
+    /// The transactions of canonical blocks sent by the filter's account.
+    pub fn sender_transactions(
+        &self,
+        filter: &SenderTransactionFilter,
+    ) -> anyhow::Result<PageOfSenderTransactions> {
+        self.timed(
+            "sender_transactions",
+            || format!("{filter:?}"),
+            || sender::sender_transactions(self, filter),
+        )
     }
 
     pub fn insert_sierra_class(
@@ -216,13 +470,19 @@ impl<'inner> Transaction<'inner> {
         casm_definition: &[u8],
         compiler_version: &str,
     ) -> anyhow::Result<()> {
-        class::insert_sierra_class(
-            self,
-            sierra_hash,
-            sierra_definition,
-            casm_hash,
-            casm_definition,
-            compiler_version,
+        self.timed(
+            "insert_sierra_class",
+            || format!("sierra_hash={sierra_hash:?}, casm_hash={casm_hash:?}"),
+            || {
+                class::insert_sierra_class(
+                    self,
+                    sierra_hash,
+                    sierra_definition,
+                    casm_hash,
+                    casm_definition,
+                    compiler_version,
+                )
+            },
         )
     }
 
@@ -232,7 +492,16 @@ impl<'inner> Transaction<'inner> {
         cairo_hash: ClassHash,
         definition: &[u8],
     ) -> anyhow::Result<()> {
-        class::insert_cairo_class(self, cairo_hash, definition)
+        self.timed(
+            "insert_cairo_class",
+            || {
+                format!(
+                    "cairo_hash={cairo_hash:?}, definition_bytes={}",
+                    definition.len()
+                )
+            },
+            || class::insert_cairo_class(self, cairo_hash, definition),
+        )
     }
 
     pub fn insert_class_commitment_leaf(
@@ -241,7 +510,11 @@ impl<'inner> Transaction<'inner> {
         leaf: &ClassCommitmentLeafHash,
         casm_hash: &CasmHash,
     ) -> anyhow::Result<()> {
-        class::insert_class_commitment_leaf(self, block, leaf, casm_hash)
+        self.timed(
+            "insert_class_commitment_leaf",
+            || format!("block={block}, leaf={leaf:?}, casm_hash={casm_hash:?}"),
+            || class::insert_class_commitment_leaf(self, block, leaf, casm_hash),
+        )
     }
 
     pub fn class_commitment_leaf(
@@ -249,19 +522,31 @@ impl<'inner> Transaction<'inner> {
         block: BlockNumber,
         casm_hash: &CasmHash,
     ) -> anyhow::Result<Option<ClassCommitmentLeafHash>> {
-        class::class_commitment_leaf(self, block, casm_hash)
+        self.timed(
+            "class_commitment_leaf",
+            || format!("block={block}, casm_hash={casm_hash:?}"),
+            || class::class_commitment_leaf(self, block, casm_hash),
+        )
     }
 
     /// Returns whether the Sierra or Cairo class definition exists in the database.
     ///
     /// Note that this does not indicate that the class is actually declared -- only that we stored it.
     pub fn class_definitions_exist(&self, classes: &[ClassHash]) -> anyhow::Result<Vec<bool>> {
-        class::classes_exist(self, classes)
+        self.timed(
+            "class_definitions_exist",
+            || format!("classes={}", classes.len()),
+            || class::classes_exist(self, classes),
+        )
     }
 
     /// Returns the uncompressed class definition.
     pub fn class_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>> {
-        class::class_definition(self, class_hash)
+        self.timed(
+            "class_definition",
+            || format!("class_hash={class_hash:?}"),
+            || class::class_definition(self, class_hash),
+        )
     }
 
     /// Returns the compressed class definition if it has been declared at `block_id`.
@@ -270,7 +555,11 @@ impl<'inner> Transaction<'inner> {
         block_id: BlockId,
         class_hash: ClassHash,
     ) -> anyhow::Result<Option<Vec<u8>>> {
-        class::compressed_class_definition_at(self, block_id, class_hash)
+        self.timed(
+            "compressed_class_definition_at",
+            || format!("block_id={block_id:?}, class_hash={class_hash:?}"),
+            || class::compressed_class_definition_at(self, block_id, class_hash),
+        )
     }
 
     /// Returns the uncompressed class definition if it has been declared at `block_id`.
@@ -279,12 +568,51 @@ impl<'inner> Transaction<'inner> {
         block_id: BlockId,
         class_hash: ClassHash,
     ) -> anyhow::Result<Option<Vec<u8>>> {
-        class::class_definition_at(self, block_id, class_hash)
+        self.timed(
+            "class_definition_at",
+            || format!("block_id={block_id:?}, class_hash={class_hash:?}"),
+            || class::class_definition_at(self, block_id, class_hash),
+        )
+    }
+
+    /// Returns the canonical block in which the class was declared.
+    pub fn class_declaration_block(
+        &self,
+        class_hash: ClassHash,
+    ) -> anyhow::Result<Option<(BlockNumber, BlockHash)>> {
+        self.timed(
+            "class_declaration_block",
+            || format!("class_hash={class_hash:?}"),
+            || class::class_declaration_block(self, class_hash),
+        )
+    }
+
+    /// Returns the deployment of a contract in a canonical block.
+    pub fn contract_deployment(
+        &self,
+        contract_address: ContractAddress,
+    ) -> anyhow::Result<Option<ContractDeployment>> {
+        self.timed(
+            "contract_deployment",
+            || format!("contract_address={contract_address:?}"),
+            || contract_deployment::contract_deployment(self, contract_address),
+        )
+    }
+
+    /// Returns the aggregates of the canonical chain, or [None] if there are no blocks.
+    pub fn chain_statistics(&self) -> anyhow::Result<Option<ChainStatistics>> {
+        self.timed("chain_statistics", String::new, || {
+            statistics::chain_statistics(self)
+        })
     }
 
     /// Returns the uncompressed compiled class definition.
     pub fn casm_definition(&self, class_hash: ClassHash) -> anyhow::Result<Option<Vec<u8>>> {
-        class::casm_definition(self, class_hash)
+        self.timed(
+            "casm_definition",
+            || format!("class_hash={class_hash:?}"),
+            || class::casm_definition(self, class_hash),
+        )
     }
 
     /// Returns the uncompressed compiled class definition if it has been declared at `block_id`.
@@ -293,7 +621,11 @@ impl<'inner> Transaction<'inner> {
         block_id: BlockId,
         class_hash: ClassHash,
     ) -> anyhow::Result<Option<Vec<u8>>> {
-        class::casm_definition_at(self, block_id, class_hash)
+        self.timed(
+            "casm_definition_at",
+            || format!("block_id={block_id:?}, class_hash={class_hash:?}"),
+            || class::casm_definition_at(self, block_id, class_hash),
+        )
     }
 
     pub fn contract_class_hash(
@@ -301,12 +633,20 @@ impl<'inner> Transaction<'inner> {
         block_id: BlockId,
         contract_address: ContractAddress,
     ) -> anyhow::Result<Option<ClassHash>> {
-        state_update::contract_class_hash(self, block_id, contract_address)
+        self.timed(
+            "contract_class_hash",
+            || format!("block_id={block_id:?}, contract_address={contract_address:?}"),
+            || state_update::contract_class_hash(self, block_id, contract_address),
+        )
     }
 
     /// Returns the compiled class hash for a class.
     pub fn casm_hash(&self, class_hash: ClassHash) -> anyhow::Result<Option<CasmHash>> {
-        class::casm_hash(self, class_hash)
+        self.timed(
+            "casm_hash",
+            || format!("class_hash={class_hash:?}"),
+            || class::casm_hash(self, class_hash),
+        )
     }
 
     /// Returns the compiled class hash for a class if it has been declared at `block_id`.
@@ -315,7 +655,11 @@ impl<'inner> Transaction<'inner> {
         block_id: BlockId,
         class_hash: ClassHash,
     ) -> anyhow::Result<Option<CasmHash>> {
-        class::casm_hash_at(self, block_id, class_hash)
+        self.timed(
+            "casm_hash_at",
+            || format!("block_id={block_id:?}, class_hash={class_hash:?}"),
+            || class::casm_hash_at(self, block_id, class_hash),
+        )
     }
 
     /// Stores the class trie information.
@@ -324,7 +668,11 @@ impl<'inner> Transaction<'inner> {
         root: ClassCommitment,
         nodes: &HashMap<Felt, Node>,
     ) -> anyhow::Result<u64> {
-        trie::trie_class::insert(self, root.0, nodes)
+        self.timed(
+            "insert_class_trie",
+            || format!("root={root:?}, nodes={}", nodes.len()),
+            || trie::trie_class::insert(self, root.0, nodes),
+        )
     }
 
     /// Stores a single contract's storage trie information.
@@ -333,7 +681,11 @@ impl<'inner> Transaction<'inner> {
         root: ContractRoot,
         nodes: &HashMap<Felt, Node>,
     ) -> anyhow::Result<u64> {
-        trie::trie_contracts::insert(self, root.0, nodes)
+        self.timed(
+            "insert_contract_trie",
+            || format!("root={root:?}, nodes={}", nodes.len()),
+            || trie::trie_contracts::insert(self, root.0, nodes),
+        )
     }
 
     /// Stores the global starknet storage trie information.
@@ -342,39 +694,75 @@ impl<'inner> Transaction<'inner> {
         root: StorageCommitment,
         nodes: &HashMap<Felt, Node>,
     ) -> anyhow::Result<u64> {
-        trie::trie_storage::insert(self, root.0, nodes)
+        self.timed(
+            "insert_storage_trie",
+            || format!("root={root:?}, nodes={}", nodes.len()),
+            || trie::trie_storage::insert(self, root.0, nodes),
+        )
     }
 
     pub fn class_trie_node(&self, index: u64) -> anyhow::Result<Option<StoredNode>> {
-        trie::trie_class::node(self, index)
+        self.timed(
+            "class_trie_node",
+            || format!("index={index}"),
+            || trie::trie_class::node(self, index),
+        )
     }
 
     pub fn storage_trie_node(&self, index: u64) -> anyhow::Result<Option<StoredNode>> {
-        trie::trie_storage::node(self, index)
+        self.timed(
+            "storage_trie_node",
+            || format!("index={index}"),
+            || trie::trie_storage::node(self, index),
+        )
     }
 
     pub fn contract_trie_node(&self, index: u64) -> anyhow::Result<Option<StoredNode>> {
-        trie::trie_contracts::node(self, index)
+        self.timed(
+            "contract_trie_node",
+            || format!("index={index}"),
+            || trie::trie_contracts::node(self, index),
+        )
     }
 
     pub fn class_trie_node_hash(&self, index: u64) -> anyhow::Result<Option<Felt>> {
-        trie::trie_class::hash(self, index)
+        self.timed(
+            "class_trie_node_hash",
+            || format!("index={index}"),
+            || trie::trie_class::hash(self, index),
+        )
     }
 
     pub fn storage_trie_node_hash(&self, index: u64) -> anyhow::Result<Option<Felt>> {
-        trie::trie_storage::hash(self, index)
+        self.timed(
+            "storage_trie_node_hash",
+            || format!("index={index}"),
+            || trie::trie_storage::hash(self, index),
+        )
     }
 
     pub fn contract_trie_node_hash(&self, index: u64) -> anyhow::Result<Option<Felt>> {
-        trie::trie_contracts::hash(self, index)
+        self.timed(
+            "contract_trie_node_hash",
+            || format!("index={index}"),
+            || trie::trie_contracts::hash(self, index),
+        )
     }
 
     pub fn class_root_index(&self, block: BlockNumber) -> anyhow::Result<Option<u64>> {
-        trie::class_root_index(self, block)
+        self.timed(
+            "class_root_index",
+            || format!("block={block}"),
+            || trie::class_root_index(self, block),
+        )
     }
 
     pub fn storage_root_index(&self, block: BlockNumber) -> anyhow::Result<Option<u64>> {
-        trie::storage_root_index(self, block)
+        self.timed(
+            "storage_root_index",
+            || format!("block={block}"),
+            || trie::storage_root_index(self, block),
+        )
     }
 
     pub fn contract_root_index(
@@ -382,7 +770,11 @@ impl<'inner> Transaction<'inner> {
         block: BlockNumber,
         contract: ContractAddress,
     ) -> anyhow::Result<Option<u64>> {
-        trie::contract_root_index(self, block, contract)
+        self.timed(
+            "contract_root_index",
+            || format!("block={block}, contract={contract:?}"),
+            || trie::contract_root_index(self, block, contract),
+        )
     }
 
     pub fn contract_root(
@@ -390,7 +782,11 @@ impl<'inner> Transaction<'inner> {
         block: BlockNumber,
         contract: ContractAddress,
     ) -> anyhow::Result<Option<ContractRoot>> {
-        trie::contract_root(self, block, contract)
+        self.timed(
+            "contract_root",
+            || format!("block={block}, contract={contract:?}"),
+            || trie::contract_root(self, block, contract),
+        )
     }
 
     pub fn insert_class_root(
@@ -398,7 +794,11 @@ impl<'inner> Transaction<'inner> {
         block_number: BlockNumber,
         root: Option<u64>,
     ) -> anyhow::Result<()> {
-        trie::insert_class_root(self, block_number, root)
+        self.timed(
+            "insert_class_root",
+            || format!("block_number={block_number}, root={root:?}"),
+            || trie::insert_class_root(self, block_number, root),
+        )
     }
 
     pub fn insert_storage_root(
@@ -406,7 +806,11 @@ impl<'inner> Transaction<'inner> {
         block_number: BlockNumber,
         root: Option<u64>,
     ) -> anyhow::Result<()> {
-        trie::insert_storage_root(self, block_number, root)
+        self.timed(
+            "insert_storage_root",
+            || format!("block_number={block_number}, root={root:?}"),
+            || trie::insert_storage_root(self, block_number, root),
+        )
     }
 
     pub fn insert_contract_root(
@@ -415,7 +819,11 @@ impl<'inner> Transaction<'inner> {
         contract: ContractAddress,
         root: Option<u64>,
     ) -> anyhow::Result<()> {
-        trie::insert_contract_root(self, block_number, contract, root)
+        self.timed(
+            "insert_contract_root",
+            || format!("block_number={block_number}, contract={contract:?}, root={root:?}"),
+            || trie::insert_contract_root(self, block_number, contract, root),
+        )
     }
 
     pub fn insert_state_update(
@@ -423,11 +831,19 @@ impl<'inner> Transaction<'inner> {
         block_number: BlockNumber,
         state_update: &StateUpdate,
     ) -> anyhow::Result<()> {
-        state_update::insert_state_update(self, block_number, state_update)
+        self.timed(
+            "insert_state_update",
+            || format!("block_number={block_number}"),
+            || state_update::insert_state_update(self, block_number, state_update),
+        )
     }
 
     pub fn state_update(&self, block: BlockId) -> anyhow::Result<Option<StateUpdate>> {
-        state_update::state_update(self, block)
+        self.timed(
+            "state_update",
+            || format!("block={block:?}"),
+            || state_update::state_update(self, block),
+        )
     }
 
     pub fn storage_value(
@@ -436,7 +852,11 @@ impl<'inner> Transaction<'inner> {
         contract_address: ContractAddress,
         key: StorageAddress,
     ) -> anyhow::Result<Option<StorageValue>> {
-        state_update::storage_value(self, block, contract_address, key)
+        self.timed(
+            "storage_value",
+            || format!("block={block:?}, contract_address={contract_address:?}, key={key:?}"),
+            || state_update::storage_value(self, block, contract_address, key),
+        )
     }
 
     pub fn contract_nonce(
@@ -444,7 +864,33 @@ impl<'inner> Transaction<'inner> {
         contract_address: ContractAddress,
         block_id: BlockId,
     ) -> anyhow::Result<Option<ContractNonce>> {
-        state_update::contract_nonce(self, contract_address, block_id)
+        self.timed(
+            "contract_nonce",
+            || format!("contract_address={contract_address:?}, block_id={block_id:?}"),
+            || state_update::contract_nonce(self, contract_address, block_id),
+        )
+    }
+
+    /// The nonce updates of canonical blocks for the filter's contract.
+    pub fn nonce_history(&self, filter: &NonceHistoryFilter) -> anyhow::Result<PageOfNonceUpdates> {
+        self.timed(
+            "nonce_history",
+            || format!("{filter:?}"),
+            || nonce::nonce_history(self, filter),
+        )
+    }
+
+    /// The first nonce update of the contract which reached at least the given nonce.
+    pub fn first_nonce_update_reaching(
+        &self,
+        contract_address: ContractAddress,
+        nonce: ContractNonce,
+    ) -> anyhow::Result<Option<NonceUpdate>> {
+        self.timed(
+            "first_nonce_update_reaching",
+            || format!("contract_address={contract_address:?}, nonce={nonce:?}"),
+            || nonce::first_nonce_update_reaching(self, contract_address, nonce),
+        )
     }
 
     pub fn contract_exists(
@@ -452,7 +898,11 @@ impl<'inner> Transaction<'inner> {
         contract_address: ContractAddress,
         block_id: BlockId,
     ) -> anyhow::Result<bool> {
-        state_update::contract_exists(self, contract_address, block_id)
+        self.timed(
+            "contract_exists",
+            || format!("contract_address={contract_address:?}, block_id={block_id:?}"),
+            || state_update::contract_exists(self, contract_address, block_id),
+        )
     }
 
     pub fn insert_signature(
@@ -460,13 +910,150 @@ impl<'inner> Transaction<'inner> {
         block_number: BlockNumber,
         signature: &BlockCommitmentSignature,
     ) -> anyhow::Result<()> {
-        signature::insert_signature(self, block_number, signature)
+        self.timed(
+            "insert_signature",
+            || format!("block_number={block_number}"),
+            || signature::insert_signature(self, block_number, signature),
+        )
+    }
+
+    pub fn signature(
+        &self,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<BlockCommitmentSignature>> {
+        self.timed(
+            "signature",
+            || format!("block_number={block_number}"),
+            || signature::signature(self, block_number),
+        )
+    }
+
+    /// Records a transaction which was submitted via this node. Re-submissions are ignored.
+    pub fn insert_submitted_transaction(
+        &self,
+        hash: TransactionHash,
+        submitted_at: u64,
+    ) -> anyhow::Result<()> {
+        self.timed(
+            "insert_submitted_transaction",
+            || format!("hash={hash:?}, submitted_at={submitted_at}"),
+            || submitted_transaction::insert_submitted_transaction(self, hash, submitted_at),
+        )
+    }
+
+    pub fn submitted_transaction(
+        &self,
+        hash: TransactionHash,
+    ) -> anyhow::Result<Option<SubmittedTransaction>> {
+        self.timed(
+            "submitted_transaction",
+            || format!("hash={hash:?}"),
+            || submitted_transaction::submitted_transaction(self, hash),
+        )
+    }
+
+    /// Returns the submitted transactions among `hashes` which are not yet included.
+    pub fn submitted_transactions_awaiting_inclusion(
+        &self,
+        hashes: &[TransactionHash],
+    ) -> anyhow::Result<Vec<TransactionHash>> {
+        self.timed(
+            "submitted_transactions_awaiting_inclusion",
+            || format!("hashes={}", hashes.len()),
+            || submitted_transaction::submitted_transactions_awaiting_inclusion(self, hashes),
+        )
+    }
+
+    /// Marks the submitted transactions among `hashes` which were not yet included as included,
+    /// returning those which were marked.
+    pub fn mark_submitted_transactions_included(
+        &self,
+        hashes: &[TransactionHash],
+        included_at: u64,
+    ) -> anyhow::Result<Vec<(TransactionHash, SubmittedTransaction)>> {
+        self.timed(
+            "mark_submitted_transactions_included",
+            || format!("hashes={}, included_at={included_at}", hashes.len()),
+            || {
+                submitted_transaction::mark_submitted_transactions_included(
+                    self,
+                    hashes,
+                    included_at,
+                )
+            },
+        )
+    }
+
+    /// Records the sync timings of a block, keeping only those of the latest `capacity` blocks.
+    pub fn insert_block_sync_timings(
+        &self,
+        timings: &BlockSyncTimings,
+        capacity: u64,
+    ) -> anyhow::Result<()> {
+        self.timed(
+            "insert_block_sync_timings",
+            || format!("block_number={}, capacity={capacity}", timings.block_number),
+            || sync_timings::insert_block_sync_timings(self, timings, capacity),
+        )
+    }
+
+    /// Returns the sync timings of the latest `limit` blocks, latest first.
+    pub fn block_sync_timings(&self, limit: u64) -> anyhow::Result<Vec<BlockSyncTimings>> {
+        self.timed(
+            "block_sync_timings",
+            || format!("limit={limit}"),
+            || sync_timings::block_sync_timings(self, limit),
+        )
+    }
+
+    /// Returns the persisted values of metric counters, by name.
+    pub fn metric_counters(&self) -> anyhow::Result<HashMap<String, u64>> {
+        self.timed("metric_counters", String::new, || {
+            metric_counters::metric_counters(self)
+        })
+    }
+
+    /// Persists the values of metric counters, replacing their previous values.
+    pub fn upsert_metric_counters(&self, counters: &[(&str, u64)]) -> anyhow::Result<()> {
+        self.timed(
+            "upsert_metric_counters",
+            || format!("counters={}", counters.len()),
+            || metric_counters::upsert_metric_counters(self, counters),
+        )
+    }
+
+    /// Returns the persisted statistics of slow queries, by query.
+    pub fn slow_queries(&self) -> anyhow::Result<Vec<(String, SlowQueryStats)>> {
+        self.timed("slow_queries", String::new, || {
+            slow_query::slow_queries(self)
+        })
+    }
+
+    /// Adds the statistics of slow queries to the persisted ones.
+    pub fn record_slow_queries(&self, queries: &[(&str, SlowQueryStats)]) -> anyhow::Result<()> {
+        self.timed(
+            "record_slow_queries",
+            || format!("queries={}", queries.len()),
+            || slow_query::record_slow_queries(self, queries),
+        )
+    }
+
+    /// Runs `ANALYZE` to update the statistics of the query planner, and returns the storage
+    /// statistics of the main database.
+    ///
+    /// This scans the whole database and can take a while.
+    pub fn analyze(&self) -> anyhow::Result<DatabaseAnalysis> {
+        analysis::analyze(self)
     }
 
     pub(self) fn inner(&self) -> &rusqlite::Transaction<'_> {
         &self.0
     }
 
+    pub(self) fn trie_cache(&self) -> &TransactionTrieCache {
+        &self.1
+    }
+
     pub fn commit(self) -> anyhow::Result<()> {
         Ok(self.0.commit()?)
     }
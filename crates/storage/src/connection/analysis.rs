@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use crate::prelude::*;
+use crate::SlowQueryStats;
+
+/// Indexes smaller than this are never reported as bloated.
+const MIN_BLOATED_INDEX_BYTES: u64 = 1024 * 1024;
+/// Indexes of tables with fewer rows are never reported as unselective.
+const MIN_UNSELECTIVE_INDEX_ROWS: u64 = 10_000;
+
+/// The storage statistics of the main database, see [Transaction::analyze].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseAnalysis {
+    pub page_size: u64,
+    pub page_count: u64,
+    /// The number of pages which are unused, and which a vacuum returns to the file system.
+    pub free_pages: u64,
+    pub tables: Vec<TableAnalysis>,
+    pub indexes: Vec<IndexAnalysis>,
+    /// The persisted statistics of slow queries, by query.
+    pub slow_queries: Vec<(String, SlowQueryStats)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableAnalysis {
+    pub name: String,
+    /// The size of the table's pages.
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexAnalysis {
+    pub name: String,
+    pub table: String,
+    /// The size of the index's pages.
+    pub bytes: u64,
+    /// The unused space within the index's pages.
+    pub unused_bytes: u64,
+    /// The number of rows of the index, as estimated by `ANALYZE`.
+    pub rows: Option<u64>,
+    /// The average number of rows with the same value of the index's first column, as estimated
+    /// by `ANALYZE`.
+    pub rows_per_key: Option<u64>,
+}
+
+impl IndexAnalysis {
+    /// Whether more than half the space of the index's pages is unused, e.g. after many rows were
+    /// deleted. A vacuum rebuilds the index compactly.
+    pub fn is_bloated(&self) -> bool {
+        self.bytes >= MIN_BLOATED_INDEX_BYTES && self.unused_bytes * 2 > self.bytes
+    }
+
+    /// Whether a lookup by the index's first column matches more than half of the rows, which
+    /// makes the query planner unlikely to use the index.
+    ///
+    /// The automatic indexes of primary keys and unique constraints are never reported, as they
+    /// enforce the constraint.
+    pub fn is_unselective(&self) -> bool {
+        if self.name.starts_with("sqlite_autoindex_") {
+            return false;
+        }
+
+        match (self.rows, self.rows_per_key) {
+            (Some(rows), Some(rows_per_key)) => {
+                rows >= MIN_UNSELECTIVE_INDEX_ROWS && rows_per_key * 2 > rows
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(super) fn analyze(tx: &Transaction<'_>) -> anyhow::Result<DatabaseAnalysis> {
+    tx.inner()
+        .execute_batch("ANALYZE main")
+        .context("Analyzing database")?;
+
+    let pragma = |name: &str| -> anyhow::Result<u64> {
+        tx.inner()
+            .query_row(&format!("PRAGMA main.{name}"), [], |row| row.get(0))
+            .with_context(|| format!("Querying {name}"))
+    };
+    let page_size = pragma("page_size")?;
+    let page_count = pragma("page_count")?;
+    let free_pages = pragma("freelist_count")?;
+
+    let mut sizes = tx
+        .inner()
+        .prepare("SELECT name, SUM(pgsize), SUM(unused) FROM dbstat('main') GROUP BY name")
+        .context("Preparing size query")?
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+        })
+        .context("Querying sizes")?
+        .collect::<Result<HashMap<String, (u64, u64)>, _>>()
+        .context("Iterating over sizes")?;
+
+    let mut stats = tx
+        .inner()
+        .prepare("SELECT idx, stat FROM main.sqlite_stat1 WHERE idx IS NOT NULL")
+        .context("Preparing index statistics query")?
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Querying index statistics")?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .context("Iterating over index statistics")?;
+
+    let mut tables = Vec::new();
+    let mut indexes = Vec::new();
+    let mut stmt = tx
+        .inner()
+        .prepare(
+            "SELECT type, name, tbl_name FROM main.sqlite_master WHERE type IN ('table', 'index')",
+        )
+        .context("Preparing schema query")?;
+    let mut rows = stmt.query([]).context("Querying schema")?;
+    while let Some(row) = rows.next().context("Iterating over schema")? {
+        let kind: String = row.get(0)?;
+        let name: String = row.get(1)?;
+        let (bytes, unused_bytes) = sizes.remove(&name).unwrap_or_default();
+
+        if kind == "table" {
+            tables.push(TableAnalysis { name, bytes });
+        } else {
+            // The statistics are a list of integers, the number of rows followed by the average
+            // number of rows per distinct value of each prefix of the index's columns.
+            let stat = stats.remove(&name).unwrap_or_default();
+            let mut stat = stat.split(' ').map(|x| x.parse::<u64>().ok());
+
+            indexes.push(IndexAnalysis {
+                name,
+                table: row.get(2)?,
+                bytes,
+                unused_bytes,
+                rows: stat.next().flatten(),
+                rows_per_key: stat.next().flatten(),
+            });
+        }
+    }
+
+    tables.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    indexes.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    let slow_queries = super::slow_query::slow_queries(tx)?;
+
+    Ok(DatabaseAnalysis {
+        page_size,
+        page_count,
+        free_pages,
+        tables,
+        indexes,
+        slow_queries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn analyze() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        tx.inner()
+            .execute_batch(
+                r"CREATE TABLE analyzed (a INTEGER, b INTEGER);
+                CREATE INDEX analyzed_a ON analyzed(a);
+                WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 20000)
+                INSERT INTO analyzed SELECT x % 2, x FROM n;",
+            )
+            .unwrap();
+
+        let analysis = tx.analyze().unwrap();
+
+        assert!(analysis.page_count > 0);
+        let table = analysis
+            .tables
+            .iter()
+            .find(|table| table.name == "analyzed")
+            .unwrap();
+        assert!(table.bytes > 0);
+
+        let index = analysis
+            .indexes
+            .iter()
+            .find(|index| index.name == "analyzed_a")
+            .unwrap();
+        assert_eq!(index.table, "analyzed");
+        assert_eq!(index.rows, Some(20000));
+        assert_eq!(index.rows_per_key, Some(10000));
+        assert!(index.is_unselective());
+        assert!(!index.is_bloated());
+    }
+}
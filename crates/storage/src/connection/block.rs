@@ -101,6 +101,10 @@ pub(super) fn purge_block(tx: &Transaction<'_>, block: BlockNumber) -> anyhow::R
         )
         .context("Deleting block from block_headers table")?;
 
+    purge_trie_roots(tx, block)
+}
+
+pub(super) fn purge_trie_roots(tx: &Transaction<'_>, block: BlockNumber) -> anyhow::Result<()> {
     tx.inner()
         .execute(
             "DELETE FROM contract_roots WHERE block_number = ?",
@@ -277,15 +281,17 @@ pub(super) fn block_header(
 }
 
 pub(super) fn block_is_l1_accepted(tx: &Transaction<'_>, block: BlockId) -> anyhow::Result<bool> {
-    let Some(l1_l2) = tx.l1_l2_pointer().context("Querying L1-L2 pointer")? else {
-        return Ok(false);
-    };
-
     let Some((block_number, _)) = tx.block_id(block).context("Fetching block number")? else {
         return Ok(false);
     };
 
-    Ok(block_number <= l1_l2)
+    tx.inner()
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM l1_accepted_blocks WHERE block_number = ?)",
+            params![&block_number],
+            |row| row.get(0),
+        )
+        .context("Querying L1 accepted blocks")
 }
 
 #[cfg(test)]
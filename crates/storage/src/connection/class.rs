@@ -1,5 +1,7 @@
 use anyhow::Context;
-use pathfinder_common::{BlockNumber, CasmHash, ClassCommitmentLeafHash, ClassHash, SierraHash};
+use pathfinder_common::{
+    BlockHash, BlockNumber, CasmHash, ClassCommitmentLeafHash, ClassHash, SierraHash,
+};
 
 use crate::{prelude::*, BlockId};
 
@@ -184,6 +186,27 @@ pub(super) fn class_definition_at(
     Ok(Some(definition))
 }
 
+pub(super) fn class_declaration_block(
+    tx: &Transaction<'_>,
+    class_hash: ClassHash,
+) -> anyhow::Result<Option<(BlockNumber, BlockHash)>> {
+    tx.inner()
+        .query_row(
+            r"SELECT canonical_blocks.number, canonical_blocks.hash
+            FROM class_definitions
+                INNER JOIN canonical_blocks ON (canonical_blocks.number = class_definitions.block_number)
+            WHERE class_definitions.hash = ?",
+            params![&class_hash],
+            |row| {
+                let number = row.get_block_number(0)?;
+                let hash = row.get_block_hash(1)?;
+                Ok((number, hash))
+            },
+        )
+        .optional()
+        .context("Querying for class declaration block")
+}
+
 pub(super) fn casm_definition(
     transaction: &Transaction<'_>,
     class_hash: ClassHash,
@@ -384,6 +407,7 @@ mod tests {
     use crate::Storage;
 
     use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::{BlockHeader, StateUpdate};
     use pathfinder_crypto::Felt;
 
     fn setup_class(transaction: &Transaction<'_>) -> (ClassHash, &'static [u8], serde_json::Value) {
@@ -502,6 +526,35 @@ mod tests {
         assert_eq!(definition, sierra_definition);
     }
 
+    #[test]
+    fn declaration_block() {
+        let mut connection = Storage::in_memory().unwrap().connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let (hash, _, _) = setup_class(&tx);
+
+        let result = class_declaration_block(&tx, hash).unwrap();
+        assert_eq!(result, None);
+
+        let header = BlockHeader::builder().finalize_with_hash(block_hash_bytes!(b"genesis"));
+        tx.insert_block_header(&header).unwrap();
+        let header = header
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 1"));
+        tx.insert_block_header(&header).unwrap();
+        tx.insert_state_update(
+            header.number,
+            &StateUpdate::default().with_declared_cairo_class(hash),
+        )
+        .unwrap();
+
+        let result = class_declaration_block(&tx, hash).unwrap();
+        assert_eq!(result, Some((header.number, header.hash)));
+
+        let result = class_declaration_block(&tx, class_hash!("0x456")).unwrap();
+        assert_eq!(result, None);
+    }
+
     #[test]
     fn compiled_class_leaves() {
         let mut connection = Storage::in_memory().unwrap().connection().unwrap();
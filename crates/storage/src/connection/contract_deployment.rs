@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use pathfinder_common::state_update::ContractClassUpdate;
+use pathfinder_common::{
+    BlockHash, BlockNumber, ClassHash, ContractAddress, StateUpdate, TransactionHash,
+};
+use starknet_gateway_types::reply::transaction as gateway;
+
+use crate::prelude::*;
+
+/// The deployment of a contract in a canonical block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractDeployment {
+    /// The class the contract was deployed with.
+    pub class_hash: ClassHash,
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    /// The `DEPLOY` or `DEPLOY_ACCOUNT` transaction which deployed the contract.
+    ///
+    /// This is [None] for contracts deployed via the `deploy` syscall.
+    pub transaction_hash: Option<TransactionHash>,
+}
+
+/// Indexes the contracts deployed by the state update.
+///
+/// The block's transactions must already be stored in order to resolve the deploying
+/// transactions.
+pub(super) fn insert_contract_deployments(
+    tx: &Transaction<'_>,
+    block_number: BlockNumber,
+    state_update: &StateUpdate,
+) -> anyhow::Result<()> {
+    let deployments = state_update
+        .contract_updates
+        .iter()
+        .filter_map(|(address, update)| match update.class {
+            Some(ContractClassUpdate::Deploy(class_hash)) => Some((*address, class_hash)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if deployments.is_empty() {
+        return Ok(());
+    }
+
+    let transactions = super::transaction::transactions_for_block(tx, block_number.into())
+        .context("Fetching block transactions")?
+        .unwrap_or_default();
+    let deploying_transactions = transactions
+        .iter()
+        .filter_map(|transaction| match transaction {
+            gateway::Transaction::Deploy(t) => Some((t.contract_address, t.transaction_hash)),
+            gateway::Transaction::DeployAccount(t) => {
+                Some((t.contract_address(), t.transaction_hash()))
+            }
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"INSERT OR IGNORE INTO contract_deployments (contract_address, class_hash, block_number, transaction_hash)
+            VALUES (?, ?, ?, ?)",
+        )
+        .context("Preparing contract deployment insert statement")?;
+
+    for (address, class_hash) in deployments {
+        let transaction_hash = deploying_transactions.get(&address).copied();
+        stmt.execute(params![
+            &address,
+            &class_hash,
+            &block_number,
+            &transaction_hash
+        ])
+        .context("Inserting contract deployment")?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn contract_deployment(
+    tx: &Transaction<'_>,
+    contract_address: ContractAddress,
+) -> anyhow::Result<Option<ContractDeployment>> {
+    tx.inner()
+        .query_row(
+            r"SELECT contract_deployments.class_hash, canonical_blocks.number, canonical_blocks.hash, contract_deployments.transaction_hash
+            FROM contract_deployments
+                INNER JOIN canonical_blocks ON (canonical_blocks.number = contract_deployments.block_number)
+            WHERE contract_deployments.contract_address = ?",
+            params![&contract_address],
+            |row| {
+                Ok(ContractDeployment {
+                    class_hash: row.get_class_hash(0)?,
+                    block_number: row.get_block_number(1)?,
+                    block_hash: row.get_block_hash(2)?,
+                    transaction_hash: row.get_optional_transaction_hash(3)?,
+                })
+            },
+        )
+        .optional()
+        .context("Querying contract deployment")
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::BlockHeader;
+    use starknet_gateway_types::reply::transaction::{
+        DeployAccountTransaction, DeployAccountTransactionV0V1, ExecutionStatus, Receipt,
+    };
+
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn deployments() {
+        let mut connection = Storage::in_memory().unwrap().connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let account = contract_address_bytes!(b"account");
+        let syscall = contract_address_bytes!(b"syscall");
+        let class_hash = class_hash_bytes!(b"class");
+        let transaction_hash = transaction_hash_bytes!(b"deploy account");
+
+        let header = BlockHeader::builder().finalize_with_hash(block_hash_bytes!(b"genesis"));
+        tx.insert_block_header(&header).unwrap();
+
+        let transaction = gateway::Transaction::DeployAccount(DeployAccountTransaction::V0V1(
+            DeployAccountTransactionV0V1 {
+                contract_address: account,
+                transaction_hash,
+                max_fee: Default::default(),
+                version: pathfinder_common::TransactionVersion::ONE,
+                signature: Default::default(),
+                nonce: Default::default(),
+                contract_address_salt: Default::default(),
+                constructor_calldata: Default::default(),
+                class_hash,
+            },
+        ));
+        let receipt = Receipt {
+            transaction_hash,
+            transaction_index: pathfinder_common::TransactionIndex::new_or_panic(0),
+            actual_fee: None,
+            events: vec![],
+            execution_resources: None,
+            l1_to_l2_consumed_message: None,
+            l2_to_l1_messages: vec![],
+            execution_status: ExecutionStatus::Succeeded,
+            revert_error: None,
+        };
+        tx.insert_transaction_data(header.hash, header.number, &[(transaction, receipt)])
+            .unwrap();
+
+        tx.insert_state_update(
+            header.number,
+            &StateUpdate::default()
+                .with_deployed_contract(account, class_hash)
+                .with_deployed_contract(syscall, class_hash),
+        )
+        .unwrap();
+
+        let result = contract_deployment(&tx, account).unwrap();
+        let expected = ContractDeployment {
+            class_hash,
+            block_number: header.number,
+            block_hash: header.hash,
+            transaction_hash: Some(transaction_hash),
+        };
+        assert_eq!(result, Some(expected));
+
+        let result = contract_deployment(&tx, syscall).unwrap();
+        let expected = ContractDeployment {
+            transaction_hash: None,
+            ..expected
+        };
+        assert_eq!(result, Some(expected));
+
+        let result = contract_deployment(&tx, contract_address_bytes!(b"missing")).unwrap();
+        assert_eq!(result, None);
+
+        // Purging the block removes its deployments.
+        tx.purge_block(header.number).unwrap();
+        let result = contract_deployment(&tx, account).unwrap();
+        assert_eq!(result, None);
+    }
+}
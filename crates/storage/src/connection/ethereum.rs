@@ -1,7 +1,9 @@
+use anyhow::Context;
 use pathfinder_common::BlockNumber;
-use pathfinder_ethereum::EthereumStateUpdate;
+use pathfinder_ethereum::{EthereumStateUpdate, L1Block, L1Transaction};
+use primitive_types::H256;
 
-use crate::prelude::*;
+use crate::{prelude::*, BlockId};
 
 pub(super) fn upsert_l1_state(
     tx: &Transaction<'_>,
@@ -11,16 +13,31 @@ pub(super) fn upsert_l1_state(
         r"INSERT OR REPLACE INTO l1_state (
                     starknet_block_number,
                     starknet_block_hash,
-                    starknet_state_root
+                    starknet_state_root,
+                    l1_transaction_hash,
+                    l1_timestamp,
+                    previous_starknet_block_number,
+                    l1_block_number,
+                    l1_block_hash
                 ) VALUES (
                     :starknet_block_number,
                     :starknet_block_hash,
-                    :starknet_state_root
+                    :starknet_state_root,
+                    :l1_transaction_hash,
+                    :l1_timestamp,
+                    :previous_starknet_block_number,
+                    :l1_block_number,
+                    :l1_block_hash
                 )",
         named_params! {
             ":starknet_block_number": &update.block_number,
             ":starknet_block_hash": &update.block_hash,
             ":starknet_state_root": &update.state_root,
+            ":l1_transaction_hash": &update.l1_transaction.map(|t| t.hash.as_bytes().to_vec()),
+            ":l1_timestamp": &update.l1_transaction.map(|t| t.timestamp),
+            ":previous_starknet_block_number": &update.previous_block_number,
+            ":l1_block_number": &update.l1_block.map(|b| b.number),
+            ":l1_block_hash": &update.l1_block.map(|b| b.hash.as_bytes().to_vec()),
         },
     )?;
 
@@ -33,18 +50,24 @@ pub(super) fn l1_state_at_number(
 ) -> anyhow::Result<Option<EthereumStateUpdate>> {
     tx.inner()
         .query_row(
-            r"SELECT starknet_block_number, starknet_block_hash, starknet_state_root FROM l1_state 
+            r"SELECT starknet_block_number, starknet_block_hash, starknet_state_root, l1_transaction_hash, l1_timestamp, previous_starknet_block_number, l1_block_number, l1_block_hash FROM l1_state 
             WHERE starknet_block_number = ?",
             params![&block],
             |row| {
                 let block_number = row.get_block_number(0)?;
                 let block_hash = row.get_block_hash(1)?;
                 let state_root = row.get_state_commitment(2)?;
+                let l1_transaction = get_l1_transaction(row, 3)?;
+                let previous_block_number = row.get_optional_block_number(5)?;
+                let l1_block = get_l1_block(row, 6)?;
 
                 Ok(EthereumStateUpdate {
                     state_root,
                     block_number,
                     block_hash,
+                    l1_transaction,
+                    previous_block_number,
+                    l1_block,
                 })
             },
         )
@@ -55,7 +78,7 @@ pub(super) fn l1_state_at_number(
 pub(super) fn latest_l1_state(tx: &Transaction<'_>) -> anyhow::Result<Option<EthereumStateUpdate>> {
     tx.inner()
         .query_row(
-            r"SELECT starknet_block_number, starknet_block_hash, starknet_state_root FROM l1_state 
+            r"SELECT starknet_block_number, starknet_block_hash, starknet_state_root, l1_transaction_hash, l1_timestamp, previous_starknet_block_number, l1_block_number, l1_block_hash FROM l1_state 
             ORDER BY starknet_block_number DESC
             LIMIT 1",
             [],
@@ -63,11 +86,17 @@ pub(super) fn latest_l1_state(tx: &Transaction<'_>) -> anyhow::Result<Option<Eth
                 let block_number = row.get_block_number(0)?;
                 let block_hash = row.get_block_hash(1)?;
                 let state_root = row.get_state_commitment(2)?;
+                let l1_transaction = get_l1_transaction(row, 3)?;
+                let previous_block_number = row.get_optional_block_number(5)?;
+                let l1_block = get_l1_block(row, 6)?;
 
                 Ok(EthereumStateUpdate {
                     state_root,
                     block_number,
                     block_hash,
+                    l1_transaction,
+                    previous_block_number,
+                    l1_block,
                 })
             },
         )
@@ -75,6 +104,68 @@ pub(super) fn latest_l1_state(tx: &Transaction<'_>) -> anyhow::Result<Option<Eth
         .map_err(|e| e.into())
 }
 
+/// Deletes the L1 state updates read at or after the L1 block `l1_block_number`, e.g. because
+/// those L1 blocks were reorged away. Returns the number of deleted updates.
+pub(super) fn purge_l1_states_since(
+    tx: &Transaction<'_>,
+    l1_block_number: u64,
+) -> anyhow::Result<usize> {
+    tx.inner()
+        .execute(
+            "DELETE FROM l1_state WHERE l1_block_number >= ?",
+            params![&l1_block_number],
+        )
+        .context("Deleting L1 state updates")
+}
+
+/// The L1 transaction which accepted the block, if the block is accepted on L1 and its
+/// transaction is known.
+pub(super) fn l1_transaction(
+    tx: &Transaction<'_>,
+    block: BlockId,
+) -> anyhow::Result<Option<L1Transaction>> {
+    let Some((block_number, _)) = tx.block_id(block).context("Fetching block number")? else {
+        return Ok(None);
+    };
+
+    let l1_transaction = tx
+        .inner()
+        .query_row(
+            "SELECT l1_transaction_hash, l1_timestamp FROM l1_accepted_blocks WHERE block_number = ?",
+            params![&block_number],
+            |row| get_l1_transaction(row, 0),
+        )
+        .optional()
+        .context("Querying L1 transaction")?;
+
+    Ok(l1_transaction.flatten())
+}
+
+/// Reads the L1 transaction's hash and timestamp columns, starting at `index`.
+fn get_l1_transaction(
+    row: &rusqlite::Row<'_>,
+    index: usize,
+) -> rusqlite::Result<Option<L1Transaction>> {
+    let hash = row.get_optional_blob(index)?;
+    let timestamp = row.get_optional_i64(index + 1)?;
+
+    Ok(hash.zip(timestamp).map(|(hash, timestamp)| L1Transaction {
+        hash: H256::from_slice(hash),
+        timestamp: timestamp as u64,
+    }))
+}
+
+/// Reads the L1 block's number and hash columns, starting at `index`.
+fn get_l1_block(row: &rusqlite::Row<'_>, index: usize) -> rusqlite::Result<Option<L1Block>> {
+    let number = row.get_optional_i64(index)?;
+    let hash = row.get_optional_blob(index + 1)?;
+
+    Ok(number.zip(hash).map(|(number, hash)| L1Block {
+        number: number as u64,
+        hash: H256::from_slice(hash),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,6 +184,15 @@ mod tests {
                 state_root: StateCommitment(Felt::from_hex_str(&"3".repeat(i + 1)).unwrap()),
                 block_number: BlockNumber::GENESIS + i as u64,
                 block_hash: BlockHash(Felt::from_hex_str(&"F".repeat(i + 1)).unwrap()),
+                l1_transaction: Some(L1Transaction {
+                    hash: H256::repeat_byte(i as u8),
+                    timestamp: 1000 + i as u64,
+                }),
+                previous_block_number: i.checked_sub(1).map(|i| BlockNumber::GENESIS + i as u64),
+                l1_block: Some(L1Block {
+                    number: 100 + i as u64,
+                    hash: H256::repeat_byte(0x10 + i as u8),
+                }),
             })
             .collect::<Vec<_>>()
             .try_into()
@@ -156,6 +256,9 @@ mod tests {
             state_root: state_commitment!("0x1234"),
             block_number: BlockNumber::new_or_panic(10),
             block_hash: block_hash!("0xabdd"),
+            l1_transaction: None,
+            previous_block_number: None,
+            l1_block: None,
         };
         upsert_l1_state(&tx, &original).unwrap();
 
@@ -163,6 +266,9 @@ mod tests {
             state_root: state_commitment!("0xabcdef"),
             block_number: original.block_number,
             block_hash: block_hash!("0xccdd22"),
+            l1_transaction: None,
+            previous_block_number: None,
+            l1_block: None,
         };
         upsert_l1_state(&tx, &new_value).unwrap();
 
@@ -171,4 +277,100 @@ mod tests {
             .unwrap();
         assert_eq!(result, new_value);
     }
+
+    #[test]
+    fn purge_since_l1_block() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let [first, second, third] = create_updates();
+        // Updates read before the L1 block was tracked are kept.
+        let untracked = EthereumStateUpdate {
+            l1_block: None,
+            ..first
+        };
+        for update in [&untracked, &second, &third] {
+            upsert_l1_state(&tx, update).unwrap();
+        }
+
+        let purged = purge_l1_states_since(&tx, second.l1_block.unwrap().number).unwrap();
+        assert_eq!(purged, 2);
+        assert_eq!(latest_l1_state(&tx).unwrap(), Some(untracked));
+    }
+
+    #[test]
+    fn l1_transaction_of_accepted_blocks() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let genesis = pathfinder_common::BlockHeader::builder()
+            .finalize_with_hash(block_hash_bytes!(b"genesis"));
+        let header1 = genesis
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 1"));
+        let header2 = header1
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 2"));
+        let header3 = header2
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 3"));
+        for header in [&genesis, &header1, &header2, &header3] {
+            tx.insert_block_header(header).unwrap();
+        }
+
+        // The update of genesis was not observed.
+        let update = EthereumStateUpdate {
+            state_root: header2.state_commitment,
+            block_number: header2.number,
+            block_hash: header2.hash,
+            l1_transaction: Some(L1Transaction {
+                hash: H256::repeat_byte(1),
+                timestamp: 1000,
+            }),
+            previous_block_number: Some(genesis.number),
+            l1_block: None,
+        };
+        upsert_l1_state(&tx, &update).unwrap();
+        tx.update_l1_l2_pointer(Some(header2.number)).unwrap();
+
+        // Only the blocks after the previous update were accepted by this update.
+        assert!(tx.block_is_l1_accepted(genesis.number.into()).unwrap());
+        assert_eq!(l1_transaction(&tx, genesis.number.into()).unwrap(), None);
+        for header in [&header1, &header2] {
+            assert!(tx.block_is_l1_accepted(header.number.into()).unwrap());
+            let result = l1_transaction(&tx, header.hash.into()).unwrap();
+            assert_eq!(result, update.l1_transaction);
+        }
+        assert!(!tx.block_is_l1_accepted(header3.number.into()).unwrap());
+        assert_eq!(l1_transaction(&tx, header3.number.into()).unwrap(), None);
+
+        // Without the previous update, the blocks this update accepted are unknown.
+        let unbounded = EthereumStateUpdate {
+            state_root: header3.state_commitment,
+            block_number: header3.number,
+            block_hash: header3.hash,
+            l1_transaction: Some(L1Transaction {
+                hash: H256::repeat_byte(2),
+                timestamp: 2000,
+            }),
+            previous_block_number: None,
+            l1_block: None,
+        };
+        upsert_l1_state(&tx, &unbounded).unwrap();
+        tx.update_l1_l2_pointer(Some(header3.number)).unwrap();
+        assert!(tx.block_is_l1_accepted(header3.number.into()).unwrap());
+        assert_eq!(l1_transaction(&tx, header3.number.into()).unwrap(), None);
+        assert_eq!(
+            l1_transaction(&tx, header2.number.into()).unwrap(),
+            update.l1_transaction
+        );
+
+        // Resetting the pointer, e.g. during a reorg, unmarks the later blocks.
+        tx.update_l1_l2_pointer(Some(genesis.number)).unwrap();
+        assert!(tx.block_is_l1_accepted(genesis.number.into()).unwrap());
+        assert!(!tx.block_is_l1_accepted(header1.number.into()).unwrap());
+        assert_eq!(l1_transaction(&tx, header1.number.into()).unwrap(), None);
+    }
 }
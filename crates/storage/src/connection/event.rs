@@ -1,4 +1,4 @@
-use crate::params::ToSql;
+use crate::params::{encode_felts, ToSql};
 use crate::{prelude::*, BlockId};
 
 use anyhow::Context;
@@ -6,7 +6,6 @@ use pathfinder_common::event::Event;
 use pathfinder_common::{
     BlockHash, BlockNumber, ContractAddress, EventData, EventKey, TransactionHash,
 };
-use pathfinder_crypto::Felt;
 
 pub const PAGE_SIZE_LIMIT: usize = 1_024;
 pub const KEY_FILTER_LIMIT: usize = 256;
@@ -73,15 +72,15 @@ pub(super) fn insert_events(
                                VALUES (:block_number, :idx, :transaction_hash, :from_address, :keys, :data)"
     )?;
 
-    let mut keys = String::new();
-    let mut buffer = Vec::new();
+    let mut keys = Vec::new();
+    let mut data = Vec::new();
 
     for (idx, event) in events.iter().enumerate() {
         keys.clear();
-        event_keys_to_base64_strings(&event.keys, &mut keys);
+        encode_felts(event.keys.iter().map(|key| &key.0), &mut keys);
 
-        buffer.clear();
-        encode_event_data_to_bytes(&event.data, &mut buffer);
+        data.clear();
+        encode_felts(event.data.iter().map(|data| &data.0), &mut data);
 
         stmt.execute(named_params![
             ":block_number": &block_number,
@@ -89,7 +88,7 @@ pub(super) fn insert_events(
             ":transaction_hash": &transaction_hash,
             ":from_address": &event.from_address,
             ":keys": &keys,
-            ":data": &buffer,
+            ":data": &data,
         ])
         .context("Insert events into events table")?;
     }
@@ -214,35 +213,16 @@ pub(super) fn get_events<K: KeyFilter>(
                 .map_err(anyhow::Error::from)?;
 
             let data = row
-                .get_ref_unwrap("data")
-                .as_blob()
+                .get_felt_blob("data")
+                .map_err(anyhow::Error::from)?
+                .decode(EventData)
                 .map_err(anyhow::Error::from)?;
-            let data: Vec<_> = data
-                .chunks_exact(32)
-                .map(|data| {
-                    let data = Felt::from_be_slice(data).map_err(anyhow::Error::from)?;
-                    Ok(EventData(data))
-                })
-                .collect::<Result<_, EventFilterError>>()?;
-
             let keys = row
-                .get_ref_unwrap("keys")
-                .as_str()
+                .get_felt_blob("keys")
+                .map_err(anyhow::Error::from)?
+                .decode(EventKey)
                 .map_err(anyhow::Error::from)?;
 
-            // no need to allocate a vec for this in loop
-            let mut temp = [0u8; 32];
-
-            let keys: Vec<_> = keys
-                .split(' ')
-                .map(|key| {
-                    let used = base64::decode_config_slice(key, base64::STANDARD, &mut temp)
-                        .map_err(anyhow::Error::from)?;
-                    let key = Felt::from_be_slice(&temp[..used]).map_err(anyhow::Error::from)?;
-                    Ok(EventKey(key))
-                })
-                .collect::<Result<_, EventFilterError>>()?;
-
             let event = EmittedEvent {
                 data,
                 from_address,
@@ -261,32 +241,6 @@ pub(super) fn get_events<K: KeyFilter>(
     })
 }
 
-fn event_keys_to_base64_strings(keys: &[EventKey], out: &mut String) {
-    // with padding it seems 44 bytes are needed for each
-    let needed = (keys.len() * (" ".len() + 44)).saturating_sub(" ".len());
-
-    if let Some(more) = needed.checked_sub(out.capacity() - out.len()) {
-        // This is a wish which is not always fulfilled
-        out.reserve(more);
-    }
-
-    keys.iter().enumerate().for_each(|(i, x)| {
-        encode_event_key_to_base64(x, out);
-
-        if i != keys.len() - 1 {
-            out.push(' ');
-        }
-    });
-}
-
-fn encode_event_key_to_base64(key: &EventKey, buf: &mut String) {
-    base64::encode_config_buf(key.0.as_be_bytes(), base64::STANDARD, buf);
-}
-
-fn encode_event_data_to_bytes(data: &[EventData], buffer: &mut Vec<u8>) {
-    buffer.extend(data.iter().flat_map(|e| (*e.0.as_be_bytes()).into_iter()))
-}
-
 fn encode_event_key_and_index_to_base32(index: u8, key: &EventKey, output: &mut String) {
     let mut buf = [0u8; 33];
     buf[0] = index;
@@ -597,6 +551,7 @@ mod tests {
     use assert_matches::assert_matches;
     use pathfinder_common::macro_prelude::*;
     use pathfinder_common::{BlockHeader, BlockTimestamp, EntryPoint, Fee};
+    use pathfinder_crypto::Felt;
 
     use starknet_gateway_types::reply::transaction as gateway_tx;
 
@@ -605,7 +560,7 @@ mod tests {
         let data = [event_data!("0x1"), event_data!("0x2"), event_data!("0x3")];
 
         let mut buffer = Vec::new();
-        encode_event_data_to_bytes(&data, &mut buffer);
+        encode_felts(data.iter().map(|data| &data.0), &mut buffer);
 
         assert_eq!(
             &buffer,
@@ -619,25 +574,26 @@ mod tests {
     }
 
     #[test]
-    fn event_keys_to_base64_strings() {
-        let event = Event {
-            from_address: contract_address!(
-                "06fbd460228d843b7fbef670ff15607bf72e19fa94de21e29811ada167b4ca39"
-            ),
-            data: vec![],
-            keys: vec![
-                event_key!("0x901823"),
-                event_key!("0x901824"),
-                event_key!("0x901825"),
-            ],
-        };
+    fn event_keys_are_stored_as_bytes() {
+        let (storage, test_data) = test_utils::setup_test_storage();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
 
-        let mut buf = String::new();
-        super::event_keys_to_base64_strings(&event.keys, &mut buf);
-        assert_eq!(
-                    buf,
-                    "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAACQGCM= AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAACQGCQ= AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAACQGCU="
-                );
+        let keys: Vec<u8> = tx
+            .inner()
+            .query_row(
+                "SELECT keys FROM starknet_events ORDER BY id LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        let mut expected = Vec::new();
+        encode_felts(
+            test_data.events[0].keys.iter().map(|key| &key.0),
+            &mut expected,
+        );
+        assert_eq!(keys, expected);
     }
 
     #[test]
@@ -0,0 +1,309 @@
+use anyhow::Context;
+use pathfinder_common::{
+    BlockHash, BlockNumber, ContractAddress, EthereumAddress, L2ToL1MessagePayloadElem,
+    TransactionHash,
+};
+use primitive_types::{H160, H256};
+use starknet_gateway_types::reply::transaction as gateway;
+
+use crate::params::encode_felts;
+use crate::prelude::*;
+
+/// A message sent to L1 by a transaction of a canonical block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageToL1 {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub transaction_hash: TransactionHash,
+    pub from_address: ContractAddress,
+    pub to_address: EthereumAddress,
+    pub payload: Vec<L2ToL1MessagePayloadElem>,
+    /// The hash with which the message is recorded on L1.
+    pub message_hash: H256,
+}
+
+#[derive(Debug)]
+pub struct MessageToL1Filter {
+    pub from_block: Option<BlockNumber>,
+    pub to_block: Option<BlockNumber>,
+    /// The L1 recipient of the messages.
+    pub to_address: Option<EthereumAddress>,
+    pub page_size: usize,
+    pub offset: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageOfMessagesToL1 {
+    pub messages: Vec<MessageToL1>,
+    pub is_last_page: bool,
+}
+
+/// Indexes the messages sent to L1 by the transaction.
+pub(super) fn insert_messages_to_l1(
+    tx: &Transaction<'_>,
+    block_number: BlockNumber,
+    transaction_idx: usize,
+    transaction_hash: TransactionHash,
+    messages: &[gateway::L2ToL1Message],
+) -> anyhow::Result<()> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"INSERT INTO l2_to_l1_messages ( block_number,  transaction_idx,  idx,  transaction_hash,  from_address,  to_address,  payload,  message_hash)
+                                     VALUES (:block_number, :transaction_idx, :idx, :transaction_hash, :from_address, :to_address, :payload, :message_hash)",
+        )
+        .context("Preparing message insert statement")?;
+
+    let mut payload = Vec::new();
+    for (idx, message) in messages.iter().enumerate() {
+        payload.clear();
+        encode_felts(message.payload.iter().map(|elem| &elem.0), &mut payload);
+
+        stmt.execute(named_params![
+            ":block_number": &block_number,
+            ":transaction_idx": &transaction_idx.try_into_sql_int()?,
+            ":idx": &idx.try_into_sql_int()?,
+            ":transaction_hash": &transaction_hash,
+            ":from_address": &message.from_address,
+            ":to_address": &message.to_address.0.as_bytes(),
+            ":payload": &payload,
+            ":message_hash": &message.calculate_message_hash().as_bytes(),
+        ])
+        .context("Inserting message to L1")?;
+    }
+
+    Ok(())
+}
+
+/// The messages matching the filter, ordered by block, transaction and the order in which they
+/// were sent.
+pub(super) fn messages_to_l1(
+    tx: &Transaction<'_>,
+    filter: &MessageToL1Filter,
+) -> anyhow::Result<PageOfMessagesToL1> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"SELECT
+                l2_to_l1_messages.block_number,
+                canonical_blocks.hash as block_hash,
+                transaction_hash,
+                from_address,
+                to_address,
+                payload,
+                message_hash
+            FROM l2_to_l1_messages
+                INNER JOIN canonical_blocks ON (canonical_blocks.number = l2_to_l1_messages.block_number)
+            WHERE (:from_block IS NULL OR l2_to_l1_messages.block_number >= :from_block)
+                AND (:to_block IS NULL OR l2_to_l1_messages.block_number <= :to_block)
+                AND (:to_address IS NULL OR to_address = :to_address)
+            ORDER BY l2_to_l1_messages.block_number, transaction_idx, idx
+            LIMIT :limit OFFSET :offset",
+        )
+        .context("Preparing statement")?;
+
+    // One extra message is requested to decide whether there are more pages.
+    let limit = filter.page_size + 1;
+    let mut rows = stmt
+        .query(named_params![
+            ":from_block": &filter.from_block,
+            ":to_block": &filter.to_block,
+            ":to_address": &filter.to_address.map(|address| address.0.as_bytes().to_vec()),
+            ":limit": &limit.try_into_sql_int()?,
+            ":offset": &filter.offset.try_into_sql_int()?,
+        ])
+        .context("Executing query")?;
+
+    let mut messages = Vec::new();
+    let mut is_last_page = true;
+    while let Some(row) = rows.next().context("Fetching next message")? {
+        if messages.len() == filter.page_size {
+            is_last_page = false;
+            break;
+        }
+
+        let payload = row
+            .get_felt_blob("payload")?
+            .decode(L2ToL1MessagePayloadElem)?;
+
+        messages.push(MessageToL1 {
+            block_number: row.get_block_number("block_number")?,
+            block_hash: row.get_block_hash("block_hash")?,
+            transaction_hash: row.get_transaction_hash("transaction_hash")?,
+            from_address: row.get_contract_address("from_address")?,
+            to_address: EthereumAddress(H160::from_slice(row.get_blob("to_address")?)),
+            payload,
+            message_hash: H256::from_slice(row.get_blob("message_hash")?),
+        });
+    }
+
+    Ok(PageOfMessagesToL1 {
+        messages,
+        is_last_page,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::{BlockHeader, TransactionIndex};
+    use starknet_gateway_types::reply::transaction::{
+        ExecutionStatus, InvokeTransaction, InvokeTransactionV1, Receipt,
+    };
+
+    use super::*;
+    use crate::Storage;
+
+    fn message(from: &[u8], to: u64) -> gateway::L2ToL1Message {
+        gateway::L2ToL1Message {
+            from_address: contract_address_bytes!(from),
+            payload: vec![l2_to_l1_message_payload_elem_bytes!(b"payload")],
+            to_address: EthereumAddress(H160::from_low_u64_be(to)),
+        }
+    }
+
+    fn transaction_data(
+        hash: TransactionHash,
+        messages: Vec<gateway::L2ToL1Message>,
+    ) -> (gateway::Transaction, Receipt) {
+        let transaction =
+            gateway::Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+                calldata: vec![],
+                sender_address: contract_address_bytes!(b"sender"),
+                max_fee: Default::default(),
+                signature: vec![],
+                nonce: Default::default(),
+                transaction_hash: hash,
+            }));
+        let receipt = Receipt {
+            transaction_hash: hash,
+            transaction_index: TransactionIndex::new_or_panic(0),
+            actual_fee: None,
+            events: vec![],
+            execution_resources: None,
+            l1_to_l2_consumed_message: None,
+            l2_to_l1_messages: messages,
+            execution_status: ExecutionStatus::Succeeded,
+            revert_error: None,
+        };
+        (transaction, receipt)
+    }
+
+    #[test]
+    fn messages() {
+        let mut connection = Storage::in_memory().unwrap().connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let genesis = BlockHeader::builder().finalize_with_hash(block_hash_bytes!(b"genesis"));
+        let block1 = genesis
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 1"));
+
+        tx.insert_block_header(&genesis).unwrap();
+        tx.insert_transaction_data(
+            genesis.hash,
+            genesis.number,
+            &[
+                transaction_data(
+                    transaction_hash_bytes!(b"tx 0"),
+                    vec![message(b"contract 0", 1), message(b"contract 1", 2)],
+                ),
+                transaction_data(transaction_hash_bytes!(b"tx 1"), vec![]),
+            ],
+        )
+        .unwrap();
+        tx.insert_block_header(&block1).unwrap();
+        tx.insert_transaction_data(
+            block1.hash,
+            block1.number,
+            &[transaction_data(
+                transaction_hash_bytes!(b"tx 2"),
+                vec![message(b"contract 2", 1)],
+            )],
+        )
+        .unwrap();
+
+        let mut filter = MessageToL1Filter {
+            from_block: None,
+            to_block: None,
+            to_address: None,
+            page_size: 10,
+            offset: 0,
+        };
+
+        let page = messages_to_l1(&tx, &filter).unwrap();
+        assert!(page.is_last_page);
+        let senders = page
+            .messages
+            .iter()
+            .map(|message| message.from_address)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            senders,
+            vec![
+                contract_address_bytes!(b"contract 0"),
+                contract_address_bytes!(b"contract 1"),
+                contract_address_bytes!(b"contract 2"),
+            ]
+        );
+
+        let expected = message(b"contract 2", 1);
+        let last = page.messages.last().unwrap();
+        assert_eq!(last.block_number, block1.number);
+        assert_eq!(last.block_hash, block1.hash);
+        assert_eq!(last.transaction_hash, transaction_hash_bytes!(b"tx 2"));
+        assert_eq!(last.to_address, expected.to_address);
+        assert_eq!(last.payload, expected.payload);
+        assert_eq!(last.message_hash, expected.calculate_message_hash());
+
+        // Filtered by recipient.
+        filter.to_address = Some(EthereumAddress(H160::from_low_u64_be(1)));
+        let page = messages_to_l1(&tx, &filter).unwrap();
+        assert_eq!(page.messages.len(), 2);
+
+        // Filtered by block range.
+        filter.from_block = Some(block1.number);
+        let page = messages_to_l1(&tx, &filter).unwrap();
+        assert_eq!(page.messages.len(), 1);
+        assert_eq!(page.messages[0].block_number, block1.number);
+
+        // Paged.
+        let filter = MessageToL1Filter {
+            from_block: None,
+            to_block: None,
+            to_address: None,
+            page_size: 2,
+            offset: 0,
+        };
+        let page = messages_to_l1(&tx, &filter).unwrap();
+        assert_eq!(page.messages.len(), 2);
+        assert!(!page.is_last_page);
+
+        let page = messages_to_l1(
+            &tx,
+            &MessageToL1Filter {
+                offset: 2,
+                ..filter
+            },
+        )
+        .unwrap();
+        assert_eq!(page.messages.len(), 1);
+        assert!(page.is_last_page);
+
+        // Purging the block removes its messages.
+        tx.purge_block(block1.number).unwrap();
+        let filter = MessageToL1Filter {
+            from_block: None,
+            to_block: None,
+            to_address: None,
+            page_size: 10,
+            offset: 0,
+        };
+        let page = messages_to_l1(&tx, &filter).unwrap();
+        assert_eq!(page.messages.len(), 2);
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use crate::prelude::*;
+
+pub(super) fn metric_counters(tx: &Transaction<'_>) -> anyhow::Result<HashMap<String, u64>> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached("SELECT name, value FROM metric_counters")
+        .context("Preparing metric counters query")?;
+
+    let counters = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Querying metric counters")?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .context("Iterating over metric counters")?;
+
+    Ok(counters)
+}
+
+pub(super) fn upsert_metric_counters(
+    tx: &Transaction<'_>,
+    counters: &[(&str, u64)],
+) -> anyhow::Result<()> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached("INSERT OR REPLACE INTO metric_counters (name, value) VALUES (?, ?)")
+        .context("Preparing metric counter upsert")?;
+
+    for (name, value) in counters {
+        stmt.execute(params![name, value])
+            .with_context(|| format!("Upserting metric counter {name}"))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn upsert() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        assert!(tx.metric_counters().unwrap().is_empty());
+
+        tx.upsert_metric_counters(&[("a", 1), ("b", 2)]).unwrap();
+        tx.upsert_metric_counters(&[("b", 3)]).unwrap();
+
+        assert_eq!(
+            tx.metric_counters().unwrap(),
+            HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 3)])
+        );
+    }
+}
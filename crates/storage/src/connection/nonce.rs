@@ -0,0 +1,220 @@
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber, ContractAddress, ContractNonce};
+
+use crate::prelude::*;
+
+/// The nonce of a contract after a canonical block which changed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonceUpdate {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub nonce: ContractNonce,
+}
+
+#[derive(Debug)]
+pub struct NonceHistoryFilter {
+    pub contract_address: ContractAddress,
+    pub from_block: Option<BlockNumber>,
+    pub to_block: Option<BlockNumber>,
+    pub page_size: usize,
+    pub offset: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageOfNonceUpdates {
+    pub updates: Vec<NonceUpdate>,
+    pub is_last_page: bool,
+}
+
+/// The nonce updates of the filter's contract, ordered by block.
+pub(super) fn nonce_history(
+    tx: &Transaction<'_>,
+    filter: &NonceHistoryFilter,
+) -> anyhow::Result<PageOfNonceUpdates> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"SELECT
+                nonce_updates.block_number,
+                canonical_blocks.hash as block_hash,
+                nonce
+            FROM nonce_updates
+                INNER JOIN canonical_blocks ON (canonical_blocks.number = nonce_updates.block_number)
+            WHERE contract_address = :contract_address
+                AND (:from_block IS NULL OR nonce_updates.block_number >= :from_block)
+                AND (:to_block IS NULL OR nonce_updates.block_number <= :to_block)
+            ORDER BY nonce_updates.block_number
+            LIMIT :limit OFFSET :offset",
+        )
+        .context("Preparing statement")?;
+
+    // One extra update is requested to decide whether there are more pages.
+    let limit = filter.page_size + 1;
+    let mut rows = stmt
+        .query(named_params![
+            ":contract_address": &filter.contract_address,
+            ":from_block": &filter.from_block,
+            ":to_block": &filter.to_block,
+            ":limit": &limit.try_into_sql_int()?,
+            ":offset": &filter.offset.try_into_sql_int()?,
+        ])
+        .context("Executing query")?;
+
+    let mut updates = Vec::new();
+    let mut is_last_page = true;
+    while let Some(row) = rows.next().context("Fetching next nonce update")? {
+        if updates.len() == filter.page_size {
+            is_last_page = false;
+            break;
+        }
+
+        updates.push(NonceUpdate {
+            block_number: row.get_block_number("block_number")?,
+            block_hash: row.get_block_hash("block_hash")?,
+            nonce: row.get_contract_nonce("nonce")?,
+        });
+    }
+
+    Ok(PageOfNonceUpdates {
+        updates,
+        is_last_page,
+    })
+}
+
+/// The first nonce update of the contract which reached at least the given nonce.
+///
+/// Nonces only ever increase, so this is the block in which the contract's nonce first reached
+/// `nonce`. The updates are compared after decoding, as nonces are stored without their leading
+/// zeros and therefore don't compare as blobs.
+pub(super) fn first_nonce_update_reaching(
+    tx: &Transaction<'_>,
+    contract_address: ContractAddress,
+    nonce: ContractNonce,
+) -> anyhow::Result<Option<NonceUpdate>> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"SELECT
+                nonce_updates.block_number,
+                canonical_blocks.hash as block_hash,
+                nonce
+            FROM nonce_updates
+                INNER JOIN canonical_blocks ON (canonical_blocks.number = nonce_updates.block_number)
+            WHERE contract_address = ?
+            ORDER BY nonce_updates.block_number",
+        )
+        .context("Preparing statement")?;
+
+    let mut rows = stmt
+        .query(params![&contract_address])
+        .context("Executing query")?;
+
+    while let Some(row) = rows.next().context("Fetching next nonce update")? {
+        let update = NonceUpdate {
+            block_number: row.get_block_number("block_number")?,
+            block_hash: row.get_block_hash("block_hash")?,
+            nonce: row.get_contract_nonce("nonce")?,
+        };
+
+        if update.nonce >= nonce {
+            return Ok(Some(update));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::{BlockHeader, StateUpdate};
+
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn nonces() {
+        let mut connection = Storage::in_memory().unwrap().connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let account = contract_address_bytes!(b"account");
+        let other = contract_address_bytes!(b"other");
+
+        let genesis = BlockHeader::builder().finalize_with_hash(block_hash_bytes!(b"genesis"));
+        let block1 = genesis
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 1"));
+        let block2 = block1
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 2"));
+
+        let updates = [
+            (
+                &genesis,
+                StateUpdate::default()
+                    .with_contract_nonce(account, contract_nonce!("0x1"))
+                    .with_contract_nonce(other, contract_nonce!("0x5")),
+            ),
+            (&block1, StateUpdate::default()),
+            (
+                &block2,
+                StateUpdate::default().with_contract_nonce(account, contract_nonce!("0x100")),
+            ),
+        ];
+        for (header, state_update) in updates {
+            tx.insert_block_header(header).unwrap();
+            tx.insert_state_update(header.number, &state_update)
+                .unwrap();
+        }
+
+        let mut filter = NonceHistoryFilter {
+            contract_address: account,
+            from_block: None,
+            to_block: None,
+            page_size: 10,
+            offset: 0,
+        };
+        let page = tx.nonce_history(&filter).unwrap();
+        assert_eq!(
+            page,
+            PageOfNonceUpdates {
+                updates: vec![
+                    NonceUpdate {
+                        block_number: genesis.number,
+                        block_hash: genesis.hash,
+                        nonce: contract_nonce!("0x1"),
+                    },
+                    NonceUpdate {
+                        block_number: block2.number,
+                        block_hash: block2.hash,
+                        nonce: contract_nonce!("0x100"),
+                    },
+                ],
+                is_last_page: true,
+            }
+        );
+
+        filter.from_block = Some(block1.number);
+        let page = tx.nonce_history(&filter).unwrap();
+        assert_eq!(page.updates.len(), 1);
+
+        filter.from_block = None;
+        filter.page_size = 1;
+        let page = tx.nonce_history(&filter).unwrap();
+        assert_eq!(page.updates.len(), 1);
+        assert!(!page.is_last_page);
+
+        let first = |nonce| {
+            tx.first_nonce_update_reaching(account, nonce)
+                .unwrap()
+                .map(|update| update.block_number)
+        };
+        assert_eq!(first(contract_nonce!("0x0")), Some(genesis.number));
+        assert_eq!(first(contract_nonce!("0x1")), Some(genesis.number));
+        // Only compares correctly once decoded, as 0x2 is stored as a single byte which is
+        // larger than the first byte of 0x100.
+        assert_eq!(first(contract_nonce!("0x2")), Some(block2.number));
+        assert_eq!(first(contract_nonce!("0x100")), Some(block2.number));
+        assert_eq!(first(contract_nonce!("0x101")), None);
+    }
+}
@@ -1,7 +1,12 @@
+use anyhow::Context;
 use pathfinder_common::BlockNumber;
 
 use crate::prelude::*;
 
+/// Also marks the blocks up to `head` as accepted on L1. Each block keeps the L1 transaction of the
+/// stored update which accepted it, i.e. the update whose previous update is before the block.
+/// The transaction is unknown if no such update is stored. Blocks after `head` are no longer
+/// accepted on L1, e.g. after a reorg.
 pub(super) fn update_l1_l2_pointer(
     tx: &Transaction<'_>,
     head: Option<BlockNumber>,
@@ -11,6 +16,31 @@ pub(super) fn update_l1_l2_pointer(
         params![&head],
     )?;
 
+    let Some(head) = head else {
+        tx.inner()
+            .execute("DELETE FROM l1_accepted_blocks", [])
+            .context("Unmarking L1 accepted blocks")?;
+        return Ok(());
+    };
+
+    tx.inner()
+        .execute(
+            "DELETE FROM l1_accepted_blocks WHERE block_number > ?",
+            params![&head],
+        )
+        .context("Unmarking L1 accepted blocks")?;
+    tx.inner()
+        .execute(
+            r"INSERT OR IGNORE INTO l1_accepted_blocks (block_number, l1_transaction_hash, l1_timestamp)
+            SELECT number, l1_transaction_hash, l1_timestamp FROM canonical_blocks
+            LEFT JOIN l1_state
+                ON previous_starknet_block_number < number AND number <= starknet_block_number
+            WHERE number <= :head
+            AND number > (SELECT COALESCE(MAX(block_number), -1) FROM l1_accepted_blocks)",
+            named_params! {":head": &head},
+        )
+        .context("Marking L1 accepted blocks")?;
+
     Ok(())
 }
 
@@ -0,0 +1,266 @@
+use anyhow::Context;
+use pathfinder_common::{BlockHash, BlockNumber, ContractAddress, TransactionHash};
+use starknet_gateway_types::reply::transaction as gateway;
+
+use crate::prelude::*;
+
+/// A transaction of a canonical block, sent by an account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SenderTransaction {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub transaction_hash: TransactionHash,
+}
+
+#[derive(Debug)]
+pub struct SenderTransactionFilter {
+    pub sender_address: ContractAddress,
+    pub from_block: Option<BlockNumber>,
+    pub to_block: Option<BlockNumber>,
+    pub page_size: usize,
+    pub offset: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageOfSenderTransactions {
+    pub transactions: Vec<SenderTransaction>,
+    pub is_last_page: bool,
+}
+
+/// Indexes the transaction by its sender, if it was sent by an account.
+pub(super) fn insert_transaction_sender(
+    tx: &Transaction<'_>,
+    block_number: BlockNumber,
+    transaction_idx: usize,
+    transaction: &gateway::Transaction,
+) -> anyhow::Result<()> {
+    let Some(sender_address) = transaction.sender_address() else {
+        return Ok(());
+    };
+
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"INSERT INTO transactions_by_sender ( sender_address,  block_number,  transaction_idx,  transaction_hash)
+                                          VALUES (:sender_address, :block_number, :transaction_idx, :transaction_hash)",
+        )
+        .context("Preparing sender insert statement")?;
+
+    stmt.execute(named_params![
+        ":sender_address": &sender_address,
+        ":block_number": &block_number,
+        ":transaction_idx": &transaction_idx.try_into_sql_int()?,
+        ":transaction_hash": &transaction.hash(),
+    ])
+    .context("Inserting transaction sender")?;
+
+    Ok(())
+}
+
+/// The transactions sent by the filter's account, ordered by block and their position in the
+/// block.
+pub(super) fn sender_transactions(
+    tx: &Transaction<'_>,
+    filter: &SenderTransactionFilter,
+) -> anyhow::Result<PageOfSenderTransactions> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"SELECT
+                transactions_by_sender.block_number,
+                canonical_blocks.hash as block_hash,
+                transaction_hash
+            FROM transactions_by_sender
+                INNER JOIN canonical_blocks ON (canonical_blocks.number = transactions_by_sender.block_number)
+            WHERE sender_address = :sender_address
+                AND (:from_block IS NULL OR transactions_by_sender.block_number >= :from_block)
+                AND (:to_block IS NULL OR transactions_by_sender.block_number <= :to_block)
+            ORDER BY transactions_by_sender.block_number, transaction_idx
+            LIMIT :limit OFFSET :offset",
+        )
+        .context("Preparing statement")?;
+
+    // One extra transaction is requested to decide whether there are more pages.
+    let limit = filter.page_size + 1;
+    let mut rows = stmt
+        .query(named_params![
+            ":sender_address": &filter.sender_address,
+            ":from_block": &filter.from_block,
+            ":to_block": &filter.to_block,
+            ":limit": &limit.try_into_sql_int()?,
+            ":offset": &filter.offset.try_into_sql_int()?,
+        ])
+        .context("Executing query")?;
+
+    let mut transactions = Vec::new();
+    let mut is_last_page = true;
+    while let Some(row) = rows.next().context("Fetching next transaction")? {
+        if transactions.len() == filter.page_size {
+            is_last_page = false;
+            break;
+        }
+
+        transactions.push(SenderTransaction {
+            block_number: row.get_block_number("block_number")?,
+            block_hash: row.get_block_hash("block_hash")?,
+            transaction_hash: row.get_transaction_hash("transaction_hash")?,
+        });
+    }
+
+    Ok(PageOfSenderTransactions {
+        transactions,
+        is_last_page,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::{BlockHeader, TransactionIndex};
+    use starknet_gateway_types::reply::transaction::{
+        ExecutionStatus, InvokeTransaction, InvokeTransactionV1, L1HandlerTransaction, Receipt,
+    };
+
+    use super::*;
+    use crate::Storage;
+
+    fn receipt(hash: TransactionHash) -> Receipt {
+        Receipt {
+            transaction_hash: hash,
+            transaction_index: TransactionIndex::new_or_panic(0),
+            actual_fee: None,
+            events: vec![],
+            execution_resources: None,
+            l1_to_l2_consumed_message: None,
+            l2_to_l1_messages: vec![],
+            execution_status: ExecutionStatus::Succeeded,
+            revert_error: None,
+        }
+    }
+
+    fn invoke(hash: TransactionHash, sender: ContractAddress) -> (gateway::Transaction, Receipt) {
+        let transaction =
+            gateway::Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+                calldata: vec![],
+                sender_address: sender,
+                max_fee: Default::default(),
+                signature: vec![],
+                nonce: Default::default(),
+                transaction_hash: hash,
+            }));
+        (transaction, receipt(hash))
+    }
+
+    fn l1_handler(
+        hash: TransactionHash,
+        contract: ContractAddress,
+    ) -> (gateway::Transaction, Receipt) {
+        let transaction = gateway::Transaction::L1Handler(L1HandlerTransaction {
+            contract_address: contract,
+            entry_point_selector: Default::default(),
+            nonce: Default::default(),
+            calldata: vec![],
+            transaction_hash: hash,
+            version: Default::default(),
+        });
+        (transaction, receipt(hash))
+    }
+
+    #[test]
+    fn transactions_by_sender() {
+        let mut connection = Storage::in_memory().unwrap().connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let account = contract_address_bytes!(b"account");
+        let other = contract_address_bytes!(b"other");
+
+        let genesis = BlockHeader::builder().finalize_with_hash(block_hash_bytes!(b"genesis"));
+        let block1 = genesis
+            .child_builder()
+            .finalize_with_hash(block_hash_bytes!(b"block 1"));
+
+        tx.insert_block_header(&genesis).unwrap();
+        tx.insert_transaction_data(
+            genesis.hash,
+            genesis.number,
+            &[
+                invoke(transaction_hash_bytes!(b"tx 0"), account),
+                invoke(transaction_hash_bytes!(b"tx 1"), other),
+                // Not sent by an account.
+                l1_handler(transaction_hash_bytes!(b"tx 2"), account),
+                invoke(transaction_hash_bytes!(b"tx 3"), account),
+            ],
+        )
+        .unwrap();
+        tx.insert_block_header(&block1).unwrap();
+        tx.insert_transaction_data(
+            block1.hash,
+            block1.number,
+            &[invoke(transaction_hash_bytes!(b"tx 4"), account)],
+        )
+        .unwrap();
+
+        let mut filter = SenderTransactionFilter {
+            sender_address: account,
+            from_block: None,
+            to_block: None,
+            page_size: 10,
+            offset: 0,
+        };
+
+        let page = tx.sender_transactions(&filter).unwrap();
+        assert!(page.is_last_page);
+        let hashes = page
+            .transactions
+            .iter()
+            .map(|transaction| transaction.transaction_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hashes,
+            vec![
+                transaction_hash_bytes!(b"tx 0"),
+                transaction_hash_bytes!(b"tx 3"),
+                transaction_hash_bytes!(b"tx 4"),
+            ]
+        );
+        assert_eq!(
+            page.transactions[2],
+            SenderTransaction {
+                block_number: block1.number,
+                block_hash: block1.hash,
+                transaction_hash: transaction_hash_bytes!(b"tx 4"),
+            }
+        );
+
+        // Filtered by block range.
+        filter.to_block = Some(genesis.number);
+        let page = tx.sender_transactions(&filter).unwrap();
+        assert_eq!(page.transactions.len(), 2);
+
+        // Paged.
+        filter.page_size = 1;
+        let page = tx.sender_transactions(&filter).unwrap();
+        assert_eq!(page.transactions.len(), 1);
+        assert!(!page.is_last_page);
+
+        filter.offset = 1;
+        let page = tx.sender_transactions(&filter).unwrap();
+        assert_eq!(
+            page.transactions[0].transaction_hash,
+            transaction_hash_bytes!(b"tx 3")
+        );
+        assert!(page.is_last_page);
+
+        // Purging the block removes its transactions.
+        tx.purge_block(block1.number).unwrap();
+        let filter = SenderTransactionFilter {
+            sender_address: account,
+            from_block: None,
+            to_block: None,
+            page_size: 10,
+            offset: 0,
+        };
+        let page = tx.sender_transactions(&filter).unwrap();
+        assert_eq!(page.transactions.len(), 2);
+    }
+}
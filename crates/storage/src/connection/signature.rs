@@ -23,3 +23,49 @@ pub(super) fn insert_signature(
 
     Ok(())
 }
+
+pub(super) fn signature(
+    tx: &Transaction<'_>,
+    block_number: BlockNumber,
+) -> anyhow::Result<Option<BlockCommitmentSignature>> {
+    tx.inner()
+        .query_row(
+            "SELECT signature_r, signature_s FROM block_signatures WHERE block_number = ?",
+            params![&block_number],
+            |row| {
+                let r = row.get_block_commitment_signature_elem(0)?;
+                let s = row.get_block_commitment_signature_elem(1)?;
+
+                Ok(BlockCommitmentSignature { r, s })
+            },
+        )
+        .optional()
+        .context("Querying signature")
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::BlockHeader;
+
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let storage = crate::Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let header = BlockHeader::builder().finalize_with_hash(block_hash_bytes!(b"genesis"));
+        tx.insert_block_header(&header).unwrap();
+
+        let signature = BlockCommitmentSignature {
+            r: block_commitment_signature_elem_bytes!(b"r"),
+            s: block_commitment_signature_elem_bytes!(b"s"),
+        };
+        tx.insert_signature(header.number, &signature).unwrap();
+
+        assert_eq!(tx.signature(header.number).unwrap(), Some(signature));
+        assert_eq!(tx.signature(BlockNumber::new_or_panic(1)).unwrap(), None);
+    }
+}
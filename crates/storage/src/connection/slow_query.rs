@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::prelude::*;
+use crate::SlowQueryStats;
+
+pub(super) fn slow_queries(tx: &Transaction<'_>) -> anyhow::Result<Vec<(String, SlowQueryStats)>> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached("SELECT name, count, total_micros, max_micros FROM slow_queries")
+        .context("Preparing slow queries query")?;
+
+    let queries = stmt
+        .query_map([], |row| {
+            let stats = SlowQueryStats {
+                count: row.get(1)?,
+                total: Duration::from_micros(row.get(2)?),
+                max: Duration::from_micros(row.get(3)?),
+            };
+            Ok((row.get(0)?, stats))
+        })
+        .context("Querying slow queries")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Iterating over slow queries")?;
+
+    Ok(queries)
+}
+
+/// Adds the statistics to those already recorded.
+pub(super) fn record_slow_queries(
+    tx: &Transaction<'_>,
+    queries: &[(&str, SlowQueryStats)],
+) -> anyhow::Result<()> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"INSERT INTO slow_queries (name, count, total_micros, max_micros) VALUES (?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                count = count + excluded.count,
+                total_micros = total_micros + excluded.total_micros,
+                max_micros = MAX(max_micros, excluded.max_micros)",
+        )
+        .context("Preparing slow query upsert")?;
+
+    for (name, stats) in queries {
+        // Saturate instead of failing for absurd durations.
+        let micros = |duration: Duration| u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        stmt.execute(params![
+            name,
+            &stats.count,
+            &micros(stats.total),
+            &micros(stats.max)
+        ])
+        .with_context(|| format!("Upserting slow query {name}"))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn record() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        assert!(tx.slow_queries().unwrap().is_empty());
+
+        let stats = |count, total, max| SlowQueryStats {
+            count,
+            total: Duration::from_millis(total),
+            max: Duration::from_millis(max),
+        };
+        tx.record_slow_queries(&[
+            ("events", stats(2, 300, 200)),
+            ("block_header", stats(1, 50, 50)),
+        ])
+        .unwrap();
+        tx.record_slow_queries(&[("events", stats(1, 100, 100))])
+            .unwrap();
+
+        let mut queries = tx.slow_queries().unwrap();
+        queries.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            queries,
+            vec![
+                ("block_header".to_owned(), stats(1, 50, 50)),
+                ("events".to_owned(), stats(3, 400, 200)),
+            ]
+        );
+    }
+}
@@ -84,10 +84,22 @@ pub(super) fn insert_state_update(
 
     let declared_classes = sierra.chain(cairo).chain(deployed);
 
+    let mut declared_class_count = 0;
     for class in declared_classes {
-        update_class_defs.execute(params![&block_number, &class])?;
+        declared_class_count += update_class_defs.execute(params![&block_number, &class])?;
     }
 
+    super::contract_deployment::insert_contract_deployments(tx, block_number, state_update)
+        .context("Indexing contract deployments")?;
+
+    super::statistics::insert_block_statistics(
+        tx,
+        block_number,
+        state_update,
+        declared_class_count,
+    )
+    .context("Updating chain statistics")?;
+
     Ok(())
 }
 
@@ -273,39 +285,60 @@ pub(super) fn state_update(
     Ok(Some(state_update))
 }
 
+/// Returns the value of the storage slot at the given block.
+///
+/// Values at the latest block are read from the flat `storage_flat` table, which holds the
+/// current value of each slot. Historical values fall back to the `storage_updates` history.
 pub(super) fn storage_value(
     tx: &Transaction<'_>,
     block: BlockId,
     contract_address: ContractAddress,
     key: StorageAddress,
 ) -> anyhow::Result<Option<StorageValue>> {
-    match block {
-        BlockId::Latest => tx.inner().query_row(
-            r"SELECT storage_value FROM storage_updates 
-                WHERE contract_address = ? AND storage_address = ?
-                ORDER BY block_number DESC LIMIT 1",
+    let latest = tx
+        .inner()
+        .query_row(
+            "SELECT storage_value, block_number FROM storage_flat WHERE contract_address = ? AND storage_address = ?",
             params![&contract_address, &key],
-            |row| row.get_storage_value(0),
-        ),
-        BlockId::Number(number) => tx.inner().query_row(
-            r"SELECT storage_value FROM storage_updates
+            |row| Ok((row.get_storage_value(0)?, row.get_block_number(1)?)),
+        )
+        .optional()
+        .context("Querying flat storage")?;
+
+    // A slot which was never written has no history either.
+    let Some((latest, updated_at)) = latest else {
+        return Ok(None);
+    };
+
+    match block {
+        BlockId::Latest => Ok(Some(latest)),
+        // The latest value is also the value at any block since it was last updated.
+        BlockId::Number(number) if number >= updated_at => Ok(Some(latest)),
+        BlockId::Number(number) => tx
+            .inner()
+            .query_row(
+                r"SELECT storage_value FROM storage_updates
                 WHERE contract_address = ? AND storage_address = ? AND block_number <= ?
                 ORDER BY block_number DESC LIMIT 1",
-            params![&contract_address, &key, &number],
-            |row| row.get_storage_value(0),
-        ),
-        BlockId::Hash(hash) => tx.inner().query_row(
-            r"SELECT storage_value FROM storage_updates
+                params![&contract_address, &key, &number],
+                |row| row.get_storage_value(0),
+            )
+            .optional()
+            .map_err(|e| e.into()),
+        BlockId::Hash(hash) => tx
+            .inner()
+            .query_row(
+                r"SELECT storage_value FROM storage_updates
                 WHERE contract_address = ? AND storage_address = ? AND block_number <= (
                     SELECT number FROM canonical_blocks WHERE hash = ?
                 )
                 ORDER BY block_number DESC LIMIT 1",
-            params![&contract_address, &key, &hash],
-            |row| row.get_storage_value(0),
-        ),
+                params![&contract_address, &key, &hash],
+                |row| row.get_storage_value(0),
+            )
+            .optional()
+            .map_err(|e| e.into()),
     }
-    .optional()
-    .map_err(|e| e.into())
 }
 
 pub(super) fn contract_exists(
@@ -735,5 +768,48 @@ mod tests {
                 storage_value(&tx, header.number.into(), invalid_contract, key).unwrap();
             assert_eq!(by_number, None);
         }
+
+        #[test]
+        fn flat_storage_follows_head() {
+            let (mut db, state_update, header) = setup();
+            let tx = db.transaction().unwrap();
+
+            let (contract, key, original) = state_update
+                .contract_updates
+                .iter()
+                .flat_map(|(addr, update)| {
+                    update
+                        .storage
+                        .iter()
+                        .map(|(key, value)| (*addr, *key, *value))
+                })
+                .next()
+                .unwrap();
+
+            let next = BlockHeader::builder()
+                .with_number(header.number + 1)
+                .finalize_with_hash(block_hash_bytes!(b"next"));
+            let updated = storage_value_bytes!(b"updated value");
+            tx.insert_block_header(&next).unwrap();
+            tx.insert_state_update(
+                next.number,
+                &StateUpdate::default().with_storage_update(contract, key, updated),
+            )
+            .unwrap();
+
+            let latest = storage_value(&tx, BlockId::Latest, contract, key).unwrap();
+            assert_eq!(latest, Some(updated));
+            let historic = storage_value(&tx, header.number.into(), contract, key).unwrap();
+            assert_eq!(historic, Some(original));
+
+            // Purging the head block reverts to the previous value.
+            tx.purge_block(next.number).unwrap();
+            let latest = storage_value(&tx, BlockId::Latest, contract, key).unwrap();
+            assert_eq!(latest, Some(original));
+
+            tx.purge_block(header.number).unwrap();
+            let latest = storage_value(&tx, BlockId::Latest, contract, key).unwrap();
+            assert_eq!(latest, None);
+        }
     }
 }
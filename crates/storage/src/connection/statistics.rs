@@ -0,0 +1,264 @@
+use anyhow::Context;
+use pathfinder_common::{BlockNumber, BlockTimestamp, GasPrice, StateUpdate};
+
+use crate::prelude::*;
+
+/// The period over which the rolling statistics are aggregated, in seconds.
+pub const STATISTICS_WINDOW: u64 = 24 * 60 * 60;
+
+/// Aggregates of the canonical chain, over the [window](STATISTICS_WINDOW) of blocks up to the
+/// latest block unless noted otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainStatistics {
+    /// The latest block.
+    pub block_number: BlockNumber,
+    /// The first block of the window.
+    pub window_start: BlockNumber,
+    pub transaction_count: u64,
+    /// The number of distinct contracts whose class, nonce or storage was updated.
+    pub active_contract_count: u64,
+    /// The average of the blocks' gas prices.
+    pub average_gas_price: GasPrice,
+    /// The number of classes declared up to and including the latest block.
+    pub declared_class_count: u64,
+}
+
+/// Updates the statistics with the block's state update.
+///
+/// `declared_class_count` is the number of classes first declared in the block.
+pub(super) fn insert_block_statistics(
+    tx: &Transaction<'_>,
+    block_number: BlockNumber,
+    state_update: &StateUpdate,
+    declared_class_count: usize,
+) -> anyhow::Result<()> {
+    let timestamp = tx
+        .inner()
+        .query_row(
+            "SELECT timestamp FROM block_headers WHERE number = ?",
+            params![&block_number],
+            |row| row.get_timestamp(0),
+        )
+        .optional()
+        .context("Querying block timestamp")?;
+    // Statistics are only kept for canonical blocks.
+    let Some(timestamp) = timestamp else {
+        return Ok(());
+    };
+
+    tx.inner()
+        .execute(
+            r"INSERT OR IGNORE INTO block_statistics (block_number, declared_class_count)
+            SELECT ?1, ?2 + IFNULL((
+                SELECT declared_class_count FROM block_statistics
+                WHERE block_number < ?1 ORDER BY block_number DESC LIMIT 1
+            ), 0)",
+            params![&block_number, &declared_class_count.try_into_sql_int()?],
+        )
+        .context("Inserting block statistics")?;
+
+    let mut insert_active = tx
+        .inner()
+        .prepare_cached(
+            "INSERT INTO block_active_contracts (block_number, contract_address) VALUES (?, ?)",
+        )
+        .context("Preparing active contract insert statement")?;
+    for address in state_update.contract_updates.keys() {
+        insert_active
+            .execute(params![&block_number, address])
+            .context("Inserting active contract")?;
+    }
+
+    // Active contracts are only needed for the window, so older blocks' are pruned.
+    let window_start = window_start(tx, timestamp)?;
+    tx.inner()
+        .execute(
+            "DELETE FROM block_active_contracts WHERE block_number < ?",
+            params![&window_start],
+        )
+        .context("Pruning active contracts")?;
+
+    Ok(())
+}
+
+pub(super) fn chain_statistics(tx: &Transaction<'_>) -> anyhow::Result<Option<ChainStatistics>> {
+    let latest = tx
+        .inner()
+        .query_row(
+            "SELECT number, timestamp FROM block_headers ORDER BY number DESC LIMIT 1",
+            [],
+            |row| Ok((row.get_block_number(0)?, row.get_timestamp(1)?)),
+        )
+        .optional()
+        .context("Querying latest block")?;
+    let Some((block_number, timestamp)) = latest else {
+        return Ok(None);
+    };
+
+    let window_start = window_start(tx, timestamp)?;
+
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            "SELECT transaction_count, gas_price FROM block_headers WHERE number >= ? AND number <= ?",
+        )
+        .context("Preparing window statement")?;
+    let mut rows = stmt
+        .query(params![&window_start, &block_number])
+        .context("Querying window blocks")?;
+
+    let mut block_count = 0u128;
+    let mut transaction_count = 0u64;
+    let mut gas_price_sum = 0u128;
+    while let Some(row) = rows.next().context("Fetching next block")? {
+        block_count += 1;
+        transaction_count += row.get_i64(0)? as u64;
+        gas_price_sum = gas_price_sum.saturating_add(row.get_gas_price(1)?.0);
+    }
+    let average_gas_price = GasPrice(gas_price_sum.checked_div(block_count).unwrap_or_default());
+
+    let active_contract_count: i64 = tx
+        .inner()
+        .query_row(
+            "SELECT COUNT(DISTINCT contract_address) FROM block_active_contracts WHERE block_number >= ?",
+            params![&window_start],
+            |row| row.get(0),
+        )
+        .context("Counting active contracts")?;
+
+    let declared_class_count: Option<i64> = tx
+        .inner()
+        .query_row(
+            "SELECT declared_class_count FROM block_statistics ORDER BY block_number DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("Querying declared class count")?;
+
+    Ok(Some(ChainStatistics {
+        block_number,
+        window_start,
+        transaction_count,
+        active_contract_count: active_contract_count as u64,
+        average_gas_price,
+        declared_class_count: declared_class_count.unwrap_or_default() as u64,
+    }))
+}
+
+/// The first block of the window ending at `timestamp`.
+fn window_start(tx: &Transaction<'_>, timestamp: BlockTimestamp) -> anyhow::Result<BlockNumber> {
+    let Some(start) = timestamp.get().checked_sub(STATISTICS_WINDOW) else {
+        return Ok(BlockNumber::GENESIS);
+    };
+    let start = BlockTimestamp::new_or_panic(start);
+
+    // Block timestamps are monotonic, so the window starts after the latest block before it.
+    // Searching from the latest block limits the scan to the window.
+    let before_window = tx
+        .inner()
+        .query_row(
+            "SELECT number FROM block_headers WHERE timestamp <= ? ORDER BY number DESC LIMIT 1",
+            params![&start],
+            |row| row.get_block_number(0),
+        )
+        .optional()
+        .context("Querying window start")?;
+
+    Ok(before_window.map_or(BlockNumber::GENESIS, |number| number + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+    use pathfinder_common::BlockHeader;
+
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn statistics() {
+        let mut connection = Storage::in_memory().unwrap().connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        assert_eq!(chain_statistics(&tx).unwrap(), None);
+
+        let contract0 = contract_address_bytes!(b"contract 0");
+        let contract1 = contract_address_bytes!(b"contract 1");
+        let class0 = class_hash_bytes!(b"class 0");
+        let class1 = class_hash_bytes!(b"class 1");
+        tx.insert_cairo_class(class0, b"class 0 definition")
+            .unwrap();
+        tx.insert_cairo_class(class1, b"class 1 definition")
+            .unwrap();
+
+        // The genesis block falls outside the window of the latest block.
+        let genesis = BlockHeader::builder()
+            .with_timestamp(BlockTimestamp::new_or_panic(1_000))
+            .with_gas_price(GasPrice(100))
+            .with_transaction_count(5)
+            .finalize_with_hash(block_hash_bytes!(b"genesis"));
+        let block1 = genesis
+            .child_builder()
+            .with_timestamp(BlockTimestamp::new_or_panic(1_000 + STATISTICS_WINDOW))
+            .with_gas_price(GasPrice(10))
+            .with_transaction_count(2)
+            .finalize_with_hash(block_hash_bytes!(b"block 1"));
+        let block2 = block1
+            .child_builder()
+            .with_timestamp(BlockTimestamp::new_or_panic(2_000 + STATISTICS_WINDOW))
+            .with_gas_price(GasPrice(21))
+            .with_transaction_count(3)
+            .finalize_with_hash(block_hash_bytes!(b"block 2"));
+
+        tx.insert_block_header(&genesis).unwrap();
+        tx.insert_state_update(
+            genesis.number,
+            &StateUpdate::default()
+                .with_declared_cairo_class(class0)
+                .with_contract_nonce(contract0, contract_nonce!("0x1")),
+        )
+        .unwrap();
+        tx.insert_block_header(&block1).unwrap();
+        tx.insert_state_update(
+            block1.number,
+            &StateUpdate::default()
+                .with_declared_cairo_class(class1)
+                .with_contract_nonce(contract1, contract_nonce!("0x1")),
+        )
+        .unwrap();
+        tx.insert_block_header(&block2).unwrap();
+        tx.insert_state_update(
+            block2.number,
+            &StateUpdate::default().with_storage_update(
+                contract1,
+                storage_address!("0x1"),
+                storage_value!("0x1"),
+            ),
+        )
+        .unwrap();
+
+        let expected = ChainStatistics {
+            block_number: block2.number,
+            window_start: block1.number,
+            transaction_count: 5,
+            active_contract_count: 1,
+            average_gas_price: GasPrice(15),
+            declared_class_count: 2,
+        };
+        assert_eq!(chain_statistics(&tx).unwrap(), Some(expected));
+
+        // Purging a block removes its statistics. The genesis block's active contracts were
+        // pruned once it left the window.
+        tx.purge_block(block2.number).unwrap();
+        let expected = ChainStatistics {
+            block_number: block1.number,
+            window_start: block1.number,
+            transaction_count: 2,
+            active_contract_count: 1,
+            average_gas_price: GasPrice(10),
+            declared_class_count: 2,
+        };
+        assert_eq!(chain_statistics(&tx).unwrap(), Some(expected));
+    }
+}
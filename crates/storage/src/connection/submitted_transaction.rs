@@ -0,0 +1,188 @@
+use anyhow::Context;
+use pathfinder_common::TransactionHash;
+
+use crate::prelude::*;
+
+/// A transaction which was submitted via this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubmittedTransaction {
+    /// Unix timestamp in seconds at which the transaction was submitted.
+    pub submitted_at: u64,
+    /// Unix timestamp in seconds at which the transaction was first seen in a (pending) block.
+    pub included_at: Option<u64>,
+}
+
+pub(super) fn insert_submitted_transaction(
+    tx: &Transaction<'_>,
+    hash: TransactionHash,
+    submitted_at: u64,
+) -> anyhow::Result<()> {
+    // Re-submissions keep the original submission time.
+    tx.inner()
+        .execute(
+            "INSERT OR IGNORE INTO submitted_transactions (hash, submitted_at) VALUES (?, ?)",
+            params![&hash, &submitted_at],
+        )
+        .context("Inserting submitted transaction")?;
+
+    Ok(())
+}
+
+pub(super) fn submitted_transaction(
+    tx: &Transaction<'_>,
+    hash: TransactionHash,
+) -> anyhow::Result<Option<SubmittedTransaction>> {
+    tx.inner()
+        .query_row(
+            "SELECT submitted_at, included_at FROM submitted_transactions WHERE hash = ?",
+            params![&hash],
+            |row| {
+                Ok(SubmittedTransaction {
+                    submitted_at: row.get(0)?,
+                    included_at: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+        .context("Querying submitted transaction")
+}
+
+/// Whether any transaction submitted via this node is not yet included. This avoids checking
+/// each transaction in the common case of nothing awaiting inclusion.
+fn any_awaiting_inclusion(tx: &Transaction<'_>) -> anyhow::Result<bool> {
+    tx.inner()
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM submitted_transactions WHERE included_at IS NULL)",
+            [],
+            |row| row.get::<_, bool>(0),
+        )
+        .context("Querying awaiting submitted transactions")
+}
+
+/// Returns those of the given transactions which were submitted via this node and are not yet
+/// included.
+pub(super) fn submitted_transactions_awaiting_inclusion(
+    tx: &Transaction<'_>,
+    hashes: &[TransactionHash],
+) -> anyhow::Result<Vec<TransactionHash>> {
+    if !any_awaiting_inclusion(tx)? {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            "SELECT EXISTS(SELECT 1 FROM submitted_transactions WHERE hash = ? AND included_at IS NULL)",
+        )
+        .context("Preparing statement")?;
+
+    let mut awaiting = Vec::new();
+    for hash in hashes {
+        let is_awaiting = stmt
+            .query_row(params![hash], |row| row.get::<_, bool>(0))
+            .context("Querying submitted transaction")?;
+        if is_awaiting {
+            awaiting.push(*hash);
+        }
+    }
+
+    Ok(awaiting)
+}
+
+/// Marks those of the given transactions which were submitted via this node and not yet included
+/// as included, returning the newly marked transactions.
+pub(super) fn mark_submitted_transactions_included(
+    tx: &Transaction<'_>,
+    hashes: &[TransactionHash],
+    included_at: u64,
+) -> anyhow::Result<Vec<(TransactionHash, SubmittedTransaction)>> {
+    if !any_awaiting_inclusion(tx)? {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            "UPDATE submitted_transactions SET included_at = ? WHERE hash = ? AND included_at IS NULL
+            RETURNING submitted_at",
+        )
+        .context("Preparing statement")?;
+
+    let mut included = Vec::new();
+    for hash in hashes {
+        let submitted_at = stmt
+            .query_row(params![&included_at, hash], |row| row.get::<_, u64>(0))
+            .optional()
+            .context("Marking submitted transaction as included")?;
+
+        if let Some(submitted_at) = submitted_at {
+            included.push((
+                *hash,
+                SubmittedTransaction {
+                    submitted_at,
+                    included_at: Some(included_at),
+                },
+            ));
+        }
+    }
+
+    Ok(included)
+}
+
+#[cfg(test)]
+mod tests {
+    use pathfinder_common::macro_prelude::*;
+
+    use super::*;
+    use crate::Storage;
+
+    #[test]
+    fn lifecycle() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        let submitted = transaction_hash!("0x1");
+        let other = transaction_hash!("0x2");
+
+        tx.insert_submitted_transaction(submitted, 10).unwrap();
+        // Re-submission should not reset the submission time.
+        tx.insert_submitted_transaction(submitted, 20).unwrap();
+
+        let result = tx.submitted_transaction(submitted).unwrap();
+        assert_eq!(
+            result,
+            Some(SubmittedTransaction {
+                submitted_at: 10,
+                included_at: None
+            })
+        );
+        assert_eq!(tx.submitted_transaction(other).unwrap(), None);
+
+        let awaiting = tx
+            .submitted_transactions_awaiting_inclusion(&[other, submitted])
+            .unwrap();
+        assert_eq!(awaiting, vec![submitted]);
+
+        let included = tx
+            .mark_submitted_transactions_included(&[other, submitted], 30)
+            .unwrap();
+        let expected = SubmittedTransaction {
+            submitted_at: 10,
+            included_at: Some(30),
+        };
+        assert_eq!(included, vec![(submitted, expected)]);
+        assert_eq!(tx.submitted_transaction(submitted).unwrap(), Some(expected));
+
+        // Already included transactions are not marked again.
+        let included = tx
+            .mark_submitted_transactions_included(&[submitted], 40)
+            .unwrap();
+        assert!(included.is_empty());
+        assert_eq!(tx.submitted_transaction(submitted).unwrap(), Some(expected));
+        let awaiting = tx
+            .submitted_transactions_awaiting_inclusion(&[submitted])
+            .unwrap();
+        assert!(awaiting.is_empty());
+    }
+}
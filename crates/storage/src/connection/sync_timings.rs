@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use pathfinder_common::BlockNumber;
+
+use crate::prelude::*;
+
+/// The time spent per stage on syncing a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSyncTimings {
+    pub block_number: BlockNumber,
+    /// Downloading the block, its state diff, declared classes and signature.
+    pub download: Duration,
+    /// Verifying the block and transaction hashes.
+    pub verification: Duration,
+    pub trie_update: Duration,
+    /// Writing and committing the block to the database.
+    pub db_write: Duration,
+}
+
+/// Records the timings of a block, keeping only those of the most recent `capacity` blocks.
+///
+/// The timings of a block which is synced again after a reorg replace its previous timings.
+pub(super) fn insert_block_sync_timings(
+    tx: &Transaction<'_>,
+    timings: &BlockSyncTimings,
+    capacity: u64,
+) -> anyhow::Result<()> {
+    fn millis(duration: Duration) -> u64 {
+        duration.as_millis().try_into().unwrap_or(u64::MAX)
+    }
+
+    tx.inner()
+        .execute(
+            r"INSERT OR REPLACE INTO block_sync_timings
+            (block_number, download_ms, verification_ms, trie_update_ms, db_write_ms)
+            VALUES (?, ?, ?, ?, ?)",
+            params![
+                &timings.block_number,
+                &millis(timings.download),
+                &millis(timings.verification),
+                &millis(timings.trie_update),
+                &millis(timings.db_write),
+            ],
+        )
+        .context("Inserting block sync timings")?;
+
+    // Timings of blocks after the current one were left behind by a reorg.
+    let oldest = timings
+        .block_number
+        .get()
+        .saturating_sub(capacity.saturating_sub(1));
+    tx.inner()
+        .execute(
+            "DELETE FROM block_sync_timings WHERE block_number < ? OR block_number > ?",
+            params![&oldest, &timings.block_number],
+        )
+        .context("Deleting old block sync timings")?;
+
+    Ok(())
+}
+
+/// Returns the timings of the most recent `limit` blocks, latest first.
+pub(super) fn block_sync_timings(
+    tx: &Transaction<'_>,
+    limit: u64,
+) -> anyhow::Result<Vec<BlockSyncTimings>> {
+    let mut stmt = tx
+        .inner()
+        .prepare_cached(
+            r"SELECT block_number, download_ms, verification_ms, trie_update_ms, db_write_ms
+            FROM block_sync_timings ORDER BY block_number DESC LIMIT ?",
+        )
+        .context("Preparing block sync timings query")?;
+
+    let timings = stmt
+        .query_map(params![&limit], |row| {
+            Ok(BlockSyncTimings {
+                block_number: row.get_block_number(0)?,
+                download: Duration::from_millis(row.get(1)?),
+                verification: Duration::from_millis(row.get(2)?),
+                trie_update: Duration::from_millis(row.get(3)?),
+                db_write: Duration::from_millis(row.get(4)?),
+            })
+        })
+        .context("Querying block sync timings")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Iterating over block sync timings")?;
+
+    Ok(timings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Storage;
+
+    fn timings(block: u64) -> BlockSyncTimings {
+        BlockSyncTimings {
+            block_number: BlockNumber::new_or_panic(block),
+            download: Duration::from_millis(block),
+            verification: Duration::from_millis(2),
+            trie_update: Duration::from_millis(3),
+            db_write: Duration::from_millis(4),
+        }
+    }
+
+    fn blocks(tx: &Transaction<'_>) -> Vec<u64> {
+        tx.block_sync_timings(10)
+            .unwrap()
+            .into_iter()
+            .map(|timings| timings.block_number.get())
+            .collect()
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let storage = Storage::in_memory().unwrap();
+        let mut connection = storage.connection().unwrap();
+        let tx = connection.transaction().unwrap();
+
+        for block in 0..5 {
+            tx.insert_block_sync_timings(&timings(block), 3).unwrap();
+        }
+        assert_eq!(blocks(&tx), vec![4, 3, 2]);
+        assert_eq!(tx.block_sync_timings(1).unwrap(), vec![timings(4)]);
+
+        // A reorg back to block 3 drops the timings of the reorged blocks.
+        tx.insert_block_sync_timings(&timings(3), 3).unwrap();
+        assert_eq!(blocks(&tx), vec![3, 2]);
+    }
+}
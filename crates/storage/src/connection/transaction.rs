@@ -53,6 +53,18 @@ pub(super) fn insert_transactions(
         // insert events from receipt
         super::event::insert_events(tx, block_number, receipt.transaction_hash, &receipt.events)
             .context("Inserting events")?;
+
+        super::message::insert_messages_to_l1(
+            tx,
+            block_number,
+            i,
+            receipt.transaction_hash,
+            &receipt.l2_to_l1_messages,
+        )
+        .context("Inserting messages to L1")?;
+
+        super::sender::insert_transaction_sender(tx, block_number, i, transaction)
+            .context("Inserting transaction sender")?;
     }
 
     Ok(())
@@ -296,10 +308,14 @@ pub(super) fn transaction_block_hash(
 #[cfg(test)]
 mod tests {
     use pathfinder_common::macro_prelude::*;
-    use pathfinder_common::{BlockHeader, TransactionIndex, TransactionVersion};
+    use pathfinder_common::{
+        BlockHeader, ResourceAmount, ResourcePricePerUnit, Tip, TransactionIndex,
+        TransactionVersion,
+    };
     use starknet_gateway_types::reply::transaction::{
-        DeclareTransactionV0V1, DeclareTransactionV2, DeployAccountTransaction, DeployTransaction,
-        InvokeTransactionV0, InvokeTransactionV1,
+        DeclareTransactionV0V1, DeclareTransactionV2, DeployAccountTransaction,
+        DeployAccountTransactionV0V1, DeployTransaction, InvokeTransactionV0, InvokeTransactionV1,
+        InvokeTransactionV3, ResourceBound, ResourceBounds,
     };
 
     use super::*;
@@ -364,23 +380,27 @@ mod tests {
                 transaction_hash: transaction_hash_bytes!(b"deploy tx hash"),
                 version: TransactionVersion::ZERO,
             }),
-            gateway::Transaction::DeployAccount(DeployAccountTransaction {
-                contract_address: contract_address_bytes!(b"deploy account contract address"),
-                transaction_hash: transaction_hash_bytes!(b"deploy account tx hash"),
-                max_fee: fee_bytes!(b"deploy account max fee"),
-                version: TransactionVersion::ZERO,
-                signature: vec![
-                    transaction_signature_elem_bytes!(b"deploy account tx sig 0"),
-                    transaction_signature_elem_bytes!(b"deploy account tx sig 1"),
-                ],
-                nonce: transaction_nonce_bytes!(b"deploy account tx nonce"),
-                contract_address_salt: contract_address_salt_bytes!(b"deploy account address salt"),
-                constructor_calldata: vec![
-                    call_param_bytes!(b"deploy account call data 0"),
-                    call_param_bytes!(b"deploy account call data 1"),
-                ],
-                class_hash: class_hash_bytes!(b"deploy account class hash"),
-            }),
+            gateway::Transaction::DeployAccount(DeployAccountTransaction::V0V1(
+                DeployAccountTransactionV0V1 {
+                    contract_address: contract_address_bytes!(b"deploy account contract address"),
+                    transaction_hash: transaction_hash_bytes!(b"deploy account tx hash"),
+                    max_fee: fee_bytes!(b"deploy account max fee"),
+                    version: TransactionVersion::ZERO,
+                    signature: vec![
+                        transaction_signature_elem_bytes!(b"deploy account tx sig 0"),
+                        transaction_signature_elem_bytes!(b"deploy account tx sig 1"),
+                    ],
+                    nonce: transaction_nonce_bytes!(b"deploy account tx nonce"),
+                    contract_address_salt: contract_address_salt_bytes!(
+                        b"deploy account address salt"
+                    ),
+                    constructor_calldata: vec![
+                        call_param_bytes!(b"deploy account call data 0"),
+                        call_param_bytes!(b"deploy account call data 1"),
+                    ],
+                    class_hash: class_hash_bytes!(b"deploy account class hash"),
+                },
+            )),
             gateway::Transaction::Invoke(gateway::InvokeTransaction::V0(InvokeTransactionV0 {
                 calldata: vec![
                     call_param_bytes!(b"invoke v0 call data 0"),
@@ -410,6 +430,27 @@ mod tests {
                 nonce: transaction_nonce_bytes!(b"invoke v1 tx nonce"),
                 transaction_hash: transaction_hash_bytes!(b"invoke v1 tx hash"),
             })),
+            gateway::Transaction::Invoke(gateway::InvokeTransaction::V3(InvokeTransactionV3 {
+                nonce: transaction_nonce_bytes!(b"invoke v3 tx nonce"),
+                nonce_data_availability_mode: gateway::DataAvailabilityMode::L1,
+                fee_data_availability_mode: gateway::DataAvailabilityMode::L2,
+                resource_bounds: ResourceBounds {
+                    l1_gas: ResourceBound {
+                        max_amount: ResourceAmount(10),
+                        max_price_per_unit: ResourcePricePerUnit(100),
+                    },
+                    l2_gas: Default::default(),
+                },
+                tip: Tip(5),
+                paymaster_data: vec![paymaster_data_elem_bytes!(b"invoke v3 paymaster data")],
+                sender_address: contract_address_bytes!(b"invoke v3 contract address"),
+                signature: vec![transaction_signature_elem_bytes!(b"invoke v3 tx sig 0")],
+                transaction_hash: transaction_hash_bytes!(b"invoke v3 tx hash"),
+                calldata: vec![call_param_bytes!(b"invoke v3 call data 0")],
+                account_deployment_data: vec![account_deployment_data_elem_bytes!(
+                    b"invoke v3 account deployment data"
+                )],
+            })),
             gateway::Transaction::L1Handler(gateway::L1HandlerTransaction {
                 contract_address: contract_address_bytes!(b"L1 handler contract address"),
                 entry_point_selector: entry_point_bytes!(b"L1 handler entry point"),
@@ -8,9 +8,9 @@ use pathfinder_crypto::Felt;
 
 use crate::prelude::*;
 
-macros::create_trie_fns!(trie_class);
-macros::create_trie_fns!(trie_contracts);
-macros::create_trie_fns!(trie_storage);
+macros::create_trie_fns!(trie_class, Class);
+macros::create_trie_fns!(trie_contracts, Contracts);
+macros::create_trie_fns!(trie_storage, Storage);
 
 pub(super) fn class_root_index(
     tx: &Transaction<'_>,
@@ -141,12 +141,14 @@ pub(super) fn insert_contract_root(
 
 mod macros {
     /// Generates the `insert`, `node` and `hash` trie functions for the given table name, within
-    /// a module with the table name.
+    /// a module with the table name. Its nodes are cached as the given [Trie](crate::trie_cache::Trie).
     macro_rules! create_trie_fns {
-        ($table: ident) => {
+        ($table: ident, $trie: ident) => {
             pub(super) mod $table {
                 use super::*;
 
+                const TRIE: crate::trie_cache::Trie = crate::trie_cache::Trie::$trie;
+
                 /// Stores the node data for this trie and returns the index of the root.
                 pub fn insert(
                     tx: &Transaction<'_>,
@@ -213,6 +215,7 @@ mod macros {
                             )
                             .context("Inserting node")?;
 
+                        tx.trie_cache().inserted(TRIE, idx);
                         indices.insert(hash, idx);
                     }
 
@@ -226,45 +229,48 @@ mod macros {
                     tx: &Transaction<'_>,
                     index: u64,
                 ) -> anyhow::Result<Option<StoredNode>> {
+                    Ok(hash_and_node(tx, index)?.map(|(_, node)| node))
+                }
+
+                /// Returns the hash of the node with the given index.
+                pub fn hash(tx: &Transaction<'_>, index: u64) -> anyhow::Result<Option<Felt>> {
+                    Ok(hash_and_node(tx, index)?.map(|(hash, _)| hash))
+                }
+
+                /// Reads the node from the [trie cache](crate::trie_cache::TrieNodeCache),
+                /// falling back to the database.
+                fn hash_and_node(
+                    tx: &Transaction<'_>,
+                    index: u64,
+                ) -> anyhow::Result<Option<(Felt, StoredNode)>> {
+                    if let Some(cached) = tx.trie_cache().get(TRIE, index) {
+                        return Ok(Some(cached));
+                    }
+
                     // We rely on sqlite caching the statement here. Storing the statement would be nice,
                     // however that leads to &mut requirements or interior mutable work-arounds.
                     let mut stmt = tx
                         .inner()
                         .prepare_cached(concat!(
-                            "SELECT data FROM ",
+                            "SELECT hash, data FROM ",
                             stringify!($table),
                             " WHERE idx = ?",
                         ))
                         .context("Creating get statement")?;
 
-                    let Some(data): Option<Vec<u8>> = stmt
-                        .query_row(params![&index], |row| row.get(0))
+                    let Some((hash, data)) = stmt
+                        .query_row(params![&index], |row| {
+                            Ok((row.get_felt(0)?, row.get::<_, Vec<u8>>(1)?))
+                        })
                         .optional()?
                     else {
                         return Ok(None);
                     };
 
                     let node = StoredNode::decode(&data).context("Decoding node")?;
+                    tx.trie_cache().read(TRIE, index, hash, node.clone());
 
-                    Ok(Some(node))
-                }
-
-                /// Returns the hash of the node with the given index.
-                pub fn hash(tx: &Transaction<'_>, index: u64) -> anyhow::Result<Option<Felt>> {
-                    // We rely on sqlite caching the statement here. Storing the statement would be nice,
-                    // however that leads to &mut requirements or interior mutable work-arounds.
-                    let mut stmt = tx
-                        .inner()
-                        .prepare_cached(concat!(
-                            "SELECT hash FROM ",
-                            stringify!($table),
-                            " WHERE idx = ?",
-                        ))
-                        .context("Creating get statement")?;
-
-                    stmt.query_row(params![&index], |row| row.get_felt(0))
-                        .optional()
-                        .map_err(Into::into)
+                    Ok(Some((hash, node)))
                 }
             }
         };
@@ -640,7 +646,7 @@ mod tests {
 
     mod trie_fns {
         use super::*;
-        macros::create_trie_fns!(test_table);
+        macros::create_trie_fns!(test_table, Class);
 
         fn setup_db() -> rusqlite::Connection {
             let db = rusqlite::Connection::open_in_memory().unwrap();
@@ -829,6 +835,86 @@ mod tests {
             assert_eq!(path, bitvec::bitvec![u8, Msb0; 1,0,1,1,1,0,0,0,0,0,1,1]);
             assert_eq!(child, 123);
         }
+
+        #[test]
+        fn cached_across_connections() {
+            let storage = crate::Storage::in_memory().unwrap();
+            let root_hash = felt_bytes!(b"root");
+            let nodes = HashMap::from([(
+                root_hash,
+                Node::LeafEdge {
+                    path: bitvec::bitvec![u8, Msb0; 1,0,1],
+                },
+            )]);
+
+            // Nodes inserted by a transaction are not cached, since their index may be reused
+            // if the transaction is rolled back.
+            let mut db = storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            let root_idx = tx
+                .insert_class_trie(ClassCommitment(root_hash), &nodes)
+                .unwrap();
+            assert_eq!(tx.class_trie_node_hash(root_idx).unwrap(), Some(root_hash));
+            assert_eq!(storage.0.trie_cache.len(), 0);
+            tx.commit().unwrap();
+
+            let mut db = storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            assert_eq!(tx.class_trie_node_hash(root_idx).unwrap(), Some(root_hash));
+            assert_eq!(storage.0.trie_cache.len(), 1);
+
+            // Subsequent reads are served from the cache, including by other connections.
+            tx.inner().execute("DELETE FROM trie_class", []).unwrap();
+            tx.commit().unwrap();
+
+            let mut db = storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            let node = tx.class_trie_node(root_idx).unwrap().unwrap();
+            assert_eq!(
+                node.into_edge_leaf(),
+                Some(bitvec::bitvec![u8, Msb0; 1,0,1])
+            );
+        }
+
+        #[test]
+        fn cached_per_trie() {
+            let storage = crate::Storage::in_memory().unwrap();
+            let leaf = || Node::LeafEdge {
+                path: bitvec::bitvec![u8, Msb0; 1,0,1],
+            };
+            let class_root = felt_bytes!(b"class root");
+            let contract_root = felt_bytes!(b"contract root");
+
+            let mut db = storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            let class_idx = tx
+                .insert_class_trie(
+                    ClassCommitment(class_root),
+                    &HashMap::from([(class_root, leaf())]),
+                )
+                .unwrap();
+            let contract_idx = tx
+                .insert_contract_trie(
+                    ContractRoot(contract_root),
+                    &HashMap::from([(contract_root, leaf())]),
+                )
+                .unwrap();
+            assert_eq!(class_idx, contract_idx);
+            tx.commit().unwrap();
+
+            // The node of one trie must not be served for the same index of another.
+            let mut db = storage.connection().unwrap();
+            let tx = db.transaction().unwrap();
+            assert_eq!(
+                tx.class_trie_node_hash(class_idx).unwrap(),
+                Some(class_root)
+            );
+            assert_eq!(
+                tx.contract_trie_node_hash(contract_idx).unwrap(),
+                Some(contract_root)
+            );
+            assert_eq!(storage.0.trie_cache.len(), 2);
+        }
     }
 
     #[test]
@@ -9,24 +9,38 @@ mod connection;
 pub mod fake;
 mod params;
 mod schema;
+mod slow_query;
 pub mod test_utils;
+mod trie_cache;
 
 use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use connection::*;
+pub use slow_query::SlowQueryStats;
 
 use pathfinder_common::{BlockHash, BlockNumber};
 use rusqlite::functions::FunctionFlags;
+use rusqlite::DatabaseName;
 
 use anyhow::Context;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
+use slow_query::SlowQueryLog;
+use trie_cache::TrieNodeCache;
 
 /// Sqlite key used for the PRAGMA user version.
 const VERSION_KEY: &str = "user_version";
 
+/// The schema name of the separate trie database, see [Storage::migrate_with_trie_database].
+const TRIE_SCHEMA: &str = "tries";
+/// The tables which are kept in the separate trie database.
+const TRIE_TABLES: [&str; 3] = ["trie_class", "trie_contracts", "trie_storage"];
+/// A table of the main database which marks that its tries were moved to a trie database.
+const TRIE_DATABASE_MARKER: &str = "split_tries";
+
 /// Specifies the [journal mode](https://sqlite.org/pragma.html#pragma_journal_mode)
 /// of the [Storage].
 #[derive(Clone, Copy)]
@@ -35,6 +49,80 @@ pub enum JournalMode {
     WAL,
 }
 
+/// Sqlite memory and IO settings, applied to every pooled connection.
+///
+/// See [cache_size](https://sqlite.org/pragma.html#pragma_cache_size),
+/// [mmap_size](https://sqlite.org/pragma.html#pragma_mmap_size) and
+/// [temp_store](https://sqlite.org/pragma.html#pragma_temp_store).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteTuning {
+    /// The page cache size of each connection, in KiB.
+    pub cache_size_kib: u64,
+    /// The maximum number of bytes of the database file to memory-map, 0 to disable.
+    pub mmap_size: u64,
+    pub temp_store: TempStore,
+}
+
+/// Where Sqlite keeps temporary tables and indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempStore {
+    /// Sqlite's compile time default, which is a file.
+    Default,
+    File,
+    Memory,
+}
+
+/// Predefined [SqliteTuning]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageProfile {
+    /// Minimizes memory use at the cost of more disk IO.
+    LowMemory,
+    /// Sqlite's own defaults.
+    Default,
+    /// Trades memory for less disk IO. Note that the cache is per connection.
+    Performance,
+}
+
+impl SqliteTuning {
+    pub fn for_profile(profile: StorageProfile) -> Self {
+        match profile {
+            StorageProfile::LowMemory => Self {
+                cache_size_kib: 512,
+                mmap_size: 0,
+                temp_store: TempStore::File,
+            },
+            StorageProfile::Default => Self {
+                cache_size_kib: 2000,
+                mmap_size: 0,
+                temp_store: TempStore::Default,
+            },
+            StorageProfile::Performance => Self {
+                cache_size_kib: 64 * 1024,
+                mmap_size: 1024 * 1024 * 1024,
+                temp_store: TempStore::Memory,
+            },
+        }
+    }
+
+    fn apply(&self, connection: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
+        // A negative cache size is interpreted as KiB instead of pages.
+        connection.pragma_update(None, "cache_size", format!("-{}", self.cache_size_kib))?;
+        connection.pragma_update(None, "mmap_size", self.mmap_size.to_string())?;
+        let temp_store = match self.temp_store {
+            TempStore::Default => "DEFAULT",
+            TempStore::File => "FILE",
+            TempStore::Memory => "MEMORY",
+        };
+        connection.pragma_update(None, "temp_store", temp_store)
+    }
+}
+
+impl Default for SqliteTuning {
+    fn default() -> Self {
+        Self::for_profile(StorageProfile::Default)
+    }
+}
+
 /// Identifies a specific starknet block stored in the database.
 ///
 /// Note that this excludes the `Pending` variant since we never store pending data
@@ -88,18 +176,59 @@ struct Inner {
     /// Uses [`Arc`] to allow _shallow_ [Storage] cloning
     database_path: Arc<PathBuf>,
     pool: Pool<SqliteConnectionManager>,
+    trie_cache: TrieNodeCache,
+    slow_query_log: Option<SlowQueryLog>,
+}
+
+/// Health information of an existing database, see [Storage::inspect].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseHealth {
+    /// The schema version of the database.
+    pub schema_version: usize,
+    /// The schema version which [Storage::migrate] migrates to.
+    pub latest_schema_version: usize,
+    /// Problems reported by Sqlite's `PRAGMA quick_check`, empty if there are none.
+    pub problems: Vec<String>,
 }
 
 pub struct StorageManager {
     database_path: PathBuf,
     journal_mode: JournalMode,
+    tuning: SqliteTuning,
+    trie_database_path: Option<PathBuf>,
+    /// Shared by all pools, so that trie nodes read by one component are cached for all others.
+    trie_cache: TrieNodeCache,
+    slow_query_log: Option<SlowQueryLog>,
 }
 
 impl StorageManager {
+    /// Sets the [SqliteTuning] of the pools created afterwards.
+    pub fn with_tuning(mut self, tuning: SqliteTuning) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    /// Logs the queries of the pools created afterwards which take longer than `threshold`, and
+    /// counts them per query in the `storage_slow_queries_total` metric.
+    ///
+    /// Their statistics are also collected for [Storage::take_slow_queries].
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_log = Some(SlowQueryLog::new(threshold));
+        self
+    }
+
     pub fn create_pool(&self, capacity: NonZeroU32) -> anyhow::Result<Storage> {
         let journal_mode = self.journal_mode;
-        let pool_manager = SqliteConnectionManager::file(&self.database_path)
-            .with_init(move |connection| setup_connection(connection, journal_mode));
+        let tuning = self.tuning;
+        let trie_database_path = self.trie_database_path.clone();
+        let pool_manager =
+            SqliteConnectionManager::file(&self.database_path).with_init(move |connection| {
+                setup_connection(connection, journal_mode)?;
+                if let Some(path) = &trie_database_path {
+                    attach_trie_database(connection, path, journal_mode)?;
+                }
+                tuning.apply(connection)
+            });
         let pool = Pool::builder()
             .max_size(capacity.get())
             .build(pool_manager)?;
@@ -107,8 +236,43 @@ impl StorageManager {
         Ok(Storage(Inner {
             database_path: Arc::new(self.database_path.clone()),
             pool,
+            trie_cache: self.trie_cache.clone(),
+            slow_query_log: self.slow_query_log.clone(),
         }))
     }
+
+    /// Rebuilds the database file, returning the number of bytes reclaimed.
+    ///
+    /// This also switches the database to incremental auto-vacuuming, which lets
+    /// [Connection::incremental_vacuum] reclaim space afterwards without a full rebuild.
+    ///
+    /// The database must not be in use by anyone else, and the rebuild requires up to twice the
+    /// size of the database in free disk space.
+    pub fn vacuum(&self) -> anyhow::Result<u64> {
+        let connection =
+            rusqlite::Connection::open(&self.database_path).context("Opening DB for vacuum")?;
+
+        let size = |connection: &rusqlite::Connection| -> anyhow::Result<u64> {
+            connection
+                .query_row(
+                    "SELECT page_count * page_size FROM pragma_page_count, pragma_page_size",
+                    [],
+                    |row| row.get::<_, u64>(0),
+                )
+                .context("Querying database size")
+        };
+
+        let before = size(&connection)?;
+        connection
+            .pragma_update(None, "auto_vacuum", "INCREMENTAL")
+            .context("Enabling incremental auto-vacuum")?;
+        connection
+            .execute_batch("VACUUM")
+            .context("Vacuuming database")?;
+        let after = size(&connection)?;
+
+        Ok(before.saturating_sub(after))
+    }
 }
 
 impl Storage {
@@ -122,12 +286,76 @@ impl Storage {
         database_path: PathBuf,
         journal_mode: JournalMode,
     ) -> anyhow::Result<StorageManager> {
+        Self::migrate_with_trie_database(database_path, None, journal_mode)
+    }
+
+    /// Like [Storage::migrate], but keeps the trie tables in the separate database file at
+    /// `trie_database_path` if one is given.
+    ///
+    /// The trie database is attached to every connection, so that the tries can be placed on a
+    /// different disk, backed up or removed independently. This requires [JournalMode::Rollback],
+    /// as commits spanning both databases are only atomic per database file in WAL mode.
+    ///
+    /// A database whose trie tables still contain data must be split using
+    /// [Storage::split_trie_database] first. A split database can't be opened without its trie
+    /// database.
+    pub fn migrate_with_trie_database(
+        database_path: PathBuf,
+        trie_database_path: Option<PathBuf>,
+        journal_mode: JournalMode,
+    ) -> anyhow::Result<StorageManager> {
+        anyhow::ensure!(
+            trie_database_path.is_none() || matches!(journal_mode, JournalMode::Rollback),
+            "A separate trie database requires the rollback journal mode, as commits spanning both databases are not atomic in WAL mode"
+        );
+
         let mut connection = rusqlite::Connection::open(&database_path)
             .context("Opening DB for setting journal mode")?;
-        setup_journal_mode(&mut connection, journal_mode).context("Setting journal mode")?;
+        if trie_database_path.is_none() {
+            anyhow::ensure!(
+                !is_split(&connection)?,
+                "The tries of this database were moved to a separate trie database, which must be opened along with it"
+            );
+        }
+        setup_journal_mode(&mut connection, DatabaseName::Main, journal_mode)
+            .context("Setting journal mode")?;
         setup_connection(&mut connection, journal_mode)
             .context("Setting up database connection")?;
+        if let Some(path) = &trie_database_path {
+            attach_trie_database(&connection, path, journal_mode)
+                .context("Attaching trie database")?;
+            setup_journal_mode(
+                &mut connection,
+                DatabaseName::Attached(TRIE_SCHEMA),
+                journal_mode,
+            )
+            .context("Setting trie database journal mode")?;
+        }
         migrate_database(&mut connection).context("Migrate database")?;
+        if trie_database_path.is_some() {
+            match trie_tables_location(&connection)? {
+                TrieTables::Split => {}
+                TrieTables::Main => {
+                    let mut empty = true;
+                    for table in TRIE_TABLES {
+                        empty &= !connection
+                            .query_row(&format!("SELECT EXISTS(SELECT 1 FROM main.{table})"), [], |row| {
+                                row.get::<_, bool>(0)
+                            })
+                            .context("Checking for trie data")?;
+                    }
+                    anyhow::ensure!(
+                        empty,
+                        "The trie data is still stored in the main database. Split it into the trie database first."
+                    );
+                    // Nothing to copy, so this is cheap.
+                    move_trie_tables(&mut connection).context("Moving empty trie tables")?;
+                }
+                TrieTables::Both => anyhow::bail!(
+                    "The trie tables exist in both databases, which indicates an interrupted split. Split the database again."
+                ),
+            }
+        }
         connection
             .close()
             .map_err(|(_connection, error)| error)
@@ -136,13 +364,102 @@ impl Storage {
         Ok(StorageManager {
             database_path,
             journal_mode,
+            tuning: Default::default(),
+            trie_database_path,
+            trie_cache: Default::default(),
+            slow_query_log: None,
+        })
+    }
+
+    /// Moves the trie tables of the migrated database at `database_path` into a separate
+    /// database file at `trie_database_path`, which is created if required.
+    ///
+    /// The database must not be in use. Afterwards it must be opened using
+    /// [Storage::migrate_with_trie_database]. The space previously used by the tries is only
+    /// returned to the file system by a [vacuum](StorageManager::vacuum).
+    ///
+    /// An interrupted split can be resumed by calling this again. The database is switched to
+    /// [JournalMode::Rollback], so that the move is atomic.
+    pub fn split_trie_database(
+        database_path: &Path,
+        trie_database_path: &Path,
+    ) -> anyhow::Result<()> {
+        let mut connection =
+            rusqlite::Connection::open(database_path).context("Opening DB for splitting")?;
+        setup_journal_mode(&mut connection, DatabaseName::Main, JournalMode::Rollback)
+            .context("Setting journal mode")?;
+        attach_trie_database(&connection, trie_database_path, JournalMode::Rollback)
+            .context("Attaching trie database")?;
+        setup_journal_mode(
+            &mut connection,
+            DatabaseName::Attached(TRIE_SCHEMA),
+            JournalMode::Rollback,
+        )
+        .context("Setting trie database journal mode")?;
+
+        match trie_tables_location(&connection)? {
+            TrieTables::Split => Ok(()),
+            TrieTables::Main | TrieTables::Both => {
+                move_trie_tables(&mut connection).context("Moving trie tables")
+            }
+        }
+    }
+
+    /// Whether the tries of the database at `database_path` were moved to a separate trie
+    /// database, see [Storage::split_trie_database].
+    pub fn is_trie_database_split(database_path: &Path) -> anyhow::Result<bool> {
+        let connection = rusqlite::Connection::open(database_path).context("Opening DB")?;
+        is_split(&connection)
+    }
+
+    /// Inspects an existing database without modifying it.
+    ///
+    /// The database is opened read-only, so this is safe to use while a node is running on it.
+    pub fn inspect(database_path: &Path) -> anyhow::Result<DatabaseHealth> {
+        let connection = rusqlite::Connection::open_with_flags(
+            database_path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .context("Opening DB for inspection")?;
+
+        let schema_version = schema_version(&connection)?;
+        let latest_schema_version = schema::BASE_SCHEMA_REVISION + schema::migrations().len();
+
+        let mut statement = connection
+            .prepare("PRAGMA quick_check")
+            .context("Preparing quick check")?;
+        let mut problems = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .context("Running quick check")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Reading quick check results")?;
+        // A single "ok" row is returned if no problems were found.
+        problems.retain(|x| x != "ok");
+
+        Ok(DatabaseHealth {
+            schema_version,
+            latest_schema_version,
+            problems,
         })
     }
 
     /// Returns a new Sqlite [Connection] to the database.
     pub fn connection(&self) -> anyhow::Result<Connection> {
         let conn = self.0.pool.get()?;
-        Ok(Connection::from_inner(conn))
+        Ok(Connection::from_inner(
+            conn,
+            self.0.trie_cache.clone(),
+            self.0.slow_query_log.clone(),
+        ))
+    }
+
+    /// Returns the statistics of the slow queries executed since the last call, by query, see
+    /// [StorageManager::with_slow_query_threshold].
+    pub fn take_slow_queries(&self) -> Vec<(&'static str, SlowQueryStats)> {
+        match &self.0.slow_query_log {
+            Some(log) => log.take(),
+            None => Vec::new(),
+        }
     }
 
     /// Convenience function for tests to create an in-memory database.
@@ -178,18 +495,38 @@ impl Storage {
     }
 }
 
+/// Whether the error was caused by the database being busy or locked by another connection for
+/// longer than the busy timeout.
+///
+/// Such errors are transient, and the failed operation can be retried.
+pub fn is_busy(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error {
+                    code: rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked,
+                    ..
+                },
+                _,
+            ))
+        )
+    })
+}
+
 fn setup_journal_mode(
     connection: &mut rusqlite::Connection,
+    schema: DatabaseName<'_>,
     journal_mode: JournalMode,
 ) -> Result<(), rusqlite::Error> {
     // set journal mode related pragmas
     match journal_mode {
-        JournalMode::Rollback => connection.pragma_update(None, "journal_mode", "DELETE"),
+        JournalMode::Rollback => connection.pragma_update(Some(schema), "journal_mode", "DELETE"),
         JournalMode::WAL => {
-            connection.pragma_update(None, "journal_mode", "WAL")?;
+            connection.pragma_update(Some(schema), "journal_mode", "WAL")?;
             // set journal size limit to 1 GB
             connection.pragma_update(
-                None,
+                Some(schema),
                 "journal_size_limit",
                 (1024usize * 1024 * 1024).to_string(),
             )
@@ -197,6 +534,98 @@ fn setup_journal_mode(
     }
 }
 
+/// Attaches the trie database as [TRIE_SCHEMA].
+///
+/// Queries do not need to qualify the trie tables, as Sqlite looks up unqualified tables in
+/// attached databases as well.
+fn attach_trie_database(
+    connection: &rusqlite::Connection,
+    path: &Path,
+    journal_mode: JournalMode,
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "ATTACH DATABASE ? AS tries",
+        [path.to_string_lossy().as_ref()],
+    )?;
+
+    let synchronous = match journal_mode {
+        JournalMode::Rollback => "full",
+        JournalMode::WAL => "normal",
+    };
+    connection.pragma_update(
+        Some(DatabaseName::Attached(TRIE_SCHEMA)),
+        "synchronous",
+        synchronous,
+    )
+}
+
+/// Where the trie tables are stored.
+enum TrieTables {
+    Main,
+    Split,
+    /// An interrupted split.
+    Both,
+}
+
+fn trie_tables_location(connection: &rusqlite::Connection) -> anyhow::Result<TrieTables> {
+    let has_tables = |schema: &str| -> anyhow::Result<bool> {
+        let count: usize = connection
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM {schema}.sqlite_master WHERE type = 'table' AND name IN ('{}')",
+                    TRIE_TABLES.join("', '")
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .with_context(|| format!("Querying trie tables of {schema}"))?;
+        Ok(count == TRIE_TABLES.len())
+    };
+
+    match (has_tables("main")?, has_tables(TRIE_SCHEMA)?) {
+        (true, false) => Ok(TrieTables::Main),
+        (false, true) => Ok(TrieTables::Split),
+        (true, true) => Ok(TrieTables::Both),
+        (false, false) => anyhow::bail!("The trie tables are missing"),
+    }
+}
+
+fn is_split(connection: &rusqlite::Connection) -> anyhow::Result<bool> {
+    connection
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM main.sqlite_master WHERE type = 'table' AND name = ?)",
+            [TRIE_DATABASE_MARKER],
+            |row| row.get(0),
+        )
+        .context("Querying trie database marker")
+}
+
+/// Moves the trie tables from the main database into the attached trie database, replacing
+/// any partial copies from an interrupted move, and marks the main database as split.
+fn move_trie_tables(connection: &mut rusqlite::Connection) -> anyhow::Result<()> {
+    let tx = connection.transaction().context("Creating transaction")?;
+
+    for table in TRIE_TABLES {
+        tx.execute_batch(&format!(
+            r"DROP TABLE IF EXISTS {TRIE_SCHEMA}.{table};
+CREATE TABLE {TRIE_SCHEMA}.{table} (
+    idx INTEGER PRIMARY KEY,
+    hash BLOB NOT NULL,
+    data BLOB
+);
+INSERT INTO {TRIE_SCHEMA}.{table} SELECT idx, hash, data FROM main.{table};
+DROP TABLE main.{table};"
+        ))
+        .with_context(|| format!("Moving {table}"))?;
+    }
+    tx.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS main.{TRIE_DATABASE_MARKER} (id INTEGER PRIMARY KEY)"
+    ))
+    .context("Marking the database as split")?;
+
+    tx.commit().context("Committing transaction")
+}
+
 fn setup_connection(
     connection: &mut rusqlite::Connection,
     journal_mode: JournalMode,
@@ -208,17 +637,17 @@ fn setup_connection(
     )?;
 
     connection.create_scalar_function(
-        "base64_felts_to_index_prefixed_base32_felts",
+        "felts_to_index_prefixed_base32_felts",
         1,
         FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
         move |ctx| {
             assert_eq!(ctx.len(), 1, "called with unexpected number of arguments");
-            let base64_felts = ctx
+            let felts = ctx
                 .get_raw(0)
-                .as_str()
+                .as_blob()
                 .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))?;
 
-            Ok(base64_felts_to_index_prefixed_base32_felts(base64_felts))
+            Ok(felts_to_index_prefixed_base32_felts(felts))
         },
     )?;
 
@@ -236,9 +665,19 @@ fn setup_connection(
     Ok(())
 }
 
-fn base64_felts_to_index_prefixed_base32_felts(base64_felts: &str) -> String {
-    let strings = base64_felts
-        .split(' ')
+/// Converts event keys stored as consecutive 32-byte felts into the tokens of the event key
+/// index.
+fn felts_to_index_prefixed_base32_felts(felts: &[u8]) -> String {
+    // Events without keys are indexed as having a single zero key. This matches the index entries
+    // created while keys were stored as base64 strings, which the delete trigger must reproduce.
+    let felts = if felts.is_empty() {
+        &[0u8; 32][..]
+    } else {
+        felts
+    };
+
+    let strings = felts
+        .chunks(32)
         // Convert only the first 256 elements so that the index fits into one u8
         // we will use as a prefix byte.
         .take(connection::EVENT_KEY_FILTER_LIMIT)
@@ -246,7 +685,7 @@ fn base64_felts_to_index_prefixed_base32_felts(base64_felts: &str) -> String {
         .map(|(index, key)| {
             let mut buf: [u8; 33] = [0u8; 33];
             buf[0] = index as u8;
-            base64::decode_config_slice(key, base64::STANDARD, &mut buf[1..]).unwrap();
+            buf[33 - key.len()..].copy_from_slice(key);
             data_encoding::BASE32_NOPAD.encode(&buf)
         })
         .collect::<Vec<_>>();
@@ -359,6 +798,23 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn busy_errors_are_detected() {
+        let busy = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        );
+        let busy = anyhow::Error::new(busy).context("Querying block");
+        assert!(is_busy(&busy));
+
+        let constraint = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+            None,
+        );
+        assert!(!is_busy(&anyhow::Error::new(constraint)));
+        assert!(!is_busy(&anyhow::anyhow!("Not a database error")));
+    }
+
     #[test]
     fn schema_version_defaults_to_zero() {
         let mut conn = rusqlite::Connection::open_in_memory().unwrap();
@@ -368,6 +824,138 @@ mod tests {
         assert_eq!(version, 0);
     }
 
+    #[test]
+    fn vacuum_enables_incremental_vacuum() {
+        let directory = tempfile::tempdir().unwrap();
+        let database_path = directory.path().join("test.sqlite");
+        let manager = Storage::migrate(database_path.clone(), JournalMode::Rollback).unwrap();
+
+        // Leave some free pages behind.
+        rusqlite::Connection::open(&database_path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE filler(data BLOB);
+                INSERT INTO filler VALUES(zeroblob(1000000));
+                DROP TABLE filler;",
+            )
+            .unwrap();
+
+        let storage = manager.create_pool(NonZeroU32::new(1).unwrap()).unwrap();
+        assert!(!storage
+            .connection()
+            .unwrap()
+            .incremental_vacuum_enabled()
+            .unwrap());
+
+        assert!(manager.vacuum().unwrap() > 0);
+
+        let conn = storage.connection().unwrap();
+        assert!(conn.incremental_vacuum_enabled().unwrap());
+        assert_eq!(conn.incremental_vacuum(100).unwrap(), 0);
+    }
+
+    #[test]
+    fn tuning_is_applied() {
+        let directory = tempfile::tempdir().unwrap();
+        let conn = rusqlite::Connection::open(directory.path().join("test.sqlite")).unwrap();
+        let tuning = SqliteTuning::for_profile(StorageProfile::Performance);
+        tuning.apply(&conn).unwrap();
+
+        let pragma = |name: &str| -> i64 {
+            conn.query_row(&format!("PRAGMA {name}"), [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(pragma("cache_size"), -(tuning.cache_size_kib as i64));
+        assert_eq!(pragma("mmap_size"), tuning.mmap_size as i64);
+        // 2 is MEMORY.
+        assert_eq!(pragma("temp_store"), 2);
+    }
+
+    #[test]
+    fn split_trie_database() {
+        let directory = tempfile::tempdir().unwrap();
+        let database_path = directory.path().join("main.sqlite");
+        let trie_database_path = directory.path().join("tries.sqlite");
+
+        let storage = Storage::migrate(database_path.clone(), JournalMode::WAL)
+            .unwrap()
+            .create_pool(NonZeroU32::new(1).unwrap())
+            .unwrap();
+        let root = felt!("0x1");
+        let nodes = std::collections::HashMap::from([(root, Node::LeafBinary)]);
+        let mut conn = storage.connection().unwrap();
+        let tx = conn.transaction().unwrap();
+        let index = tx
+            .insert_storage_trie(pathfinder_common::StorageCommitment(root), &nodes)
+            .unwrap();
+        tx.commit().unwrap();
+        drop(conn);
+        drop(storage);
+
+        // Existing trie data must be split off explicitly.
+        Storage::migrate_with_trie_database(
+            database_path.clone(),
+            Some(trie_database_path.clone()),
+            JournalMode::Rollback,
+        )
+        .unwrap_err();
+
+        assert!(!Storage::is_trie_database_split(&database_path).unwrap());
+        Storage::split_trie_database(&database_path, &trie_database_path).unwrap();
+        assert!(Storage::is_trie_database_split(&database_path).unwrap());
+
+        // The split database can't be opened without its tries.
+        Storage::migrate(database_path.clone(), JournalMode::Rollback).unwrap_err();
+
+        let storage = Storage::migrate_with_trie_database(
+            database_path.clone(),
+            Some(trie_database_path),
+            JournalMode::Rollback,
+        )
+        .unwrap()
+        .create_pool(NonZeroU32::new(1).unwrap())
+        .unwrap();
+        let mut conn = storage.connection().unwrap();
+        let tx = conn.transaction().unwrap();
+        assert_eq!(tx.storage_trie_node_hash(index).unwrap(), Some(root));
+
+        let main = rusqlite::Connection::open(&database_path).unwrap();
+        let trie_tables: usize = main
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name LIKE 'trie_%'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(trie_tables, 0);
+    }
+
+    #[test]
+    fn new_database_with_trie_database() {
+        let directory = tempfile::tempdir().unwrap();
+
+        for _ in 0..2 {
+            Storage::migrate_with_trie_database(
+                directory.path().join("main.sqlite"),
+                Some(directory.path().join("tries.sqlite")),
+                JournalMode::Rollback,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn trie_database_requires_rollback_journal() {
+        let directory = tempfile::tempdir().unwrap();
+
+        Storage::migrate_with_trie_database(
+            directory.path().join("main.sqlite"),
+            Some(directory.path().join("tries.sqlite")),
+            JournalMode::WAL,
+        )
+        .unwrap_err();
+    }
+
     #[test]
     fn full_migration() {
         let mut conn = rusqlite::Connection::open_in_memory().unwrap();
@@ -392,6 +980,25 @@ mod tests {
         migrate_database(&mut conn).unwrap_err();
     }
 
+    #[test]
+    fn inspect() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_path = dir.path().join("inspect.sqlite");
+
+        Storage::migrate(database_path.clone(), JournalMode::Rollback).unwrap();
+
+        let health = Storage::inspect(&database_path).unwrap();
+        let expected = schema::migrations().len() + schema::BASE_SCHEMA_REVISION;
+        assert_eq!(
+            health,
+            DatabaseHealth {
+                schema_version: expected,
+                latest_schema_version: expected,
+                problems: vec![],
+            }
+        );
+    }
+
     #[test]
     fn foreign_keys_are_enforced() {
         let conn = rusqlite::Connection::open_in_memory().unwrap();
@@ -433,29 +1040,35 @@ mod tests {
 
     #[test]
     fn felts_to_index_prefixed_base32_strings() {
-        let input: String = [felt!("0x901823"), felt!("0x901823"), felt!("0x901825")]
+        let input: Vec<u8> = [felt!("0x901823"), felt!("0x901823"), felt!("0x901825")]
             .iter()
-            .map(|f| base64::encode(f.as_be_bytes()))
-            .collect::<Vec<_>>()
-            .join(" ");
+            .flat_map(|f| f.to_be_bytes())
+            .collect();
         assert_eq!(
-            super::base64_felts_to_index_prefixed_base32_felts(&input),
+            super::felts_to_index_prefixed_base32_felts(&input),
             "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAASAMCG AEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAASAMCG AIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAASAMCK".to_owned()
         );
     }
 
     #[test]
     fn felts_to_index_prefixed_base32_strings_encodes_the_first_256_felts() {
-        let input = [Felt::ZERO; 257]
+        let input: Vec<u8> = [Felt::ZERO; 257]
             .iter()
-            .map(|f| base64::encode(f.as_be_bytes()))
-            .collect::<Vec<_>>()
-            .join(" ");
-        let output = super::base64_felts_to_index_prefixed_base32_felts(&input);
+            .flat_map(|f| f.to_be_bytes())
+            .collect();
+        let output = super::felts_to_index_prefixed_base32_felts(&input);
 
         assert_eq!(output.split(' ').count(), 256);
     }
 
+    #[test]
+    fn felts_to_index_prefixed_base32_strings_indexes_no_keys_as_zero() {
+        assert_eq!(
+            super::felts_to_index_prefixed_base32_felts(&[]),
+            super::felts_to_index_prefixed_base32_felts(Felt::ZERO.as_be_bytes())
+        );
+    }
+
     #[test]
     fn rpc_test_db_is_migrated() {
         let mut source_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -8,7 +8,7 @@ use pathfinder_common::{
     SierraHash, StarknetVersion, StateCommitment, StorageAddress, StorageCommitment, StorageValue,
     TransactionCommitment, TransactionHash, TransactionNonce, TransactionSignatureElem,
 };
-use pathfinder_crypto::Felt;
+use pathfinder_crypto::{Felt, OverflowError};
 use rusqlite::types::{FromSqlError, ToSqlOutput};
 use rusqlite::RowIndex;
 
@@ -120,6 +120,17 @@ pub trait RowExt {
         Ok(felt)
     }
 
+    /// Returns a column of consecutive 32-byte felts, borrowed from the row.
+    fn get_felt_blob<Index: RowIndex>(&self, index: Index) -> rusqlite::Result<FeltBlob<'_>> {
+        let blob = self.get_blob(index)?;
+        FeltBlob::new(blob).ok_or_else(|| {
+            rusqlite::types::FromSqlError::Other(
+                format!("Felt blob length {} is not a multiple of 32", blob.len()).into(),
+            )
+            .into()
+        })
+    }
+
     fn get_optional_felt<Index: RowIndex>(&self, index: Index) -> rusqlite::Result<Option<Felt>> {
         let Some(blob) = self.get_optional_blob(index)? else {
             return Ok(None);
@@ -148,6 +159,13 @@ pub trait RowExt {
         Ok(self.get_optional_felt(index)?.map(CasmHash))
     }
 
+    fn get_optional_transaction_hash<Index: RowIndex>(
+        &self,
+        index: Index,
+    ) -> rusqlite::Result<Option<TransactionHash>> {
+        Ok(self.get_optional_felt(index)?.map(TransactionHash))
+    }
+
     fn get_optional_storage_commitment<Index: RowIndex>(
         &self,
         index: Index,
@@ -250,6 +268,10 @@ pub trait RowExt {
     }
 
     row_felt_wrapper!(get_block_hash, BlockHash);
+    row_felt_wrapper!(
+        get_block_commitment_signature_elem,
+        BlockCommitmentSignatureElem
+    );
     row_felt_wrapper!(get_casm_hash, CasmHash);
     row_felt_wrapper!(get_class_hash, ClassHash);
     row_felt_wrapper!(get_state_commitment, StateCommitment);
@@ -285,6 +307,49 @@ impl<'a> RowExt for &rusqlite::Row<'a> {
     }
 }
 
+/// Felts stored as consecutive 32-byte big-endian values in a single blob, e.g. the keys or data
+/// of an event.
+///
+/// The felts are decoded directly from the borrowed blob, without intermediate allocations.
+#[derive(Clone, Copy, Debug)]
+pub struct FeltBlob<'a>(&'a [u8]);
+
+impl<'a> FeltBlob<'a> {
+    /// Returns [None] if the length of `blob` is not a multiple of 32.
+    pub fn new(blob: &'a [u8]) -> Option<Self> {
+        (blob.len() % 32 == 0).then_some(Self(blob))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len() / 32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Result<Felt, OverflowError>> + 'a {
+        self.0.chunks_exact(32).map(|chunk| {
+            let bytes: [u8; 32] = chunk.try_into().expect("Chunks are 32 bytes");
+            Felt::from_be_bytes(bytes)
+        })
+    }
+
+    /// Decodes all felts into `T`, e.g. [EventKey].
+    pub fn decode<T>(&self, f: impl Fn(Felt) -> T) -> Result<Vec<T>, OverflowError> {
+        let mut values = Vec::with_capacity(self.len());
+        for felt in self.iter() {
+            values.push(f(felt?));
+        }
+        Ok(values)
+    }
+}
+
+/// Writes the felts as consecutive 32-byte big-endian values, the inverse of [FeltBlob].
+pub fn encode_felts<'a>(felts: impl IntoIterator<Item = &'a Felt>, buffer: &mut Vec<u8>) {
+    buffer.extend(felts.into_iter().flat_map(|felt| *felt.as_be_bytes()))
+}
+
 /// Implements [ToSql] for the target [Felt] newtype.
 ///
 /// Writes the full underlying bytes (no compression).
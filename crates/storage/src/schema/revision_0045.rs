@@ -0,0 +1,15 @@
+use anyhow::Context;
+
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE submitted_transactions (
+    hash BLOB PRIMARY KEY NOT NULL,
+    submitted_at INTEGER NOT NULL,
+    included_at INTEGER
+);",
+    )
+    .context("Creating submitted_transactions table")?;
+
+    Ok(())
+}
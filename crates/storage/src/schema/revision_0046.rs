@@ -0,0 +1,69 @@
+use anyhow::Context;
+use starknet_gateway_types::reply::transaction as gateway;
+
+/// Indexes the contracts deployed by the stored state updates, along with their deploying
+/// transactions.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE contract_deployments (
+    contract_address BLOB PRIMARY KEY NOT NULL,
+    class_hash BLOB NOT NULL,
+    block_number INTEGER NOT NULL REFERENCES canonical_blocks(number) ON DELETE CASCADE,
+    transaction_hash BLOB
+);
+CREATE INDEX contract_deployments_block_number ON contract_deployments(block_number);",
+    )
+    .context("Creating contract_deployments table")?;
+
+    tracing::info!("Indexing contract deployments, this may take a while");
+
+    // A contract's first class update is its deployment.
+    tx.execute(
+        r"INSERT INTO contract_deployments (contract_address, class_hash, block_number)
+            SELECT contract_address, class_hash, MIN(block_number) FROM contract_updates
+            GROUP BY contract_address",
+        [],
+    )
+    .context("Indexing existing contract deployments")?;
+
+    // Contracts deployed via the `deploy` syscall have no deploying transaction.
+    let mut query = tx
+        .prepare(
+            r"SELECT canonical_blocks.number, starknet_transactions.tx
+            FROM starknet_transactions
+                INNER JOIN canonical_blocks ON (canonical_blocks.hash = starknet_transactions.block_hash)",
+        )
+        .context("Preparing transaction query")?;
+    let mut update = tx
+        .prepare(
+            r"UPDATE contract_deployments SET transaction_hash = ?
+            WHERE contract_address = ? AND block_number = ?",
+        )
+        .context("Preparing deployment update statement")?;
+
+    let mut rows = query.query([]).context("Querying transactions")?;
+    while let Some(row) = rows.next().context("Fetching next transaction")? {
+        let block_number: i64 = row.get(0)?;
+        let transaction = row.get_ref(1)?.as_blob()?;
+
+        let transaction = zstd::decode_all(transaction).context("Decompressing transaction")?;
+        let transaction: gateway::Transaction =
+            serde_json::from_slice(&transaction).context("Deserializing transaction")?;
+        let (contract_address, transaction_hash) = match &transaction {
+            gateway::Transaction::Deploy(t) => (t.contract_address, t.transaction_hash),
+            gateway::Transaction::DeployAccount(t) => (t.contract_address(), t.transaction_hash()),
+            _ => continue,
+        };
+
+        update
+            .execute(rusqlite::params![
+                transaction_hash.0.as_be_bytes(),
+                contract_address.0.as_be_bytes(),
+                block_number,
+            ])
+            .context("Updating deploying transaction")?;
+    }
+
+    Ok(())
+}
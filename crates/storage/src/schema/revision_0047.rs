@@ -0,0 +1,56 @@
+use anyhow::Context;
+
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute(
+        r"CREATE TABLE storage_flat (
+    contract_address BLOB NOT NULL,
+    storage_address BLOB NOT NULL,
+    storage_value BLOB NOT NULL,
+    block_number INTEGER NOT NULL,
+    PRIMARY KEY (contract_address, storage_address)
+) WITHOUT ROWID",
+        [],
+    )
+    .context("Creating storage_flat table")?;
+
+    tracing::info!("Indexing current contract storage, this may take a while");
+
+    // SQLite takes the bare columns from the row with the maximum block number.
+    tx.execute(
+        r"INSERT INTO storage_flat (contract_address, storage_address, storage_value, block_number)
+            SELECT contract_address, storage_address, storage_value, MAX(block_number) FROM storage_updates
+            GROUP BY contract_address, storage_address",
+        [],
+    )
+    .context("Indexing current contract storage")?;
+
+    // Keep the table in sync with storage_updates. If the current value's update is deleted, e.g.
+    // because its block was purged during a reorg, the latest remaining update is restored.
+    tx.execute_batch(
+        r"
+CREATE TRIGGER storage_flat_ai AFTER INSERT ON storage_updates BEGIN
+    INSERT INTO storage_flat (contract_address, storage_address, storage_value, block_number)
+    VALUES (new.contract_address, new.storage_address, new.storage_value, new.block_number)
+    ON CONFLICT (contract_address, storage_address) DO UPDATE SET storage_value = excluded.storage_value, block_number = excluded.block_number
+    WHERE excluded.block_number >= storage_flat.block_number;
+END;
+
+CREATE TRIGGER storage_flat_ad AFTER DELETE ON storage_updates
+WHEN EXISTS (
+    SELECT 1 FROM storage_flat WHERE contract_address = old.contract_address
+    AND storage_address = old.storage_address AND block_number = old.block_number
+)
+BEGIN
+    DELETE FROM storage_flat
+    WHERE contract_address = old.contract_address AND storage_address = old.storage_address;
+
+    INSERT INTO storage_flat (contract_address, storage_address, storage_value, block_number)
+    SELECT contract_address, storage_address, storage_value, block_number FROM storage_updates
+    WHERE contract_address = old.contract_address AND storage_address = old.storage_address
+    ORDER BY block_number DESC LIMIT 1;
+END;",
+    )
+    .context("Creating storage_flat triggers")?;
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anyhow::Context;
+
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+ALTER TABLE l1_state ADD COLUMN l1_transaction_hash BLOB;
+ALTER TABLE l1_state ADD COLUMN l1_timestamp INTEGER;
+
+CREATE TABLE l1_accepted_blocks (
+    block_number INTEGER PRIMARY KEY NOT NULL REFERENCES canonical_blocks(number) ON DELETE CASCADE,
+    l1_transaction_hash BLOB,
+    l1_timestamp INTEGER
+);",
+    )
+    .context("Creating l1_accepted_blocks table")?;
+
+    // The L1 transactions of blocks which are already L1 accepted are unknown.
+    tx.execute(
+        r"INSERT INTO l1_accepted_blocks (block_number)
+            SELECT number FROM canonical_blocks
+            WHERE number <= (SELECT l1_l2_head FROM refs WHERE idx = 1)",
+        [],
+    )
+    .context("Marking L1 accepted blocks")?;
+
+    Ok(())
+}
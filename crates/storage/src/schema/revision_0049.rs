@@ -0,0 +1,89 @@
+use anyhow::Context;
+use rusqlite::functions::FunctionFlags;
+
+/// Stores event keys as consecutive 32-byte felts instead of space separated base64 strings, like
+/// event data already is.
+///
+/// The event key index is left as is, since its tokens are derived from the same felts.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+DROP TRIGGER starknet_events_03_ai;
+DROP TRIGGER starknet_events_03_ad;
+DROP TRIGGER starknet_events_03_au;",
+    )
+    .context("Dropping event key index triggers")?;
+
+    tx.create_scalar_function(
+        "base64_felts_to_bytes",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let base64_felts = ctx
+                .get_raw(0)
+                .as_str()
+                .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))?;
+            base64_felts_to_bytes(base64_felts)
+                .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+        },
+    )
+    .context("Registering base64_felts_to_bytes")?;
+
+    tracing::info!("Converting event keys to bytes, this may take a while");
+    tx.execute(
+        "UPDATE starknet_events SET keys = base64_felts_to_bytes(keys)",
+        [],
+    )
+    .context("Converting event keys")?;
+
+    tx.remove_function("base64_felts_to_bytes", 1)
+        .context("Removing base64_felts_to_bytes")?;
+
+    tx.execute_batch(
+        r"
+CREATE TRIGGER starknet_events_03_ai AFTER INSERT ON starknet_events BEGIN
+    INSERT INTO starknet_events_keys_03(rowid, keys) VALUES (
+        new.id,
+        felts_to_index_prefixed_base32_felts(new.keys)
+    );
+END;
+CREATE TRIGGER starknet_events_03_ad AFTER DELETE ON starknet_events BEGIN
+    INSERT INTO starknet_events_keys_03(starknet_events_keys_03, rowid, keys) VALUES (
+        'delete',
+        old.id,
+        felts_to_index_prefixed_base32_felts(old.keys)
+    );
+END;
+CREATE TRIGGER starknet_events_03_au AFTER UPDATE ON starknet_events BEGIN
+    INSERT INTO starknet_events_keys_03(starknet_events_keys_03, rowid, keys) VALUES (
+        'delete',
+        old.id,
+        felts_to_index_prefixed_base32_felts(old.keys)
+    );
+    INSERT INTO starknet_events_keys_03(rowid, keys) VALUES (
+        new.id,
+        felts_to_index_prefixed_base32_felts(new.keys)
+    );
+END;",
+    )
+    .context("Recreating event key index triggers")?;
+
+    Ok(())
+}
+
+fn base64_felts_to_bytes(base64_felts: &str) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    if base64_felts.is_empty() {
+        return Ok(bytes);
+    }
+
+    for key in base64_felts.split(' ') {
+        let mut felt = [0u8; 32];
+        let used = base64::decode_config_slice(key, base64::STANDARD, &mut felt)
+            .context("Decoding base64 felt")?;
+        anyhow::ensure!(used == 32, "Felt has {used} bytes");
+        bytes.extend_from_slice(&felt);
+    }
+
+    Ok(bytes)
+}
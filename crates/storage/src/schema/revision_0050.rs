@@ -0,0 +1,82 @@
+use anyhow::Context;
+use starknet_gateway_types::reply::transaction as gateway;
+
+use crate::params::encode_felts;
+
+/// Indexes the messages sent to L1 by the stored transactions, by their L1 recipient.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE l2_to_l1_messages (
+    block_number INTEGER NOT NULL REFERENCES canonical_blocks(number) ON DELETE CASCADE,
+    transaction_idx INTEGER NOT NULL,
+    idx INTEGER NOT NULL,
+    transaction_hash BLOB NOT NULL,
+    from_address BLOB NOT NULL,
+    to_address BLOB NOT NULL,
+    payload BLOB NOT NULL,
+    message_hash BLOB NOT NULL
+);
+CREATE INDEX l2_to_l1_messages_block_number ON l2_to_l1_messages(block_number);
+CREATE INDEX l2_to_l1_messages_to_address_block_number ON l2_to_l1_messages(to_address, block_number);",
+    )
+    .context("Creating l2_to_l1_messages table")?;
+
+    tracing::info!("Indexing messages to L1, this may take a while");
+
+    let mut query = tx
+        .prepare(
+            r"SELECT canonical_blocks.number, starknet_transactions.idx, starknet_transactions.receipt
+            FROM starknet_transactions
+                INNER JOIN canonical_blocks ON (canonical_blocks.hash = starknet_transactions.block_hash)",
+        )
+        .context("Preparing receipt query")?;
+    let mut insert = tx
+        .prepare(
+            r"INSERT INTO l2_to_l1_messages (block_number, transaction_idx, idx, transaction_hash, from_address, to_address, payload, message_hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .context("Preparing message insert statement")?;
+
+    let mut rows = query.query([]).context("Querying receipts")?;
+    let mut payload = Vec::new();
+    while let Some(row) = rows.next().context("Fetching next receipt")? {
+        let block_number: i64 = row.get(0)?;
+        let transaction_idx: i64 = row.get(1)?;
+        let Some(receipt) = row.get_ref(2)?.as_blob_or_null()? else {
+            continue;
+        };
+
+        let receipt = zstd::decode_all(receipt).context("Decompressing receipt")?;
+        let receipt: MessagesOnly =
+            serde_json::from_slice(&receipt).context("Deserializing receipt")?;
+
+        for (idx, message) in receipt.l2_to_l1_messages.iter().enumerate() {
+            payload.clear();
+            encode_felts(message.payload.iter().map(|elem| &elem.0), &mut payload);
+
+            insert
+                .execute(rusqlite::params![
+                    block_number,
+                    transaction_idx,
+                    idx as i64,
+                    receipt.transaction_hash.0.as_be_bytes(),
+                    message.from_address.0.as_be_bytes(),
+                    message.to_address.0.as_bytes(),
+                    payload,
+                    message.calculate_message_hash().as_bytes(),
+                ])
+                .context("Inserting message to L1")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The part of a stored receipt needed for the index, so that the rest of it need not be
+/// deserialized.
+#[derive(serde::Deserialize)]
+struct MessagesOnly {
+    transaction_hash: pathfinder_common::TransactionHash,
+    l2_to_l1_messages: Vec<gateway::L2ToL1Message>,
+}
@@ -0,0 +1,71 @@
+use anyhow::Context;
+use rusqlite::OptionalExtension;
+
+/// Adds the tables the chain statistics are maintained in, see `connection::statistics`.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE block_statistics (
+    block_number INTEGER PRIMARY KEY NOT NULL REFERENCES canonical_blocks(number) ON DELETE CASCADE,
+    declared_class_count INTEGER NOT NULL
+);
+CREATE TABLE block_active_contracts (
+    block_number INTEGER NOT NULL REFERENCES canonical_blocks(number) ON DELETE CASCADE,
+    contract_address BLOB NOT NULL
+);
+CREATE INDEX block_active_contracts_block_number ON block_active_contracts(block_number);",
+    )
+    .context("Creating chain statistics tables")?;
+
+    tracing::info!("Computing chain statistics, this may take a while");
+
+    // The cumulative number of classes declared up to each block.
+    tx.execute(
+        r"INSERT INTO block_statistics (block_number, declared_class_count)
+            SELECT number, SUM(declared) OVER (ORDER BY number) FROM (
+                SELECT canonical_blocks.number, COUNT(class_definitions.hash) AS declared
+                FROM canonical_blocks
+                    LEFT JOIN class_definitions ON (class_definitions.block_number = canonical_blocks.number)
+                GROUP BY canonical_blocks.number
+            )",
+        [],
+    )
+    .context("Counting declared classes")?;
+
+    // Active contracts are only kept for the blocks within a day of the latest block.
+    let latest_timestamp: Option<i64> = tx
+        .query_row(
+            "SELECT timestamp FROM block_headers ORDER BY number DESC LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("Querying latest block timestamp")?;
+    let Some(latest_timestamp) = latest_timestamp else {
+        return Ok(());
+    };
+    let window_start: i64 = tx
+        .query_row(
+            "SELECT IFNULL(MAX(number) + 1, 0) FROM block_headers WHERE timestamp <= ?",
+            [latest_timestamp - 24 * 60 * 60],
+            |row| row.get(0),
+        )
+        .context("Querying statistics window start")?;
+
+    tx.execute(
+        r"INSERT INTO block_active_contracts (block_number, contract_address)
+            SELECT block_number, contract_address FROM contract_updates WHERE block_number >= ?1
+            UNION
+            SELECT block_number, contract_address FROM nonce_updates WHERE block_number >= ?1
+            UNION
+            SELECT block_number, contract_address FROM storage_updates WHERE block_number >= ?1
+                AND contract_address != ?2",
+        rusqlite::params![
+            window_start,
+            pathfinder_common::ContractAddress::ONE.0.as_be_bytes()
+        ],
+    )
+    .context("Indexing active contracts")?;
+
+    Ok(())
+}
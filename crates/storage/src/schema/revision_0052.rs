@@ -0,0 +1,12 @@
+use anyhow::Context;
+
+/// Adds the L1 block at which each L1 state update was read, so that L1 reorgs can be detected.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+ALTER TABLE l1_state ADD COLUMN l1_block_number INTEGER;
+ALTER TABLE l1_state ADD COLUMN l1_block_hash BLOB;
+CREATE INDEX l1_state_l1_block_number ON l1_state(l1_block_number);",
+    )
+    .context("Adding L1 block columns to l1_state")
+}
@@ -0,0 +1,59 @@
+use anyhow::Context;
+use starknet_gateway_types::reply::transaction as gateway;
+
+/// Indexes the stored transactions by the account which sent them.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE transactions_by_sender (
+    sender_address BLOB NOT NULL,
+    block_number INTEGER NOT NULL REFERENCES canonical_blocks(number) ON DELETE CASCADE,
+    transaction_idx INTEGER NOT NULL,
+    transaction_hash BLOB NOT NULL
+);
+CREATE INDEX transactions_by_sender_sender_address_block_number ON transactions_by_sender(sender_address, block_number, transaction_idx);
+CREATE INDEX transactions_by_sender_block_number ON transactions_by_sender(block_number);",
+    )
+    .context("Creating transactions_by_sender table")?;
+
+    tracing::info!("Indexing transactions by sender, this may take a while");
+
+    let mut query = tx
+        .prepare(
+            r"SELECT canonical_blocks.number, starknet_transactions.idx, starknet_transactions.tx
+            FROM starknet_transactions
+                INNER JOIN canonical_blocks ON (canonical_blocks.hash = starknet_transactions.block_hash)",
+        )
+        .context("Preparing transaction query")?;
+    let mut insert = tx
+        .prepare(
+            r"INSERT INTO transactions_by_sender (sender_address, block_number, transaction_idx, transaction_hash)
+            VALUES (?, ?, ?, ?)",
+        )
+        .context("Preparing sender insert statement")?;
+
+    let mut rows = query.query([]).context("Querying transactions")?;
+    while let Some(row) = rows.next().context("Fetching next transaction")? {
+        let block_number: i64 = row.get(0)?;
+        let transaction_idx: i64 = row.get(1)?;
+        let transaction = row.get_ref(2)?.as_blob()?;
+
+        let transaction = zstd::decode_all(transaction).context("Decompressing transaction")?;
+        let transaction: gateway::Transaction =
+            serde_json::from_slice(&transaction).context("Deserializing transaction")?;
+        let Some(sender_address) = transaction.sender_address() else {
+            continue;
+        };
+
+        insert
+            .execute(rusqlite::params![
+                sender_address.0.as_be_bytes(),
+                block_number,
+                transaction_idx,
+                transaction.hash().0.as_be_bytes(),
+            ])
+            .context("Inserting transaction sender")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use anyhow::Context;
+
+/// Adds the per-block sync timings, which only the most recent blocks are kept of.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE block_sync_timings (
+    block_number INTEGER PRIMARY KEY NOT NULL,
+    download_ms INTEGER NOT NULL,
+    verification_ms INTEGER NOT NULL,
+    trie_update_ms INTEGER NOT NULL,
+    db_write_ms INTEGER NOT NULL
+);",
+    )
+    .context("Creating block_sync_timings table")?;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use anyhow::Context;
+
+/// Adds the values of the metric counters which are persisted across restarts.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE metric_counters (
+    name TEXT PRIMARY KEY NOT NULL,
+    value INTEGER NOT NULL
+);",
+    )
+    .context("Creating metric_counters table")?;
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use anyhow::Context;
+
+/// Adds the statistics of slow queries, which are collected if a slow query threshold is set.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+CREATE TABLE slow_queries (
+    name TEXT PRIMARY KEY NOT NULL,
+    count INTEGER NOT NULL,
+    total_micros INTEGER NOT NULL,
+    max_micros INTEGER NOT NULL
+);",
+    )
+    .context("Creating slow_queries table")?;
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use anyhow::Context;
+
+/// Adds the block of the preceding L1 state update, which bounds the blocks an update accepted.
+///
+/// Blocks were stamped with the L1 transaction of the update which advanced the L1-L2 pointer,
+/// even if an earlier, unobserved update accepted them. These transactions are cleared as the
+/// correct ones are unknown.
+pub(crate) fn migrate(tx: &rusqlite::Transaction<'_>) -> anyhow::Result<()> {
+    tx.execute_batch(
+        r"
+ALTER TABLE l1_state ADD COLUMN previous_starknet_block_number INTEGER;
+UPDATE l1_accepted_blocks SET l1_transaction_hash = NULL, l1_timestamp = NULL;",
+    )
+    .context("Adding previous block column to l1_state")
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Aggregates of the executions of a query which exceeded the slow query threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlowQueryStats {
+    pub count: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl SlowQueryStats {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    /// The average duration of the slow executions.
+    pub fn average(&self) -> Duration {
+        let nanos = self.total.as_nanos() / u128::from(self.count.max(1));
+        Duration::from_nanos(nanos as u64)
+    }
+}
+
+/// Collects the queries exceeding the threshold, by the name of their
+/// [Transaction](crate::Transaction) method.
+///
+/// A single log is shared by all connections of a [StorageManager](crate::StorageManager). The
+/// collected statistics are taken by [Storage::take_slow_queries](crate::Storage::take_slow_queries)
+/// to be persisted.
+#[derive(Clone)]
+pub(crate) struct SlowQueryLog(Arc<Shared>);
+
+struct Shared {
+    threshold: Duration,
+    stats: Mutex<HashMap<&'static str, SlowQueryStats>>,
+}
+
+impl SlowQueryLog {
+    pub fn new(threshold: Duration) -> Self {
+        Self(Arc::new(Shared {
+            threshold,
+            stats: Default::default(),
+        }))
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.0.threshold
+    }
+
+    pub fn record(&self, name: &'static str, elapsed: Duration) {
+        self.0
+            .stats
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .record(elapsed);
+    }
+
+    /// Returns the statistics collected since the last call.
+    pub fn take(&self) -> Vec<(&'static str, SlowQueryStats)> {
+        let mut stats = self.0.stats.lock().unwrap();
+        std::mem::take(&mut *stats).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take() {
+        let log = SlowQueryLog::new(Duration::from_millis(10));
+        log.record("events", Duration::from_millis(20));
+        log.record("events", Duration::from_millis(40));
+
+        let expected = SlowQueryStats {
+            count: 2,
+            total: Duration::from_millis(60),
+            max: Duration::from_millis(40),
+        };
+        assert_eq!(log.take(), vec![("events", expected)]);
+        assert_eq!(expected.average(), Duration::from_millis(30));
+
+        assert!(log.take().is_empty());
+    }
+}
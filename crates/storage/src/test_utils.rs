@@ -215,3 +215,9 @@ pub fn setup_test_storage() -> (Storage, TestData) {
         },
     )
 }
+
+/// An error as returned by a query while the database is busy, see [crate::is_busy].
+pub fn busy_error() -> anyhow::Error {
+    rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None)
+        .into()
+}
@@ -0,0 +1,167 @@
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use pathfinder_common::cache::{CacheRegistry, MemoryAccounted};
+use pathfinder_crypto::Felt;
+
+use crate::StoredNode;
+
+/// Number of trie nodes cached by default.
+///
+/// This comfortably holds the upper levels of all three tries, which are read for every block
+/// commit and storage proof.
+const DEFAULT_CAPACITY: usize = 200_000;
+
+/// The trie a node belongs to. Node indices are only unique within a trie's table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Trie {
+    Class,
+    Contracts,
+    Storage,
+}
+
+/// Identifies a node by its trie and index within that trie.
+type Key = (Trie, u64);
+
+/// A size-limited cache of trie nodes read from the database.
+///
+/// A single cache is shared by all connections of a [StorageManager](crate::StorageManager),
+/// i.e. between the sync writer and RPC readers.
+#[derive(Clone)]
+pub(crate) struct TrieNodeCache(Arc<Shared>);
+
+struct Shared(Mutex<Inner>);
+
+struct Inner {
+    cache: lru::LruCache<Key, (Felt, StoredNode)>,
+    capacity: usize,
+    /// Estimated total size of the cached nodes.
+    bytes: usize,
+}
+
+impl TrieNodeCache {
+    pub fn new(capacity: usize) -> Self {
+        // The cache is unbounded to avoid allocating the full capacity up front, and is
+        // instead trimmed on insertion.
+        let shared = Arc::new(Shared(Mutex::new(Inner {
+            cache: lru::LruCache::unbounded(),
+            capacity,
+            bytes: 0,
+        })));
+
+        let accounted: Arc<dyn MemoryAccounted> = shared.clone();
+        CacheRegistry::global().register("trie_nodes", &accounted);
+
+        Self(shared)
+    }
+
+    fn get(&self, key: Key) -> Option<(Felt, StoredNode)> {
+        self.0.lock().cache.get(&key).cloned()
+    }
+
+    fn insert(&self, key: Key, hash: Felt, node: StoredNode) {
+        let mut inner = self.0.lock();
+        inner.bytes += entry_size(&node);
+        if let Some((_, (_, replaced))) = inner.cache.push(key, (hash, node)) {
+            inner.bytes -= entry_size(&replaced);
+        }
+
+        let capacity = inner.capacity;
+        inner.shrink(|inner| inner.cache.len() > capacity);
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.0.lock().cache.len()
+    }
+}
+
+impl Default for TrieNodeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// A [Transaction's](crate::Transaction) view of the [TrieNodeCache].
+///
+/// The trie tables don't use `AUTOINCREMENT`, so the index of a node inserted by a transaction
+/// which is later rolled back can be reused for a different node. Nodes inserted by this
+/// transaction are therefore never cached. Since indices are assigned in increasing order, these
+/// are exactly the nodes at or above the first index inserted into the same trie.
+pub(crate) struct TransactionTrieCache {
+    cache: TrieNodeCache,
+    first_inserted: RefCell<Vec<Key>>,
+}
+
+impl TransactionTrieCache {
+    pub fn new(cache: TrieNodeCache) -> Self {
+        Self {
+            cache,
+            first_inserted: Default::default(),
+        }
+    }
+
+    pub fn get(&self, trie: Trie, index: u64) -> Option<(Felt, StoredNode)> {
+        self.cache.get((trie, index))
+    }
+
+    /// Caches a node which was read from the database.
+    pub fn read(&self, trie: Trie, index: u64, hash: Felt, node: StoredNode) {
+        let uncommitted = self
+            .first_inserted
+            .borrow()
+            .iter()
+            .any(|(t, first)| *t == trie && index >= *first);
+
+        if !uncommitted {
+            self.cache.insert((trie, index), hash, node);
+        }
+    }
+
+    /// Records a node inserted by this transaction.
+    pub fn inserted(&self, trie: Trie, index: u64) {
+        let mut first_inserted = self.first_inserted.borrow_mut();
+        match first_inserted.iter_mut().find(|(t, _)| *t == trie) {
+            Some((_, first)) => *first = (*first).min(index),
+            None => first_inserted.push((trie, index)),
+        }
+    }
+}
+
+/// Estimates the memory used by a cache entry, including the path stored by edge nodes.
+fn entry_size(node: &StoredNode) -> usize {
+    let path = match node {
+        StoredNode::Edge { path, .. } | StoredNode::LeafEdge { path } => path.as_raw_slice().len(),
+        StoredNode::Binary { .. } | StoredNode::LeafBinary => 0,
+    };
+
+    std::mem::size_of::<(Key, (Felt, StoredNode))>() + path
+}
+
+impl Inner {
+    /// Evicts the least recently used nodes while `condition` holds.
+    fn shrink(&mut self, condition: impl Fn(&Self) -> bool) {
+        while condition(self) {
+            let Some((_, (_, evicted))) = self.cache.pop_lru() else {
+                break;
+            };
+            self.bytes -= entry_size(&evicted);
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.0.lock().unwrap()
+    }
+}
+
+impl MemoryAccounted for Shared {
+    fn memory_usage(&self) -> usize {
+        self.lock().bytes
+    }
+
+    fn shrink_to(&self, bytes: usize) {
+        self.lock().shrink(|inner| inner.bytes > bytes);
+    }
+}